@@ -0,0 +1,230 @@
+use criterion::{criterion_group, criterion_main, BenchmarkId, Criterion};
+#[cfg(unix)]
+use pprof::criterion::{Output, PProfProfiler};
+use uniswap_sdk_core::{prelude::*, token};
+use uniswap_v3_sdk::{bench_utils::*, prelude::*};
+
+const SEED: u64 = 42;
+
+fn tokens() -> Vec<Token> {
+    tokens_n(4)
+}
+
+fn tokens_n(n: usize) -> Vec<Token> {
+    let tokens = [
+        token!(1, "0000000000000000000000000000000000000001", 18, "t0"),
+        token!(1, "0000000000000000000000000000000000000002", 18, "t1"),
+        token!(1, "0000000000000000000000000000000000000003", 18, "t2"),
+        token!(1, "0000000000000000000000000000000000000004", 18, "t3"),
+        token!(1, "0000000000000000000000000000000000000005", 18, "t4"),
+        token!(1, "0000000000000000000000000000000000000006", 18, "t5"),
+    ];
+    tokens[..n].to_vec()
+}
+
+/// Generates a pool for every pair of tokens.
+fn pools(rng: &mut DeterministicRng) -> Vec<Pool<TickListDataProvider>> {
+    pools_of(&tokens(), rng)
+}
+
+/// Generates a pool for every pair of the given tokens.
+fn pools_of(tokens: &[Token], rng: &mut DeterministicRng) -> Vec<Pool<TickListDataProvider>> {
+    let mut pools = Vec::new();
+    for (i, token_a) in tokens.iter().enumerate() {
+        for token_b in &tokens[i + 1..] {
+            pools.push(
+                PoolGenerator::new(token_a.clone(), token_b.clone(), FeeAmount::MEDIUM)
+                    .generate(rng),
+            );
+        }
+    }
+    pools
+}
+
+fn swap_simulation_benchmark(c: &mut Criterion) {
+    let mut rng = DeterministicRng::new(SEED);
+    let tokens = tokens();
+    let pool = PoolGenerator {
+        num_positions: 1000,
+        ..PoolGenerator::new(tokens[0].clone(), tokens[1].clone(), FeeAmount::LOW)
+    }
+    .generate(&mut rng);
+    let amounts = generate_swap_amounts(&pool, 100, 10_u128.pow(24), &mut rng);
+    c.bench_function("swap_simulation", |b| {
+        b.iter(|| {
+            for amount in &amounts {
+                let _ = pool.get_output_amount(amount, None);
+            }
+        })
+    });
+}
+
+/// Simulates swaps against pools with increasingly dense liquidity, i.e. more initialized ticks
+/// crossed by the same amounts.
+fn tick_density_benchmark(c: &mut Criterion) {
+    let tokens = tokens();
+    let mut group = c.benchmark_group("get_output_amount_by_positions");
+    for num_positions in [10, 100, 1000, 10000] {
+        let mut rng = DeterministicRng::new(SEED);
+        let pool = PoolGenerator {
+            num_positions,
+            median_width: 5.0,
+            ..PoolGenerator::new(tokens[0].clone(), tokens[1].clone(), FeeAmount::LOW)
+        }
+        .generate(&mut rng);
+        let amounts = generate_swap_amounts(&pool, 100, 10_u128.pow(24), &mut rng);
+        group.bench_with_input(
+            BenchmarkId::from_parameter(num_positions),
+            &amounts,
+            |b, amounts| {
+                b.iter(|| {
+                    for amount in amounts {
+                        let _ = pool.get_output_amount(amount, None);
+                    }
+                })
+            },
+        );
+    }
+    group.finish();
+}
+
+/// Searches the best route among a pool for every pair of an increasing number of tokens.
+fn route_search_scaling_benchmark(c: &mut Criterion) {
+    let mut group = c.benchmark_group("route_search_by_pools");
+    for num_tokens in [3, 4, 5, 6] {
+        let mut rng = DeterministicRng::new(SEED);
+        let tokens = tokens_n(num_tokens);
+        let pools = pools_of(&tokens, &mut rng);
+        let amount_in =
+            CurrencyAmount::from_raw_amount(tokens[0].clone(), 10_u128.pow(18)).unwrap();
+        let token_out = tokens.last().unwrap();
+        group.bench_with_input(
+            BenchmarkId::from_parameter(pools.len()),
+            &pools,
+            |b, pools| {
+                b.iter(|| {
+                    let mut best_trades = Vec::new();
+                    let _ = Trade::best_trade_exact_in(
+                        pools.clone(),
+                        &amount_in,
+                        token_out,
+                        BestTradeOptions {
+                            max_num_results: Some(3),
+                            max_hops: Some(3),
+                            ..Default::default()
+                        },
+                        Vec::new(),
+                        None,
+                        &mut best_trades,
+                    );
+                })
+            },
+        );
+    }
+    group.finish();
+}
+
+fn route_search_benchmark(c: &mut Criterion) {
+    let mut rng = DeterministicRng::new(SEED);
+    let tokens = tokens();
+    let pools = pools(&mut rng);
+    let amount_in = CurrencyAmount::from_raw_amount(tokens[0].clone(), 10_u128.pow(18)).unwrap();
+    c.bench_function("route_search", |b| {
+        b.iter(|| {
+            let mut best_trades = Vec::new();
+            let _ = Trade::best_trade_exact_in(
+                pools.clone(),
+                &amount_in,
+                &tokens[3],
+                BestTradeOptions {
+                    max_num_results: Some(3),
+                    max_hops: Some(3),
+                    ..Default::default()
+                },
+                Vec::new(),
+                None,
+                &mut best_trades,
+            );
+        })
+    });
+}
+
+fn encoding_benchmark(c: &mut Criterion) {
+    let mut rng = DeterministicRng::new(SEED);
+    let tokens = tokens();
+    let pools = pools(&mut rng);
+    let trade = Trade::from_route(
+        Route::new(
+            vec![pools[0].clone(), pools[3].clone()],
+            tokens[0].clone(),
+            tokens[2].clone(),
+        ),
+        CurrencyAmount::from_raw_amount(tokens[0].clone(), 10_u128.pow(18)).unwrap(),
+        TradeType::ExactInput,
+    )
+    .unwrap();
+    let options = SwapOptions {
+        slippage_tolerance: Percent::new(1, 100),
+        ..Default::default()
+    };
+    c.bench_function("swap_call_parameters", |b| {
+        b.iter(|| {
+            let _ = swap_call_parameters(&mut [trade.clone()], options.clone());
+        })
+    });
+}
+
+/// Measures the `_cached` methods queried repeatedly on a trade, labelled by whether the
+/// `no-cache` feature is enabled, so that both builds can be compared.
+fn cache_benchmark(c: &mut Criterion) {
+    let mut rng = DeterministicRng::new(SEED);
+    let tokens = tokens();
+    let pools = pools(&mut rng);
+    let trade = Trade::from_route(
+        Route::new(
+            vec![pools[0].clone(), pools[3].clone()],
+            tokens[0].clone(),
+            tokens[2].clone(),
+        ),
+        CurrencyAmount::from_raw_amount(tokens[0].clone(), 10_u128.pow(18)).unwrap(),
+        TradeType::ExactInput,
+    )
+    .unwrap();
+    let slippage_tolerance = Percent::new(1, 100);
+    let label = if CACHE_ENABLED { "cache" } else { "no-cache" };
+    let mut group = c.benchmark_group("cached_queries");
+    group.bench_function(
+        BenchmarkId::new(label, core::mem::size_of_val(&trade)),
+        |b| {
+            b.iter(|| {
+                let mut trade = trade.clone();
+                for _ in 0..10 {
+                    let _ = trade.execution_price_cached();
+                    let _ = trade.price_impact_cached();
+                    let _ = trade.minimum_amount_out_cached(slippage_tolerance.clone(), None);
+                }
+            })
+        },
+    );
+    group.finish();
+}
+
+/// Profiles the benchmarks into flamegraphs when run with `--profile-time`.
+fn config() -> Criterion {
+    let criterion = Criterion::default();
+    #[cfg(unix)]
+    let criterion = criterion.with_profiler(PProfProfiler::new(100, Output::Flamegraph(None)));
+    criterion
+}
+
+criterion_group!(
+    name = benches;
+    config = config();
+    targets = swap_simulation_benchmark,
+    tick_density_benchmark,
+    route_search_benchmark,
+    route_search_scaling_benchmark,
+    encoding_benchmark,
+    cache_benchmark,
+);
+criterion_main!(benches);
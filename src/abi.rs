@@ -4,6 +4,45 @@ sol! {
     interface IMulticall {
         function multicall(bytes[] calldata data) external payable returns (bytes[] memory results);
     }
+
+    interface IMulticallExtended {
+        function multicall(uint256 deadline, bytes[] calldata data) external payable returns (bytes[] memory results);
+    }
+
+    interface IMulticall3 {
+        #[derive(Debug, Default, PartialEq, Eq)]
+        struct Call3 {
+            address target;
+            bool allowFailure;
+            bytes callData;
+        }
+
+        #[derive(Debug, Default, PartialEq, Eq)]
+        struct Call3Value {
+            address target;
+            bool allowFailure;
+            uint256 value;
+            bytes callData;
+        }
+
+        #[derive(Debug, Default, PartialEq, Eq)]
+        struct Result {
+            bool success;
+            bytes returnData;
+        }
+
+        function aggregate3(Call3[] calldata calls) external payable returns (Result[] memory returnData);
+
+        function aggregate3Value(Call3Value[] calldata calls) external payable returns (Result[] memory returnData);
+    }
+}
+
+sol! {
+    interface IERC20 {
+        function allowance(address owner, address spender) external view returns (uint256);
+
+        function approve(address spender, uint256 amount) external returns (bool);
+    }
 }
 
 sol! {
@@ -107,6 +146,10 @@ sol! {
             bytes32 r,
             bytes32 s
         ) external payable;
+
+        function DOMAIN_SEPARATOR() external view returns (bytes32);
+
+        function PERMIT_TYPEHASH() external pure returns (bytes32);
     }
 
     interface ISelfPermit {
@@ -173,6 +216,12 @@ sol! {
             address refundee;
         }
 
+        function createIncentive(IncentiveKey memory key, uint256 reward) external;
+
+        function endIncentive(IncentiveKey memory key) external returns (uint256 refund);
+
+        function transferDeposit(uint256 tokenId, address to) external;
+
         function withdrawToken(
             uint256 tokenId,
             address to,
@@ -322,3 +371,232 @@ sol! {
         function exactOutput(ExactOutputParams calldata params) external payable returns (uint256 amountIn);
     }
 }
+
+sol! {
+    interface ISwapRouter {
+        #[derive(Debug, Default, PartialEq, Eq)]
+        struct ExactInputSingleParams {
+            address tokenIn;
+            address tokenOut;
+            uint24 fee;
+            address recipient;
+            uint256 deadline;
+            uint256 amountIn;
+            uint256 amountOutMinimum;
+            uint160 sqrtPriceLimitX96;
+        }
+
+        function exactInputSingle(ExactInputSingleParams calldata params) external payable returns (uint256 amountOut);
+
+        #[derive(Debug, Default, PartialEq, Eq)]
+        struct ExactInputParams {
+            bytes path;
+            address recipient;
+            uint256 deadline;
+            uint256 amountIn;
+            uint256 amountOutMinimum;
+        }
+
+        function exactInput(ExactInputParams calldata params) external payable returns (uint256 amountOut);
+
+        #[derive(Debug, Default, PartialEq, Eq)]
+        struct ExactOutputSingleParams {
+            address tokenIn;
+            address tokenOut;
+            uint24 fee;
+            address recipient;
+            uint256 deadline;
+            uint256 amountOut;
+            uint256 amountInMaximum;
+            uint160 sqrtPriceLimitX96;
+        }
+
+        function exactOutputSingle(ExactOutputSingleParams calldata params) external payable returns (uint256 amountIn);
+
+        #[derive(Debug, Default, PartialEq, Eq)]
+        struct ExactOutputParams {
+            bytes path;
+            address recipient;
+            uint256 deadline;
+            uint256 amountOut;
+            uint256 amountInMaximum;
+        }
+
+        function exactOutput(ExactOutputParams calldata params) external payable returns (uint256 amountIn);
+    }
+}
+
+sol! {
+    interface IApproveAndCall {
+        #[derive(Debug, Default, PartialEq, Eq)]
+        struct MintParams {
+            address token0;
+            address token1;
+            uint24 fee;
+            int24 tickLower;
+            int24 tickUpper;
+            uint256 amount0Min;
+            uint256 amount1Min;
+            address recipient;
+        }
+
+        function approveMax(address token) external payable;
+
+        function approveMaxMinusOne(address token) external payable;
+
+        function approveZeroThenMax(address token) external payable;
+
+        function approveZeroThenMaxMinusOne(address token) external payable;
+
+        function mint(MintParams calldata params) external payable returns (bytes memory result);
+
+        function callPositionManager(bytes memory data) external payable returns (bytes memory result);
+    }
+
+    interface IPeripheryPaymentsExtended {
+        function pull(address token, uint256 value) external payable;
+
+        function sweepToken(address token, uint256 amountMinimum) external payable;
+
+        function wrapETH(uint256 value) external payable;
+
+        function unwrapWETH9(uint256 amountMinimum) external payable;
+
+        function unwrapWETH9WithFee(
+            uint256 amountMinimum,
+            uint256 feeBips,
+            address feeRecipient
+        ) external payable;
+
+        function sweepTokenWithFee(
+            address token,
+            uint256 amountMinimum,
+            uint256 feeBips,
+            address feeRecipient
+        ) external payable;
+    }
+}
+
+sol! {
+    interface IAllowanceTransfer {
+        #[derive(Debug, Default, PartialEq, Eq)]
+        struct PermitDetails {
+            address token;
+            uint160 amount;
+            uint48 expiration;
+            uint48 nonce;
+        }
+
+        #[derive(Debug, Default, PartialEq, Eq)]
+        struct PermitSingle {
+            PermitDetails details;
+            address spender;
+            uint256 sigDeadline;
+        }
+
+        #[derive(Debug, Default, PartialEq, Eq)]
+        struct PermitBatch {
+            PermitDetails[] details;
+            address spender;
+            uint256 sigDeadline;
+        }
+
+        function permit(address owner, PermitSingle memory permitSingle, bytes calldata signature) external;
+
+        function permit(address owner, PermitBatch memory permitBatch, bytes calldata signature) external;
+
+        function allowance(address user, address token, address spender) external view returns (uint160 amount, uint48 expiration, uint48 nonce);
+    }
+
+    interface IUniversalRouter {
+        function execute(bytes calldata commands, bytes[] calldata inputs, uint256 deadline) external payable;
+    }
+}
+
+sol! {
+    interface IUniswapV3Factory {
+        function feeAmountTickSpacing(uint24 fee) external view returns (int24);
+
+        function getPool(address tokenA, address tokenB, uint24 fee) external view returns (address pool);
+    }
+}
+
+sol! {
+    interface ITickLens {
+        struct PopulatedTick {
+            int24 tick;
+            int128 liquidityNet;
+            uint128 liquidityGross;
+        }
+
+        function getPopulatedTicksInWord(address pool, int16 tickBitmapIndex) external view returns (PopulatedTick[] memory populatedTicks);
+    }
+}
+
+sol! {
+    interface IUniswapV3PoolActions {
+        function flash(address recipient, uint256 amount0, uint256 amount1, bytes calldata data) external;
+    }
+}
+
+sol! {
+    interface IUniswapV3PoolEvents {
+        #[derive(Debug, PartialEq, Eq)]
+        event Swap(
+            address indexed sender,
+            address indexed recipient,
+            int256 amount0,
+            int256 amount1,
+            uint160 sqrtPriceX96,
+            uint128 liquidity,
+            int24 tick
+        );
+
+        #[derive(Debug, PartialEq, Eq)]
+        event Mint(
+            address sender,
+            address indexed owner,
+            int24 indexed tickLower,
+            int24 indexed tickUpper,
+            uint128 amount,
+            uint256 amount0,
+            uint256 amount1
+        );
+
+        #[derive(Debug, PartialEq, Eq)]
+        event Burn(
+            address indexed owner,
+            int24 indexed tickLower,
+            int24 indexed tickUpper,
+            uint128 amount,
+            uint256 amount0,
+            uint256 amount1
+        );
+    }
+}
+
+sol! {
+    interface IERC20Events {
+        #[derive(Debug, PartialEq, Eq)]
+        event Transfer(address indexed from, address indexed to, uint256 value);
+    }
+
+    interface IWETH9Events {
+        #[derive(Debug, PartialEq, Eq)]
+        event Withdrawal(address indexed src, uint256 wad);
+    }
+
+    interface INonfungiblePositionManagerEvents {
+        #[derive(Debug, PartialEq, Eq)]
+        event IncreaseLiquidity(uint256 indexed tokenId, uint128 liquidity, uint256 amount0, uint256 amount1);
+
+        #[derive(Debug, PartialEq, Eq)]
+        event DecreaseLiquidity(uint256 indexed tokenId, uint128 liquidity, uint256 amount0, uint256 amount1);
+
+        #[derive(Debug, PartialEq, Eq)]
+        event Collect(uint256 indexed tokenId, address recipient, uint256 amount0, uint256 amount1);
+
+        #[derive(Debug, PartialEq, Eq)]
+        event Transfer(address indexed from, address indexed to, uint256 indexed tokenId);
+    }
+}
@@ -0,0 +1,305 @@
+//! ## Approve And Call
+//! Encodes the calls of `SwapRouter02` that add liquidity to the position manager with the tokens
+//! held by the router, so that a swap and a mint can be bundled in a single `multicall`.
+
+use crate::prelude::{Error, *};
+use alloc::vec::Vec;
+use alloy_primitives::{address, Bytes, U160, U256};
+use alloy_sol_types::SolCall;
+use uniswap_sdk_core::prelude::*;
+
+/// The recipient that `SwapRouter02` replaces with `msg.sender`.
+pub const MSG_SENDER: Address = address!("0000000000000000000000000000000000000001");
+/// The recipient that `SwapRouter02` replaces with its own address.
+pub const ADDRESS_THIS: Address = address!("0000000000000000000000000000000000000002");
+
+/// The approval of a token from `SwapRouter02` to the position manager, as returned by its
+/// `getApprovalType`.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, Hash)]
+pub enum ApprovalType {
+    /// The router has already approved the position manager.
+    #[default]
+    NotRequired,
+    Max,
+    MaxMinusOne,
+    ZeroThenMax,
+    ZeroThenMaxMinusOne,
+}
+
+/// Encodes the call approving the token to the position manager, if any
+///
+/// ## Arguments
+///
+/// * `token`: The token to approve
+/// * `approval_type`: The approval required
+#[inline]
+#[must_use]
+pub fn encode_approve(token: Address, approval_type: ApprovalType) -> Option<Bytes> {
+    Some(
+        match approval_type {
+            ApprovalType::NotRequired => return None,
+            ApprovalType::Max => IApproveAndCall::approveMaxCall { token }.abi_encode(),
+            ApprovalType::MaxMinusOne => {
+                IApproveAndCall::approveMaxMinusOneCall { token }.abi_encode()
+            }
+            ApprovalType::ZeroThenMax => {
+                IApproveAndCall::approveZeroThenMaxCall { token }.abi_encode()
+            }
+            ApprovalType::ZeroThenMaxMinusOne => {
+                IApproveAndCall::approveZeroThenMaxMinusOneCall { token }.abi_encode()
+            }
+        }
+        .into(),
+    )
+}
+
+/// Options for producing the calldata to buy one side of a position and mint it.
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+pub struct SwapAndMintOptions {
+    /// How much the swap input and the pool price are allowed to move unfavorably.
+    pub slippage_tolerance: Percent,
+    /// When the transaction expires, in epoch seconds.
+    pub deadline: U256,
+    /// The account that should receive the minted NFT.
+    pub recipient: Address,
+    /// The optional permit parameters for spending the input token.
+    pub input_token_permit: Option<PermitOptions>,
+    /// The approval of token0 from the router to the position manager.
+    pub token0_approval: ApprovalType,
+    /// The approval of token1 from the router to the position manager.
+    pub token1_approval: ApprovalType,
+}
+
+/// The calldata of a swap and mint, and the trade of the swap, `None` if the position needs none
+/// of the other token.
+pub type SwapAndMintParameters<TP> = (MethodParameters, Option<Trade<Token, Token, TP>>);
+
+/// Produces the calldata for `SwapRouter02` that buys the amount of one token of a position with
+/// an exact output swap from the other token, then mints the position with the tokens held by the
+/// router, all in one `multicall` checked against the deadline.
+///
+/// The exact output swap makes the router hold exactly the mint amount of the bought token, so the
+/// mint amounts do not depend on the execution of the swap. The input token is pulled from the
+/// sender for the rest of the position, and any amount left unused by the mint is swept back.
+/// A route through the pool of the position moves its price, which must stay within the slippage
+/// tolerance of the mint.
+///
+/// ## Arguments
+///
+/// * `position`: The position to mint
+/// * `route`: The route from the token spent to the other token of the position
+/// * `options`: Additional information necessary for generating the calldata
+///
+/// returns: The calldata and the trade of the swap, `None` if the position needs none of the
+/// other token
+#[inline]
+pub fn swap_and_mint_call_parameters<TP: Clone + TickDataProvider>(
+    position: &mut Position<TP>,
+    route: Route<Token, Token, TP>,
+    options: SwapAndMintOptions,
+) -> Result<SwapAndMintParameters<TP>, Error> {
+    assert!(position.liquidity > 0, "ZERO_LIQUIDITY");
+    let token0 = position.pool.token0.clone();
+    let token1 = position.pool.token1.clone();
+    let (token_in, token_out) = if route.output.equals(&token1) {
+        (token0.clone(), token1.clone())
+    } else if route.output.equals(&token0) {
+        (token1.clone(), token0.clone())
+    } else {
+        return Err(Error::CurrencyMismatch {
+            expected: token1.address(),
+            actual: route.output.address(),
+        });
+    };
+    if !route.input.equals(&token_in) {
+        return Err(Error::CurrencyMismatch {
+            expected: token_in.address(),
+            actual: route.input.address(),
+        });
+    }
+
+    let MintAmounts { amount0, amount1 } = position.mint_amounts_cached()?;
+    let MintAmounts {
+        amount0: amount0_min,
+        amount1: amount1_min,
+    } = position.mint_amounts_with_slippage(&options.slippage_tolerance)?;
+    let (amount_in_desired, amount_out) = if token_out.equals(&token1) {
+        (amount0, amount1)
+    } else {
+        (amount1, amount0)
+    };
+
+    let mut calldatas: Vec<Bytes> = Vec::with_capacity(8);
+    if let Some(permit) = options.input_token_permit {
+        calldatas.push(encode_permit(&token_in, permit));
+    }
+
+    let trade = if amount_out.is_zero() {
+        None
+    } else {
+        let trade = Trade::exact_out(
+            route,
+            CurrencyAmount::from_raw_amount(token_out.clone(), amount_out.to_big_int())?,
+        )?;
+        let amount_in_maximum = big_int_to_u256(
+            &trade
+                .maximum_amount_in(options.slippage_tolerance, None)?
+                .quotient(),
+        )?;
+        let route = &trade.swaps[0].route;
+        calldatas.push(if route.pools.len() == 1 {
+            IV3SwapRouter::exactOutputSingleCall {
+                params: IV3SwapRouter::ExactOutputSingleParams {
+                    tokenIn: token_in.address(),
+                    tokenOut: token_out.address(),
+                    fee: route.pools[0].fee.into(),
+                    recipient: ADDRESS_THIS,
+                    amountOut: amount_out,
+                    amountInMaximum: amount_in_maximum,
+                    sqrtPriceLimitX96: U160::ZERO,
+                },
+            }
+            .abi_encode()
+            .into()
+        } else {
+            IV3SwapRouter::exactOutputCall {
+                params: IV3SwapRouter::ExactOutputParams {
+                    path: encode_route_to_path(route, true),
+                    recipient: ADDRESS_THIS,
+                    amountOut: amount_out,
+                    amountInMaximum: amount_in_maximum,
+                },
+            }
+            .abi_encode()
+            .into()
+        });
+        Some(trade)
+    };
+
+    if !amount_in_desired.is_zero() {
+        calldatas.push(
+            IPeripheryPaymentsExtended::pullCall {
+                token: token_in.address(),
+                value: amount_in_desired,
+            }
+            .abi_encode()
+            .into(),
+        );
+    }
+    calldatas.extend(encode_approve(token0.address(), options.token0_approval));
+    calldatas.extend(encode_approve(token1.address(), options.token1_approval));
+    calldatas.push(
+        IApproveAndCall::mintCall {
+            params: IApproveAndCall::MintParams {
+                token0: token0.address(),
+                token1: token1.address(),
+                fee: position.pool.fee.into(),
+                tickLower: position.tick_lower.to_i24(),
+                tickUpper: position.tick_upper.to_i24(),
+                amount0Min: amount0_min,
+                amount1Min: amount1_min,
+                recipient: options.recipient,
+            },
+        }
+        .abi_encode()
+        .into(),
+    );
+    for token in [&token0, &token1] {
+        calldatas.push(
+            IPeripheryPaymentsExtended::sweepTokenCall {
+                token: token.address(),
+                amountMinimum: U256::ZERO,
+            }
+            .abi_encode()
+            .into(),
+        );
+    }
+
+    Ok((
+        MethodParameters {
+            calldata: IMulticallExtended::multicallCall {
+                deadline: options.deadline,
+                data: calldatas,
+            }
+            .abi_encode()
+            .into(),
+            value: U256::ZERO,
+        },
+        trade,
+    ))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::tests::*;
+    use alloy_primitives::uint;
+
+    fn position() -> Position<TickListDataProvider> {
+        let pool = make_pool(TOKEN0.clone(), TOKEN1.clone());
+        let tick_spacing = pool.tick_spacing();
+        Position::new(pool, 1_000_000, -tick_spacing * 10, tick_spacing * 10)
+    }
+
+    fn options() -> SwapAndMintOptions {
+        SwapAndMintOptions {
+            slippage_tolerance: Percent::new(1, 100),
+            deadline: uint!(123_U256),
+            recipient: MSG_SENDER,
+            token1_approval: ApprovalType::Max,
+            ..Default::default()
+        }
+    }
+
+    #[test]
+    fn buys_the_other_side_and_mints() {
+        let mut position = position();
+        let MintAmounts { amount0, amount1 } = position.mint_amounts().unwrap();
+        let route = Route::new(
+            vec![make_pool(TOKEN0.clone(), TOKEN1.clone())],
+            TOKEN0.clone(),
+            TOKEN1.clone(),
+        );
+        let (params, trade) =
+            swap_and_mint_call_parameters(&mut position, route, options()).unwrap();
+        assert_eq!(params.value, U256::ZERO);
+        assert_eq!(
+            trade.unwrap().output_amount().unwrap().quotient(),
+            amount1.to_big_int()
+        );
+
+        let call = IMulticallExtended::multicallCall::abi_decode(&params.calldata, true).unwrap();
+        assert_eq!(call.deadline, uint!(123_U256));
+        let selectors: Vec<_> = call.data.iter().map(|data| &data[..4]).collect();
+        assert_eq!(
+            selectors,
+            [
+                &IV3SwapRouter::exactOutputSingleCall::SELECTOR[..],
+                &IPeripheryPaymentsExtended::pullCall::SELECTOR,
+                &IApproveAndCall::approveMaxCall::SELECTOR,
+                &IApproveAndCall::mintCall::SELECTOR,
+                &IPeripheryPaymentsExtended::sweepTokenCall::SELECTOR,
+                &IPeripheryPaymentsExtended::sweepTokenCall::SELECTOR,
+            ]
+        );
+        let swap = IV3SwapRouter::exactOutputSingleCall::abi_decode(&call.data[0], true).unwrap();
+        assert_eq!(swap.params.amountOut, amount1);
+        assert_eq!(swap.params.recipient, ADDRESS_THIS);
+        let pull = IPeripheryPaymentsExtended::pullCall::abi_decode(&call.data[1], true).unwrap();
+        assert_eq!(pull.token, TOKEN0.address());
+        assert_eq!(pull.value, amount0);
+    }
+
+    #[test]
+    fn rejects_route_to_another_token() {
+        let route = Route::new(
+            vec![make_pool(TOKEN0.clone(), TOKEN2.clone())],
+            TOKEN0.clone(),
+            TOKEN2.clone(),
+        );
+        assert!(matches!(
+            swap_and_mint_call_parameters(&mut position(), route, options()),
+            Err(Error::CurrencyMismatch { actual, .. }) if actual == TOKEN2.address()
+        ));
+    }
+}
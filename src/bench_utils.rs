@@ -0,0 +1,195 @@
+//! ## Bench Utils
+//! Deterministic generators of realistic pools and trade flows for benchmarking, enabled by the
+//! `bench-utils` feature. The same seed always yields the same scenario, so results are
+//! comparable across runs and machines.
+
+use crate::prelude::*;
+use alloc::{collections::BTreeMap, vec::Vec};
+use uniswap_sdk_core::prelude::*;
+
+/// A small deterministic pseudo-random number generator based on SplitMix64.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct DeterministicRng(u64);
+
+impl DeterministicRng {
+    #[inline]
+    #[must_use]
+    pub const fn new(seed: u64) -> Self {
+        Self(seed)
+    }
+
+    /// Returns the next uniformly distributed `u64`
+    #[inline]
+    pub fn next_u64(&mut self) -> u64 {
+        self.0 = self.0.wrapping_add(0x9e37_79b9_7f4a_7c15);
+        let mut z = self.0;
+        z = (z ^ (z >> 30)).wrapping_mul(0xbf58_476d_1ce4_e5b9);
+        z = (z ^ (z >> 27)).wrapping_mul(0x94d0_49bb_1331_11eb);
+        z ^ (z >> 31)
+    }
+
+    /// Returns the next uniformly distributed `f64` in `[0, 1)`
+    #[inline]
+    pub fn next_f64(&mut self) -> f64 {
+        (self.next_u64() >> 11) as f64 / (1_u64 << 53) as f64
+    }
+
+    /// Returns the next standard normally distributed `f64` using the Box-Muller transform
+    #[inline]
+    pub fn next_normal(&mut self) -> f64 {
+        let u1 = 1.0 - self.next_f64();
+        let u2 = self.next_f64();
+        (-2.0 * u1.ln()).sqrt() * (core::f64::consts::TAU * u2).cos()
+    }
+}
+
+/// Generates pools whose liquidity is made of positions with log-normally distributed widths
+/// scattered around the current tick, which resembles the liquidity distribution of real pools.
+#[derive(Clone, Debug, PartialEq)]
+pub struct PoolGenerator {
+    pub token0: Token,
+    pub token1: Token,
+    pub fee: FeeAmount,
+    /// The current tick of the generated pools.
+    pub tick_current: i32,
+    /// The number of positions making up the liquidity.
+    pub num_positions: usize,
+    /// The total liquidity of all positions.
+    pub depth: u128,
+    /// The median width of a position in tick spacings.
+    pub median_width: f64,
+    /// The standard deviation of the logarithm of the position widths.
+    pub sigma: f64,
+}
+
+impl PoolGenerator {
+    /// Creates a generator with 100 positions of median width 20 tick spacings around tick 0
+    ///
+    /// ## Arguments
+    ///
+    /// * `token0`: One of the tokens of the pools
+    /// * `token1`: The other token of the pools
+    /// * `fee`: The fee tier of the pools
+    #[inline]
+    #[must_use]
+    pub const fn new(token0: Token, token1: Token, fee: FeeAmount) -> Self {
+        Self {
+            token0,
+            token1,
+            fee,
+            tick_current: 0,
+            num_positions: 100,
+            depth: 10_u128.pow(24),
+            median_width: 20.0,
+            sigma: 1.0,
+        }
+    }
+
+    /// Generates a pool
+    ///
+    /// ## Arguments
+    ///
+    /// * `rng`: The random number generator
+    #[inline]
+    pub fn generate(&self, rng: &mut DeterministicRng) -> Pool<TickListDataProvider> {
+        let tick_spacing = self.fee.tick_spacing().as_i32();
+        let min_tick = nearest_usable_tick(MIN_TICK_I32, tick_spacing);
+        let max_tick = nearest_usable_tick(MAX_TICK_I32, tick_spacing);
+        let max_width = (max_tick - min_tick) / tick_spacing;
+        let liquidity_per_position = self.depth / self.num_positions.max(1) as u128;
+
+        let mut ticks: BTreeMap<i32, (u128, i128)> = BTreeMap::new();
+        let mut liquidity = 0_u128;
+        for _ in 0..self.num_positions {
+            let width = (self.median_width * (self.sigma * rng.next_normal()).exp())
+                .clamp(1.0, max_width as f64) as i32;
+            let offset = rng.next_normal() * self.median_width * tick_spacing as f64;
+            let center = self.tick_current + offset as i32;
+            let tick_lower = ((center - width * tick_spacing / 2).div_euclid(tick_spacing)
+                * tick_spacing)
+                .clamp(min_tick, max_tick - tick_spacing);
+            let tick_upper = (tick_lower + width * tick_spacing).min(max_tick);
+            for (tick, liquidity_net) in [
+                (tick_lower, liquidity_per_position as i128),
+                (tick_upper, -(liquidity_per_position as i128)),
+            ] {
+                let entry = ticks.entry(tick).or_default();
+                entry.0 += liquidity_per_position;
+                entry.1 += liquidity_net;
+            }
+            if tick_lower <= self.tick_current && self.tick_current < tick_upper {
+                liquidity += liquidity_per_position;
+            }
+        }
+        let ticks = ticks
+            .into_iter()
+            .map(|(index, (liquidity_gross, liquidity_net))| {
+                Tick::new(index, liquidity_gross, liquidity_net)
+            })
+            .collect();
+        Pool::new_with_tick_data_provider(
+            self.token0.clone(),
+            self.token1.clone(),
+            self.fee,
+            get_sqrt_ratio_at_tick(self.tick_current.to_i24()).unwrap(),
+            liquidity,
+            TickListDataProvider::new(ticks, tick_spacing),
+        )
+        .unwrap()
+    }
+}
+
+/// Generates a flow of exact input amounts in either token of the pool, log-uniformly
+/// distributed between 1 and `max_amount`
+///
+/// ## Arguments
+///
+/// * `pool`: The pool to trade against
+/// * `count`: The number of amounts to generate
+/// * `max_amount`: The maximum raw amount
+/// * `rng`: The random number generator
+#[inline]
+pub fn generate_swap_amounts<TP: TickDataProvider>(
+    pool: &Pool<TP>,
+    count: usize,
+    max_amount: u128,
+    rng: &mut DeterministicRng,
+) -> Vec<CurrencyAmount<Token>> {
+    (0..count)
+        .map(|_| {
+            let token = if rng.next_u64() % 2 == 0 {
+                &pool.token0
+            } else {
+                &pool.token1
+            };
+            let amount = (max_amount as f64).powf(rng.next_f64()).max(1.0) as u128;
+            CurrencyAmount::from_raw_amount(token.clone(), amount).unwrap()
+        })
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::tests::*;
+
+    #[test]
+    fn generates_deterministic_pools() {
+        let generator = PoolGenerator::new(TOKEN0.clone(), TOKEN1.clone(), FeeAmount::MEDIUM);
+        let pool = generator.generate(&mut DeterministicRng::new(42));
+        assert_eq!(
+            pool.tick_data_provider,
+            generator
+                .generate(&mut DeterministicRng::new(42))
+                .tick_data_provider
+        );
+        assert_eq!(pool.tick_current, 0);
+        assert!(pool.liquidity > 0);
+        let amounts =
+            generate_swap_amounts(&pool, 10, 10_u128.pow(18), &mut DeterministicRng::new(7));
+        assert_eq!(amounts.len(), 10);
+        for amount in amounts {
+            pool.get_output_amount(&amount, None).unwrap();
+        }
+    }
+}
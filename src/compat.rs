@@ -0,0 +1,209 @@
+//! ## Compat
+//! Conversions between the `ethers-core` types of integrations written against the pre-alloy
+//! versions of this crate and the alloy types used across the public API, enabled by the `compat`
+//! feature.
+//!
+//! The orphan rule forbids `From` implementations between two foreign crates, so the conversions
+//! are provided by the [`ToAlloy`] and [`ToEthers`] extension traits instead, e.g.
+//! `pool.token0.address() == h160.to_alloy()`.
+
+use alloy_primitives::{Address, Bytes, B256, U256};
+use ethers_core::types as ethers;
+
+/// Converts an `ethers-core` type into its alloy counterpart.
+pub trait ToAlloy {
+    /// The alloy counterpart of the type.
+    type To;
+
+    /// Converts the value into its alloy counterpart
+    fn to_alloy(self) -> Self::To;
+}
+
+/// Converts an alloy type into its `ethers-core` counterpart.
+pub trait ToEthers {
+    /// The `ethers-core` counterpart of the type.
+    type To;
+
+    /// Converts the value into its `ethers-core` counterpart
+    fn to_ethers(self) -> Self::To;
+}
+
+impl ToAlloy for ethers::H160 {
+    type To = Address;
+
+    #[inline]
+    fn to_alloy(self) -> Address {
+        Address::new(self.0)
+    }
+}
+
+impl ToEthers for Address {
+    type To = ethers::H160;
+
+    #[inline]
+    fn to_ethers(self) -> ethers::H160 {
+        ethers::H160(self.0 .0)
+    }
+}
+
+impl ToAlloy for ethers::H256 {
+    type To = B256;
+
+    #[inline]
+    fn to_alloy(self) -> B256 {
+        B256::new(self.0)
+    }
+}
+
+impl ToEthers for B256 {
+    type To = ethers::H256;
+
+    #[inline]
+    fn to_ethers(self) -> ethers::H256 {
+        ethers::H256(self.0)
+    }
+}
+
+impl ToAlloy for ethers::U256 {
+    type To = U256;
+
+    #[inline]
+    fn to_alloy(self) -> U256 {
+        // both store the limbs in little-endian order
+        U256::from_limbs(self.0)
+    }
+}
+
+impl ToEthers for U256 {
+    type To = ethers::U256;
+
+    #[inline]
+    fn to_ethers(self) -> ethers::U256 {
+        ethers::U256(self.into_limbs())
+    }
+}
+
+impl ToAlloy for ethers::Bytes {
+    type To = Bytes;
+
+    #[inline]
+    fn to_alloy(self) -> Bytes {
+        Bytes(self.0)
+    }
+}
+
+impl ToEthers for Bytes {
+    type To = ethers::Bytes;
+
+    #[inline]
+    fn to_ethers(self) -> ethers::Bytes {
+        ethers::Bytes(self.0)
+    }
+}
+
+#[cfg(feature = "extensions")]
+mod block {
+    use super::*;
+    use alloy::eips::{BlockId, BlockNumberOrTag};
+
+    impl ToAlloy for ethers::BlockNumber {
+        type To = BlockNumberOrTag;
+
+        #[inline]
+        fn to_alloy(self) -> BlockNumberOrTag {
+            match self {
+                Self::Latest => BlockNumberOrTag::Latest,
+                Self::Finalized => BlockNumberOrTag::Finalized,
+                Self::Safe => BlockNumberOrTag::Safe,
+                Self::Earliest => BlockNumberOrTag::Earliest,
+                Self::Pending => BlockNumberOrTag::Pending,
+                Self::Number(number) => BlockNumberOrTag::Number(number.as_u64()),
+            }
+        }
+    }
+
+    impl ToEthers for BlockNumberOrTag {
+        type To = ethers::BlockNumber;
+
+        #[inline]
+        fn to_ethers(self) -> ethers::BlockNumber {
+            match self {
+                Self::Latest => ethers::BlockNumber::Latest,
+                Self::Finalized => ethers::BlockNumber::Finalized,
+                Self::Safe => ethers::BlockNumber::Safe,
+                Self::Earliest => ethers::BlockNumber::Earliest,
+                Self::Pending => ethers::BlockNumber::Pending,
+                Self::Number(number) => ethers::BlockNumber::Number(number.into()),
+            }
+        }
+    }
+
+    impl ToAlloy for ethers::BlockId {
+        type To = BlockId;
+
+        #[inline]
+        fn to_alloy(self) -> BlockId {
+            match self {
+                Self::Hash(hash) => BlockId::hash(hash.to_alloy()),
+                Self::Number(number) => BlockId::Number(number.to_alloy()),
+            }
+        }
+    }
+
+    impl ToEthers for BlockId {
+        type To = ethers::BlockId;
+
+        #[inline]
+        fn to_ethers(self) -> ethers::BlockId {
+            match self {
+                Self::Hash(hash) => ethers::BlockId::Hash(hash.block_hash.to_ethers()),
+                Self::Number(number) => ethers::BlockId::Number(number.to_ethers()),
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use alloy_primitives::{address, b256, bytes, uint};
+
+    #[test]
+    fn round_trips_primitives() {
+        let address = address!("1F98431c8aD98523631AE4a59f267346ea31F984");
+        let h160 = address.to_ethers();
+        assert_eq!(h160.as_bytes(), address.as_slice());
+        assert_eq!(h160.to_alloy(), address);
+
+        let hash = b256!("e34f199b19b2b4f47f68442619d555527d244f78a3297ea89325f843f87b8b54");
+        assert_eq!(hash.to_ethers().to_alloy(), hash);
+
+        let value = uint!(0x0102030405060708090a0b0c0d0e0f1011121314151617181920_U256);
+        let u256 = value.to_ethers();
+        assert_eq!(
+            u256,
+            ethers::U256::from_dec_str(&value.to_string()).unwrap()
+        );
+        assert_eq!(u256.to_alloy(), value);
+
+        let data = bytes!("deadbeef");
+        assert_eq!(data.clone().to_ethers().to_alloy(), data);
+    }
+
+    #[cfg(feature = "extensions")]
+    #[test]
+    fn round_trips_block_ids() {
+        use alloy::eips::{BlockId, BlockNumberOrTag};
+
+        for block_id in [
+            BlockId::Number(BlockNumberOrTag::Number(17188000)),
+            BlockId::Number(BlockNumberOrTag::Latest),
+            BlockId::Number(BlockNumberOrTag::Finalized),
+            BlockId::hash(b256!(
+                "e34f199b19b2b4f47f68442619d555527d244f78a3297ea89325f843f87b8b54"
+            )),
+        ] {
+            assert_eq!(block_id.to_ethers().to_alloy(), block_id);
+        }
+    }
+}
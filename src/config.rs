@@ -0,0 +1,332 @@
+//! ## Config
+//! Serializable counterparts of the option structs with JSON Schemas, enabled by the `schemars`
+//! feature, so that pipelines generating calldata can be driven and validated by JSON or YAML
+//! configs. Percentages are expressed in basis points, deadlines in epoch seconds and integers
+//! larger than 64 bits as hex strings.
+
+use crate::prelude::*;
+use alloc::{collections::BTreeMap, vec::Vec};
+use alloy_primitives::{Address, ChainId, U160, U256};
+use schemars::{schema::RootSchema, schema_for, JsonSchema};
+use serde::{Deserialize, Serialize};
+use uniswap_sdk_core::prelude::*;
+
+#[inline]
+fn percent_from_bips(bips: u32) -> Percent {
+    Percent::new(bips, 10_000)
+}
+
+/// The serializable counterpart of [`FeeOptions`].
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Serialize, Deserialize, JsonSchema)]
+pub struct FeeConfig {
+    /// The fee taken on the output, in basis points.
+    pub fee_bips: u32,
+    /// The recipient of the fee.
+    #[schemars(with = "String")]
+    pub recipient: Address,
+}
+
+impl From<FeeConfig> for FeeOptions {
+    #[inline]
+    fn from(config: FeeConfig) -> Self {
+        Self {
+            fee: percent_from_bips(config.fee_bips),
+            recipient: config.recipient,
+        }
+    }
+}
+
+/// The serializable counterpart of [`SwapOptions`], without the input token permit which has to
+/// be signed for every swap.
+#[derive(Clone, Debug, PartialEq, Eq, Serialize, Deserialize, JsonSchema)]
+pub struct SwapConfig {
+    /// How much the execution price is allowed to move unfavorably, in basis points.
+    pub slippage_tolerance_bips: u32,
+    /// The account that should receive the output.
+    #[schemars(with = "String")]
+    pub recipient: Address,
+    /// The optional price limit for the trade.
+    #[serde(default)]
+    #[schemars(with = "Option<String>")]
+    pub sqrt_price_limit_x96: Option<U160>,
+    /// Optional information for taking a fee on output.
+    #[serde(default)]
+    pub fee: Option<FeeConfig>,
+    /// The target the router forwards the output to after the swaps, instead of `recipient`.
+    #[serde(default)]
+    #[schemars(with = "Option<String>")]
+    pub sweep_output: Option<Address>,
+    /// Whether to wrap the calls in a `multicall`.
+    #[serde(default)]
+    pub multicall_wrapping: MulticallWrapping,
+    /// Optional recipients overriding `recipient` for the trade at the same index.
+    #[serde(default)]
+    #[schemars(with = "Vec<Option<String>>")]
+    pub trade_recipients: Vec<Option<Address>>,
+    /// The currency the fee is taken in, only relevant if a fee is taken on a native output.
+    #[serde(default)]
+    pub fee_currency: FeeCurrency,
+    /// The router the calls are encoded for, if not the canonical `SwapRouter02`.
+    #[serde(default)]
+    pub router: Option<RouterTarget>,
+    /// The deadline encoded in the swaps, required by a router with the original `SwapRouter`
+    /// encoding.
+    #[serde(default)]
+    #[schemars(with = "Option<String>")]
+    pub deadline: Option<U256>,
+    /// Whether to derive the price limit of the single-hop swaps of exact input trades from the
+    /// slippage tolerance.
+    #[serde(default)]
+    pub derive_sqrt_price_limit: bool,
+}
+
+impl From<SwapConfig> for SwapOptions {
+    #[inline]
+    fn from(config: SwapConfig) -> Self {
+        Self {
+            slippage_tolerance: percent_from_bips(config.slippage_tolerance_bips),
+            recipient: config.recipient,
+            input_token_permit: None,
+            input_token_permit2: None,
+            sqrt_price_limit_x96: config.sqrt_price_limit_x96,
+            fee: config.fee.map(Into::into),
+            sweep_output: config.sweep_output,
+            multicall_wrapping: config.multicall_wrapping,
+            trade_recipients: config.trade_recipients,
+            fee_currency: config.fee_currency,
+            router: config.router,
+            deadline: config.deadline,
+            derive_sqrt_price_limit: config.derive_sqrt_price_limit,
+        }
+    }
+}
+
+/// The serializable counterpart of [`AddLiquiditySpecificOptions`].
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Serialize, Deserialize, JsonSchema)]
+#[serde(rename_all = "snake_case")]
+pub enum AddLiquiditySpecificConfig {
+    Mint {
+        /// The account that should receive the minted NFT.
+        #[schemars(with = "String")]
+        recipient: Address,
+        /// Creates pool if not initialized before mint.
+        #[serde(default)]
+        create_pool: bool,
+    },
+    Increase {
+        /// The ID of the position to increase liquidity for.
+        #[schemars(with = "String")]
+        token_id: U256,
+    },
+}
+
+impl From<AddLiquiditySpecificConfig> for AddLiquiditySpecificOptions {
+    #[inline]
+    fn from(config: AddLiquiditySpecificConfig) -> Self {
+        match config {
+            AddLiquiditySpecificConfig::Mint {
+                recipient,
+                create_pool,
+            } => Self::Mint(MintSpecificOptions {
+                recipient,
+                create_pool,
+            }),
+            AddLiquiditySpecificConfig::Increase { token_id } => {
+                Self::Increase(IncreaseSpecificOptions { token_id })
+            }
+        }
+    }
+}
+
+/// The serializable counterpart of [`AddLiquidityOptions`], without the token permits which have
+/// to be signed for every transaction.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Serialize, Deserialize, JsonSchema)]
+pub struct AddLiquidityConfig {
+    /// How much the pool price is allowed to move, in basis points.
+    pub slippage_tolerance_bips: u32,
+    /// When the transaction expires, in epoch seconds.
+    pub deadline: u64,
+    /// Whether to spend ether. If true, one of the pool tokens must be the wrapped native token.
+    #[serde(default)]
+    pub use_native: bool,
+    /// Whether to mint a new position or increase an existing one.
+    pub specific_opts: AddLiquiditySpecificConfig,
+}
+
+impl AddLiquidityConfig {
+    /// Returns the options to add liquidity with
+    ///
+    /// ## Arguments
+    ///
+    /// * `chain_id`: The chain of the native currency spent if `use_native` is set
+    #[inline]
+    #[must_use]
+    pub fn to_options(&self, chain_id: ChainId) -> AddLiquidityOptions {
+        AddLiquidityOptions {
+            slippage_tolerance: percent_from_bips(self.slippage_tolerance_bips),
+            deadline: U256::from(self.deadline),
+            use_native: self.use_native.then(|| Ether::on_chain(chain_id)),
+            token0_permit: None,
+            token1_permit: None,
+            specific_opts: self.specific_opts.into(),
+        }
+    }
+}
+
+/// The serializable counterpart of [`CollectOptions`]. The expected amounts owed depend on the
+/// state of the position and are given when converting.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Serialize, Deserialize, JsonSchema)]
+pub struct CollectConfig {
+    /// The ID of the position to collect for.
+    #[schemars(with = "String")]
+    pub token_id: U256,
+    /// The account that should receive the tokens.
+    #[schemars(with = "String")]
+    pub recipient: Address,
+}
+
+impl CollectConfig {
+    /// Returns the options to collect with
+    ///
+    /// ## Arguments
+    ///
+    /// * `expected_currency_owed0`: The expected value of tokensOwed0
+    /// * `expected_currency_owed1`: The expected value of tokensOwed1
+    #[inline]
+    #[must_use]
+    pub const fn to_options<Currency0: BaseCurrency, Currency1: BaseCurrency>(
+        &self,
+        expected_currency_owed0: CurrencyAmount<Currency0>,
+        expected_currency_owed1: CurrencyAmount<Currency1>,
+    ) -> CollectOptions<Currency0, Currency1> {
+        CollectOptions {
+            token_id: self.token_id,
+            expected_currency_owed0,
+            expected_currency_owed1,
+            recipient: self.recipient,
+        }
+    }
+}
+
+/// The serializable counterpart of [`RemoveLiquidityOptions`], without the NFT permit which has
+/// to be signed for every transaction.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Serialize, Deserialize, JsonSchema)]
+pub struct RemoveLiquidityConfig {
+    /// The percentage of position liquidity to exit, in basis points.
+    pub liquidity_percentage_bips: u32,
+    /// How much the pool price is allowed to move, in basis points.
+    pub slippage_tolerance_bips: u32,
+    /// When the transaction expires, in epoch seconds.
+    pub deadline: u64,
+    /// Whether the NFT should be burned if the entire position is being exited.
+    #[serde(default)]
+    pub burn_token: bool,
+    /// Parameters to be passed on to collect.
+    pub collect_options: CollectConfig,
+}
+
+impl RemoveLiquidityConfig {
+    /// Returns the options to exit a position with
+    ///
+    /// ## Arguments
+    ///
+    /// * `expected_currency_owed0`: The expected value of tokensOwed0, excluding the liquidity
+    ///   removed
+    /// * `expected_currency_owed1`: The expected value of tokensOwed1, excluding the liquidity
+    ///   removed
+    #[inline]
+    #[must_use]
+    pub fn to_options<Currency0: BaseCurrency, Currency1: BaseCurrency>(
+        &self,
+        expected_currency_owed0: CurrencyAmount<Currency0>,
+        expected_currency_owed1: CurrencyAmount<Currency1>,
+    ) -> RemoveLiquidityOptions<Currency0, Currency1> {
+        RemoveLiquidityOptions {
+            token_id: self.collect_options.token_id,
+            liquidity_percentage: percent_from_bips(self.liquidity_percentage_bips),
+            slippage_tolerance: percent_from_bips(self.slippage_tolerance_bips),
+            deadline: U256::from(self.deadline),
+            burn_token: self.burn_token,
+            permit: None,
+            collect_options: self
+                .collect_options
+                .to_options(expected_currency_owed0, expected_currency_owed1),
+        }
+    }
+}
+
+/// Returns the JSON Schemas of all configs keyed by the name of the corresponding options
+#[inline]
+#[must_use]
+pub fn option_schemas() -> BTreeMap<&'static str, RootSchema> {
+    BTreeMap::from([
+        ("SwapOptions", schema_for!(SwapConfig)),
+        ("AddLiquidityOptions", schema_for!(AddLiquidityConfig)),
+        ("RemoveLiquidityOptions", schema_for!(RemoveLiquidityConfig)),
+        ("CollectOptions", schema_for!(CollectConfig)),
+        ("FeeOptions", schema_for!(FeeConfig)),
+    ])
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::tests::*;
+    use alloy_primitives::address;
+
+    #[test]
+    fn swap_config_from_json() {
+        let config: SwapConfig = serde_json::from_str(
+            r#"{
+                "slippage_tolerance_bips": 50,
+                "recipient": "0x0000000000000000000000000000000000000003",
+                "fee": { "fee_bips": 10, "recipient": "0x0000000000000000000000000000000000000009" },
+                "multicall_wrapping": "Always"
+            }"#,
+        )
+        .unwrap();
+        let options = SwapOptions::from(config);
+        assert_eq!(options.slippage_tolerance, Percent::new(50, 10_000));
+        assert_eq!(
+            options.recipient,
+            address!("0000000000000000000000000000000000000003")
+        );
+        assert_eq!(options.fee.unwrap().fee, Percent::new(10, 10_000));
+        assert_eq!(options.multicall_wrapping, MulticallWrapping::Always);
+        assert!(options.sweep_output.is_none());
+        assert!(options.trade_recipients.is_empty());
+    }
+
+    #[test]
+    fn add_liquidity_config_to_options() {
+        let config: AddLiquidityConfig = serde_json::from_str(
+            r#"{
+                "slippage_tolerance_bips": 100,
+                "deadline": 123,
+                "use_native": true,
+                "specific_opts": { "increase": { "token_id": "0x1" } }
+            }"#,
+        )
+        .unwrap();
+        let options = config.to_options(1);
+        assert_eq!(options.deadline, U256::from(123));
+        assert_eq!(options.use_native, Some(ETHER.clone()));
+        assert_eq!(
+            options.specific_opts,
+            AddLiquiditySpecificOptions::Increase(IncreaseSpecificOptions {
+                token_id: U256::from(1)
+            })
+        );
+    }
+
+    #[test]
+    fn schemas_describe_every_config() {
+        let schemas = option_schemas();
+        assert_eq!(schemas.len(), 5);
+        let swap = serde_json::to_value(&schemas["SwapOptions"]).unwrap();
+        let required = swap["required"].as_array().unwrap();
+        assert!(required.contains(&"slippage_tolerance_bips".into()));
+        assert!(required.contains(&"recipient".into()));
+        assert!(!required.contains(&"fee".into()));
+    }
+}
@@ -1,14 +1,223 @@
+use crate::utils::compute_pool_address;
 use alloy_primitives::{
     address,
     aliases::{I24, U24},
-    b256, Address, B256,
+    b256, Address, ChainId, B256,
 };
+use uniswap_sdk_core::prelude::{Ether, Token};
 
 pub const FACTORY_ADDRESS: Address = address!("1F98431c8aD98523631AE4a59f267346ea31F984");
 
+/// The address of the `Multicall3` contract, the same on every chain.
+pub const MULTICALL3_ADDRESS: Address = address!("cA11bde05977b3631167028862bE2a173976CA11");
+
 pub const POOL_INIT_CODE_HASH: B256 =
     b256!("e34f199b19b2b4f47f68442619d555527d244f78a3297ea89325f843f87b8b54");
 
+/// The factory and the pool init code hash of a Uniswap V3 deployment, from which the addresses
+/// of its pools are derived.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub struct PoolDeployment {
+    /// The address of the factory.
+    pub factory: Address,
+    /// The hash of the creation code of the pools.
+    pub init_code_hash: B256,
+}
+
+impl PoolDeployment {
+    /// The canonical deployment on Ethereum, Optimism, Polygon and Arbitrum One.
+    pub const CANONICAL: Self = Self::new(FACTORY_ADDRESS, POOL_INIT_CODE_HASH);
+
+    /// Creates a deployment from its factory and pool init code hash
+    ///
+    /// ## Arguments
+    ///
+    /// * `factory`: The address of the factory
+    /// * `init_code_hash`: The hash of the creation code of the pools
+    #[inline]
+    #[must_use]
+    pub const fn new(factory: Address, init_code_hash: B256) -> Self {
+        Self {
+            factory,
+            init_code_hash,
+        }
+    }
+
+    /// Returns the deployment of the Uniswap Labs factory on a chain, or the canonical deployment
+    /// if the chain is not in [`ChainDeployment::for_chain`]
+    ///
+    /// ## Arguments
+    ///
+    /// * `chain_id`: The chain id
+    #[inline]
+    #[must_use]
+    pub const fn for_chain(chain_id: ChainId) -> Self {
+        match ChainDeployment::for_chain(chain_id) {
+            Some(deployment) => deployment.pool,
+            None => Self::CANONICAL,
+        }
+    }
+
+    /// Returns the deployment of a factory on a chain, with the pool init code hash of
+    /// [`Self::for_chain`], e.g. for the pools of a factory given by address
+    ///
+    /// ## Arguments
+    ///
+    /// * `chain_id`: The chain id
+    /// * `factory`: The address of the factory
+    #[inline]
+    #[must_use]
+    pub const fn for_factory(chain_id: ChainId, factory: Address) -> Self {
+        Self::new(factory, Self::for_chain(chain_id).init_code_hash)
+    }
+
+    /// Computes the address of a pool of the deployment, see [`compute_pool_address()`]
+    ///
+    /// ## Arguments
+    ///
+    /// * `token_a`: The first token of the pair, irrespective of sort order
+    /// * `token_b`: The second token of the pair, irrespective of sort order
+    /// * `fee`: The fee tier of the pool
+    /// * `chain_id`: The chain id, which selects the create2 derivation, e.g. on zkSync
+    #[inline]
+    #[must_use]
+    pub fn pool_address(
+        self,
+        token_a: Address,
+        token_b: Address,
+        fee: FeeAmount,
+        chain_id: ChainId,
+    ) -> Address {
+        compute_pool_address(
+            self.factory,
+            token_a,
+            token_b,
+            fee,
+            Some(self.init_code_hash),
+            Some(chain_id),
+        )
+    }
+}
+
+/// The addresses of the Uniswap Labs deployment of Uniswap V3 on a chain.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub struct ChainDeployment {
+    /// The factory and pool init code hash.
+    pub pool: PoolDeployment,
+    /// The address of the `NonfungiblePositionManager`.
+    pub nonfungible_position_manager: Address,
+    /// The address of `QuoterV2`.
+    pub quoter_v2: Address,
+}
+
+impl ChainDeployment {
+    /// The chains with a known deployment.
+    pub const CHAIN_IDS: [ChainId; 7] = [1, 10, 56, 137, 324, 8453, 42161];
+
+    /// Returns the deployment on a chain, or `None` if the chain is not supported
+    ///
+    /// ## Arguments
+    ///
+    /// * `chain_id`: The chain id
+    #[inline]
+    #[must_use]
+    pub const fn for_chain(chain_id: ChainId) -> Option<Self> {
+        const CANONICAL: ChainDeployment = ChainDeployment {
+            pool: PoolDeployment::CANONICAL,
+            nonfungible_position_manager: address!("C36442b4a4522E871399CD717aBDD847Ab11FE88"),
+            quoter_v2: address!("61fFE014bA17989E743c5F6cB21bF9697530B21e"),
+        };
+        Some(match chain_id {
+            1 | 10 | 137 | 42161 => CANONICAL,
+            56 => Self {
+                pool: PoolDeployment::new(
+                    address!("dB1d10011AD0Ff90774D0C6Bb92e5C5c8b4461F7"),
+                    POOL_INIT_CODE_HASH,
+                ),
+                nonfungible_position_manager: address!("7b8A01B39D58278b5DE7e48c8449c9f4F5170613"),
+                quoter_v2: address!("78D78E420Da98ad378D7799bE8f4AF69033EB077"),
+            },
+            // zkSync Era derives the pool addresses with its own create2
+            324 => Self {
+                pool: PoolDeployment::new(
+                    address!("8FdA5a7a8dCA67BBcDd10F02Fa0649A937215422"),
+                    b256!("010013f177ea1fcbc4520f9a3ca7cd2d1d77959e05aa66484027cb38e712aeed"),
+                ),
+                nonfungible_position_manager: address!("0616e5762c1E7Dc3723c50663dF10a162D690a86"),
+                quoter_v2: address!("8Cb537fc92E26d8EBBb760E632c95484b6Ea3e28"),
+            },
+            8453 => Self {
+                pool: PoolDeployment::new(
+                    address!("33128a8fC17869897dcE68Ed026d694621f6FDfD"),
+                    POOL_INIT_CODE_HASH,
+                ),
+                nonfungible_position_manager: address!("03a520b32C04BF3bEEf7BEb72E919cf822Ed34f1"),
+                quoter_v2: address!("3d4e44Eb1374240CE5F1B871ab261CD16335B76a"),
+            },
+            _ => return None,
+        })
+    }
+}
+
+/// The version of the calldata produced by the encoders in this crate.
+///
+/// The value is bumped whenever any encoder emits different bytes for the same inputs, e.g. a
+/// change in the order of multicall subcalls or in the default recipient. Releases that do not
+/// bump it are guaranteed to produce byte-identical calldata, which is enforced by the golden
+/// corpus in `tests/calldata`. See the "Calldata stability" section of the README.
+pub const ENCODING_VERSION: u32 = 1;
+
+/// Returns whether the native currency of a chain is itself an ERC20 token, e.g. CELO on Celo.
+///
+/// There is no WETH9 to wrap into or unwrap from on such chains, so the encoders treat the native
+/// currency as its ERC20 token: no value is sent and the output is not unwrapped. Use
+/// [`native_currency`] to obtain a native currency that wraps to that token.
+///
+/// ## Arguments
+///
+/// * `chain_id`: The chain id
+#[inline]
+#[must_use]
+pub const fn is_native_erc20(chain_id: ChainId) -> bool {
+    const CELO: ChainId = uniswap_sdk_core::prelude::ChainId::CELO as ChainId;
+    const CELO_ALFAJORES: ChainId = uniswap_sdk_core::prelude::ChainId::CELO_ALFAJORES as ChainId;
+    matches!(chain_id, CELO | CELO_ALFAJORES)
+}
+
+/// Returns the native currency of a chain.
+///
+/// Unlike [`Ether::on_chain`], which panics with "WRAPPED" when the native currency of a chain
+/// where [`is_native_erc20`] is wrapped, e.g. CELO on Celo, the returned currency wraps to its
+/// ERC20 token, so that it can be routed and encoded like any other token.
+///
+/// ## Arguments
+///
+/// * `chain_id`: The chain id
+#[inline]
+#[must_use]
+pub fn native_currency(chain_id: ChainId) -> Ether {
+    let celo = match chain_id {
+        42220 => address!("471EcE3750Da237f93B8E339c536989b8978a438"),
+        44787 => address!("F194afDf50B03e69Bd7D057c1Aa9e10c9954E4C9"),
+        _ => return Ether::on_chain(chain_id),
+    };
+    Ether {
+        chain_id,
+        decimals: 18,
+        symbol: Some("CELO".into()),
+        name: Some("Celo".into()),
+        meta: Some(Token::new(
+            chain_id,
+            celo,
+            18,
+            Some("CELO".into()),
+            Some("Celo".into()),
+            0,
+            0,
+        )),
+    }
+}
+
 /// The default factory enabled fee amounts, denominated in hundredths of bips.
 #[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
 #[repr(u32)]
@@ -96,3 +305,181 @@ impl From<U24> for FeeAmount {
         (fee.into_limbs()[0] as u32).into()
     }
 }
+
+/// Tokens of the [`KnownPool`] presets.
+#[derive(Clone, Copy)]
+enum KnownToken {
+    Weth,
+    Usdc,
+    Usdt,
+    Wbtc,
+    Dai,
+}
+
+impl KnownToken {
+    /// The address of the token on Ethereum, Optimism, Polygon and Arbitrum One.
+    const fn address(self, chain_id: ChainId) -> Option<Address> {
+        Some(match (chain_id, self) {
+            (1, Self::Weth) => address!("C02aaA39b223FE8D0A0e5C4F27eAD9083C756Cc2"),
+            (1, Self::Usdc) => address!("A0b86991c6218b36c1d19D4a2e9Eb0cE3606eB48"),
+            (1, Self::Usdt) => address!("dAC17F958D2ee523a2206206994597C13D831ec7"),
+            (1, Self::Wbtc) => address!("2260FAC5E5542a773Aa44fBCfeDf7C193bc2C599"),
+            (1, Self::Dai) => address!("6B175474E89094C44Da98b954EedeAC495271d0F"),
+            (10, Self::Weth) => address!("4200000000000000000000000000000000000006"),
+            (10, Self::Usdc) => address!("0b2C639c533813f4Aa9D7837cAf62653d097Ff85"),
+            (10, Self::Usdt) => address!("94b008aA00579c1307B0EF2c499aD98a8ce58e58"),
+            (10, Self::Wbtc) => address!("68f180fcCe6836688e9084f035309E29Bf0A2095"),
+            (10, Self::Dai) => address!("DA10009cBd5D07dd0CeCc66161FC93D7c9000da1"),
+            (137, Self::Weth) => address!("7ceB23fD6bC0adD59E62ac25578270cFf1b9f619"),
+            (137, Self::Usdc) => address!("3c499c542cEF5E3811e1192ce70d8cC03d5c3359"),
+            (137, Self::Usdt) => address!("c2132D05D31c914a87C6611C10748AEb04B58e8F"),
+            (137, Self::Wbtc) => address!("1BFD67037B42Cf73acF2047067bd4F2C47D9BfD6"),
+            (137, Self::Dai) => address!("8f3Cf7ad23Cd3CaDbD9735AFf958023239c6A063"),
+            (42161, Self::Weth) => address!("82aF49447D8a07e3bd95BD0d56f35241523fBab1"),
+            (42161, Self::Usdc) => address!("af88d065e77c8cC2239327C5EDb3A432268e5831"),
+            (42161, Self::Usdt) => address!("Fd086bC7CD5C481DCC9C85ebE478A1C0b69FCbb9"),
+            (42161, Self::Wbtc) => address!("2f2a2543B76A4166549F7aaB2e75Bef0aefC5B0f"),
+            (42161, Self::Dai) => address!("DA10009cBd5D07dd0CeCc66161FC93D7c9000da1"),
+            _ => return None,
+        })
+    }
+}
+
+/// Presets for popular pools deployed by the canonical [`FACTORY_ADDRESS`].
+///
+/// The pool addresses are derived from the token addresses on each supported chain, i.e. Ethereum,
+/// Optimism, Polygon and Arbitrum One. USDC refers to the native USDC on the L2s.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum KnownPool {
+    /// WETH/USDC 0.05%
+    WethUsdc500,
+    /// WETH/USDC 0.3%
+    WethUsdc3000,
+    /// WETH/USDT 0.05%
+    WethUsdt500,
+    /// WETH/USDT 0.3%
+    WethUsdt3000,
+    /// WBTC/WETH 0.05%
+    WbtcWeth500,
+    /// WBTC/WETH 0.3%
+    WbtcWeth3000,
+    /// DAI/USDC 0.01%
+    DaiUsdc100,
+}
+
+impl KnownPool {
+    /// All the presets.
+    pub const ALL: [Self; 7] = [
+        Self::WethUsdc500,
+        Self::WethUsdc3000,
+        Self::WethUsdt500,
+        Self::WethUsdt3000,
+        Self::WbtcWeth500,
+        Self::WbtcWeth3000,
+        Self::DaiUsdc100,
+    ];
+
+    /// The chains on which the presets are available.
+    pub const CHAIN_IDS: [ChainId; 4] = [1, 10, 137, 42161];
+
+    const fn known_tokens(self) -> (KnownToken, KnownToken) {
+        match self {
+            Self::WethUsdc500 | Self::WethUsdc3000 => (KnownToken::Weth, KnownToken::Usdc),
+            Self::WethUsdt500 | Self::WethUsdt3000 => (KnownToken::Weth, KnownToken::Usdt),
+            Self::WbtcWeth500 | Self::WbtcWeth3000 => (KnownToken::Wbtc, KnownToken::Weth),
+            Self::DaiUsdc100 => (KnownToken::Dai, KnownToken::Usdc),
+        }
+    }
+
+    /// The fee tier of the pool.
+    #[inline]
+    #[must_use]
+    pub const fn fee(self) -> FeeAmount {
+        match self {
+            Self::WethUsdc500 | Self::WethUsdt500 | Self::WbtcWeth500 => FeeAmount::LOW,
+            Self::WethUsdc3000 | Self::WethUsdt3000 | Self::WbtcWeth3000 => FeeAmount::MEDIUM,
+            Self::DaiUsdc100 => FeeAmount::LOWEST,
+        }
+    }
+
+    /// The token addresses of the pool in the order of the name, not sorted, or `None` if the
+    /// chain is not supported.
+    #[inline]
+    #[must_use]
+    pub const fn tokens(self, chain_id: ChainId) -> Option<(Address, Address)> {
+        let (token_a, token_b) = self.known_tokens();
+        match (token_a.address(chain_id), token_b.address(chain_id)) {
+            (Some(token_a), Some(token_b)) => Some((token_a, token_b)),
+            _ => None,
+        }
+    }
+
+    /// The address of the pool, or `None` if the chain is not supported.
+    #[inline]
+    #[must_use]
+    pub fn address(self, chain_id: ChainId) -> Option<Address> {
+        let (token_a, token_b) = self.tokens(chain_id)?;
+        Some(compute_pool_address(
+            FACTORY_ADDRESS,
+            token_a,
+            token_b,
+            self.fee(),
+            None,
+            Some(chain_id),
+        ))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn known_pool_addresses() {
+        assert_eq!(
+            KnownPool::WethUsdc500.address(1),
+            Some(address!("88e6A0c2dDD26FEEb64F039a2c41296FcB3f5640"))
+        );
+        assert_eq!(
+            KnownPool::WethUsdc3000.address(1),
+            Some(address!("8ad599c3A0ff1De082011EFDDc58f1908eb6e6D8"))
+        );
+        assert_eq!(
+            KnownPool::WethUsdt3000.address(1),
+            Some(address!("4e68Ccd3E89f51C3074ca5072bbAC773960dFa36"))
+        );
+        assert_eq!(
+            KnownPool::WbtcWeth3000.address(1),
+            Some(address!("CBCdF9626bC03E24f779434178A73a0B4bad62eD"))
+        );
+        assert_eq!(
+            KnownPool::WethUsdc500.address(42161),
+            Some(address!("C6962004f452bE9203591991D15f6b388e09E8D0"))
+        );
+        assert_eq!(KnownPool::WethUsdc500.address(56), None);
+    }
+
+    #[test]
+    fn pool_deployments() {
+        assert_eq!(PoolDeployment::for_chain(1), PoolDeployment::CANONICAL);
+        assert_eq!(PoolDeployment::for_chain(5), PoolDeployment::CANONICAL);
+        assert!(ChainDeployment::CHAIN_IDS
+            .iter()
+            .all(|&chain_id| ChainDeployment::for_chain(chain_id).is_some()));
+        // WETH/USDC 0.05% on Base
+        assert_eq!(
+            PoolDeployment::for_chain(8453).pool_address(
+                address!("4200000000000000000000000000000000000006"),
+                address!("833589fCD6eDb6E08f4c7C32D4f71b54bdA02913"),
+                FeeAmount::LOW,
+                8453
+            ),
+            address!("d0b53D9277642d899DF5C87A3966A349A798F224")
+        );
+        let factory = address!("0000000000000000000000000000000000000001");
+        assert_eq!(
+            PoolDeployment::for_factory(324, factory),
+            PoolDeployment::new(factory, PoolDeployment::for_chain(324).init_code_hash)
+        );
+    }
+}
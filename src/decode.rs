@@ -0,0 +1,786 @@
+//! ## Decode
+//! Decodes router and position manager calldata, e.g. returned by [`swap_call_parameters`] or
+//! [`add_call_parameters`], back into structured types, so that indexers and simulators can
+//! introspect the transactions of a mempool and monitor liquidity providers.
+
+use crate::prelude::{Error, *};
+use alloc::vec::Vec;
+use alloy_primitives::{aliases::U24, Address, Bytes, U160, U256};
+use alloy_sol_types::{SolCall, SolInterface};
+use uniswap_sdk_core::prelude::TradeType;
+use INonfungiblePositionManager::INonfungiblePositionManagerCalls;
+use ISwapRouter::ISwapRouterCalls;
+use IV3SwapRouter::IV3SwapRouterCalls;
+
+/// Decodes an encoded path, the inverse of [`encode_route_to_path()`]
+///
+/// ## Arguments
+///
+/// * `path`: The packed path, i.e. `token (fee token)+` with 20-byte addresses and 3-byte fees
+///
+/// ## Returns
+///
+/// The tokens and the fees of the pools between them, in the order they are encoded, i.e. from
+/// output to input for the paths of exact output swaps
+#[inline]
+pub fn decode_path(path: &[u8]) -> Result<(Vec<Address>, Vec<U24>), Error> {
+    if path.len() < 43 || !(path.len() - 20).is_multiple_of(23) {
+        return Err(Error::InvalidPath(path.len()));
+    }
+    let hops = (path.len() - 20) / 23;
+    let mut tokens = Vec::with_capacity(hops + 1);
+    let mut fees = Vec::with_capacity(hops);
+    for hop in path[..path.len() - 20].chunks_exact(23) {
+        tokens.push(Address::from_slice(&hop[..20]));
+        fees.push(U24::from_be_slice(&hop[20..]));
+    }
+    tokens.push(Address::from_slice(&path[path.len() - 20..]));
+    Ok((tokens, fees))
+}
+
+/// Splits calldata wrapped in `multicall(bytes[])` or `multicall(uint256,bytes[])` into its calls
+///
+/// ## Arguments
+///
+/// * `calldata`: The calldata of the transaction
+///
+/// ## Returns
+///
+/// The deadline of the multicall if any, and the calls, or the calldata itself if it is not a
+/// multicall
+#[inline]
+#[must_use]
+pub fn unwrap_multicall(calldata: &[u8]) -> (Option<U256>, Vec<Bytes>) {
+    split_multicall(calldata)
+        .unwrap_or_else(|| (None, alloc::vec![Bytes::copy_from_slice(calldata)]))
+}
+
+/// Same as [`unwrap_multicall`] but returns `None` if the calldata is not a multicall
+#[inline]
+pub(crate) fn split_multicall(calldata: &[u8]) -> Option<(Option<U256>, Vec<Bytes>)> {
+    if let Ok(call) = IMulticall::multicallCall::abi_decode(calldata, true) {
+        Some((None, call.data))
+    } else if let Ok(call) = IMulticallExtended::multicallCall::abi_decode(calldata, true) {
+        Some((Some(call.deadline), call.data))
+    } else {
+        None
+    }
+}
+
+/// Decodes the path of a swap into its tokens and fees from input to output
+fn decode_swap_path(path: &[u8], trade_type: TradeType) -> Result<(Vec<Address>, Vec<U24>), Error> {
+    let (mut tokens, mut fees) = decode_path(path)?;
+    // exact output paths are encoded in reverse
+    if trade_type == TradeType::ExactOutput {
+        tokens.reverse();
+        fees.reverse();
+    }
+    Ok((tokens, fees))
+}
+
+/// A swap decoded from the calldata of `SwapRouter02` or the original `SwapRouter`.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct DecodedSwap {
+    /// The tokens of the route from input to output.
+    pub tokens: Vec<Address>,
+    /// The fees of the pools of the route, in the same order.
+    pub fees: Vec<U24>,
+    /// Whether the input or the output of the swap is exact.
+    pub trade_type: TradeType,
+    /// The exact input for exact input swaps, the exact output for exact output swaps.
+    pub amount: U256,
+    /// The minimum output for exact input swaps, the maximum input for exact output swaps.
+    pub amount_limit: U256,
+    /// The recipient of the output, the zero address if the router takes custody of the output to
+    /// unwrap or sweep it in a later call.
+    pub recipient: Address,
+    /// The price limit of a single pool swap, zero if none.
+    pub sqrt_price_limit_x96: U160,
+    /// The deadline of the swap, only encoded by the original `SwapRouter`.
+    pub deadline: Option<U256>,
+}
+
+impl DecodedSwap {
+    /// The input token of the swap
+    #[inline]
+    #[must_use]
+    pub fn token_in(&self) -> Address {
+        self.tokens[0]
+    }
+
+    /// The output token of the swap
+    #[inline]
+    #[must_use]
+    pub fn token_out(&self) -> Address {
+        self.tokens[self.tokens.len() - 1]
+    }
+
+    /// Decodes a swap call of `SwapRouter02`, or of the original `SwapRouter`
+    ///
+    /// ## Arguments
+    ///
+    /// * `calldata`: The calldata of the call
+    ///
+    /// ## Returns
+    ///
+    /// `None` if the calldata is not a swap call, or an error if its path is invalid
+    #[inline]
+    pub fn decode(calldata: &[u8]) -> Result<Option<Self>, Error> {
+        if let Ok(call) = IV3SwapRouterCalls::abi_decode(calldata, true) {
+            return Self::decode_v3_swap_router(call).map(Some);
+        }
+        let Ok(call) = ISwapRouterCalls::abi_decode(calldata, true) else {
+            return Ok(None);
+        };
+        Ok(Some(match call {
+            ISwapRouterCalls::exactInputSingle(call) => Self {
+                tokens: alloc::vec![call.params.tokenIn, call.params.tokenOut],
+                fees: alloc::vec![call.params.fee],
+                trade_type: TradeType::ExactInput,
+                amount: call.params.amountIn,
+                amount_limit: call.params.amountOutMinimum,
+                recipient: call.params.recipient,
+                sqrt_price_limit_x96: call.params.sqrtPriceLimitX96,
+                deadline: Some(call.params.deadline),
+            },
+            ISwapRouterCalls::exactOutputSingle(call) => Self {
+                tokens: alloc::vec![call.params.tokenIn, call.params.tokenOut],
+                fees: alloc::vec![call.params.fee],
+                trade_type: TradeType::ExactOutput,
+                amount: call.params.amountOut,
+                amount_limit: call.params.amountInMaximum,
+                recipient: call.params.recipient,
+                sqrt_price_limit_x96: call.params.sqrtPriceLimitX96,
+                deadline: Some(call.params.deadline),
+            },
+            ISwapRouterCalls::exactInput(call) => {
+                let (tokens, fees) = decode_swap_path(&call.params.path, TradeType::ExactInput)?;
+                Self {
+                    tokens,
+                    fees,
+                    trade_type: TradeType::ExactInput,
+                    amount: call.params.amountIn,
+                    amount_limit: call.params.amountOutMinimum,
+                    recipient: call.params.recipient,
+                    sqrt_price_limit_x96: U160::ZERO,
+                    deadline: Some(call.params.deadline),
+                }
+            }
+            ISwapRouterCalls::exactOutput(call) => {
+                let (tokens, fees) = decode_swap_path(&call.params.path, TradeType::ExactOutput)?;
+                Self {
+                    tokens,
+                    fees,
+                    trade_type: TradeType::ExactOutput,
+                    amount: call.params.amountOut,
+                    amount_limit: call.params.amountInMaximum,
+                    recipient: call.params.recipient,
+                    sqrt_price_limit_x96: U160::ZERO,
+                    deadline: Some(call.params.deadline),
+                }
+            }
+        }))
+    }
+
+    fn decode_v3_swap_router(call: IV3SwapRouterCalls) -> Result<Self, Error> {
+        Ok(match call {
+            IV3SwapRouterCalls::exactInputSingle(call) => Self {
+                tokens: alloc::vec![call.params.tokenIn, call.params.tokenOut],
+                fees: alloc::vec![call.params.fee],
+                trade_type: TradeType::ExactInput,
+                amount: call.params.amountIn,
+                amount_limit: call.params.amountOutMinimum,
+                recipient: call.params.recipient,
+                sqrt_price_limit_x96: call.params.sqrtPriceLimitX96,
+                deadline: None,
+            },
+            IV3SwapRouterCalls::exactOutputSingle(call) => Self {
+                tokens: alloc::vec![call.params.tokenIn, call.params.tokenOut],
+                fees: alloc::vec![call.params.fee],
+                trade_type: TradeType::ExactOutput,
+                amount: call.params.amountOut,
+                amount_limit: call.params.amountInMaximum,
+                recipient: call.params.recipient,
+                sqrt_price_limit_x96: call.params.sqrtPriceLimitX96,
+                deadline: None,
+            },
+            IV3SwapRouterCalls::exactInput(call) => {
+                let (tokens, fees) = decode_swap_path(&call.params.path, TradeType::ExactInput)?;
+                Self {
+                    tokens,
+                    fees,
+                    trade_type: TradeType::ExactInput,
+                    amount: call.params.amountIn,
+                    amount_limit: call.params.amountOutMinimum,
+                    recipient: call.params.recipient,
+                    sqrt_price_limit_x96: U160::ZERO,
+                    deadline: None,
+                }
+            }
+            IV3SwapRouterCalls::exactOutput(call) => {
+                let (tokens, fees) = decode_swap_path(&call.params.path, TradeType::ExactOutput)?;
+                Self {
+                    tokens,
+                    fees,
+                    trade_type: TradeType::ExactOutput,
+                    amount: call.params.amountOut,
+                    amount_limit: call.params.amountInMaximum,
+                    recipient: call.params.recipient,
+                    sqrt_price_limit_x96: U160::ZERO,
+                    deadline: None,
+                }
+            }
+        })
+    }
+}
+
+/// A call of `SwapRouter02` decoded by [`decode_swap_calldata`].
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub enum DecodedRouterCall {
+    /// A swap through one or more pools.
+    Swap(DecodedSwap),
+    /// A payment, e.g. unwrapping WETH, sweeping a token or refunding ether.
+    Payment(PaymentCall),
+    /// A call that is neither a swap nor a payment, e.g. a permit.
+    Other(Bytes),
+}
+
+/// The calls of a `SwapRouter02` transaction decoded by [`decode_swap_calldata`].
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct DecodedSwapCalldata {
+    /// The deadline of the multicall, if any.
+    pub deadline: Option<U256>,
+    /// The calls in the order they are made.
+    pub calls: Vec<DecodedRouterCall>,
+}
+
+impl DecodedSwapCalldata {
+    /// The swaps of the transaction in the order they are made
+    #[inline]
+    pub fn swaps(&self) -> impl Iterator<Item = &DecodedSwap> {
+        self.calls.iter().filter_map(|call| match call {
+            DecodedRouterCall::Swap(swap) => Some(swap),
+            _ => None,
+        })
+    }
+
+    /// The payments of the transaction in the order they are made
+    #[inline]
+    pub fn payments(&self) -> impl Iterator<Item = &PaymentCall> {
+        self.calls.iter().filter_map(|call| match call {
+            DecodedRouterCall::Payment(payment) => Some(payment),
+            _ => None,
+        })
+    }
+}
+
+/// Decodes the calldata of `SwapRouter02` or the original `SwapRouter`, wrapped in a `multicall`
+/// or not, into its swaps and payments, the reverse of [`swap_call_parameters`]
+///
+/// ## Arguments
+///
+/// * `calldata`: The calldata of the transaction
+#[inline]
+pub fn decode_swap_calldata(calldata: &[u8]) -> Result<DecodedSwapCalldata, Error> {
+    let (deadline, data) = unwrap_multicall(calldata);
+    let calls = data
+        .into_iter()
+        .map(|call| {
+            if let Some(swap) = DecodedSwap::decode(&call)? {
+                Ok(DecodedRouterCall::Swap(swap))
+            } else if let Some(payment) = PaymentCall::decode(&call) {
+                Ok(DecodedRouterCall::Payment(payment))
+            } else {
+                Ok(DecodedRouterCall::Other(call))
+            }
+        })
+        .collect::<Result<_, Error>>()?;
+    Ok(DecodedSwapCalldata { deadline, calls })
+}
+
+/// A mint of a position decoded by [`DecodedPositionManagerCall::decode`].
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct DecodedMint {
+    /// The recipient of the position, and whether the pool is created in the same call.
+    pub specific_opts: MintSpecificOptions,
+    /// The first token of the pool.
+    pub token0: Address,
+    /// The second token of the pool.
+    pub token1: Address,
+    /// The fee of the pool.
+    pub fee: FeeAmount,
+    /// The lower tick of the position.
+    pub tick_lower: i32,
+    /// The upper tick of the position.
+    pub tick_upper: i32,
+    /// The amount of token0 to add at most.
+    pub amount0_desired: U256,
+    /// The amount of token1 to add at most.
+    pub amount1_desired: U256,
+    /// The amount of token0 to add at least.
+    pub amount0_min: U256,
+    /// The amount of token1 to add at least.
+    pub amount1_min: U256,
+    /// When the transaction expires, in epoch seconds.
+    pub deadline: U256,
+}
+
+/// An increase of the liquidity of a position decoded by [`DecodedPositionManagerCall::decode`].
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct DecodedIncreaseLiquidity {
+    /// The ID of the position to increase liquidity for.
+    pub specific_opts: IncreaseSpecificOptions,
+    /// The amount of token0 to add at most.
+    pub amount0_desired: U256,
+    /// The amount of token1 to add at most.
+    pub amount1_desired: U256,
+    /// The amount of token0 to add at least.
+    pub amount0_min: U256,
+    /// The amount of token1 to add at least.
+    pub amount1_min: U256,
+    /// When the transaction expires, in epoch seconds.
+    pub deadline: U256,
+}
+
+/// A decrease of the liquidity of a position decoded by [`DecodedPositionManagerCall::decode`].
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct DecodedDecreaseLiquidity {
+    /// The ID of the position to decrease liquidity for.
+    pub token_id: U256,
+    /// The liquidity to remove.
+    pub liquidity: u128,
+    /// The amount of token0 to remove at least.
+    pub amount0_min: U256,
+    /// The amount of token1 to remove at least.
+    pub amount1_min: U256,
+    /// When the transaction expires, in epoch seconds.
+    pub deadline: U256,
+}
+
+/// A collection of the tokens owed to a position decoded by
+/// [`DecodedPositionManagerCall::decode`].
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct DecodedCollect {
+    /// The ID of the position to collect for.
+    pub token_id: U256,
+    /// The account that receives the tokens, the zero address if the position manager keeps them
+    /// to unwrap or sweep them in a later call.
+    pub recipient: Address,
+    /// The amount of token0 to collect at most.
+    pub amount0_max: u128,
+    /// The amount of token1 to collect at most.
+    pub amount1_max: u128,
+}
+
+/// A call of the `NonfungiblePositionManager` decoded by [`decode_position_manager_calldata`].
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub enum DecodedPositionManagerCall {
+    /// Creates the pool of the tokens and the fee, and initializes it at the price, if necessary.
+    CreateAndInitializePool {
+        token0: Address,
+        token1: Address,
+        fee: FeeAmount,
+        sqrt_price_x96: U160,
+    },
+    /// Mints a new position.
+    Mint(DecodedMint),
+    /// Adds liquidity to an existing position.
+    IncreaseLiquidity(DecodedIncreaseLiquidity),
+    /// Removes liquidity from a position, crediting the tokens to it.
+    DecreaseLiquidity(DecodedDecreaseLiquidity),
+    /// Collects the tokens owed to a position.
+    Collect(DecodedCollect),
+    /// Burns a position without liquidity nor tokens owed.
+    Burn { token_id: U256 },
+    /// A payment, e.g. unwrapping WETH or sweeping a token collected to the position manager.
+    Payment(PaymentCall),
+    /// A call that is none of the above, e.g. a permit or a transfer of the position.
+    Other(Bytes),
+}
+
+impl DecodedPositionManagerCall {
+    /// Decodes a call of the `NonfungiblePositionManager`
+    ///
+    /// A mint decoded on its own never creates the pool, see [`decode_position_manager_calldata`].
+    ///
+    /// ## Arguments
+    ///
+    /// * `calldata`: The calldata of the call
+    #[inline]
+    #[must_use]
+    pub fn decode(calldata: &[u8]) -> Self {
+        let Ok(call) = INonfungiblePositionManagerCalls::abi_decode(calldata, true) else {
+            return PaymentCall::decode(calldata).map_or_else(
+                || Self::Other(Bytes::copy_from_slice(calldata)),
+                Self::Payment,
+            );
+        };
+        match call {
+            INonfungiblePositionManagerCalls::createAndInitializePoolIfNecessary(call) => {
+                Self::CreateAndInitializePool {
+                    token0: call.token0,
+                    token1: call.token1,
+                    fee: call.fee.into(),
+                    sqrt_price_x96: call.sqrtPriceX96,
+                }
+            }
+            INonfungiblePositionManagerCalls::mint(call) => {
+                let params = call.params;
+                Self::Mint(DecodedMint {
+                    specific_opts: MintSpecificOptions {
+                        recipient: params.recipient,
+                        create_pool: false,
+                    },
+                    token0: params.token0,
+                    token1: params.token1,
+                    fee: params.fee.into(),
+                    tick_lower: params.tickLower.as_i32(),
+                    tick_upper: params.tickUpper.as_i32(),
+                    amount0_desired: params.amount0Desired,
+                    amount1_desired: params.amount1Desired,
+                    amount0_min: params.amount0Min,
+                    amount1_min: params.amount1Min,
+                    deadline: params.deadline,
+                })
+            }
+            INonfungiblePositionManagerCalls::increaseLiquidity(call) => {
+                let params = call.params;
+                Self::IncreaseLiquidity(DecodedIncreaseLiquidity {
+                    specific_opts: IncreaseSpecificOptions {
+                        token_id: params.tokenId,
+                    },
+                    amount0_desired: params.amount0Desired,
+                    amount1_desired: params.amount1Desired,
+                    amount0_min: params.amount0Min,
+                    amount1_min: params.amount1Min,
+                    deadline: params.deadline,
+                })
+            }
+            INonfungiblePositionManagerCalls::decreaseLiquidity(call) => {
+                let params = call.params;
+                Self::DecreaseLiquidity(DecodedDecreaseLiquidity {
+                    token_id: params.tokenId,
+                    liquidity: params.liquidity,
+                    amount0_min: params.amount0Min,
+                    amount1_min: params.amount1Min,
+                    deadline: params.deadline,
+                })
+            }
+            INonfungiblePositionManagerCalls::collect(call) => {
+                let params = call.params;
+                Self::Collect(DecodedCollect {
+                    token_id: params.tokenId,
+                    recipient: params.recipient,
+                    amount0_max: params.amount0Max,
+                    amount1_max: params.amount1Max,
+                })
+            }
+            INonfungiblePositionManagerCalls::burn(call) => Self::Burn {
+                token_id: call.tokenId,
+            },
+            _ => Self::Other(Bytes::copy_from_slice(calldata)),
+        }
+    }
+
+    /// The token id of the position the call acts on, `None` for mints and calls that are not
+    /// specific to a position
+    #[inline]
+    #[must_use]
+    pub const fn token_id(&self) -> Option<U256> {
+        match self {
+            Self::IncreaseLiquidity(call) => Some(call.specific_opts.token_id),
+            Self::DecreaseLiquidity(call) => Some(call.token_id),
+            Self::Collect(call) => Some(call.token_id),
+            Self::Burn { token_id } => Some(*token_id),
+            _ => None,
+        }
+    }
+}
+
+/// Decodes the calldata of the `NonfungiblePositionManager`, wrapped in a `multicall` or not,
+/// into its calls in the order they are made, the reverse of [`add_call_parameters`],
+/// [`remove_call_parameters`] and [`collect_call_parameters`]
+///
+/// ## Arguments
+///
+/// * `calldata`: The calldata of the transaction
+#[inline]
+#[must_use]
+pub fn decode_position_manager_calldata(calldata: &[u8]) -> Vec<DecodedPositionManagerCall> {
+    let (_, calls) = unwrap_multicall(calldata);
+    let mut decoded: Vec<DecodedPositionManagerCall> = Vec::with_capacity(calls.len());
+    for call in calls {
+        let mut call = DecodedPositionManagerCall::decode(&call);
+        if let DecodedPositionManagerCall::Mint(mint) = &mut call {
+            mint.specific_opts.create_pool = decoded.iter().any(|prev| {
+                matches!(
+                    prev,
+                    DecodedPositionManagerCall::CreateAndInitializePool { token0, token1, fee, .. }
+                        if *token0 == mint.token0 && *token1 == mint.token1 && *fee == mint.fee
+                )
+            });
+        }
+        decoded.push(call);
+    }
+    decoded
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{prelude::Error, tests::*};
+    use alloy_primitives::{address, hex};
+    use uniswap_sdk_core::prelude::*;
+
+    #[test]
+    fn decodes_encoded_paths() {
+        let pools = vec![
+            make_pool(TOKEN0.clone(), TOKEN1.clone()),
+            make_pool(TOKEN1.clone(), TOKEN2.clone()),
+        ];
+        let route = Route::new(pools, TOKEN0.clone(), TOKEN2.clone());
+        let fee: U24 = FeeAmount::MEDIUM.into();
+        assert_eq!(
+            decode_path(&encode_route_to_path(&route, false)).unwrap(),
+            (
+                vec![TOKEN0.address(), TOKEN1.address(), TOKEN2.address()],
+                vec![fee, fee]
+            )
+        );
+        assert_eq!(
+            decode_path(&encode_route_to_path(&route, true)).unwrap().0,
+            vec![TOKEN2.address(), TOKEN1.address(), TOKEN0.address()]
+        );
+        assert!(matches!(
+            decode_path(&hex!("0000000000000000000000000000000000000001000bb8")),
+            Err(Error::InvalidPath(23))
+        ));
+    }
+
+    #[test]
+    fn decodes_swap_call_parameters() {
+        let recipient = address!("0000000000000000000000000000000000000003");
+        let pools = vec![
+            make_pool(TOKEN0.clone(), TOKEN1.clone()),
+            make_pool(TOKEN1.clone(), WETH.clone()),
+        ];
+        let route = Route::new(pools, TOKEN0.clone(), ETHER.clone());
+        let trade = Trade::from_route(
+            route,
+            CurrencyAmount::from_raw_amount(ETHER.clone(), 100).unwrap(),
+            TradeType::ExactOutput,
+        )
+        .unwrap();
+        let MethodParameters { calldata, .. } = swap_call_parameters(
+            &mut [trade],
+            SwapOptions {
+                recipient,
+                ..Default::default()
+            },
+        )
+        .unwrap();
+        let decoded = decode_swap_calldata(&calldata).unwrap();
+        let swaps: Vec<_> = decoded.swaps().collect();
+        assert_eq!(swaps.len(), 1);
+        assert_eq!(swaps[0].trade_type, TradeType::ExactOutput);
+        assert_eq!(
+            swaps[0].tokens,
+            vec![TOKEN0.address(), TOKEN1.address(), WETH.address()]
+        );
+        assert_eq!(swaps[0].amount, U256::from(100));
+        assert_eq!(swaps[0].recipient, Address::ZERO);
+        assert_eq!(
+            decoded.payments().collect::<Vec<_>>(),
+            vec![&PaymentCall::UnwrapWeth9 {
+                amount_minimum: U256::from(100),
+                recipient: Some(recipient),
+                fee_options: None,
+            }]
+        );
+    }
+
+    #[test]
+    fn decodes_swap_router_calls() {
+        let trade = Trade::from_route(
+            Route::new(
+                vec![make_pool(TOKEN0.clone(), TOKEN1.clone())],
+                TOKEN0.clone(),
+                TOKEN1.clone(),
+            ),
+            CurrencyAmount::from_raw_amount(TOKEN0.clone(), 100).unwrap(),
+            TradeType::ExactInput,
+        )
+        .unwrap();
+        let deadline = U256::from(1_700_000_000);
+        let MethodParameters { calldata, .. } = swap_call_parameters(
+            &mut [trade],
+            SwapOptions {
+                router: Some(RouterTarget {
+                    variant: RouterVariant::SwapRouter,
+                    swap_abi: SwapAbi::SwapRouter,
+                    ..Default::default()
+                }),
+                deadline: Some(deadline),
+                ..Default::default()
+            },
+        )
+        .unwrap();
+        let decoded = decode_swap_calldata(&calldata).unwrap();
+        let swap = decoded.swaps().next().unwrap();
+        assert_eq!(swap.tokens, vec![TOKEN0.address(), TOKEN1.address()]);
+        assert_eq!(swap.amount, U256::from(100));
+        assert_eq!(swap.deadline, Some(deadline));
+    }
+
+    #[test]
+    fn keeps_unknown_calls() {
+        let permit = encode_permit(
+            &*TOKEN0,
+            PermitOptions::Allowed(AllowedPermitArguments::new(
+                U256::from(1),
+                U256::from(2),
+                false,
+                U256::ZERO,
+                U256::MAX,
+            )),
+        );
+        let swap = IV3SwapRouter::exactInputSingleCall {
+            params: IV3SwapRouter::ExactInputSingleParams {
+                tokenIn: TOKEN0.address(),
+                tokenOut: TOKEN1.address(),
+                fee: FeeAmount::MEDIUM.into(),
+                amountIn: U256::from(1),
+                ..Default::default()
+            },
+        }
+        .abi_encode();
+        let decoded = decode_swap_calldata(&encode_multicall([permit.to_vec(), swap])).unwrap();
+        assert_eq!(decoded.deadline, None);
+        assert_eq!(decoded.calls[0], DecodedRouterCall::Other(permit));
+        assert_eq!(
+            decoded.swaps().next().unwrap().token_out(),
+            TOKEN1.address()
+        );
+    }
+
+    #[test]
+    fn decodes_collect_call_parameters() {
+        let recipient = address!("0000000000000000000000000000000000000003");
+        let MethodParameters { calldata, .. } = collect_call_parameters(&CollectOptions {
+            token_id: U256::from(1),
+            expected_currency_owed0: CurrencyAmount::from_raw_amount(TOKEN1.clone(), 0).unwrap(),
+            expected_currency_owed1: CurrencyAmount::from_raw_amount(ETHER.clone(), 0).unwrap(),
+            recipient,
+        });
+        let calls = decode_position_manager_calldata(&calldata);
+        assert_eq!(calls.len(), 3);
+        let DecodedPositionManagerCall::Collect(collect) = &calls[0] else {
+            panic!("expected a collect");
+        };
+        assert_eq!(collect.recipient, Address::ZERO);
+        assert_eq!(collect.amount0_max, u128::MAX);
+        assert_eq!(calls[0].token_id(), Some(U256::from(1)));
+        assert_eq!(
+            calls[1],
+            DecodedPositionManagerCall::Payment(PaymentCall::UnwrapWeth9 {
+                amount_minimum: U256::ZERO,
+                recipient: Some(recipient),
+                fee_options: None,
+            })
+        );
+        assert!(matches!(
+            calls[2],
+            DecodedPositionManagerCall::Payment(PaymentCall::SweepToken { token, .. })
+                if token == TOKEN1.address()
+        ));
+    }
+
+    #[test]
+    fn decodes_add_call_parameters() {
+        let mut position = Position::new(
+            POOL_0_1.clone(),
+            1,
+            -FEE_AMOUNT.tick_spacing().as_i32(),
+            FEE_AMOUNT.tick_spacing().as_i32(),
+        );
+        let recipient = address!("0000000000000000000000000000000000000003");
+        let MethodParameters { calldata, .. } = add_call_parameters(
+            &mut position,
+            AddLiquidityOptions {
+                slippage_tolerance: Percent::new(1, 100),
+                deadline: U256::from(123),
+                use_native: None,
+                token0_permit: None,
+                token1_permit: None,
+                specific_opts: AddLiquiditySpecificOptions::Mint(MintSpecificOptions {
+                    recipient,
+                    create_pool: true,
+                }),
+            },
+        )
+        .unwrap();
+        let calls = decode_position_manager_calldata(&calldata);
+        assert_eq!(
+            calls[0],
+            DecodedPositionManagerCall::CreateAndInitializePool {
+                token0: TOKEN0.address(),
+                token1: TOKEN1.address(),
+                fee: FEE_AMOUNT,
+                sqrt_price_x96: SQRT_RATIO_X96,
+            }
+        );
+        let DecodedPositionManagerCall::Mint(mint) = &calls[1] else {
+            panic!("expected a mint");
+        };
+        assert_eq!(
+            mint.specific_opts,
+            MintSpecificOptions {
+                recipient,
+                create_pool: true,
+            }
+        );
+        assert_eq!(mint.fee, FEE_AMOUNT);
+        assert_eq!(mint.tick_lower, -60);
+        assert_eq!(mint.tick_upper, 60);
+        assert_eq!(mint.deadline, U256::from(123));
+        assert_eq!(calls[1].token_id(), None);
+        // without the preceding call, the mint does not create the pool
+        let DecodedPositionManagerCall::Mint(mint) =
+            decode_position_manager_calldata(&split_multicall(&calldata).unwrap().1[1])[0]
+        else {
+            panic!("expected a mint");
+        };
+        assert!(!mint.specific_opts.create_pool);
+    }
+
+    #[test]
+    fn decodes_position_manager_calls() {
+        let decrease = INonfungiblePositionManager::decreaseLiquidityCall {
+            params: INonfungiblePositionManager::DecreaseLiquidityParams {
+                tokenId: U256::from(7),
+                liquidity: 100,
+                ..Default::default()
+            },
+        }
+        .abi_encode();
+        let burn = INonfungiblePositionManager::burnCall {
+            tokenId: U256::from(7),
+        }
+        .abi_encode();
+        let calls = decode_position_manager_calldata(&encode_multicall([decrease, burn]));
+        assert!(matches!(
+            &calls[0],
+            DecodedPositionManagerCall::DecreaseLiquidity(decrease) if decrease.liquidity == 100
+        ));
+        assert_eq!(
+            calls[1],
+            DecodedPositionManagerCall::Burn {
+                token_id: U256::from(7)
+            }
+        );
+        assert_eq!(
+            DecodedPositionManagerCall::decode(&hex!("deadbeef")),
+            DecodedPositionManagerCall::Other(hex!("deadbeef").into())
+        );
+    }
+}
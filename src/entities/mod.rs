@@ -1,14 +1,20 @@
 pub mod pool;
 pub mod position;
 pub mod route;
+pub mod router;
+pub mod static_tick_data_provider;
 pub mod tick;
 pub mod tick_data_provider;
 pub mod tick_list_data_provider;
 pub mod trade;
 
-pub use pool::Pool;
-pub use position::{MintAmounts, Position};
-pub use route::Route;
+pub use pool::{Pool, PoolDiff, PoolProvenance, PoolState, SwapDirection, SwapQuote};
+pub use position::{
+    HedgeNotional, MintAmounts, Position, PositionGreeks, RebalancePlan, TickRangeAdjustment,
+};
+pub use route::{PoolSummary, RoundTripReport, Route, RouteKey, RouteSummary, TokenSummary};
+pub use router::{plan_slices, ExternalQuoter, SlicePlan, SplitTradeOptions, TradeSlice, Venue};
+pub use static_tick_data_provider::StaticTickDataProvider;
 pub use tick::{Tick, TickIndex};
 pub use tick_data_provider::*;
 pub use tick_list_data_provider::TickListDataProvider;
@@ -1,10 +1,97 @@
 use crate::prelude::{Error, *};
-use alloy_primitives::{ChainId, B256, I256, U160};
+use alloy_primitives::{ChainId, B256, I256, U160, U256, U512};
 use once_cell::sync::Lazy;
 use uniswap_sdk_core::prelude::*;
 
 static _Q192: Lazy<BigUint> = Lazy::new(|| Q192.to_big_uint());
 
+/// The direction of a swap through a pool derived from the token sold.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+pub struct SwapDirection {
+    /// Whether token0 is sold for token1, i.e. the price moves down.
+    pub zero_for_one: bool,
+    /// The loosest valid price limit in this direction, `MIN_SQRT_RATIO + 1` when selling token0
+    /// and `MAX_SQRT_RATIO - 1` otherwise.
+    pub price_limit_default: U160,
+}
+
+impl SwapDirection {
+    #[inline]
+    #[must_use]
+    pub const fn new(zero_for_one: bool) -> Self {
+        Self {
+            zero_for_one,
+            price_limit_default: if zero_for_one {
+                MIN_SQRT_RATIO.wrapping_add(ONE)
+            } else {
+                MAX_SQRT_RATIO.wrapping_sub(ONE)
+            },
+        }
+    }
+}
+
+/// The result of quoting a swap through a pool, with the costs borne along the way.
+#[derive(Clone, Debug, PartialEq)]
+pub struct SwapQuote {
+    /// The output amount for exact input quotes, the input amount for exact output quotes.
+    pub amount: CurrencyAmount<Token>,
+    /// The total fee paid to liquidity providers, in the input token.
+    pub fee_amount: CurrencyAmount<Token>,
+    /// The number of initialized ticks crossed, each costing extra gas.
+    pub ticks_crossed: u32,
+    /// The sqrt price of the pool after the swap.
+    pub sqrt_price_x96_after: U160,
+}
+
+/// The differences between two states of the same pool, e.g. fetched from redundant sources, as
+/// `other - self`.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, Hash)]
+pub struct PoolDiff {
+    /// The difference of the sqrt prices.
+    pub sqrt_ratio_x96_delta: I256,
+    /// The difference of the in range liquidity.
+    pub liquidity_delta: I256,
+    /// The difference of the current ticks.
+    pub tick_delta: i32,
+}
+
+impl PoolDiff {
+    /// Returns whether the states are identical
+    #[inline]
+    #[must_use]
+    pub fn is_empty(&self) -> bool {
+        self.sqrt_ratio_x96_delta.is_zero()
+            && self.liquidity_delta.is_zero()
+            && self.tick_delta == 0
+    }
+}
+
+/// A possibly partial state of a pool reported by one source, e.g. the liquidity derived from
+/// events or the `slot0` read over RPC.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, Hash)]
+pub struct PoolState {
+    pub sqrt_ratio_x96: Option<U160>,
+    pub liquidity: Option<u128>,
+}
+
+impl<TP: TickDataProvider> From<&Pool<TP>> for PoolState {
+    #[inline]
+    fn from(pool: &Pool<TP>) -> Self {
+        Self {
+            sqrt_ratio_x96: Some(pool.sqrt_ratio_x96),
+            liquidity: Some(pool.liquidity),
+        }
+    }
+}
+
+/// The source each field of a pool merged by [`Pool::merge_states`] was taken from, `None` if it
+/// was kept from the base state.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, Hash)]
+pub struct PoolProvenance<S> {
+    pub sqrt_ratio_x96: Option<S>,
+    pub liquidity: Option<S>,
+}
+
 /// Represents a V3 pool
 #[derive(Clone, Debug)]
 pub struct Pool<TP = NoTickDataProvider>
@@ -14,10 +101,16 @@ where
     pub token0: Token,
     pub token1: Token,
     pub fee: FeeAmount,
+    /// The tick spacing enabled by the factory for the fee, the default of the fee tier unless
+    /// set otherwise, e.g. for custom fee tiers.
+    pub tick_spacing: TP::Index,
     pub sqrt_ratio_x96: U160,
     pub liquidity: u128,
     pub tick_current: TP::Index,
     pub tick_data_provider: TP,
+    /// The deployment the pool belongs to, the Uniswap Labs deployment on the chain of its tokens
+    /// unless set otherwise, e.g. for forks, see [`Pool::deployment`].
+    deployment: Option<PoolDeployment>,
 }
 
 impl<TP> PartialEq for Pool<TP>
@@ -29,9 +122,11 @@ where
         self.token0 == other.token0
             && self.token1 == other.token1
             && self.fee == other.fee
+            && self.tick_spacing == other.tick_spacing
             && self.sqrt_ratio_x96 == other.sqrt_ratio_x96
             && self.liquidity == other.liquidity
             && self.tick_current == other.tick_current
+            && self.deployment() == other.deployment()
     }
 }
 
@@ -102,12 +197,13 @@ impl Pool {
         init_code_hash_manual_override: Option<B256>,
         factory_address_override: Option<Address>,
     ) -> Address {
+        let deployment = PoolDeployment::for_chain(token_a.chain_id());
         compute_pool_address(
-            factory_address_override.unwrap_or(FACTORY_ADDRESS),
+            factory_address_override.unwrap_or(deployment.factory),
             token_a.address(),
             token_b.address(),
             fee,
-            init_code_hash_manual_override,
+            Some(init_code_hash_manual_override.unwrap_or(deployment.init_code_hash)),
             Some(token_a.chain_id()),
         )
     }
@@ -121,23 +217,58 @@ impl<TP: TickDataProvider> Pool<TP> {
         init_code_hash_manual_override: Option<B256>,
         factory_address_override: Option<Address>,
     ) -> Address {
-        Pool::get_address(
-            &self.token0,
-            &self.token1,
+        let deployment = self.deployment();
+        compute_pool_address(
+            factory_address_override.unwrap_or(deployment.factory),
+            self.token0.address(),
+            self.token1.address(),
             self.fee,
-            init_code_hash_manual_override,
-            factory_address_override,
+            Some(init_code_hash_manual_override.unwrap_or(deployment.init_code_hash)),
+            Some(self.chain_id()),
         )
     }
 
+    /// Returns the deployment the pool belongs to, see [`PoolDeployment::for_chain`]
+    #[inline]
+    pub fn deployment(&self) -> PoolDeployment {
+        self.deployment
+            .unwrap_or_else(|| PoolDeployment::for_chain(self.chain_id()))
+    }
+
+    /// Returns the pool as a pool of the given deployment, e.g. a fork with its own factory, so
+    /// that its address is derived from the factory and init code hash of the deployment
+    ///
+    /// ## Arguments
+    ///
+    /// * `deployment`: The factory and pool init code hash of the deployment
+    #[inline]
+    #[must_use]
+    pub const fn with_deployment(mut self, deployment: PoolDeployment) -> Self {
+        self.deployment = Some(deployment);
+        self
+    }
+
     #[inline]
     pub fn chain_id(&self) -> ChainId {
         self.token0.chain_id()
     }
 
     #[inline]
-    pub fn tick_spacing(&self) -> TP::Index {
-        TP::Index::from_i24(self.fee.tick_spacing())
+    pub const fn tick_spacing(&self) -> TP::Index {
+        self.tick_spacing
+    }
+
+    /// Returns the pool with the tick spacing enabled by the factory for its fee, e.g. as queried
+    /// from `feeAmountTickSpacing` for a custom fee tier
+    ///
+    /// ## Arguments
+    ///
+    /// * `tick_spacing`: The tick spacing of the pool
+    #[inline]
+    #[must_use]
+    pub const fn with_tick_spacing(mut self, tick_spacing: TP::Index) -> Self {
+        self.tick_spacing = tick_spacing;
+        self
     }
 
     /// Returns true if the token is either token0 or token1
@@ -178,6 +309,113 @@ impl<TP: TickDataProvider> Pool<TP> {
         )
     }
 
+    /// Returns the mid price of token0 in terms of token1 as a Q192 fixed point number, i.e. the
+    /// square of `sqrt_ratio_x96`, without constructing a [`Price`]
+    #[inline]
+    #[must_use]
+    pub fn mid_price_ratio_x192(&self) -> U512 {
+        let sqrt_ratio_x96 = U512::from(self.sqrt_ratio_x96);
+        sqrt_ratio_x96 * sqrt_ratio_x96
+    }
+
+    /// Returns the mid price of the given token in terms of the other token as a Q128.128 fixed
+    /// point number rounded down, without constructing a [`Price`]
+    ///
+    /// ## Arguments
+    ///
+    /// * `token`: The token to return price of
+    #[inline]
+    pub fn price_of_x128(&self, token: &Token) -> Result<U256, Error> {
+        let zero_for_one = self.direction_for(token)?.zero_for_one;
+        let sqrt_ratio_x96 = U256::from(self.sqrt_ratio_x96);
+        // same as `OracleLibrary.getQuoteAtTick`, the square only fits in 256 bits below 2^128
+        if sqrt_ratio_x96 <= U256::from(u128::MAX) {
+            let ratio_x192 = sqrt_ratio_x96 * sqrt_ratio_x96;
+            if zero_for_one {
+                Ok(ratio_x192 >> 64)
+            } else {
+                mul_div(Q192, Q128, ratio_x192)
+            }
+        } else {
+            let ratio_x128 = mul_div(sqrt_ratio_x96, sqrt_ratio_x96, U256::from(1_u128 << 64))?;
+            if zero_for_one {
+                Ok(ratio_x128)
+            } else {
+                mul_div(Q128, Q128, ratio_x128)
+            }
+        }
+    }
+
+    /// Returns the direction of a swap selling `token_in`
+    ///
+    /// ## Arguments
+    ///
+    /// * `token_in`: The token sold, either token0 or token1
+    #[inline]
+    pub fn direction_for(&self, token_in: &impl BaseCurrency) -> Result<SwapDirection, Error> {
+        if self.token0.equals(token_in) {
+            Ok(SwapDirection::new(true))
+        } else if self.token1.equals(token_in) {
+            Ok(SwapDirection::new(false))
+        } else {
+            Err(Error::InvalidToken)
+        }
+    }
+
+    /// Returns the direction of a swap selling `token_in` for `token_out`
+    ///
+    /// ## Arguments
+    ///
+    /// * `token_in`: The token sold
+    /// * `token_out`: The token bought, the other token of the pool
+    #[inline]
+    pub fn direction_for_pair(
+        &self,
+        token_in: &impl BaseCurrency,
+        token_out: &impl BaseCurrency,
+    ) -> Result<SwapDirection, Error> {
+        let direction = self.direction_for(token_in)?;
+        let expected = if direction.zero_for_one {
+            &self.token1
+        } else {
+            &self.token0
+        };
+        if expected.equals(token_out) {
+            Ok(direction)
+        } else {
+            Err(Error::InvalidToken)
+        }
+    }
+
+    /// Checks that a price limit is on the correct side of the current price for the given
+    /// direction, returning the default limit of the direction if none is given
+    ///
+    /// ## Arguments
+    ///
+    /// * `direction`: The direction of the swap
+    /// * `sqrt_price_limit_x96`: The optional price limit
+    #[inline]
+    pub fn validate_price_limit(
+        &self,
+        direction: SwapDirection,
+        sqrt_price_limit_x96: impl Into<Option<U160>>,
+    ) -> Result<U160, Error> {
+        let sqrt_price_limit_x96: Option<U160> = sqrt_price_limit_x96.into();
+        let Some(limit) = sqrt_price_limit_x96 else {
+            return Ok(direction.price_limit_default);
+        };
+        let valid = if direction.zero_for_one {
+            limit > MIN_SQRT_RATIO && limit < self.sqrt_ratio_x96
+        } else {
+            limit < MAX_SQRT_RATIO && limit > self.sqrt_ratio_x96
+        };
+        if valid {
+            Ok(limit)
+        } else {
+            Err(Error::InvalidPriceLimit(limit))
+        }
+    }
+
     /// Return the price of the given token in terms of the other token in the pool.
     ///
     /// ## Arguments
@@ -217,6 +455,12 @@ impl<TP: TickDataProvider> Pool<TP> {
         liquidity: u128,
         tick_data_provider: TP,
     ) -> Result<Self, Error> {
+        if token_a.chain_id() != token_b.chain_id() {
+            return Err(Error::ChainMismatch {
+                expected: token_a.chain_id(),
+                actual: token_b.chain_id(),
+            });
+        }
         let (token0, token1) = if token_a.sorts_before(&token_b)? {
             (token_a, token_b)
         } else {
@@ -226,20 +470,76 @@ impl<TP: TickDataProvider> Pool<TP> {
             token0,
             token1,
             fee,
+            tick_spacing: TP::Index::from_i24(fee.tick_spacing()),
             sqrt_ratio_x96,
             liquidity,
             tick_current: TP::Index::from_i24(sqrt_ratio_x96.get_tick_at_sqrt_ratio()?),
             tick_data_provider,
+            deployment: None,
         })
     }
 
-    fn _swap(
+    /// Erases the type of the tick data provider, so that pools backed by different providers can
+    /// be managed uniformly
+    #[inline]
+    pub fn into_boxed(self) -> Pool<BoxedTickDataProvider<TP::Index>>
+    where
+        TP: Send + Sync + 'static,
+    {
+        Pool {
+            token0: self.token0,
+            token1: self.token1,
+            fee: self.fee,
+            tick_spacing: self.tick_spacing,
+            sqrt_ratio_x96: self.sqrt_ratio_x96,
+            liquidity: self.liquidity,
+            tick_current: self.tick_current,
+            tick_data_provider: BoxedTickDataProvider::new(self.tick_data_provider),
+            deployment: self.deployment,
+        }
+    }
+
+    /// Compares the state of the pool with another state of the same pool, e.g. fetched from
+    /// another source
+    ///
+    /// ## Arguments
+    ///
+    /// * `other`: The other state of the pool
+    ///
+    /// returns: The differences as `other - self`
+    #[inline]
+    pub fn diff<TP2: TickDataProvider>(&self, other: &Pool<TP2>) -> Result<PoolDiff, Error> {
+        let (expected, actual) = (self.address(None, None), other.address(None, None));
+        if expected != actual {
+            return Err(Error::PoolMismatch { expected, actual });
+        }
+        Ok(PoolDiff {
+            sqrt_ratio_x96_delta: I256::from_raw(U256::from(other.sqrt_ratio_x96))
+                - I256::from_raw(U256::from(self.sqrt_ratio_x96)),
+            liquidity_delta: I256::from_raw(U256::from(other.liquidity))
+                - I256::from_raw(U256::from(self.liquidity)),
+            tick_delta: other.tick_current.to_i24().as_i32() - self.tick_current.to_i24().as_i32(),
+        })
+    }
+
+    /// Starts a swap driven step by step, e.g. to interleave it with the state of another venue,
+    /// see [`SwapStateMachine`]
+    ///
+    /// ## Arguments
+    ///
+    /// * `zero_for_one`: Whether the amount in is token0 or token1
+    /// * `amount_specified`: The amount of the swap, positive for an exact input, negative for an
+    ///   exact output
+    /// * `sqrt_price_limit_x96`: The Q64.96 sqrt price limit
+    #[inline]
+    pub fn swap_state_machine(
         &self,
         zero_for_one: bool,
         amount_specified: I256,
-        sqrt_price_limit_x96: Option<U160>,
-    ) -> Result<SwapState<TP::Index>, Error> {
-        v3_swap(
+        sqrt_price_limit_x96: impl Into<Option<U160>>,
+    ) -> SwapStateMachine<'_, TP> {
+        let sqrt_price_limit_x96: Option<U160> = sqrt_price_limit_x96.into();
+        SwapStateMachine::new(
             self.fee.into(),
             self.sqrt_ratio_x96,
             self.tick_current,
@@ -251,23 +551,42 @@ impl<TP: TickDataProvider> Pool<TP> {
             sqrt_price_limit_x96,
         )
     }
-}
 
-impl<TP: Clone + TickDataProvider> Pool<TP> {
-    /// Given an input amount of a token, return the computed output amount
+    /// Returns an iterator over the steps of a swap, each up to the next initialized tick or the
+    /// end of a tick bitmap word, e.g. to chart the liquidity depth or break a fill down per tick
     ///
     /// ## Arguments
     ///
-    /// * `input_amount`: The input amount for which to quote the output amount
+    /// * `amount_specified`: The amount of the swap, positive for an exact input, negative for an
+    ///   exact output
+    /// * `zero_for_one`: Whether the amount in is token0 or token1
     /// * `sqrt_price_limit_x96`: The Q64.96 sqrt price limit
+    #[inline]
+    pub fn swap_steps(
+        &self,
+        amount_specified: I256,
+        zero_for_one: bool,
+        sqrt_price_limit_x96: impl Into<Option<U160>>,
+    ) -> SwapSteps<'_, TP> {
+        let sqrt_price_limit_x96: Option<U160> = sqrt_price_limit_x96.into();
+        self.swap_state_machine(zero_for_one, amount_specified, sqrt_price_limit_x96)
+            .into_steps()
+    }
+
+    /// Given an input amount of a token, return the computed output amount along with the fee paid
+    /// and the number of initialized ticks crossed
     ///
-    /// returns: The output amount
+    /// ## Arguments
+    ///
+    /// * `input_amount`: The input amount for which to quote the output amount
+    /// * `sqrt_price_limit_x96`: The Q64.96 sqrt price limit
     #[inline]
-    pub fn get_output_amount(
+    pub fn quote_output(
         &self,
         input_amount: &CurrencyAmount<impl BaseCurrency>,
-        sqrt_price_limit_x96: Option<U160>,
-    ) -> Result<CurrencyAmount<Token>, Error> {
+        sqrt_price_limit_x96: impl Into<Option<U160>>,
+    ) -> Result<SwapQuote, Error> {
+        let sqrt_price_limit_x96: Option<U160> = sqrt_price_limit_x96.into();
         if !self.involves_token(&input_amount.currency) {
             return Err(Error::InvalidToken);
         }
@@ -277,6 +596,9 @@ impl<TP: Clone + TickDataProvider> Pool<TP> {
         let SwapState {
             amount_specified_remaining,
             amount_calculated: output_amount,
+            sqrt_price_x96,
+            fee_amount,
+            ticks_crossed,
             ..
         } = self._swap(
             zero_for_one,
@@ -288,13 +610,163 @@ impl<TP: Clone + TickDataProvider> Pool<TP> {
             return Err(Error::InsufficientLiquidity);
         }
 
-        let output_token = if zero_for_one {
-            &self.token1
+        let (input_token, output_token) = if zero_for_one {
+            (&self.token0, &self.token1)
         } else {
+            (&self.token1, &self.token0)
+        };
+        Ok(SwapQuote {
+            amount: CurrencyAmount::from_raw_amount(
+                output_token.clone(),
+                -output_amount.to_big_int(),
+            )?,
+            fee_amount: CurrencyAmount::from_raw_amount(
+                input_token.clone(),
+                fee_amount.to_big_int(),
+            )?,
+            ticks_crossed,
+            sqrt_price_x96_after: sqrt_price_x96,
+        })
+    }
+
+    /// Given a desired output amount of a token, return the computed input amount along with the
+    /// fee paid and the number of initialized ticks crossed
+    ///
+    /// ## Arguments
+    ///
+    /// * `output_amount`: the output amount for which to quote the input amount
+    /// * `sqrt_price_limit_x96`: The Q64.96 sqrt price limit
+    #[inline]
+    pub fn quote_input(
+        &self,
+        output_amount: &CurrencyAmount<impl BaseCurrency>,
+        sqrt_price_limit_x96: impl Into<Option<U160>>,
+    ) -> Result<SwapQuote, Error> {
+        let sqrt_price_limit_x96: Option<U160> = sqrt_price_limit_x96.into();
+        if !self.involves_token(&output_amount.currency) {
+            return Err(Error::InvalidToken);
+        }
+
+        let zero_for_one = output_amount.currency.equals(&self.token1);
+
+        let SwapState {
+            amount_specified_remaining,
+            amount_calculated: input_amount,
+            sqrt_price_x96,
+            fee_amount,
+            ticks_crossed,
+            ..
+        } = self._swap(
+            zero_for_one,
+            I256::from_big_int(-output_amount.quotient()),
+            sqrt_price_limit_x96,
+        )?;
+
+        if !amount_specified_remaining.is_zero() && sqrt_price_limit_x96.is_none() {
+            return Err(Error::InsufficientLiquidity);
+        }
+
+        let input_token = if zero_for_one {
             &self.token0
+        } else {
+            &self.token1
         };
-        CurrencyAmount::from_raw_amount(output_token.clone(), -output_amount.to_big_int())
-            .map_err(Error::Core)
+        Ok(SwapQuote {
+            amount: CurrencyAmount::from_raw_amount(
+                input_token.clone(),
+                input_amount.to_big_int(),
+            )?,
+            fee_amount: CurrencyAmount::from_raw_amount(
+                input_token.clone(),
+                fee_amount.to_big_int(),
+            )?,
+            ticks_crossed,
+            sqrt_price_x96_after: sqrt_price_x96,
+        })
+    }
+
+    fn _swap(
+        &self,
+        zero_for_one: bool,
+        amount_specified: I256,
+        sqrt_price_limit_x96: Option<U160>,
+    ) -> Result<SwapState<TP::Index>, Error> {
+        v3_swap(
+            self.fee.into(),
+            self.sqrt_ratio_x96,
+            self.tick_current,
+            self.liquidity,
+            self.tick_spacing(),
+            &self.tick_data_provider,
+            zero_for_one,
+            amount_specified,
+            sqrt_price_limit_x96,
+        )
+        .map_err(|err| {
+            err.with_context(
+                ErrorContext::new()
+                    .with_pool(self.address(None, None))
+                    .with_amount(amount_specified.unsigned_abs()),
+            )
+        })
+    }
+}
+
+impl<TP: Clone + TickDataProvider> Pool<TP> {
+    /// Merges the states reported by several sources, in order of preference, into a copy of the
+    /// pool. Each field is taken from the first source that reports it, or kept from the pool if
+    /// none does, and the current tick is recomputed from the merged price.
+    ///
+    /// ## Arguments
+    ///
+    /// * `sources`: The states labeled by their source, the preferred first
+    ///
+    /// returns: The merged pool and the source of each field
+    #[inline]
+    pub fn merge_states<S: Copy>(
+        &self,
+        sources: &[(S, PoolState)],
+    ) -> Result<(Self, PoolProvenance<S>), Error> {
+        let mut pool = self.clone();
+        let mut provenance = PoolProvenance {
+            sqrt_ratio_x96: None,
+            liquidity: None,
+        };
+        if let Some((source, sqrt_ratio_x96)) = sources
+            .iter()
+            .find_map(|(source, state)| Some((*source, state.sqrt_ratio_x96?)))
+        {
+            pool.sqrt_ratio_x96 = sqrt_ratio_x96;
+            pool.tick_current = TP::Index::from_i24(sqrt_ratio_x96.get_tick_at_sqrt_ratio()?);
+            provenance.sqrt_ratio_x96 = Some(source);
+        }
+        if let Some((source, liquidity)) = sources
+            .iter()
+            .find_map(|(source, state)| Some((*source, state.liquidity?)))
+        {
+            pool.liquidity = liquidity;
+            provenance.liquidity = Some(source);
+        }
+        Ok((pool, provenance))
+    }
+
+    /// Given an input amount of a token, return the computed output amount
+    ///
+    /// ## Arguments
+    ///
+    /// * `input_amount`: The input amount for which to quote the output amount
+    /// * `sqrt_price_limit_x96`: The Q64.96 sqrt price limit
+    ///
+    /// returns: The output amount
+    #[inline]
+    pub fn get_output_amount(
+        &self,
+        input_amount: &CurrencyAmount<impl BaseCurrency>,
+        sqrt_price_limit_x96: impl Into<Option<U160>>,
+    ) -> Result<CurrencyAmount<Token>, Error> {
+        let sqrt_price_limit_x96: Option<U160> = sqrt_price_limit_x96.into();
+        self.quote_output(input_amount, sqrt_price_limit_x96)
+            .map(|quote| quote.amount)
     }
 
     /// Given an input amount of a token, return the computed output amount, updating the pool state
@@ -309,8 +781,9 @@ impl<TP: Clone + TickDataProvider> Pool<TP> {
     pub fn get_output_amount_mut(
         &mut self,
         input_amount: &CurrencyAmount<impl BaseCurrency>,
-        sqrt_price_limit_x96: Option<U160>,
+        sqrt_price_limit_x96: impl Into<Option<U160>>,
     ) -> Result<CurrencyAmount<Token>, Error> {
+        let sqrt_price_limit_x96: Option<U160> = sqrt_price_limit_x96.into();
         if !self.involves_token(&input_amount.currency) {
             return Err(Error::InvalidToken);
         }
@@ -361,35 +834,11 @@ impl<TP: Clone + TickDataProvider> Pool<TP> {
     pub fn get_input_amount(
         &self,
         output_amount: &CurrencyAmount<impl BaseCurrency>,
-        sqrt_price_limit_x96: Option<U160>,
+        sqrt_price_limit_x96: impl Into<Option<U160>>,
     ) -> Result<CurrencyAmount<Token>, Error> {
-        if !self.involves_token(&output_amount.currency) {
-            return Err(Error::InvalidToken);
-        }
-
-        let zero_for_one = output_amount.currency.equals(&self.token1);
-
-        let SwapState {
-            amount_specified_remaining,
-            amount_calculated: input_amount,
-            ..
-        } = self._swap(
-            zero_for_one,
-            I256::from_big_int(-output_amount.quotient()),
-            sqrt_price_limit_x96,
-        )?;
-
-        if !amount_specified_remaining.is_zero() && sqrt_price_limit_x96.is_none() {
-            return Err(Error::InsufficientLiquidity);
-        }
-
-        let input_token = if zero_for_one {
-            &self.token0
-        } else {
-            &self.token1
-        };
-        CurrencyAmount::from_raw_amount(input_token.clone(), input_amount.to_big_int())
-            .map_err(Error::Core)
+        let sqrt_price_limit_x96: Option<U160> = sqrt_price_limit_x96.into();
+        self.quote_input(output_amount, sqrt_price_limit_x96)
+            .map(|quote| quote.amount)
     }
 
     /// Given a desired output amount of a token, return the computed input amount, updating the
@@ -407,8 +856,9 @@ impl<TP: Clone + TickDataProvider> Pool<TP> {
     pub fn get_input_amount_mut(
         &mut self,
         output_amount: &CurrencyAmount<impl BaseCurrency>,
-        sqrt_price_limit_x96: Option<U160>,
+        sqrt_price_limit_x96: impl Into<Option<U160>>,
     ) -> Result<CurrencyAmount<Token>, Error> {
+        let sqrt_price_limit_x96: Option<U160> = sqrt_price_limit_x96.into();
         if !self.involves_token(&output_amount.currency) {
             return Err(Error::InvalidToken);
         }
@@ -458,10 +908,15 @@ mod tests {
         use super::*;
 
         #[test]
-        #[should_panic(expected = "CHAIN_IDS")]
         fn cannot_be_used_for_tokens_on_different_chains() {
             let weth9 = WETH9::default().get(3).unwrap().clone();
-            Pool::new(USDC.clone(), weth9, FeeAmount::MEDIUM, ONE_ETHER, 0).expect("CHAIN_IDS");
+            assert!(matches!(
+                Pool::new(USDC.clone(), weth9, FeeAmount::MEDIUM, ONE_ETHER, 0),
+                Err(Error::ChainMismatch {
+                    expected: 1,
+                    actual: 3
+                })
+            ));
         }
 
         #[test]
@@ -502,6 +957,57 @@ mod tests {
         assert_eq!(result, address!("6c6Bc977E13Df9b0de53b251522280BB72383700"));
     }
 
+    #[test]
+    fn address_uses_the_deployment_of_the_pool() {
+        let pool = Pool::new(
+            USDC.clone(),
+            DAI.clone(),
+            FeeAmount::LOW,
+            encode_sqrt_ratio_x96(1, 1),
+            0,
+        )
+        .unwrap();
+        assert_eq!(pool.deployment(), PoolDeployment::CANONICAL);
+        assert_eq!(
+            pool.address(None, None),
+            address!("6c6Bc977E13Df9b0de53b251522280BB72383700")
+        );
+        let factory = address!("0000000000000000000000000000000000000001");
+        let pool = pool.with_deployment(PoolDeployment::new(factory, POOL_INIT_CODE_HASH));
+        assert_eq!(
+            pool.address(None, None),
+            compute_pool_address(
+                factory,
+                USDC.address(),
+                DAI.address(),
+                FeeAmount::LOW,
+                None,
+                None
+            )
+        );
+    }
+
+    #[test]
+    fn default_deployment_equals_the_deployment_of_the_chain() {
+        let pool = Pool::new(
+            USDC.clone(),
+            DAI.clone(),
+            FeeAmount::LOW,
+            encode_sqrt_ratio_x96(1, 1),
+            0,
+        )
+        .unwrap();
+        assert_eq!(
+            pool.clone().with_deployment(PoolDeployment::CANONICAL),
+            pool
+        );
+        assert_ne!(
+            pool.clone()
+                .with_deployment(PoolDeployment::for_chain(8453)),
+            pool
+        );
+    }
+
     #[test]
     fn token0_always_is_the_token_that_sorts_before() {
         let pool = Pool::new(
@@ -610,6 +1116,53 @@ mod tests {
         assert_eq!(pool.price_of(&USDC.clone()).unwrap(), pool.token1_price());
     }
 
+    #[test]
+    fn mid_price_ratios_match_prices() {
+        let pool = Pool::new(
+            USDC.clone(),
+            DAI.clone(),
+            FeeAmount::LOW,
+            encode_sqrt_ratio_x96(101e6 as u128, 100e18 as u128),
+            0,
+        )
+        .unwrap();
+        let sqrt_ratio_x96 = pool.sqrt_ratio_x96.to_big_int();
+        assert_eq!(
+            pool.mid_price_ratio_x192().to_big_int(),
+            &sqrt_ratio_x96 * &sqrt_ratio_x96
+        );
+        for token in [&pool.token0, &pool.token1] {
+            let price = pool.price_of(token).unwrap();
+            assert_eq!(
+                pool.price_of_x128(token).unwrap().to_big_int(),
+                price.numerator * Q128.to_big_int() / price.denominator
+            );
+        }
+        assert!(matches!(
+            pool.price_of_x128(&WETH9::default().get(1).unwrap().clone()),
+            Err(Error::InvalidToken)
+        ));
+
+        let pool = Pool::new(
+            USDC.clone(),
+            DAI.clone(),
+            FeeAmount::LOW,
+            MAX_SQRT_RATIO - ONE,
+            0,
+        )
+        .unwrap();
+        assert_eq!(
+            pool.price_of_x128(&pool.token0).unwrap(),
+            mul_div(
+                U256::from(pool.sqrt_ratio_x96),
+                U256::from(pool.sqrt_ratio_x96),
+                U256::from(1_u128 << 64)
+            )
+            .unwrap()
+        );
+        assert!(pool.price_of_x128(&pool.token1).unwrap() > U256::ZERO);
+    }
+
     #[test]
     #[should_panic(expected = "InvalidToken")]
     fn price_of_throws_if_invalid_token() {
@@ -662,6 +1215,65 @@ mod tests {
         assert!(!pool.involves_token(&WETH9::default().get(1).unwrap().clone()));
     }
 
+    mod direction {
+        use super::*;
+
+        #[test]
+        fn direction_for_token0_and_token1() {
+            let direction = POOL_0_1.direction_for(&*TOKEN0).unwrap();
+            assert!(direction.zero_for_one);
+            assert_eq!(
+                direction.price_limit_default,
+                MIN_SQRT_RATIO + U160::from(1)
+            );
+            let direction = POOL_0_1.direction_for_pair(&*TOKEN1, &*TOKEN0).unwrap();
+            assert!(!direction.zero_for_one);
+            assert_eq!(
+                direction.price_limit_default,
+                MAX_SQRT_RATIO - U160::from(1)
+            );
+            assert!(matches!(
+                POOL_0_1.direction_for(&*TOKEN2),
+                Err(Error::InvalidToken)
+            ));
+            assert!(matches!(
+                POOL_0_1.direction_for_pair(&*TOKEN0, &*TOKEN0),
+                Err(Error::InvalidToken)
+            ));
+        }
+
+        #[test]
+        fn validate_price_limit() {
+            let sell_token0 = POOL_0_1.direction_for(&*TOKEN0).unwrap();
+            let below = POOL_0_1.sqrt_ratio_x96 - U160::from(1);
+            let above = POOL_0_1.sqrt_ratio_x96 + U160::from(1);
+            assert_eq!(
+                POOL_0_1.validate_price_limit(sell_token0, None).unwrap(),
+                sell_token0.price_limit_default
+            );
+            assert_eq!(
+                POOL_0_1
+                    .validate_price_limit(sell_token0, Some(below))
+                    .unwrap(),
+                below
+            );
+            // a bare limit is accepted as well
+            assert_eq!(
+                POOL_0_1.validate_price_limit(sell_token0, below).unwrap(),
+                below
+            );
+            assert!(matches!(
+                POOL_0_1.validate_price_limit(sell_token0, Some(above)),
+                Err(Error::InvalidPriceLimit(_))
+            ));
+            let sell_token1 = POOL_0_1.direction_for(&*TOKEN1).unwrap();
+            assert!(matches!(
+                POOL_0_1.validate_price_limit(sell_token1, Some(below)),
+                Err(Error::InvalidPriceLimit(_))
+            ));
+        }
+    }
+
     mod swaps {
         use super::*;
         use crate::utils::tick_math::{MAX_TICK, MIN_TICK};
@@ -716,6 +1328,56 @@ mod tests {
             assert_eq!(output_amount.quotient(), 98.into());
         }
 
+        #[test]
+        fn swap_state_machine_matches_get_output_amount() {
+            let amount = 10_u128.pow(18);
+            // USDC is token1
+            let mut machine =
+                POOL.swap_state_machine(false, I256::from_raw(U256::from(amount)), None);
+            let mut steps = 0;
+            while let Some(step) = machine.next_step().unwrap() {
+                assert!(step.sqrt_price_start_x96 < machine.state().sqrt_price_x96);
+                steps += 1;
+            }
+            assert!(steps > 1);
+            assert!(machine.is_done());
+            assert_eq!(machine.next_step().unwrap(), None);
+            let output_amount = POOL
+                .get_output_amount(
+                    &CurrencyAmount::from_raw_amount(USDC.clone(), amount).unwrap(),
+                    None,
+                )
+                .unwrap();
+            assert_eq!(
+                (-machine.state().amount_calculated).into_raw().to_big_int(),
+                output_amount.quotient()
+            );
+        }
+
+        #[test]
+        fn swap_steps_add_up_to_the_swap() {
+            let amount = I256::from_raw(U256::from(10_u128.pow(18)));
+            let steps: Vec<_> = POOL
+                .swap_steps(amount, false, None)
+                .collect::<Result<_, _>>()
+                .unwrap();
+            assert!(steps.len() > 1);
+            for pair in steps.windows(2) {
+                assert_eq!(pair[0].sqrt_price_end_x96, pair[1].sqrt_price_start_x96);
+            }
+            let last = steps.last().unwrap();
+            let state = POOL._swap(false, amount, None).unwrap();
+            assert_eq!(last.sqrt_price_end_x96, state.sqrt_price_x96);
+            assert_eq!(last.tick_end, state.tick_current);
+            let amount_in = steps
+                .iter()
+                .map(|step| step.amount_in + step.fee_amount)
+                .sum::<U256>();
+            let amount_out = steps.iter().map(|step| step.amount_out).sum::<U256>();
+            assert_eq!(amount_in, amount.into_raw());
+            assert_eq!(-state.amount_calculated, I256::from_raw(amount_out));
+        }
+
         #[test]
         fn get_input_amount_usdc_to_dai() {
             let input_amount = POOL
@@ -739,5 +1401,223 @@ mod tests {
             assert!(input_amount.currency.equals(&DAI.clone()));
             assert_eq!(input_amount.quotient(), 100.into());
         }
+
+        #[test]
+        fn boxed_pools_quote_the_same() {
+            let pools: Vec<Pool<BoxedTickDataProvider>> = vec![
+                POOL.clone().into_boxed(),
+                Pool::new_with_tick_data_provider(
+                    POOL.token0.clone(),
+                    POOL.token1.clone(),
+                    POOL.fee,
+                    POOL.sqrt_ratio_x96,
+                    POOL.liquidity,
+                    alloc::sync::Arc::new(POOL.tick_data_provider.clone()),
+                )
+                .unwrap()
+                .into_boxed(),
+            ];
+            let input_amount = CurrencyAmount::from_raw_amount(USDC.clone(), 100).unwrap();
+            let expected = POOL.get_output_amount(&input_amount, None).unwrap();
+            for pool in &pools {
+                assert_eq!(
+                    pool.get_output_amount(&input_amount, None).unwrap(),
+                    expected
+                );
+            }
+        }
+
+        #[test]
+        fn quote_output_returns_fee_paid() {
+            let input_amount = CurrencyAmount::from_raw_amount(USDC.clone(), 1_000_000).unwrap();
+            let quote = POOL.quote_output(&input_amount, None).unwrap();
+            assert_eq!(
+                quote.amount,
+                POOL.get_output_amount(&input_amount, None).unwrap()
+            );
+            // 0.05% of the input
+            assert!(quote.fee_amount.currency.equals(&USDC.clone()));
+            assert_eq!(quote.fee_amount.quotient(), 500.into());
+            assert_eq!(quote.ticks_crossed, 0);
+
+            let mut pool = POOL.clone();
+            pool.get_output_amount_mut(&input_amount, None).unwrap();
+            assert_eq!(quote.sqrt_price_x96_after, pool.sqrt_ratio_x96);
+        }
+
+        #[test]
+        fn quote_input_returns_fee_paid() {
+            let output_amount = CurrencyAmount::from_raw_amount(DAI.clone(), 98).unwrap();
+            let quote = POOL.quote_input(&output_amount, None).unwrap();
+            assert_eq!(
+                quote.amount,
+                POOL.get_input_amount(&output_amount, None).unwrap()
+            );
+            assert!(quote.fee_amount.currency.equals(&USDC.clone()));
+            assert!(quote.fee_amount.quotient() > 0.into());
+            assert!(quote.fee_amount.quotient() < quote.amount.quotient());
+        }
+
+        #[test]
+        fn quote_counts_initialized_ticks_crossed() {
+            let liquidity = ONE_ETHER.into_limbs()[0] as u128;
+            let pool = Pool::new_with_tick_data_provider(
+                USDC.clone(),
+                DAI.clone(),
+                FeeAmount::LOW,
+                encode_sqrt_ratio_x96(1, 1),
+                liquidity,
+                TickListDataProvider::new(
+                    vec![
+                        Tick::new(
+                            nearest_usable_tick(MIN_TICK, FeeAmount::LOW.tick_spacing()).as_i32(),
+                            liquidity,
+                            liquidity as i128,
+                        ),
+                        Tick::new(10, liquidity, liquidity as i128),
+                        Tick::new(
+                            nearest_usable_tick(MAX_TICK, FeeAmount::LOW.tick_spacing()).as_i32(),
+                            2 * liquidity,
+                            -2 * (liquidity as i128),
+                        ),
+                    ],
+                    FeeAmount::LOW.tick_spacing().as_i32(),
+                ),
+            )
+            .unwrap();
+            // USDC is token1, selling it moves the price up through tick 10
+            let small = CurrencyAmount::from_raw_amount(USDC.clone(), 1_000_000).unwrap();
+            assert_eq!(pool.quote_output(&small, None).unwrap().ticks_crossed, 0);
+            let large =
+                CurrencyAmount::from_raw_amount(USDC.clone(), 1_000_000_000_000_000_u64).unwrap();
+            assert_eq!(pool.quote_output(&large, None).unwrap().ticks_crossed, 1);
+        }
+
+        #[test]
+        #[cfg(feature = "std")]
+        fn swap_errors_carry_pool_amount_and_tick() {
+            let pool = Pool::new(
+                USDC.clone(),
+                DAI.clone(),
+                FeeAmount::LOW,
+                encode_sqrt_ratio_x96(1, 1),
+                0,
+            )
+            .unwrap();
+            let err = pool
+                .get_output_amount(
+                    &CurrencyAmount::from_raw_amount(USDC.clone(), 100).unwrap(),
+                    None,
+                )
+                .unwrap_err();
+            assert!(matches!(err.root(), Error::NoTickDataError));
+            assert_eq!(
+                err.to_string(),
+                format!(
+                    "pool {}, amount 100: tick 0: No tick data provider was given",
+                    pool.address(None, None)
+                )
+            );
+        }
+    }
+
+    mod merge {
+        use super::*;
+
+        fn pool() -> Pool {
+            Pool::new(
+                USDC.clone(),
+                DAI.clone(),
+                FeeAmount::MEDIUM,
+                encode_sqrt_ratio_x96(1, 1),
+                1000,
+            )
+            .unwrap()
+        }
+
+        #[test]
+        fn diff_of_identical_states_is_empty() {
+            assert!(pool().diff(&pool()).unwrap().is_empty());
+        }
+
+        #[test]
+        fn diff_is_other_minus_self() {
+            let other = Pool::new(
+                USDC.clone(),
+                DAI.clone(),
+                FeeAmount::MEDIUM,
+                encode_sqrt_ratio_x96(101, 100),
+                400,
+            )
+            .unwrap();
+            let diff = pool().diff(&other).unwrap();
+            assert_eq!(
+                diff.sqrt_ratio_x96_delta,
+                I256::from_raw(U256::from(other.sqrt_ratio_x96))
+                    - I256::from_raw(encode_sqrt_ratio_x96(1, 1))
+            );
+            assert_eq!(diff.liquidity_delta, I256::try_from(-600).unwrap());
+            assert_eq!(diff.tick_delta, other.tick_current);
+            assert!(!diff.is_empty());
+        }
+
+        #[test]
+        fn diff_of_different_pools_errors() {
+            let other = Pool::new(
+                USDC.clone(),
+                DAI.clone(),
+                FeeAmount::LOW,
+                encode_sqrt_ratio_x96(1, 1),
+                1000,
+            )
+            .unwrap();
+            assert!(matches!(
+                pool().diff(&other),
+                Err(Error::PoolMismatch { .. })
+            ));
+        }
+
+        #[test]
+        fn merge_takes_first_source_per_field() {
+            let sqrt_ratio_x96 = encode_sqrt_ratio_x96(4, 1);
+            let (merged, provenance) = pool()
+                .merge_states(&[
+                    (
+                        "events",
+                        PoolState {
+                            sqrt_ratio_x96: None,
+                            liquidity: Some(2000),
+                        },
+                    ),
+                    (
+                        "rpc",
+                        PoolState {
+                            sqrt_ratio_x96: Some(sqrt_ratio_x96),
+                            liquidity: Some(3000),
+                        },
+                    ),
+                ])
+                .unwrap();
+            assert_eq!(merged.liquidity, 2000);
+            assert_eq!(merged.sqrt_ratio_x96, sqrt_ratio_x96);
+            assert_eq!(
+                merged.tick_current,
+                sqrt_ratio_x96.get_tick_at_sqrt_ratio().unwrap().as_i32()
+            );
+            assert_eq!(
+                provenance,
+                PoolProvenance {
+                    sqrt_ratio_x96: Some("rpc"),
+                    liquidity: Some("events"),
+                }
+            );
+        }
+
+        #[test]
+        fn merge_without_sources_keeps_state() {
+            let (merged, provenance) = pool().merge_states::<u8>(&[]).unwrap();
+            assert_eq!(merged, pool());
+            assert_eq!(provenance, PoolProvenance::default());
+        }
     }
 }
@@ -13,9 +13,9 @@ where
     pub tick_lower: TP::Index,
     pub tick_upper: TP::Index,
     pub liquidity: u128,
-    _token0_amount: Option<CurrencyAmount<Token>>,
-    _token1_amount: Option<CurrencyAmount<Token>>,
-    _mint_amounts: Option<MintAmounts>,
+    _token0_amount: Cached<CurrencyAmount<Token>>,
+    _token1_amount: Cached<CurrencyAmount<Token>>,
+    _mint_amounts: Cached<MintAmounts>,
 }
 
 #[derive(Debug, Clone, Copy, PartialEq, Eq)]
@@ -24,6 +24,201 @@ pub struct MintAmounts {
     pub amount1: U256,
 }
 
+/// The corrections made by [`Position::new_clamped`] to a requested tick range.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Hash)]
+pub struct TickRangeAdjustment<I = i32> {
+    /// The lower tick as requested.
+    pub requested_tick_lower: I,
+    /// The upper tick as requested.
+    pub requested_tick_upper: I,
+    /// Whether the requested ticks were in descending order and swapped.
+    pub reordered: bool,
+    /// Whether a requested tick was outside of [`MIN_TICK`] and [`MAX_TICK`].
+    pub clamped: bool,
+    /// Whether a tick was rounded outwards to a multiple of the tick spacing.
+    pub aligned: bool,
+    /// Whether the range was widened by a tick spacing because both ticks rounded to the same
+    /// usable tick.
+    pub widened: bool,
+}
+
+impl<I> TickRangeAdjustment<I> {
+    /// Whether the tick range of the position differs from the requested one
+    #[inline]
+    #[must_use]
+    pub const fn is_adjusted(&self) -> bool {
+        self.reordered || self.clamped || self.aligned || self.widened
+    }
+}
+
+/// The sensitivities of a position's value to the price of the base token, i.e. the pool token
+/// that is not the quote currency.
+///
+/// Prices are in raw units, i.e. the amount of the smallest unit of the quote token per smallest
+/// unit of the base token.
+#[derive(Debug, Clone, PartialEq)]
+pub struct PositionGreeks {
+    /// The first derivative of the position value with respect to the price, which equals the
+    /// amount of the base token held by the position.
+    pub delta: CurrencyAmount<Token>,
+    /// The second derivative of the position value with respect to the price. Always
+    /// non-positive within the range and zero outside of it.
+    pub gamma: Fraction,
+}
+
+/// The short offsetting the exposure of a position to the price of its base token, i.e. the pool
+/// token that is not the quote currency, as returned by [`Position::hedge_notional`].
+#[derive(Debug, Clone, PartialEq)]
+pub struct HedgeNotional {
+    /// The amount of the base token to short, which equals the delta of the position.
+    pub size: CurrencyAmount<Token>,
+    /// The value of the short in the quote currency at the hedged price.
+    pub notional: CurrencyAmount<Token>,
+    /// The sqrt price the hedge is computed at.
+    pub sqrt_ratio_x96: U160,
+}
+
+impl HedgeNotional {
+    /// Returns the amount of the base token to short, if positive, or to buy back, if negative,
+    /// to bring an existing short to the size of the hedge
+    ///
+    /// ## Arguments
+    ///
+    /// * `current_short`: The amount of the base token currently shorted
+    #[inline]
+    pub fn rebalance_amount(&self, current_short: &CurrencyAmount<Token>) -> Result<BigInt, Error> {
+        if !current_short.currency.equals(&self.size.currency) {
+            return Err(Error::InvalidToken);
+        }
+        Ok(self.size.quotient() - current_short.quotient())
+    }
+}
+
+/// The exit of a position and the swap re-entering a new range with the freed amounts, see
+/// [`Position::rebalance_to_range`].
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct RebalancePlan {
+    /// The amount of token0 available, e.g. freed by exiting a position, excluding fees.
+    pub amount0_freed: U256,
+    /// The amount of token1 available, e.g. freed by exiting a position, excluding fees.
+    pub amount1_freed: U256,
+    /// Whether token0 is swapped for token1.
+    pub zero_for_one: bool,
+    /// The amount swapped in, zero if no swap is needed.
+    pub amount_in: U256,
+    /// The amount received from the swap.
+    pub amount_out: U256,
+    /// The sqrt price of the pool after the swap.
+    pub sqrt_ratio_x96_after: U160,
+    /// The liquidity of the new position minted with the amounts after the swap.
+    pub liquidity: u128,
+}
+
+impl RebalancePlan {
+    /// Computes the swap in a pool, in amount and direction, after which the given amounts mint
+    /// the maximum liquidity in a range
+    ///
+    /// The swap is found by bisection on the amount in, so that its price impact is accounted for.
+    ///
+    /// ## Arguments
+    ///
+    /// * `pool`: The pool the swap is simulated on and the range is minted in
+    /// * `amount0`: The amount of token0 available
+    /// * `amount1`: The amount of token1 available
+    /// * `new_tick_lower`: The lower tick of the range
+    /// * `new_tick_upper`: The upper tick of the range
+    #[inline]
+    pub fn for_amounts<TP: TickDataProvider>(
+        pool: &Pool<TP>,
+        amount0: U256,
+        amount1: U256,
+        new_tick_lower: TP::Index,
+        new_tick_upper: TP::Index,
+    ) -> Result<Self, Error> {
+        if new_tick_lower >= new_tick_upper {
+            return Err(Error::InvalidRange);
+        }
+        let sqrt_ratio_a_x96 = get_sqrt_ratio_at_tick(new_tick_lower.to_i24())?;
+        let sqrt_ratio_b_x96 = get_sqrt_ratio_at_tick(new_tick_upper.to_i24())?;
+        // whether token0 would be left over when minting the new range at the given price
+        let token0_in_excess = |sqrt_ratio_x96: U160, amount0: U256, amount1: U256| {
+            max_liquidity_for_amounts(
+                sqrt_ratio_x96,
+                sqrt_ratio_a_x96,
+                sqrt_ratio_b_x96,
+                amount0,
+                U256::MAX,
+                true,
+            ) > max_liquidity_for_amounts(
+                sqrt_ratio_x96,
+                sqrt_ratio_a_x96,
+                sqrt_ratio_b_x96,
+                U256::MAX,
+                amount1,
+                true,
+            )
+        };
+        let zero_for_one = token0_in_excess(pool.sqrt_ratio_x96, amount0, amount1);
+        let (token_in, amount_in_max) = if zero_for_one {
+            (&pool.token0, amount0)
+        } else {
+            (&pool.token1, amount1)
+        };
+        let swap = |amount_in: U256| -> Result<(U256, U160), Error> {
+            if amount_in.is_zero() {
+                return Ok((U256::ZERO, pool.sqrt_ratio_x96));
+            }
+            let quote = pool.quote_output(
+                &CurrencyAmount::from_raw_amount(token_in.clone(), amount_in.to_big_int())?,
+                None,
+            )?;
+            Ok((
+                U256::from_big_int(quote.amount.quotient()),
+                quote.sqrt_price_x96_after,
+            ))
+        };
+        let balances = |amount_in: U256, amount_out: U256| {
+            if zero_for_one {
+                (amount0 - amount_in, amount1 + amount_out)
+            } else {
+                (amount0 + amount_out, amount1 - amount_in)
+            }
+        };
+
+        // the largest amount in after which the input token is still in excess
+        let (mut lo, mut hi) = (U256::ZERO, amount_in_max);
+        while lo < hi {
+            let mid = lo + (hi - lo + U256::from(1)) / U256::from(2);
+            let (amount_out, sqrt_ratio_x96) = swap(mid)?;
+            let (balance0, balance1) = balances(mid, amount_out);
+            if token0_in_excess(sqrt_ratio_x96, balance0, balance1) == zero_for_one {
+                lo = mid;
+            } else {
+                hi = mid - U256::from(1);
+            }
+        }
+        let (amount_out, sqrt_ratio_x96_after) = swap(lo)?;
+        let (balance0, balance1) = balances(lo, amount_out);
+        let liquidity = max_liquidity_for_amounts(
+            sqrt_ratio_x96_after,
+            sqrt_ratio_a_x96,
+            sqrt_ratio_b_x96,
+            balance0,
+            balance1,
+            true,
+        );
+        Ok(Self {
+            amount0_freed: amount0,
+            amount1_freed: amount1,
+            zero_for_one,
+            amount_in: lo,
+            amount_out,
+            sqrt_ratio_x96_after,
+            liquidity: liquidity.to_u128().ok_or(Error::LiquidityOverflow)?,
+        })
+    }
+}
+
 impl<TP> PartialEq for Position<TP>
 where
     TP: TickDataProvider<Index: PartialEq>,
@@ -69,12 +264,77 @@ impl<TP: TickDataProvider> Position<TP> {
             liquidity,
             tick_lower,
             tick_upper,
-            _token0_amount: None,
-            _token1_amount: None,
-            _mint_amounts: None,
+            _token0_amount: Cached::new(),
+            _token1_amount: Cached::new(),
+            _mint_amounts: Cached::new(),
         }
     }
 
+    /// Constructs a position for a given pool with the given liquidity, snapping the requested
+    /// ticks to the nearest valid range instead of panicking like [`Position::new`], e.g. for
+    /// ranges typed as arbitrary prices
+    ///
+    /// The ticks are put in ascending order, clamped to [`MIN_TICK`] and [`MAX_TICK`] and rounded
+    /// outwards to the tick spacing of the pool, so that the range contains the requested one
+    /// whenever possible. If both round to the same tick, the range is widened by a tick spacing.
+    ///
+    /// ## Arguments
+    ///
+    /// * `pool`: For which pool the liquidity is assigned
+    /// * `liquidity`: The amount of liquidity that is in the position
+    /// * `tick_lower`: The requested lower tick of the position
+    /// * `tick_upper`: The requested upper tick of the position
+    ///
+    /// ## Returns
+    ///
+    /// The position and the adjustments made to the requested ticks
+    #[inline]
+    pub fn new_clamped(
+        pool: Pool<TP>,
+        liquidity: u128,
+        tick_lower: TP::Index,
+        tick_upper: TP::Index,
+    ) -> (Self, TickRangeAdjustment<TP::Index>) {
+        let tick_spacing = pool.tick_spacing();
+        let min_tick = TP::Index::from_i24(MIN_TICK);
+        let max_tick = TP::Index::from_i24(MAX_TICK);
+        let reordered = tick_lower > tick_upper;
+        let (lower, upper) = if reordered {
+            (tick_upper, tick_lower)
+        } else {
+            (tick_lower, tick_upper)
+        };
+        let (clamped_lower, clamped_upper) = (
+            lower.clamp(min_tick, max_tick),
+            upper.clamp(min_tick, max_tick),
+        );
+        let clamped = clamped_lower != lower || clamped_upper != upper;
+        let (aligned_lower, aligned_upper) = (
+            usable_tick_floor(clamped_lower, tick_spacing),
+            usable_tick_ceil(clamped_upper, tick_spacing),
+        );
+        let aligned = aligned_lower != clamped_lower || aligned_upper != clamped_upper;
+        let widened = aligned_lower == aligned_upper;
+        let (new_lower, new_upper) = if !widened {
+            (aligned_lower, aligned_upper)
+        } else if aligned_upper + tick_spacing <= max_tick {
+            (aligned_lower, aligned_upper + tick_spacing)
+        } else {
+            (aligned_lower - tick_spacing, aligned_upper)
+        };
+        (
+            Self::new(pool, liquidity, new_lower, new_upper),
+            TickRangeAdjustment {
+                requested_tick_lower: tick_lower,
+                requested_tick_upper: tick_upper,
+                reordered,
+                clamped,
+                aligned,
+                widened,
+            },
+        )
+    }
+
     /// Returns the price of token0 at the lower tick
     #[inline]
     pub fn token0_price_lower(&self) -> Result<Price<Token, Token>, Error> {
@@ -131,11 +391,11 @@ impl<TP: TickDataProvider> Position<TP> {
     /// current pool price
     #[inline]
     pub fn amount0_cached(&mut self) -> Result<CurrencyAmount<Token>, Error> {
-        if let Some(amount) = &self._token0_amount {
+        if let Some(amount) = self._token0_amount.get() {
             return Ok(amount.clone());
         }
         let amount = self.amount0()?;
-        self._token0_amount = Some(amount.clone());
+        self._token0_amount.set(amount.clone());
         Ok(amount)
     }
 
@@ -175,14 +435,123 @@ impl<TP: TickDataProvider> Position<TP> {
     /// current pool price
     #[inline]
     pub fn amount1_cached(&mut self) -> Result<CurrencyAmount<Token>, Error> {
-        if let Some(amount) = &self._token1_amount {
+        if let Some(amount) = self._token1_amount.get() {
             return Ok(amount.clone());
         }
         let amount = self.amount1()?;
-        self._token1_amount = Some(amount.clone());
+        self._token1_amount.set(amount.clone());
         Ok(amount)
     }
 
+    /// Returns the delta and gamma of the position valued in `quote_currency`, using the closed-form
+    /// derivatives of the liquidity math with respect to the sqrt price.
+    ///
+    /// Within the range, the value in token1 is `V(P) = L * (2 * sqrt(P) - P / sqrt(P_b) -
+    /// sqrt(P_a))`, so the delta is `L * (1 / sqrt(P) - 1 / sqrt(P_b))`, i.e. the amount of token0,
+    /// and the gamma is `-L / (2 * P^(3/2))`. The same holds for token0 with the price inverted.
+    ///
+    /// ## Arguments
+    ///
+    /// * `quote_currency`: The pool token to value the position in
+    #[inline]
+    pub fn greeks(&self, quote_currency: &Token) -> Result<PositionGreeks, Error> {
+        let quote_is_token1 = if quote_currency.equals(&self.pool.token1) {
+            true
+        } else if quote_currency.equals(&self.pool.token0) {
+            false
+        } else {
+            return Err(Error::InvalidToken);
+        };
+        let delta = if quote_is_token1 {
+            self.amount0()?
+        } else {
+            self.amount1()?
+        };
+        let in_range =
+            self.tick_lower <= self.pool.tick_current && self.pool.tick_current < self.tick_upper;
+        let gamma = if in_range {
+            let liquidity = BigInt::from(self.liquidity);
+            let sqrt_price_x96_cubed = self.pool.sqrt_ratio_x96.to_big_int().pow(3);
+            let q288 = Q96.to_big_int().pow(3);
+            if quote_is_token1 {
+                Fraction::new(-(liquidity * q288), sqrt_price_x96_cubed * 2)
+            } else {
+                Fraction::new(-(liquidity * sqrt_price_x96_cubed), q288 * 2)
+            }
+        } else {
+            Fraction::new(0, 1)
+        };
+        Ok(PositionGreeks { delta, gamma })
+    }
+
+    /// Returns the short of the base token that delta-hedges the position valued in
+    /// `quote_currency` at the current pool price
+    ///
+    /// ## Arguments
+    ///
+    /// * `quote_currency`: The pool token to value the position in
+    #[inline]
+    pub fn hedge_notional(&self, quote_currency: &Token) -> Result<HedgeNotional, Error> {
+        self.hedge_notional_at(quote_currency, self.pool.sqrt_ratio_x96)
+    }
+
+    /// Returns the short of the base token that delta-hedges the position valued in
+    /// `quote_currency` at the given price, so that the hedge can be recomputed as the price moves
+    /// without constructing a new pool
+    ///
+    /// ## Arguments
+    ///
+    /// * `quote_currency`: The pool token to value the position in
+    /// * `sqrt_ratio_x96`: The sqrt price to hedge at
+    #[inline]
+    pub fn hedge_notional_at(
+        &self,
+        quote_currency: &Token,
+        sqrt_ratio_x96: U160,
+    ) -> Result<HedgeNotional, Error> {
+        let quote_is_token1 = if quote_currency.equals(&self.pool.token1) {
+            true
+        } else if quote_currency.equals(&self.pool.token0) {
+            false
+        } else {
+            return Err(Error::InvalidToken);
+        };
+        if sqrt_ratio_x96 < MIN_SQRT_RATIO || sqrt_ratio_x96 >= MAX_SQRT_RATIO {
+            return Err(Error::InvalidSqrtPrice(sqrt_ratio_x96));
+        }
+        let sqrt_ratio_a_x96 = get_sqrt_ratio_at_tick(self.tick_lower.to_i24())?;
+        let sqrt_ratio_b_x96 = get_sqrt_ratio_at_tick(self.tick_upper.to_i24())?;
+        let sqrt_ratio_clamped_x96 = sqrt_ratio_x96.clamp(sqrt_ratio_a_x96, sqrt_ratio_b_x96);
+        let price_x192 = sqrt_ratio_x96.to_big_int().pow(2);
+        // the delta is the amount of the base token held at the price
+        let (base, size, notional) = if quote_is_token1 {
+            let size = get_amount_0_delta(
+                sqrt_ratio_clamped_x96,
+                sqrt_ratio_b_x96,
+                self.liquidity,
+                false,
+            )?
+            .to_big_int();
+            let notional = &size * price_x192 / Q192.to_big_int();
+            (&self.pool.token0, size, notional)
+        } else {
+            let size = get_amount_1_delta(
+                sqrt_ratio_a_x96,
+                sqrt_ratio_clamped_x96,
+                self.liquidity,
+                false,
+            )?
+            .to_big_int();
+            let notional = &size * Q192.to_big_int() / price_x192;
+            (&self.pool.token1, size, notional)
+        };
+        Ok(HedgeNotional {
+            size: CurrencyAmount::from_raw_amount(base.clone(), size)?,
+            notional: CurrencyAmount::from_raw_amount(quote_currency.clone(), notional)?,
+            sqrt_ratio_x96,
+        })
+    }
+
     /// Returns the lower and upper sqrt ratios if the price 'slips' up to slippage tolerance
     /// percentage
     ///
@@ -397,14 +766,57 @@ impl<TP: TickDataProvider> Position<TP> {
     /// by the position at the current price for the pool
     #[inline]
     pub fn mint_amounts_cached(&mut self) -> Result<MintAmounts, Error> {
-        if let Some(amounts) = &self._mint_amounts {
+        if let Some(amounts) = self._mint_amounts.get() {
             return Ok(*amounts);
         }
         let amounts = self.mint_amounts()?;
-        self._mint_amounts = Some(amounts);
+        self._mint_amounts.set(amounts);
         Ok(amounts)
     }
 
+    /// Computes the fees accrued by the position since its fee growth checkpoints, i.e. the fees
+    /// not yet credited to `tokensOwed` on chain, from the fee growth of the pool and of the ticks
+    /// of the position at the current tick of the pool
+    ///
+    /// ## Arguments
+    ///
+    /// * `fee_growth_inside_0_last_x128`: The fee growth of token0 inside the range at the last
+    ///   checkpoint of the position
+    /// * `fee_growth_inside_1_last_x128`: The fee growth of token1 inside the range at the last
+    ///   checkpoint of the position
+    /// * `lower`: The fee growth outside the lower tick
+    /// * `upper`: The fee growth outside the upper tick
+    /// * `fee_growth_global0_x128`: The global fee growth of token0 of the pool
+    /// * `fee_growth_global1_x128`: The global fee growth of token1 of the pool
+    #[inline]
+    #[must_use]
+    pub fn owed_fees(
+        &self,
+        fee_growth_inside_0_last_x128: U256,
+        fee_growth_inside_1_last_x128: U256,
+        lower: FeeGrowthOutside<256, 4>,
+        upper: FeeGrowthOutside<256, 4>,
+        fee_growth_global0_x128: U256,
+        fee_growth_global1_x128: U256,
+    ) -> (U256, U256) {
+        let (fee_growth_inside_0_x128, fee_growth_inside_1_x128) = get_fee_growth_inside(
+            lower,
+            upper,
+            self.tick_lower,
+            self.tick_upper,
+            self.pool.tick_current,
+            fee_growth_global0_x128,
+            fee_growth_global1_x128,
+        );
+        get_tokens_owed(
+            fee_growth_inside_0_last_x128,
+            fee_growth_inside_1_last_x128,
+            self.liquidity,
+            fee_growth_inside_0_x128,
+            fee_growth_inside_1_x128,
+        )
+    }
+
     /// Computes the maximum amount of liquidity received for a given amount of token0, token1,
     /// and the prices at the tick boundaries.
     ///
@@ -420,7 +832,8 @@ impl<TP: TickDataProvider> Position<TP> {
     ///
     /// ## Returns
     ///
-    /// The position with the maximum amount of liquidity received
+    /// The position with the maximum amount of liquidity received, or
+    /// [`Error::LiquidityOverflow`] if it does not fit in 128 bits
     #[inline]
     pub fn from_amounts(
         pool: Pool<TP>,
@@ -442,7 +855,7 @@ impl<TP: TickDataProvider> Position<TP> {
         );
         Ok(Self::new(
             pool,
-            liquidity.to_u128().unwrap(),
+            liquidity.to_u128().ok_or(Error::LiquidityOverflow)?,
             tick_lower,
             tick_upper,
         ))
@@ -496,6 +909,31 @@ impl<TP: TickDataProvider> Position<TP> {
         // this function always uses full precision
         Self::from_amounts(pool, tick_lower, tick_upper, U256::MAX, amount1, true)
     }
+
+    /// Computes the amounts freed by exiting the position and the swap, in amount and direction,
+    /// after which they mint the maximum liquidity in a new range of the same pool
+    ///
+    /// The swap is simulated on the pool as is, i.e. with the liquidity of the position still in
+    /// it, see [`RebalancePlan::for_amounts`].
+    ///
+    /// ## Arguments
+    ///
+    /// * `new_tick_lower`: The lower tick of the new range
+    /// * `new_tick_upper`: The upper tick of the new range
+    #[inline]
+    pub fn rebalance_to_range(
+        &self,
+        new_tick_lower: TP::Index,
+        new_tick_upper: TP::Index,
+    ) -> Result<RebalancePlan, Error> {
+        RebalancePlan::for_amounts(
+            &self.pool,
+            U256::from_big_int(self.amount0()?.quotient()),
+            U256::from_big_int(self.amount1()?.quotient()),
+            new_tick_lower,
+            new_tick_upper,
+        )
+    }
 }
 
 #[cfg(test)]
@@ -504,6 +942,7 @@ mod tests {
     use crate::tests::*;
     use alloy_primitives::aliases::I24;
     use once_cell::sync::Lazy;
+    use uniswap_sdk_core::token;
 
     static POOL_SQRT_RATIO_START: Lazy<U160> =
         Lazy::new(|| encode_sqrt_ratio_x96(BigInt::from(10).pow(8), BigInt::from(10).pow(20)));
@@ -524,6 +963,152 @@ mod tests {
 
     const TWO: I24 = I24::from_limbs([2]);
 
+    mod owed_fees {
+        use super::*;
+
+        const GLOBAL: FeeGrowthOutside<256, 4> = FeeGrowthOutside {
+            fee_growth_outside0_x128: Q128,
+            fee_growth_outside1_x128: Q128,
+        };
+
+        #[test]
+        fn in_range() {
+            let position = Position::new(POOL_0_1.clone(), 100, -60, 60);
+            let fees = position.owed_fees(
+                U256::ZERO,
+                U256::ZERO,
+                FeeGrowthOutside::default(),
+                FeeGrowthOutside::default(),
+                Q128 * U256::from(2),
+                Q128,
+            );
+            assert_eq!(fees, (U256::from(200), U256::from(100)));
+        }
+
+        #[test]
+        fn since_checkpoint() {
+            let position = Position::new(POOL_0_1.clone(), 100, -60, 60);
+            let fees = position.owed_fees(
+                Q128,
+                Q128 * U256::from(3),
+                FeeGrowthOutside::default(),
+                FeeGrowthOutside::default(),
+                Q128 * U256::from(3),
+                Q128 * U256::from(3),
+            );
+            assert_eq!(fees, (U256::from(200), U256::ZERO));
+        }
+
+        #[test]
+        fn below_range() {
+            let position = Position::new(POOL_0_1.clone(), 100, 60, 120);
+            let fees = position.owed_fees(
+                U256::ZERO,
+                U256::ZERO,
+                GLOBAL,
+                FeeGrowthOutside::default(),
+                Q128 * U256::from(2),
+                Q128 * U256::from(2),
+            );
+            assert_eq!(fees, (U256::from(100), U256::from(100)));
+        }
+    }
+
+    mod greeks {
+        use super::*;
+
+        #[test]
+        fn in_range() {
+            let position = Position::new(POOL_0_1.clone(), 100e18 as u128, -60, 60);
+            let greeks = position.greeks(&TOKEN1).unwrap();
+            assert_eq!(greeks.delta, position.amount0().unwrap());
+            assert_eq!(greeks.gamma.quotient(), BigInt::from(-50e18 as i128));
+            let greeks = position.greeks(&TOKEN0).unwrap();
+            assert_eq!(greeks.delta, position.amount1().unwrap());
+            assert_eq!(greeks.gamma.quotient(), BigInt::from(-50e18 as i128));
+        }
+
+        #[test]
+        fn out_of_range() {
+            let position = Position::new(POOL_0_1.clone(), 100e18 as u128, 60, 120);
+            let greeks = position.greeks(&TOKEN1).unwrap();
+            assert_eq!(greeks.delta, position.amount0().unwrap());
+            assert_eq!(greeks.gamma.quotient(), BigInt::ZERO);
+            let greeks = position.greeks(&TOKEN0).unwrap();
+            assert_eq!(greeks.delta.quotient(), BigInt::ZERO);
+        }
+
+        #[test]
+        fn invalid_quote_currency() {
+            let position = Position::new(POOL_0_1.clone(), 100, -60, 60);
+            assert!(matches!(position.greeks(&TOKEN2), Err(Error::InvalidToken)));
+        }
+    }
+
+    mod hedge_notional {
+        use super::*;
+
+        #[test]
+        fn in_range() {
+            let position = Position::new(POOL_0_1.clone(), 100e18 as u128, -60, 60);
+            let hedge = position.hedge_notional(&TOKEN1).unwrap();
+            assert_eq!(hedge.size, position.greeks(&TOKEN1).unwrap().delta);
+            // the price is 1
+            assert!(hedge.notional.currency.equals(&TOKEN1.clone()));
+            assert_eq!(hedge.notional.quotient(), hedge.size.quotient());
+            let hedge = position.hedge_notional(&TOKEN0).unwrap();
+            assert_eq!(hedge.size, position.amount1().unwrap());
+            assert!(hedge.notional.currency.equals(&TOKEN0.clone()));
+        }
+
+        #[test]
+        fn recomputed_as_price_moves() {
+            let position = Position::new(POOL_0_1.clone(), 100e18 as u128, -60, 60);
+            let hedge = position.hedge_notional(&TOKEN1).unwrap();
+            let sqrt_ratio_at =
+                |tick: i32| get_sqrt_ratio_at_tick(I24::try_from(tick).unwrap()).unwrap();
+
+            // all token0 below the range
+            let below = position
+                .hedge_notional_at(&TOKEN1, sqrt_ratio_at(-120))
+                .unwrap();
+            let amount0 = get_amount_0_delta(
+                sqrt_ratio_at(-60),
+                sqrt_ratio_at(60),
+                position.liquidity,
+                false,
+            )
+            .unwrap();
+            assert_eq!(below.size.quotient(), amount0.to_big_int());
+            assert!(below.rebalance_amount(&hedge.size).unwrap() > BigInt::ZERO);
+
+            // nothing to hedge above the range
+            let above = position
+                .hedge_notional_at(&TOKEN1, sqrt_ratio_at(120))
+                .unwrap();
+            assert_eq!(above.size.quotient(), BigInt::ZERO);
+            assert_eq!(
+                above.rebalance_amount(&hedge.size).unwrap(),
+                -hedge.size.quotient()
+            );
+        }
+
+        #[test]
+        fn invalid_tokens() {
+            let position = Position::new(POOL_0_1.clone(), 100, -60, 60);
+            assert!(matches!(
+                position.hedge_notional(&TOKEN2),
+                Err(Error::InvalidToken)
+            ));
+            let hedge = position.hedge_notional(&TOKEN1).unwrap();
+            let short = CurrencyAmount::from_raw_amount(TOKEN1.clone(), 1).unwrap();
+            assert!(matches!(
+                hedge.rebalance_amount(&short),
+                Err(Error::InvalidToken)
+            ));
+        }
+    }
+
     #[test]
     fn can_be_constructed_around_0_tick() {
         let position = Position::new(DAI_USDC_POOL.clone(), 1, -10, 10);
@@ -587,6 +1172,73 @@ mod tests {
         );
     }
 
+    mod new_clamped {
+        use super::*;
+
+        #[test]
+        fn keeps_a_valid_range() {
+            let (position, adjustment) = Position::new_clamped(POOL_0_1.clone(), 1, -60, 60);
+            assert_eq!(position, Position::new(POOL_0_1.clone(), 1, -60, 60));
+            assert!(!adjustment.is_adjusted());
+            assert_eq!(
+                (
+                    adjustment.requested_tick_lower,
+                    adjustment.requested_tick_upper
+                ),
+                (-60, 60)
+            );
+        }
+
+        #[test]
+        fn reorders_and_rounds_outwards() {
+            let (position, adjustment) = Position::new_clamped(POOL_0_1.clone(), 1, 125, -10);
+            assert_eq!((position.tick_lower, position.tick_upper), (-60, 180));
+            assert_eq!(
+                adjustment,
+                TickRangeAdjustment {
+                    requested_tick_lower: 125,
+                    requested_tick_upper: -10,
+                    reordered: true,
+                    clamped: false,
+                    aligned: true,
+                    widened: false,
+                }
+            );
+        }
+
+        #[test]
+        fn clamps_to_the_usable_ticks() {
+            let (position, adjustment) =
+                Position::new_clamped(POOL_0_1.clone(), 1, MIN_TICK_I32 - 100, i32::MAX);
+            assert_eq!(
+                (position.tick_lower, position.tick_upper),
+                (
+                    nearest_usable_tick(MIN_TICK_I32, 60),
+                    nearest_usable_tick(MAX_TICK_I32, 60)
+                )
+            );
+            assert!(adjustment.clamped && adjustment.aligned);
+            assert!(!adjustment.reordered && !adjustment.widened);
+        }
+
+        #[test]
+        fn widens_an_empty_range() {
+            let (position, adjustment) = Position::new_clamped(POOL_0_1.clone(), 1, 60, 60);
+            assert_eq!((position.tick_lower, position.tick_upper), (60, 120));
+            assert!(adjustment.widened && !adjustment.aligned);
+
+            // at the largest usable tick the range is widened downwards
+            let max_usable_tick = nearest_usable_tick(MAX_TICK_I32, 60);
+            let (position, adjustment) =
+                Position::new_clamped(POOL_0_1.clone(), 1, max_usable_tick, MAX_TICK_I32);
+            assert_eq!(
+                (position.tick_lower, position.tick_upper),
+                (max_usable_tick - 60, max_usable_tick)
+            );
+            assert!(adjustment.widened && adjustment.aligned && !adjustment.clamped);
+        }
+    }
+
     #[test]
     fn amount0_is_correct_for_price_above() {
         let position = Position::new(
@@ -973,4 +1625,141 @@ mod tests {
         assert_eq!(amount0.to_string(), "120054069145287995769397");
         assert_eq!(amount1.to_string(), "79831926243");
     }
+
+    #[test]
+    fn rebalance_to_range_swaps_into_the_new_range() {
+        let pool = make_pool(TOKEN0.clone(), TOKEN1.clone());
+        let tick = |n: i32| n * pool.tick_spacing();
+        // below the current price, the position only holds token1
+        let position = Position::new(pool.clone(), 100000, tick(-10), tick(-1));
+
+        // above the current price, the new range only takes token0
+        let plan = position.rebalance_to_range(tick(1), tick(10)).unwrap();
+        assert!(plan.amount0_freed.is_zero());
+        assert!(!plan.zero_for_one);
+        assert_eq!(plan.amount_in, plan.amount1_freed);
+        assert!(plan.liquidity > 0);
+
+        // around the current price, about half of token1 is swapped
+        let plan = position.rebalance_to_range(tick(-10), tick(10)).unwrap();
+        assert!(!plan.zero_for_one);
+        assert!(plan.amount_in > U256::ZERO && plan.amount_in < plan.amount1_freed);
+        let liquidity_without_swap = max_liquidity_for_amounts(
+            pool.sqrt_ratio_x96,
+            get_sqrt_ratio_at_tick(tick(-10).to_i24()).unwrap(),
+            get_sqrt_ratio_at_tick(tick(10).to_i24()).unwrap(),
+            plan.amount0_freed,
+            plan.amount1_freed,
+            true,
+        );
+        assert!(BigUint::from(plan.liquidity) > liquidity_without_swap);
+
+        assert!(matches!(
+            position.rebalance_to_range(tick(10), tick(-10)),
+            Err(Error::InvalidRange)
+        ));
+    }
+
+    #[test]
+    fn rebalance_to_range_without_swap() {
+        let pool = make_pool(TOKEN0.clone(), TOKEN1.clone());
+        let tick = |n: i32| n * pool.tick_spacing();
+        let position = Position::new(pool.clone(), 100000, tick(-10), tick(-1));
+
+        // below the current price, the new range only takes the token1 held
+        let plan = position.rebalance_to_range(tick(-20), tick(-5)).unwrap();
+        assert!(plan.amount0_freed.is_zero());
+        assert!(plan.amount_in.is_zero());
+        assert!(plan.amount_out.is_zero());
+        assert_eq!(plan.sqrt_ratio_x96_after, pool.sqrt_ratio_x96);
+        assert!(plan.liquidity > 0);
+    }
+
+    #[test]
+    fn rebalance_to_range_swaps_token0_into_the_new_range() {
+        let pool = make_pool(TOKEN0.clone(), TOKEN1.clone());
+        let tick = |n: i32| n * pool.tick_spacing();
+        // above the current price, the position only holds token0
+        let position = Position::new(pool.clone(), 100000, tick(1), tick(10));
+
+        // around the current price, about half of token0 is swapped
+        let plan = position.rebalance_to_range(tick(-10), tick(10)).unwrap();
+        assert!(plan.amount1_freed.is_zero());
+        assert!(plan.zero_for_one);
+        assert!(plan.amount_in > U256::ZERO && plan.amount_in < plan.amount0_freed);
+        assert!(plan.amount_out > U256::ZERO);
+        assert!(plan.sqrt_ratio_x96_after < pool.sqrt_ratio_x96);
+        let liquidity_without_swap = max_liquidity_for_amounts(
+            pool.sqrt_ratio_x96,
+            get_sqrt_ratio_at_tick(tick(-10).to_i24()).unwrap(),
+            get_sqrt_ratio_at_tick(tick(10).to_i24()).unwrap(),
+            plan.amount0_freed,
+            plan.amount1_freed,
+            true,
+        );
+        assert!(BigUint::from(plan.liquidity) > liquidity_without_swap);
+    }
+
+    #[test]
+    fn from_amounts_for_tokens_with_0_and_36_decimals() {
+        let token_0_decimals = token!(
+            1,
+            "3333333333333333333333333333333333333333",
+            0,
+            "T3",
+            "token3"
+        );
+        let token_36_decimals = token!(
+            1,
+            "5555555555555555555555555555555555555555",
+            36,
+            "T5",
+            "token5"
+        );
+        let tick_spacing = FeeAmount::MEDIUM.tick_spacing().as_i32();
+        let tick_lower = nearest_usable_tick(MIN_TICK_I32, tick_spacing);
+        let tick_upper = nearest_usable_tick(MAX_TICK_I32, tick_spacing);
+        // 1 unit of token3 is worth 1 unit of token5
+        let pool = Pool::new(
+            token_0_decimals,
+            token_36_decimals.clone(),
+            FeeAmount::MEDIUM,
+            encode_sqrt_ratio_x96(BigInt::from(10).pow(36), 1),
+            0,
+        )
+        .unwrap();
+        let amount0 = U256::from(1000);
+        let amount1 = U256::from(2000) * U256::from(10).pow(U256::from(36));
+        let position =
+            Position::from_amounts(pool, tick_lower, tick_upper, amount0, amount1, true).unwrap();
+        assert!(position.liquidity > 0);
+        let amount0_deposited = position.amount0().unwrap().quotient();
+        let amount1_deposited = position.amount1().unwrap().quotient();
+        assert!(amount0_deposited <= amount0.to_big_int());
+        assert!(amount1_deposited <= amount1.to_big_int());
+        // the liquidity is limited by token3, whose amount is exhausted up to rounding
+        assert!(amount0_deposited >= amount0.to_big_int() - 1);
+        assert!(amount1_deposited < amount1.to_big_int());
+
+        // a million units of two 36 decimals tokens at price 1 need more than 128 bits of liquidity
+        let pool = Pool::new(
+            token!(
+                1,
+                "4444444444444444444444444444444444444444",
+                36,
+                "T4",
+                "token4"
+            ),
+            token_36_decimals,
+            FeeAmount::MEDIUM,
+            encode_sqrt_ratio_x96(1, 1),
+            0,
+        )
+        .unwrap();
+        let amount = U256::from(10).pow(U256::from(42));
+        assert!(matches!(
+            Position::from_amounts(pool, tick_lower, tick_upper, amount, amount, true),
+            Err(Error::LiquidityOverflow)
+        ));
+    }
 }
@@ -1,7 +1,88 @@
 use crate::prelude::{Error, *};
-use alloy_primitives::ChainId;
+use alloc::string::String;
+use alloy_primitives::{aliases::U24, keccak256, Address, Bytes, ChainId, B256, U160, U256};
+use core::cmp::Ordering;
 use uniswap_sdk_core::prelude::*;
 
+/// Returns the sum of the fee tiers of the pools in hundredths of a bip, saturating at
+/// [`u32::MAX`]
+#[inline]
+pub(crate) fn total_fee<TP: TickDataProvider>(pools: &[Pool<TP>]) -> u32 {
+    pools.iter().fold(0, |total, pool| {
+        let fee: U24 = pool.fee.into();
+        total.saturating_add(fee.to::<u32>())
+    })
+}
+
+/// A token along a [`RouteSummary`].
+#[derive(Clone, Debug, PartialEq, Eq, Hash)]
+#[cfg_attr(
+    feature = "schemars",
+    derive(serde::Serialize, serde::Deserialize, schemars::JsonSchema)
+)]
+pub struct TokenSummary {
+    #[cfg_attr(feature = "schemars", schemars(with = "String"))]
+    pub address: Address,
+    pub symbol: Option<String>,
+    pub decimals: u8,
+}
+
+/// A pool along a [`RouteSummary`].
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+#[cfg_attr(
+    feature = "schemars",
+    derive(serde::Serialize, serde::Deserialize, schemars::JsonSchema)
+)]
+pub struct PoolSummary {
+    /// The address of the pool computed from the canonical factory.
+    #[cfg_attr(feature = "schemars", schemars(with = "String"))]
+    pub address: Address,
+    /// The fee tier in hundredths of a bip.
+    pub fee: u32,
+    pub tick_spacing: i32,
+}
+
+/// A description of a [`Route`] without its generics, for logging and API responses.
+#[derive(Clone, Debug, PartialEq, Eq, Hash)]
+#[cfg_attr(
+    feature = "schemars",
+    derive(serde::Serialize, serde::Deserialize, schemars::JsonSchema)
+)]
+pub struct RouteSummary {
+    pub chain_id: ChainId,
+    /// The tokens the route goes through, wrapped if native.
+    pub tokens: Vec<TokenSummary>,
+    /// The pools the route goes through, in order.
+    pub pools: Vec<PoolSummary>,
+    pub input_is_native: bool,
+    pub output_is_native: bool,
+}
+
+/// The identity of a [`Route`], independent of the state of its pools and of whether its ends
+/// are native, see [`Route::canonical_key`].
+///
+/// Keys are ordered by chain, then by path, so that they can be sorted and deduplicated.
+#[derive(Clone, Debug, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub struct RouteKey {
+    /// The chain of the tokens and pools of the route.
+    pub chain_id: ChainId,
+    /// The packed path from input to output, i.e. `token (fee token)*` with 20-byte addresses
+    /// and 3-byte fees, as encoded by [`encode_route_to_path()`].
+    pub path: Bytes,
+}
+
+impl RouteKey {
+    /// Returns the keccak256 hash of the chain ID as 8 big-endian bytes followed by the path
+    #[inline]
+    #[must_use]
+    pub fn hash(&self) -> B256 {
+        let mut preimage = Vec::with_capacity(8 + self.path.len());
+        preimage.extend_from_slice(&self.chain_id.to_be_bytes());
+        preimage.extend_from_slice(&self.path);
+        keccak256(preimage)
+    }
+}
+
 /// Represents a list of pools through which a swap can occur
 #[derive(Clone, PartialEq, Debug)]
 pub struct Route<TInput, TOutput, TP>
@@ -15,7 +96,7 @@ where
     pub input: TInput,
     /// The output token
     pub output: TOutput,
-    _mid_price: Option<Price<TInput, TOutput>>,
+    _mid_price: Cached<Price<TInput, TOutput>>,
 }
 
 impl<TInput, TOutput, TP> Route<TInput, TOutput, TP>
@@ -31,41 +112,78 @@ where
     /// * `pools`: An array of [`Pool`] objects, ordered by the route the swap will take
     /// * `input`: The input token
     /// * `output`: The output token
+    ///
+    /// ## Panics
+    ///
+    /// Panics if the route is invalid, see [`Route::try_new`].
     #[inline]
     pub fn new(pools: Vec<Pool<TP>>, input: TInput, output: TOutput) -> Self {
-        assert!(!pools.is_empty(), "POOLS");
+        Self::try_new(pools, input, output).unwrap()
+    }
+
+    /// Creates an instance of route, returning an error if the route is invalid.
+    ///
+    /// ## Arguments
+    ///
+    /// * `pools`: An array of [`Pool`] objects, ordered by the route the swap will take
+    /// * `input`: The input token
+    /// * `output`: The output token
+    ///
+    /// ## Errors
+    ///
+    /// * [`Error::ChainMismatch`] if the pools and currencies are not all on the same chain
+    /// * [`Error::EmptyRoute`] if no pool is given
+    /// * [`Error::PathMismatch`] if the input is not in the first pool or the pools do not form a
+    ///   path
+    /// * [`Error::CurrencyMismatch`] if the path does not end in the output
+    #[inline]
+    pub fn try_new(pools: Vec<Pool<TP>>, input: TInput, output: TOutput) -> Result<Self, Error> {
+        if pools.is_empty() {
+            return Err(Error::EmptyRoute);
+        }
 
         let chain_id = pools[0].chain_id();
-        let all_on_same_chain = pools.iter().all(|pool| pool.chain_id() == chain_id);
-        assert!(all_on_same_chain, "CHAIN_IDS");
+        let chain_ids = pools
+            .iter()
+            .map(Pool::chain_id)
+            .chain([input.chain_id(), output.chain_id()]);
+        for actual in chain_ids {
+            if actual != chain_id {
+                return Err(Error::ChainMismatch {
+                    expected: chain_id,
+                    actual,
+                });
+            }
+        }
 
         let wrapped_input = input.wrapped();
-        assert!(pools[0].involves_token(wrapped_input), "INPUT");
-
         let wrapped_output = output.wrapped();
-        assert!(
-            pools.last().unwrap().involves_token(wrapped_output),
-            "OUTPUT"
-        );
-
         let mut current_input_token = wrapped_input;
-        for pool in &pools {
+        for (index, pool) in pools.iter().enumerate() {
             current_input_token = if current_input_token.equals(&pool.token0) {
                 &pool.token1
             } else if current_input_token.equals(&pool.token1) {
                 &pool.token0
             } else {
-                panic!("PATH")
+                return Err(Error::PathMismatch {
+                    index,
+                    token: current_input_token.address(),
+                });
             };
         }
-        assert!(current_input_token.equals(wrapped_output), "PATH");
+        if !current_input_token.equals(wrapped_output) {
+            return Err(Error::CurrencyMismatch {
+                expected: wrapped_output.address(),
+                actual: current_input_token.address(),
+            });
+        }
 
-        Self {
+        Ok(Self {
             pools,
             input,
             output,
-            _mid_price: None,
-        }
+            _mid_price: Cached::new(),
+        })
     }
 
     /// Returns the path of tokens that the route will take
@@ -89,6 +207,65 @@ where
         self.pools[0].chain_id()
     }
 
+    /// Returns the cumulative fee of the pools along the route in hundredths of a bip, e.g. 6000
+    /// for two 0.3% hops
+    #[inline]
+    #[must_use]
+    pub fn total_fee(&self) -> u32 {
+        total_fee(&self.pools)
+    }
+
+    /// Returns a serializable summary of the tokens and pools of the route
+    #[inline]
+    #[must_use]
+    pub fn summary(&self) -> RouteSummary {
+        RouteSummary {
+            chain_id: self.chain_id(),
+            tokens: self
+                .token_path()
+                .into_iter()
+                .map(|token| TokenSummary {
+                    address: token.address(),
+                    symbol: token.symbol.clone(),
+                    decimals: token.decimals,
+                })
+                .collect(),
+            pools: self
+                .pools
+                .iter()
+                .map(|pool| {
+                    let fee: U24 = pool.fee.into();
+                    PoolSummary {
+                        address: pool.address(None, None),
+                        fee: fee.to(),
+                        tick_spacing: pool.tick_spacing().to_i24().as_i32(),
+                    }
+                })
+                .collect(),
+            input_is_native: self.input.is_native(),
+            output_is_native: self.output.is_native(),
+        }
+    }
+
+    /// Returns the identity of the route: its chain and the pools it goes through, by tokens and
+    /// fees, in the direction of the swap
+    #[inline]
+    #[must_use]
+    pub fn canonical_key(&self) -> RouteKey {
+        RouteKey {
+            chain_id: self.chain_id(),
+            path: encode_route_to_path(self, false),
+        }
+    }
+
+    /// Returns a hash of [`Self::canonical_key`] which is stable across processes and versions,
+    /// e.g. to dedupe orders or cache quotes by route
+    #[inline]
+    #[must_use]
+    pub fn canonical_hash(&self) -> B256 {
+        self.canonical_key().hash()
+    }
+
     /// Returns the mid price of the route
     #[inline]
     pub fn mid_price(&self) -> Result<Price<TInput, TOutput>, Error> {
@@ -104,23 +281,231 @@ where
         ))
     }
 
+    /// Returns the mid price the route would have if its pools were at the given sqrt prices,
+    /// e.g. the states simulated after a candidate trade, without cloning or mutating the pools
+    ///
+    /// ## Arguments
+    ///
+    /// * `sqrt_ratios_x96`: The sqrt price of every pool of the route, in the order of the pools
+    #[inline]
+    pub fn mid_price_at_sqrt_ratios(
+        &self,
+        sqrt_ratios_x96: &[U160],
+    ) -> Result<Price<TInput, TOutput>, Error> {
+        if sqrt_ratios_x96.len() != self.pools.len() {
+            return Err(Error::LengthMismatch {
+                expected: self.pools.len(),
+                actual: sqrt_ratios_x96.len(),
+            });
+        }
+        let q192 = Q192.to_big_int();
+        let mut token = self.input.wrapped();
+        let mut numerator = BigInt::from(1);
+        let mut denominator = BigInt::from(1);
+        for (pool, sqrt_ratio_x96) in self.pools.iter().zip(sqrt_ratios_x96) {
+            if sqrt_ratio_x96.is_zero() {
+                return Err(Error::InvalidSqrtPrice(*sqrt_ratio_x96));
+            }
+            let ratio_x192 = sqrt_ratio_x96.to_big_int().pow(2);
+            if token.equals(&pool.token0) {
+                numerator *= ratio_x192;
+                denominator *= &q192;
+                token = &pool.token1;
+            } else {
+                numerator *= &q192;
+                denominator *= ratio_x192;
+                token = &pool.token0;
+            }
+        }
+        Ok(Price::new(
+            self.input.clone(),
+            self.output.clone(),
+            denominator,
+            numerator,
+        ))
+    }
+
+    /// Returns the relative decrease of the mid price of the route if its pools moved to the given
+    /// sqrt prices, i.e. the impact of a trade that leaves the pools in that state on the next
+    /// trade along the route
+    ///
+    /// ## Arguments
+    ///
+    /// * `sqrt_ratios_x96`: The sqrt price of every pool of the route after the trade
+    #[inline]
+    pub fn mid_price_impact_at_sqrt_ratios(
+        &self,
+        sqrt_ratios_x96: &[U160],
+    ) -> Result<Percent, Error> {
+        let before = self.mid_price()?;
+        let after = self.mid_price_at_sqrt_ratios(sqrt_ratios_x96)?;
+        // 1 - after / before
+        let denominator = &after.denominator * &before.numerator;
+        Ok(Percent::new(
+            &denominator - &after.numerator * &before.denominator,
+            denominator,
+        ))
+    }
+
+    /// Returns the mid price of the route in terms of the output per input as a Q128.128 fixed
+    /// point number, rounded down at every hop
+    ///
+    /// Unlike [`Self::mid_price`], no big number or [`Price`] is allocated, which makes it suited
+    /// for ranking many candidate routes.
+    #[inline]
+    pub fn mid_price_ratio(&self) -> Result<U256, Error> {
+        let mut token = self.input.wrapped();
+        let mut ratio = Q128;
+        for pool in &self.pools {
+            ratio = ratio.mul_div(pool.price_of_x128(token)?, Q128)?;
+            token = if token.equals(&pool.token0) {
+                &pool.token1
+            } else {
+                &pool.token0
+            };
+        }
+        Ok(ratio)
+    }
+
+    /// Compares the mid price of the route with that of another route between the same
+    /// currencies, using [`Self::mid_price_ratio`]
+    ///
+    /// ## Arguments
+    ///
+    /// * `other`: The route to compare with
+    #[inline]
+    pub fn cmp_mid_price<TP2: TickDataProvider>(
+        &self,
+        other: &Route<TInput, TOutput, TP2>,
+    ) -> Result<Ordering, Error> {
+        Ok(self.mid_price_ratio()?.cmp(&other.mid_price_ratio()?))
+    }
+
     /// Returns the cached mid price of the route
     #[inline]
     pub fn mid_price_cached(&mut self) -> Result<Price<TInput, TOutput>, Error> {
-        if let Some(mid_price) = &self._mid_price {
+        if let Some(mid_price) = self._mid_price.get() {
             return Ok(mid_price.clone());
         }
         let mid_price = self.mid_price()?;
-        self._mid_price = Some(mid_price.clone());
+        self._mid_price.set(mid_price.clone());
         Ok(mid_price)
     }
 }
 
+impl<TInput, TOutput, TP> Route<TInput, TOutput, TP>
+where
+    TInput: BaseCurrency,
+    TOutput: BaseCurrency,
+    TP: Clone + TickDataProvider,
+{
+    /// Quotes the route exact input, then exact output with the received amount, and reports how
+    /// much the two quotes disagree
+    ///
+    /// Both quotes pay the same fees, so the input required for the output should match the
+    /// amount swapped up to rounding. A large asymmetry indicates broken tick data, e.g. missing
+    /// ticks, and makes a useful sanity check before trading.
+    ///
+    /// ## Arguments
+    ///
+    /// * `amount_in`: The amount of the input currency swapped, which must not be zero
+    #[inline]
+    pub fn round_trip(
+        &self,
+        amount_in: CurrencyAmount<TInput>,
+    ) -> Result<RoundTripReport<TInput, TOutput>, Error> {
+        if amount_in.quotient() == BigInt::ZERO {
+            return Err(Error::ZeroAmount);
+        }
+        let amount_out = Trade::from_route(self.clone(), amount_in.clone(), TradeType::ExactInput)?
+            .output_amount()?;
+        let amount_in_required =
+            Trade::from_route(self.clone(), amount_out.clone(), TradeType::ExactOutput)?
+                .input_amount()?;
+        let expected = amount_in.quotient();
+        let actual = amount_in_required.quotient();
+        let difference = if actual >= expected {
+            actual - &expected
+        } else {
+            expected.clone() - actual
+        };
+        Ok(RoundTripReport {
+            amount_in,
+            amount_out,
+            amount_in_required,
+            asymmetry: Percent::new(difference, expected),
+        })
+    }
+
+    /// Returns the price impact of every hop when swapping an exact input through the route
+    ///
+    /// Each impact is the percent difference between the output of the pool and the amount quoted
+    /// at its mid price for the input of the hop, so the bottleneck pool of a multihop route is
+    /// the one with the largest impact. One minus the impacts multiply to one minus the price
+    /// impact of the whole trade.
+    ///
+    /// ## Arguments
+    ///
+    /// * `amount_in`: The amount of the input currency swapped
+    #[inline]
+    pub fn hop_price_impacts(
+        &self,
+        amount_in: &CurrencyAmount<impl BaseCurrency>,
+    ) -> Result<Vec<Percent>, Error> {
+        if !amount_in.currency.wrapped().equals(self.input.wrapped()) {
+            return Err(Error::CurrencyMismatch {
+                expected: self.input.wrapped().address(),
+                actual: amount_in.currency.wrapped().address(),
+            });
+        }
+        let mut amount: CurrencyAmount<Token> = amount_in.wrapped_owned()?;
+        let mut impacts = Vec::with_capacity(self.pools.len());
+        for pool in &self.pools {
+            let spot_output_amount = pool.price_of(&amount.currency)?.quote(&amount)?;
+            let output_amount = pool.get_output_amount(&amount, None)?;
+            let impact = spot_output_amount
+                .subtract(&output_amount)?
+                .divide(&spot_output_amount)?;
+            impacts.push(Percent::new(impact.numerator, impact.denominator));
+            amount = output_amount;
+        }
+        Ok(impacts)
+    }
+}
+
+/// The result of quoting a route exact input and then exact output with the received amount,
+/// returned by [`Route::round_trip`].
+#[derive(Clone, Debug, PartialEq)]
+pub struct RoundTripReport<TInput: BaseCurrency, TOutput: BaseCurrency> {
+    /// The amount swapped exact input.
+    pub amount_in: CurrencyAmount<TInput>,
+    /// The output of the exact input swap.
+    pub amount_out: CurrencyAmount<TOutput>,
+    /// The input required by an exact output swap of `amount_out`.
+    pub amount_in_required: CurrencyAmount<TInput>,
+    /// The difference between `amount_in_required` and `amount_in` relative to `amount_in`.
+    pub asymmetry: Percent,
+}
+
+impl<TInput: BaseCurrency, TOutput: BaseCurrency> RoundTripReport<TInput, TOutput> {
+    /// Returns whether the asymmetry is within the given tolerance
+    ///
+    /// ## Arguments
+    ///
+    /// * `tolerance`: The maximum asymmetry, e.g. a few basis points to allow for rounding
+    #[inline]
+    #[must_use]
+    pub fn is_consistent(&self, tolerance: &Percent) -> bool {
+        self.asymmetry <= *tolerance
+    }
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
     use crate::tests::*;
     use once_cell::sync::Lazy;
+    use uniswap_sdk_core::token;
 
     mod path {
         use super::*;
@@ -136,15 +521,94 @@ mod tests {
         }
 
         #[test]
-        #[should_panic(expected = "INPUT")]
+        fn summarizes_tokens_and_pools() {
+            let route = Route::new(
+                vec![POOL_0_1.clone(), POOL_1_WETH.clone()],
+                TOKEN0.clone(),
+                ETHER.clone(),
+            );
+            let summary = route.summary();
+            assert_eq!(summary.chain_id, 1);
+            assert_eq!(
+                summary
+                    .tokens
+                    .iter()
+                    .map(|token| token.address)
+                    .collect::<Vec<_>>(),
+                vec![TOKEN0.address(), TOKEN1.address(), WETH.address()]
+            );
+            assert_eq!(summary.tokens[0].symbol.as_deref(), Some("t0"));
+            assert_eq!(summary.tokens[0].decimals, 18);
+            assert_eq!(
+                summary.pools[0],
+                PoolSummary {
+                    address: POOL_0_1.address(None, None),
+                    fee: 3000,
+                    tick_spacing: 60,
+                }
+            );
+            assert!(!summary.input_is_native);
+            assert!(summary.output_is_native);
+        }
+
+        #[test]
         fn fails_if_the_input_is_not_in_the_first_pool() {
-            Route::new(vec![POOL_0_1.clone()], WETH.clone(), TOKEN1.clone());
+            let result = Route::try_new(vec![POOL_0_1.clone()], WETH.clone(), TOKEN1.clone());
+            assert!(matches!(
+                result,
+                Err(Error::PathMismatch { index: 0, token }) if token == WETH.address()
+            ));
+        }
+
+        #[test]
+        fn fails_if_the_pools_do_not_form_a_path() {
+            let result = Route::try_new(
+                vec![POOL_0_1.clone(), POOL_0_WETH.clone()],
+                TOKEN0.clone(),
+                WETH.clone(),
+            );
+            assert!(matches!(
+                result,
+                Err(Error::PathMismatch { index: 1, token }) if token == TOKEN1.address()
+            ));
+        }
+
+        #[test]
+        fn fails_if_there_are_no_pools() {
+            assert!(matches!(
+                Route::<Token, Token, NoTickDataProvider>::try_new(
+                    vec![],
+                    TOKEN0.clone(),
+                    TOKEN1.clone()
+                ),
+                Err(Error::EmptyRoute)
+            ));
         }
 
         #[test]
-        #[should_panic(expected = "OUTPUT")]
         fn fails_if_output_is_not_in_the_last_pool() {
-            Route::new(vec![POOL_0_1.clone()], TOKEN0.clone(), WETH.clone());
+            let result = Route::try_new(vec![POOL_0_1.clone()], TOKEN0.clone(), WETH.clone());
+            assert!(matches!(
+                result,
+                Err(Error::CurrencyMismatch { expected, actual })
+                    if expected == WETH.address() && actual == TOKEN1.address()
+            ));
+        }
+
+        #[test]
+        fn fails_if_the_currencies_are_on_another_chain() {
+            let result = Route::try_new(
+                vec![POOL_0_1.clone()],
+                TOKEN0.clone(),
+                token!(3, "0000000000000000000000000000000000000002", 18, "t1"),
+            );
+            assert!(matches!(
+                result,
+                Err(Error::ChainMismatch {
+                    expected: 1,
+                    actual: 3
+                })
+            ));
         }
 
         #[test]
@@ -179,6 +643,53 @@ mod tests {
         }
     }
 
+    mod canonical_hash {
+        use super::*;
+
+        #[test]
+        fn ignores_native_ends_and_pool_state() {
+            let wrapped = Route::new(vec![POOL_0_WETH.clone()], WETH.clone(), TOKEN0.clone());
+            let native = Route::new(vec![POOL_0_WETH.clone()], ETHER.clone(), TOKEN0.clone());
+            assert_eq!(wrapped.canonical_key(), native.canonical_key());
+            assert_eq!(wrapped.canonical_hash(), native.canonical_hash());
+
+            let mut pool = POOL_0_WETH.clone();
+            pool.liquidity += 1;
+            let route = Route::new(vec![pool], WETH.clone(), TOKEN0.clone());
+            assert_eq!(route.canonical_hash(), wrapped.canonical_hash());
+        }
+
+        #[test]
+        fn depends_on_direction_and_pools() {
+            let route = Route::new(
+                vec![POOL_0_1.clone(), POOL_1_WETH.clone()],
+                TOKEN0.clone(),
+                WETH.clone(),
+            );
+            let reversed = Route::new(
+                vec![POOL_1_WETH.clone(), POOL_0_1.clone()],
+                WETH.clone(),
+                TOKEN0.clone(),
+            );
+            let other = Route::new(vec![POOL_0_WETH.clone()], TOKEN0.clone(), WETH.clone());
+            assert_ne!(route.canonical_hash(), reversed.canonical_hash());
+            assert_ne!(route.canonical_hash(), other.canonical_hash());
+            assert_eq!(
+                route.canonical_key().path,
+                encode_route_to_path(&route, false)
+            );
+
+            let mut keys = vec![
+                route.canonical_key(),
+                reversed.canonical_key(),
+                route.canonical_key(),
+            ];
+            keys.sort();
+            keys.dedup();
+            assert_eq!(keys.len(), 2);
+        }
+    }
+
     mod mid_price {
         use super::*;
 
@@ -232,11 +743,12 @@ mod tests {
             assert_eq!(price.quote_currency, *TOKEN1);
         }
 
+        #[cfg(not(feature = "no-cache"))]
         #[test]
         fn is_cached() {
             let mut route = Route::new(vec![POOL_0_1.clone()], TOKEN0.clone(), TOKEN1.clone());
             let price = route.mid_price_cached().unwrap();
-            assert_eq!(price, route._mid_price.unwrap());
+            assert_eq!(price, *route._mid_price.get().unwrap());
         }
 
         #[test]
@@ -317,5 +829,205 @@ mod tests {
             assert_eq!(price.base_currency, *WETH);
             assert_eq!(price.quote_currency, *ETHER);
         }
+
+        #[test]
+        fn at_sqrt_ratios_of_the_pools_matches_mid_price() {
+            let route = Route::new(
+                vec![POOL_0_WETH.clone(), POOL_0_1.clone(), POOL_1_WETH.clone()],
+                ETHER.clone(),
+                WETH.clone(),
+            );
+            let sqrt_ratios_x96: Vec<_> =
+                route.pools.iter().map(|pool| pool.sqrt_ratio_x96).collect();
+            let price = route.mid_price_at_sqrt_ratios(&sqrt_ratios_x96).unwrap();
+            assert_eq!(price, route.mid_price().unwrap());
+            assert_eq!(
+                route
+                    .mid_price_impact_at_sqrt_ratios(&sqrt_ratios_x96)
+                    .unwrap()
+                    .numerator,
+                BigInt::ZERO
+            );
+        }
+
+        #[test]
+        fn at_hypothetical_sqrt_ratios() {
+            let route = Route::new(
+                vec![POOL_0_1.clone(), POOL_1_2.clone()],
+                TOKEN0.clone(),
+                TOKEN2.clone(),
+            );
+            // the price of token0 in token1 drops from 0.2 to 0.05
+            let sqrt_ratios_x96 = [encode_sqrt_ratio_x96(1, 20), POOL_1_2.sqrt_ratio_x96];
+            let price = route.mid_price_at_sqrt_ratios(&sqrt_ratios_x96).unwrap();
+            assert_eq!(price.to_fixed(4, None), "0.0250");
+            assert_eq!(price.base_currency, *TOKEN0);
+            assert_eq!(price.quote_currency, *TOKEN2);
+            assert_eq!(
+                route
+                    .mid_price_impact_at_sqrt_ratios(&sqrt_ratios_x96)
+                    .unwrap()
+                    .to_fixed(2, None),
+                "75.00"
+            );
+        }
+
+        #[test]
+        fn at_sqrt_ratios_requires_one_nonzero_price_per_pool() {
+            let route = Route::new(vec![POOL_0_1.clone()], TOKEN0.clone(), TOKEN1.clone());
+            assert!(matches!(
+                route.mid_price_at_sqrt_ratios(&[]),
+                Err(Error::LengthMismatch {
+                    expected: 1,
+                    actual: 0
+                })
+            ));
+            assert!(matches!(
+                route.mid_price_impact_at_sqrt_ratios(&[U160::ZERO]),
+                Err(Error::InvalidSqrtPrice(_))
+            ));
+        }
+
+        fn assert_matches_mid_price<TInput: BaseCurrency, TOutput: BaseCurrency>(
+            route: &Route<TInput, TOutput, NoTickDataProvider>,
+        ) {
+            let price = route.mid_price().unwrap();
+            let expected = &price.numerator * Q128.to_big_int() / &price.denominator;
+            let ratio = route.mid_price_ratio().unwrap().to_big_int();
+            // rounded down at every hop
+            assert!(ratio <= expected);
+            assert!(&expected - &ratio <= &expected >> 96_u32);
+        }
+
+        #[test]
+        fn ratio_matches_mid_price() {
+            assert_matches_mid_price(&Route::new(
+                vec![POOL_0_1.clone()],
+                TOKEN0.clone(),
+                TOKEN1.clone(),
+            ));
+            assert_matches_mid_price(&Route::new(
+                vec![POOL_0_1.clone()],
+                TOKEN1.clone(),
+                TOKEN0.clone(),
+            ));
+            assert_matches_mid_price(&Route::new(
+                vec![POOL_1_2.clone(), POOL_0_1.clone()],
+                TOKEN2.clone(),
+                TOKEN0.clone(),
+            ));
+            assert_matches_mid_price(&Route::new(
+                vec![POOL_0_WETH.clone(), POOL_0_1.clone(), POOL_1_WETH.clone()],
+                ETHER.clone(),
+                WETH.clone(),
+            ));
+        }
+
+        #[test]
+        fn cmp_mid_price() {
+            let pool_0_2 = Pool::new(
+                TOKEN0.clone(),
+                TOKEN2.clone(),
+                FeeAmount::MEDIUM,
+                encode_sqrt_ratio_x96(1, 8),
+                0,
+            )
+            .unwrap();
+            let direct = Route::new(vec![pool_0_2], TOKEN0.clone(), TOKEN2.clone());
+            let multihop = Route::new(
+                vec![POOL_0_1.clone(), POOL_1_2.clone()],
+                TOKEN0.clone(),
+                TOKEN2.clone(),
+            );
+            // 0.125 vs 0.1
+            assert_eq!(direct.cmp_mid_price(&multihop).unwrap(), Ordering::Greater);
+            assert_eq!(multihop.cmp_mid_price(&direct).unwrap(), Ordering::Less);
+            assert_eq!(direct.cmp_mid_price(&direct).unwrap(), Ordering::Equal);
+        }
+    }
+
+    mod round_trip {
+        use super::*;
+
+        #[test]
+        fn consistent_for_healthy_pools() {
+            let route = Route::new(
+                vec![
+                    make_pool(TOKEN0.clone(), TOKEN1.clone()),
+                    make_pool(TOKEN1.clone(), TOKEN2.clone()),
+                ],
+                TOKEN0.clone(),
+                TOKEN2.clone(),
+            );
+            let amount_in = CurrencyAmount::from_raw_amount(TOKEN0.clone(), 10000).unwrap();
+            let report = route.round_trip(amount_in.clone()).unwrap();
+            assert_eq!(report.amount_in, amount_in);
+            assert!(report.amount_out.quotient() > BigInt::ZERO);
+            assert!(report.is_consistent(&Percent::new(1, 1000)));
+        }
+
+        #[test]
+        fn rejects_zero_amount() {
+            let route = Route::new(
+                vec![make_pool(TOKEN0.clone(), TOKEN1.clone())],
+                TOKEN0.clone(),
+                TOKEN1.clone(),
+            );
+            let amount_in = CurrencyAmount::from_raw_amount(TOKEN0.clone(), 0).unwrap();
+            assert!(matches!(
+                route.round_trip(amount_in),
+                Err(Error::ZeroAmount)
+            ));
+        }
+    }
+
+    mod hop_price_impacts {
+        use super::*;
+
+        #[test]
+        fn compose_to_trade_price_impact() {
+            let route = Route::new(
+                vec![
+                    make_pool(TOKEN0.clone(), TOKEN1.clone()),
+                    make_pool(TOKEN1.clone(), TOKEN2.clone()),
+                ],
+                TOKEN0.clone(),
+                TOKEN2.clone(),
+            );
+            let amount_in = CurrencyAmount::from_raw_amount(TOKEN0.clone(), 10_000).unwrap();
+            let impacts = route.hop_price_impacts(&amount_in).unwrap();
+            assert_eq!(impacts.len(), 2);
+            assert!(impacts.iter().all(|impact| impact.numerator > BigInt::ZERO));
+
+            let trade = Trade::from_route(route, amount_in, TradeType::ExactInput).unwrap();
+            let total = trade.price_impact().unwrap();
+            let (numerator, denominator) = impacts.iter().fold(
+                (BigInt::from(1), BigInt::from(1)),
+                |(numerator, denominator), impact| {
+                    (
+                        numerator * (&impact.denominator - &impact.numerator),
+                        denominator * &impact.denominator,
+                    )
+                },
+            );
+            assert_eq!(
+                numerator * &total.denominator,
+                denominator * (&total.denominator - &total.numerator)
+            );
+        }
+
+        #[test]
+        fn rejects_other_input() {
+            let route = Route::new(
+                vec![make_pool(TOKEN0.clone(), TOKEN1.clone())],
+                TOKEN0.clone(),
+                TOKEN1.clone(),
+            );
+            let amount_in = CurrencyAmount::from_raw_amount(TOKEN1.clone(), 10_000).unwrap();
+            assert!(matches!(
+                route.hop_price_impacts(&amount_in),
+                Err(Error::CurrencyMismatch { .. })
+            ));
+        }
     }
 }
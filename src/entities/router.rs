@@ -0,0 +1,870 @@
+use crate::prelude::{Error, *};
+use alloc::{vec, vec::Vec};
+use alloy_primitives::{aliases::U24, Address, U160};
+use core::cmp::Ordering;
+use num_traits::Zero;
+use uniswap_sdk_core::prelude::*;
+
+/// Options for searching the best trade split across several routes.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct SplitTradeOptions {
+    /// The granularity of the split in percent of the amount, e.g. 5 to allocate the amount in
+    /// 5% steps.
+    pub distribution_percent: u8,
+    /// The maximum number of routes the amount is split across.
+    pub max_splits: usize,
+    /// The maximum number of candidate routes considered.
+    pub max_routes: usize,
+    /// The maximum number of hops a route should contain.
+    pub max_hops: usize,
+}
+
+impl Default for SplitTradeOptions {
+    #[inline]
+    fn default() -> Self {
+        Self {
+            distribution_percent: 5,
+            max_splits: 3,
+            max_routes: 10,
+            max_hops: 3,
+        }
+    }
+}
+
+impl SplitTradeOptions {
+    /// Checks that the distribution percent is between 1 and 100 and that the amount can be split
+    #[inline]
+    const fn validate(&self) -> Result<(), Error> {
+        if self.distribution_percent == 0 || self.distribution_percent > 100 || self.max_splits == 0
+        {
+            Err(Error::InvalidSplit)
+        } else {
+            Ok(())
+        }
+    }
+}
+
+/// Returns the amount out of a route for an amount in, or the amount in for an amount out if
+/// `exact_output`, `None` if the route lacks the liquidity
+fn quote_route<TP: Clone + TickDataProvider>(
+    pools: &[Pool<TP>],
+    token: &Token,
+    amount: &BigInt,
+    exact_output: bool,
+) -> Result<Option<BigInt>, Error> {
+    let mut amount = CurrencyAmount::from_raw_amount(token.clone(), amount.clone())?;
+    let quote = |pool: &Pool<TP>, amount: &CurrencyAmount<Token>| {
+        if exact_output {
+            pool.get_input_amount(amount, None)
+        } else {
+            pool.get_output_amount(amount, None)
+        }
+    };
+    if exact_output {
+        for pool in pools.iter().rev() {
+            amount = match quote(pool, &amount) {
+                Ok(amount) => amount,
+                Err(e) if matches!(e.root(), Error::InsufficientLiquidity) => return Ok(None),
+                Err(e) => return Err(e),
+            };
+        }
+    } else {
+        for pool in pools {
+            amount = match quote(pool, &amount) {
+                Ok(amount) => amount,
+                Err(e) if matches!(e.root(), Error::InsufficientLiquidity) => return Ok(None),
+                Err(e) => return Err(e),
+            };
+        }
+    }
+    Ok(Some(amount.quotient()))
+}
+
+/// Allocates the amount across the routes in steps, each step going to the route with the best
+/// marginal quote, i.e. the largest increase of the output for exact input or the smallest
+/// increase of the input for exact output
+///
+/// Routes sharing a pool are never both allocated, so that the quotes of the routes are
+/// independent and the resulting trade executes as quoted.
+fn split_amount<TInput, TOutput, TP>(
+    routes: &[Route<TInput, TOutput, TP>],
+    amount: &BigInt,
+    options: SplitTradeOptions,
+    exact_output: bool,
+) -> Result<Vec<(usize, BigInt)>, Error>
+where
+    TInput: BaseCurrency,
+    TOutput: BaseCurrency,
+    TP: Clone + TickDataProvider,
+{
+    let pool_addresses: Vec<Vec<Address>> = routes
+        .iter()
+        .map(|route| {
+            route
+                .pools
+                .iter()
+                .map(|pool| pool.address(None, None))
+                .collect()
+        })
+        .collect();
+    let parts = 100 / options.distribution_percent as u32;
+    let mut allocated = vec![BigInt::ZERO; routes.len()];
+    let mut quotes = vec![BigInt::ZERO; routes.len()];
+    let mut splits = 0;
+    for part in 0..parts {
+        let step = amount * (part + 1) / parts - amount * part / parts;
+        if step.is_zero() {
+            continue;
+        }
+        let mut best: Option<(usize, BigInt, BigInt)> = None;
+        for (i, route) in routes.iter().enumerate() {
+            if allocated[i].is_zero() {
+                let shares_pool = allocated.iter().enumerate().any(|(j, amount)| {
+                    !amount.is_zero()
+                        && pool_addresses[j]
+                            .iter()
+                            .any(|address| pool_addresses[i].contains(address))
+                });
+                if splits == options.max_splits || shares_pool {
+                    continue;
+                }
+            }
+            let token = if exact_output {
+                route.output.wrapped()
+            } else {
+                route.input.wrapped()
+            };
+            let Some(quote) =
+                quote_route(&route.pools, token, &(&allocated[i] + &step), exact_output)?
+            else {
+                continue;
+            };
+            let marginal = &quote - &quotes[i];
+            let is_better = best.as_ref().is_none_or(|(_, _, best)| {
+                if exact_output {
+                    marginal < *best
+                } else {
+                    marginal > *best
+                }
+            });
+            if is_better {
+                best = Some((i, quote, marginal));
+            }
+        }
+        let (i, quote, _) = best.ok_or(Error::InsufficientLiquidity)?;
+        if allocated[i].is_zero() {
+            splits += 1;
+        }
+        allocated[i] += step;
+        quotes[i] = quote;
+    }
+    Ok(allocated
+        .into_iter()
+        .enumerate()
+        .filter(|(_, amount)| !amount.is_zero())
+        .collect())
+}
+
+/// Returns the marginal rate of a route after swapping an exact amount out along it, i.e. the
+/// output per unit of input net of the fees at the prices the pools are left at, as a fraction,
+/// `None` if the route lacks the liquidity
+fn marginal_rate_after_output<TInput, TOutput, TP>(
+    route: &Route<TInput, TOutput, TP>,
+    amount_out: &BigInt,
+) -> Result<Option<(BigInt, BigInt)>, Error>
+where
+    TInput: BaseCurrency,
+    TOutput: BaseCurrency,
+    TP: TickDataProvider,
+{
+    let mut sqrt_ratios_x96 = vec![U160::ZERO; route.pools.len()];
+    let mut amount =
+        CurrencyAmount::from_raw_amount(route.output.wrapped().clone(), amount_out.clone())?;
+    let mut numerator = BigInt::from(1);
+    let mut denominator = BigInt::from(1);
+    for (i, pool) in route.pools.iter().enumerate().rev() {
+        let quote = match pool.quote_input(&amount, None) {
+            Ok(quote) => quote,
+            Err(e) if matches!(e.root(), Error::InsufficientLiquidity) => return Ok(None),
+            Err(e) => return Err(e),
+        };
+        sqrt_ratios_x96[i] = quote.sqrt_price_x96_after;
+        amount = quote.amount;
+        let fee: U24 = pool.fee.into();
+        numerator *= 1_000_000 - fee.to::<u32>();
+        denominator *= 1_000_000_u32;
+    }
+    let price = route.mid_price_at_sqrt_ratios(&sqrt_ratios_x96)?;
+    Ok(Some((
+        numerator * &price.numerator,
+        denominator * &price.denominator,
+    )))
+}
+
+/// Returns the largest amount out of a route, up to `amount_max`, at which the marginal rate of the
+/// route still meets the threshold, by binary search since the rate decreases with the amount
+fn amount_out_at_rate<TInput, TOutput, TP>(
+    route: &Route<TInput, TOutput, TP>,
+    amount_max: &BigInt,
+    meets: impl Fn(&(BigInt, BigInt)) -> bool,
+) -> Result<BigInt, Error>
+where
+    TInput: BaseCurrency,
+    TOutput: BaseCurrency,
+    TP: TickDataProvider,
+{
+    let meets_at = |amount: &BigInt| -> Result<bool, Error> {
+        Ok(marginal_rate_after_output(route, amount)?.is_some_and(|rate| meets(&rate)))
+    };
+    if !meets_at(&BigInt::ZERO)? {
+        return Ok(BigInt::ZERO);
+    }
+    if meets_at(amount_max)? {
+        return Ok(amount_max.clone());
+    }
+    let (mut lower, mut upper) = (BigInt::ZERO, amount_max.clone());
+    while &upper - &lower > BigInt::from(1) {
+        let amount = (&lower + &upper) >> 1;
+        if meets_at(&amount)? {
+            lower = amount;
+        } else {
+            upper = amount;
+        }
+    }
+    Ok(lower)
+}
+
+impl<TInput, TOutput, TP> Trade<TInput, TOutput, TP>
+where
+    TInput: BaseCurrency,
+    TOutput: BaseCurrency,
+    TP: Clone + TickDataProvider,
+{
+    /// Given a list of pools and a fixed amount in, returns the trade with the largest output
+    /// splitting the amount across up to `max_splits` routes of pairwise distinct pools.
+    ///
+    /// The candidate routes are the best [`Trade::best_trade_exact_in`] routes for a single step of
+    /// the split, and the amount is allocated greedily step by step to the route with the largest
+    /// marginal output.
+    ///
+    /// ## Arguments
+    ///
+    /// * `pools`: The pools to consider in finding the best trade
+    /// * `currency_amount_in`: The exact amount of input currency to spend
+    /// * `currency_out`: The desired currency out
+    /// * `options`: The granularity of the split and the limits on the routes
+    #[inline]
+    pub fn best_split_trade_exact_in(
+        pools: Vec<Pool<TP>>,
+        currency_amount_in: &CurrencyAmount<TInput>,
+        currency_out: &TOutput,
+        options: SplitTradeOptions,
+    ) -> Result<Self, Error> {
+        options.validate()?;
+        let amount = currency_amount_in.quotient();
+        let step = CurrencyAmount::from_raw_amount(
+            currency_amount_in.currency.clone(),
+            (&amount * u32::from(options.distribution_percent) / 100_u32).max(BigInt::from(1)),
+        )?;
+        let mut candidates = Vec::new();
+        Self::best_trade_exact_in(
+            pools,
+            &step,
+            currency_out,
+            BestTradeOptions {
+                max_num_results: Some(options.max_routes),
+                max_hops: Some(options.max_hops),
+                ..Default::default()
+            },
+            vec![],
+            None,
+            &mut candidates,
+        )?;
+        let routes: Vec<_> = candidates
+            .into_iter()
+            .filter_map(|trade| trade.swaps.into_iter().next())
+            .map(|swap| swap.route)
+            .collect();
+        let splits = split_amount(&routes, &amount, options, false)?;
+        Self::from_routes(
+            splits
+                .into_iter()
+                .map(|(i, amount)| {
+                    Ok((
+                        CurrencyAmount::from_raw_amount(
+                            currency_amount_in.currency.clone(),
+                            amount,
+                        )?,
+                        routes[i].clone(),
+                    ))
+                })
+                .collect::<Result<_, Error>>()?,
+            TradeType::ExactInput,
+        )
+    }
+
+    /// Splits an exact amount out across the given routes so that their marginal prices after the
+    /// swaps are equal, which minimizes the total amount in, e.g. to pass to
+    /// [`Trade::from_routes`] after dropping the empty allocations.
+    ///
+    /// The marginal price of a route is derived from the prices its pools are left at by the
+    /// swap, simulated with their tick data, and the common marginal price is found by bisection.
+    /// The routes must not share pools, since each is simulated against the initial state of its
+    /// pools.
+    ///
+    /// ## Arguments
+    ///
+    /// * `routes`: The routes to split the amount across
+    /// * `total_amount_out`: The exact amount of output currency to receive
+    ///
+    /// ## Returns
+    ///
+    /// The amount out of each route, in the order of `routes`
+    #[inline]
+    pub fn split_exact_output(
+        routes: &[Route<TInput, TOutput, TP>],
+        total_amount_out: &CurrencyAmount<TOutput>,
+    ) -> Result<Vec<CurrencyAmount<TOutput>>, Error> {
+        if routes.is_empty() {
+            return Err(Error::NoTrades);
+        }
+        let total = total_amount_out.quotient();
+        // the best marginal rate before the swaps, from which the thresholds are scaled down
+        let mut best = (BigInt::ZERO, BigInt::from(1));
+        for route in routes {
+            let rate = marginal_rate_after_output(route, &BigInt::ZERO)?
+                .ok_or(Error::InsufficientLiquidity)?;
+            if &rate.0 * &best.1 > &best.0 * &rate.1 {
+                best = rate;
+            }
+        }
+        let scale = BigInt::from(1) << 64;
+        // the amounts of the routes whose marginal rate stays at least `best * threshold / scale`
+        let allocate = |threshold: &BigInt| -> Result<Vec<BigInt>, Error> {
+            routes
+                .iter()
+                .map(|route| {
+                    amount_out_at_rate(route, &total, |(numerator, denominator)| {
+                        numerator * &best.1 * &scale >= &best.0 * threshold * denominator
+                    })
+                })
+                .collect()
+        };
+        let sum = |amounts: &[BigInt]| amounts.iter().sum::<BigInt>();
+
+        // the allocation grows as the threshold decreases, so bisect between an allocation short
+        // of the total and one exceeding it
+        let mut upper = allocate(&scale)?;
+        let (base, cap) = if sum(&upper) >= total {
+            (vec![BigInt::ZERO; routes.len()], upper)
+        } else {
+            let mut lower = allocate(&BigInt::ZERO)?;
+            if sum(&lower) < total {
+                return Err(Error::InsufficientLiquidity);
+            }
+            let (mut threshold_lower, mut threshold_upper) = (BigInt::ZERO, scale.clone());
+            while &threshold_upper - &threshold_lower > BigInt::from(1) {
+                let threshold = (&threshold_lower + &threshold_upper) >> 1;
+                let amounts = allocate(&threshold)?;
+                match sum(&amounts).cmp(&total) {
+                    Ordering::Less => {
+                        threshold_upper = threshold;
+                        upper = amounts;
+                    }
+                    Ordering::Equal => {
+                        lower.clone_from(&amounts);
+                        upper = amounts;
+                        break;
+                    }
+                    Ordering::Greater => {
+                        threshold_lower = threshold;
+                        lower = amounts;
+                    }
+                }
+            }
+            (upper, lower)
+        };
+
+        // the remainder goes to the routes that can take more at the marginal price
+        let mut remaining = &total - sum(&base);
+        base.into_iter()
+            .zip(cap)
+            .map(|(amount, cap)| {
+                let extra = (cap - &amount).min(remaining.clone());
+                remaining -= &extra;
+                CurrencyAmount::from_raw_amount(total_amount_out.currency.clone(), amount + extra)
+                    .map_err(Into::into)
+            })
+            .collect()
+    }
+
+    /// Given a list of pools and a fixed amount out, returns the trade with the smallest input
+    /// splitting the amount across up to `max_splits` routes of pairwise distinct pools.
+    ///
+    /// The candidate routes are the best [`Trade::best_trade_exact_out`] routes for a single step
+    /// of the split, and the amount is allocated greedily step by step to the route with the
+    /// smallest marginal input.
+    ///
+    /// ## Arguments
+    ///
+    /// * `pools`: The pools to consider in finding the best trade
+    /// * `currency_in`: The currency to spend
+    /// * `currency_amount_out`: The desired currency amount out
+    /// * `options`: The granularity of the split and the limits on the routes
+    #[inline]
+    pub fn best_split_trade_exact_out(
+        pools: Vec<Pool<TP>>,
+        currency_in: &TInput,
+        currency_amount_out: &CurrencyAmount<TOutput>,
+        options: SplitTradeOptions,
+    ) -> Result<Self, Error> {
+        options.validate()?;
+        let amount = currency_amount_out.quotient();
+        let step = CurrencyAmount::from_raw_amount(
+            currency_amount_out.currency.clone(),
+            (&amount * u32::from(options.distribution_percent) / 100_u32).max(BigInt::from(1)),
+        )?;
+        let mut candidates = Vec::new();
+        Self::best_trade_exact_out(
+            pools,
+            currency_in,
+            &step,
+            BestTradeOptions {
+                max_num_results: Some(options.max_routes),
+                max_hops: Some(options.max_hops),
+                ..Default::default()
+            },
+            vec![],
+            None,
+            &mut candidates,
+        )?;
+        let routes: Vec<_> = candidates
+            .into_iter()
+            .filter_map(|trade| trade.swaps.into_iter().next())
+            .map(|swap| swap.route)
+            .collect();
+        let splits = split_amount(&routes, &amount, options, true)?;
+        Self::from_routes(
+            splits
+                .into_iter()
+                .map(|(i, amount)| {
+                    Ok((
+                        CurrencyAmount::from_raw_amount(
+                            currency_amount_out.currency.clone(),
+                            amount,
+                        )?,
+                        routes[i].clone(),
+                    ))
+                })
+                .collect::<Result<_, Error>>()?,
+            TradeType::ExactOutput,
+        )
+    }
+}
+
+/// A source of quotes outside the pools, e.g. an RFQ market maker or another venue, compared
+/// against the pools for every slice of a trade by [`plan_slices`].
+///
+/// Implemented by any closure with the signature of [`ExternalQuoter::quote_exact_input`].
+pub trait ExternalQuoter<TInput: BaseCurrency, TOutput: BaseCurrency> {
+    /// Returns the amount out offered for a slice, `None` if the source does not quote it
+    ///
+    /// ## Arguments
+    ///
+    /// * `amount_in`: The amount in of the slice
+    /// * `filled_in`: The amount in of the slices already routed to the source, for sources whose
+    ///   price depends on the size
+    fn quote_exact_input(
+        &self,
+        amount_in: &CurrencyAmount<TInput>,
+        filled_in: &CurrencyAmount<TInput>,
+    ) -> Option<CurrencyAmount<TOutput>>;
+}
+
+impl<TInput, TOutput, F> ExternalQuoter<TInput, TOutput> for F
+where
+    TInput: BaseCurrency,
+    TOutput: BaseCurrency,
+    F: Fn(&CurrencyAmount<TInput>, &CurrencyAmount<TInput>) -> Option<CurrencyAmount<TOutput>>,
+{
+    #[inline]
+    fn quote_exact_input(
+        &self,
+        amount_in: &CurrencyAmount<TInput>,
+        filled_in: &CurrencyAmount<TInput>,
+    ) -> Option<CurrencyAmount<TOutput>> {
+        self(amount_in, filled_in)
+    }
+}
+
+/// Where a slice of a trade is executed, see [`plan_slices`].
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+pub enum Venue {
+    /// The pools of the route.
+    Pool,
+    /// The [`ExternalQuoter`], e.g. an RFQ market maker.
+    External,
+}
+
+/// A slice of a trade and the venue chosen for it.
+#[derive(Clone, Debug, PartialEq)]
+pub struct TradeSlice<TInput: BaseCurrency, TOutput: BaseCurrency> {
+    /// The venue the slice is executed at.
+    pub venue: Venue,
+    /// The amount in of the slice.
+    pub amount_in: CurrencyAmount<TInput>,
+    /// The amount out of the pools after the slices executed by them before, `None` if they lack
+    /// the liquidity.
+    pub pool_amount_out: Option<CurrencyAmount<TOutput>>,
+    /// The amount out quoted by the external source, `None` if it did not quote the slice.
+    pub external_amount_out: Option<CurrencyAmount<TOutput>>,
+}
+
+impl<TInput: BaseCurrency, TOutput: BaseCurrency> TradeSlice<TInput, TOutput> {
+    /// The amount out of the slice at the chosen venue, [`Error::InsufficientLiquidity`] if the
+    /// venue has no amount out for it
+    #[inline]
+    pub fn amount_out(&self) -> Result<&CurrencyAmount<TOutput>, Error> {
+        match self.venue {
+            Venue::Pool => self.pool_amount_out.as_ref(),
+            Venue::External => self.external_amount_out.as_ref(),
+        }
+        .ok_or(Error::InsufficientLiquidity)
+    }
+}
+
+/// The slices of a trade split between the pools and an external source by [`plan_slices`].
+#[derive(Clone, Debug, PartialEq)]
+pub struct SlicePlan<TInput: BaseCurrency, TOutput: BaseCurrency> {
+    /// The slices in the order they were planned.
+    pub slices: Vec<TradeSlice<TInput, TOutput>>,
+}
+
+impl<TInput: BaseCurrency, TOutput: BaseCurrency> SlicePlan<TInput, TOutput> {
+    /// The total amount in of the slices executed by a venue, e.g. to build the pool side with
+    /// [`Trade::from_route`]
+    ///
+    /// ## Arguments
+    ///
+    /// * `venue`: The venue
+    #[inline]
+    #[must_use]
+    pub fn amount_in(&self, venue: Venue) -> BigInt {
+        self.slices
+            .iter()
+            .filter(|slice| slice.venue == venue)
+            .map(|slice| slice.amount_in.quotient())
+            .sum()
+    }
+
+    /// The total amount out of the slices executed by a venue
+    ///
+    /// ## Arguments
+    ///
+    /// * `venue`: The venue
+    #[inline]
+    pub fn amount_out(&self, venue: Venue) -> Result<BigInt, Error> {
+        self.slices
+            .iter()
+            .filter(|slice| slice.venue == venue)
+            .map(|slice| slice.amount_out().map(CurrencyAmount::quotient))
+            .sum()
+    }
+}
+
+/// Swaps an exact amount in through the pools, updating their state, and returns the amount out,
+/// `None` if the pools lack the liquidity
+fn swap_exact_input<TP: Clone + TickDataProvider>(
+    pools: &mut [Pool<TP>],
+    token: &Token,
+    amount: BigInt,
+) -> Result<Option<BigInt>, Error> {
+    let mut amount = CurrencyAmount::from_raw_amount(token.clone(), amount)?;
+    for pool in pools {
+        amount = match pool.get_output_amount_mut(&amount, None) {
+            Ok(amount) => amount,
+            Err(e) if matches!(e.root(), Error::InsufficientLiquidity) => return Ok(None),
+            Err(e) => return Err(e),
+        };
+    }
+    Ok(Some(amount.quotient()))
+}
+
+/// Splits an exact amount in into equal slices and executes each at the venue with the larger
+/// output, the pools of the route or an external quote, e.g. from an RFQ market maker
+///
+/// The pools are simulated slice after slice, so that their output reflects the price impact of
+/// the slices routed to them before, and the remaining slices fall back to the pools whenever the
+/// external source does not quote them.
+///
+/// ## Arguments
+///
+/// * `route`: The route of the pool side
+/// * `amount_in`: The exact amount in of the trade
+/// * `slices`: The number of slices, at least one
+/// * `quoter`: The external source
+#[inline]
+pub fn plan_slices<TInput, TOutput, TP>(
+    route: &Route<TInput, TOutput, TP>,
+    amount_in: &CurrencyAmount<TInput>,
+    slices: usize,
+    quoter: &impl ExternalQuoter<TInput, TOutput>,
+) -> Result<SlicePlan<TInput, TOutput>, Error>
+where
+    TInput: BaseCurrency,
+    TOutput: BaseCurrency,
+    TP: Clone + TickDataProvider,
+{
+    if slices == 0 {
+        return Err(Error::InvalidSplit);
+    }
+    let amount = amount_in.quotient();
+    let mut pools = route.pools.clone();
+    let mut filled_in = CurrencyAmount::from_raw_amount(amount_in.currency.clone(), 0)?;
+    let mut planned = Vec::with_capacity(slices);
+    for i in 0..slices {
+        let slice_amount = &amount * (i + 1) / slices - &amount * i / slices;
+        let slice_in = CurrencyAmount::from_raw_amount(amount_in.currency.clone(), slice_amount)?;
+
+        // simulate the slice on copies of the pools, kept only if the pools execute it
+        let mut next_pools = pools.clone();
+        let pool_amount_out =
+            match swap_exact_input(&mut next_pools, route.input.wrapped(), slice_in.quotient())? {
+                Some(amount) => Some(CurrencyAmount::from_raw_amount(
+                    route.output.clone(),
+                    amount,
+                )?),
+                None => None,
+            };
+        let external_amount_out = quoter.quote_exact_input(&slice_in, &filled_in);
+
+        let venue = match (&pool_amount_out, &external_amount_out) {
+            (Some(pool), Some(external)) if external.quotient() > pool.quotient() => {
+                Venue::External
+            }
+            (Some(_), _) => Venue::Pool,
+            (None, Some(_)) => Venue::External,
+            (None, None) => return Err(Error::InsufficientLiquidity),
+        };
+        match venue {
+            Venue::Pool => pools = next_pools,
+            Venue::External => filled_in = filled_in.add(&slice_in)?,
+        }
+        planned.push(TradeSlice {
+            venue,
+            amount_in: slice_in,
+            pool_amount_out,
+            external_amount_out,
+        });
+    }
+    Ok(SlicePlan { slices: planned })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::tests::*;
+
+    fn pools() -> Vec<Pool<TickListDataProvider>> {
+        vec![
+            make_pool(TOKEN0.clone(), TOKEN2.clone()),
+            make_pool(TOKEN0.clone(), TOKEN1.clone()),
+            make_pool(TOKEN1.clone(), TOKEN2.clone()),
+        ]
+    }
+
+    #[test]
+    fn splits_large_exact_input_across_routes() {
+        let amount_in = CurrencyAmount::from_raw_amount(TOKEN0.clone(), 1_000_000).unwrap();
+        let split = Trade::best_split_trade_exact_in(
+            pools(),
+            &amount_in,
+            &*TOKEN2,
+            SplitTradeOptions::default(),
+        )
+        .unwrap();
+        assert_eq!(split.swaps.len(), 2);
+        assert_eq!(split.input_amount().unwrap(), amount_in);
+
+        let mut linear = Vec::new();
+        Trade::best_trade_exact_in(
+            pools(),
+            &amount_in,
+            &*TOKEN2,
+            BestTradeOptions::default(),
+            vec![],
+            None,
+            &mut linear,
+        )
+        .unwrap();
+        assert!(
+            split.output_amount().unwrap().quotient()
+                > linear[0].output_amount().unwrap().quotient()
+        );
+    }
+
+    #[test]
+    fn keeps_small_exact_input_on_one_route() {
+        let amount_in = CurrencyAmount::from_raw_amount(TOKEN0.clone(), 100).unwrap();
+        let split = Trade::best_split_trade_exact_in(
+            pools(),
+            &amount_in,
+            &*TOKEN2,
+            SplitTradeOptions::default(),
+        )
+        .unwrap();
+        assert_eq!(split.swaps.len(), 1);
+        assert_eq!(split.swaps[0].route.pools.len(), 1);
+    }
+
+    #[test]
+    fn splits_large_exact_output_across_routes() {
+        let amount_out = CurrencyAmount::from_raw_amount(TOKEN2.clone(), 1_000_000).unwrap();
+        let split = Trade::best_split_trade_exact_out(
+            pools(),
+            &*TOKEN0,
+            &amount_out,
+            SplitTradeOptions::default(),
+        )
+        .unwrap();
+        assert_eq!(split.swaps.len(), 2);
+        assert_eq!(split.output_amount().unwrap(), amount_out);
+    }
+
+    #[test]
+    fn rejects_invalid_split_options() {
+        let amount_in = CurrencyAmount::from_raw_amount(TOKEN0.clone(), 100).unwrap();
+        for options in [
+            SplitTradeOptions {
+                distribution_percent: 0,
+                ..Default::default()
+            },
+            SplitTradeOptions {
+                distribution_percent: 101,
+                ..Default::default()
+            },
+            SplitTradeOptions {
+                max_splits: 0,
+                ..Default::default()
+            },
+        ] {
+            assert!(matches!(
+                Trade::best_split_trade_exact_in(pools(), &amount_in, &*TOKEN2, options),
+                Err(Error::InvalidSplit)
+            ));
+        }
+        let amount_out = CurrencyAmount::from_raw_amount(TOKEN2.clone(), 100).unwrap();
+        let routes: [Route<Token, Token, TickListDataProvider>; 0] = [];
+        assert!(matches!(
+            Trade::split_exact_output(&routes, &amount_out),
+            Err(Error::NoTrades)
+        ));
+    }
+
+    #[test]
+    fn splits_exact_output_at_equal_marginal_prices() {
+        let [pool02, pool01, pool12]: [_; 3] = pools().try_into().unwrap();
+        let routes = [
+            Route::new(vec![pool02], TOKEN0.clone(), TOKEN2.clone()),
+            Route::new(vec![pool01, pool12], TOKEN0.clone(), TOKEN2.clone()),
+        ];
+        let amount_out = CurrencyAmount::from_raw_amount(TOKEN2.clone(), 100_000).unwrap();
+        let amounts = Trade::split_exact_output(&routes, &amount_out).unwrap();
+        let (direct, two_hops) = (amounts[0].quotient(), amounts[1].quotient());
+        assert_eq!(&direct + &two_hops, amount_out.quotient());
+        // the direct route pays a single fee so it takes the larger share
+        assert!(direct > two_hops && two_hops > BigInt::ZERO);
+
+        let amount_in = |direct: &BigInt, two_hops: &BigInt| {
+            Trade::from_routes(
+                vec![
+                    (
+                        CurrencyAmount::from_raw_amount(TOKEN2.clone(), direct.clone()).unwrap(),
+                        routes[0].clone(),
+                    ),
+                    (
+                        CurrencyAmount::from_raw_amount(TOKEN2.clone(), two_hops.clone()).unwrap(),
+                        routes[1].clone(),
+                    ),
+                ],
+                TradeType::ExactOutput,
+            )
+            .unwrap()
+            .input_amount()
+            .unwrap()
+            .quotient()
+        };
+        let optimal = amount_in(&direct, &two_hops);
+        // shifting part of the amount to either route costs more
+        let shift = BigInt::from(5000);
+        assert!(optimal < amount_in(&(&direct - &shift), &(&two_hops + &shift)));
+        assert!(optimal < amount_in(&(&direct + &shift), &(&two_hops - &shift)));
+    }
+
+    #[test]
+    fn plans_slices_between_pools_and_external_quotes() {
+        let route = Route::new(
+            vec![make_pool(TOKEN0.clone(), TOKEN1.clone())],
+            TOKEN0.clone(),
+            TOKEN1.clone(),
+        );
+        let amount_in = CurrencyAmount::from_raw_amount(TOKEN0.clone(), 100_000).unwrap();
+        // a market maker filling at 98% of the input
+        let rfq = |amount_in: &CurrencyAmount<Token>, _: &CurrencyAmount<Token>| {
+            CurrencyAmount::from_raw_amount(TOKEN1.clone(), amount_in.quotient() * 98 / 100).ok()
+        };
+        let plan = plan_slices(&route, &amount_in, 10, &rfq).unwrap();
+        assert_eq!(plan.slices.len(), 10);
+        // the first slice gets 9871 out of the pool, the second only 9678 after its impact
+        assert_eq!(plan.slices[0].venue, Venue::Pool);
+        assert!(plan.slices[1..]
+            .iter()
+            .all(|slice| slice.venue == Venue::External));
+        assert_eq!(plan.amount_in(Venue::Pool), BigInt::from(10_000));
+        assert_eq!(plan.amount_in(Venue::External), BigInt::from(90_000));
+        assert_eq!(
+            plan.amount_out(Venue::External).unwrap(),
+            BigInt::from(88_200)
+        );
+
+        // the pools take the slices the market maker does not quote
+        let capped = |amount_in: &CurrencyAmount<Token>, filled_in: &CurrencyAmount<Token>| {
+            if filled_in.quotient() < BigInt::from(20_000) {
+                rfq(amount_in, filled_in)
+            } else {
+                None
+            }
+        };
+        let plan = plan_slices(&route, &amount_in, 10, &capped).unwrap();
+        assert_eq!(plan.amount_in(Venue::External), BigInt::from(20_000));
+        assert_eq!(plan.amount_in(Venue::Pool), BigInt::from(80_000));
+
+        assert!(matches!(
+            plan_slices(&route, &amount_in, 0, &rfq),
+            Err(Error::InvalidSplit)
+        ));
+        let mut slice = plan.slices[0].clone();
+        slice.pool_amount_out = None;
+        slice.venue = Venue::Pool;
+        assert!(matches!(
+            slice.amount_out(),
+            Err(Error::InsufficientLiquidity)
+        ));
+    }
+
+    #[test]
+    fn respects_max_splits() {
+        let amount_in = CurrencyAmount::from_raw_amount(TOKEN0.clone(), 1_000_000).unwrap();
+        let split = Trade::best_split_trade_exact_in(
+            pools(),
+            &amount_in,
+            &*TOKEN2,
+            SplitTradeOptions {
+                max_splits: 1,
+                ..Default::default()
+            },
+        )
+        .unwrap();
+        assert_eq!(split.swaps.len(), 1);
+    }
+}
@@ -0,0 +1,116 @@
+use crate::prelude::*;
+use core::ops::Deref;
+
+/// A data provider for ticks that is backed by a fixed-size array of ticks.
+///
+/// Unlike [`TickListDataProvider`], it never allocates, so a pool using it can be built and
+/// swapped against in environments where heap usage matters, e.g. embedded, wasm or zkVM guests.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct StaticTickDataProvider<const N: usize, I = i32>([Tick<I>; N]);
+
+impl<const N: usize, I: TickIndex> StaticTickDataProvider<N, I> {
+    /// Creates a provider from a sorted array of initialized ticks
+    ///
+    /// ## Arguments
+    ///
+    /// * `ticks`: The sorted array of initialized ticks
+    /// * `tick_spacing`: The tick spacing of the pool
+    #[inline]
+    pub fn new(ticks: [Tick<I>; N], tick_spacing: I) -> Self {
+        ticks.validate_list(tick_spacing);
+        Self(ticks)
+    }
+
+    /// Returns the array of ticks
+    #[inline]
+    #[must_use]
+    pub const fn ticks(&self) -> &[Tick<I>; N] {
+        &self.0
+    }
+
+    /// Consumes the provider and returns the array of ticks
+    #[inline]
+    #[must_use]
+    pub const fn into_inner(self) -> [Tick<I>; N] {
+        self.0
+    }
+}
+
+impl<const N: usize, I> Deref for StaticTickDataProvider<N, I> {
+    type Target = [Tick<I>];
+
+    #[inline]
+    fn deref(&self) -> &Self::Target {
+        &self.0
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::tests::*;
+    use once_cell::sync::Lazy;
+    use uniswap_sdk_core::prelude::*;
+
+    static PROVIDER: Lazy<StaticTickDataProvider<2>> =
+        Lazy::new(|| StaticTickDataProvider::new([Tick::new(-1, 1, 1), Tick::new(1, 1, -1)], 1));
+
+    #[test]
+    #[should_panic(expected = "LENGTH")]
+    fn throws_for_an_empty_array_of_ticks() {
+        StaticTickDataProvider::<0>::new([], 1);
+    }
+
+    #[test]
+    #[should_panic(expected = "ZERO_NET")]
+    fn throws_for_uneven_tick_array() {
+        StaticTickDataProvider::new([Tick::new(-1, 1, -1), Tick::new(1, 1, 2)], 1);
+    }
+
+    #[test]
+    fn gets_ticks_from_the_array() {
+        assert_eq!(PROVIDER.get_tick(-1).unwrap().liquidity_net, 1);
+        assert_eq!(PROVIDER.get_tick(1).unwrap().liquidity_net, -1);
+        assert_eq!(
+            PROVIDER
+                .next_initialized_tick_within_one_word(0, false, 1)
+                .unwrap(),
+            (1, true)
+        );
+    }
+
+    #[test]
+    fn swaps_like_a_tick_list() {
+        let tick_spacing = FEE_AMOUNT.tick_spacing().as_i32();
+        let ticks = [
+            Tick::new(
+                nearest_usable_tick(MIN_TICK_I32, tick_spacing),
+                LIQUIDITY,
+                LIQUIDITY as i128,
+            ),
+            Tick::new(
+                nearest_usable_tick(MAX_TICK_I32, tick_spacing),
+                LIQUIDITY,
+                -(LIQUIDITY as i128),
+            ),
+        ];
+        let input = CurrencyAmount::from_raw_amount(TOKEN0.clone(), 100).unwrap();
+        let output = Pool::new_with_tick_data_provider(
+            TOKEN0.clone(),
+            TOKEN1.clone(),
+            FEE_AMOUNT,
+            SQRT_RATIO_X96,
+            LIQUIDITY,
+            StaticTickDataProvider::new(ticks, tick_spacing),
+        )
+        .unwrap()
+        .get_output_amount(&input, None)
+        .unwrap();
+        assert_eq!(
+            output,
+            make_pool(TOKEN0.clone(), TOKEN1.clone())
+                .get_output_amount(&input, None)
+                .unwrap()
+        );
+    }
+}
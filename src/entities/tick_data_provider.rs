@@ -1,5 +1,9 @@
 use crate::prelude::*;
-use core::ops::Deref;
+use alloc::{boxed::Box, sync::Arc};
+#[cfg(target_has_atomic = "64")]
+use core::sync::atomic::{AtomicU64, Ordering};
+use core::{fmt, ops::Deref};
+use once_cell::race::OnceBox;
 
 /// Provides information about ticks
 pub trait TickDataProvider {
@@ -56,6 +60,60 @@ where
     }
 }
 
+/// Guard rails for tick data providers that load ticks eagerly, so that pathological pools fail
+/// with [`Error::TickLimitExceeded`] instead of exhausting memory or stalling on huge fetches.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, Hash)]
+pub struct TickDataProviderLimits {
+    /// The maximum number of initialized ticks to load.
+    pub max_ticks: Option<usize>,
+    /// The maximum number of tick bitmap words the tick range may span.
+    pub max_word_range: Option<u32>,
+}
+
+impl TickDataProviderLimits {
+    /// Checks the number of ticks against `max_ticks`
+    ///
+    /// ## Arguments
+    ///
+    /// * `num_ticks`: The number of initialized ticks to load
+    #[inline]
+    pub const fn check_ticks(&self, num_ticks: usize) -> Result<(), Error> {
+        match self.max_ticks {
+            Some(max_ticks) if num_ticks > max_ticks => Err(Error::TickLimitExceeded),
+            _ => Ok(()),
+        }
+    }
+
+    /// Checks the number of tick bitmap words spanned by a tick range against `max_word_range`
+    ///
+    /// ## Arguments
+    ///
+    /// * `tick_lower`: The lower tick of the range
+    /// * `tick_upper`: The upper tick of the range
+    /// * `tick_spacing`: The tick spacing of the pool
+    #[inline]
+    pub fn check_tick_range<I: TickIndex>(
+        &self,
+        tick_lower: I,
+        tick_upper: I,
+        tick_spacing: I,
+    ) -> Result<(), Error> {
+        let Some(max_word_range) = self.max_word_range else {
+            return Ok(());
+        };
+        let (word_lower, _) = tick_lower.compress(tick_spacing).position();
+        let (word_upper, _) = tick_upper.compress(tick_spacing).position();
+        let words: i32 = (word_upper - word_lower)
+            .try_into()
+            .map_err(|_| Error::TickLimitExceeded)?;
+        if words as i64 + 1 > max_word_range as i64 {
+            Err(Error::TickLimitExceeded)
+        } else {
+            Ok(())
+        }
+    }
+}
+
 /// This tick data provider does not know how to fetch any tick data. It throws whenever it is
 /// required. Useful if you do not need to load tick data for your use case.
 #[derive(Clone, Copy, Debug)]
@@ -80,6 +138,348 @@ impl TickDataProvider for NoTickDataProvider {
     }
 }
 
+/// A tick data provider backed by a pair of closures, useful for mocking tick data without
+/// implementing [`TickDataProvider`] for a new type.
+///
+/// `get_tick` returns owned ticks, which the provider keeps once fetched so that it can lend them
+/// out by reference, and is called at most once per tick. Since [`TickDataProvider`] is
+/// synchronous, async fetchers should be driven to completion within the closures, e.g. with
+/// `block_on`.
+#[derive(Clone)]
+pub struct FnTickDataProvider<I, F, G> {
+    get_tick: F,
+    next_initialized_tick_within_one_word: G,
+    ticks: OnceBox<FetchedTick<I>>,
+}
+
+/// A tick fetched by a [`FnTickDataProvider`], in a list that only grows so that references to
+/// the ticks remain valid for the lifetime of the provider.
+#[derive(Clone)]
+struct FetchedTick<I> {
+    tick: Tick<I>,
+    next: OnceBox<Self>,
+}
+
+impl<I, F, G> fmt::Debug for FnTickDataProvider<I, F, G> {
+    #[inline]
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("FnTickDataProvider").finish_non_exhaustive()
+    }
+}
+
+impl<I, F, G> FnTickDataProvider<I, F, G> {
+    /// Creates a new [`FnTickDataProvider`] from the given closures
+    ///
+    /// ## Arguments
+    ///
+    /// * `get_tick`: Returns the tick at the given index
+    /// * `next_initialized_tick_within_one_word`: Given `(tick, lte, tick_spacing)`, returns the
+    ///   next initialized tick within one word and whether it is initialized
+    #[inline]
+    #[must_use]
+    pub const fn new(get_tick: F, next_initialized_tick_within_one_word: G) -> Self {
+        Self {
+            get_tick,
+            next_initialized_tick_within_one_word,
+            ticks: OnceBox::new(),
+        }
+    }
+}
+
+impl<I, F, G> TickDataProvider for FnTickDataProvider<I, F, G>
+where
+    I: TickIndex,
+    F: Fn(I) -> Result<Tick<I>, Error>,
+    G: Fn(I, bool, I) -> Result<(I, bool), Error>,
+{
+    type Index = I;
+
+    #[inline]
+    fn get_tick(&self, tick: I) -> Result<&Tick<I>, Error> {
+        let mut slot = &self.ticks;
+        loop {
+            let fetched = slot.get_or_try_init(|| {
+                (self.get_tick)(tick).map(|tick| {
+                    Box::new(FetchedTick {
+                        tick,
+                        next: OnceBox::new(),
+                    })
+                })
+            })?;
+            if fetched.tick.index == tick {
+                return Ok(&fetched.tick);
+            }
+            slot = &fetched.next;
+        }
+    }
+
+    #[inline]
+    fn next_initialized_tick_within_one_word(
+        &self,
+        tick: I,
+        lte: bool,
+        tick_spacing: I,
+    ) -> Result<(I, bool), Error> {
+        (self.next_initialized_tick_within_one_word)(tick, lte, tick_spacing)
+    }
+}
+
+/// A type-erased tick data provider, so that pools backed by different providers can be stored in
+/// the same collection, e.g. with [`Pool::into_boxed`].
+///
+/// [`TickDataProvider`] is synchronous and dyn-compatible, so no boxed futures are involved: async
+/// sources such as the extension providers are fetched up front and erased once loaded. The
+/// provider is shared behind an [`Arc`] so that the pools remain cheap to clone.
+#[derive(Clone)]
+pub struct BoxedTickDataProvider<I: TickIndex = i32>(
+    Arc<dyn TickDataProvider<Index = I> + Send + Sync>,
+);
+
+impl<I: TickIndex> fmt::Debug for BoxedTickDataProvider<I> {
+    #[inline]
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("BoxedTickDataProvider")
+            .finish_non_exhaustive()
+    }
+}
+
+impl<I: TickIndex> BoxedTickDataProvider<I> {
+    /// Erases the type of a tick data provider
+    ///
+    /// ## Arguments
+    ///
+    /// * `provider`: The tick data provider to erase
+    #[inline]
+    pub fn new<TP>(provider: TP) -> Self
+    where
+        TP: TickDataProvider<Index = I> + Send + Sync + 'static,
+    {
+        Self(Arc::new(provider))
+    }
+}
+
+impl<I: TickIndex> Deref for BoxedTickDataProvider<I> {
+    type Target = dyn TickDataProvider<Index = I> + Send + Sync;
+
+    #[inline]
+    fn deref(&self) -> &Self::Target {
+        &*self.0
+    }
+}
+
+/// A sink for the counters of tick data providers and swap simulations, so that the provider
+/// configuration, e.g. the fetched tick range, can be tuned with real data.
+///
+/// All methods do nothing by default and take `&self`, so that a sink can be shared, e.g. one
+/// backed by atomics like [`AtomicTickMetrics`] or by a metrics library.
+pub trait TickMetrics {
+    /// Records requests sent to a node, counting each call of a JSON-RPC batch
+    #[inline]
+    fn record_rpc_calls(&self, _count: u64) {}
+
+    /// Records initialized ticks fetched from a node
+    #[inline]
+    fn record_ticks_fetched(&self, _count: u64) {}
+
+    /// Records a simulation served by the ticks already fetched
+    #[inline]
+    fn record_cache_hit(&self) {}
+
+    /// Records a simulation which needed more ticks to be fetched
+    #[inline]
+    fn record_cache_miss(&self) {}
+
+    /// Records a lookup of the next initialized tick, i.e. a step of a swap
+    #[inline]
+    fn record_tick_lookups(&self, _count: u64) {}
+
+    /// Records the initialized ticks crossed by a simulated swap
+    #[inline]
+    fn record_ticks_crossed(&self, _count: u64) {}
+}
+
+/// A [`TickMetrics`] sink which discards everything.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, Hash)]
+pub struct NoopTickMetrics;
+
+impl TickMetrics for NoopTickMetrics {}
+
+impl<M: TickMetrics + ?Sized> TickMetrics for &M {
+    #[inline]
+    fn record_rpc_calls(&self, count: u64) {
+        (**self).record_rpc_calls(count);
+    }
+
+    #[inline]
+    fn record_ticks_fetched(&self, count: u64) {
+        (**self).record_ticks_fetched(count);
+    }
+
+    #[inline]
+    fn record_cache_hit(&self) {
+        (**self).record_cache_hit();
+    }
+
+    #[inline]
+    fn record_cache_miss(&self) {
+        (**self).record_cache_miss();
+    }
+
+    #[inline]
+    fn record_tick_lookups(&self, count: u64) {
+        (**self).record_tick_lookups(count);
+    }
+
+    #[inline]
+    fn record_ticks_crossed(&self, count: u64) {
+        (**self).record_ticks_crossed(count);
+    }
+}
+
+/// The counters of an [`AtomicTickMetrics`] at some point.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, Hash)]
+pub struct TickMetricsSnapshot {
+    /// The requests sent to a node, see [`TickMetrics::record_rpc_calls`].
+    pub rpc_calls: u64,
+    /// The initialized ticks fetched from a node.
+    pub ticks_fetched: u64,
+    /// The simulations served by the ticks already fetched.
+    pub cache_hits: u64,
+    /// The simulations which needed more ticks to be fetched.
+    pub cache_misses: u64,
+    /// The lookups of the next initialized tick, i.e. the steps of the swaps.
+    pub tick_lookups: u64,
+    /// The initialized ticks crossed by the simulated swaps.
+    pub ticks_crossed: u64,
+}
+
+/// A [`TickMetrics`] sink which sums the counters in atomics.
+#[cfg(target_has_atomic = "64")]
+#[derive(Debug, Default)]
+pub struct AtomicTickMetrics {
+    rpc_calls: AtomicU64,
+    ticks_fetched: AtomicU64,
+    cache_hits: AtomicU64,
+    cache_misses: AtomicU64,
+    tick_lookups: AtomicU64,
+    ticks_crossed: AtomicU64,
+}
+
+#[cfg(target_has_atomic = "64")]
+impl AtomicTickMetrics {
+    #[inline]
+    #[must_use]
+    pub const fn new() -> Self {
+        Self {
+            rpc_calls: AtomicU64::new(0),
+            ticks_fetched: AtomicU64::new(0),
+            cache_hits: AtomicU64::new(0),
+            cache_misses: AtomicU64::new(0),
+            tick_lookups: AtomicU64::new(0),
+            ticks_crossed: AtomicU64::new(0),
+        }
+    }
+
+    /// Returns the current counters
+    #[inline]
+    #[must_use]
+    pub fn snapshot(&self) -> TickMetricsSnapshot {
+        TickMetricsSnapshot {
+            rpc_calls: self.rpc_calls.load(Ordering::Relaxed),
+            ticks_fetched: self.ticks_fetched.load(Ordering::Relaxed),
+            cache_hits: self.cache_hits.load(Ordering::Relaxed),
+            cache_misses: self.cache_misses.load(Ordering::Relaxed),
+            tick_lookups: self.tick_lookups.load(Ordering::Relaxed),
+            ticks_crossed: self.ticks_crossed.load(Ordering::Relaxed),
+        }
+    }
+}
+
+#[cfg(target_has_atomic = "64")]
+impl TickMetrics for AtomicTickMetrics {
+    #[inline]
+    fn record_rpc_calls(&self, count: u64) {
+        self.rpc_calls.fetch_add(count, Ordering::Relaxed);
+    }
+
+    #[inline]
+    fn record_ticks_fetched(&self, count: u64) {
+        self.ticks_fetched.fetch_add(count, Ordering::Relaxed);
+    }
+
+    #[inline]
+    fn record_cache_hit(&self) {
+        self.cache_hits.fetch_add(1, Ordering::Relaxed);
+    }
+
+    #[inline]
+    fn record_cache_miss(&self) {
+        self.cache_misses.fetch_add(1, Ordering::Relaxed);
+    }
+
+    #[inline]
+    fn record_tick_lookups(&self, count: u64) {
+        self.tick_lookups.fetch_add(count, Ordering::Relaxed);
+    }
+
+    #[inline]
+    fn record_ticks_crossed(&self, count: u64) {
+        self.ticks_crossed.fetch_add(count, Ordering::Relaxed);
+    }
+}
+
+/// A tick data provider which reports the tick walk of the swaps simulated with it to a
+/// [`TickMetrics`] sink.
+///
+/// Swaps only load a tick when crossing it, so every [`TickDataProvider::get_tick`] is recorded
+/// as a tick crossed and every [`TickDataProvider::next_initialized_tick_within_one_word`] as a
+/// lookup.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub struct MeteredTickDataProvider<TP, M> {
+    pub inner: TP,
+    pub metrics: M,
+}
+
+impl<TP, M> MeteredTickDataProvider<TP, M> {
+    /// Wraps a tick data provider
+    ///
+    /// ## Arguments
+    ///
+    /// * `inner`: The tick data provider to meter
+    /// * `metrics`: The sink, e.g. a reference to an [`AtomicTickMetrics`]
+    #[inline]
+    #[must_use]
+    pub const fn new(inner: TP, metrics: M) -> Self {
+        Self { inner, metrics }
+    }
+}
+
+impl<TP, M> TickDataProvider for MeteredTickDataProvider<TP, M>
+where
+    TP: TickDataProvider,
+    M: TickMetrics,
+{
+    type Index = TP::Index;
+
+    #[inline]
+    fn get_tick(&self, tick: Self::Index) -> Result<&Tick<Self::Index>, Error> {
+        self.metrics.record_ticks_crossed(1);
+        self.inner.get_tick(tick)
+    }
+
+    #[inline]
+    fn next_initialized_tick_within_one_word(
+        &self,
+        tick: Self::Index,
+        lte: bool,
+        tick_spacing: Self::Index,
+    ) -> Result<(Self::Index, bool), Error> {
+        self.metrics.record_tick_lookups(1);
+        self.inner
+            .next_initialized_tick_within_one_word(tick, lte, tick_spacing)
+    }
+}
+
 #[cfg(all(feature = "std", test))]
 mod tests {
     use super::*;
@@ -99,4 +499,103 @@ mod tests {
             Error::NoTickDataError.to_string()
         );
     }
+
+    #[test]
+    fn test_tick_data_provider_limits() {
+        let limits = TickDataProviderLimits {
+            max_ticks: Some(2),
+            max_word_range: Some(2),
+        };
+        assert!(limits.check_ticks(2).is_ok());
+        assert!(matches!(
+            limits.check_ticks(3),
+            Err(Error::TickLimitExceeded)
+        ));
+        assert!(limits.check_tick_range(-10, 2550, 10).is_ok());
+        assert!(matches!(
+            limits.check_tick_range(-10, 2560, 10),
+            Err(Error::TickLimitExceeded)
+        ));
+        assert!(TickDataProviderLimits::default()
+            .check_tick_range(MIN_TICK_I32, MAX_TICK_I32, 1)
+            .is_ok());
+    }
+
+    #[test]
+    fn test_boxed_tick_data_provider() {
+        let ticks = TickListDataProvider::new(vec![Tick::new(-1, 1, 1), Tick::new(1, 1, -1)], 1);
+        let providers = [
+            BoxedTickDataProvider::new(ticks.clone()),
+            BoxedTickDataProvider::new(Arc::new(ticks.clone())),
+            BoxedTickDataProvider::new(NoTickDataProvider),
+        ];
+        for provider in &providers[..2] {
+            assert_eq!(provider.get_tick(1).unwrap(), &Tick::new(1, 1, -1));
+            assert_eq!(
+                provider
+                    .next_initialized_tick_within_one_word(0, true, 1)
+                    .unwrap(),
+                ticks
+                    .next_initialized_tick_within_one_word(0, true, 1)
+                    .unwrap()
+            );
+        }
+        assert!(matches!(
+            providers[2].get_tick(1),
+            Err(Error::NoTickDataError)
+        ));
+    }
+
+    #[test]
+    fn test_fn_tick_data_provider() {
+        let ticks = TickListDataProvider::new(vec![Tick::new(-1, 1, 1), Tick::new(1, 1, -1)], 1);
+        let calls = core::cell::Cell::new(0);
+        let provider = FnTickDataProvider::new(
+            |tick| {
+                calls.set(calls.get() + 1);
+                ticks.get_tick(tick).cloned()
+            },
+            |tick, lte, tick_spacing| {
+                ticks.next_initialized_tick_within_one_word(tick, lte, tick_spacing)
+            },
+        );
+        assert_eq!(provider.get_tick(1).unwrap(), &Tick::new(1, 1, -1));
+        assert_eq!(provider.get_tick(-1).unwrap(), &Tick::new(-1, 1, 1));
+        assert_eq!(provider.get_tick(1).unwrap(), &Tick::new(1, 1, -1));
+        assert_eq!(calls.get(), 2);
+        assert!(provider.get_tick(0).is_err());
+        assert_eq!(
+            provider
+                .next_initialized_tick_within_one_word(0, true, 1)
+                .unwrap(),
+            ticks
+                .next_initialized_tick_within_one_word(0, true, 1)
+                .unwrap()
+        );
+    }
+
+    #[test]
+    fn test_metered_tick_data_provider() {
+        let metrics = AtomicTickMetrics::new();
+        let ticks = TickListDataProvider::new(vec![Tick::new(-1, 1, 1), Tick::new(1, 1, -1)], 1);
+        let provider = MeteredTickDataProvider::new(ticks, &metrics);
+        provider.get_tick(1).unwrap();
+        provider
+            .next_initialized_tick_within_one_word(0, true, 1)
+            .unwrap();
+        provider
+            .next_initialized_tick_within_one_word(0, false, 1)
+            .unwrap();
+        NoopTickMetrics.record_cache_hit();
+        (&metrics).record_cache_miss();
+        assert_eq!(
+            metrics.snapshot(),
+            TickMetricsSnapshot {
+                ticks_crossed: 1,
+                tick_lookups: 2,
+                cache_misses: 1,
+                ..Default::default()
+            }
+        );
+    }
 }
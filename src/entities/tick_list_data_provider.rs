@@ -1,17 +1,75 @@
 use crate::prelude::*;
-use alloc::vec::Vec;
+use alloc::{sync::Arc, vec::Vec};
 use derive_more::Deref;
 
 /// A data provider for ticks that is backed by an in-memory array of ticks.
+///
+/// The ticks are shared behind an [`Arc`], so cloning the provider, or a pool using it, is cheap.
+/// The ticks are only copied when mutated through [`Self::ticks_mut`] while shared.
 #[derive(Clone, Debug, Default, PartialEq, Deref)]
-pub struct TickListDataProvider<I = i32>(Vec<Tick<I>>);
+#[deref(forward)]
+pub struct TickListDataProvider<I = i32>(Arc<Vec<Tick<I>>>);
 
 impl<I: TickIndex> TickListDataProvider<I> {
     #[inline]
     pub fn new(ticks: Vec<Tick<I>>, tick_spacing: I) -> Self {
+        Self::from_shared(Arc::new(ticks), tick_spacing)
+    }
+
+    /// Creates a provider backed by an already shared list of ticks without copying it
+    ///
+    /// ## Arguments
+    ///
+    /// * `ticks`: The sorted list of initialized ticks
+    /// * `tick_spacing`: The tick spacing of the pool
+    #[inline]
+    pub fn from_shared(ticks: Arc<Vec<Tick<I>>>, tick_spacing: I) -> Self {
         ticks.validate_list(tick_spacing);
         Self(ticks)
     }
+
+    /// Returns the shared list of ticks
+    #[inline]
+    #[must_use]
+    pub const fn shared(&self) -> &Arc<Vec<Tick<I>>> {
+        &self.0
+    }
+
+    /// Returns whether both providers share the same tick list allocation
+    #[inline]
+    #[must_use]
+    pub fn shares_ticks_with(&self, other: &Self) -> bool {
+        Arc::ptr_eq(&self.0, &other.0)
+    }
+
+    /// Returns a mutable reference to the ticks, copying them first if they are shared with
+    /// another provider.
+    ///
+    /// The caller is responsible for keeping the list sorted and valid for the tick spacing.
+    #[inline]
+    pub fn ticks_mut(&mut self) -> &mut Vec<Tick<I>> {
+        Arc::make_mut(&mut self.0)
+    }
+
+    /// Same as [`Self::new`] but rejects tick lists exceeding the given limits
+    ///
+    /// ## Arguments
+    ///
+    /// * `ticks`: The sorted list of initialized ticks
+    /// * `tick_spacing`: The tick spacing of the pool
+    /// * `limits`: The limits to enforce
+    #[inline]
+    pub fn new_with_limits(
+        ticks: Vec<Tick<I>>,
+        tick_spacing: I,
+        limits: TickDataProviderLimits,
+    ) -> Result<Self, Error> {
+        limits.check_ticks(ticks.len())?;
+        if let (Some(first), Some(last)) = (ticks.first(), ticks.last()) {
+            limits.check_tick_range(first.index, last.index, tick_spacing)?;
+        }
+        Ok(Self::new(ticks, tick_spacing))
+    }
 }
 
 #[cfg(test)]
@@ -33,6 +91,32 @@ mod tests {
         TickListDataProvider::new(vec![], 0);
     }
 
+    #[test]
+    fn throws_if_limits_exceeded() {
+        let ticks = vec![Tick::new(-1, 1, 1), Tick::new(1, 1, -1)];
+        let limits = TickDataProviderLimits {
+            max_ticks: Some(1),
+            max_word_range: None,
+        };
+        assert!(matches!(
+            TickListDataProvider::new_with_limits(ticks.clone(), 1, limits),
+            Err(Error::TickLimitExceeded)
+        ));
+        assert!(TickListDataProvider::new_with_limits(ticks, 1, Default::default()).is_ok());
+    }
+
+    #[test]
+    fn clones_share_ticks_until_mutated() {
+        let provider = PROVIDER.clone();
+        assert!(provider.shares_ticks_with(&PROVIDER));
+        let mut mutated = provider.clone();
+        mutated.ticks_mut()[0].liquidity_gross = 2;
+        assert!(provider.shares_ticks_with(&PROVIDER));
+        assert!(!mutated.shares_ticks_with(&PROVIDER));
+        assert_eq!(PROVIDER.get_tick(-1).unwrap().liquidity_gross, 1);
+        assert_eq!(mutated.get_tick(-1).unwrap().liquidity_gross, 2);
+    }
+
     #[test]
     #[should_panic(expected = "ZERO_NET")]
     fn throws_for_uneven_tick_list() {
@@ -1,8 +1,14 @@
+use super::route::total_fee;
 use crate::prelude::{Error, *};
-use alloc::vec;
-use alloy_primitives::map::rustc_hash::FxHashSet;
+use alloc::{
+    collections::BTreeMap,
+    format,
+    string::{String, ToString},
+    vec,
+};
+use alloy_primitives::{aliases::U24, keccak256, map::rustc_hash::FxHashSet, Address, U160, U256};
 use core::cmp::Ordering;
-use uniswap_sdk_core::prelude::{sorted_insert, *};
+use uniswap_sdk_core::prelude::*;
 
 /// Trades comparator, an extension of the input output comparator that also considers other
 /// dimensions of the trade in ranking them
@@ -65,12 +71,610 @@ where
     }
 }
 
-#[derive(Clone, Copy, Debug, Default, PartialEq)]
-pub struct BestTradeOptions {
+/// Measured gas costs of routes, e.g. the `gasEstimate` returned by `QuoterV2`, and the prices
+/// converting them into the currency that trades are ranked by, see
+/// [`gas_adjusted_trade_comparator`].
+#[derive(Clone, Debug, Default, PartialEq)]
+pub struct RouteGasCosts {
+    /// The gas used by each route, keyed by the addresses of its pools in order.
+    pub gas_estimates: BTreeMap<Vec<Address>, U256>,
+    /// The gas price in wei.
+    pub gas_price: U256,
+    /// The price of one wei in raw units of the output currency for exact input trades, or of the
+    /// input currency for exact output trades.
+    pub native_price: Fraction,
+}
+
+impl RouteGasCosts {
+    /// Creates the costs without any gas estimate
+    ///
+    /// ## Arguments
+    ///
+    /// * `gas_price`: The gas price in wei
+    /// * `native_price`: The price of one wei in raw units of the currency trades are ranked by
+    #[inline]
+    #[must_use]
+    pub const fn new(gas_price: U256, native_price: Fraction) -> Self {
+        Self {
+            gas_estimates: BTreeMap::new(),
+            gas_price,
+            native_price,
+        }
+    }
+
+    #[inline]
+    fn route_key<TInput, TOutput, TP>(route: &Route<TInput, TOutput, TP>) -> Vec<Address>
+    where
+        TInput: BaseCurrency,
+        TOutput: BaseCurrency,
+        TP: TickDataProvider,
+    {
+        route
+            .pools
+            .iter()
+            .map(|pool| pool.address(None, None))
+            .collect()
+    }
+
+    /// Records the measured gas of a route
+    ///
+    /// ## Arguments
+    ///
+    /// * `route`: The route the gas was measured for
+    /// * `gas_estimate`: The gas used by a swap through the route
+    #[inline]
+    pub fn insert<TInput, TOutput, TP>(
+        &mut self,
+        route: &Route<TInput, TOutput, TP>,
+        gas_estimate: U256,
+    ) where
+        TInput: BaseCurrency,
+        TOutput: BaseCurrency,
+        TP: TickDataProvider,
+    {
+        self.gas_estimates
+            .insert(Self::route_key(route), gas_estimate);
+    }
+
+    /// Returns the total measured gas of the routes of a trade, or `None` if any route has not
+    /// been measured
+    ///
+    /// ## Arguments
+    ///
+    /// * `trade`: The trade to estimate the gas of
+    #[inline]
+    #[must_use]
+    pub fn trade_gas<TInput, TOutput, TP>(&self, trade: &Trade<TInput, TOutput, TP>) -> Option<U256>
+    where
+        TInput: BaseCurrency,
+        TOutput: BaseCurrency,
+        TP: TickDataProvider,
+    {
+        trade.swaps.iter().try_fold(U256::ZERO, |total, swap| {
+            Some(total + *self.gas_estimates.get(&Self::route_key(&swap.route))?)
+        })
+    }
+
+    /// Returns the output of an exact input trade net of the cost of its gas, or the input of an
+    /// exact output trade plus the cost of its gas, in raw units. Returns `None` if any route of
+    /// the trade has not been measured.
+    ///
+    /// ## Arguments
+    ///
+    /// * `trade`: The trade to adjust
+    #[inline]
+    pub fn gas_adjusted_amount<TInput, TOutput, TP>(
+        &self,
+        trade: &Trade<TInput, TOutput, TP>,
+    ) -> Result<Option<Fraction>, Error>
+    where
+        TInput: BaseCurrency,
+        TOutput: BaseCurrency,
+        TP: TickDataProvider,
+    {
+        let Some(gas) = self.trade_gas(trade) else {
+            return Ok(None);
+        };
+        let cost =
+            Fraction::new((gas * self.gas_price).to_big_int(), 1) * self.native_price.clone();
+        Ok(Some(match trade.trade_type {
+            TradeType::ExactInput => trade.output_amount()?.as_fraction() - cost,
+            TradeType::ExactOutput => trade.input_amount()?.as_fraction() + cost,
+        }))
+    }
+}
+
+/// Same as [`trade_comparator`] but ranks trades whose routes have all been measured by their
+/// output net of gas for exact input trades, or their input plus gas for exact output trades,
+/// instead of by the number of hops. Trades that have not been measured or with equal adjusted
+/// amounts are compared by [`trade_comparator`].
+///
+/// ## Arguments
+///
+/// * `a`: The first trade to compare
+/// * `b`: The second trade to compare
+/// * `costs`: The measured gas of the routes and the prices to convert it
+///
+/// ## Returns
+///
+/// The ordering of the trades, or the error computing the input or output amount of either
+#[inline]
+pub fn gas_adjusted_trade_comparator<TInput, TOutput, TP>(
+    a: &Trade<TInput, TOutput, TP>,
+    b: &Trade<TInput, TOutput, TP>,
+    costs: &RouteGasCosts,
+) -> Result<Ordering, Error>
+where
+    TInput: BaseCurrency,
+    TOutput: BaseCurrency,
+    TP: TickDataProvider,
+{
+    let (Some(a_amount), Some(b_amount)) =
+        (costs.gas_adjusted_amount(a)?, costs.gas_adjusted_amount(b)?)
+    else {
+        return Ok(trade_comparator(a, b));
+    };
+    Ok(compare_adjusted_amounts(a, b, &a_amount, &b_amount))
+}
+
+/// Compares two trades by their amounts adjusted for gas, falling back to [`trade_comparator`]
+/// if equal
+fn compare_adjusted_amounts<TInput, TOutput, TP>(
+    a: &Trade<TInput, TOutput, TP>,
+    b: &Trade<TInput, TOutput, TP>,
+    a_amount: &Fraction,
+    b_amount: &Fraction,
+) -> Ordering
+where
+    TInput: BaseCurrency,
+    TOutput: BaseCurrency,
+    TP: TickDataProvider,
+{
+    if a_amount == b_amount {
+        return trade_comparator(a, b);
+    }
+    // more net output, or less gross input, comes first
+    let a_first = match a.trade_type {
+        TradeType::ExactInput => a_amount > b_amount,
+        TradeType::ExactOutput => a_amount < b_amount,
+    };
+    if a_first {
+        Ordering::Less
+    } else {
+        Ordering::Greater
+    }
+}
+
+/// Estimates the gas used by a trade from its shape, see [`Trade::estimate_gas_use_with`].
+///
+/// Unlike [`RouteGasCosts`], which needs the gas of every route to be measured, a model prices
+/// trades offline and so can rank every candidate of the best trade search.
+pub trait GasModel {
+    /// The gas of a trade regardless of its routes, e.g. the intrinsic gas of the transaction
+    fn base_gas(&self) -> u64;
+
+    /// The gas of a swap through a route
+    ///
+    /// ## Arguments
+    ///
+    /// * `ticks_crossed`: The number of initialized ticks crossed in each pool of the route
+    fn route_gas(&self, ticks_crossed: &[u32]) -> u64;
+
+    /// The gas of wrapping native currency into the input or unwrapping the output into native
+    /// currency
+    ///
+    /// ## Arguments
+    ///
+    /// * `wrap_input`: Whether the input is native currency
+    /// * `unwrap_output`: Whether the output is native currency
+    fn native_gas(&self, wrap_input: bool, unwrap_output: bool) -> u64;
+}
+
+/// A [`GasModel`] with a fixed cost per hop and per initialized tick crossed.
+///
+/// The default costs are rough averages of swaps through `SwapRouter02` on mainnet.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+pub struct DefaultGasModel {
+    /// The gas of a trade regardless of its routes.
+    pub base: u64,
+    /// The gas of each pool swapped through.
+    pub per_hop: u64,
+    /// The gas of each initialized tick crossed.
+    pub per_initialized_tick: u64,
+    /// The gas of wrapping the native input.
+    pub wrap: u64,
+    /// The gas of unwrapping the native output.
+    pub unwrap: u64,
+}
+
+impl Default for DefaultGasModel {
+    #[inline]
+    fn default() -> Self {
+        Self {
+            base: 21_000,
+            per_hop: 80_000,
+            per_initialized_tick: 31_000,
+            wrap: 25_000,
+            unwrap: 30_000,
+        }
+    }
+}
+
+impl GasModel for DefaultGasModel {
+    #[inline]
+    fn base_gas(&self) -> u64 {
+        self.base
+    }
+
+    #[inline]
+    fn route_gas(&self, ticks_crossed: &[u32]) -> u64 {
+        ticks_crossed
+            .iter()
+            .map(|&ticks| self.per_hop + self.per_initialized_tick * ticks as u64)
+            .sum()
+    }
+
+    #[inline]
+    fn native_gas(&self, wrap_input: bool, unwrap_output: bool) -> u64 {
+        let mut gas = 0;
+        if wrap_input {
+            gas += self.wrap;
+        }
+        if unwrap_output {
+            gas += self.unwrap;
+        }
+        gas
+    }
+}
+
+/// A [`TradeRanking`] by the output net of the gas estimated by a [`GasModel`] for exact input
+/// trades, or the input plus gas for exact output trades, falling back to [`trade_comparator`]
+/// for trades with equal adjusted amounts.
+#[derive(Debug)]
+pub struct GasModelRanking<'a, G> {
+    /// The model estimating the gas of the trades.
+    pub model: &'a G,
+    /// The gas price in wei.
+    pub gas_price: U256,
+    /// The price of one wei in raw units of the output currency for exact input trades, or of the
+    /// input currency for exact output trades.
+    pub native_price: &'a Fraction,
+}
+
+// not derived to not require `G: Copy`
+impl<G> Clone for GasModelRanking<'_, G> {
+    #[inline]
+    fn clone(&self) -> Self {
+        *self
+    }
+}
+
+impl<G> Copy for GasModelRanking<'_, G> {}
+
+impl<G: GasModel> GasModelRanking<'_, G> {
+    /// Returns the output of an exact input trade net of the cost of its estimated gas, or the
+    /// input of an exact output trade plus the cost of its estimated gas, in raw units
+    ///
+    /// ## Arguments
+    ///
+    /// * `trade`: The trade to adjust
+    #[inline]
+    pub fn gas_adjusted_amount<TInput, TOutput, TP>(
+        &self,
+        trade: &Trade<TInput, TOutput, TP>,
+    ) -> Result<Fraction, Error>
+    where
+        TInput: BaseCurrency,
+        TOutput: BaseCurrency,
+        TP: TickDataProvider,
+    {
+        let gas = U256::from(trade.estimate_gas_use_with(self.model)?);
+        let cost =
+            Fraction::new((gas * self.gas_price).to_big_int(), 1) * self.native_price.clone();
+        Ok(match trade.trade_type {
+            TradeType::ExactInput => trade.output_amount()?.as_fraction() - cost,
+            TradeType::ExactOutput => trade.input_amount()?.as_fraction() + cost,
+        })
+    }
+}
+
+impl<TInput, TOutput, TP, G> TradeRanking<TInput, TOutput, TP> for GasModelRanking<'_, G>
+where
+    TInput: BaseCurrency,
+    TOutput: BaseCurrency,
+    TP: TickDataProvider,
+    G: GasModel,
+{
+    #[inline]
+    fn compare(
+        &self,
+        a: &Trade<TInput, TOutput, TP>,
+        b: &Trade<TInput, TOutput, TP>,
+    ) -> Result<Ordering, Error> {
+        Ok(compare_adjusted_amounts(
+            a,
+            b,
+            &self.gas_adjusted_amount(a)?,
+            &self.gas_adjusted_amount(b)?,
+        ))
+    }
+}
+
+/// Ranks the candidate trades of [`Trade::best_trade_exact_in`] and
+/// [`Trade::best_trade_exact_out`], [`Ordering::Less`] meaning that `a` is the better trade.
+///
+/// Implemented by [`DefaultRanking`], [`GasModelRanking`] and by any closure comparing two trades,
+/// e.g. to rank by a gas adjusted output or by the total fee.
+pub trait TradeRanking<TInput, TOutput, TP>
+where
+    TInput: BaseCurrency,
+    TOutput: BaseCurrency,
+    TP: TickDataProvider,
+{
+    /// Compares two trades, returning the error computing the amounts they are ranked by, which
+    /// fails the best trade search
+    fn compare(
+        &self,
+        a: &Trade<TInput, TOutput, TP>,
+        b: &Trade<TInput, TOutput, TP>,
+    ) -> Result<Ordering, Error>;
+}
+
+/// The ranking of the best trade search by [`trade_comparator`].
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub struct DefaultRanking;
+
+impl<TInput, TOutput, TP> TradeRanking<TInput, TOutput, TP> for DefaultRanking
+where
+    TInput: BaseCurrency,
+    TOutput: BaseCurrency,
+    TP: TickDataProvider,
+{
+    #[inline]
+    fn compare(
+        &self,
+        a: &Trade<TInput, TOutput, TP>,
+        b: &Trade<TInput, TOutput, TP>,
+    ) -> Result<Ordering, Error> {
+        Ok(trade_comparator(a, b))
+    }
+}
+
+impl<TInput, TOutput, TP, F> TradeRanking<TInput, TOutput, TP> for F
+where
+    TInput: BaseCurrency,
+    TOutput: BaseCurrency,
+    TP: TickDataProvider,
+    F: Fn(&Trade<TInput, TOutput, TP>, &Trade<TInput, TOutput, TP>) -> Ordering,
+{
+    #[inline]
+    fn compare(
+        &self,
+        a: &Trade<TInput, TOutput, TP>,
+        b: &Trade<TInput, TOutput, TP>,
+    ) -> Result<Ordering, Error> {
+        Ok(self(a, b))
+    }
+}
+
+/// How the best trade search orders the trades ranked equal by its [`TradeRanking`].
+///
+/// Except for [`TieBreaker::Discovery`], the pools are sorted and deduplicated by address before
+/// the search, so the results do not depend on the order the pools are given in.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, Hash)]
+pub enum TieBreaker {
+    /// In the order the trades are found, which depends on the order of the pools.
+    #[default]
+    Discovery,
+    /// By the addresses of the pools along the route.
+    PoolAddresses,
+    /// By the hash of the seed and the addresses of the pools along the route, e.g. to spread
+    /// equal routes evenly while staying reproducible.
+    Seeded(u64),
+}
+
+impl TieBreaker {
+    /// Sorts and deduplicates the pools by address, unless the tie breaker is
+    /// [`TieBreaker::Discovery`]
+    fn canonicalize<TP: TickDataProvider>(self, pools: &mut Vec<Pool<TP>>) {
+        if self == Self::Discovery {
+            return;
+        }
+        pools.sort_by_cached_key(|pool| pool.address(None, None));
+        pools.dedup_by(|a, b| a.address(None, None) == b.address(None, None));
+    }
+
+    /// Compares two trades ranked equal
+    ///
+    /// ## Arguments
+    ///
+    /// * `a`: The first trade to compare
+    /// * `b`: The second trade to compare
+    #[inline]
+    #[must_use]
+    pub fn compare<TInput, TOutput, TP>(
+        self,
+        a: &Trade<TInput, TOutput, TP>,
+        b: &Trade<TInput, TOutput, TP>,
+    ) -> Ordering
+    where
+        TInput: BaseCurrency,
+        TOutput: BaseCurrency,
+        TP: TickDataProvider,
+    {
+        let route_addresses = |trade: &Trade<TInput, TOutput, TP>| -> Vec<Address> {
+            trade
+                .swaps
+                .iter()
+                .flat_map(|swap| swap.route.pools.iter())
+                .map(|pool| pool.address(None, None))
+                .collect()
+        };
+        match self {
+            Self::Discovery => Ordering::Equal,
+            Self::PoolAddresses => route_addresses(a).cmp(&route_addresses(b)),
+            Self::Seeded(seed) => {
+                let key = |trade| {
+                    let mut preimage = seed.to_be_bytes().to_vec();
+                    for address in route_addresses(trade) {
+                        preimage.extend_from_slice(address.as_slice());
+                    }
+                    keccak256(preimage)
+                };
+                key(a).cmp(&key(b))
+            }
+        }
+    }
+}
+
+/// Sorts the trades by the ranking and tie breaker of the options and keeps the best
+/// `max_num_results`, returning the first error of the ranking
+///
+/// The trades are inserted one by one after those ranked equal, so that equally ranked trades stay
+/// in the order they were found, and a failing comparison can be propagated rather than leaving
+/// the trades half sorted.
+fn rank_trades<TInput, TOutput, TP, R>(
+    trades: &mut Vec<Trade<TInput, TOutput, TP>>,
+    options: &BestTradeOptions<R>,
+    max_num_results: usize,
+) -> Result<(), Error>
+where
+    TInput: BaseCurrency,
+    TOutput: BaseCurrency,
+    TP: TickDataProvider,
+    R: TradeRanking<TInput, TOutput, TP>,
+{
+    let mut ranked: Vec<Trade<TInput, TOutput, TP>> = Vec::with_capacity(trades.len());
+    for trade in trades.drain(..) {
+        let (mut lo, mut hi) = (0, ranked.len());
+        while lo < hi {
+            let mid = (lo + hi) / 2;
+            let ordering = options
+                .ranking
+                .compare(&ranked[mid], &trade)?
+                .then_with(|| options.tie_breaker.compare(&ranked[mid], &trade));
+            if ordering == Ordering::Greater {
+                hi = mid;
+            } else {
+                lo = mid + 1;
+            }
+        }
+        ranked.insert(lo, trade);
+    }
+    ranked.truncate(max_num_results);
+    *trades = ranked;
+    Ok(())
+}
+
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct BestTradeOptions<R = DefaultRanking> {
     /// how many results to return
     pub max_num_results: Option<usize>,
     /// the maximum number of hops a trade should contain
     pub max_hops: Option<usize>,
+    /// the maximum sum of the fee tiers along a route in hundredths of a bip, the unit of
+    /// [`FeeAmount`] and [`Route::total_fee`], e.g. 6000 to exclude routes through two 1% pools
+    pub max_total_fee: Option<u32>,
+    /// how the trades are ranked, see [`TradeRanking`]
+    pub ranking: R,
+    /// how the trades ranked equal are ordered, see [`TieBreaker`]
+    pub tie_breaker: TieBreaker,
+}
+
+impl Default for BestTradeOptions {
+    #[inline]
+    fn default() -> Self {
+        Self {
+            max_num_results: None,
+            max_hops: None,
+            max_total_fee: None,
+            ranking: DefaultRanking,
+            tie_breaker: TieBreaker::Discovery,
+        }
+    }
+}
+
+/// How [`Trade::describe_with`] formats a trade.
+#[derive(Clone, Debug, PartialEq)]
+pub struct TradeDescriptionOptions {
+    /// The number of significant digits of the amounts, which are rounded down except for the
+    /// maximum amount in, which is rounded up.
+    pub significant_digits: u8,
+    /// The slippage tolerance, if any, in which case the minimum amount out of an exact input
+    /// trade or the maximum amount in of an exact output trade is shown.
+    pub slippage_tolerance: Option<Percent>,
+    /// The separator between groups of thousands of the amounts, if any.
+    pub group_separator: Option<char>,
+    /// The decimal separator of the amounts and percentages.
+    pub decimal_separator: char,
+}
+
+impl Default for TradeDescriptionOptions {
+    #[inline]
+    fn default() -> Self {
+        Self {
+            significant_digits: 6,
+            slippage_tolerance: None,
+            group_separator: Some(','),
+            decimal_separator: '.',
+        }
+    }
+}
+
+impl TradeDescriptionOptions {
+    /// Formats a decimal number printed by the SDK with the separators of the options
+    fn localize(&self, number: &str, group: bool) -> String {
+        let (integer, fraction) = number.split_once('.').unwrap_or((number, ""));
+        let mut localized = String::with_capacity(number.len() + integer.len() / 3);
+        for (i, digit) in integer.chars().enumerate() {
+            if let Some(separator) = self.group_separator.filter(|_| group) {
+                if i > 0 && (integer.len() - i) % 3 == 0 {
+                    localized.push(separator);
+                }
+            }
+            localized.push(digit);
+        }
+        if !fraction.is_empty() {
+            localized.push(self.decimal_separator);
+            localized.push_str(fraction);
+        }
+        localized
+    }
+
+    /// Formats an amount with its symbol, falling back to the address of the token
+    fn amount<T: BaseCurrency>(
+        &self,
+        amount: &CurrencyAmount<T>,
+        rounding: Rounding,
+    ) -> Result<String, Error> {
+        let value = amount.to_significant(self.significant_digits, Some(rounding))?;
+        Ok(format!(
+            "{} {}",
+            self.localize(&value, true),
+            currency_symbol(&amount.meta.currency)
+        ))
+    }
+
+    /// Formats a fee in hundredths of a bip as a percentage, e.g. `0.05%`
+    fn fee(&self, fee: FeeAmount) -> String {
+        let fee: U24 = fee.into();
+        let pips = fee.to::<u32>();
+        let fraction = format!("{:04}", pips % 10_000);
+        let fraction = fraction.trim_end_matches('0');
+        if fraction.is_empty() {
+            format!("{}%", pips / 10_000)
+        } else {
+            format!("{}{}{}%", pips / 10_000, self.decimal_separator, fraction)
+        }
+    }
+}
+
+/// Returns the symbol of a currency, or the address of its wrapped token if it has none
+fn currency_symbol(currency: &impl BaseCurrency) -> String {
+    currency
+        .symbol()
+        .cloned()
+        .unwrap_or_else(|| currency.wrapped().address().to_string())
 }
 
 /// Represents a swap through a route
@@ -125,6 +729,51 @@ where
     }
 }
 
+/// The slippage-adjusted bounds of a single swap of a [`BoundedTrade`], in raw amounts.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, Hash)]
+pub struct SwapBounds {
+    /// The maximum amount of the input currency spent.
+    pub max_in: U256,
+    /// The minimum amount of the output currency received.
+    pub min_out: U256,
+}
+
+/// The slippage-adjusted bounds of a trade materialized into raw amounts, returned by
+/// [`Trade::clone_with_slippage_applied`].
+///
+/// Unlike the fractional amounts of a [`Trade`], the bounds are exact integers that can be
+/// persisted and compared against the amounts of an execution receipt without rounding drift.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct BoundedTrade {
+    /// The type of the trade, either exact in or exact out.
+    pub trade_type: TradeType,
+    /// The maximum amount of the input currency spent by the whole trade.
+    pub max_in: U256,
+    /// The minimum amount of the output currency received by the whole trade.
+    pub min_out: U256,
+    /// The bounds of each swap, in the same order as [`Trade::swaps`].
+    pub swaps: Vec<SwapBounds>,
+    /// The sqrt price limit of each swap derived from the slippage tolerance, see
+    /// [`sqrt_price_limit_from_slippage`], in the same order as [`Trade::swaps`]. Only single-hop
+    /// swaps of exact input trades have a limit, since the router rejects limits on multi-hop
+    /// swaps and a limit could stop an exact output swap short of its output.
+    pub price_limits: Vec<Option<U160>>,
+}
+
+impl BoundedTrade {
+    /// Returns whether the executed amounts are within the bounds of the trade
+    ///
+    /// ## Arguments
+    ///
+    /// * `amount_in`: The raw amount of the input currency actually spent
+    /// * `amount_out`: The raw amount of the output currency actually received
+    #[inline]
+    #[must_use]
+    pub fn is_satisfied_by(&self, amount_in: U256, amount_out: U256) -> bool {
+        amount_in <= self.max_in && amount_out >= self.min_out
+    }
+}
+
 /// Represents a trade executed against a set of routes where some percentage of the input is split
 /// across each route.
 ///
@@ -145,13 +794,13 @@ where
     /// The type of the trade, either exact in or exact out.
     pub trade_type: TradeType,
     /// The cached result of the input amount computation
-    _input_amount: Option<CurrencyAmount<TInput>>,
+    _input_amount: Cached<CurrencyAmount<TInput>>,
     /// The cached result of the output amount computation
-    _output_amount: Option<CurrencyAmount<TOutput>>,
+    _output_amount: Cached<CurrencyAmount<TOutput>>,
     /// The cached result of the computed execution price
-    _execution_price: Option<Price<TInput, TOutput>>,
+    _execution_price: Cached<Price<TInput, TOutput>>,
     /// The cached result of the price impact computation
-    _price_impact: Option<Percent>,
+    _price_impact: Cached<Percent>,
 }
 
 impl<TInput, TOutput, TP> Trade<TInput, TOutput, TP>
@@ -171,14 +820,18 @@ where
         let input_currency = swaps[0].input_currency().wrapped();
         let output_currency = swaps[0].output_currency().wrapped();
         for Swap { route, .. } in &swaps {
-            assert!(
-                input_currency.equals(route.input.wrapped()),
-                "INPUT_CURRENCY_MATCH"
-            );
-            assert!(
-                output_currency.equals(route.output.wrapped()),
-                "OUTPUT_CURRENCY_MATCH"
-            );
+            if !input_currency.equals(route.input.wrapped()) {
+                return Err(Error::CurrencyMismatch {
+                    expected: input_currency.address(),
+                    actual: route.input.wrapped().address(),
+                });
+            }
+            if !output_currency.equals(route.output.wrapped()) {
+                return Err(Error::CurrencyMismatch {
+                    expected: output_currency.address(),
+                    actual: route.output.wrapped().address(),
+                });
+            }
         }
         let num_pools = swaps
             .iter()
@@ -193,10 +846,10 @@ where
         Ok(Self {
             swaps,
             trade_type,
-            _input_amount: None,
-            _output_amount: None,
-            _execution_price: None,
-            _price_impact: None,
+            _input_amount: Cached::new(),
+            _output_amount: Cached::new(),
+            _execution_price: Cached::new(),
+            _price_impact: Cached::new(),
         })
     }
 
@@ -232,6 +885,29 @@ where
         &self.swaps[0].route
     }
 
+    /// Returns the cumulative fee of the routes of the trade in hundredths of a bip, see
+    /// [`Route::total_fee`], averaged over the swaps weighted by the amount specified of each, i.e.
+    /// the share of the trade routed through it, and rounded down
+    #[inline]
+    #[must_use]
+    pub fn total_fee(&self) -> u32 {
+        let mut weighted_fee = BigInt::ZERO;
+        let mut total_amount = BigInt::ZERO;
+        for swap in &self.swaps {
+            let amount = match self.trade_type {
+                TradeType::ExactInput => swap.input_amount.quotient(),
+                TradeType::ExactOutput => swap.output_amount.quotient(),
+            };
+            weighted_fee += &amount * BigInt::from(swap.route.total_fee());
+            total_amount += amount;
+        }
+        if total_amount == BigInt::ZERO {
+            return 0;
+        }
+        // the weighted mean does not exceed the largest fee of the routes
+        u32::try_from(weighted_fee / total_amount).unwrap_or(u32::MAX)
+    }
+
     /// Returns the input currency of the swap
     #[inline]
     pub fn input_currency(&self) -> &TInput {
@@ -256,11 +932,11 @@ where
     /// The input amount for the trade assuming no slippage.
     #[inline]
     pub fn input_amount_cached(&mut self) -> Result<CurrencyAmount<TInput>, Error> {
-        if let Some(input_amount) = &self._input_amount {
+        if let Some(input_amount) = self._input_amount.get() {
             return Ok(input_amount.clone());
         }
         let input_amount = self.input_amount()?;
-        self._input_amount = Some(input_amount.clone());
+        self._input_amount.set(input_amount.clone());
         Ok(input_amount)
     }
 
@@ -288,11 +964,11 @@ where
     /// The output amount for the trade assuming no slippage.
     #[inline]
     pub fn output_amount_cached(&mut self) -> Result<CurrencyAmount<TOutput>, Error> {
-        if let Some(output_amount) = &self._output_amount {
+        if let Some(output_amount) = self._output_amount.get() {
             return Ok(output_amount.clone());
         }
         let output_amount = self.output_amount()?;
-        self._output_amount = Some(output_amount.clone());
+        self._output_amount.set(output_amount.clone());
         Ok(output_amount)
     }
 
@@ -307,13 +983,13 @@ where
     /// The price expressed in terms of output amount/input amount.
     #[inline]
     pub fn execution_price_cached(&mut self) -> Result<Price<TInput, TOutput>, Error> {
-        if let Some(execution_price) = &self._execution_price {
+        if let Some(execution_price) = self._execution_price.get() {
             return Ok(execution_price.clone());
         }
         let input_amount = self.input_amount_cached()?;
         let output_amount = self.output_amount_cached()?;
         let execution_price = Price::from_currency_amounts(input_amount, output_amount);
-        self._execution_price = Some(execution_price.clone());
+        self._execution_price.set(execution_price.clone());
         Ok(execution_price)
     }
 
@@ -343,7 +1019,7 @@ where
     /// Returns the percent difference between the route's mid price and the price impact
     #[inline]
     pub fn price_impact_cached(&mut self) -> Result<Percent, Error> {
-        if let Some(price_impact) = &self._price_impact {
+        if let Some(price_impact) = self._price_impact.get() {
             return Ok(price_impact.clone());
         }
         let mut spot_output_amount =
@@ -360,11 +1036,9 @@ where
         let price_impact = spot_output_amount
             .subtract(&self.output_amount_cached()?)?
             .divide(&spot_output_amount)?;
-        self._price_impact = Some(Percent::new(
-            price_impact.numerator,
-            price_impact.denominator,
-        ));
-        Ok(self._price_impact.clone().unwrap())
+        let price_impact = Percent::new(price_impact.numerator, price_impact.denominator);
+        self._price_impact.set(price_impact.clone());
+        Ok(price_impact)
     }
 
     /// Get the minimum amount that must be received from this trade for the given slippage
@@ -504,6 +1178,241 @@ where
             self.minimum_amount_out_cached(slippage_tolerance, None)?,
         ))
     }
+
+    /// Materializes the slippage-adjusted bounds of the trade and each of its swaps into raw
+    /// amounts, rounded the same way as in [`swap_call_parameters`]
+    ///
+    /// ## Arguments
+    ///
+    /// * `slippage_tolerance`: The allowed tolerated slippage
+    #[inline]
+    pub fn clone_with_slippage_applied(
+        &self,
+        slippage_tolerance: Percent,
+    ) -> Result<BoundedTrade, Error> {
+        let swaps = self
+            .swaps
+            .iter()
+            .map(|swap| {
+                Ok(SwapBounds {
+                    max_in: big_int_to_u256(
+                        &self
+                            .maximum_amount_in(
+                                slippage_tolerance.clone(),
+                                Some(swap.input_amount.clone()),
+                            )?
+                            .quotient(),
+                    )?,
+                    min_out: big_int_to_u256(
+                        &self
+                            .minimum_amount_out(
+                                slippage_tolerance.clone(),
+                                Some(swap.output_amount.clone()),
+                            )?
+                            .quotient(),
+                    )?,
+                })
+            })
+            .collect::<Result<Vec<_>, Error>>()?;
+        let price_limits = self
+            .swaps
+            .iter()
+            .map(
+                |swap| match (self.trade_type, swap.route.pools.as_slice()) {
+                    (TradeType::ExactInput, [pool]) => {
+                        let quote = pool.quote_output(&swap.input_amount, None)?;
+                        Ok(Some(sqrt_price_limit_from_slippage(
+                            quote.sqrt_price_x96_after,
+                            swap.route.input.wrapped().equals(&pool.token0),
+                            &slippage_tolerance,
+                        )))
+                    }
+                    _ => Ok(None),
+                },
+            )
+            .collect::<Result<Vec<_>, Error>>()?;
+        Ok(BoundedTrade {
+            trade_type: self.trade_type,
+            max_in: big_int_to_u256(
+                &self
+                    .maximum_amount_in(slippage_tolerance.clone(), None)?
+                    .quotient(),
+            )?,
+            min_out: big_int_to_u256(
+                &self
+                    .minimum_amount_out(slippage_tolerance, None)?
+                    .quotient(),
+            )?,
+            swaps,
+            price_limits,
+        })
+    }
+
+    /// Checks that every swap of the trade is possible given the state of its pools, i.e. that the
+    /// output does not exceed the input quoted at the mid price of the route, net of the fee of
+    /// every pool. Impossible trades, e.g. simulated on corrupted tick data, are rejected with
+    /// [`Error::TradeInvariantViolated`].
+    ///
+    /// Run automatically by [`Trade::from_route`] and [`Trade::from_routes`].
+    #[inline]
+    pub fn verify_invariants(&self) -> Result<(), Error> {
+        const MAX_FEE: u32 = 1_000_000;
+        for Swap {
+            route,
+            input_amount,
+            output_amount,
+        } in &self.swaps
+        {
+            let spot_output_amount = route.mid_price()?.quote(input_amount)?.as_fraction();
+            let output_amount = output_amount.as_fraction();
+            if output_amount > spot_output_amount {
+                return Err(Error::TradeInvariantViolated(TradeInvariant::Conservation));
+            }
+            let fee_complement = route.pools.iter().fold(Fraction::new(1, 1), |acc, pool| {
+                let fee: U24 = pool.fee.into();
+                let fee = fee.into_limbs()[0] as u32;
+                acc * Fraction::new(MAX_FEE - fee, MAX_FEE)
+            });
+            if output_amount > spot_output_amount * fee_complement {
+                return Err(Error::TradeInvariantViolated(TradeInvariant::FeeBounds));
+            }
+        }
+        Ok(())
+    }
+
+    /// Returns the number of initialized ticks crossed in each pool of each swap of the trade, by
+    /// simulating the swaps with the tick data providers of the pools
+    #[inline]
+    pub fn ticks_crossed(&self) -> Result<Vec<Vec<u32>>, Error> {
+        self.swaps
+            .iter()
+            .map(|swap| {
+                let pools = &swap.route.pools;
+                let mut ticks_crossed = Vec::with_capacity(pools.len());
+                match self.trade_type {
+                    TradeType::ExactInput => {
+                        let mut amount: CurrencyAmount<Token> =
+                            swap.input_amount.wrapped_owned()?;
+                        for pool in pools {
+                            let quote = pool.quote_output(&amount, None)?;
+                            ticks_crossed.push(quote.ticks_crossed);
+                            amount = quote.amount;
+                        }
+                    }
+                    TradeType::ExactOutput => {
+                        let mut amount: CurrencyAmount<Token> =
+                            swap.output_amount.wrapped_owned()?;
+                        for pool in pools.iter().rev() {
+                            let quote = pool.quote_input(&amount, None)?;
+                            ticks_crossed.push(quote.ticks_crossed);
+                            amount = quote.amount;
+                        }
+                        ticks_crossed.reverse();
+                    }
+                }
+                Ok(ticks_crossed)
+            })
+            .collect()
+    }
+
+    /// Estimates the gas used by the trade with the [`DefaultGasModel`]
+    #[inline]
+    pub fn estimate_gas_use(&self) -> Result<u64, Error> {
+        self.estimate_gas_use_with(&DefaultGasModel::default())
+    }
+
+    /// Estimates the gas used by the trade from its hops, the initialized ticks crossed and the
+    /// wrapping or unwrapping of native currency
+    ///
+    /// ## Arguments
+    ///
+    /// * `model`: The gas model pricing each part of the trade
+    #[inline]
+    pub fn estimate_gas_use_with(&self, model: &impl GasModel) -> Result<u64, Error> {
+        let routes_gas = self
+            .ticks_crossed()?
+            .iter()
+            .map(|ticks_crossed| model.route_gas(ticks_crossed))
+            .sum::<u64>();
+        Ok(model.base_gas()
+            + routes_gas
+            + model.native_gas(
+                self.input_currency().is_native(),
+                self.output_currency().is_native(),
+            ))
+    }
+
+    /// Describes the trade for logs, bots or command line output, e.g. `Swap 1.5 WETH → 2,912.45
+    /// USDC via WETH/USDC 0.05% (impact 0.12%)`, with the default options
+    #[inline]
+    pub fn describe(&self) -> Result<String, Error> {
+        self.describe_with(&TradeDescriptionOptions::default())
+    }
+
+    /// Describes the trade for logs, bots or command line output, e.g. `Swap 1.5 WETH → min
+    /// 2,912.45 USDC via WETH/USDC 0.05% (impact 0.12%)`
+    ///
+    /// The routes of a trade split across several routes are joined by `+`, and the hops of a
+    /// multi-hop route by `,`.
+    ///
+    /// ## Arguments
+    ///
+    /// * `options`: The precision, the slippage tolerance and the separators of the description
+    #[inline]
+    pub fn describe_with(&self, options: &TradeDescriptionOptions) -> Result<String, Error> {
+        let (input, output) = match (&options.slippage_tolerance, self.trade_type) {
+            (Some(slippage_tolerance), TradeType::ExactInput) => (
+                options.amount(&self.input_amount()?, Rounding::RoundDown)?,
+                format!(
+                    "min {}",
+                    options.amount(
+                        &self.minimum_amount_out(slippage_tolerance.clone(), None)?,
+                        Rounding::RoundDown,
+                    )?
+                ),
+            ),
+            (Some(slippage_tolerance), TradeType::ExactOutput) => (
+                format!(
+                    "max {}",
+                    options.amount(
+                        &self.maximum_amount_in(slippage_tolerance.clone(), None)?,
+                        Rounding::RoundUp,
+                    )?
+                ),
+                options.amount(&self.output_amount()?, Rounding::RoundDown)?,
+            ),
+            (None, _) => (
+                options.amount(&self.input_amount()?, Rounding::RoundDown)?,
+                options.amount(&self.output_amount()?, Rounding::RoundDown)?,
+            ),
+        };
+        let routes = self
+            .swaps
+            .iter()
+            .map(|Swap { route, .. }| {
+                let tokens = route.token_path();
+                route
+                    .pools
+                    .iter()
+                    .zip(tokens.windows(2))
+                    .map(|(pool, pair)| {
+                        format!(
+                            "{}/{} {}",
+                            currency_symbol(&pair[0]),
+                            currency_symbol(&pair[1]),
+                            options.fee(pool.fee)
+                        )
+                    })
+                    .collect::<Vec<_>>()
+                    .join(", ")
+            })
+            .collect::<Vec<_>>()
+            .join(" + ");
+        let impact = options.localize(&self.price_impact()?.to_fixed(2, None), false);
+        Ok(format!(
+            "Swap {input} → {output} via {routes} (impact {impact}%)"
+        ))
+    }
 }
 
 impl<TInput, TOutput, TP> Trade<TInput, TOutput, TP>
@@ -553,15 +1462,31 @@ where
         amount: CurrencyAmount<impl BaseCurrency>,
         trade_type: TradeType,
     ) -> Result<Self, Error> {
+        let trade = Self::new(
+            vec![Self::simulate_swap(route, amount, trade_type)?],
+            trade_type,
+        )?;
+        trade.verify_invariants()?;
+        Ok(trade)
+    }
+
+    /// Simulates a swap of the given amount through a route, without checking its invariants
+    fn simulate_swap(
+        route: Route<TInput, TOutput, TP>,
+        amount: CurrencyAmount<impl BaseCurrency>,
+        trade_type: TradeType,
+    ) -> Result<Swap<TInput, TOutput, TP>, Error> {
         let mut token_amount: CurrencyAmount<Token> = amount.wrapped_owned()?;
         let input_amount: CurrencyAmount<TInput>;
         let output_amount: CurrencyAmount<TOutput>;
         match trade_type {
             TradeType::ExactInput => {
-                assert!(
-                    amount.currency.wrapped().equals(route.input.wrapped()),
-                    "INPUT"
-                );
+                if !amount.currency.wrapped().equals(route.input.wrapped()) {
+                    return Err(Error::CurrencyMismatch {
+                        expected: route.input.wrapped().address(),
+                        actual: amount.currency.wrapped().address(),
+                    });
+                }
                 for pool in &route.pools {
                     token_amount = pool.get_output_amount(&token_amount, None)?;
                 }
@@ -577,10 +1502,12 @@ where
                 )?;
             }
             TradeType::ExactOutput => {
-                assert!(
-                    amount.currency.wrapped().equals(route.output.wrapped()),
-                    "OUTPUT"
-                );
+                if !amount.currency.wrapped().equals(route.output.wrapped()) {
+                    return Err(Error::CurrencyMismatch {
+                        expected: route.output.wrapped().address(),
+                        actual: amount.currency.wrapped().address(),
+                    });
+                }
                 for pool in route.pools.iter().rev() {
                     token_amount = pool.get_input_amount(&token_amount, None)?;
                 }
@@ -596,10 +1523,7 @@ where
                 )?;
             }
         }
-        Self::new(
-            vec![Swap::new(route, input_amount, output_amount)],
-            trade_type,
-        )
+        Ok(Swap::new(route, input_amount, output_amount))
     }
 
     /// Constructs a trade from routes by simulating swaps
@@ -617,12 +1541,13 @@ where
         )>,
         trade_type: TradeType,
     ) -> Result<Self, Error> {
-        let mut populated_routes: Vec<Swap<TInput, TOutput, TP>> = Vec::with_capacity(routes.len());
-        for (amount, route) in routes {
-            let trade = Self::from_route(route, amount, trade_type)?;
-            populated_routes.push(trade.swaps.into_iter().next().unwrap());
-        }
-        Self::new(populated_routes, trade_type)
+        let populated_routes = routes
+            .into_iter()
+            .map(|(amount, route)| Self::simulate_swap(route, amount, trade_type))
+            .collect::<Result<Vec<_>, _>>()?;
+        let trade = Self::new(populated_routes, trade_type)?;
+        trade.verify_invariants()?;
+        Ok(trade)
     }
 
     /// Given a list of pools, and a fixed amount in, returns the top `max_num_results` trades that
@@ -631,34 +1556,42 @@ where
     /// ## Note
     ///
     /// This does not consider aggregation, as routes are linear. It's possible a better route
-    /// exists by splitting the amount in among multiple routes.
+    /// exists by splitting the amount in among multiple routes, see
+    /// [`Trade::best_split_trade_exact_in`].
     ///
     /// ## Arguments
     ///
     /// * `pools`: The pools to consider in finding the best trade
     /// * `currency_amount_in`: The exact amount of input currency to spend
     /// * `currency_out`: The desired currency out
-    /// * `best_trade_options`: Maximum number of results to return and maximum number of hops a
-    ///   returned trade can make, e.g. 1 hop goes through a single pool
+    /// * `best_trade_options`: Maximum number of results to return, maximum number of hops a
+    ///   returned trade can make, e.g. 1 hop goes through a single pool, maximum sum of the fee
+    ///   tiers along a route and the ranking of the trades
     /// * `current_pools`: Used in recursion; the current list of pools
     /// * `next_amount_in`: Used in recursion; the original value of the currency_amount_in
     ///   parameter
     /// * `best_trades`: Used in recursion; the current list of best trades
     #[inline]
     #[allow(clippy::needless_pass_by_value)]
-    pub fn best_trade_exact_in<'a>(
-        pools: Vec<Pool<TP>>,
+    pub fn best_trade_exact_in<'a, R>(
+        mut pools: Vec<Pool<TP>>,
         currency_amount_in: &'a CurrencyAmount<TInput>,
         currency_out: &'a TOutput,
-        best_trade_options: BestTradeOptions,
+        best_trade_options: BestTradeOptions<R>,
         current_pools: Vec<Pool<TP>>,
         next_amount_in: Option<CurrencyAmount<&'a Token>>,
         best_trades: &'a mut Vec<Self>,
-    ) -> Result<&'a mut Vec<Self>, Error> {
+    ) -> Result<&'a mut Vec<Self>, Error>
+    where
+        R: TradeRanking<TInput, TOutput, TP> + Copy,
+    {
         assert!(!pools.is_empty(), "POOLS");
         let max_num_results = best_trade_options.max_num_results.unwrap_or(3);
         let max_hops = best_trade_options.max_hops.unwrap_or(3);
         assert!(max_hops > 0, "MAX_HOPS");
+        if current_pools.is_empty() {
+            best_trade_options.tie_breaker.canonicalize(&mut pools);
+        }
         let amount_in = match next_amount_in {
             Some(amount_in) => {
                 assert!(!current_pools.is_empty(), "INVALID_RECURSION");
@@ -672,9 +1605,19 @@ where
             if !pool.involves_token(&amount_in.currency) {
                 continue;
             }
+            // over the fee budget
+            if let Some(max_total_fee) = best_trade_options.max_total_fee {
+                let fee: U24 = pool.fee.into();
+                if total_fee(&current_pools)
+                    .checked_add(fee.to::<u32>())
+                    .is_none_or(|fee| fee > max_total_fee)
+                {
+                    continue;
+                }
+            }
             let amount_out = match pool.get_output_amount(&amount_in, None) {
                 Ok(amount_out) => amount_out,
-                Err(Error::InsufficientLiquidity) => continue,
+                Err(e) if matches!(e.root(), Error::InsufficientLiquidity) => continue,
                 Err(e) => return Err(e),
             };
             // we have arrived at the output token, so this is the final trade of one of the paths
@@ -690,7 +1633,7 @@ where
                     currency_amount_in.wrapped()?,
                     TradeType::ExactInput,
                 )?;
-                sorted_insert(best_trades, trade, max_num_results, trade_comparator);
+                best_trades.push(trade);
             } else if max_hops > 1 && pools.len() > 1 {
                 let pools_excluding_this_pool = pools
                     .iter()
@@ -709,6 +1652,7 @@ where
                     BestTradeOptions {
                         max_num_results: Some(max_num_results),
                         max_hops: Some(max_hops - 1),
+                        ..best_trade_options
                     },
                     next_pools,
                     Some(amount_out.wrapped()?),
@@ -716,6 +1660,7 @@ where
                 )?;
             }
         }
+        rank_trades(best_trades, &best_trade_options, max_num_results)?;
         Ok(best_trades)
     }
 
@@ -725,33 +1670,41 @@ where
     /// ## Note
     ///
     /// This does not consider aggregation, as routes are linear. It's possible a better route
-    /// exists by splitting the amount in among multiple routes.
+    /// exists by splitting the amount in among multiple routes, see
+    /// [`Trade::best_split_trade_exact_out`].
     ///
     /// ## Arguments
     ///
     /// * `pools`: The pools to consider in finding the best trade
     /// * `currency_in`: The currency to spend
     /// * `currency_amount_out`: The desired currency amount out
-    /// * `best_trade_options`: Maximum number of results to return and maximum number of hops a
-    ///   returned trade can make, e.g. 1 hop goes through a single pool
+    /// * `best_trade_options`: Maximum number of results to return, maximum number of hops a
+    ///   returned trade can make, e.g. 1 hop goes through a single pool, maximum sum of the fee
+    ///   tiers along a route and the ranking of the trades
     /// * `current_pools`: Used in recursion; the current list of pools
     /// * `next_amount_out`: Used in recursion; the exact amount of currency out
     /// * `best_trades`: Used in recursion; the current list of best trades
     #[inline]
     #[allow(clippy::needless_pass_by_value)]
-    pub fn best_trade_exact_out<'a>(
-        pools: Vec<Pool<TP>>,
+    pub fn best_trade_exact_out<'a, R>(
+        mut pools: Vec<Pool<TP>>,
         currency_in: &'a TInput,
         currency_amount_out: &'a CurrencyAmount<TOutput>,
-        best_trade_options: BestTradeOptions,
+        best_trade_options: BestTradeOptions<R>,
         current_pools: Vec<Pool<TP>>,
         next_amount_out: Option<CurrencyAmount<&'a Token>>,
         best_trades: &'a mut Vec<Self>,
-    ) -> Result<&'a mut Vec<Self>, Error> {
+    ) -> Result<&'a mut Vec<Self>, Error>
+    where
+        R: TradeRanking<TInput, TOutput, TP> + Copy,
+    {
         assert!(!pools.is_empty(), "POOLS");
         let max_num_results = best_trade_options.max_num_results.unwrap_or(3);
         let max_hops = best_trade_options.max_hops.unwrap_or(3);
         assert!(max_hops > 0, "MAX_HOPS");
+        if current_pools.is_empty() {
+            best_trade_options.tie_breaker.canonicalize(&mut pools);
+        }
         let amount_out = match next_amount_out {
             Some(amount_out) => {
                 assert!(!current_pools.is_empty(), "INVALID_RECURSION");
@@ -765,9 +1718,19 @@ where
             if !pool.involves_token(&amount_out.currency) {
                 continue;
             }
+            // over the fee budget
+            if let Some(max_total_fee) = best_trade_options.max_total_fee {
+                let fee: U24 = pool.fee.into();
+                if total_fee(&current_pools)
+                    .checked_add(fee.to::<u32>())
+                    .is_none_or(|fee| fee > max_total_fee)
+                {
+                    continue;
+                }
+            }
             let amount_in = match pool.get_input_amount(&amount_out, None) {
                 Ok(amount_in) => amount_in,
-                Err(Error::InsufficientLiquidity) => continue,
+                Err(e) if matches!(e.root(), Error::InsufficientLiquidity) => continue,
                 Err(e) => return Err(e),
             };
             // we have arrived at the input token, so this is the first trade of one of the paths
@@ -783,7 +1746,7 @@ where
                     currency_amount_out.wrapped()?,
                     TradeType::ExactOutput,
                 )?;
-                sorted_insert(best_trades, trade, max_num_results, trade_comparator);
+                best_trades.push(trade);
             } else if max_hops > 1 && pools.len() > 1 {
                 let pools_excluding_this_pool = pools
                     .iter()
@@ -802,6 +1765,7 @@ where
                     BestTradeOptions {
                         max_num_results: Some(max_num_results),
                         max_hops: Some(max_hops - 1),
+                        ..best_trade_options
                     },
                     next_pools,
                     Some(amount_in.wrapped()?),
@@ -809,6 +1773,7 @@ where
                 )?;
             }
         }
+        rank_trades(best_trades, &best_trade_options, max_num_results)?;
         Ok(best_trades)
     }
 }
@@ -1086,25 +2051,25 @@ mod tests {
         use super::*;
 
         #[test]
-        #[should_panic(expected = "INPUT_CURRENCY_MATCH")]
         fn throws_if_input_currency_does_not_match_route() {
-            let _ = Trade::create_unchecked_trade(
+            let result = Trade::create_unchecked_trade(
                 Route::new(vec![POOL_0_1.clone()], TOKEN0.clone(), TOKEN1.clone()),
                 CurrencyAmount::from_raw_amount(TOKEN2.clone(), 10000).unwrap(),
                 CurrencyAmount::from_raw_amount(TOKEN1.clone(), 10000).unwrap(),
                 TradeType::ExactInput,
             );
+            assert!(matches!(result, Err(Error::CurrencyMismatch { .. })));
         }
 
         #[test]
-        #[should_panic(expected = "OUTPUT_CURRENCY_MATCH")]
         fn throws_if_output_currency_does_not_match_route() {
-            let _ = Trade::create_unchecked_trade(
+            let result = Trade::create_unchecked_trade(
                 Route::new(vec![POOL_0_1.clone()], TOKEN0.clone(), TOKEN1.clone()),
                 CurrencyAmount::from_raw_amount(TOKEN0.clone(), 10000).unwrap(),
                 CurrencyAmount::from_raw_amount(TOKEN2.clone(), 10000).unwrap(),
                 TradeType::ExactInput,
             );
+            assert!(matches!(result, Err(Error::CurrencyMismatch { .. })));
         }
 
         #[test]
@@ -1134,9 +2099,8 @@ mod tests {
         use super::*;
 
         #[test]
-        #[should_panic(expected = "INPUT_CURRENCY_MATCH")]
         fn throws_if_input_currency_does_not_match_route_with_multiple_routes() {
-            let _ = Trade::create_unchecked_trade_with_multiple_routes(
+            let result = Trade::create_unchecked_trade_with_multiple_routes(
                 vec![
                     Swap {
                         route: Route::new(vec![POOL_1_2.clone()], TOKEN2.clone(), TOKEN1.clone()),
@@ -1154,14 +2118,13 @@ mod tests {
                     },
                 ],
                 TradeType::ExactInput,
-            )
-            .unwrap();
+            );
+            assert!(matches!(result, Err(Error::CurrencyMismatch { .. })));
         }
 
         #[test]
-        #[should_panic(expected = "OUTPUT_CURRENCY_MATCH")]
         fn throws_if_output_currency_does_not_match_route_with_multiple_routes() {
-            let _ = Trade::create_unchecked_trade_with_multiple_routes(
+            let result = Trade::create_unchecked_trade_with_multiple_routes(
                 vec![
                     Swap {
                         route: Route::new(vec![POOL_0_2.clone()], TOKEN0.clone(), TOKEN2.clone()),
@@ -1179,8 +2142,8 @@ mod tests {
                     },
                 ],
                 TradeType::ExactInput,
-            )
-            .unwrap();
+            );
+            assert!(matches!(result, Err(Error::CurrencyMismatch { .. })));
         }
 
         #[test]
@@ -1240,24 +2203,213 @@ mod tests {
         }
     }
 
-    mod route_and_swaps {
+    mod route_and_swaps {
+        use super::*;
+
+        static SINGLE_ROUTE: Lazy<Trade<Token, Token, TickListDataProvider>> = Lazy::new(|| {
+            Trade::create_unchecked_trade(
+                Route::new(
+                    vec![POOL_0_1.clone(), POOL_1_2.clone()],
+                    TOKEN0.clone(),
+                    TOKEN2.clone(),
+                ),
+                CurrencyAmount::from_raw_amount(TOKEN0.clone(), 100).unwrap(),
+                CurrencyAmount::from_raw_amount(TOKEN2.clone(), 69).unwrap(),
+                TradeType::ExactInput,
+            )
+            .unwrap()
+        });
+        static MULTI_ROUTE: Lazy<Trade<Token, Token, TickListDataProvider>> = Lazy::new(|| {
+            Trade::create_unchecked_trade_with_multiple_routes(
+                vec![
+                    Swap {
+                        route: Route::new(
+                            vec![POOL_0_1.clone(), POOL_1_2.clone()],
+                            TOKEN0.clone(),
+                            TOKEN2.clone(),
+                        ),
+                        input_amount: CurrencyAmount::from_raw_amount(TOKEN0.clone(), 50).unwrap(),
+                        output_amount: CurrencyAmount::from_raw_amount(TOKEN2.clone(), 35).unwrap(),
+                    },
+                    Swap {
+                        route: Route::new(vec![POOL_0_2.clone()], TOKEN0.clone(), TOKEN2.clone()),
+                        input_amount: CurrencyAmount::from_raw_amount(TOKEN0.clone(), 50).unwrap(),
+                        output_amount: CurrencyAmount::from_raw_amount(TOKEN2.clone(), 34).unwrap(),
+                    },
+                ],
+                TradeType::ExactInput,
+            )
+            .unwrap()
+        });
+
+        #[test]
+        fn can_access_route_for_single_route_trade() {
+            let _ = SINGLE_ROUTE.route();
+        }
+
+        #[test]
+        fn can_access_swaps_for_single_and_multi_route_trades() {
+            assert_eq!(SINGLE_ROUTE.swaps.len(), 1);
+            assert_eq!(MULTI_ROUTE.swaps.len(), 2);
+        }
+
+        #[test]
+        #[should_panic(expected = "MULTIPLE_ROUTES")]
+        fn throws_if_access_route_on_multi_route_trade() {
+            let _ = MULTI_ROUTE.route();
+        }
+    }
+
+    mod verify_invariants {
+        use super::*;
+
+        fn unchecked_trade(amount_out: u64) -> Trade<Token, Token, TickListDataProvider> {
+            Trade::create_unchecked_trade(
+                Route::new(vec![POOL_0_2.clone()], TOKEN0.clone(), TOKEN2.clone()),
+                CurrencyAmount::from_raw_amount(TOKEN0.clone(), 1000).unwrap(),
+                CurrencyAmount::from_raw_amount(TOKEN2.clone(), amount_out).unwrap(),
+                TradeType::ExactInput,
+            )
+            .unwrap()
+        }
+
+        #[test]
+        fn simulated_trades_satisfy_invariants() {
+            let route = Route::new(
+                vec![POOL_0_1.clone(), POOL_1_2.clone()],
+                TOKEN0.clone(),
+                TOKEN2.clone(),
+            );
+            for (amount, trade_type) in [
+                (
+                    CurrencyAmount::from_raw_amount(TOKEN0.clone(), 10000).unwrap(),
+                    TradeType::ExactInput,
+                ),
+                (
+                    CurrencyAmount::from_raw_amount(TOKEN2.clone(), 10000).unwrap(),
+                    TradeType::ExactOutput,
+                ),
+            ] {
+                let trade = Trade::from_route(route.clone(), amount.clone(), trade_type).unwrap();
+                assert!(trade.verify_invariants().is_ok());
+                let trade = Trade::from_routes(
+                    vec![
+                        (amount.clone(), route.clone()),
+                        (
+                            amount,
+                            Route::new(vec![POOL_0_2.clone()], TOKEN0.clone(), TOKEN2.clone()),
+                        ),
+                    ],
+                    trade_type,
+                )
+                .unwrap();
+                assert!(trade.verify_invariants().is_ok());
+            }
+            // the mid price is 1.1 and the fee 0.3%
+            assert!(unchecked_trade(1096).verify_invariants().is_ok());
+        }
+
+        #[test]
+        fn rejects_output_above_mid_price_quote() {
+            assert!(matches!(
+                unchecked_trade(1101).verify_invariants(),
+                Err(Error::TradeInvariantViolated(TradeInvariant::Conservation))
+            ));
+        }
+
+        #[test]
+        fn rejects_output_above_quote_net_of_fees() {
+            assert!(matches!(
+                unchecked_trade(1098).verify_invariants(),
+                Err(Error::TradeInvariantViolated(TradeInvariant::FeeBounds))
+            ));
+        }
+    }
+
+    mod estimate_gas_use {
         use super::*;
 
-        static SINGLE_ROUTE: Lazy<Trade<Token, Token, TickListDataProvider>> = Lazy::new(|| {
-            Trade::create_unchecked_trade(
+        #[test]
+        fn counts_hops_and_native_currency() {
+            let model = DefaultGasModel::default();
+            let two_hops = Trade::exact_in(
                 Route::new(
                     vec![POOL_0_1.clone(), POOL_1_2.clone()],
                     TOKEN0.clone(),
                     TOKEN2.clone(),
                 ),
-                CurrencyAmount::from_raw_amount(TOKEN0.clone(), 100).unwrap(),
-                CurrencyAmount::from_raw_amount(TOKEN2.clone(), 69).unwrap(),
-                TradeType::ExactInput,
+                CurrencyAmount::from_raw_amount(TOKEN0.clone(), 10000).unwrap(),
             )
-            .unwrap()
-        });
-        static MULTI_ROUTE: Lazy<Trade<Token, Token, TickListDataProvider>> = Lazy::new(|| {
-            Trade::create_unchecked_trade_with_multiple_routes(
+            .unwrap();
+            assert_eq!(two_hops.ticks_crossed().unwrap(), vec![vec![0, 0]]);
+            assert_eq!(
+                two_hops.estimate_gas_use().unwrap(),
+                model.base + 2 * model.per_hop
+            );
+
+            let from_ether = Trade::exact_out(
+                Route::new(vec![POOL_WETH_0.clone()], ETHER.clone(), TOKEN0.clone()),
+                CurrencyAmount::from_raw_amount(TOKEN0.clone(), 10000).unwrap(),
+            )
+            .unwrap();
+            assert_eq!(
+                from_ether.estimate_gas_use().unwrap(),
+                model.base + model.per_hop + model.wrap
+            );
+        }
+
+        #[test]
+        fn counts_initialized_ticks_crossed() {
+            // full range liquidity plus as much concentrated in [-60, 60]
+            let liquidity = 10_u128.pow(18);
+            let tick_spacing = FeeAmount::MEDIUM.tick_spacing();
+            let min_tick = nearest_usable_tick(MIN_TICK, tick_spacing).as_i32();
+            let max_tick = nearest_usable_tick(MAX_TICK, tick_spacing).as_i32();
+            let pool = Pool::new_with_tick_data_provider(
+                TOKEN0.clone(),
+                TOKEN1.clone(),
+                FeeAmount::MEDIUM,
+                encode_sqrt_ratio_x96(1, 1),
+                liquidity * 2,
+                TickListDataProvider::new(
+                    vec![
+                        Tick::new(min_tick, liquidity, liquidity as i128),
+                        Tick::new(-60, liquidity, liquidity as i128),
+                        Tick::new(60, liquidity, -(liquidity as i128)),
+                        Tick::new(max_tick, liquidity, -(liquidity as i128)),
+                    ],
+                    60,
+                ),
+            )
+            .unwrap();
+            let route = Route::new(vec![pool], TOKEN0.clone(), TOKEN1.clone());
+            let small = Trade::exact_in(
+                route.clone(),
+                CurrencyAmount::from_raw_amount(TOKEN0.clone(), 10_u128.pow(15)).unwrap(),
+            )
+            .unwrap();
+            let large = Trade::exact_in(
+                route,
+                CurrencyAmount::from_raw_amount(TOKEN0.clone(), 10_u128.pow(17)).unwrap(),
+            )
+            .unwrap();
+            assert_eq!(small.ticks_crossed().unwrap(), vec![vec![0]]);
+            assert_eq!(large.ticks_crossed().unwrap(), vec![vec![1]]);
+            let model = DefaultGasModel::default();
+            assert_eq!(
+                large.estimate_gas_use_with(&model).unwrap()
+                    - small.estimate_gas_use_with(&model).unwrap(),
+                model.per_initialized_tick
+            );
+        }
+    }
+
+    mod clone_with_slippage_applied {
+        use super::*;
+
+        #[test]
+        fn materializes_bounds_of_each_swap() {
+            let trade = Trade::create_unchecked_trade_with_multiple_routes(
                 vec![
                     Swap {
                         route: Route::new(
@@ -1276,24 +2428,40 @@ mod tests {
                 ],
                 TradeType::ExactInput,
             )
-            .unwrap()
-        });
-
-        #[test]
-        fn can_access_route_for_single_route_trade() {
-            let _ = SINGLE_ROUTE.route();
-        }
-
-        #[test]
-        fn can_access_swaps_for_single_and_multi_route_trades() {
-            assert_eq!(SINGLE_ROUTE.swaps.len(), 1);
-            assert_eq!(MULTI_ROUTE.swaps.len(), 2);
-        }
-
-        #[test]
-        #[should_panic(expected = "MULTIPLE_ROUTES")]
-        fn throws_if_access_route_on_multi_route_trade() {
-            let _ = MULTI_ROUTE.route();
+            .unwrap();
+            let bounded = trade
+                .clone_with_slippage_applied(Percent::new(10, 100))
+                .unwrap();
+            let single_hop_limit = sqrt_price_limit_from_slippage(
+                POOL_0_2
+                    .quote_output(&trade.swaps[1].input_amount, None)
+                    .unwrap()
+                    .sqrt_price_x96_after,
+                true,
+                &Percent::new(10, 100),
+            );
+            assert_eq!(
+                bounded,
+                BoundedTrade {
+                    trade_type: TradeType::ExactInput,
+                    max_in: U256::from(100),
+                    min_out: U256::from(62),
+                    swaps: vec![
+                        SwapBounds {
+                            max_in: U256::from(50),
+                            min_out: U256::from(31),
+                        },
+                        SwapBounds {
+                            max_in: U256::from(50),
+                            min_out: U256::from(30),
+                        },
+                    ],
+                    price_limits: vec![None, Some(single_hop_limit)],
+                }
+            );
+            assert!(bounded.is_satisfied_by(U256::from(100), U256::from(62)));
+            assert!(!bounded.is_satisfied_by(U256::from(100), U256::from(61)));
+            assert!(!bounded.is_satisfied_by(U256::from(101), U256::from(69)));
         }
     }
 
@@ -1554,12 +2722,13 @@ mod tests {
                     .unwrap()
                 });
 
+            #[cfg(not(feature = "no-cache"))]
             #[test]
             fn is_cached() {
                 let mut trade = EXACT_IN.clone();
                 assert_eq!(
                     trade.price_impact_cached().unwrap(),
-                    trade._price_impact.unwrap()
+                    *trade._price_impact.get().unwrap()
                 );
             }
 
@@ -1576,12 +2745,13 @@ mod tests {
                 );
             }
 
+            #[cfg(not(feature = "no-cache"))]
             #[test]
             fn is_cached_with_multiple_routes() {
                 let mut trade = EXACT_IN_MULTI_ROUTES.clone();
                 assert_eq!(
                     trade.price_impact_cached().unwrap(),
-                    trade._price_impact.unwrap()
+                    *trade._price_impact.get().unwrap()
                 );
             }
 
@@ -1650,12 +2820,13 @@ mod tests {
                     .unwrap()
                 });
 
+            #[cfg(not(feature = "no-cache"))]
             #[test]
             fn is_cached() {
                 let mut trade = EXACT_OUT.clone();
                 assert_eq!(
                     trade.price_impact_cached().unwrap(),
-                    trade._price_impact.unwrap()
+                    *trade._price_impact.get().unwrap()
                 );
             }
 
@@ -1672,12 +2843,13 @@ mod tests {
                 );
             }
 
+            #[cfg(not(feature = "no-cache"))]
             #[test]
             fn is_cached_with_multiple_routes() {
                 let mut trade = EXACT_OUT_MULTI_ROUTES.clone();
                 assert_eq!(
                     trade.price_impact_cached().unwrap(),
-                    trade._price_impact.unwrap()
+                    *trade._price_impact.get().unwrap()
                 );
             }
 
@@ -1723,6 +2895,7 @@ mod tests {
                 BestTradeOptions {
                     max_hops: Some(0),
                     max_num_results: None,
+                    ..Default::default()
                 },
                 vec![],
                 None,
@@ -1782,6 +2955,7 @@ mod tests {
                 BestTradeOptions {
                     max_hops: Some(1),
                     max_num_results: None,
+                    ..Default::default()
                 },
                 vec![],
                 None,
@@ -1796,6 +2970,221 @@ mod tests {
             );
         }
 
+        #[test]
+        fn respects_custom_ranking() {
+            // rank the longest routes first
+            let ranking =
+                |a: &Trade<Token, Token, TickListDataProvider>,
+                 b: &Trade<Token, Token, TickListDataProvider>| {
+                    b.swaps[0]
+                        .route
+                        .pools
+                        .len()
+                        .cmp(&a.swaps[0].route.pools.len())
+                };
+            let result = &mut vec![];
+            Trade::best_trade_exact_in(
+                vec![POOL_0_1.clone(), POOL_0_2.clone(), POOL_1_2.clone()],
+                &CurrencyAmount::from_raw_amount(TOKEN0.clone(), 10000).unwrap(),
+                &TOKEN2.clone(),
+                BestTradeOptions {
+                    max_num_results: Some(1),
+                    max_hops: None,
+                    max_total_fee: None,
+                    ranking,
+                    tie_breaker: TieBreaker::Discovery,
+                },
+                vec![],
+                None,
+                result,
+            )
+            .unwrap();
+            assert_eq!(result.len(), 1);
+            assert_eq!(
+                result[0].swaps[0].route.token_path(),
+                vec![TOKEN0.clone(), TOKEN1.clone(), TOKEN2.clone()]
+            );
+        }
+
+        #[test]
+        fn ranks_by_gas_model() {
+            let model = DefaultGasModel::default();
+            let search = |native_price: &Fraction| {
+                let result = &mut vec![];
+                Trade::best_trade_exact_in(
+                    vec![POOL_0_1.clone(), POOL_0_2.clone(), POOL_1_2.clone()],
+                    &CurrencyAmount::from_raw_amount(TOKEN0.clone(), 10000).unwrap(),
+                    &TOKEN2.clone(),
+                    BestTradeOptions {
+                        max_num_results: Some(1),
+                        max_hops: None,
+                        max_total_fee: None,
+                        ranking: GasModelRanking {
+                            model: &model,
+                            gas_price: U256::from(1),
+                            native_price,
+                        },
+                        tie_breaker: TieBreaker::Discovery,
+                    },
+                    vec![],
+                    None,
+                    result,
+                )
+                .unwrap();
+                result[0].swaps[0].route.pools.len()
+            };
+            // free gas ranks by output like the default ranking
+            let default_result = &mut vec![];
+            Trade::best_trade_exact_in(
+                vec![POOL_0_1.clone(), POOL_0_2.clone(), POOL_1_2.clone()],
+                &CurrencyAmount::from_raw_amount(TOKEN0.clone(), 10000).unwrap(),
+                &TOKEN2.clone(),
+                BestTradeOptions {
+                    max_num_results: Some(1),
+                    ..Default::default()
+                },
+                vec![],
+                None,
+                default_result,
+            )
+            .unwrap();
+            assert_eq!(
+                search(&Fraction::new(0, 1)),
+                default_result[0].swaps[0].route.pools.len()
+            );
+            // expensive gas favors the direct route
+            assert_eq!(search(&Fraction::new(1, 1)), 1);
+        }
+
+        #[test]
+        fn propagates_ranking_error() {
+            #[derive(Clone, Copy)]
+            struct FailingRanking;
+
+            impl TradeRanking<Token, Token, TickListDataProvider> for FailingRanking {
+                fn compare(
+                    &self,
+                    _: &Trade<Token, Token, TickListDataProvider>,
+                    _: &Trade<Token, Token, TickListDataProvider>,
+                ) -> Result<Ordering, Error> {
+                    Err(Error::InsufficientLiquidity)
+                }
+            }
+
+            let result = &mut vec![];
+            let err = Trade::best_trade_exact_in(
+                vec![POOL_0_1.clone(), POOL_0_2.clone(), POOL_1_2.clone()],
+                &CurrencyAmount::from_raw_amount(TOKEN0.clone(), 10000).unwrap(),
+                &TOKEN2.clone(),
+                BestTradeOptions {
+                    max_num_results: None,
+                    max_hops: None,
+                    max_total_fee: None,
+                    ranking: FailingRanking,
+                    tie_breaker: TieBreaker::Discovery,
+                },
+                vec![],
+                None,
+                result,
+            )
+            .unwrap_err();
+            assert!(matches!(err, Error::InsufficientLiquidity));
+        }
+
+        #[test]
+        fn breaks_ties_independently_of_pool_order() {
+            let search = |pools: Vec<Pool<TickListDataProvider>>, tie_breaker| {
+                let result = &mut vec![];
+                Trade::best_trade_exact_in(
+                    pools,
+                    &CurrencyAmount::from_raw_amount(TOKEN0.clone(), 10000).unwrap(),
+                    &TOKEN2.clone(),
+                    BestTradeOptions {
+                        max_num_results: None,
+                        max_hops: None,
+                        max_total_fee: None,
+                        // every trade ties
+                        ranking: |_: &Trade<Token, Token, TickListDataProvider>,
+                                  _: &Trade<Token, Token, TickListDataProvider>| {
+                            Ordering::Equal
+                        },
+                        tie_breaker,
+                    },
+                    vec![],
+                    None,
+                    result,
+                )
+                .unwrap();
+                result
+                    .iter()
+                    .map(|trade| trade.swaps[0].route.token_path())
+                    .collect::<Vec<_>>()
+            };
+            for tie_breaker in [TieBreaker::PoolAddresses, TieBreaker::Seeded(42)] {
+                let forward = search(
+                    vec![POOL_0_1.clone(), POOL_0_2.clone(), POOL_1_2.clone()],
+                    tie_breaker,
+                );
+                // reversed, with a duplicate pool
+                let backward = search(
+                    vec![
+                        POOL_1_2.clone(),
+                        POOL_0_2.clone(),
+                        POOL_0_1.clone(),
+                        POOL_0_2.clone(),
+                    ],
+                    tie_breaker,
+                );
+                assert_eq!(forward.len(), 2);
+                assert_eq!(forward, backward);
+            }
+        }
+
+        #[test]
+        fn respects_max_total_fee() {
+            let result = &mut vec![];
+            Trade::best_trade_exact_in(
+                vec![POOL_0_1.clone(), POOL_0_2.clone(), POOL_1_2.clone()],
+                &CurrencyAmount::from_raw_amount(TOKEN0.clone(), 10).unwrap(),
+                &TOKEN2.clone(),
+                BestTradeOptions {
+                    max_total_fee: Some(3000),
+                    ..Default::default()
+                },
+                vec![],
+                None,
+                result,
+            )
+            .unwrap();
+            assert_eq!(result.len(), 1);
+            assert_eq!(result[0].swaps[0].route.pools.len(), 1);
+            assert_eq!(result[0].total_fee(), 3000);
+        }
+
+        #[test]
+        fn total_fee_is_weighted_by_amount() {
+            let trade = Trade::from_routes(
+                vec![
+                    (
+                        CurrencyAmount::from_raw_amount(TOKEN0.clone(), 30).unwrap(),
+                        Route::new(vec![POOL_0_2.clone()], TOKEN0.clone(), TOKEN2.clone()),
+                    ),
+                    (
+                        CurrencyAmount::from_raw_amount(TOKEN0.clone(), 10).unwrap(),
+                        Route::new(
+                            vec![POOL_0_1.clone(), POOL_1_2.clone()],
+                            TOKEN0.clone(),
+                            TOKEN2.clone(),
+                        ),
+                    ),
+                ],
+                TradeType::ExactInput,
+            )
+            .unwrap();
+            // (3000 * 30 + 6000 * 10) / 40
+            assert_eq!(trade.total_fee(), 3750);
+        }
+
         #[test]
         fn insufficient_input_for_one_pool() {
             let result = &mut vec![];
@@ -1831,6 +3220,7 @@ mod tests {
                 BestTradeOptions {
                     max_hops: None,
                     max_num_results: Some(1),
+                    ..Default::default()
                 },
                 vec![],
                 None,
@@ -2196,6 +3586,7 @@ mod tests {
                 BestTradeOptions {
                     max_hops: Some(0),
                     max_num_results: None,
+                    ..Default::default()
                 },
                 vec![],
                 None,
@@ -2255,6 +3646,7 @@ mod tests {
                 BestTradeOptions {
                     max_hops: Some(1),
                     max_num_results: None,
+                    ..Default::default()
                 },
                 vec![],
                 None,
@@ -2269,6 +3661,27 @@ mod tests {
             );
         }
 
+        #[test]
+        fn respects_max_total_fee() {
+            let result = &mut vec![];
+            Trade::best_trade_exact_out(
+                vec![POOL_0_1.clone(), POOL_0_2.clone(), POOL_1_2.clone()],
+                &TOKEN0.clone(),
+                &CurrencyAmount::from_raw_amount(TOKEN2.clone(), 10).unwrap(),
+                BestTradeOptions {
+                    max_total_fee: Some(6000),
+                    ..Default::default()
+                },
+                vec![],
+                None,
+                result,
+            )
+            .unwrap();
+            assert_eq!(result.len(), 2);
+            assert_eq!(result[1].swaps[0].route.pools.len(), 2);
+            assert_eq!(result[1].total_fee(), 6000);
+        }
+
         #[test]
         fn insufficient_liquidity() {
             let result = &mut vec![];
@@ -2311,6 +3724,7 @@ mod tests {
                 BestTradeOptions {
                     max_hops: None,
                     max_num_results: Some(1),
+                    ..Default::default()
                 },
                 vec![],
                 None,
@@ -2412,4 +3826,138 @@ mod tests {
             assert_eq!(result[1].output_amount().unwrap().currency, ETHER.clone());
         }
     }
+
+    mod describe {
+        use super::*;
+
+        static POOL_WETH_USDC: Lazy<Pool<TickListDataProvider>> = Lazy::new(|| {
+            v2_style_pool(
+                CurrencyAmount::from_raw_amount(USDC.clone(), 3_000_000 * 10_u128.pow(6)).unwrap(),
+                CurrencyAmount::from_raw_amount(WETH.clone(), 1000 * 10_u128.pow(18)).unwrap(),
+                None,
+            )
+        });
+
+        fn trade(trade_type: TradeType) -> Trade<Ether, Token, TickListDataProvider> {
+            Trade::create_unchecked_trade(
+                Route::new(vec![POOL_WETH_USDC.clone()], ETHER.clone(), USDC.clone()),
+                CurrencyAmount::from_raw_amount(ETHER.clone(), 15 * 10_u128.pow(17)).unwrap(),
+                CurrencyAmount::from_raw_amount(USDC.clone(), 4_486_500_000_u128).unwrap(),
+                trade_type,
+            )
+            .unwrap()
+        }
+
+        #[test]
+        fn describes_exact_input() {
+            assert_eq!(
+                trade(TradeType::ExactInput).describe().unwrap(),
+                "Swap 1.5 ETH → 4,486.5 USDC via WETH/USDC 0.3% (impact 0.30%)"
+            );
+        }
+
+        #[test]
+        fn describes_minimum_amount_out() {
+            let options = TradeDescriptionOptions {
+                slippage_tolerance: Some(Percent::new(5, 1000)),
+                ..Default::default()
+            };
+            assert_eq!(
+                trade(TradeType::ExactInput)
+                    .describe_with(&options)
+                    .unwrap(),
+                "Swap 1.5 ETH → min 4,464.17 USDC via WETH/USDC 0.3% (impact 0.30%)"
+            );
+        }
+
+        #[test]
+        fn describes_maximum_amount_in_with_localized_separators() {
+            let options = TradeDescriptionOptions {
+                significant_digits: 3,
+                slippage_tolerance: Some(Percent::new(1, 100)),
+                group_separator: Some('.'),
+                decimal_separator: ',',
+            };
+            assert_eq!(
+                trade(TradeType::ExactOutput)
+                    .describe_with(&options)
+                    .unwrap(),
+                "Swap max 1,52 ETH → 4.480 USDC via WETH/USDC 0,3% (impact 0,30%)"
+            );
+        }
+
+        #[test]
+        fn describes_multi_hop_routes() {
+            let trade = Trade::create_unchecked_trade(
+                Route::new(
+                    vec![POOL_0_1.clone(), POOL_1_2.clone()],
+                    TOKEN0.clone(),
+                    TOKEN2.clone(),
+                ),
+                CurrencyAmount::from_raw_amount(TOKEN0.clone(), 10_u128.pow(18)).unwrap(),
+                CurrencyAmount::from_raw_amount(TOKEN2.clone(), 8 * 10_u128.pow(17)).unwrap(),
+                TradeType::ExactInput,
+            )
+            .unwrap();
+            assert!(trade
+                .describe()
+                .unwrap()
+                .starts_with("Swap 1 t0 → 0.8 t2 via t0/t1 0.3%, t1/t2 0.3% (impact "));
+        }
+    }
+
+    mod gas_adjusted_trade_comparator {
+        use super::*;
+
+        fn trades() -> Vec<Trade<Token, Token, TickListDataProvider>> {
+            let mut result = vec![];
+            Trade::best_trade_exact_in(
+                vec![POOL_0_1.clone(), POOL_0_2.clone(), POOL_1_2.clone()],
+                &CurrencyAmount::from_raw_amount(TOKEN0.clone(), 100).unwrap(),
+                &TOKEN2.clone(),
+                BestTradeOptions::default(),
+                vec![],
+                None,
+                &mut result,
+            )
+            .unwrap();
+            result
+        }
+
+        #[test]
+        fn ranks_by_output_net_of_gas() {
+            let mut trades = trades();
+            assert_eq!(trades.len(), 2);
+            assert_eq!(trades[0].swaps[0].route.pools.len(), 1);
+            // one wei is worth 1/10000 of the smallest unit of the output
+            let mut costs = RouteGasCosts::new(U256::from(1), Fraction::new(1, 10000));
+            costs.insert(&trades[0].swaps[0].route, U256::from(1_000_000));
+            costs.insert(&trades[1].swaps[0].route, U256::from(100_000));
+            assert_eq!(costs.trade_gas(&trades[0]), Some(U256::from(1_000_000)));
+            let direct_output = trades[0].output_amount().unwrap().as_fraction();
+            assert_eq!(
+                costs.gas_adjusted_amount(&trades[0]).unwrap().unwrap(),
+                direct_output - Fraction::new(100, 1)
+            );
+
+            assert_eq!(
+                gas_adjusted_trade_comparator(&trades[0], &trades[1], &costs).unwrap(),
+                Ordering::Greater
+            );
+            trades.sort_by(|a, b| gas_adjusted_trade_comparator(a, b, &costs).unwrap());
+            assert_eq!(trades[0].swaps[0].route.pools.len(), 2);
+            assert_eq!(trades[1].swaps[0].route.pools.len(), 1);
+        }
+
+        #[test]
+        fn falls_back_without_estimates() {
+            let mut trades = trades();
+            let mut costs = RouteGasCosts::new(U256::from(1), Fraction::new(1, 10000));
+            costs.insert(&trades[0].swaps[0].route, U256::from(1_000_000));
+            assert_eq!(costs.trade_gas(&trades[1]), None);
+            trades.reverse();
+            trades.sort_by(|a, b| gas_adjusted_trade_comparator(a, b, &costs).unwrap());
+            assert_eq!(trades[0].swaps[0].route.pools.len(), 1);
+        }
+    }
 }
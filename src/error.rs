@@ -6,19 +6,35 @@ use crate::prelude::*;
 #[cfg(feature = "extensions")]
 use alloy::contract::Error as ContractError;
 #[cfg(feature = "extensions")]
+use alloy_primitives::{aliases::U24, B256};
+#[cfg(feature = "extensions")]
 use uniswap_lens::error::Error as LensError;
 
-use alloy_primitives::{aliases::I24, U160};
+use alloy_primitives::{aliases::I24, Address, ChainId, Selector, U160, U256};
 use uniswap_sdk_core::error::Error as CoreError;
 
 #[derive(Debug, thiserror::Error)]
-#[cfg_attr(not(feature = "extensions"), derive(Clone, Copy, Hash, PartialEq, Eq))]
+#[cfg_attr(not(feature = "extensions"), derive(Clone, Hash, PartialEq, Eq))]
+#[cfg_attr(not(any(feature = "std", feature = "extensions")), derive(Copy))]
 pub enum Error {
     /// Thrown when an error occurs in the core library.
     #[error("{0}")]
     Core(#[from] CoreError),
 
-    /// Thrown when the token passed to [`Pool::price_of`] is not one of the pool's tokens.
+    /// An error with the pool, tick or amount it occurred with, see [`Error::with_context`]. Only
+    /// available with the `std` feature, since it boxes the error.
+    #[cfg(feature = "std")]
+    #[error("{context}: {source}")]
+    Context {
+        /// The pool, tick and amount the error occurred with.
+        context: ErrorContext,
+        /// The error the context is attached to.
+        #[source]
+        source: Box<Self>,
+    },
+
+    /// Thrown when the token passed to [`Pool::price_of`] is not one of the pool's tokens, or by
+    /// [`plan_fee_tier_migration`] when the target pool is not of the pair of the position.
     #[error("Invalid token")]
     InvalidToken,
 
@@ -32,6 +48,11 @@ pub enum Error {
     #[error("Invalid square root price: {0}")]
     InvalidSqrtPrice(U160),
 
+    /// Thrown by [`Pool::validate_price_limit`] when a price limit is out of bounds or on the
+    /// wrong side of the current price for the direction of the swap.
+    #[error("Invalid price limit: {0}")]
+    InvalidPriceLimit(U160),
+
     #[error("Invalid price or liquidity")]
     InvalidPriceOrLiquidity,
 
@@ -47,9 +68,20 @@ pub enum Error {
     #[error("Overflow when casting to U160")]
     SafeCastToU160Overflow,
 
+    /// Thrown when a [`BigInt`](num_bigint::BigInt) amount is negative or does not fit in 256 bits.
+    #[error("Overflow when casting to U256")]
+    SafeCastToU256Overflow,
+
+    /// Thrown when a price or its sqrt ratio does not fit in the range of [`U160`] sqrt ratios, e.g.
+    /// by [`try_encode_sqrt_ratio_x96`] for tokens whose decimals differ widely.
     #[error("Overflow in price calculation")]
     PriceOverflow,
 
+    /// Thrown by [`Position::from_amounts`] when the liquidity for the amounts does not fit in 128
+    /// bits, e.g. for large amounts of tokens with 24 or more decimals.
+    #[error("Overflow when casting liquidity to u128")]
+    LiquidityOverflow,
+
     #[error("Insufficient liquidity")]
     InsufficientLiquidity,
 
@@ -59,7 +91,144 @@ pub enum Error {
     #[error("{0}")]
     TickListError(#[from] TickListError),
 
-    #[cfg(feature = "extensions")]
+    /// Thrown when a tick data provider would load more ticks or span more tick bitmap words than
+    /// allowed by its [`TickDataProviderLimits`].
+    #[error("Tick data provider limits exceeded")]
+    TickLimitExceeded,
+
+    /// Thrown by [`validate_swap_route`] when the native input or output of a route is
+    /// inconsistent with the swap options or the recipient of the swap call.
+    #[error("Native currency is inconsistent with the swap options")]
+    NativeCurrencyMismatch,
+
+    /// Thrown when tokens, pools or routes that must be on the same chain are not.
+    #[error("Chain mismatch: expected {expected}, actual {actual}")]
+    ChainMismatch {
+        /// The chain required.
+        expected: ChainId,
+        /// The chain given.
+        actual: ChainId,
+    },
+
+    /// Thrown when a currency does not match the currency it is required to be, compared by the
+    /// address of the wrapped token.
+    #[error("Currency mismatch: expected {expected}, actual {actual}")]
+    CurrencyMismatch {
+        /// The address of the wrapped token of the currency required.
+        expected: Address,
+        /// The address of the wrapped token of the currency given.
+        actual: Address,
+    },
+
+    /// Thrown when the trades to encode or execute, or the routes to split an amount across, are
+    /// empty.
+    #[error("No trades")]
+    NoTrades,
+
+    /// Thrown by [`Route::try_new`] when no pool is given.
+    #[error("Route has no pools")]
+    EmptyRoute,
+
+    /// Thrown by [`Trade::best_split_trade_exact_in`] and [`Trade::best_split_trade_exact_out`]
+    /// when the distribution percent is not between 1 and 100 or the maximum number of splits is
+    /// zero, and by [`plan_slices`] when the number of slices is zero.
+    #[error("Invalid split")]
+    InvalidSplit,
+
+    /// Thrown by [`Route::try_new`] when the pools do not form a path from the input, i.e. the
+    /// pool at `index` does not contain `token`, the token reached through the previous pools.
+    #[error("Path mismatch: pool {index} does not contain {token}")]
+    PathMismatch {
+        /// The index of the pool in the route.
+        index: usize,
+        /// The token reached through the previous pools.
+        token: Address,
+    },
+
+    /// Thrown by [`Route::mid_price_at_sqrt_ratios`] when the number of sqrt prices is not the
+    /// number of pools, or by [`add_call_parameters_batch`] and [`remove_call_parameters_batch`]
+    /// when the number of options is not the number of positions.
+    #[error("Length mismatch: expected {expected}, actual {actual}")]
+    LengthMismatch {
+        /// The length required.
+        expected: usize,
+        /// The length given.
+        actual: usize,
+    },
+
+    /// Thrown by [`Route::round_trip`] when the amount swapped is zero, for which the asymmetry
+    /// is undefined.
+    #[error("Zero amount")]
+    ZeroAmount,
+
+    /// Thrown when two states compared or merged are not of the same pool, compared by address.
+    #[error("Pool mismatch: expected {expected}, actual {actual}")]
+    PoolMismatch {
+        /// The address of the pool of the first state.
+        expected: Address,
+        /// The address of the pool of the other state.
+        actual: Address,
+    },
+
+    /// Thrown by [`swap_call_parameters`] when per-trade recipients are given for more trades than
+    /// there are, or when the router takes custody of the output and cannot honor them.
+    #[error("Invalid recipient override")]
+    InvalidRecipientOverride,
+
+    /// Thrown by [`swap_call_parameters`] when [`MulticallWrapping::Never`] is set but the swap
+    /// encodes to the given number of calls.
+    #[error("Multicall required for {0} calls")]
+    MulticallRequired(usize),
+
+    /// Thrown by [`swap_call_parameters`] when the [`RouterTarget`] uses the
+    /// [`SwapAbi::SwapRouter`] encoding but no deadline is given.
+    #[error("Deadline required")]
+    DeadlineRequired,
+
+    /// Thrown by [`validate_fee_options`] when the fee is to be sent to the zero address.
+    #[error("Invalid fee recipient")]
+    InvalidFeeRecipient,
+
+    /// Thrown by [`validate_fee_options`] when the fee is zero or exceeds [`MAX_FEE_BIPS`].
+    #[error("Invalid fee bips: {0}")]
+    InvalidFeeBips(U256),
+
+    /// Thrown by [`swap_call_parameters`] when the fee is to be taken in the wrapped native token
+    /// but no fee is taken or the output is not native.
+    #[error("Invalid fee currency")]
+    InvalidFeeCurrency,
+
+    /// Thrown by [`validate_swap_route`] when a fee is taken but the router does not take custody
+    /// of the output.
+    #[error("Fee taken without router custody of the output")]
+    FeeWithoutCustody,
+
+    /// Thrown when the calls encoded for a [`RouterTarget`] include a function it does not
+    /// implement.
+    #[error("Unsupported router call: {0}")]
+    UnsupportedRouterCall(Selector),
+
+    /// Thrown when the calls encoded for a position manager configured by [`NpmConfig`] need to
+    /// sweep tokens but it does not support `sweepToken`.
+    #[error("Sweep not supported by position manager {0}")]
+    SweepNotSupported(Address),
+
+    /// Thrown by [`Trade::verify_invariants`] when a swap of the trade is impossible, e.g. because
+    /// it was simulated on corrupted tick data.
+    #[error("Trade invariant violated: {0}")]
+    TradeInvariantViolated(#[source] TradeInvariant),
+
+    /// Thrown by [`decode_path`] when the length of an encoded path is not that of at least one
+    /// pool, i.e. 20 bytes per token and 3 bytes per fee.
+    #[error("Invalid path length: {0}")]
+    InvalidPath(usize),
+
+    /// Thrown when a preset such as [`KnownPool`] is not available on the given chain.
+    #[error("Unsupported chain: {0}")]
+    UnsupportedChain(ChainId),
+
+    /// Thrown when a range of ticks is empty or out of bounds, e.g. by
+    /// [`RebalancePlan::for_amounts`] when the lower tick is not below the upper tick.
     #[error("Invalid tick range")]
     InvalidRange,
 
@@ -74,6 +243,198 @@ pub enum Error {
     #[cfg(feature = "extensions")]
     #[error("Invalid access list")]
     InvalidAccessList,
+
+    /// Thrown by [`verify_router_target`] when there is no contract at the address of the router.
+    #[cfg(feature = "extensions")]
+    #[error("Router not deployed: {0}")]
+    RouterNotDeployed(Address),
+
+    /// Thrown by [`get_fee_amount_tick_spacing`] when the factory has not enabled the fee.
+    #[cfg(feature = "extensions")]
+    #[error("Fee not enabled: {0}")]
+    FeeNotEnabled(U24),
+
+    /// Thrown by [`PositionHistory::apply_event`] when a `DecreaseLiquidity` event removes more
+    /// liquidity than the position has, e.g. because earlier events were missed.
+    #[cfg(feature = "extensions")]
+    #[error("Liquidity underflow: {liquidity} removed from {available}")]
+    LiquidityUnderflow {
+        /// The liquidity removed by the event.
+        liquidity: u128,
+        /// The liquidity of the position before the event.
+        available: u128,
+    },
+
+    /// Thrown by [`get_candles`] when the node does not return a block whose timestamp is needed.
+    #[cfg(feature = "extensions")]
+    #[error("Block not found: {0}")]
+    BlockNotFound(u64),
+
+    /// Thrown by [`aggregate_candles`] and [`get_candles`] when the width of a candle is zero.
+    #[cfg(feature = "extensions")]
+    #[error("Invalid candle interval")]
+    InvalidInterval,
+
+    /// Thrown by [`OracleReplica`] when the target timestamp predates the oldest observation.
+    #[cfg(feature = "extensions")]
+    #[error("Observation too old: {0}")]
+    ObservationTooOld(u32),
+
+    /// Thrown by [`verify_permit_domain`] when the EIP-712 domain or permit type of the position
+    /// manager differs from the one constructed locally.
+    #[cfg(feature = "extensions")]
+    #[error("Permit domain mismatch: expected {expected}, actual {actual}")]
+    PermitDomainMismatch {
+        /// The domain separator or permit type hash constructed locally.
+        expected: B256,
+        /// The domain separator or permit type hash of the position manager.
+        actual: B256,
+    },
+
+    /// Thrown by [`EphemeralTickDataProvider::refresh`] when the block to refresh to precedes the
+    /// block the ticks were fetched at.
+    #[cfg(feature = "extensions")]
+    #[error("Block {requested} before fetched block {fetched}")]
+    BlockBeforeFetched {
+        /// The block the ticks were fetched at.
+        fetched: u64,
+        /// The block to refresh to.
+        requested: u64,
+    },
+}
+
+impl Error {
+    /// Attaches the pool, tick or amount the error occurred with, shown by its [`Display`]
+    /// implementation before the error, which is also its [`source`](core::error::Error::source).
+    /// Returns the error unchanged without the `std` feature, under which [`Error`] is also `Copy`
+    /// unless the `extensions` feature is enabled.
+    ///
+    /// ## Arguments
+    ///
+    /// * `context`: The context of the error
+    ///
+    /// [`Display`]: core::fmt::Display
+    #[inline]
+    #[must_use]
+    #[cfg_attr(not(feature = "std"), allow(clippy::missing_const_for_fn))]
+    pub fn with_context(self, context: ErrorContext) -> Self {
+        #[cfg(feature = "std")]
+        {
+            Self::Context {
+                context,
+                source: Box::new(self),
+            }
+        }
+        #[cfg(not(feature = "std"))]
+        {
+            let _ = context;
+            self
+        }
+    }
+
+    /// Returns the error without the context attached by [`Error::with_context`], e.g. to match
+    /// on its variant
+    #[inline]
+    #[must_use]
+    #[cfg_attr(not(feature = "std"), allow(clippy::missing_const_for_fn))]
+    pub fn root(&self) -> &Self {
+        #[cfg(feature = "std")]
+        if let Self::Context { source, .. } = self {
+            return source.root();
+        }
+        self
+    }
+}
+
+/// The pool, tick and amount an [`Error`] occurred with, see [`Error::with_context`].
+#[derive(Clone, Copy, Debug, Default, Hash, PartialEq, Eq)]
+pub struct ErrorContext {
+    /// The address of the pool, if any.
+    pub pool: Option<Address>,
+    /// The tick, e.g. the current tick of a swap step, if any.
+    pub tick: Option<i32>,
+    /// The amount, e.g. of a swap, if any.
+    pub amount: Option<U256>,
+}
+
+impl ErrorContext {
+    /// Creates an empty context
+    #[inline]
+    #[must_use]
+    pub const fn new() -> Self {
+        Self {
+            pool: None,
+            tick: None,
+            amount: None,
+        }
+    }
+
+    /// Sets the pool the error occurred with
+    ///
+    /// ## Arguments
+    ///
+    /// * `pool`: The address of the pool
+    #[inline]
+    #[must_use]
+    pub const fn with_pool(mut self, pool: Address) -> Self {
+        self.pool = Some(pool);
+        self
+    }
+
+    /// Sets the tick the error occurred at
+    ///
+    /// ## Arguments
+    ///
+    /// * `tick`: The tick
+    #[inline]
+    #[must_use]
+    pub const fn with_tick(mut self, tick: i32) -> Self {
+        self.tick = Some(tick);
+        self
+    }
+
+    /// Sets the amount the error occurred with
+    ///
+    /// ## Arguments
+    ///
+    /// * `amount`: The amount, e.g. of a swap
+    #[inline]
+    #[must_use]
+    pub const fn with_amount(mut self, amount: U256) -> Self {
+        self.amount = Some(amount);
+        self
+    }
+}
+
+impl core::fmt::Display for ErrorContext {
+    fn fmt(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
+        let mut separator = "";
+        if let Some(pool) = self.pool {
+            write!(f, "pool {pool}")?;
+            separator = ", ";
+        }
+        if let Some(tick) = self.tick {
+            write!(f, "{separator}tick {tick}")?;
+            separator = ", ";
+        }
+        if let Some(amount) = self.amount {
+            write!(f, "{separator}amount {amount}")?;
+        }
+        Ok(())
+    }
+}
+
+/// The invariants every swap of a trade satisfies, checked by [`Trade::verify_invariants`].
+#[derive(Clone, Copy, Debug, Hash, PartialEq, Eq, thiserror::Error)]
+pub enum TradeInvariant {
+    /// The output does not exceed the input quoted at the mid price of the route, i.e. the price
+    /// impact is not negative.
+    #[error("Output exceeds the mid price quote")]
+    Conservation,
+    /// The output does not exceed the input quoted at the mid price of the route net of the fee
+    /// of every pool.
+    #[error("Output exceeds the quote net of fees")]
+    FeeBounds,
 }
 
 #[derive(Clone, Copy, Debug, Hash, PartialEq, Eq, thiserror::Error)]
@@ -92,3 +453,39 @@ impl From<alloy::transports::TransportError> for Error {
         Self::ContractError(ContractError::TransportError(e))
     }
 }
+
+#[cfg(all(test, feature = "std"))]
+mod tests {
+    use super::*;
+    use std::error::Error as _;
+
+    #[test]
+    fn test_context_chaining() {
+        let pool = alloy_primitives::address!("88e6A0c2dDD26FEEb64F039a2c41296FcB3f5640");
+        let error = Error::InsufficientLiquidity.with_context(
+            ErrorContext::new()
+                .with_pool(pool)
+                .with_tick(-10)
+                .with_amount(U256::from(100)),
+        );
+        assert_eq!(
+            error.to_string(),
+            format!("pool {pool}, tick -10, amount 100: Insufficient liquidity")
+        );
+        assert_eq!(
+            error.source().unwrap().to_string(),
+            Error::InsufficientLiquidity.to_string()
+        );
+        assert!(matches!(error.root(), Error::InsufficientLiquidity));
+    }
+
+    #[test]
+    fn test_source_chaining() {
+        let error = Error::from(TickListError::NotContained);
+        assert_eq!(error.to_string(), "Not contained in tick list");
+        assert_eq!(
+            error.source().unwrap().to_string(),
+            "Not contained in tick list"
+        );
+    }
+}
@@ -0,0 +1,295 @@
+//! ## Candles
+//! Aggregates the `Swap` events of a pool over a block range into OHLCV candles, priced via the
+//! `sqrtPriceX96` emitted by each swap, without requiring a separate indexer.
+
+use crate::prelude::{Error, IUniswapV3PoolEvents::Swap, *};
+use alloc::{collections::BTreeMap, vec::Vec};
+use alloy::{
+    contract::Error as ContractError,
+    eips::BlockNumberOrTag,
+    providers::Provider,
+    rpc::types::{BlockTransactionsKind, Filter},
+    transports::Transport,
+};
+use alloy_primitives::{Address, I256, U160, U256};
+use alloy_sol_types::SolEvent;
+use uniswap_sdk_core::prelude::*;
+
+/// The maximum number of blocks to query logs for in a single `eth_getLogs` request.
+pub(crate) const LOG_CHUNK_SIZE: u64 = 2000;
+
+/// The width of a candle.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+pub enum CandleInterval {
+    /// A candle spans the given number of blocks.
+    Blocks(u64),
+    /// A candle spans the given number of seconds, based on the block timestamps.
+    Seconds(u64),
+}
+
+impl CandleInterval {
+    #[inline]
+    const fn size(self) -> u64 {
+        match self {
+            Self::Blocks(size) | Self::Seconds(size) => size,
+        }
+    }
+}
+
+/// An OHLCV candle. Prices are kept as `sqrtPriceX96` of token1 in terms of token0, which is
+/// monotonic in the price, so the high and low are exact.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct Candle {
+    /// The first block number or timestamp covered by the candle, depending on the interval.
+    pub start: u64,
+    /// The `sqrtPriceX96` after the first swap of the candle.
+    pub open: U160,
+    /// The highest `sqrtPriceX96` after a swap of the candle.
+    pub high: U160,
+    /// The lowest `sqrtPriceX96` after a swap of the candle.
+    pub low: U160,
+    /// The `sqrtPriceX96` after the last swap of the candle.
+    pub close: U160,
+    /// The absolute amount of token0 swapped.
+    pub volume0: U256,
+    /// The absolute amount of token1 swapped.
+    pub volume1: U256,
+    /// The number of swaps in the candle.
+    pub swaps: u64,
+}
+
+impl Candle {
+    #[inline]
+    fn new(start: u64, swap: &Swap) -> Self {
+        Self {
+            start,
+            open: swap.sqrtPriceX96,
+            high: swap.sqrtPriceX96,
+            low: swap.sqrtPriceX96,
+            close: swap.sqrtPriceX96,
+            volume0: swap.amount0.unsigned_abs(),
+            volume1: swap.amount1.unsigned_abs(),
+            swaps: 1,
+        }
+    }
+
+    #[inline]
+    fn update(&mut self, swap: &Swap) {
+        self.high = self.high.max(swap.sqrtPriceX96);
+        self.low = self.low.min(swap.sqrtPriceX96);
+        self.close = swap.sqrtPriceX96;
+        self.volume0 += swap.amount0.unsigned_abs();
+        self.volume1 += swap.amount1.unsigned_abs();
+        self.swaps += 1;
+    }
+
+    /// Converts the open, high, low and close of the candle to prices of `base_token` in
+    /// `quote_token`.
+    ///
+    /// Note that the high and low are swapped when `base_token` is token1 of the pool.
+    ///
+    /// ## Arguments
+    ///
+    /// * `base_token`: The base token of the price
+    /// * `quote_token`: The quote token of the price
+    #[inline]
+    pub fn prices(
+        &self,
+        base_token: &Token,
+        quote_token: &Token,
+    ) -> Result<[Price<Token, Token>; 4], Error> {
+        let price = |sqrt_ratio_x96| {
+            sqrt_ratio_x96_to_price(sqrt_ratio_x96, base_token.clone(), quote_token.clone())
+        };
+        Ok([
+            price(self.open)?,
+            price(self.high)?,
+            price(self.low)?,
+            price(self.close)?,
+        ])
+    }
+}
+
+/// Aggregates swaps into candles of `interval_size` blocks or seconds.
+///
+/// ## Arguments
+///
+/// * `swaps`: The swaps keyed by block number or timestamp, in the order they were executed
+/// * `interval_size`: The width of a candle in the same unit as the keys, [`Error::InvalidInterval`]
+///   if zero
+#[inline]
+pub fn aggregate_candles<'a, I>(swaps: I, interval_size: u64) -> Result<Vec<Candle>, Error>
+where
+    I: IntoIterator<Item = (u64, &'a Swap)>,
+{
+    if interval_size == 0 {
+        return Err(Error::InvalidInterval);
+    }
+    let mut candles: Vec<Candle> = Vec::new();
+    for (key, swap) in swaps {
+        let start = key - key % interval_size;
+        match candles.last_mut() {
+            Some(candle) if candle.start == start => candle.update(swap),
+            _ => candles.push(Candle::new(start, swap)),
+        }
+    }
+    Ok(candles)
+}
+
+/// Fetches the `Swap` events of a pool in a block range and aggregates them into candles
+///
+/// ## Arguments
+///
+/// * `pool`: The pool address
+/// * `from_block`: The first block to include
+/// * `to_block`: The last block to include
+/// * `interval`: The width of a candle
+/// * `provider`: The alloy provider
+///
+/// ## Returns
+///
+/// [`Error::InvalidInterval`] if the interval is empty, [`Error::BlockNotFound`] if the timestamp
+/// of a block with swaps is needed but the node does not return the block, or the error decoding
+/// a `Swap` log
+#[inline]
+pub async fn get_candles<T, P>(
+    pool: Address,
+    from_block: u64,
+    to_block: u64,
+    interval: CandleInterval,
+    provider: P,
+) -> Result<Vec<Candle>, Error>
+where
+    T: Transport + Clone,
+    P: Provider<T>,
+{
+    if interval.size() == 0 {
+        return Err(Error::InvalidInterval);
+    }
+    let mut swaps: Vec<(u64, Swap)> = Vec::new();
+    let mut timestamps: BTreeMap<u64, u64> = BTreeMap::new();
+    let mut chunk_start = from_block;
+    while chunk_start <= to_block {
+        let chunk_end = to_block.min(chunk_start + LOG_CHUNK_SIZE - 1);
+        let filter = Filter::new()
+            .address(pool)
+            .event_signature(Swap::SIGNATURE_HASH)
+            .from_block(chunk_start)
+            .to_block(chunk_end);
+        for log in provider.get_logs(&filter).await? {
+            let Some(block_number) = log.block_number else {
+                continue;
+            };
+            let swap = Swap::decode_log_data(log.data(), true).map_err(ContractError::from)?;
+            let key = match interval {
+                CandleInterval::Blocks(_) => block_number,
+                CandleInterval::Seconds(_) => match log.block_timestamp {
+                    Some(timestamp) => timestamp,
+                    None => match timestamps.get(&block_number) {
+                        Some(timestamp) => *timestamp,
+                        None => {
+                            let timestamp = provider
+                                .get_block_by_number(
+                                    BlockNumberOrTag::Number(block_number),
+                                    BlockTransactionsKind::Hashes,
+                                )
+                                .await?
+                                .ok_or(Error::BlockNotFound(block_number))?
+                                .header
+                                .timestamp;
+                            timestamps.insert(block_number, timestamp);
+                            timestamp
+                        }
+                    },
+                },
+            };
+            swaps.push((key, swap));
+        }
+        chunk_start = chunk_end + 1;
+    }
+    aggregate_candles(
+        swaps.iter().map(|(key, swap)| (*key, swap)),
+        interval.size(),
+    )
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::tests::*;
+    use alloy_primitives::{address, aliases::I24};
+
+    fn swap(amount0: i64, amount1: i64, sqrt_price_x96: u64) -> Swap {
+        Swap {
+            sender: Address::ZERO,
+            recipient: Address::ZERO,
+            amount0: I256::try_from(amount0).unwrap(),
+            amount1: I256::try_from(amount1).unwrap(),
+            sqrtPriceX96: U160::from(sqrt_price_x96),
+            liquidity: 0,
+            tick: I24::ZERO,
+        }
+    }
+
+    #[test]
+    fn test_aggregate_candles() {
+        let swaps = [
+            (100, swap(10, -20, 5)),
+            (105, swap(-3, 6, 7)),
+            (109, swap(1, -1, 4)),
+            (125, swap(2, -2, 6)),
+        ];
+        let candles = aggregate_candles(swaps.iter().map(|(key, swap)| (*key, swap)), 10).unwrap();
+        assert_eq!(candles.len(), 2);
+        assert_eq!(
+            candles[0],
+            Candle {
+                start: 100,
+                open: U160::from(5),
+                high: U160::from(7),
+                low: U160::from(4),
+                close: U160::from(4),
+                volume0: U256::from(14),
+                volume1: U256::from(27),
+                swaps: 3,
+            }
+        );
+        assert_eq!(candles[1].start, 120);
+        assert_eq!(candles[1].swaps, 1);
+        assert!(matches!(
+            aggregate_candles(swaps.iter().map(|(key, swap)| (*key, swap)), 0),
+            Err(Error::InvalidInterval)
+        ));
+    }
+
+    #[tokio::test]
+    async fn test_get_candles() {
+        let block_number = BLOCK_ID.unwrap().as_u64().unwrap();
+        let candles = get_candles(
+            address!("88e6A0c2dDD26FEEb64F039a2c41296FcB3f5640"),
+            block_number - 100,
+            block_number,
+            CandleInterval::Blocks(25),
+            PROVIDER.clone(),
+        )
+        .await
+        .unwrap();
+        assert!(!candles.is_empty());
+        for candle in candles {
+            assert!(candle.low <= candle.open && candle.open <= candle.high);
+            assert!(candle.low <= candle.close && candle.close <= candle.high);
+            assert!(candle.swaps > 0);
+        }
+        assert!(matches!(
+            get_candles(
+                address!("88e6A0c2dDD26FEEb64F039a2c41296FcB3f5640"),
+                block_number - 100,
+                block_number,
+                CandleInterval::Blocks(0),
+                PROVIDER.clone(),
+            )
+            .await,
+            Err(Error::InvalidInterval)
+        ));
+    }
+}
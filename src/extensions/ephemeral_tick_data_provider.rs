@@ -2,10 +2,15 @@
 //! A data provider that fetches ticks using an [ephemeral contract](https://github.com/Aperture-Finance/Aperture-Lens/blob/904101e4daed59e02fd4b758b98b0749e70b583b/contracts/EphemeralGetPopulatedTicksInRange.sol) in a single `eth_call`.
 
 use crate::prelude::*;
-use alloy::{eips::BlockId, providers::Provider, transports::Transport};
-use alloy_primitives::{aliases::I24, Address};
+use alloy::{
+    eips::{BlockId, BlockNumberOrTag},
+    providers::Provider,
+    transports::Transport,
+};
+use alloy_primitives::{aliases::I24, Address, U160};
 use derive_more::Deref;
-use uniswap_lens::pool_lens;
+use uniswap_lens::{bindings::iuniswapv3pool::IUniswapV3Pool::IUniswapV3PoolInstance, pool_lens};
+use uniswap_sdk_core::prelude::{BaseCurrency, CurrencyAmount, Token};
 
 /// A data provider that fetches ticks using an ephemeral contract in a single `eth_call`.
 #[derive(Clone, Debug, PartialEq, Deref)]
@@ -32,13 +37,48 @@ impl<I: TickIndex> EphemeralTickDataProvider<I> {
         T: Transport + Clone,
         P: Provider<T>,
     {
+        Self::new_metered(
+            pool,
+            provider,
+            tick_lower,
+            tick_upper,
+            block_id,
+            NoopTickMetrics,
+        )
+        .await
+    }
+
+    /// Same as [`Self::new`] but records the call and the ticks fetched to a [`TickMetrics`] sink
+    ///
+    /// ## Arguments
+    ///
+    /// * `pool`: The pool address
+    /// * `provider`: The alloy provider
+    /// * `tick_lower`: The lower tick of the range to fetch, defaults to [`MIN_TICK`]
+    /// * `tick_upper`: The upper tick of the range to fetch, defaults to [`MAX_TICK`]
+    /// * `block_id`: Optional block number to query
+    /// * `metrics`: The sink
+    #[inline]
+    pub async fn new_metered<T, P>(
+        pool: Address,
+        provider: P,
+        tick_lower: Option<I>,
+        tick_upper: Option<I>,
+        block_id: Option<BlockId>,
+        metrics: impl TickMetrics,
+    ) -> Result<Self, Error>
+    where
+        T: Transport + Clone,
+        P: Provider<T>,
+    {
+        metrics.record_rpc_calls(1);
         let tick_lower = tick_lower.map_or(MIN_TICK, I::to_i24);
         let tick_upper = tick_upper.map_or(MAX_TICK, I::to_i24);
         let (ticks, tick_spacing) = pool_lens::get_populated_ticks_in_range(
             pool, tick_lower, tick_upper, provider, block_id,
         )
         .await
-        .map_err(Error::LensError)?;
+        .map_err(|err| Error::LensError(err).with_context(ErrorContext::new().with_pool(pool)))?;
         let ticks: Vec<_> = ticks
             .into_iter()
             .map(|tick| {
@@ -49,6 +89,7 @@ impl<I: TickIndex> EphemeralTickDataProvider<I> {
                 )
             })
             .collect();
+        metrics.record_ticks_fetched(ticks.len() as u64);
         Ok(Self {
             pool,
             tick_lower: I::from_i24(tick_lower),
@@ -58,6 +99,417 @@ impl<I: TickIndex> EphemeralTickDataProvider<I> {
             ticks,
         })
     }
+
+    /// Same as [`Self::new`] but fails with [`Error::TickLimitExceeded`] if the tick range spans
+    /// too many bitmap words, before fetching, or contains too many initialized ticks
+    ///
+    /// ## Arguments
+    ///
+    /// * `pool`: The pool address
+    /// * `provider`: The alloy provider
+    /// * `tick_lower`: The lower tick of the range to fetch, defaults to [`MIN_TICK`]
+    /// * `tick_upper`: The upper tick of the range to fetch, defaults to [`MAX_TICK`]
+    /// * `block_id`: Optional block number to query
+    /// * `limits`: The limits to enforce
+    #[inline]
+    pub async fn new_with_limits<T, P>(
+        pool: Address,
+        provider: P,
+        tick_lower: Option<I>,
+        tick_upper: Option<I>,
+        block_id: Option<BlockId>,
+        limits: TickDataProviderLimits,
+    ) -> Result<Self, Error>
+    where
+        T: Transport + Clone,
+        P: Provider<T>,
+    {
+        if limits.max_word_range.is_some() {
+            let tick_spacing = IUniswapV3PoolInstance::<T, _>::new(pool, &provider)
+                .tickSpacing()
+                .block(block_id.unwrap_or(BlockId::Number(BlockNumberOrTag::Latest)))
+                .call()
+                .await?
+                ._0;
+            limits.check_tick_range(
+                tick_lower.map_or(MIN_TICK, I::to_i24),
+                tick_upper.map_or(MAX_TICK, I::to_i24),
+                tick_spacing,
+            )?;
+        }
+        let provider = Self::new(pool, provider, tick_lower, tick_upper, block_id).await?;
+        limits.check_ticks(provider.ticks.len())?;
+        Ok(provider)
+    }
+
+    /// Fetches only the ticks within `num_spacings` tick spacings on each side of the current tick
+    ///
+    /// ## Arguments
+    ///
+    /// * `pool`: The pool address
+    /// * `provider`: The alloy provider
+    /// * `num_spacings`: The number of tick spacings to fetch on each side of the current tick
+    /// * `block_id`: Optional block number to query
+    /// * `limits`: The limits to enforce
+    #[inline]
+    pub async fn new_within_spacings<T, P>(
+        pool: Address,
+        provider: P,
+        num_spacings: u32,
+        block_id: Option<BlockId>,
+        limits: TickDataProviderLimits,
+    ) -> Result<Self, Error>
+    where
+        T: Transport + Clone,
+        P: Provider<T>,
+    {
+        let pool_contract = IUniswapV3PoolInstance::<T, _>::new(pool, &provider);
+        let block = block_id.unwrap_or(BlockId::Number(BlockNumberOrTag::Latest));
+        let tick_current = pool_contract.slot0().block(block).call().await?.tick;
+        let tick_spacing = pool_contract.tickSpacing().block(block).call().await?._0;
+        let (tick_lower, tick_upper) = tick_range_around(tick_current, tick_spacing, num_spacings);
+        Self::new_with_limits(
+            pool,
+            provider,
+            Some(I::from_i24(tick_lower)),
+            Some(I::from_i24(tick_upper)),
+            block_id,
+            limits,
+        )
+        .await
+    }
+
+    /// Fetches only the ticks in the `num_words_each_side` tick bitmap words on each side of the
+    /// word containing the current tick, which is usually sufficient for realistic trade sizes.
+    ///
+    /// Use [`Pool::get_output_amount_lazy`] to extend the range on demand when a swap walks past
+    /// the fetched region.
+    ///
+    /// ## Arguments
+    ///
+    /// * `pool`: The pool address
+    /// * `provider`: The alloy provider
+    /// * `num_words_each_side`: The number of bitmap words to fetch on each side
+    /// * `block_id`: Optional block number to query
+    #[inline]
+    pub async fn new_around_current<T, P>(
+        pool: Address,
+        provider: P,
+        num_words_each_side: u32,
+        block_id: Option<BlockId>,
+    ) -> Result<Self, Error>
+    where
+        T: Transport + Clone,
+        P: Provider<T>,
+    {
+        let pool_contract = IUniswapV3PoolInstance::<T, _>::new(pool, &provider);
+        let block = block_id.unwrap_or(BlockId::Number(BlockNumberOrTag::Latest));
+        let tick_current = pool_contract.slot0().block(block).call().await?.tick;
+        let tick_spacing = pool_contract.tickSpacing().block(block).call().await?._0;
+        let (word, _) = tick_current.compress(tick_spacing).position();
+        let word = word.as_i32();
+        let n = num_words_each_side as i32;
+        let (tick_lower, tick_upper) =
+            word_range_to_tick_range(word - n, word + n, tick_spacing.as_i32());
+        Self::new(
+            pool,
+            provider,
+            Some(I::try_from(tick_lower).unwrap()),
+            Some(I::try_from(tick_upper).unwrap()),
+            block_id,
+        )
+        .await
+    }
+
+    /// Whether the fetched tick range contains the given tick
+    #[inline]
+    #[must_use]
+    pub fn covers(&self, tick: I) -> bool {
+        self.tick_lower <= tick && tick <= self.tick_upper
+    }
+
+    /// Whether the fetched tick range spans all usable ticks
+    #[inline]
+    #[must_use]
+    pub fn is_complete(&self) -> bool {
+        self.tick_lower - self.tick_spacing < I::from_i24(MIN_TICK)
+            && self.tick_upper + self.tick_spacing > I::from_i24(MAX_TICK)
+    }
+
+    /// Re-fetches the ticks with the range widened by `num_words_each_side` tick bitmap words on
+    /// each side, at the same block
+    ///
+    /// ## Arguments
+    ///
+    /// * `provider`: The alloy provider
+    /// * `num_words_each_side`: The number of bitmap words to add on each side
+    #[inline]
+    pub async fn extend<T, P>(&mut self, provider: P, num_words_each_side: u32) -> Result<(), Error>
+    where
+        T: Transport + Clone,
+        P: Provider<T>,
+    {
+        self.extend_metered(provider, num_words_each_side, NoopTickMetrics)
+            .await
+    }
+
+    /// Same as [`Self::extend`] but records the call and the ticks fetched to a [`TickMetrics`]
+    /// sink
+    ///
+    /// ## Arguments
+    ///
+    /// * `provider`: The alloy provider
+    /// * `num_words_each_side`: The number of bitmap words to add on each side
+    /// * `metrics`: The sink
+    #[inline]
+    pub async fn extend_metered<T, P>(
+        &mut self,
+        provider: P,
+        num_words_each_side: u32,
+        metrics: impl TickMetrics,
+    ) -> Result<(), Error>
+    where
+        T: Transport + Clone,
+        P: Provider<T>,
+    {
+        let tick_spacing: i32 = self.tick_spacing.try_into().unwrap();
+        let (word_lower, _) = self.tick_lower.compress(self.tick_spacing).position();
+        let (word_upper, _) = self.tick_upper.compress(self.tick_spacing).position();
+        let word_lower: i32 = word_lower.try_into().unwrap();
+        let word_upper: i32 = word_upper.try_into().unwrap();
+        let n = num_words_each_side as i32;
+        let (tick_lower, tick_upper) =
+            word_range_to_tick_range(word_lower - n, word_upper + n, tick_spacing);
+        *self = Self::new_metered(
+            self.pool,
+            provider,
+            Some(I::try_from(tick_lower).unwrap()),
+            Some(I::try_from(tick_upper).unwrap()),
+            self.block_id,
+            metrics,
+        )
+        .await?;
+        Ok(())
+    }
+
+    /// Applies a `Mint` or `Burn` event to the fetched ticks, initializing or clearing ticks as
+    /// needed. Bounds outside the fetched range are ignored, while bounds that do not fit the tick
+    /// index type are rejected before any tick is updated.
+    ///
+    /// ## Arguments
+    ///
+    /// * `event`: The event emitted by the pool after the fetched block
+    #[inline]
+    pub fn apply_liquidity_event(&mut self, event: &LiquidityEvent) -> Result<(), Error> {
+        let to_index = |tick: i32| {
+            I::try_from(tick).map_err(|_| match I24::try_from(tick) {
+                Ok(tick) => Error::InvalidTick(tick),
+                Err(_) => Error::InvalidRange,
+            })
+        };
+        let indices = [to_index(event.tick_lower)?, to_index(event.tick_upper)?];
+        for (tick, index) in [event.tick_lower, event.tick_upper]
+            .into_iter()
+            .zip(indices)
+        {
+            if !self.covers(index) {
+                continue;
+            }
+            let liquidity_net = event.liquidity_net_delta(tick);
+            match self.ticks.binary_search_by(|tick| tick.index.cmp(&index)) {
+                Ok(i) => {
+                    let tick = &mut self.ticks[i];
+                    tick.liquidity_gross = match event.kind {
+                        LiquidityEventKind::Mint => tick.liquidity_gross + event.amount,
+                        LiquidityEventKind::Burn => {
+                            tick.liquidity_gross.saturating_sub(event.amount)
+                        }
+                    };
+                    tick.liquidity_net += liquidity_net;
+                    if tick.liquidity_gross == 0 {
+                        self.ticks.remove(i);
+                    }
+                }
+                Err(i) => {
+                    if event.kind == LiquidityEventKind::Mint {
+                        self.ticks
+                            .insert(i, Tick::new(index, event.amount, liquidity_net));
+                    }
+                }
+            }
+        }
+        Ok(())
+    }
+
+    /// Applies the `Mint` and `Burn` events emitted after the fetched block, in order
+    ///
+    /// ## Arguments
+    ///
+    /// * `events`: The events, e.g. from [`get_liquidity_events`]
+    #[inline]
+    pub fn apply_liquidity_events<'a>(
+        &mut self,
+        events: impl IntoIterator<Item = &'a LiquidityEvent>,
+    ) -> Result<(), Error> {
+        for event in events {
+            self.apply_liquidity_event(event)?;
+        }
+        Ok(())
+    }
+
+    /// Brings the ticks up to date with the given block by applying the `Mint` and `Burn` events
+    /// since the fetched block, instead of fetching the whole range again
+    ///
+    /// Falls back to a full fetch if the ticks were not fetched at a block number, e.g. at the
+    /// latest block, since the events to apply are then unknown.
+    ///
+    /// Returns [`Error::BlockBeforeFetched`] if the block precedes the fetched block, since the
+    /// events applied since cannot be reverted.
+    ///
+    /// ## Arguments
+    ///
+    /// * `provider`: The alloy provider
+    /// * `block_id`: The block to refresh to, defaults to the latest block
+    #[inline]
+    pub async fn refresh<T, P>(
+        &mut self,
+        provider: P,
+        block_id: Option<BlockId>,
+    ) -> Result<(), Error>
+    where
+        T: Transport + Clone,
+        P: Provider<T>,
+    {
+        let to_block = match block_id {
+            Some(BlockId::Number(BlockNumberOrTag::Number(number))) => number,
+            _ => provider.get_block_number().await?,
+        };
+        match self.block_id {
+            Some(BlockId::Number(BlockNumberOrTag::Number(from_block))) => {
+                if to_block < from_block {
+                    return Err(Error::BlockBeforeFetched {
+                        fetched: from_block,
+                        requested: to_block,
+                    });
+                }
+                if to_block > from_block {
+                    let events =
+                        get_liquidity_events(self.pool, from_block + 1, to_block, &provider)
+                            .await?;
+                    self.apply_liquidity_events(&events)?;
+                }
+                self.block_id = Some(BlockId::from(to_block));
+            }
+            _ => {
+                *self = Self::new(
+                    self.pool,
+                    provider,
+                    Some(self.tick_lower),
+                    Some(self.tick_upper),
+                    Some(BlockId::from(to_block)),
+                )
+                .await?;
+            }
+        }
+        Ok(())
+    }
+}
+
+/// Converts an inclusive range of tick bitmap words to the usable tick range it covers
+#[inline]
+fn word_range_to_tick_range(word_lower: i32, word_upper: i32, tick_spacing: i32) -> (i32, i32) {
+    let max_usable = MAX_TICK_I32 / tick_spacing * tick_spacing;
+    let tick_lower = (word_lower as i64 * 256 * tick_spacing as i64).max(-max_usable as i64);
+    let tick_upper =
+        (((word_upper as i64 + 1) * 256 - 1) * tick_spacing as i64).min(max_usable as i64);
+    (tick_lower as i32, tick_upper as i32)
+}
+
+impl<I: TickIndex> Pool<EphemeralTickDataProvider<I>> {
+    /// Same as [`Pool::get_output_amount`] but extends the fetched tick range, doubling it each
+    /// time, until the swap ends within the range or all ticks have been fetched
+    ///
+    /// ## Arguments
+    ///
+    /// * `input_amount`: The input amount for which to quote the output amount
+    /// * `sqrt_price_limit_x96`: The Q64.96 sqrt price limit
+    /// * `provider`: The alloy provider
+    #[inline]
+    pub async fn get_output_amount_lazy<T, P>(
+        &mut self,
+        input_amount: &CurrencyAmount<impl BaseCurrency>,
+        sqrt_price_limit_x96: impl Into<Option<U160>>,
+        provider: P,
+    ) -> Result<CurrencyAmount<Token>, Error>
+    where
+        T: Transport + Clone,
+        P: Provider<T> + Clone,
+    {
+        let sqrt_price_limit_x96: Option<U160> = sqrt_price_limit_x96.into();
+        self.quote_output_lazy(
+            input_amount,
+            sqrt_price_limit_x96,
+            provider,
+            NoopTickMetrics,
+        )
+        .await
+        .map(|quote| quote.amount)
+    }
+
+    /// Same as [`Self::get_output_amount_lazy`] but returns the whole [`SwapQuote`] and records
+    /// the attempts served by the fetched ticks as cache hits, the extensions of the range as
+    /// cache misses, and the ticks crossed by the swap to a [`TickMetrics`] sink
+    ///
+    /// ## Arguments
+    ///
+    /// * `input_amount`: The input amount for which to quote the output amount
+    /// * `sqrt_price_limit_x96`: The Q64.96 sqrt price limit
+    /// * `provider`: The alloy provider
+    /// * `metrics`: The sink
+    #[inline]
+    pub async fn quote_output_lazy<T, P>(
+        &mut self,
+        input_amount: &CurrencyAmount<impl BaseCurrency>,
+        sqrt_price_limit_x96: impl Into<Option<U160>>,
+        provider: P,
+        metrics: impl TickMetrics,
+    ) -> Result<SwapQuote, Error>
+    where
+        T: Transport + Clone,
+        P: Provider<T> + Clone,
+    {
+        let sqrt_price_limit_x96: Option<U160> = sqrt_price_limit_x96.into();
+        loop {
+            let result = self.quote_output(input_amount, sqrt_price_limit_x96);
+            let tick_data_provider = &self.tick_data_provider;
+            let walked_past = match &result {
+                Ok(quote) => !tick_data_provider.covers(I::from_i24(
+                    quote.sqrt_price_x96_after.get_tick_at_sqrt_ratio()?,
+                )),
+                Err(e) => matches!(e.root(), Error::InsufficientLiquidity),
+            };
+            if !walked_past || tick_data_provider.is_complete() {
+                if let Ok(quote) = &result {
+                    metrics.record_cache_hit();
+                    metrics.record_ticks_crossed(quote.ticks_crossed.into());
+                }
+                return result;
+            }
+            metrics.record_cache_miss();
+            let (word_lower, _) = tick_data_provider
+                .tick_lower
+                .compress(tick_data_provider.tick_spacing)
+                .position();
+            let (word_upper, _) = tick_data_provider
+                .tick_upper
+                .compress(tick_data_provider.tick_spacing)
+                .position();
+            let span: i32 = (word_upper - word_lower).try_into().unwrap();
+            self.tick_data_provider
+                .extend_metered(provider.clone(), span.max(1) as u32, &metrics)
+                .await?;
+        }
+    }
 }
 
 impl<I: TickIndex> From<EphemeralTickDataProvider<I>> for TickListDataProvider<I> {
@@ -108,4 +560,184 @@ mod tests {
         assert_eq!(tick.liquidity_net, 398290794261);
         Ok(())
     }
+
+    #[tokio::test]
+    async fn test_get_output_amount_lazy() {
+        let pool = Pool::from_pool_key(
+            1,
+            FACTORY_ADDRESS,
+            address!("A0b86991c6218b36c1d19D4a2e9Eb0cE3606eB48"),
+            address!("C02aaA39b223FE8D0A0e5C4F27eAD9083C756Cc2"),
+            FeeAmount::LOW,
+            PROVIDER.clone(),
+            *BLOCK_ID,
+        )
+        .await
+        .unwrap();
+        let address = pool.address(None, None);
+        let full =
+            EphemeralTickDataProvider::<i32>::new(address, PROVIDER.clone(), None, None, *BLOCK_ID)
+                .await
+                .unwrap();
+        let partial =
+            EphemeralTickDataProvider::new_around_current(address, PROVIDER.clone(), 0, *BLOCK_ID)
+                .await
+                .unwrap();
+        assert!(!partial.is_complete());
+        let full_pool = Pool::new_with_tick_data_provider(
+            pool.token0.clone(),
+            pool.token1.clone(),
+            pool.fee,
+            pool.sqrt_ratio_x96,
+            pool.liquidity,
+            full,
+        )
+        .unwrap();
+        let mut lazy_pool = Pool::new_with_tick_data_provider(
+            pool.token0.clone(),
+            pool.token1.clone(),
+            pool.fee,
+            pool.sqrt_ratio_x96,
+            pool.liquidity,
+            partial,
+        )
+        .unwrap();
+        // 1,000,000 WETH walks far past the word containing the current tick
+        let amount_in =
+            CurrencyAmount::from_raw_amount(pool.token1.clone(), 10_u128.pow(24)).unwrap();
+        let metrics = AtomicTickMetrics::new();
+        let lazy = lazy_pool
+            .quote_output_lazy(&amount_in, None, PROVIDER.clone(), &metrics)
+            .await
+            .unwrap();
+        let full = full_pool.quote_output(&amount_in, None).unwrap();
+        assert_eq!(lazy, full);
+        let snapshot = metrics.snapshot();
+        assert_eq!(snapshot.cache_hits, 1);
+        assert!(snapshot.cache_misses > 0);
+        assert_eq!(snapshot.rpc_calls, snapshot.cache_misses);
+        assert!(snapshot.ticks_fetched > 0);
+        assert_eq!(snapshot.ticks_crossed, full.ticks_crossed as u64);
+        // the range now covers the swap
+        assert_eq!(
+            lazy_pool
+                .get_output_amount_lazy(&amount_in, None, PROVIDER.clone())
+                .await
+                .unwrap(),
+            full.amount
+        );
+    }
+
+    #[test]
+    fn test_apply_liquidity_events() {
+        let mut provider = EphemeralTickDataProvider {
+            pool: Address::ZERO,
+            tick_lower: -100,
+            tick_upper: 100,
+            tick_spacing: TICK_SPACING,
+            block_id: None,
+            ticks: vec![Tick::new(-10, 100, 100), Tick::new(10, 100, -100)],
+        };
+        let event = |kind, tick_lower, tick_upper, amount| LiquidityEvent {
+            kind,
+            owner: Address::ZERO,
+            tick_lower,
+            tick_upper,
+            amount,
+            block_number: 0,
+            transaction_hash: None,
+        };
+        provider
+            .apply_liquidity_events(&[
+                event(LiquidityEventKind::Mint, 0, 200, 50),
+                event(LiquidityEventKind::Burn, -10, 10, 100),
+                event(LiquidityEventKind::Mint, -10, 0, 30),
+            ])
+            .unwrap();
+        assert_eq!(
+            provider.ticks,
+            [Tick::new(-10, 30, 30), Tick::new(0, 80, 20)]
+        );
+        let mut provider = EphemeralTickDataProvider::<I24> {
+            pool: Address::ZERO,
+            tick_lower: I24::MIN,
+            tick_upper: I24::MAX,
+            tick_spacing: I24::ONE,
+            block_id: None,
+            ticks: vec![],
+        };
+        assert!(matches!(
+            provider.apply_liquidity_event(&event(LiquidityEventKind::Mint, 0, 1 << 23, 1)),
+            Err(Error::InvalidRange)
+        ));
+        assert!(provider.ticks.is_empty());
+    }
+
+    #[tokio::test]
+    async fn test_refresh_matches_full_fetch() {
+        let pool = address!("88e6A0c2dDD26FEEb64F039a2c41296FcB3f5640");
+        let block = 17000000;
+        let mut provider = EphemeralTickDataProvider::<i32>::new(
+            pool,
+            PROVIDER.clone(),
+            None,
+            None,
+            Some(BlockId::from(block - 100)),
+        )
+        .await
+        .unwrap();
+        provider
+            .refresh(PROVIDER.clone(), Some(BlockId::from(block)))
+            .await
+            .unwrap();
+        let full = EphemeralTickDataProvider::<i32>::new(
+            pool,
+            PROVIDER.clone(),
+            None,
+            None,
+            Some(BlockId::from(block)),
+        )
+        .await
+        .unwrap();
+        assert_eq!(provider, full);
+        assert!(matches!(
+            provider
+                .refresh(PROVIDER.clone(), Some(BlockId::from(block - 1)))
+                .await,
+            Err(Error::BlockBeforeFetched { fetched, requested })
+                if fetched == block && requested == block - 1
+        ));
+    }
+
+    #[tokio::test]
+    async fn test_ephemeral_tick_data_provider_limits() {
+        let limits = TickDataProviderLimits {
+            max_ticks: None,
+            max_word_range: Some(4),
+        };
+        let res = EphemeralTickDataProvider::<i32>::new_with_limits(
+            address!("88e6A0c2dDD26FEEb64F039a2c41296FcB3f5640"),
+            PROVIDER.clone(),
+            None,
+            None,
+            *BLOCK_ID,
+            limits,
+        )
+        .await;
+        assert!(matches!(res, Err(Error::TickLimitExceeded)));
+        let provider = EphemeralTickDataProvider::<i32>::new_within_spacings(
+            address!("88e6A0c2dDD26FEEb64F039a2c41296FcB3f5640"),
+            PROVIDER.clone(),
+            100,
+            *BLOCK_ID,
+            limits,
+        )
+        .await
+        .unwrap();
+        assert_eq!(
+            provider.tick_upper - provider.tick_lower,
+            201 * TICK_SPACING
+        );
+        assert!(!provider.ticks.is_empty());
+    }
 }
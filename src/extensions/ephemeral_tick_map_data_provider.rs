@@ -43,6 +43,83 @@ impl<I: TickIndex> EphemeralTickMapDataProvider<I> {
             tick_map: TickMap::new(provider.ticks, provider.tick_spacing),
         })
     }
+
+    /// Same as [`Self::new`] but records the call and the ticks fetched to a [`TickMetrics`] sink,
+    /// see [`EphemeralTickDataProvider::new_metered`]
+    ///
+    /// ## Arguments
+    ///
+    /// * `pool`: The pool address
+    /// * `provider`: The alloy provider
+    /// * `tick_lower`: The lower tick of the range to fetch, defaults to [`MIN_TICK`]
+    /// * `tick_upper`: The upper tick of the range to fetch, defaults to [`MAX_TICK`]
+    /// * `block_id`: Optional block number to query
+    /// * `metrics`: The sink
+    #[inline]
+    pub async fn new_metered<T, P>(
+        pool: Address,
+        provider: P,
+        tick_lower: Option<I>,
+        tick_upper: Option<I>,
+        block_id: Option<BlockId>,
+        metrics: impl TickMetrics,
+    ) -> Result<Self, Error>
+    where
+        T: Transport + Clone,
+        P: Provider<T>,
+    {
+        let provider = EphemeralTickDataProvider::new_metered(
+            pool, provider, tick_lower, tick_upper, block_id, metrics,
+        )
+        .await?;
+        Ok(Self {
+            pool,
+            tick_lower: provider.tick_lower,
+            tick_upper: provider.tick_upper,
+            tick_spacing: provider.tick_spacing,
+            block_id,
+            tick_map: TickMap::new(provider.ticks, provider.tick_spacing),
+        })
+    }
+
+    /// Same as [`Self::new`] but fails with [`Error::TickLimitExceeded`] if the tick range spans
+    /// too many bitmap words, before fetching, or contains too many initialized ticks, see
+    /// [`EphemeralTickDataProvider::new_with_limits`]
+    ///
+    /// ## Arguments
+    ///
+    /// * `pool`: The pool address
+    /// * `provider`: The alloy provider
+    /// * `tick_lower`: The lower tick of the range to fetch, defaults to [`MIN_TICK`]
+    /// * `tick_upper`: The upper tick of the range to fetch, defaults to [`MAX_TICK`]
+    /// * `block_id`: Optional block number to query
+    /// * `limits`: The limits to enforce
+    #[inline]
+    pub async fn new_with_limits<T, P>(
+        pool: Address,
+        provider: P,
+        tick_lower: Option<I>,
+        tick_upper: Option<I>,
+        block_id: Option<BlockId>,
+        limits: TickDataProviderLimits,
+    ) -> Result<Self, Error>
+    where
+        T: Transport + Clone,
+        P: Provider<T>,
+    {
+        let provider = EphemeralTickDataProvider::new_with_limits(
+            pool, provider, tick_lower, tick_upper, block_id, limits,
+        )
+        .await?;
+        Ok(Self {
+            pool,
+            tick_lower: provider.tick_lower,
+            tick_upper: provider.tick_upper,
+            tick_spacing: provider.tick_spacing,
+            block_id,
+            tick_map: TickMap::new(provider.ticks, provider.tick_spacing),
+        })
+    }
 }
 
 #[cfg(test)]
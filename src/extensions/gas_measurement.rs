@@ -0,0 +1,169 @@
+//! ## Gas Measurement
+//! Measures the gas used by calldata generated by this crate with `eth_estimateGas` on a node or
+//! a fork, e.g. anvil, with optional state overrides, so that encoding strategies such as
+//! multicall vs single call or permit vs approve can be compared with real numbers.
+
+use crate::prelude::{Error, MethodParameters};
+use alloc::{string::String, vec::Vec};
+use alloy::{
+    eips::BlockId,
+    providers::Provider,
+    rpc::types::{state::StateOverride, TransactionRequest},
+    transports::Transport,
+};
+use alloy_primitives::{Address, Bytes};
+use alloy_sol_types::decode_revert_reason;
+
+/// The outcome of measuring the gas of a call with [`measure_gas`].
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub enum GasMeasurement {
+    /// The call succeeded with the given gas estimate.
+    Success { gas: u64 },
+    /// The call reverted.
+    Reverted {
+        /// The revert data, empty if the node did not return it.
+        data: Bytes,
+        /// The decoded `Error(string)` or `Panic(uint256)` reason, if any.
+        reason: Option<String>,
+    },
+}
+
+impl GasMeasurement {
+    /// The gas estimate if the call succeeded
+    #[inline]
+    #[must_use]
+    pub const fn gas(&self) -> Option<u64> {
+        match self {
+            Self::Success { gas } => Some(*gas),
+            Self::Reverted { .. } => None,
+        }
+    }
+}
+
+/// Measures the gas used by a call, decoding the revert reason if it fails
+///
+/// ## Arguments
+///
+/// * `provider`: The alloy provider, e.g. connected to an anvil fork
+/// * `to`: The contract the calldata is sent to
+/// * `parameters`: The calldata and value to send
+/// * `from`: The sender of the call
+/// * `overrides`: Optional state overrides, e.g. from [`get_erc20_state_overrides`](crate::extensions::get_erc20_state_overrides)
+/// * `block_id`: Optional block number to query
+#[inline]
+pub async fn measure_gas<T, P>(
+    provider: &P,
+    to: Address,
+    parameters: &MethodParameters,
+    from: Address,
+    overrides: Option<&StateOverride>,
+    block_id: Option<BlockId>,
+) -> Result<GasMeasurement, Error>
+where
+    T: Transport + Clone,
+    P: Provider<T>,
+{
+    let tx = TransactionRequest::default()
+        .from(from)
+        .to(to)
+        .value(parameters.value)
+        .input(parameters.calldata.clone().into());
+    let mut call = provider
+        .estimate_gas(&tx)
+        .block(block_id.unwrap_or_default());
+    if let Some(overrides) = overrides {
+        call = call.overrides(overrides);
+    }
+    match call.await {
+        Ok(gas) => Ok(GasMeasurement::Success { gas }),
+        Err(err) => {
+            let Some(payload) = err.as_error_resp() else {
+                return Err(err.into());
+            };
+            if let Some(data) = payload.as_revert_data() {
+                Ok(GasMeasurement::Reverted {
+                    reason: decode_revert_reason(&data),
+                    data,
+                })
+            } else if payload.message.contains("revert") {
+                Ok(GasMeasurement::Reverted {
+                    data: Bytes::new(),
+                    reason: None,
+                })
+            } else {
+                Err(err.into())
+            }
+        }
+    }
+}
+
+/// Same as [`measure_gas`] for several calls, each measured independently against the same
+/// state rather than one after the other
+///
+/// ## Arguments
+///
+/// * `provider`: The alloy provider, e.g. connected to an anvil fork
+/// * `calls`: The contracts and the calldata and value sent to them
+/// * `from`: The sender of the calls
+/// * `overrides`: Optional state overrides applied to every call
+/// * `block_id`: Optional block number to query
+#[inline]
+pub async fn measure_gas_batch<T, P>(
+    provider: &P,
+    calls: &[(Address, MethodParameters)],
+    from: Address,
+    overrides: Option<&StateOverride>,
+    block_id: Option<BlockId>,
+) -> Result<Vec<GasMeasurement>, Error>
+where
+    T: Transport + Clone,
+    P: Provider<T>,
+{
+    let mut measurements = Vec::with_capacity(calls.len());
+    for (to, parameters) in calls {
+        measurements.push(measure_gas(provider, *to, parameters, from, overrides, block_id).await?);
+    }
+    Ok(measurements)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::tests::*;
+    use alloy_primitives::{address, U256};
+    use alloy_sol_types::SolCall;
+    use uniswap_lens::bindings::ierc20::IERC20;
+    use uniswap_sdk_core::prelude::BaseCurrency;
+
+    #[tokio::test]
+    async fn test_measure_gas() {
+        let provider = PROVIDER.clone();
+        let owner = address!("88e6A0c2dDD26FEEb64F039a2c41296FcB3f5640");
+        let transfer = |amount| MethodParameters {
+            calldata: IERC20::transferCall {
+                to: address!("0000000000000000000000000000000000000001"),
+                amount,
+            }
+            .abi_encode()
+            .into(),
+            value: U256::ZERO,
+        };
+        let measurements = measure_gas_batch(
+            &provider,
+            &[
+                (USDC.address(), transfer(U256::from(1))),
+                (USDC.address(), transfer(U256::MAX)),
+            ],
+            owner,
+            None,
+            *BLOCK_ID,
+        )
+        .await
+        .unwrap();
+        assert!(measurements[0].gas().unwrap() > 21000);
+        let GasMeasurement::Reverted { reason, .. } = &measurements[1] else {
+            panic!("expected a revert");
+        };
+        assert!(reason.is_some());
+    }
+}
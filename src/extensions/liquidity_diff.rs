@@ -0,0 +1,373 @@
+//! ## Liquidity Diff
+//! Compares the initialized ticks of a pool at two blocks and attributes the changes to the
+//! `Mint` and `Burn` events emitted in between, so that liquidity movements can be analyzed
+//! without a subgraph.
+
+use super::candles::LOG_CHUNK_SIZE;
+use crate::prelude::{
+    Error,
+    IUniswapV3PoolEvents::{Burn, Mint},
+    *,
+};
+use alloc::{collections::BTreeMap, vec, vec::Vec};
+use alloy::{
+    eips::BlockId,
+    providers::Provider,
+    rpc::types::{Filter, Log},
+    transports::Transport,
+};
+use alloy_primitives::{Address, B256};
+use alloy_sol_types::SolEvent;
+
+/// Whether a [`LiquidityEvent`] added or removed liquidity.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+pub enum LiquidityEventKind {
+    /// A `Mint` event, adding liquidity.
+    Mint,
+    /// A `Burn` event, removing liquidity.
+    Burn,
+}
+
+/// A `Mint` or `Burn` event of a pool.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct LiquidityEvent {
+    /// Whether the event added or removed liquidity.
+    pub kind: LiquidityEventKind,
+    /// The owner of the liquidity, i.e. the position manager for positions held as NFTs.
+    pub owner: Address,
+    /// The lower tick of the position.
+    pub tick_lower: i32,
+    /// The upper tick of the position.
+    pub tick_upper: i32,
+    /// The liquidity added or removed.
+    pub amount: u128,
+    /// The block the event was emitted in.
+    pub block_number: u64,
+    /// The transaction that emitted the event, if known.
+    pub transaction_hash: Option<B256>,
+}
+
+impl LiquidityEvent {
+    /// Decodes a `Mint` or `Burn` log of a pool, returning `None` for other logs, logs without a
+    /// block number and burns of zero liquidity, which only update the fees owed to a position
+    ///
+    /// ## Arguments
+    ///
+    /// * `log`: The log emitted by the pool
+    #[inline]
+    #[must_use]
+    pub fn from_log(log: &Log) -> Option<Self> {
+        let block_number = log.block_number?;
+        let topic0 = *log.topic0()?;
+        let (kind, owner, tick_lower, tick_upper, amount) = if topic0 == Mint::SIGNATURE_HASH {
+            let mint = Mint::decode_log_data(log.data(), true).ok()?;
+            (
+                LiquidityEventKind::Mint,
+                mint.owner,
+                mint.tickLower,
+                mint.tickUpper,
+                mint.amount,
+            )
+        } else if topic0 == Burn::SIGNATURE_HASH {
+            let burn = Burn::decode_log_data(log.data(), true).ok()?;
+            (
+                LiquidityEventKind::Burn,
+                burn.owner,
+                burn.tickLower,
+                burn.tickUpper,
+                burn.amount,
+            )
+        } else {
+            return None;
+        };
+        if amount == 0 {
+            return None;
+        }
+        Some(Self {
+            kind,
+            owner,
+            tick_lower: tick_lower.as_i32(),
+            tick_upper: tick_upper.as_i32(),
+            amount,
+            block_number,
+            transaction_hash: log.transaction_hash,
+        })
+    }
+
+    /// The change of `liquidityNet` caused by the event at the given tick
+    ///
+    /// ## Arguments
+    ///
+    /// * `tick`: The tick to get the change at
+    #[inline]
+    #[must_use]
+    pub const fn liquidity_net_delta(&self, tick: i32) -> i128 {
+        let amount = match self.kind {
+            LiquidityEventKind::Mint => self.amount as i128,
+            LiquidityEventKind::Burn => -(self.amount as i128),
+        };
+        if tick == self.tick_lower {
+            amount
+        } else if tick == self.tick_upper {
+            -amount
+        } else {
+            0
+        }
+    }
+}
+
+/// The change of an initialized tick between two blocks.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct TickLiquidityChange {
+    /// The index of the tick.
+    pub index: i32,
+    /// The `liquidityGross` of the tick at the first block, zero if it was not initialized.
+    pub liquidity_gross_before: u128,
+    /// The `liquidityGross` of the tick at the second block, zero if it is no longer initialized.
+    pub liquidity_gross_after: u128,
+    /// The `liquidityNet` of the tick at the first block.
+    pub liquidity_net_before: i128,
+    /// The `liquidityNet` of the tick at the second block.
+    pub liquidity_net_after: i128,
+    /// The indices in [`LiquidityDiff::events`] of the events with a bound at this tick.
+    pub events: Vec<usize>,
+    /// The part of the change of `liquidityNet` not explained by [`Self::events`], which is
+    /// nonzero only if the events are incomplete.
+    pub unattributed_liquidity_net: i128,
+}
+
+/// The liquidity changes of a pool between two blocks.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct LiquidityDiff {
+    /// The first block, whose state is the baseline.
+    pub block_a: u64,
+    /// The second block, whose state is compared to the baseline.
+    pub block_b: u64,
+    /// The changed ticks, sorted by index.
+    pub ticks: Vec<TickLiquidityChange>,
+    /// The `Mint` and `Burn` events after `block_a` up to and including `block_b`, in the order
+    /// they were emitted.
+    pub events: Vec<LiquidityEvent>,
+}
+
+/// Computes the changed ticks between two tick lists and attributes them to the given events
+///
+/// ## Arguments
+///
+/// * `before`: The initialized ticks at the first block
+/// * `after`: The initialized ticks at the second block
+/// * `events`: The events emitted in between
+#[inline]
+#[must_use]
+pub fn diff_ticks<I: TickIndex>(
+    before: &[Tick<I>],
+    after: &[Tick<I>],
+    events: &[LiquidityEvent],
+) -> Vec<TickLiquidityChange> {
+    let mut states: BTreeMap<i32, ((u128, i128), (u128, i128))> = BTreeMap::new();
+    for tick in before {
+        states.entry(tick.index.to_i24().as_i32()).or_default().0 =
+            (tick.liquidity_gross, tick.liquidity_net);
+    }
+    for tick in after {
+        states.entry(tick.index.to_i24().as_i32()).or_default().1 =
+            (tick.liquidity_gross, tick.liquidity_net);
+    }
+    states
+        .into_iter()
+        .filter(|(_, (before, after))| before != after)
+        .map(|(index, (before, after))| {
+            let indices: Vec<usize> = events
+                .iter()
+                .enumerate()
+                .filter(|(_, event)| event.tick_lower == index || event.tick_upper == index)
+                .map(|(i, _)| i)
+                .collect();
+            let attributed: i128 = indices
+                .iter()
+                .map(|&i| events[i].liquidity_net_delta(index))
+                .sum();
+            TickLiquidityChange {
+                index,
+                liquidity_gross_before: before.0,
+                liquidity_gross_after: after.0,
+                liquidity_net_before: before.1,
+                liquidity_net_after: after.1,
+                events: indices,
+                unattributed_liquidity_net: after.1 - before.1 - attributed,
+            }
+        })
+        .collect()
+}
+
+/// Fetches the `Mint` and `Burn` events of a pool in a block range, see
+/// [`LiquidityEvent::from_log`]
+///
+/// ## Arguments
+///
+/// * `pool`: The pool address
+/// * `from_block`: The first block to include
+/// * `to_block`: The last block to include
+/// * `provider`: The alloy provider
+#[inline]
+pub async fn get_liquidity_events<T, P>(
+    pool: Address,
+    from_block: u64,
+    to_block: u64,
+    provider: &P,
+) -> Result<Vec<LiquidityEvent>, Error>
+where
+    T: Transport + Clone,
+    P: Provider<T>,
+{
+    let mut events = Vec::new();
+    let mut chunk_start = from_block;
+    while chunk_start <= to_block {
+        let chunk_end = to_block.min(chunk_start + LOG_CHUNK_SIZE - 1);
+        let filter = Filter::new()
+            .address(pool)
+            .event_signature(vec![Mint::SIGNATURE_HASH, Burn::SIGNATURE_HASH])
+            .from_block(chunk_start)
+            .to_block(chunk_end);
+        events.extend(
+            provider
+                .get_logs(&filter)
+                .await?
+                .iter()
+                .filter_map(LiquidityEvent::from_log),
+        );
+        chunk_start = chunk_end + 1;
+    }
+    Ok(events)
+}
+
+/// Fetches the initialized ticks of a pool at two blocks and returns the changed ticks,
+/// attributed to the `Mint` and `Burn` events after `block_a` up to and including `block_b`
+///
+/// ## Arguments
+///
+/// * `provider`: The alloy provider
+/// * `pool`: The pool address
+/// * `block_a`: The earlier block
+/// * `block_b`: The later block
+#[inline]
+pub async fn diff_liquidity<T, P>(
+    provider: &P,
+    pool: Address,
+    block_a: u64,
+    block_b: u64,
+) -> Result<LiquidityDiff, Error>
+where
+    T: Transport + Clone,
+    P: Provider<T>,
+{
+    let before = EphemeralTickDataProvider::<i32>::new(
+        pool,
+        provider,
+        None,
+        None,
+        Some(BlockId::from(block_a)),
+    )
+    .await?;
+    let after = EphemeralTickDataProvider::<i32>::new(
+        pool,
+        provider,
+        None,
+        None,
+        Some(BlockId::from(block_b)),
+    )
+    .await?;
+    let events = if block_a < block_b {
+        get_liquidity_events(pool, block_a + 1, block_b, provider).await?
+    } else {
+        Vec::new()
+    };
+    Ok(LiquidityDiff {
+        block_a,
+        block_b,
+        ticks: diff_ticks(&before.ticks, &after.ticks, &events),
+        events,
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::tests::*;
+    use alloy_primitives::address;
+
+    fn event(
+        kind: LiquidityEventKind,
+        tick_lower: i32,
+        tick_upper: i32,
+        amount: u128,
+    ) -> LiquidityEvent {
+        LiquidityEvent {
+            kind,
+            owner: Address::ZERO,
+            tick_lower,
+            tick_upper,
+            amount,
+            block_number: 0,
+            transaction_hash: None,
+        }
+    }
+
+    #[test]
+    fn test_diff_ticks() {
+        let before = [Tick::new(-10, 100, 100), Tick::new(10, 100, -100)];
+        let after = [
+            Tick::new(-10, 150, 150),
+            Tick::new(0, 20, 20),
+            Tick::new(10, 150, -150),
+            Tick::new(20, 20, -20),
+        ];
+        let events = [
+            event(LiquidityEventKind::Mint, -10, 10, 50),
+            event(LiquidityEventKind::Mint, 0, 20, 30),
+            event(LiquidityEventKind::Burn, 0, 20, 10),
+        ];
+        let ticks = diff_ticks(&before, &after, &events);
+        assert_eq!(
+            ticks.iter().map(|tick| tick.index).collect::<Vec<_>>(),
+            [-10, 0, 10, 20]
+        );
+        assert_eq!(ticks[0].events, [0]);
+        assert_eq!(ticks[1].liquidity_gross_before, 0);
+        assert_eq!(ticks[1].events, [1, 2]);
+        assert_eq!(ticks[2].liquidity_net_after, -150);
+        assert_eq!(ticks[3].events, [1, 2]);
+        assert!(ticks
+            .iter()
+            .all(|tick| tick.unattributed_liquidity_net == 0));
+    }
+
+    #[test]
+    fn test_diff_ticks_missing_events() {
+        let before = [Tick::new(-10, 100, 100), Tick::new(10, 100, -100)];
+        let ticks = diff_ticks(&before, &[], &[]);
+        assert_eq!(ticks.len(), 2);
+        assert_eq!(ticks[0].unattributed_liquidity_net, -100);
+        assert_eq!(ticks[1].unattributed_liquidity_net, 100);
+    }
+
+    #[tokio::test]
+    async fn test_diff_liquidity() {
+        let block_b = 17000000;
+        let diff = diff_liquidity(
+            &PROVIDER.clone(),
+            address!("88e6A0c2dDD26FEEb64F039a2c41296FcB3f5640"),
+            block_b - 100,
+            block_b,
+        )
+        .await
+        .unwrap();
+        assert!(diff
+            .events
+            .iter()
+            .all(|event| event.block_number > diff.block_a && event.block_number <= block_b));
+        for tick in &diff.ticks {
+            assert_eq!(tick.unattributed_liquidity_net, 0);
+        }
+    }
+}
@@ -1,21 +1,59 @@
 //! Extensions to the core library.
 
+mod candles;
 mod ephemeral_tick_data_provider;
 mod ephemeral_tick_map_data_provider;
+mod gas_measurement;
+mod liquidity_diff;
+mod onchain_quoter;
+mod oracle;
+mod pending_pool;
+mod permit2_allowance;
+mod permit_domain;
+mod permit_pruning;
 mod pool;
+mod pool_tracker;
 mod position;
+mod position_history;
+mod position_monitor;
 mod price_tick_conversions;
+mod receipt;
+mod router_probe;
+#[cfg(feature = "fixtures")]
+mod rpc_fixtures;
 mod state_overrides;
+mod storage_tick_data_provider;
+mod swap_executor;
 mod tick_bit_map;
+mod tick_lens_data_provider;
 mod tick_map;
 
+pub use candles::*;
 pub use ephemeral_tick_data_provider::EphemeralTickDataProvider;
 pub use ephemeral_tick_map_data_provider::EphemeralTickMapDataProvider;
+pub use gas_measurement::*;
+pub use liquidity_diff::*;
+pub use onchain_quoter::*;
+pub use oracle::*;
+pub use pending_pool::*;
+pub use permit2_allowance::*;
+pub use permit_domain::*;
+pub use permit_pruning::*;
 pub use pool::*;
+pub use pool_tracker::*;
 pub use position::*;
+pub use position_history::*;
+pub use position_monitor::*;
 pub use price_tick_conversions::*;
+pub use receipt::*;
+pub use router_probe::*;
+#[cfg(feature = "fixtures")]
+pub use rpc_fixtures::*;
 pub use state_overrides::*;
+pub use storage_tick_data_provider::*;
+pub use swap_executor::*;
 pub use tick_bit_map::*;
+pub use tick_lens_data_provider::*;
 pub use tick_map::*;
 
 pub use uniswap_lens as lens;
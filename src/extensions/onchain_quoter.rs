@@ -0,0 +1,232 @@
+//! ## On-chain Quoter
+//! Builds trades from the exact amounts returned by `QuoterV2` in an `eth_call`, so that a route
+//! can be quoted accurately without fetching the tick data of its pools.
+
+use crate::prelude::{Error, *};
+use alloc::{vec, vec::Vec};
+use alloy::{
+    contract::Error as ContractError, eips::BlockId, providers::Provider,
+    rpc::types::TransactionRequest, transports::Transport,
+};
+use alloy_primitives::{Address, U160, U256};
+use alloy_sol_types::SolCall;
+use uniswap_sdk_core::prelude::*;
+
+/// The quote of a route returned by `QuoterV2`.
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+pub struct QuoterV2Quote {
+    /// The amount out for an exact input, or the amount in for an exact output.
+    pub amount: U256,
+    /// The square root price of each pool after the swap, in the order of the route.
+    pub sqrt_price_x96_after_list: Vec<U160>,
+    /// The number of initialized ticks crossed in each pool, in the order of the route.
+    pub initialized_ticks_crossed_list: Vec<u32>,
+    /// The gas estimate of the swap reported by the quoter.
+    pub gas_estimate: U256,
+}
+
+/// Quotes a route with `QuoterV2` in an `eth_call`
+///
+/// ## Arguments
+///
+/// * `provider`: The alloy provider
+/// * `quoter`: The address of `QuoterV2`
+/// * `route`: The route to quote
+/// * `amount`: The amount of the input for an exact input, or of the output for an exact output
+/// * `trade_type`: The trade type
+/// * `block_id`: Optional block number to query
+#[inline]
+pub async fn quote_route_with_quoter_v2<TInput, TOutput, TP, T, P>(
+    provider: &P,
+    quoter: Address,
+    route: &Route<TInput, TOutput, TP>,
+    amount: &CurrencyAmount<impl BaseCurrency>,
+    trade_type: TradeType,
+    block_id: Option<BlockId>,
+) -> Result<QuoterV2Quote, Error>
+where
+    TInput: BaseCurrency,
+    TOutput: BaseCurrency,
+    TP: TickDataProvider,
+    T: Transport + Clone,
+    P: Provider<T>,
+{
+    let params = quote_call_parameters(
+        route,
+        amount,
+        trade_type,
+        Some(QuoteOptions {
+            sqrt_price_limit_x96: U160::ZERO,
+            use_quoter_v2: true,
+        }),
+    );
+    let tx = TransactionRequest::default()
+        .to(quoter)
+        .input(params.calldata.into());
+    let res = provider
+        .call(&tx)
+        .block(block_id.unwrap_or_default())
+        .await?;
+    let quote = match (trade_type, route.pools.len() == 1) {
+        (TradeType::ExactInput, true) => {
+            let r = IQuoterV2::quoteExactInputSingleCall::abi_decode_returns(&res, true)
+                .map_err(ContractError::from)?;
+            QuoterV2Quote {
+                amount: r.amountOut,
+                sqrt_price_x96_after_list: vec![r.sqrtPriceX96After],
+                initialized_ticks_crossed_list: vec![r.initializedTicksCrossed],
+                gas_estimate: r.gasEstimate,
+            }
+        }
+        (TradeType::ExactInput, false) => {
+            let r = IQuoterV2::quoteExactInputCall::abi_decode_returns(&res, true)
+                .map_err(ContractError::from)?;
+            QuoterV2Quote {
+                amount: r.amountOut,
+                sqrt_price_x96_after_list: r.sqrtPriceX96AfterList,
+                initialized_ticks_crossed_list: r.initializedTicksCrossedList,
+                gas_estimate: r.gasEstimate,
+            }
+        }
+        (TradeType::ExactOutput, true) => {
+            let r = IQuoterV2::quoteExactOutputSingleCall::abi_decode_returns(&res, true)
+                .map_err(ContractError::from)?;
+            QuoterV2Quote {
+                amount: r.amountIn,
+                sqrt_price_x96_after_list: vec![r.sqrtPriceX96After],
+                initialized_ticks_crossed_list: vec![r.initializedTicksCrossed],
+                gas_estimate: r.gasEstimate,
+            }
+        }
+        (TradeType::ExactOutput, false) => {
+            // the path of an exact output is encoded in reverse, and so are the lists
+            let r = IQuoterV2::quoteExactOutputCall::abi_decode_returns(&res, true)
+                .map_err(ContractError::from)?;
+            let mut sqrt_price_x96_after_list = r.sqrtPriceX96AfterList;
+            let mut initialized_ticks_crossed_list = r.initializedTicksCrossedList;
+            sqrt_price_x96_after_list.reverse();
+            initialized_ticks_crossed_list.reverse();
+            QuoterV2Quote {
+                amount: r.amountIn,
+                sqrt_price_x96_after_list,
+                initialized_ticks_crossed_list,
+                gas_estimate: r.gasEstimate,
+            }
+        }
+    };
+    Ok(quote)
+}
+
+impl<TInput, TOutput, TP> Trade<TInput, TOutput, TP>
+where
+    TInput: BaseCurrency,
+    TOutput: BaseCurrency,
+    TP: TickDataProvider,
+{
+    /// Constructs a trade from the amounts quoted by `QuoterV2` instead of simulating the swaps
+    /// locally, so the pools of the route may come without tick data
+    ///
+    /// ## Arguments
+    ///
+    /// * `provider`: The alloy provider
+    /// * `quoter`: The address of `QuoterV2`
+    /// * `route`: The route to swap through
+    /// * `amount`: The amount specified, either input or output, depending on `trade_type`
+    /// * `trade_type`: Whether the trade is an exact input or exact output swap
+    /// * `block_id`: Optional block number to query
+    #[inline]
+    pub async fn from_route_with_quoter<T, P>(
+        provider: &P,
+        quoter: Address,
+        route: Route<TInput, TOutput, TP>,
+        amount: CurrencyAmount<impl BaseCurrency>,
+        trade_type: TradeType,
+        block_id: Option<BlockId>,
+    ) -> Result<Self, Error>
+    where
+        T: Transport + Clone,
+        P: Provider<T>,
+    {
+        let expected = match trade_type {
+            TradeType::ExactInput => route.input.wrapped(),
+            TradeType::ExactOutput => route.output.wrapped(),
+        };
+        if !amount.currency.wrapped().equals(expected) {
+            return Err(Error::CurrencyMismatch {
+                expected: expected.address(),
+                actual: amount.currency.wrapped().address(),
+            });
+        }
+        let quote =
+            quote_route_with_quoter_v2(provider, quoter, &route, &amount, trade_type, block_id)
+                .await?;
+        let (amount_in, amount_out) = match trade_type {
+            TradeType::ExactInput => (amount.quotient(), quote.amount.to_big_int()),
+            TradeType::ExactOutput => (quote.amount.to_big_int(), amount.quotient()),
+        };
+        let input_amount = CurrencyAmount::from_raw_amount(route.input.clone(), amount_in)?;
+        let output_amount = CurrencyAmount::from_raw_amount(route.output.clone(), amount_out)?;
+        Self::create_unchecked_trade(route, input_amount, output_amount, trade_type)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::tests::*;
+    use alloy_primitives::address;
+
+    const QUOTER_V2: Address = address!("61fFE014bA17989E743c5F6cB21bF9697530B21e");
+
+    async fn pool(fee: FeeAmount) -> Pool {
+        Pool::from_pool_key(
+            1,
+            FACTORY_ADDRESS,
+            WETH.address(),
+            USDC.address(),
+            fee,
+            PROVIDER.clone(),
+            *BLOCK_ID,
+        )
+        .await
+        .unwrap()
+    }
+
+    #[tokio::test]
+    async fn test_from_route_with_quoter_exact_input() {
+        let pool = pool(FeeAmount::LOW).await;
+        let amount_in = CurrencyAmount::from_raw_amount(WETH.clone(), 10_u128.pow(18)).unwrap();
+        let trade = Trade::from_route_with_quoter(
+            &PROVIDER.clone(),
+            QUOTER_V2,
+            Route::new(vec![pool], WETH.clone(), USDC.clone()),
+            amount_in.clone(),
+            TradeType::ExactInput,
+            *BLOCK_ID,
+        )
+        .await
+        .unwrap();
+        assert_eq!(trade.input_amount().unwrap(), amount_in);
+        assert!(trade.output_amount().unwrap().quotient() > BigInt::ZERO);
+    }
+
+    #[tokio::test]
+    async fn test_quote_multi_hop_exact_output() {
+        let (pool0, pool1) = (pool(FeeAmount::LOW).await, pool(FeeAmount::MEDIUM).await);
+        let route = Route::new(vec![pool0, pool1], WETH.clone(), WETH.clone());
+        let amount_out = CurrencyAmount::from_raw_amount(WETH.clone(), 10_u128.pow(18)).unwrap();
+        let quote = quote_route_with_quoter_v2(
+            &PROVIDER.clone(),
+            QUOTER_V2,
+            &route,
+            &amount_out,
+            TradeType::ExactOutput,
+            *BLOCK_ID,
+        )
+        .await
+        .unwrap();
+        assert!(quote.amount > U256::from(10_u128.pow(18)));
+        assert_eq!(quote.sqrt_price_x96_after_list.len(), 2);
+        assert_eq!(quote.initialized_ticks_crossed_list.len(), 2);
+    }
+}
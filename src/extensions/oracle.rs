@@ -0,0 +1,524 @@
+//! ## Oracle
+//! Reads the observations ring buffer of a pool directly from its storage with batched
+//! `eth_getStorageAt` requests and keeps a local replica in sync block by block, so that `observe`
+//! and TWAP queries are served locally without RPC latency, including at arbitrary past timestamps
+//! still covered by the buffer.
+
+use super::storage_tick_data_provider::get_storage_batched;
+use crate::prelude::{Error, *};
+use alloc::vec::Vec;
+use alloy::{
+    eips::{BlockId, BlockNumberOrTag},
+    providers::Provider,
+    transports::Transport,
+};
+use alloy_primitives::{
+    aliases::{I24, U24},
+    Address, U160, U256,
+};
+
+/// The storage slot of `slot0` of `UniswapV3Pool`.
+pub const SLOT0_SLOT: u64 = 0;
+/// The storage slot of `liquidity` of `UniswapV3Pool`.
+pub const LIQUIDITY_SLOT: u64 = 4;
+/// The storage slot of the first element of the `observations` array of `UniswapV3Pool`.
+pub const OBSERVATIONS_SLOT: u64 = 8;
+
+/// An element of the observations ring buffer of a pool.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, Hash)]
+pub struct Observation {
+    /// The block timestamp of the observation.
+    pub block_timestamp: u32,
+    /// The tick accumulator, i.e. tick * time elapsed since the pool was first initialized.
+    pub tick_cumulative: i64,
+    /// The seconds per liquidity, i.e. seconds elapsed / max(1, liquidity) since the pool was
+    /// first initialized.
+    pub seconds_per_liquidity_cumulative_x128: U160,
+    /// Whether the observation has been initialized.
+    pub initialized: bool,
+}
+
+impl Observation {
+    /// Decodes an observation from its packed storage slot
+    ///
+    /// ## Arguments
+    ///
+    /// * `value`: The value of the storage slot
+    #[inline]
+    #[must_use]
+    pub fn from_storage(value: U256) -> Self {
+        // int56 sign-extended from the 56 bits after the timestamp
+        let tick_cumulative = (((value >> 32).as_limbs()[0] << 8) as i64) >> 8;
+        Self {
+            block_timestamp: value.as_limbs()[0] as u32,
+            tick_cumulative,
+            seconds_per_liquidity_cumulative_x128: U160::from_be_slice(
+                &(value >> 88).to_be_bytes::<32>()[12..],
+            ),
+            initialized: value.bit(248),
+        }
+    }
+
+    /// Returns the observation extrapolated to a later timestamp, given the tick and liquidity in
+    /// effect since this observation, as `Oracle.transform`
+    ///
+    /// ## Arguments
+    ///
+    /// * `block_timestamp`: The timestamp of the new observation
+    /// * `tick`: The active tick since this observation
+    /// * `liquidity`: The in range liquidity since this observation
+    #[inline]
+    #[must_use]
+    pub fn transform(&self, block_timestamp: u32, tick: I24, liquidity: u128) -> Self {
+        let delta = block_timestamp.wrapping_sub(self.block_timestamp);
+        Self {
+            block_timestamp,
+            tick_cumulative: self
+                .tick_cumulative
+                .wrapping_add(tick.as_i64() * delta as i64),
+            seconds_per_liquidity_cumulative_x128: self
+                .seconds_per_liquidity_cumulative_x128
+                .wrapping_add(U160::from(
+                    (U256::from(delta) << 128) / U256::from(liquidity.max(1)),
+                )),
+            initialized: true,
+        }
+    }
+}
+
+/// Comparator for 32-bit timestamps that may have overflowed, as `Oracle.lte`
+#[inline]
+const fn lte(time: u32, a: u32, b: u32) -> bool {
+    if a <= time && b <= time {
+        return a <= b;
+    }
+    let a_adjusted = if a > time {
+        a as u64
+    } else {
+        a as u64 + (1 << 32)
+    };
+    let b_adjusted = if b > time {
+        b as u64
+    } else {
+        b as u64 + (1 << 32)
+    };
+    a_adjusted <= b_adjusted
+}
+
+/// The state of `slot0` relevant to the oracle.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+struct OracleSlot0 {
+    tick: I24,
+    observation_index: u16,
+    observation_cardinality: u16,
+}
+
+impl OracleSlot0 {
+    #[inline]
+    fn from_storage(value: U256) -> Self {
+        let upper = (value >> 160).as_limbs()[0];
+        Self {
+            tick: I24::from_raw(U24::from(upper & 0xffffff)),
+            observation_index: (upper >> 24) as u16,
+            observation_cardinality: (upper >> 40) as u16,
+        }
+    }
+}
+
+/// A local replica of the oracle of a pool, i.e. its observations ring buffer along with the
+/// current tick and liquidity, serving `observe` queries without RPC calls.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct OracleReplica {
+    /// The pool address.
+    pub pool: Address,
+    /// The observations ring buffer, of length `observation_cardinality`.
+    pub observations: Vec<Observation>,
+    /// The index of the most recently written observation.
+    pub observation_index: u16,
+    /// The current tick of the pool.
+    pub tick: I24,
+    /// The current in range liquidity of the pool.
+    pub liquidity: u128,
+}
+
+impl OracleReplica {
+    /// Reads the whole observations ring buffer of a pool along with its current tick and
+    /// liquidity in batched requests
+    ///
+    /// ## Arguments
+    ///
+    /// * `pool`: The pool address
+    /// * `provider`: The alloy provider
+    /// * `block_id`: Optional block number to query
+    #[inline]
+    pub async fn new<T, P>(
+        pool: Address,
+        provider: P,
+        block_id: Option<BlockId>,
+    ) -> Result<Self, Error>
+    where
+        T: Transport + Clone,
+        P: Provider<T>,
+    {
+        let block = block_id.unwrap_or(BlockId::Number(BlockNumberOrTag::Latest));
+        let (slot0, liquidity) = Self::read_state(&provider, pool, block).await?;
+        assert!(
+            slot0.observation_cardinality > 0,
+            "Pool has been created but not yet initialized"
+        );
+        let mut replica = Self {
+            pool,
+            observations: Vec::new(),
+            observation_index: slot0.observation_index,
+            tick: slot0.tick,
+            liquidity,
+        };
+        let indices: Vec<u16> = (0..slot0.observation_cardinality).collect();
+        replica.observations = replica
+            .read_observations(&provider, &indices, block)
+            .await?;
+        Ok(replica)
+    }
+
+    /// Syncs the replica with a later block, reading only the observations written since the
+    /// last sync, or the whole buffer if its cardinality has grown. Observations overwritten more
+    /// than once between two syncs are not detected, so the replica should be synced at least
+    /// once every `observation_cardinality` blocks with swaps.
+    ///
+    /// ## Arguments
+    ///
+    /// * `provider`: The alloy provider
+    /// * `block_id`: Optional block number to sync to
+    #[inline]
+    pub async fn sync<T, P>(&mut self, provider: P, block_id: Option<BlockId>) -> Result<(), Error>
+    where
+        T: Transport + Clone,
+        P: Provider<T>,
+    {
+        let block = block_id.unwrap_or(BlockId::Number(BlockNumberOrTag::Latest));
+        let (slot0, liquidity) = Self::read_state(&provider, self.pool, block).await?;
+        let cardinality = slot0.observation_cardinality;
+        if cardinality as usize != self.observations.len() {
+            let indices: Vec<u16> = (0..cardinality).collect();
+            self.observations = self.read_observations(&provider, &indices, block).await?;
+        } else if slot0.observation_index != self.observation_index {
+            let mut indices = Vec::new();
+            let mut index = self.observation_index;
+            while index != slot0.observation_index {
+                index = (index + 1) % cardinality;
+                indices.push(index);
+            }
+            let observations = self.read_observations(&provider, &indices, block).await?;
+            for (index, observation) in indices.into_iter().zip(observations) {
+                self.observations[index as usize] = observation;
+            }
+        }
+        self.observation_index = slot0.observation_index;
+        self.tick = slot0.tick;
+        self.liquidity = liquidity;
+        Ok(())
+    }
+
+    async fn read_state<T, P>(
+        provider: &P,
+        pool: Address,
+        block: BlockId,
+    ) -> Result<(OracleSlot0, u128), Error>
+    where
+        T: Transport + Clone,
+        P: Provider<T>,
+    {
+        let values = get_storage_batched(
+            provider,
+            pool,
+            &[U256::from(SLOT0_SLOT), U256::from(LIQUIDITY_SLOT)],
+            block,
+        )
+        .await?;
+        let liquidity = values[1].as_limbs();
+        Ok((
+            OracleSlot0::from_storage(values[0]),
+            liquidity[0] as u128 | ((liquidity[1] as u128) << 64),
+        ))
+    }
+
+    async fn read_observations<T, P>(
+        &self,
+        provider: &P,
+        indices: &[u16],
+        block: BlockId,
+    ) -> Result<Vec<Observation>, Error>
+    where
+        T: Transport + Clone,
+        P: Provider<T>,
+    {
+        let slots: Vec<U256> = indices
+            .iter()
+            .map(|&index| U256::from(OBSERVATIONS_SLOT + index as u64))
+            .collect();
+        let values = get_storage_batched(provider, self.pool, &slots, block).await?;
+        Ok(values.into_iter().map(Observation::from_storage).collect())
+    }
+
+    /// Returns the observations immediately before and at or after the target, as
+    /// `Oracle.binarySearch`
+    fn binary_search(&self, time: u32, target: u32) -> (Observation, Observation) {
+        let cardinality = self.observations.len();
+        let mut l = (self.observation_index as usize + 1) % cardinality;
+        let mut r = l + cardinality - 1;
+        loop {
+            let i = (l + r) / 2;
+            let before_or_at = self.observations[i % cardinality];
+            if !before_or_at.initialized {
+                l = i + 1;
+                continue;
+            }
+            let at_or_after = self.observations[(i + 1) % cardinality];
+            let target_at_or_after = lte(time, before_or_at.block_timestamp, target);
+            if target_at_or_after && lte(time, target, at_or_after.block_timestamp) {
+                return (before_or_at, at_or_after);
+            }
+            if target_at_or_after {
+                l = i + 1;
+            } else {
+                r = i - 1;
+            }
+        }
+    }
+
+    /// Returns the observations surrounding the target, as `Oracle.getSurroundingObservations`
+    fn surrounding_observations(
+        &self,
+        time: u32,
+        target: u32,
+    ) -> Result<(Observation, Observation), Error> {
+        let before_or_at = self.observations[self.observation_index as usize];
+        if lte(time, before_or_at.block_timestamp, target) {
+            return Ok(if before_or_at.block_timestamp == target {
+                (before_or_at, Observation::default())
+            } else {
+                (
+                    before_or_at,
+                    before_or_at.transform(target, self.tick, self.liquidity),
+                )
+            });
+        }
+        let mut oldest =
+            self.observations[(self.observation_index as usize + 1) % self.observations.len()];
+        if !oldest.initialized {
+            oldest = self.observations[0];
+        }
+        if !lte(time, oldest.block_timestamp, target) {
+            return Err(Error::ObservationTooOld(target));
+        }
+        Ok(self.binary_search(time, target))
+    }
+
+    /// Returns the accumulators `seconds_ago` seconds before `time`, as `Oracle.observeSingle`
+    ///
+    /// ## Arguments
+    ///
+    /// * `time`: The current block timestamp
+    /// * `seconds_ago`: How long ago to look back
+    ///
+    /// ## Returns
+    ///
+    /// (tick_cumulative, seconds_per_liquidity_cumulative_x128)
+    #[inline]
+    pub fn observe_single(&self, time: u32, seconds_ago: u32) -> Result<(i64, U160), Error> {
+        if seconds_ago == 0 {
+            let mut last = self.observations[self.observation_index as usize];
+            if last.block_timestamp != time {
+                last = last.transform(time, self.tick, self.liquidity);
+            }
+            return Ok((
+                last.tick_cumulative,
+                last.seconds_per_liquidity_cumulative_x128,
+            ));
+        }
+        let target = time.wrapping_sub(seconds_ago);
+        let (before_or_at, at_or_after) = self.surrounding_observations(time, target)?;
+        Ok(if target == before_or_at.block_timestamp {
+            (
+                before_or_at.tick_cumulative,
+                before_or_at.seconds_per_liquidity_cumulative_x128,
+            )
+        } else if target == at_or_after.block_timestamp {
+            (
+                at_or_after.tick_cumulative,
+                at_or_after.seconds_per_liquidity_cumulative_x128,
+            )
+        } else {
+            let observation_time_delta = at_or_after
+                .block_timestamp
+                .wrapping_sub(before_or_at.block_timestamp);
+            let target_delta = target.wrapping_sub(before_or_at.block_timestamp);
+            let tick_cumulative = before_or_at.tick_cumulative
+                + (at_or_after.tick_cumulative - before_or_at.tick_cumulative)
+                    / observation_time_delta as i64
+                    * target_delta as i64;
+            let seconds_per_liquidity_delta = U256::from(
+                at_or_after
+                    .seconds_per_liquidity_cumulative_x128
+                    .wrapping_sub(before_or_at.seconds_per_liquidity_cumulative_x128),
+            );
+            let seconds_per_liquidity_cumulative_x128 = before_or_at
+                .seconds_per_liquidity_cumulative_x128
+                .wrapping_add(U160::from(
+                    seconds_per_liquidity_delta * U256::from(target_delta)
+                        / U256::from(observation_time_delta),
+                ));
+            (tick_cumulative, seconds_per_liquidity_cumulative_x128)
+        })
+    }
+
+    /// Returns the accumulators as of each of the given seconds ago, as `UniswapV3Pool.observe`
+    ///
+    /// ## Arguments
+    ///
+    /// * `time`: The current block timestamp
+    /// * `seconds_agos`: How long ago to look back for each accumulator
+    ///
+    /// ## Returns
+    ///
+    /// (tick_cumulatives, seconds_per_liquidity_cumulative_x128s)
+    #[inline]
+    pub fn observe(&self, time: u32, seconds_agos: &[u32]) -> Result<(Vec<i64>, Vec<U160>), Error> {
+        seconds_agos
+            .iter()
+            .map(|&seconds_ago| self.observe_single(time, seconds_ago))
+            .collect::<Result<Vec<_>, _>>()
+            .map(|observations| observations.into_iter().unzip())
+    }
+
+    /// Returns the arithmetic mean tick and harmonic mean liquidity over the `period` seconds
+    /// ending `end_seconds_ago` seconds before `time`, as `OracleLibrary.consult` but for any past
+    /// window covered by the buffer
+    ///
+    /// ## Arguments
+    ///
+    /// * `time`: The current block timestamp
+    /// * `period`: The length of the window in seconds
+    /// * `end_seconds_ago`: How long ago the window ends, zero for a window ending now
+    #[inline]
+    pub fn consult(
+        &self,
+        time: u32,
+        period: u32,
+        end_seconds_ago: u32,
+    ) -> Result<(I24, u128), Error> {
+        assert!(period != 0, "BP");
+        let (tick_cumulatives, seconds_per_liquidity_cumulative_x128s) =
+            self.observe(time, &[end_seconds_ago + period, end_seconds_ago])?;
+        let tick_cumulatives_delta = tick_cumulatives[1] - tick_cumulatives[0];
+        let mut arithmetic_mean_tick = tick_cumulatives_delta / period as i64;
+        // always round to negative infinity
+        if tick_cumulatives_delta < 0 && tick_cumulatives_delta % period as i64 != 0 {
+            arithmetic_mean_tick -= 1;
+        }
+        let seconds_per_liquidity_delta = U256::from(
+            seconds_per_liquidity_cumulative_x128s[1]
+                .wrapping_sub(seconds_per_liquidity_cumulative_x128s[0]),
+        );
+        let seconds_ago_x160 = U256::from(period) * U256::from(U160::MAX);
+        let harmonic_mean_liquidity =
+            seconds_ago_x160 / (seconds_per_liquidity_delta << 32).max(U256::from(1));
+        Ok((
+            I24::try_from(arithmetic_mean_tick).unwrap(),
+            harmonic_mean_liquidity.as_limbs()[0] as u128
+                | ((harmonic_mean_liquidity.as_limbs()[1] as u128) << 64),
+        ))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::tests::*;
+    use alloy::rpc::types::BlockTransactionsKind;
+    use alloy_primitives::address;
+    use uniswap_lens::bindings::iuniswapv3pool::IUniswapV3Pool::IUniswapV3PoolInstance;
+
+    const POOL: Address = address!("88e6A0c2dDD26FEEb64F039a2c41296FcB3f5640");
+
+    #[test]
+    fn test_lte() {
+        assert!(lte(100, 50, 60));
+        assert!(!lte(100, 60, 50));
+        // timestamps after `time` have overflowed and are older
+        assert!(lte(100, u32::MAX, 50));
+        assert!(!lte(100, 50, u32::MAX));
+    }
+
+    #[test]
+    fn test_transform() {
+        let observation = Observation {
+            block_timestamp: 100,
+            tick_cumulative: 1000,
+            seconds_per_liquidity_cumulative_x128: U160::ZERO,
+            initialized: true,
+        };
+        let transformed = observation.transform(110, I24::try_from(-5).unwrap(), 0);
+        assert_eq!(transformed.block_timestamp, 110);
+        assert_eq!(transformed.tick_cumulative, 950);
+        assert_eq!(
+            transformed.seconds_per_liquidity_cumulative_x128,
+            U160::from(10) << 128
+        );
+    }
+
+    #[tokio::test]
+    async fn test_oracle_replica() {
+        let replica = OracleReplica::new(POOL, PROVIDER.clone(), *BLOCK_ID)
+            .await
+            .unwrap();
+        assert!(replica.observations.len() > 1);
+        let time = PROVIDER
+            .get_block_by_number(
+                BlockNumberOrTag::Number(17000000),
+                BlockTransactionsKind::Hashes,
+            )
+            .await
+            .unwrap()
+            .unwrap()
+            .header
+            .timestamp as u32;
+        let seconds_agos = vec![0, 60, 1800, 3600];
+        let (tick_cumulatives, seconds_per_liquidity_cumulative_x128s) =
+            replica.observe(time, &seconds_agos).unwrap();
+        let expected = IUniswapV3PoolInstance::new(POOL, PROVIDER.clone())
+            .observe(seconds_agos)
+            .block(BLOCK_ID.unwrap())
+            .call()
+            .await
+            .unwrap();
+        assert_eq!(
+            tick_cumulatives,
+            expected
+                .tickCumulatives
+                .iter()
+                .map(|tick_cumulative| tick_cumulative.as_i64())
+                .collect::<Vec<_>>()
+        );
+        assert_eq!(
+            seconds_per_liquidity_cumulative_x128s,
+            expected.secondsPerLiquidityCumulativeX128s
+        );
+        assert!(matches!(
+            replica.observe_single(time, u32::MAX / 2),
+            Err(Error::ObservationTooOld(_))
+        ));
+    }
+
+    #[tokio::test]
+    async fn test_oracle_replica_sync() {
+        let mut replica = OracleReplica::new(POOL, PROVIDER.clone(), Some(BlockId::from(16999900)))
+            .await
+            .unwrap();
+        replica.sync(PROVIDER.clone(), *BLOCK_ID).await.unwrap();
+        let expected = OracleReplica::new(POOL, PROVIDER.clone(), *BLOCK_ID)
+            .await
+            .unwrap();
+        assert_eq!(replica, expected);
+    }
+}
@@ -0,0 +1,253 @@
+//! ## Pending Pool
+//! Overlays the swaps of pending transactions, e.g. from a mempool stream, on the confirmed state of
+//! a pool, so that quotes reflect the state the pool is likely to be in once they are mined.
+
+use crate::prelude::{Error, *};
+use alloc::vec::Vec;
+use alloy_primitives::{aliases::U24, Address, U160, U256};
+use uniswap_sdk_core::prelude::*;
+
+/// A swap through a single pool decoded from the calldata of a pending transaction.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct PendingSwap {
+    pub token_in: Address,
+    pub token_out: Address,
+    pub fee: U24,
+    /// The input for exact input swaps, the output for exact output swaps.
+    pub amount: U256,
+    pub trade_type: TradeType,
+    /// The price limit of the swap, zero if none.
+    pub sqrt_price_limit_x96: U160,
+}
+
+impl From<&DecodedSwap> for PendingSwap {
+    /// Takes the pool the amount specified by the swap applies to, i.e. the first pool for exact
+    /// input, or the last pool for exact output
+    #[inline]
+    fn from(swap: &DecodedSwap) -> Self {
+        let hop = match swap.trade_type {
+            TradeType::ExactInput => 0,
+            TradeType::ExactOutput => swap.fees.len() - 1,
+        };
+        Self {
+            token_in: swap.tokens[hop],
+            token_out: swap.tokens[hop + 1],
+            fee: swap.fees[hop],
+            amount: swap.amount,
+            trade_type: swap.trade_type,
+            sqrt_price_limit_x96: swap.sqrt_price_limit_x96,
+        }
+    }
+}
+
+/// Decodes the swaps in the calldata of `SwapRouter02` or the original `SwapRouter`, wrapped in a
+/// `multicall` or not, see [`decode_swap_calldata`]. Swaps with an invalid path are skipped.
+///
+/// The amount specified by a multihop swap only applies to its first pool for exact input, or its
+/// last pool for exact output, so only that pool is returned. The amounts through the other pools
+/// depend on the state of the preceding ones.
+///
+/// ## Arguments
+///
+/// * `input`: The calldata of the transaction
+#[inline]
+#[must_use]
+pub fn decode_router_swaps(input: &[u8]) -> Vec<PendingSwap> {
+    let (_, calls) = unwrap_multicall(input);
+    calls
+        .iter()
+        .filter_map(|call| DecodedSwap::decode(call).ok().flatten())
+        .map(|swap| PendingSwap::from(&swap))
+        .collect()
+}
+
+/// The confirmed state of a pool overlaid with the swaps of pending transactions.
+#[derive(Clone, Debug)]
+pub struct PendingPool<TP: TickDataProvider> {
+    /// The state of the pool in the latest block.
+    pub confirmed: Pool<TP>,
+    /// The state of the pool after the pending swaps.
+    pub pending: Pool<TP>,
+    /// The routers whose transactions are decoded.
+    pub routers: Vec<Address>,
+    /// The pending swaps applied to the pool, in order.
+    pub swaps: Vec<PendingSwap>,
+}
+
+impl<TP: Clone + TickDataProvider> PendingPool<TP> {
+    /// Creates an overlay without any pending swap
+    ///
+    /// ## Arguments
+    ///
+    /// * `pool`: The confirmed state of the pool
+    /// * `routers`: The routers whose transactions are decoded, e.g. `SwapRouter02`
+    #[inline]
+    #[must_use]
+    pub fn new(pool: Pool<TP>, routers: Vec<Address>) -> Self {
+        Self {
+            pending: pool.clone(),
+            confirmed: pool,
+            routers,
+            swaps: Vec::new(),
+        }
+    }
+
+    /// Replaces the confirmed state, e.g. on a new block, and drops the pending swaps
+    ///
+    /// ## Arguments
+    ///
+    /// * `pool`: The new confirmed state of the pool
+    #[inline]
+    pub fn reset(&mut self, pool: Pool<TP>) {
+        self.pending = pool.clone();
+        self.confirmed = pool;
+        self.swaps.clear();
+    }
+
+    /// Applies the swaps of a pending transaction through the pool, returning how many were
+    /// applied. Transactions that are not sent to one of the routers are ignored.
+    ///
+    /// ## Arguments
+    ///
+    /// * `to`: The recipient of the transaction
+    /// * `input`: The calldata of the transaction
+    #[inline]
+    pub fn apply_transaction(&mut self, to: Address, input: &[u8]) -> usize {
+        if !self.routers.contains(&to) {
+            return 0;
+        }
+        decode_router_swaps(input)
+            .iter()
+            .filter(|swap| self.apply_swap(swap))
+            .count()
+    }
+
+    /// Simulates a pending swap against the pending state, returning whether it went through the
+    /// pool. Swaps that would revert, e.g. for lack of liquidity, leave the state unchanged.
+    ///
+    /// ## Arguments
+    ///
+    /// * `swap`: The swap to apply
+    #[inline]
+    pub fn apply_swap(&mut self, swap: &PendingSwap) -> bool {
+        let pool = &mut self.pending;
+        let fee: U24 = pool.fee.into();
+        if swap.fee != fee || swap.token_in == swap.token_out || swap.amount.is_zero() {
+            return false;
+        }
+        let (token_in, token_out) = if swap.token_in == pool.token0.address()
+            && swap.token_out == pool.token1.address()
+        {
+            (pool.token0.clone(), pool.token1.clone())
+        } else if swap.token_in == pool.token1.address() && swap.token_out == pool.token0.address()
+        {
+            (pool.token1.clone(), pool.token0.clone())
+        } else {
+            return false;
+        };
+        let sqrt_price_limit_x96 =
+            (!swap.sqrt_price_limit_x96.is_zero()).then_some(swap.sqrt_price_limit_x96);
+        let applied = match swap.trade_type {
+            TradeType::ExactInput => {
+                CurrencyAmount::from_raw_amount(token_in, swap.amount.to_big_int())
+                    .map_err(Error::Core)
+                    .and_then(|amount| pool.get_output_amount_mut(&amount, sqrt_price_limit_x96))
+                    .is_ok()
+            }
+            TradeType::ExactOutput => {
+                CurrencyAmount::from_raw_amount(token_out, swap.amount.to_big_int())
+                    .map_err(Error::Core)
+                    .and_then(|amount| pool.get_input_amount_mut(&amount, sqrt_price_limit_x96))
+                    .is_ok()
+            }
+        };
+        if applied {
+            self.swaps.push(*swap);
+        }
+        applied
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::tests::*;
+    use alloy_primitives::address;
+
+    const ROUTER: Address = address!("68b3465833fb72A70ecDF485E0e4C7bD8665Fc45");
+
+    fn exact_input(
+        pools: Vec<Pool<TickListDataProvider>>,
+        output: &Token,
+    ) -> Trade<Token, Token, TickListDataProvider> {
+        Trade::from_route(
+            Route::new(pools, TOKEN0.clone(), output.clone()),
+            CurrencyAmount::from_raw_amount(TOKEN0.clone(), 10000).unwrap(),
+            TradeType::ExactInput,
+        )
+        .unwrap()
+    }
+
+    #[test]
+    fn applies_pending_swaps() {
+        let pool = make_pool(TOKEN0.clone(), TOKEN1.clone());
+        let mut pending = PendingPool::new(pool.clone(), vec![ROUTER]);
+        let params = swap_call_parameters(
+            &mut [exact_input(vec![pool.clone()], &TOKEN1)],
+            SwapOptions {
+                slippage_tolerance: Percent::new(1, 100),
+                recipient: ROUTER,
+                ..Default::default()
+            },
+        )
+        .unwrap();
+
+        assert_eq!(
+            pending.apply_transaction(TOKEN0.address(), &params.calldata),
+            0
+        );
+        assert_eq!(pending.apply_transaction(ROUTER, &params.calldata), 1);
+        let mut expected = pool.clone();
+        expected
+            .get_output_amount_mut(
+                &CurrencyAmount::from_raw_amount(TOKEN0.clone(), 10000).unwrap(),
+                None,
+            )
+            .unwrap();
+        assert_eq!(pending.pending.sqrt_ratio_x96, expected.sqrt_ratio_x96);
+        assert_eq!(pending.pending.liquidity, expected.liquidity);
+        assert_eq!(pending.confirmed, pool);
+        assert_eq!(pending.swaps.len(), 1);
+
+        pending.reset(pool.clone());
+        assert_eq!(pending.pending.sqrt_ratio_x96, pool.sqrt_ratio_x96);
+        assert!(pending.swaps.is_empty());
+    }
+
+    #[test]
+    fn decodes_first_hop_of_multihop_swaps() {
+        let pool_0_1 = make_pool(TOKEN0.clone(), TOKEN1.clone());
+        let pool_1_2 = make_pool(TOKEN1.clone(), TOKEN2.clone());
+        let params = swap_call_parameters(
+            &mut [exact_input(vec![pool_0_1.clone(), pool_1_2], &TOKEN2)],
+            SwapOptions {
+                slippage_tolerance: Percent::new(1, 100),
+                recipient: ROUTER,
+                ..Default::default()
+            },
+        )
+        .unwrap();
+        let swaps = decode_router_swaps(&params.calldata);
+        assert_eq!(swaps.len(), 1);
+        assert_eq!(swaps[0].token_in, TOKEN0.address());
+        assert_eq!(swaps[0].token_out, TOKEN1.address());
+        assert_eq!(swaps[0].amount, U256::from(10000));
+
+        let mut pending = PendingPool::new(pool_0_1, vec![ROUTER]);
+        assert!(pending.apply_swap(&swaps[0]));
+        assert!(!pending.apply_swap(&PendingSwap {
+            token_out: TOKEN2.address(),
+            ..swaps[0]
+        }));
+    }
+}
@@ -0,0 +1,188 @@
+//! ## Permit2 Allowance
+//! Reads the `Permit2` allowances of an owner and plans whether a new signature is needed for a
+//! trade before building its calldata, see [`plan_permit2`].
+
+use super::permit_pruning::get_allowance;
+use crate::prelude::{Error, *};
+use alloy::{
+    contract::Error as ContractError, eips::BlockId, providers::Provider,
+    rpc::types::TransactionRequest, transports::Transport,
+};
+use alloy_primitives::{Address, U256};
+use alloy_sol_types::SolCall;
+use uniswap_sdk_core::prelude::*;
+
+/// Returns the `Permit2` allowance of a spender over the tokens of an owner
+///
+/// ## Arguments
+///
+/// * `provider`: The alloy provider
+/// * `owner`: The owner of the tokens
+/// * `token`: The address of the token
+/// * `spender`: The spender of the tokens, e.g. the Universal Router
+/// * `block_id`: The block to query
+#[inline]
+pub async fn get_permit2_allowance<T, P>(
+    provider: &P,
+    owner: Address,
+    token: Address,
+    spender: Address,
+    block_id: BlockId,
+) -> Result<Permit2Allowance, Error>
+where
+    T: Transport + Clone,
+    P: Provider<T>,
+{
+    let tx = TransactionRequest::default().to(PERMIT2_ADDRESS).input(
+        IAllowanceTransfer::allowanceCall {
+            user: owner,
+            token,
+            spender,
+        }
+        .abi_encode()
+        .into(),
+    );
+    let res = provider.call(&tx).block(block_id).await?;
+    let allowance = IAllowanceTransfer::allowanceCall::abi_decode_returns(&res, true)
+        .map_err(ContractError::from)?;
+    Ok(Permit2Allowance {
+        amount: allowance.amount,
+        expiration: allowance.expiration.to(),
+        nonce: allowance.nonce.to(),
+    })
+}
+
+/// Fetches the allowances of the owner over the input token of the trades and plans whether
+/// `Permit2` must be approved and a new permit signed for the spender to pull the maximum amount
+/// in of the trades
+///
+/// Trades from the native currency need neither, since the input is sent as value.
+///
+/// ## Arguments
+///
+/// * `provider`: The alloy provider
+/// * `trades`: The trades to swap
+/// * `slippage_tolerance`: The slippage tolerance of the trades
+/// * `owner`: The account sending the transaction
+/// * `spender`: The spender of the allowance, e.g. the Universal Router
+/// * `valid_until`: The deadline of the swap
+/// * `options`: The terms of the new permit if one is needed
+/// * `block_id`: The block the allowances are checked at
+#[inline]
+#[allow(clippy::too_many_arguments)]
+pub async fn plan_permit2_for_trades<TInput, TOutput, TP, T, P>(
+    provider: &P,
+    trades: &mut [Trade<TInput, TOutput, TP>],
+    slippage_tolerance: &Percent,
+    owner: Address,
+    spender: Address,
+    valid_until: u64,
+    options: &Permit2PlanOptions,
+    block_id: BlockId,
+) -> Result<Permit2Plan, Error>
+where
+    TInput: BaseCurrency,
+    TOutput: BaseCurrency,
+    TP: TickDataProvider,
+    T: Transport + Clone,
+    P: Provider<T>,
+{
+    if trades.is_empty() {
+        return Err(Error::NoTrades);
+    }
+    // native input is sent as value, so there is nothing to approve
+    if trades[0].input_currency().is_native() {
+        return Ok(Permit2Plan::default());
+    }
+    let token_in = trades[0].input_currency().wrapped().address();
+    let mut amount_in_maximum = BigInt::ZERO;
+    for trade in trades.iter_mut() {
+        amount_in_maximum += trade
+            .maximum_amount_in_cached(slippage_tolerance.clone(), None)?
+            .quotient();
+    }
+    let erc20_allowance =
+        get_allowance(provider, token_in, owner, PERMIT2_ADDRESS, block_id).await?;
+    let allowance = get_permit2_allowance(provider, owner, token_in, spender, block_id).await?;
+    Ok(plan_permit2(
+        token_in,
+        spender,
+        big_int_to_u256(&amount_in_maximum)?,
+        valid_until,
+        erc20_allowance,
+        &allowance,
+        options,
+    ))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::tests::*;
+    use alloy_primitives::address;
+
+    #[tokio::test]
+    async fn test_get_permit2_allowance() {
+        // an account without any allowance
+        let owner = address!("0000000000000000000000000000000000000009");
+        let spender = address!("3fC91A3afd70395Cd496C647d5a6CC9D4B2b7FAD");
+        let allowance = get_permit2_allowance(
+            &PROVIDER.clone(),
+            owner,
+            USDC.address(),
+            spender,
+            BLOCK_ID.unwrap(),
+        )
+        .await
+        .unwrap();
+        assert_eq!(allowance, Permit2Allowance::default());
+        let plan = plan_permit2(
+            USDC.address(),
+            spender,
+            U256::from(1),
+            0,
+            U256::ZERO,
+            &allowance,
+            &Permit2PlanOptions::default(),
+        );
+        assert!(plan.approve_permit2);
+        assert!(plan.permit.is_some());
+    }
+
+    #[tokio::test]
+    async fn test_plan_permit2_for_native_input() {
+        let mut trades = [Trade::from_route(
+            Route::new(vec![POOL_0_WETH.clone()], ETHER.clone(), TOKEN0.clone()),
+            CurrencyAmount::from_raw_amount(ETHER.clone(), 100).unwrap(),
+            TradeType::ExactInput,
+        )
+        .unwrap()];
+        let plan = plan_permit2_for_trades(
+            &PROVIDER.clone(),
+            &mut trades,
+            &Percent::new(1, 100),
+            address!("0000000000000000000000000000000000000009"),
+            address!("3fC91A3afd70395Cd496C647d5a6CC9D4B2b7FAD"),
+            0,
+            &Permit2PlanOptions::default(),
+            BLOCK_ID.unwrap(),
+        )
+        .await
+        .unwrap();
+        assert_eq!(plan, Permit2Plan::default());
+
+        let mut trades: [Trade<Ether, Token, NoTickDataProvider>; 0] = [];
+        let result = plan_permit2_for_trades(
+            &PROVIDER.clone(),
+            &mut trades,
+            &Percent::new(1, 100),
+            address!("0000000000000000000000000000000000000009"),
+            address!("3fC91A3afd70395Cd496C647d5a6CC9D4B2b7FAD"),
+            0,
+            &Permit2PlanOptions::default(),
+            BLOCK_ID.unwrap(),
+        )
+        .await;
+        assert!(matches!(result, Err(Error::NoTrades)));
+    }
+}
@@ -0,0 +1,166 @@
+//! ## Permit Domain
+//! Reads the EIP-712 domain separator and permit type hash of a position manager, so that the
+//! domain constructed locally for the NFT permits can be verified against the deployed contract
+//! before any signature is collected.
+
+use crate::prelude::{Error, *};
+use alloy::{
+    contract::Error as ContractError, eips::BlockId, providers::Provider,
+    rpc::types::TransactionRequest, transports::Transport,
+};
+use alloy_primitives::{Address, B256};
+use alloy_sol_types::{SolCall, SolStruct};
+
+/// Returns the `DOMAIN_SEPARATOR` of a position manager
+///
+/// ## Arguments
+///
+/// * `provider`: The alloy provider
+/// * `position_manager`: The address of the position manager
+/// * `block_id`: Optional block number to query
+#[inline]
+pub async fn get_permit_domain_separator<T, P>(
+    provider: &P,
+    position_manager: Address,
+    block_id: Option<BlockId>,
+) -> Result<B256, Error>
+where
+    T: Transport + Clone,
+    P: Provider<T>,
+{
+    let tx = TransactionRequest::default()
+        .to(position_manager)
+        .input(IERC721Permit::DOMAIN_SEPARATORCall {}.abi_encode().into());
+    let res = provider
+        .call(&tx)
+        .block(block_id.unwrap_or_default())
+        .await?;
+    Ok(
+        IERC721Permit::DOMAIN_SEPARATORCall::abi_decode_returns(&res, true)
+            .map_err(ContractError::from)?
+            ._0,
+    )
+}
+
+/// Returns the `PERMIT_TYPEHASH` of a position manager
+///
+/// ## Arguments
+///
+/// * `provider`: The alloy provider
+/// * `position_manager`: The address of the position manager
+/// * `block_id`: Optional block number to query
+#[inline]
+pub async fn get_permit_typehash<T, P>(
+    provider: &P,
+    position_manager: Address,
+    block_id: Option<BlockId>,
+) -> Result<B256, Error>
+where
+    T: Transport + Clone,
+    P: Provider<T>,
+{
+    let tx = TransactionRequest::default()
+        .to(position_manager)
+        .input(IERC721Permit::PERMIT_TYPEHASHCall {}.abi_encode().into());
+    let res = provider
+        .call(&tx)
+        .block(block_id.unwrap_or_default())
+        .await?;
+    Ok(
+        IERC721Permit::PERMIT_TYPEHASHCall::abi_decode_returns(&res, true)
+            .map_err(ContractError::from)?
+            ._0,
+    )
+}
+
+/// Verifies that the EIP-712 domain of the config, as used by [`get_permit_data_with_config`],
+/// and the type of [`NFTPermitValues`] match those of the deployed position manager
+///
+/// ## Arguments
+///
+/// * `provider`: The alloy provider
+/// * `config`: The position manager the permits are signed for
+/// * `chain_id`: The chain ID
+/// * `block_id`: Optional block number to query
+///
+/// ## Returns
+///
+/// [`Error::PermitDomainMismatch`] with the local and on-chain hashes if either differs
+#[inline]
+pub async fn verify_permit_domain<T, P>(
+    provider: &P,
+    config: &NpmConfig,
+    chain_id: u64,
+    block_id: Option<BlockId>,
+) -> Result<(), Error>
+where
+    T: Transport + Clone,
+    P: Provider<T>,
+{
+    let expected = config.eip712_domain(chain_id).separator();
+    let actual = get_permit_domain_separator(provider, config.address, block_id).await?;
+    if expected != actual {
+        return Err(Error::PermitDomainMismatch { expected, actual });
+    }
+    let expected = NFTPermitValues::default().eip712_type_hash();
+    let actual = get_permit_typehash(provider, config.address, block_id).await?;
+    if expected != actual {
+        return Err(Error::PermitDomainMismatch { expected, actual });
+    }
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::tests::*;
+    use alloy_primitives::{address, U256};
+
+    const NPM: Address = address!("C36442b4a4522E871399CD717aBDD847Ab11FE88");
+
+    #[tokio::test]
+    async fn test_verify_permit_domain() {
+        let mut config = NpmConfig::new(NPM);
+        verify_permit_domain(&PROVIDER.clone(), &config, 1, *BLOCK_ID)
+            .await
+            .unwrap();
+        config.eip712_name = "Fork Positions NFT-V1".into();
+        assert!(matches!(
+            verify_permit_domain(&PROVIDER.clone(), &config, 1, *BLOCK_ID).await,
+            Err(Error::PermitDomainMismatch { .. })
+        ));
+    }
+
+    #[tokio::test]
+    async fn test_known_token_permit_domains() {
+        for token in [DAI.clone(), USDC.clone()] {
+            let data = get_token_permit_data(
+                &token,
+                Address::ZERO,
+                Address::ZERO,
+                U256::ZERO,
+                U256::ZERO,
+                U256::ZERO,
+                &KnownPermitConfigs,
+            )
+            .unwrap();
+            // both tokens expose the same getters as the position manager
+            assert_eq!(
+                data.domain().separator(),
+                get_permit_domain_separator(&PROVIDER.clone(), token.address(), *BLOCK_ID)
+                    .await
+                    .unwrap()
+            );
+            let type_hash = match &data {
+                TokenPermitData::Standard(permit) => permit.values.eip712_type_hash(),
+                TokenPermitData::Allowed(permit) => permit.values.eip712_type_hash(),
+            };
+            assert_eq!(
+                type_hash,
+                get_permit_typehash(&PROVIDER.clone(), token.address(), *BLOCK_ID)
+                    .await
+                    .unwrap()
+            );
+        }
+    }
+}
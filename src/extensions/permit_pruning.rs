@@ -0,0 +1,376 @@
+//! ## Permit Pruning
+//! Drops the `selfPermit` calls of tokens that the owner has already approved enough of, checked
+//! against the allowances at a pinned block, so that unnecessary permits neither lengthen the
+//! calldata nor consume a nonce.
+
+use crate::prelude::{Error, *};
+use alloy::{
+    contract::Error as ContractError, eips::BlockId, providers::Provider,
+    rpc::types::TransactionRequest, transports::Transport,
+};
+use alloy_primitives::{Address, U256};
+use alloy_sol_types::SolCall;
+use uniswap_sdk_core::prelude::*;
+
+/// Returns the allowance of a token from the owner to the spender
+///
+/// ## Arguments
+///
+/// * `provider`: The alloy provider
+/// * `token`: The address of the token
+/// * `owner`: The owner of the tokens
+/// * `spender`: The spender of the tokens
+/// * `block_id`: The block to query
+#[inline]
+pub async fn get_allowance<T, P>(
+    provider: &P,
+    token: Address,
+    owner: Address,
+    spender: Address,
+    block_id: BlockId,
+) -> Result<U256, Error>
+where
+    T: Transport + Clone,
+    P: Provider<T>,
+{
+    let tx = TransactionRequest::default()
+        .to(token)
+        .input(IERC20::allowanceCall { owner, spender }.abi_encode().into());
+    let res = provider.call(&tx).block(block_id).await?;
+    Ok(IERC20::allowanceCall::abi_decode_returns(&res, true)
+        .map_err(ContractError::from)?
+        ._0)
+}
+
+/// Drops the permits of the options for the tokens whose allowance to the position manager already
+/// covers the desired amounts of the position, returning how many were dropped
+///
+/// ## Arguments
+///
+/// * `options`: The options to add liquidity with
+/// * `position`: The position to add liquidity to
+/// * `owner`: The account sending the transaction
+/// * `position_manager`: The address of the position manager
+/// * `provider`: The alloy provider
+/// * `block_id`: The block the allowances are checked at
+#[inline]
+pub async fn prune_add_liquidity_permits<TP, T, P>(
+    options: &mut AddLiquidityOptions,
+    position: &mut Position<TP>,
+    owner: Address,
+    position_manager: Address,
+    provider: &P,
+    block_id: BlockId,
+) -> Result<usize, Error>
+where
+    TP: TickDataProvider,
+    T: Transport + Clone,
+    P: Provider<T>,
+{
+    let mut allowances = [U256::ZERO; 2];
+    for (allowance, permit, token) in [
+        (
+            &mut allowances[0],
+            &options.token0_permit,
+            position.pool.token0.address(),
+        ),
+        (
+            &mut allowances[1],
+            &options.token1_permit,
+            position.pool.token1.address(),
+        ),
+    ] {
+        if permit.is_some() {
+            *allowance = get_allowance(provider, token, owner, position_manager, block_id).await?;
+        }
+    }
+    prune_add_liquidity_permits_with_allowances(options, position, allowances)
+}
+
+/// Same as [`prune_add_liquidity_permits`] but with the allowances of the tokens to the position
+/// manager already known, e.g. fetched in a batch
+///
+/// ## Arguments
+///
+/// * `options`: The options to add liquidity with
+/// * `position`: The position to add liquidity to
+/// * `allowances`: The allowances of token0 and token1 to the position manager
+#[inline]
+pub fn prune_add_liquidity_permits_with_allowances<TP: TickDataProvider>(
+    options: &mut AddLiquidityOptions,
+    position: &mut Position<TP>,
+    allowances: [U256; 2],
+) -> Result<usize, Error> {
+    let MintAmounts { amount0, amount1 } = position.mint_amounts_cached()?;
+    let mut pruned = 0;
+    for (permit, allowance, amount) in [
+        (&mut options.token0_permit, allowances[0], amount0),
+        (&mut options.token1_permit, allowances[1], amount1),
+    ] {
+        if permit.is_some() && allowance >= amount {
+            *permit = None;
+            pruned += 1;
+        }
+    }
+    Ok(pruned)
+}
+
+/// Drops the input token permit of the options if the allowance to the router already covers the
+/// maximum amount spent by the trades, returning whether it was dropped
+///
+/// ## Arguments
+///
+/// * `options`: The options to swap with
+/// * `trades`: The trades to swap
+/// * `owner`: The account sending the transaction
+/// * `router`: The address of the router
+/// * `provider`: The alloy provider
+/// * `block_id`: The block the allowance is checked at
+#[inline]
+pub async fn prune_swap_permit<TInput, TOutput, TP, T, P>(
+    options: &mut SwapOptions,
+    trades: &mut [Trade<TInput, TOutput, TP>],
+    owner: Address,
+    router: Address,
+    provider: &P,
+    block_id: BlockId,
+) -> Result<bool, Error>
+where
+    TInput: BaseCurrency,
+    TOutput: BaseCurrency,
+    TP: TickDataProvider,
+    T: Transport + Clone,
+    P: Provider<T>,
+{
+    if options.input_token_permit.is_none() || trades.is_empty() {
+        return Ok(false);
+    }
+    let token_in = trades[0].input_currency().wrapped().address();
+    let allowance = get_allowance(provider, token_in, owner, router, block_id).await?;
+    prune_swap_permit_with_allowance(options, trades, allowance)
+}
+
+/// Same as [`prune_swap_permit`] but with the allowance of the input token to the router already
+/// known
+///
+/// ## Arguments
+///
+/// * `options`: The options to swap with
+/// * `trades`: The trades to swap
+/// * `allowance`: The allowance of the input token to the router
+#[inline]
+pub fn prune_swap_permit_with_allowance<TInput, TOutput, TP>(
+    options: &mut SwapOptions,
+    trades: &mut [Trade<TInput, TOutput, TP>],
+    allowance: U256,
+) -> Result<bool, Error>
+where
+    TInput: BaseCurrency,
+    TOutput: BaseCurrency,
+    TP: TickDataProvider,
+{
+    if options.input_token_permit.is_none() || trades.is_empty() {
+        return Ok(false);
+    }
+    let mut amount_in_maximum = BigInt::ZERO;
+    for trade in trades.iter_mut() {
+        amount_in_maximum += trade
+            .maximum_amount_in_cached(options.slippage_tolerance.clone(), None)?
+            .quotient();
+    }
+    if allowance < big_int_to_u256(&amount_in_maximum)? {
+        return Ok(false);
+    }
+    options.input_token_permit = None;
+    Ok(true)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::tests::*;
+    use alloy_primitives::address;
+
+    const OWNER: Address = address!("0000000000000000000000000000000000000009");
+
+    fn permit() -> PermitOptions {
+        PermitOptions::Standard(StandardPermitArguments::new(
+            U256::from(1),
+            U256::from(2),
+            false,
+            U256::MAX,
+            U256::MAX,
+        ))
+    }
+
+    #[test]
+    fn test_prune_add_liquidity_permits_with_allowances() {
+        let tick_spacing = FeeAmount::MEDIUM.tick_spacing().as_i32();
+        let position = Position::new(POOL_0_1.clone(), 1_000_000, -tick_spacing, tick_spacing);
+        let MintAmounts { amount0, amount1 } = position.clone().mint_amounts_cached().unwrap();
+        assert!(amount0 > U256::ZERO && amount1 > U256::ZERO);
+        let options = AddLiquidityOptions {
+            slippage_tolerance: Percent::new(1, 100),
+            deadline: U256::MAX,
+            use_native: None,
+            token0_permit: Some(permit()),
+            token1_permit: Some(permit()),
+            specific_opts: AddLiquiditySpecificOptions::Increase(IncreaseSpecificOptions {
+                token_id: U256::from(1),
+            }),
+        };
+        let prune = |options: &mut AddLiquidityOptions, allowances| {
+            prune_add_liquidity_permits_with_allowances(options, &mut position.clone(), allowances)
+                .unwrap()
+        };
+
+        // an allowance of exactly the desired amount suffices
+        let mut pruned = options.clone();
+        assert_eq!(prune(&mut pruned, [amount0, amount1 - U256::from(1)]), 1);
+        assert!(pruned.token0_permit.is_none());
+        assert!(pruned.token1_permit.is_some());
+
+        let mut pruned = options.clone();
+        assert_eq!(prune(&mut pruned, [U256::MAX, U256::MAX]), 2);
+        assert!(pruned.token0_permit.is_none());
+        assert!(pruned.token1_permit.is_none());
+        // the calldata no longer spends the permits
+        let with_permits = add_call_parameters(&mut position.clone(), options.clone()).unwrap();
+        let without_permits = add_call_parameters(&mut position.clone(), pruned).unwrap();
+        assert!(without_permits.calldata.len() < with_permits.calldata.len());
+
+        let mut pruned = options;
+        assert_eq!(prune(&mut pruned, [U256::ZERO, U256::ZERO]), 0);
+        assert!(pruned.token0_permit.is_some());
+        assert!(pruned.token1_permit.is_some());
+    }
+
+    #[test]
+    fn test_prune_swap_permit_with_allowance() {
+        let trade = || {
+            Trade::from_route(
+                Route::new(
+                    vec![make_pool(TOKEN0.clone(), TOKEN1.clone())],
+                    TOKEN0.clone(),
+                    TOKEN1.clone(),
+                ),
+                CurrencyAmount::from_raw_amount(TOKEN0.clone(), 100).unwrap(),
+                TradeType::ExactInput,
+            )
+            .unwrap()
+        };
+        let options = SwapOptions {
+            slippage_tolerance: Percent::new(1, 100),
+            input_token_permit: Some(permit()),
+            ..Default::default()
+        };
+        // an exact input trade spends at most its input
+        let mut pruned = options.clone();
+        assert!(
+            !prune_swap_permit_with_allowance(&mut pruned, &mut [trade()], U256::from(99)).unwrap()
+        );
+        assert!(pruned.input_token_permit.is_some());
+        assert!(
+            prune_swap_permit_with_allowance(&mut pruned, &mut [trade()], U256::from(100)).unwrap()
+        );
+        assert!(pruned.input_token_permit.is_none());
+        // the allowance must cover every trade
+        let mut pruned = options;
+        assert!(!prune_swap_permit_with_allowance(
+            &mut pruned,
+            &mut [trade(), trade()],
+            U256::from(100)
+        )
+        .unwrap());
+        assert!(prune_swap_permit_with_allowance(
+            &mut pruned,
+            &mut [trade(), trade()],
+            U256::from(200)
+        )
+        .unwrap());
+        // nothing to prune without a permit
+        assert!(!prune_swap_permit_with_allowance(&mut pruned, &mut [trade()], U256::MAX).unwrap());
+    }
+
+    #[tokio::test]
+    async fn test_prune_add_liquidity_permits() {
+        let pool = Pool::from_pool_key(
+            1,
+            FACTORY_ADDRESS,
+            WETH.address(),
+            USDC.address(),
+            FeeAmount::LOW,
+            PROVIDER.clone(),
+            *BLOCK_ID,
+        )
+        .await
+        .unwrap();
+        let tick_spacing = pool.tick_spacing();
+        let tick_upper = nearest_usable_tick(pool.tick_current, tick_spacing) - tick_spacing * 10;
+        // below the current tick, the position only needs token1
+        let mut position =
+            Position::new(pool, 1_000_000, tick_upper - tick_spacing * 10, tick_upper);
+        let mut options = AddLiquidityOptions {
+            slippage_tolerance: Percent::new(1, 100),
+            deadline: U256::MAX,
+            use_native: None,
+            token0_permit: Some(permit()),
+            token1_permit: Some(permit()),
+            specific_opts: AddLiquiditySpecificOptions::Increase(IncreaseSpecificOptions {
+                token_id: U256::from(1),
+            }),
+        };
+        let npm = *NONFUNGIBLE_POSITION_MANAGER_ADDRESSES.get(&1).unwrap();
+        let pruned = prune_add_liquidity_permits(
+            &mut options,
+            &mut position,
+            OWNER,
+            npm,
+            &PROVIDER.clone(),
+            BLOCK_ID.unwrap(),
+        )
+        .await
+        .unwrap();
+        assert_eq!(pruned, 1);
+        assert!(options.token0_permit.is_none());
+        assert!(options.token1_permit.is_some());
+    }
+
+    #[tokio::test]
+    async fn test_prune_swap_permit() {
+        let pool = Pool::from_pool_key(
+            1,
+            FACTORY_ADDRESS,
+            WETH.address(),
+            USDC.address(),
+            FeeAmount::LOW,
+            PROVIDER.clone(),
+            *BLOCK_ID,
+        )
+        .await
+        .unwrap();
+        let mut trades = [Trade::from_route(
+            Route::new(vec![pool], USDC.clone(), WETH.clone()),
+            CurrencyAmount::from_raw_amount(USDC.clone(), 1_000_000).unwrap(),
+            TradeType::ExactInput,
+        )
+        .unwrap()];
+        let mut options = SwapOptions {
+            slippage_tolerance: Percent::new(1, 100),
+            input_token_permit: Some(permit()),
+            ..Default::default()
+        };
+        let router = address!("68b3465833fb72A70ecDF485E0e4C7bD8665Fc45");
+        assert!(!prune_swap_permit(
+            &mut options,
+            &mut trades,
+            OWNER,
+            router,
+            &PROVIDER.clone(),
+            BLOCK_ID.unwrap(),
+        )
+        .await
+        .unwrap());
+        assert!(options.input_token_permit.is_some());
+    }
+}
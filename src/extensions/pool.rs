@@ -4,15 +4,23 @@
 //! in a single `eth_call`.
 
 use crate::prelude::*;
+use alloc::{collections::BTreeMap, vec::Vec};
 use alloy::{
+    contract::Error as ContractError,
     eips::{BlockId, BlockNumberOrTag},
     providers::Provider,
+    rpc::types::TransactionRequest,
     transports::Transport,
 };
-use alloy_primitives::{Address, ChainId, B256};
+use alloy_primitives::{
+    aliases::{I24, U24},
+    Address, ChainId, B256, U160,
+};
+use alloy_sol_types::SolCall;
 use uniswap_lens::{
     bindings::{
-        ierc20metadata::IERC20Metadata, iuniswapv3pool::IUniswapV3Pool::IUniswapV3PoolInstance,
+        ierc20metadata::IERC20Metadata,
+        iuniswapv3pool::{IUniswapV3Pool, IUniswapV3Pool::IUniswapV3PoolInstance},
     },
     pool_lens,
 };
@@ -36,9 +44,116 @@ where
     )
 }
 
+/// Queries the tick spacing enabled by the factory for a fee, i.e. `feeAmountTickSpacing(fee)`
+///
+/// ## Arguments
+///
+/// * `factory`: The factory address
+/// * `fee`: The fee tier
+/// * `provider`: The alloy provider
+/// * `block_id`: Optional block number to query.
+#[inline]
+pub async fn get_fee_amount_tick_spacing<T, P>(
+    factory: Address,
+    fee: FeeAmount,
+    provider: P,
+    block_id: Option<BlockId>,
+) -> Result<I24, Error>
+where
+    T: Transport + Clone,
+    P: Provider<T>,
+{
+    let fee: U24 = fee.into();
+    let tx = TransactionRequest::default().to(factory).input(
+        IUniswapV3Factory::feeAmountTickSpacingCall { fee }
+            .abi_encode()
+            .into(),
+    );
+    let res = provider
+        .call(&tx)
+        .block(block_id.unwrap_or(BlockId::Number(BlockNumberOrTag::Latest)))
+        .await?;
+    let tick_spacing = IUniswapV3Factory::feeAmountTickSpacingCall::abi_decode_returns(&res, true)
+        .map_err(ContractError::from)?
+        ._0;
+    if tick_spacing <= I24::ZERO {
+        return Err(Error::FeeNotEnabled(fee));
+    }
+    Ok(tick_spacing)
+}
+
+/// The tick spacings enabled by factories for custom fee tiers, keyed by chain, factory and fee,
+/// so that each is only queried once.
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+pub struct TickSpacingCache(BTreeMap<(ChainId, Address, U24), I24>);
+
+impl TickSpacingCache {
+    #[inline]
+    #[must_use]
+    pub const fn new() -> Self {
+        Self(BTreeMap::new())
+    }
+
+    /// Records the tick spacing enabled by a factory for a fee
+    ///
+    /// ## Arguments
+    ///
+    /// * `chain_id`: The chain id
+    /// * `factory`: The factory address
+    /// * `fee`: The fee tier
+    /// * `tick_spacing`: The tick spacing enabled for the fee
+    #[inline]
+    pub fn insert(
+        &mut self,
+        chain_id: ChainId,
+        factory: Address,
+        fee: FeeAmount,
+        tick_spacing: I24,
+    ) {
+        self.0.insert((chain_id, factory, fee.into()), tick_spacing);
+    }
+
+    /// Returns the tick spacing enabled by a factory for a fee. The default spacing is returned
+    /// for the known fee tiers, while custom fee tiers are queried from the factory once.
+    ///
+    /// ## Arguments
+    ///
+    /// * `chain_id`: The chain id
+    /// * `factory`: The factory address
+    /// * `fee`: The fee tier
+    /// * `provider`: The alloy provider
+    /// * `block_id`: Optional block number to query.
+    #[inline]
+    pub async fn get_or_fetch<T, P>(
+        &mut self,
+        chain_id: ChainId,
+        factory: Address,
+        fee: FeeAmount,
+        provider: P,
+        block_id: Option<BlockId>,
+    ) -> Result<I24, Error>
+    where
+        T: Transport + Clone,
+        P: Provider<T>,
+    {
+        if !matches!(fee, FeeAmount::CUSTOM(_)) {
+            return Ok(fee.tick_spacing());
+        }
+        let key = (chain_id, factory, fee.into());
+        if let Some(tick_spacing) = self.0.get(&key) {
+            return Ok(*tick_spacing);
+        }
+        let tick_spacing = get_fee_amount_tick_spacing(factory, fee, provider, block_id).await?;
+        self.0.insert(key, tick_spacing);
+        Ok(tick_spacing)
+    }
+}
+
 impl Pool {
     /// Get a [`Pool`] struct from pool key
     ///
+    /// The tick spacing of custom fee tiers is queried from the factory.
+    ///
     /// ## Arguments
     ///
     /// * `chain_id`: The chain id
@@ -62,8 +177,65 @@ impl Pool {
         T: Transport + Clone,
         P: Provider<T> + Clone,
     {
+        Self::from_pool_key_with_tick_spacing_cache(
+            chain_id,
+            factory,
+            token_a,
+            token_b,
+            fee,
+            provider,
+            block_id,
+            &mut TickSpacingCache::new(),
+        )
+        .await
+        .map_err(|err| {
+            err.with_context(
+                ErrorContext::new().with_pool(
+                    PoolDeployment::for_factory(chain_id, factory)
+                        .pool_address(token_a, token_b, fee, chain_id),
+                ),
+            )
+        })
+    }
+
+    /// Same as [`Pool::from_pool_key`] but looks up the tick spacing of custom fee tiers in a
+    /// cache shared across pools
+    ///
+    /// ## Arguments
+    ///
+    /// * `chain_id`: The chain id
+    /// * `factory`: The factory address
+    /// * `token_a`: One of the tokens in the pool
+    /// * `token_b`: The other token in the pool
+    /// * `fee`: Fee tier of the pool
+    /// * `provider`: The alloy provider
+    /// * `block_id`: Optional block number to query.
+    /// * `tick_spacings`: The cache of the tick spacings of custom fee tiers
+    #[inline]
+    #[allow(clippy::too_many_arguments)]
+    pub async fn from_pool_key_with_tick_spacing_cache<T, P>(
+        chain_id: ChainId,
+        factory: Address,
+        token_a: Address,
+        token_b: Address,
+        fee: FeeAmount,
+        provider: P,
+        block_id: Option<BlockId>,
+        tick_spacings: &mut TickSpacingCache,
+    ) -> Result<Self, Error>
+    where
+        T: Transport + Clone,
+        P: Provider<T> + Clone,
+    {
+        let tick_spacing = tick_spacings
+            .get_or_fetch(chain_id, factory, fee, provider.clone(), block_id)
+            .await?;
         let block_id = block_id.unwrap_or(BlockId::Number(BlockNumberOrTag::Latest));
-        let pool_contract = get_pool_contract(factory, token_a, token_b, fee, provider.clone());
+        let deployment = PoolDeployment::for_factory(chain_id, factory);
+        let pool_contract = IUniswapV3PoolInstance::new(
+            deployment.pool_address(token_a, token_b, fee, chain_id),
+            provider.clone(),
+        );
         let token_a_contract = IERC20Metadata::new(token_a, provider.clone());
         let token_b_contract = IERC20Metadata::new(token_b, provider);
         // TODO: use multicall
@@ -80,7 +252,7 @@ impl Pool {
             !sqrt_price_x96.is_zero(),
             "Pool has been created but not yet initialized"
         );
-        Self::new(
+        Ok(Self::new(
             token!(
                 chain_id,
                 token_a,
@@ -98,7 +270,394 @@ impl Pool {
             fee,
             sqrt_price_x96,
             liquidity,
+        )?
+        .with_tick_spacing(TickIndex::from_i24(tick_spacing))
+        .with_deployment(deployment))
+    }
+}
+
+/// Fetches the state and the tokens of many pools in a single `aggregate3` call to `Multicall3`,
+/// instead of the separate calls made by [`Pool::from_pool_key`] for every pool. Tokens shared by
+/// several pools are only queried once.
+///
+/// The tick spacing of custom fee tiers is queried from the factory.
+///
+/// ## Arguments
+///
+/// * `chain_id`: The chain id
+/// * `factory`: The factory address
+/// * `pairs`: The tokens and the fee tier of each pool
+/// * `provider`: The alloy provider
+/// * `block_id`: Optional block number to query.
+///
+/// ## Returns
+///
+/// The pools in the order of `pairs`
+#[inline]
+pub async fn get_pools<T, P>(
+    chain_id: ChainId,
+    factory: Address,
+    pairs: &[(Address, Address, FeeAmount)],
+    provider: P,
+    block_id: Option<BlockId>,
+) -> Result<Vec<Pool>, Error>
+where
+    T: Transport + Clone,
+    P: Provider<T> + Clone,
+{
+    let block_id = block_id.unwrap_or(BlockId::Number(BlockNumberOrTag::Latest));
+    let mut tokens: Vec<Address> = Vec::new();
+    for &(token_a, token_b, _) in pairs {
+        for token in [token_a, token_b] {
+            if !tokens.contains(&token) {
+                tokens.push(token);
+            }
+        }
+    }
+    let call = |target: Address, call_data: Vec<u8>| IMulticall3::Call3 {
+        target,
+        allowFailure: false,
+        callData: call_data.into(),
+    };
+    let deployment = PoolDeployment::for_factory(chain_id, factory);
+    let mut calls = Vec::with_capacity(pairs.len() * 2 + tokens.len() * 3);
+    for &(token_a, token_b, fee) in pairs {
+        let pool = deployment.pool_address(token_a, token_b, fee, chain_id);
+        calls.push(call(pool, IUniswapV3Pool::slot0Call {}.abi_encode()));
+        calls.push(call(pool, IUniswapV3Pool::liquidityCall {}.abi_encode()));
+    }
+    for &token in &tokens {
+        calls.push(call(token, IERC20Metadata::decimalsCall {}.abi_encode()));
+        calls.push(call(token, IERC20Metadata::nameCall {}.abi_encode()));
+        calls.push(call(token, IERC20Metadata::symbolCall {}.abi_encode()));
+    }
+    let results = aggregate3(&provider, calls, block_id).await?;
+    let (pool_results, token_results) = results.split_at(pairs.len() * 2);
+
+    let mut fetched_tokens = BTreeMap::new();
+    for (&address, results) in tokens.iter().zip(token_results.chunks_exact(3)) {
+        let decimals =
+            IERC20Metadata::decimalsCall::abi_decode_returns(&results[0].returnData, true)
+                .map_err(ContractError::from)?
+                ._0;
+        let name = IERC20Metadata::nameCall::abi_decode_returns(&results[1].returnData, true)
+            .map_err(ContractError::from)?
+            ._0;
+        let symbol = IERC20Metadata::symbolCall::abi_decode_returns(&results[2].returnData, true)
+            .map_err(ContractError::from)?
+            ._0;
+        fetched_tokens.insert(address, token!(chain_id, address, decimals, symbol, name));
+    }
+
+    let mut tick_spacings = TickSpacingCache::new();
+    let mut pools = Vec::with_capacity(pairs.len());
+    for (&(token_a, token_b, fee), results) in pairs.iter().zip(pool_results.chunks_exact(2)) {
+        let sqrt_price_x96 =
+            IUniswapV3Pool::slot0Call::abi_decode_returns(&results[0].returnData, true)
+                .map_err(ContractError::from)?
+                .sqrtPriceX96;
+        let liquidity =
+            IUniswapV3Pool::liquidityCall::abi_decode_returns(&results[1].returnData, true)
+                .map_err(ContractError::from)?
+                ._0;
+        assert!(
+            !sqrt_price_x96.is_zero(),
+            "Pool has been created but not yet initialized"
+        );
+        let tick_spacing = tick_spacings
+            .get_or_fetch(chain_id, factory, fee, provider.clone(), Some(block_id))
+            .await?;
+        pools.push(
+            Pool::new(
+                fetched_tokens[&token_a].clone(),
+                fetched_tokens[&token_b].clone(),
+                fee,
+                sqrt_price_x96,
+                liquidity,
+            )?
+            .with_tick_spacing(TickIndex::from_i24(tick_spacing))
+            .with_deployment(deployment),
+        );
+    }
+    Ok(pools)
+}
+
+/// Whether a pool exists and is initialized, see [`probe_pools`].
+#[derive(Clone, Debug, PartialEq)]
+pub enum PoolStatus {
+    /// The factory has not created a pool for the tokens and the fee.
+    NotDeployed,
+    /// The pool has been created but its price has not been initialized yet.
+    DeployedUninitialized,
+    /// The pool is initialized and can be swapped against.
+    Initialized { pool: Pool },
+}
+
+impl PoolStatus {
+    /// The pool if it is initialized
+    #[inline]
+    #[must_use]
+    pub const fn pool(&self) -> Option<&Pool> {
+        match self {
+            Self::Initialized { pool } => Some(pool),
+            _ => None,
+        }
+    }
+}
+
+/// Returns whether the pool of a pair and a fee tier has been created by the factory and
+/// initialized, fetching the pool if it has, see [`probe_pools`]
+///
+/// ## Arguments
+///
+/// * `chain_id`: The chain id
+/// * `factory`: The factory address
+/// * `token_a`: One of the tokens in the pool
+/// * `token_b`: The other token in the pool
+/// * `fee`: Fee tier of the pool
+/// * `provider`: The alloy provider
+/// * `block_id`: Optional block number to query.
+#[inline]
+pub async fn probe_pool<T, P>(
+    chain_id: ChainId,
+    factory: Address,
+    token_a: Address,
+    token_b: Address,
+    fee: FeeAmount,
+    provider: P,
+    block_id: Option<BlockId>,
+) -> Result<PoolStatus, Error>
+where
+    T: Transport + Clone,
+    P: Provider<T> + Clone,
+{
+    let mut statuses = probe_pools(
+        chain_id,
+        factory,
+        token_a,
+        token_b,
+        &[fee],
+        provider,
+        block_id,
+    )
+    .await?;
+    Ok(statuses.pop().unwrap())
+}
+
+/// Returns whether the pools of a pair have been created by the factory and initialized for
+/// each of the fee tiers, e.g. `[FeeAmount::LOWEST, FeeAmount::LOW, FeeAmount::MEDIUM,
+/// FeeAmount::HIGH]`, in two `aggregate3` calls to `Multicall3`. The initialized pools are
+/// fetched along the way.
+///
+/// The pools are looked up with `getPool` on the factory along with the metadata of the tokens,
+/// then their state is read from the returned addresses, so that forks with another pool init
+/// code hash are probed correctly. Tokens whose metadata cannot be read, e.g. with a `bytes32`
+/// symbol, get 18 decimals and no name or symbol.
+///
+/// ## Arguments
+///
+/// * `chain_id`: The chain id
+/// * `factory`: The factory address
+/// * `token_a`: One of the tokens in the pools
+/// * `token_b`: The other token in the pools
+/// * `fees`: The fee tiers to probe
+/// * `provider`: The alloy provider
+/// * `block_id`: Optional block number to query.
+///
+/// ## Returns
+///
+/// The status of the pools in the order of `fees`
+#[inline]
+pub async fn probe_pools<T, P>(
+    chain_id: ChainId,
+    factory: Address,
+    token_a: Address,
+    token_b: Address,
+    fees: &[FeeAmount],
+    provider: P,
+    block_id: Option<BlockId>,
+) -> Result<Vec<PoolStatus>, Error>
+where
+    T: Transport + Clone,
+    P: Provider<T> + Clone,
+{
+    let block_id = block_id.unwrap_or(BlockId::Number(BlockNumberOrTag::Latest));
+    let call = |target: Address, allow_failure: bool, call_data: Vec<u8>| IMulticall3::Call3 {
+        target,
+        allowFailure: allow_failure,
+        callData: call_data.into(),
+    };
+    let mut calls = Vec::with_capacity(fees.len() + 6);
+    for &fee in fees {
+        calls.push(call(
+            factory,
+            false,
+            IUniswapV3Factory::getPoolCall {
+                tokenA: token_a,
+                tokenB: token_b,
+                fee: fee.into(),
+            }
+            .abi_encode(),
+        ));
+    }
+    for token in [token_a, token_b] {
+        calls.push(call(
+            token,
+            true,
+            IERC20Metadata::decimalsCall {}.abi_encode(),
+        ));
+        calls.push(call(token, true, IERC20Metadata::nameCall {}.abi_encode()));
+        calls.push(call(
+            token,
+            true,
+            IERC20Metadata::symbolCall {}.abi_encode(),
+        ));
+    }
+    let results = aggregate3(&provider, calls, block_id).await?;
+    let (pool_results, token_results) = results.split_at(fees.len());
+
+    let mut tokens = Vec::with_capacity(2);
+    for (address, results) in [token_a, token_b]
+        .into_iter()
+        .zip(token_results.chunks_exact(3))
+    {
+        let decimals = results[0]
+            .success
+            .then(|| IERC20Metadata::decimalsCall::abi_decode_returns(&results[0].returnData, true))
+            .and_then(Result::ok)
+            .map_or(18, |res| res._0);
+        let name = results[1]
+            .success
+            .then(|| IERC20Metadata::nameCall::abi_decode_returns(&results[1].returnData, true))
+            .and_then(Result::ok)
+            .map(|res| res._0);
+        let symbol = results[2]
+            .success
+            .then(|| IERC20Metadata::symbolCall::abi_decode_returns(&results[2].returnData, true))
+            .and_then(Result::ok)
+            .map(|res| res._0);
+        tokens.push(Token::new(chain_id, address, decimals, symbol, name, 0, 0));
+    }
+
+    let mut pools = Vec::with_capacity(fees.len());
+    for result in pool_results {
+        pools.push(
+            IUniswapV3Factory::getPoolCall::abi_decode_returns(&result.returnData, true)
+                .map_err(ContractError::from)?
+                .pool,
+        );
+    }
+    let mut calls = Vec::with_capacity(fees.len() * 2);
+    for &pool in pools.iter().filter(|pool| !pool.is_zero()) {
+        calls.push(call(pool, true, IUniswapV3Pool::slot0Call {}.abi_encode()));
+        calls.push(call(
+            pool,
+            true,
+            IUniswapV3Pool::liquidityCall {}.abi_encode(),
+        ));
+    }
+    let results = if calls.is_empty() {
+        Vec::new()
+    } else {
+        aggregate3(&provider, calls, block_id).await?
+    };
+    let mut results = results.chunks_exact(2);
+
+    let deployment = PoolDeployment::for_factory(chain_id, factory);
+    let mut tick_spacings = TickSpacingCache::new();
+    let mut statuses = Vec::with_capacity(fees.len());
+    for (&fee, pool) in fees.iter().zip(pools) {
+        if pool.is_zero() {
+            statuses.push(PoolStatus::NotDeployed);
+            continue;
+        }
+        let results = results
+            .next()
+            .ok_or(ContractError::from(alloy_sol_types::Error::Overrun))?;
+        let sqrt_price_x96 = if results[0].success {
+            IUniswapV3Pool::slot0Call::abi_decode_returns(&results[0].returnData, true)
+                .map_err(ContractError::from)?
+                .sqrtPriceX96
+        } else {
+            U160::ZERO
+        };
+        if sqrt_price_x96.is_zero() {
+            statuses.push(PoolStatus::DeployedUninitialized);
+            continue;
+        }
+        let liquidity =
+            IUniswapV3Pool::liquidityCall::abi_decode_returns(&results[1].returnData, true)
+                .map_err(ContractError::from)?
+                ._0;
+        let tick_spacing = tick_spacings
+            .get_or_fetch(chain_id, factory, fee, provider.clone(), Some(block_id))
+            .await?;
+        statuses.push(PoolStatus::Initialized {
+            pool: Pool::new(
+                tokens[0].clone(),
+                tokens[1].clone(),
+                fee,
+                sqrt_price_x96,
+                liquidity,
+            )?
+            .with_tick_spacing(TickIndex::from_i24(tick_spacing))
+            .with_deployment(deployment),
+        });
+    }
+    Ok(statuses)
+}
+
+/// Sends the calls in a single `aggregate3` call to `Multicall3` and returns their results
+async fn aggregate3<T, P>(
+    provider: &P,
+    calls: Vec<IMulticall3::Call3>,
+    block_id: BlockId,
+) -> Result<Vec<IMulticall3::Result>, Error>
+where
+    T: Transport + Clone,
+    P: Provider<T>,
+{
+    let tx = TransactionRequest::default()
+        .to(MULTICALL3_ADDRESS)
+        .input(IMulticall3::aggregate3Call { calls }.abi_encode().into());
+    let res = provider.call(&tx).block(block_id).await?;
+    Ok(IMulticall3::aggregate3Call::abi_decode_returns(&res, true)
+        .map_err(ContractError::from)?
+        .returnData)
+}
+
+impl KnownPool {
+    /// Get a [`Pool`] struct for the preset on the given chain
+    ///
+    /// ## Arguments
+    ///
+    /// * `chain_id`: The chain id
+    /// * `provider`: The alloy provider
+    /// * `block_id`: Optional block number to query.
+    #[inline]
+    pub async fn fetch<T, P>(
+        self,
+        chain_id: ChainId,
+        provider: P,
+        block_id: Option<BlockId>,
+    ) -> Result<Pool, Error>
+    where
+        T: Transport + Clone,
+        P: Provider<T> + Clone,
+    {
+        let (token_a, token_b) = self
+            .tokens(chain_id)
+            .ok_or(Error::UnsupportedChain(chain_id))?;
+        Pool::from_pool_key(
+            chain_id,
+            FACTORY_ADDRESS,
+            token_a,
+            token_b,
+            self.fee(),
+            provider,
+            block_id,
         )
+        .await
     }
 }
 
@@ -177,14 +736,15 @@ impl<I: TickIndex> Pool<EphemeralTickMapDataProvider<I>> {
             block_id,
         )
         .await?;
-        Self::new_with_tick_data_provider(
+        Ok(Self::new_with_tick_data_provider(
             pool.token0,
             pool.token1,
             pool.fee,
             pool.sqrt_ratio_x96,
             pool.liquidity,
             tick_data_provider,
-        )
+        )?
+        .with_tick_spacing(I::from_i24(pool.tick_spacing.to_i24())))
     }
 }
 
@@ -333,6 +893,160 @@ mod tests {
         assert_eq!(pool.liquidity, 786352807736110014);
     }
 
+    #[tokio::test]
+    async fn test_get_pools() {
+        let wbtc = address!("2260FAC5E5542a773Aa44fBCfeDf7C193bc2C599");
+        let weth = address!("C02aaA39b223FE8D0A0e5C4F27eAD9083C756Cc2");
+        let pools = get_pools(
+            1,
+            FACTORY_ADDRESS,
+            &[
+                (wbtc, weth, FeeAmount::LOW),
+                (weth, wbtc, FeeAmount::MEDIUM),
+            ],
+            PROVIDER.clone(),
+            *BLOCK_ID,
+        )
+        .await
+        .unwrap();
+        assert_eq!(pools.len(), 2);
+        let pool = pool().await;
+        assert_eq!(pools[0].token0, pool.token0);
+        assert_eq!(pools[0].token1, pool.token1);
+        assert_eq!(pools[0].sqrt_ratio_x96, pool.sqrt_ratio_x96);
+        assert_eq!(pools[0].liquidity, pool.liquidity);
+        assert_eq!(pools[1].fee, FeeAmount::MEDIUM);
+        assert_eq!(pools[1].token0.symbol.as_deref(), Some("WBTC"));
+    }
+
+    #[tokio::test]
+    async fn test_probe_pools() {
+        let wbtc = address!("2260FAC5E5542a773Aa44fBCfeDf7C193bc2C599");
+        let weth = address!("C02aaA39b223FE8D0A0e5C4F27eAD9083C756Cc2");
+        let statuses = probe_pools(
+            1,
+            FACTORY_ADDRESS,
+            weth,
+            wbtc,
+            &[FeeAmount::LOW, FeeAmount::CUSTOM(1234)],
+            PROVIDER.clone(),
+            *BLOCK_ID,
+        )
+        .await
+        .unwrap();
+        let pool = statuses[0].pool().unwrap();
+        assert_eq!(pool.token0.symbol.as_deref(), Some("WBTC"));
+        assert_eq!(pool.tick_current, 257344);
+        assert_eq!(statuses[1], PoolStatus::NotDeployed);
+        let status = probe_pool(
+            1,
+            FACTORY_ADDRESS,
+            wbtc,
+            weth,
+            FeeAmount::LOW,
+            PROVIDER.clone(),
+            *BLOCK_ID,
+        )
+        .await
+        .unwrap();
+        assert_eq!(status, statuses[0]);
+
+        // MKR returns its name and symbol as `bytes32`
+        let mkr = address!("9f8F72aA9304c8B593d555F12eF6589cC3A579A2");
+        let status = probe_pool(
+            1,
+            FACTORY_ADDRESS,
+            mkr,
+            weth,
+            FeeAmount::MEDIUM,
+            PROVIDER.clone(),
+            *BLOCK_ID,
+        )
+        .await
+        .unwrap();
+        let pool = status.pool().unwrap();
+        assert_eq!(
+            pool.address(None, None),
+            compute_pool_address(FACTORY_ADDRESS, mkr, weth, FeeAmount::MEDIUM, None, None)
+        );
+        assert_eq!(pool.token0.address(), mkr);
+        assert_eq!(pool.token0.decimals, 18);
+        assert_eq!(pool.token0.symbol, None);
+        assert_eq!(pool.token1.symbol.as_deref(), Some("WETH"));
+    }
+
+    #[tokio::test]
+    async fn test_known_pool_fetch() {
+        let pool = KnownPool::WbtcWeth500
+            .fetch(1, PROVIDER.clone(), *BLOCK_ID)
+            .await
+            .unwrap();
+        assert_eq!(pool.token0.symbol.unwrap(), "WBTC");
+        assert_eq!(pool.tick_current, 257344);
+        assert!(matches!(
+            KnownPool::WbtcWeth500
+                .fetch(56, PROVIDER.clone(), *BLOCK_ID)
+                .await,
+            Err(Error::UnsupportedChain(56))
+        ));
+    }
+
+    #[tokio::test]
+    async fn test_get_fee_amount_tick_spacing() {
+        let tick_spacing = get_fee_amount_tick_spacing(
+            FACTORY_ADDRESS,
+            FeeAmount::HIGH,
+            PROVIDER.clone(),
+            *BLOCK_ID,
+        )
+        .await
+        .unwrap();
+        assert_eq!(tick_spacing, FeeAmount::HIGH.tick_spacing());
+        assert!(matches!(
+            get_fee_amount_tick_spacing(
+                FACTORY_ADDRESS,
+                FeeAmount::CUSTOM(2500),
+                PROVIDER.clone(),
+                *BLOCK_ID
+            )
+            .await,
+            Err(Error::FeeNotEnabled(fee)) if fee == U24::from(2500)
+        ));
+    }
+
+    #[tokio::test]
+    async fn test_tick_spacing_cache() {
+        let mut cache = TickSpacingCache::new();
+        // known fee tiers are not queried
+        assert_eq!(
+            cache
+                .get_or_fetch(
+                    1,
+                    Address::ZERO,
+                    FeeAmount::LOW,
+                    PROVIDER.clone(),
+                    *BLOCK_ID
+                )
+                .await
+                .unwrap(),
+            FeeAmount::LOW.tick_spacing()
+        );
+        let fee = FeeAmount::CUSTOM(2500);
+        cache.insert(1, FACTORY_ADDRESS, fee, I24::from_limbs([25]));
+        assert_eq!(
+            cache
+                .get_or_fetch(1, FACTORY_ADDRESS, fee, PROVIDER.clone(), *BLOCK_ID)
+                .await
+                .unwrap(),
+            I24::from_limbs([25])
+        );
+        // cached per chain, and the fee is not enabled by the factory queried
+        assert!(cache
+            .get_or_fetch(10, FACTORY_ADDRESS, fee, PROVIDER.clone(), *BLOCK_ID)
+            .await
+            .is_err());
+    }
+
     #[tokio::test]
     async fn test_get_liquidity_array_for_pool() {
         let pool = pool().await;
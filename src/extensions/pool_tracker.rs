@@ -0,0 +1,389 @@
+//! ## Pool Tracker
+//! Keeps the price, liquidity and ticks of a set of pools up to date by applying their `Swap`,
+//! `Mint` and `Burn` events, either streamed from a pubsub subscription or polled with
+//! `eth_getLogs`, so that monitoring and arbitrage do not refetch the pools on every block.
+
+use crate::prelude::{Error, IUniswapV3PoolEvents::Swap, *};
+use alloc::{collections::BTreeMap, vec, vec::Vec};
+use alloy::{
+    eips::BlockId,
+    providers::Provider,
+    rpc::types::{Filter, Log},
+    transports::Transport,
+};
+use alloy_primitives::{aliases::I24, Address, ChainId};
+use alloy_sol_types::SolEvent;
+use uniswap_lens::bindings::iuniswapv3pool::IUniswapV3Pool::IUniswapV3PoolInstance;
+
+/// A pool followed by a [`PoolTracker`].
+#[derive(Clone, Debug, PartialEq)]
+pub struct TrackedPool<I = I24> {
+    pub pool: Pool<EphemeralTickDataProvider<I>>,
+    /// The last block reflected in the state of the pool, possibly partially.
+    pub block_number: u64,
+    /// The index of the last log applied in `block_number`, `None` if the block is fully
+    /// reflected. Logs up to this position are ignored.
+    pub log_index: Option<u64>,
+    /// Whether events may have been missed, e.g. after a reorg or a lagging subscription, in
+    /// which case the pool should be resynced with [`PoolTracker::resync`].
+    pub stale: bool,
+}
+
+/// Applies the `Swap`, `Mint` and `Burn` events of a set of pools to their local state.
+#[derive(Clone, Debug, Default, PartialEq)]
+pub struct PoolTracker<I = I24> {
+    pools: BTreeMap<Address, TrackedPool<I>>,
+}
+
+impl<I: TickIndex> PoolTracker<I> {
+    /// Creates a tracker following no pools
+    #[inline]
+    #[must_use]
+    pub const fn new() -> Self {
+        Self {
+            pools: BTreeMap::new(),
+        }
+    }
+
+    /// Starts following a pool whose state reflects the given block
+    ///
+    /// ## Arguments
+    ///
+    /// * `pool`: The pool, with the ticks fetched at `block_number`
+    /// * `block_number`: The block the state of the pool was fetched at
+    #[inline]
+    pub fn insert(&mut self, pool: Pool<EphemeralTickDataProvider<I>>, block_number: u64) {
+        self.pools.insert(
+            pool.tick_data_provider.pool,
+            TrackedPool {
+                pool,
+                block_number,
+                log_index: None,
+                stale: false,
+            },
+        );
+    }
+
+    /// Fetches a pool and its ticks at the given block, the latest if `None`, and starts
+    /// following it
+    ///
+    /// ## Arguments
+    ///
+    /// * `chain_id`: The chain id
+    /// * `factory`: The factory address
+    /// * `token_a`: One of the tokens in the pool
+    /// * `token_b`: The other token in the pool
+    /// * `fee`: Fee tier of the pool
+    /// * `provider`: The alloy provider
+    /// * `block_number`: Optional block number to fetch the pool at
+    #[inline]
+    #[allow(clippy::too_many_arguments)]
+    pub async fn track<T, P>(
+        &mut self,
+        chain_id: ChainId,
+        factory: Address,
+        token_a: Address,
+        token_b: Address,
+        fee: FeeAmount,
+        provider: &P,
+        block_number: Option<u64>,
+    ) -> Result<Address, Error>
+    where
+        T: Transport + Clone,
+        P: Provider<T>,
+    {
+        let block_number = match block_number {
+            Some(block_number) => block_number,
+            None => provider.get_block_number().await?,
+        };
+        let block_id = Some(BlockId::from(block_number));
+        let pool =
+            Pool::from_pool_key(chain_id, factory, token_a, token_b, fee, provider, block_id)
+                .await?;
+        let address = pool.address(None, None);
+        let tick_data_provider =
+            EphemeralTickDataProvider::new(address, provider, None, None, block_id).await?;
+        let pool = Pool::new_with_tick_data_provider(
+            pool.token0,
+            pool.token1,
+            pool.fee,
+            pool.sqrt_ratio_x96,
+            pool.liquidity,
+            tick_data_provider,
+        )?
+        .with_tick_spacing(I::from_i24(pool.tick_spacing.to_i24()));
+        self.insert(pool, block_number);
+        Ok(address)
+    }
+
+    /// Stops following a pool
+    ///
+    /// ## Arguments
+    ///
+    /// * `address`: The pool address
+    #[inline]
+    pub fn remove(&mut self, address: Address) -> Option<TrackedPool<I>> {
+        self.pools.remove(&address)
+    }
+
+    /// Returns the state of a followed pool
+    ///
+    /// ## Arguments
+    ///
+    /// * `address`: The pool address
+    #[inline]
+    #[must_use]
+    pub fn get(&self, address: Address) -> Option<&TrackedPool<I>> {
+        self.pools.get(&address)
+    }
+
+    /// Returns the followed pools by address
+    #[inline]
+    pub fn pools(&self) -> impl Iterator<Item = (&Address, &TrackedPool<I>)> {
+        self.pools.iter()
+    }
+
+    /// The filter matching the `Swap`, `Mint` and `Burn` events of the followed pools, e.g. to
+    /// subscribe to or to poll
+    #[inline]
+    #[must_use]
+    pub fn filter(&self) -> Filter {
+        Filter::new()
+            .address(self.pools.keys().copied().collect::<Vec<_>>())
+            .event_signature(vec![
+                Swap::SIGNATURE_HASH,
+                IUniswapV3PoolEvents::Mint::SIGNATURE_HASH,
+                IUniswapV3PoolEvents::Burn::SIGNATURE_HASH,
+            ])
+    }
+
+    /// Applies a log to the pool that emitted it, returning its address if the state changed
+    ///
+    /// Logs of other pools, of blocks already reflected in the state and pending logs are
+    /// ignored. A removed log marks the pool as stale, since the events cannot be reverted, as
+    /// does a `Mint` or `Burn` whose ticks do not fit the tick index type.
+    ///
+    /// ## Arguments
+    ///
+    /// * `log`: The log emitted by a pool
+    #[inline]
+    pub fn apply_log(&mut self, log: &Log) -> Option<Address> {
+        let address = log.address();
+        let tracked = self.pools.get_mut(&address)?;
+        if log.removed {
+            tracked.stale = true;
+            return None;
+        }
+        let position = (log.block_number?, log.log_index.unwrap_or_default());
+        let applied = match tracked.log_index {
+            Some(log_index) => (tracked.block_number, log_index) >= position,
+            None => tracked.block_number >= position.0,
+        };
+        if applied {
+            return None;
+        }
+        let pool = &mut tracked.pool;
+        if log.topic0() == Some(&Swap::SIGNATURE_HASH) {
+            let swap = Swap::decode_log_data(log.data(), true).ok()?;
+            pool.sqrt_ratio_x96 = swap.sqrtPriceX96;
+            pool.liquidity = swap.liquidity;
+            pool.tick_current = I::from_i24(swap.tick);
+        } else {
+            let event = LiquidityEvent::from_log(log)?;
+            if pool
+                .tick_data_provider
+                .apply_liquidity_event(&event)
+                .is_err()
+            {
+                tracked.stale = true;
+                return None;
+            }
+            let tick_current = pool.tick_current.to_i24().as_i32();
+            if event.tick_lower <= tick_current && tick_current < event.tick_upper {
+                pool.liquidity = match event.kind {
+                    LiquidityEventKind::Mint => pool.liquidity + event.amount,
+                    LiquidityEventKind::Burn => pool.liquidity.saturating_sub(event.amount),
+                };
+            }
+        }
+        (tracked.block_number, tracked.log_index) = (position.0, Some(position.1));
+        Some(address)
+    }
+
+    /// Refetches the state and ticks of the pools marked as stale, or of all pools if `all` is
+    /// set, at the given block, the latest if `None`
+    ///
+    /// ## Arguments
+    ///
+    /// * `provider`: The alloy provider
+    /// * `block_number`: Optional block number to resync at
+    /// * `all`: Whether to resync the pools not marked as stale
+    #[inline]
+    pub async fn resync<T, P>(
+        &mut self,
+        provider: &P,
+        block_number: Option<u64>,
+        all: bool,
+    ) -> Result<(), Error>
+    where
+        T: Transport + Clone,
+        P: Provider<T>,
+    {
+        let block_number = match block_number {
+            Some(block_number) => block_number,
+            None => provider.get_block_number().await?,
+        };
+        let block_id = BlockId::from(block_number);
+        for (address, tracked) in &mut self.pools {
+            if !all && !tracked.stale {
+                continue;
+            }
+            let contract = IUniswapV3PoolInstance::<T, _>::new(*address, provider);
+            let slot0 = contract.slot0().block(block_id).call().await?;
+            let liquidity = contract.liquidity().block(block_id).call().await?._0;
+            let pool = &mut tracked.pool;
+            pool.sqrt_ratio_x96 = slot0.sqrtPriceX96;
+            pool.tick_current = I::from_i24(slot0.tick);
+            pool.liquidity = liquidity;
+            // the events applied since the ticks were fetched are already reflected, so fetch the
+            // ticks again instead of refreshing them from events
+            pool.tick_data_provider.block_id = None;
+            pool.tick_data_provider
+                .refresh(provider, Some(block_id))
+                .await?;
+            tracked.block_number = block_number;
+            tracked.log_index = None;
+            tracked.stale = false;
+        }
+        Ok(())
+    }
+
+    /// Marks all pools as stale, e.g. after the subscription feeding the tracker lagged behind
+    #[inline]
+    pub fn mark_all_stale(&mut self) {
+        for tracked in self.pools.values_mut() {
+            tracked.stale = true;
+        }
+    }
+
+    /// Subscribes to the events of the followed pools and applies them until the subscription
+    /// ends, calling `on_update` with each pool whose state changed. Requires the `pubsub`
+    /// feature and a pubsub transport, e.g. a WebSocket.
+    ///
+    /// When the subscription ends, either closed or lagging behind, the pools are marked as stale
+    /// since events may be missed until a new subscription, see [`Self::resync`].
+    ///
+    /// ## Arguments
+    ///
+    /// * `provider`: The alloy provider with a pubsub transport
+    /// * `on_update`: Called with the address and state of each updated pool
+    #[cfg(feature = "pubsub")]
+    #[inline]
+    pub async fn run<T, P, F>(&mut self, provider: &P, mut on_update: F) -> Result<(), Error>
+    where
+        T: Transport + Clone,
+        P: Provider<T>,
+        F: FnMut(Address, &TrackedPool<I>),
+    {
+        let mut subscription = provider.subscribe_logs(&self.filter()).await?;
+        while let Ok(log) = subscription.recv().await {
+            if let Some(address) = self.apply_log(&log) {
+                on_update(address, &self.pools[&address]);
+            }
+        }
+        self.mark_all_stale();
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::tests::*;
+    use alloy_primitives::{address, U160, U256};
+
+    const POOL: Address = address!("0000000000000000000000000000000000000042");
+
+    fn log(data: alloy_primitives::LogData, block_number: u64, log_index: u64) -> Log {
+        Log {
+            inner: alloy_primitives::Log {
+                address: POOL,
+                data,
+            },
+            block_number: Some(block_number),
+            log_index: Some(log_index),
+            ..Default::default()
+        }
+    }
+
+    fn mint(tick_lower: i32, tick_upper: i32, amount: u128) -> alloy_primitives::LogData {
+        IUniswapV3PoolEvents::Mint {
+            sender: Address::ZERO,
+            owner: Address::ZERO,
+            tickLower: I24::try_from(tick_lower).unwrap(),
+            tickUpper: I24::try_from(tick_upper).unwrap(),
+            amount,
+            amount0: U256::ZERO,
+            amount1: U256::ZERO,
+        }
+        .encode_log_data()
+    }
+
+    #[test]
+    fn test_apply_log() {
+        let tick_data_provider = EphemeralTickDataProvider {
+            pool: POOL,
+            tick_lower: MIN_TICK_I32,
+            tick_upper: MAX_TICK_I32,
+            tick_spacing: 60,
+            block_id: None,
+            ticks: vec![Tick::new(-60, 1000, 1000), Tick::new(60, 1000, -1000)],
+        };
+        let pool = Pool::new_with_tick_data_provider(
+            TOKEN0.clone(),
+            TOKEN1.clone(),
+            FeeAmount::MEDIUM,
+            encode_sqrt_ratio_x96(1, 1),
+            1000,
+            tick_data_provider,
+        )
+        .unwrap();
+        let mut tracker = PoolTracker::new();
+        tracker.insert(pool, 100);
+
+        // the mint is in range, so it adds to the liquidity
+        let mint = log(mint(-120, 120, 500), 101, 0);
+        assert_eq!(tracker.apply_log(&mint), Some(POOL));
+        // already applied, or before the state of the pool
+        assert_eq!(tracker.apply_log(&mint), None);
+        assert_eq!(tracker.apply_log(&log(mint.data().clone(), 100, 5)), None);
+        let tracked = tracker.get(POOL).unwrap();
+        assert_eq!(tracked.pool.liquidity, 1500);
+        assert_eq!(tracked.pool.tick_data_provider.ticks.len(), 4);
+
+        let sqrt_price_x96: U160 = encode_sqrt_ratio_x96(2, 1);
+        let swap = Swap {
+            sender: Address::ZERO,
+            recipient: Address::ZERO,
+            amount0: Default::default(),
+            amount1: Default::default(),
+            sqrtPriceX96: sqrt_price_x96,
+            liquidity: 500,
+            tick: I24::try_from(6931).unwrap(),
+        };
+        assert_eq!(
+            tracker.apply_log(&log(swap.encode_log_data(), 101, 1)),
+            Some(POOL)
+        );
+        let tracked = tracker.get(POOL).unwrap();
+        assert_eq!(tracked.pool.sqrt_ratio_x96, sqrt_price_x96);
+        assert_eq!(tracked.pool.liquidity, 500);
+        assert_eq!(tracked.pool.tick_current, 6931);
+        assert_eq!((tracked.block_number, tracked.log_index), (101, Some(1)));
+
+        let mut removed = log(mint(-120, 120, 500), 102, 0);
+        removed.removed = true;
+        assert_eq!(tracker.apply_log(&removed), None);
+        assert!(tracker.get(POOL).unwrap().stale);
+    }
+}
@@ -9,7 +9,10 @@ use alloy::{
     providers::Provider,
     transports::Transport,
 };
-use alloy_primitives::{Address, ChainId, U256};
+use alloy_primitives::{
+    aliases::{I24, U96},
+    Address, ChainId, U256,
+};
 use anyhow::Result;
 use base64::{engine::general_purpose, Engine};
 use num_bigint::ToBigInt;
@@ -20,6 +23,7 @@ use uniswap_lens::{
         iuniswapv3nonfungiblepositionmanager::IUniswapV3NonfungiblePositionManager::{
             positionsReturn, IUniswapV3NonfungiblePositionManagerInstance,
         },
+        iuniswapv3pool::IUniswapV3Pool::IUniswapV3PoolInstance,
     },
     position_lens,
 };
@@ -37,7 +41,59 @@ where
     IUniswapV3NonfungiblePositionManagerInstance::new(nonfungible_position_manager, provider)
 }
 
-/// Get a [`Position`] struct from the token id
+/// The state of a position in the nonfungible position manager, including the fee growth snapshot
+/// taken at its last update and the tokens owed to it, see [`get_position_details`].
+#[derive(Clone, Debug, PartialEq)]
+pub struct PositionDetails {
+    pub token_id: U256,
+    /// The nonce for permits.
+    pub nonce: U96,
+    /// The address approved to spend the position.
+    pub operator: Address,
+    /// The fee growth inside the range of the position as of its last update.
+    pub fee_growth_inside0_last_x128: U256,
+    pub fee_growth_inside1_last_x128: U256,
+    /// The fees credited at the last update of the position and the principal removed but not yet
+    /// collected.
+    pub tokens_owed0: u128,
+    pub tokens_owed1: u128,
+    /// The position with its pool at the block.
+    pub position: Position,
+}
+
+impl PositionDetails {
+    /// Returns the fees of the position given the current fee growth inside its range, e.g. from
+    /// [`FeeGrowthInputs::fee_growth_inside`]
+    ///
+    /// ## Arguments
+    ///
+    /// * `fee_growth_inside_0x128`: The current fee growth of token0 inside the range
+    /// * `fee_growth_inside_1x128`: The current fee growth of token1 inside the range
+    #[inline]
+    #[must_use]
+    pub fn fees(
+        &self,
+        fee_growth_inside_0x128: U256,
+        fee_growth_inside_1x128: U256,
+    ) -> PositionFees {
+        let (accrued0, accrued1) = get_tokens_owed(
+            self.fee_growth_inside0_last_x128,
+            self.fee_growth_inside1_last_x128,
+            self.position.liquidity,
+            fee_growth_inside_0x128,
+            fee_growth_inside_1x128,
+        );
+        PositionFees {
+            tokens_owed0: U256::from(self.tokens_owed0),
+            tokens_owed1: U256::from(self.tokens_owed1),
+            accrued0,
+            accrued1,
+        }
+    }
+}
+
+/// Get the state of a position from the nonfungible position manager along with a [`Position`]
+/// struct on its pool
 ///
 /// ## Arguments
 ///
@@ -47,13 +103,13 @@ where
 /// * `provider`: The alloy provider
 /// * `block_id`: Optional block number to query
 #[inline]
-pub async fn get_position<T, P>(
+pub async fn get_position_details<T, P>(
     chain_id: ChainId,
     nonfungible_position_manager: Address,
     token_id: U256,
     provider: P,
     block_id: Option<BlockId>,
-) -> Result<Position, Error>
+) -> Result<PositionDetails, Error>
 where
     T: Transport + Clone,
     P: Provider<T> + Clone,
@@ -69,13 +125,18 @@ where
         .call()
         .await?;
     let positionsReturn {
+        nonce,
+        operator,
         token0,
         token1,
         fee,
         tickLower: tick_lower,
         tickUpper: tick_upper,
         liquidity,
-        ..
+        feeGrowthInside0LastX128: fee_growth_inside0_last_x128,
+        feeGrowthInside1LastX128: fee_growth_inside1_last_x128,
+        tokensOwed0: tokens_owed0,
+        tokensOwed1: tokens_owed1,
     } = position;
     let pool = Pool::from_pool_key(
         chain_id,
@@ -87,12 +148,48 @@ where
         block_id,
     )
     .await?;
-    Ok(Position::new(
-        pool,
-        liquidity,
-        tick_lower.as_i32(),
-        tick_upper.as_i32(),
-    ))
+    Ok(PositionDetails {
+        token_id,
+        nonce,
+        operator,
+        fee_growth_inside0_last_x128,
+        fee_growth_inside1_last_x128,
+        tokens_owed0,
+        tokens_owed1,
+        position: Position::new(pool, liquidity, tick_lower.as_i32(), tick_upper.as_i32()),
+    })
+}
+
+/// Get a [`Position`] struct from the token id
+///
+/// ## Arguments
+///
+/// * `chain_id`: The chain id
+/// * `nonfungible_position_manager`: The nonfungible position manager address
+/// * `token_id`: The token id
+/// * `provider`: The alloy provider
+/// * `block_id`: Optional block number to query
+#[inline]
+pub async fn get_position<T, P>(
+    chain_id: ChainId,
+    nonfungible_position_manager: Address,
+    token_id: U256,
+    provider: P,
+    block_id: Option<BlockId>,
+) -> Result<Position, Error>
+where
+    T: Transport + Clone,
+    P: Provider<T> + Clone,
+{
+    get_position_details(
+        chain_id,
+        nonfungible_position_manager,
+        token_id,
+        provider,
+        block_id,
+    )
+    .await
+    .map(|details| details.position)
 }
 
 impl Position {
@@ -192,6 +289,7 @@ impl<I: TickIndex> Position<EphemeralTickMapDataProvider<I>> {
             block_id,
         )
         .await?;
+        let tick_spacing = pool.tick_spacing.to_i24();
         let pool = Pool::new_with_tick_data_provider(
             pool.token0,
             pool.token1,
@@ -199,7 +297,8 @@ impl<I: TickIndex> Position<EphemeralTickMapDataProvider<I>> {
             pool.sqrt_ratio_x96,
             pool.liquidity,
             tick_data_provider,
-        )?;
+        )?
+        .with_tick_spacing(TickIndex::from_i24(tick_spacing));
         Ok(Self::new(
             pool,
             position.liquidity,
@@ -244,27 +343,51 @@ where
     .map_err(Error::LensError)
 }
 
-/// Get the real-time collectable token amounts.
+/// The fees of a position, split between the amounts already credited on chain and the fees
+/// accrued since.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub struct PositionFees {
+    /// The `tokensOwed0` of the position, i.e. the fees in token0 credited at the last update of
+    /// the position and the principal removed but not yet collected.
+    pub tokens_owed0: U256,
+    /// The `tokensOwed1` of the position, i.e. the fees in token1 credited at the last update of
+    /// the position and the principal removed but not yet collected.
+    pub tokens_owed1: U256,
+    /// The fees in token0 accrued since the last update of the position, see
+    /// [`Position::owed_fees`].
+    pub accrued0: U256,
+    /// The fees in token1 accrued since the last update of the position, see
+    /// [`Position::owed_fees`].
+    pub accrued1: U256,
+}
+
+impl PositionFees {
+    /// The amounts that `collect` would transfer, i.e. the tokens owed plus the accrued fees
+    #[inline]
+    #[must_use]
+    pub fn collectable(&self) -> (U256, U256) {
+        (
+            self.tokens_owed0 + self.accrued0,
+            self.tokens_owed1 + self.accrued1,
+        )
+    }
+}
+
+/// Get the uncollected fees of a position from the fee growth of its pool and ticks.
 ///
 /// ## Arguments
 ///
-/// * `chain_id`: The chain id
 /// * `nonfungible_position_manager`: The nonfungible position manager address
 /// * `token_id`: The token id
 /// * `provider`: The alloy provider
 /// * `block_id`: Optional block number to query
-///
-/// ## Returns
-///
-/// A tuple of the collectable token amounts.
 #[inline]
-pub async fn get_collectable_token_amounts<T, P>(
-    _chain_id: ChainId,
+pub async fn get_position_fees<T, P>(
     nonfungible_position_manager: Address,
     token_id: U256,
     provider: P,
     block_id: Option<BlockId>,
-) -> Result<(U256, U256)>
+) -> Result<PositionFees>
 where
     T: Transport + Clone,
     P: Provider<T> + Clone,
@@ -279,73 +402,131 @@ where
         .block(block_id_)
         .call()
         .await?;
-    let pool_contract = get_pool_contract(
+    let pool = compute_pool_address(
         factory,
         position.token0,
         position.token1,
         position.fee.into(),
+        None,
+        None,
+    );
+    let (fee_growth_inside_0x128, fee_growth_inside_1x128) = get_fee_growth_inputs(
+        pool,
+        position.tickLower,
+        position.tickUpper,
         provider,
+        block_id,
+    )
+    .await?
+    .fee_growth_inside();
+    let (accrued0, accrued1) = get_tokens_owed(
+        position.feeGrowthInside0LastX128,
+        position.feeGrowthInside1LastX128,
+        position.liquidity,
+        fee_growth_inside_0x128,
+        fee_growth_inside_1x128,
     );
-    let tick = pool_contract.slot0().block(block_id_).call().await?.tick;
-    let fee_growth_global_0x128 = pool_contract
+    Ok(PositionFees {
+        tokens_owed0: U256::from(position.tokensOwed0),
+        tokens_owed1: U256::from(position.tokensOwed1),
+        accrued0,
+        accrued1,
+    })
+}
+
+/// Fetches the global fee growth, the current tick and the fee growth outside the bounds of a tick
+/// range of a pool, from which [`FeeGrowthInputs::fee_growth_inside`] computes the fee growth
+/// inside the range at the block, e.g. to distribute liquidity mining rewards pro rata to fees
+///
+/// ## Arguments
+///
+/// * `pool`: The pool address
+/// * `tick_lower`: The lower tick of the range
+/// * `tick_upper`: The upper tick of the range
+/// * `provider`: The alloy provider
+/// * `block_id`: Optional block number to query
+#[inline]
+pub async fn get_fee_growth_inputs<T, P>(
+    pool: Address,
+    tick_lower: I24,
+    tick_upper: I24,
+    provider: P,
+    block_id: Option<BlockId>,
+) -> Result<FeeGrowthInputs<I24, 256, 4>>
+where
+    T: Transport + Clone,
+    P: Provider<T>,
+{
+    let block_id = block_id.unwrap_or(BlockId::Number(BlockNumberOrTag::Latest));
+    let pool_contract = IUniswapV3PoolInstance::new(pool, provider);
+    let tick_current = pool_contract.slot0().block(block_id).call().await?.tick;
+    let fee_growth_global0_x128 = pool_contract
         .feeGrowthGlobal0X128()
-        .block(block_id_)
+        .block(block_id)
         .call()
         .await?
         ._0;
-    let fee_growth_global_1x128 = pool_contract
+    let fee_growth_global1_x128 = pool_contract
         .feeGrowthGlobal1X128()
-        .block(block_id_)
+        .block(block_id)
         .call()
         .await?
         ._0;
     let tick_info_lower = pool_contract
-        .ticks(position.tickLower)
-        .block(block_id_)
+        .ticks(tick_lower)
+        .block(block_id)
         .call()
         .await?;
-    let fee_growth_outside_0x128_lower = tick_info_lower.feeGrowthOutside0X128;
-    let fee_growth_outside_1x128_lower = tick_info_lower.feeGrowthOutside1X128;
     let tick_info_upper = pool_contract
-        .ticks(position.tickUpper)
-        .block(block_id_)
+        .ticks(tick_upper)
+        .block(block_id)
         .call()
         .await?;
-    let fee_growth_outside_0x128_upper = tick_info_upper.feeGrowthOutside0X128;
-    let fee_growth_outside_1x128_upper = tick_info_upper.feeGrowthOutside1X128;
+    Ok(FeeGrowthInputs {
+        tick_lower,
+        tick_upper,
+        tick_current,
+        fee_growth_global0_x128,
+        fee_growth_global1_x128,
+        lower: FeeGrowthOutside {
+            fee_growth_outside0_x128: tick_info_lower.feeGrowthOutside0X128,
+            fee_growth_outside1_x128: tick_info_lower.feeGrowthOutside1X128,
+        },
+        upper: FeeGrowthOutside {
+            fee_growth_outside0_x128: tick_info_upper.feeGrowthOutside0X128,
+            fee_growth_outside1_x128: tick_info_upper.feeGrowthOutside1X128,
+        },
+    })
+}
 
-    // https://github.com/Uniswap/v4-core/blob/f630c8ca8c669509d958353200953762fd15761a/contracts/libraries/Pool.sol#L566
-    let (fee_growth_inside_0x128, fee_growth_inside_1x128) = if tick < position.tickLower {
-        (
-            fee_growth_outside_0x128_lower - fee_growth_outside_0x128_upper,
-            fee_growth_outside_1x128_lower - fee_growth_outside_1x128_upper,
-        )
-    } else if tick >= position.tickUpper {
-        (
-            fee_growth_outside_0x128_upper - fee_growth_outside_0x128_lower,
-            fee_growth_outside_1x128_upper - fee_growth_outside_1x128_lower,
-        )
-    } else {
-        (
-            fee_growth_global_0x128
-                - fee_growth_outside_0x128_lower
-                - fee_growth_outside_0x128_upper,
-            fee_growth_global_1x128
-                - fee_growth_outside_1x128_lower
-                - fee_growth_outside_1x128_upper,
-        )
-    };
-    let (tokens_owed_0, tokens_owed_1) = get_tokens_owed(
-        position.feeGrowthInside0LastX128,
-        position.feeGrowthInside1LastX128,
-        position.liquidity,
-        fee_growth_inside_0x128,
-        fee_growth_inside_1x128,
-    );
-    Ok((
-        U256::from(position.tokensOwed0) + tokens_owed_0,
-        U256::from(position.tokensOwed1) + tokens_owed_1,
-    ))
+/// Get the real-time collectable token amounts.
+///
+/// ## Arguments
+///
+/// * `chain_id`: The chain id
+/// * `nonfungible_position_manager`: The nonfungible position manager address
+/// * `token_id`: The token id
+/// * `provider`: The alloy provider
+/// * `block_id`: Optional block number to query
+///
+/// ## Returns
+///
+/// A tuple of the collectable token amounts.
+#[inline]
+pub async fn get_collectable_token_amounts<T, P>(
+    _chain_id: ChainId,
+    nonfungible_position_manager: Address,
+    token_id: U256,
+    provider: P,
+    block_id: Option<BlockId>,
+) -> Result<(U256, U256)>
+where
+    T: Transport + Clone,
+    P: Provider<T> + Clone,
+{
+    let fees =
+        get_position_fees(nonfungible_position_manager, token_id, provider, block_id).await?;
+    Ok(fees.collectable())
 }
 
 /// Get the token SVG URL of the specified position.
@@ -445,7 +626,8 @@ where
         sqrt_price_x96,
         position.pool.liquidity,
         position.pool.tick_data_provider,
-    )?;
+    )?
+    .with_tick_spacing(position.pool.tick_spacing);
     Ok(Position::new(
         pool_at_new_price,
         position.liquidity,
@@ -559,6 +741,105 @@ mod tests {
         assert_eq!(tokens_owed_1, uint!(516299277575296150_U256));
     }
 
+    #[tokio::test]
+    async fn test_get_position_fees() {
+        let fees = get_position_fees(NPM, uint!(4_U256), PROVIDER.clone(), BLOCK_ID)
+            .await
+            .unwrap();
+        assert_eq!(
+            fees.collectable(),
+            (uint!(3498422_U256), uint!(516299277575296150_U256))
+        );
+        assert!(fees.accrued0 > U256::ZERO);
+    }
+
+    #[tokio::test]
+    async fn test_get_position_details() {
+        let details = get_position_details(1, NPM, uint!(4_U256), PROVIDER.clone(), BLOCK_ID)
+            .await
+            .unwrap();
+        assert_eq!(details.token_id, uint!(4_U256));
+        assert_eq!(details.position.liquidity, 34399999543676);
+        assert_eq!(details.position.tick_lower, 253320);
+        assert_eq!(details.position.tick_upper, 264600);
+        let pool = details.position.pool.address(None, None);
+        let inputs = get_fee_growth_inputs(
+            pool,
+            details.position.tick_lower.to_i24(),
+            details.position.tick_upper.to_i24(),
+            PROVIDER.clone(),
+            BLOCK_ID,
+        )
+        .await
+        .unwrap();
+        let (fee_growth_inside_0x128, fee_growth_inside_1x128) = inputs.fee_growth_inside();
+        assert_eq!(
+            details.fees(fee_growth_inside_0x128, fee_growth_inside_1x128),
+            get_position_fees(NPM, uint!(4_U256), PROVIDER.clone(), BLOCK_ID)
+                .await
+                .unwrap()
+        );
+    }
+
+    #[tokio::test]
+    async fn test_get_fee_growth_inputs() {
+        let position = Position::from_token_id(1, NPM, uint!(4_U256), PROVIDER.clone(), BLOCK_ID)
+            .await
+            .unwrap();
+        let pool = position.pool.address(None, None);
+        let tick_lower = position.tick_lower.to_i24();
+        let tick_upper = position.tick_upper.to_i24();
+        let inputs =
+            get_fee_growth_inputs(pool, tick_lower, tick_upper, PROVIDER.clone(), BLOCK_ID)
+                .await
+                .unwrap();
+
+        // compare with the pool state read at the same block
+        let block_id = BLOCK_ID.unwrap();
+        let pool_contract = IUniswapV3PoolInstance::new(pool, PROVIDER.clone());
+        let tick_current = pool_contract
+            .slot0()
+            .block(block_id)
+            .call()
+            .await
+            .unwrap()
+            .tick;
+        let global0 = pool_contract
+            .feeGrowthGlobal0X128()
+            .block(block_id)
+            .call()
+            .await
+            .unwrap()
+            ._0;
+        let global1 = pool_contract
+            .feeGrowthGlobal1X128()
+            .block(block_id)
+            .call()
+            .await
+            .unwrap()
+            ._0;
+        assert_eq!(inputs.tick_current, tick_current);
+        assert_eq!(inputs.fee_growth_global0_x128, global0);
+        assert_eq!(inputs.fee_growth_global1_x128, global1);
+        for (tick, outside) in [(tick_lower, inputs.lower), (tick_upper, inputs.upper)] {
+            let info = pool_contract
+                .ticks(tick)
+                .block(block_id)
+                .call()
+                .await
+                .unwrap();
+            // the ticks of a position with liquidity are initialized
+            assert!(info.initialized);
+            assert_eq!(
+                outside,
+                FeeGrowthOutside {
+                    fee_growth_outside0_x128: info.feeGrowthOutside0X128,
+                    fee_growth_outside1_x128: info.feeGrowthOutside1X128,
+                }
+            );
+        }
+    }
+
     #[tokio::test]
     async fn test_get_token_svg() {
         let svg = get_token_svg(NPM, uint!(4_U256), PROVIDER.clone(), BLOCK_ID)
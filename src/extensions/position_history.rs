@@ -0,0 +1,257 @@
+//! ## Position History
+//! Decodes the events emitted by the `NonfungiblePositionManager` for a position and folds them
+//! into its state, so that indexers can maintain positions purely from logs.
+
+use crate::prelude::{
+    Error,
+    INonfungiblePositionManagerEvents::{Collect, DecreaseLiquidity, IncreaseLiquidity, Transfer},
+    *,
+};
+use alloy_primitives::{Address, LogData, U256};
+use alloy_sol_types::SolEvent;
+
+/// An event emitted by the `NonfungiblePositionManager` that changes the state of a position.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub enum PositionEvent {
+    IncreaseLiquidity(IncreaseLiquidity),
+    DecreaseLiquidity(DecreaseLiquidity),
+    Collect(Collect),
+    Transfer(Transfer),
+}
+
+impl PositionEvent {
+    /// Decodes a log emitted by the `NonfungiblePositionManager`, returning `None` if it is not a
+    /// position event
+    ///
+    /// ## Arguments
+    ///
+    /// * `data`: The log data
+    #[inline]
+    #[must_use]
+    pub fn decode_log_data(data: &LogData) -> Option<Self> {
+        let topic0 = *data.topics().first()?;
+        if topic0 == IncreaseLiquidity::SIGNATURE_HASH {
+            IncreaseLiquidity::decode_log_data(data, true)
+                .ok()
+                .map(Self::IncreaseLiquidity)
+        } else if topic0 == DecreaseLiquidity::SIGNATURE_HASH {
+            DecreaseLiquidity::decode_log_data(data, true)
+                .ok()
+                .map(Self::DecreaseLiquidity)
+        } else if topic0 == Collect::SIGNATURE_HASH {
+            Collect::decode_log_data(data, true).ok().map(Self::Collect)
+        } else if topic0 == Transfer::SIGNATURE_HASH {
+            Transfer::decode_log_data(data, true)
+                .ok()
+                .map(Self::Transfer)
+        } else {
+            None
+        }
+    }
+
+    /// The token id of the position the event is about
+    #[inline]
+    #[must_use]
+    pub const fn token_id(&self) -> U256 {
+        match self {
+            Self::IncreaseLiquidity(event) => event.tokenId,
+            Self::DecreaseLiquidity(event) => event.tokenId,
+            Self::Collect(event) => event.tokenId,
+            Self::Transfer(event) => event.tokenId,
+        }
+    }
+}
+
+/// The state of a position folded from its [`PositionEvent`]s.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub struct PositionHistory {
+    /// The token id of the position.
+    pub token_id: U256,
+    /// The current owner, [`Address::ZERO`] if not minted yet or burned.
+    pub owner: Address,
+    /// The current liquidity.
+    pub liquidity: u128,
+    /// The principal removed from the position that has not been collected yet. Fees are not
+    /// included since they are not logged until collected.
+    pub tokens_owed0: U256,
+    pub tokens_owed1: U256,
+    /// The total amounts deposited.
+    pub deposited0: U256,
+    pub deposited1: U256,
+    /// The total principal removed.
+    pub withdrawn0: U256,
+    pub withdrawn1: U256,
+    /// The total amounts collected, including fees.
+    pub collected0: U256,
+    pub collected1: U256,
+}
+
+impl PositionHistory {
+    /// Creates an empty history for the given position
+    #[inline]
+    #[must_use]
+    pub fn new(token_id: U256) -> Self {
+        Self {
+            token_id,
+            ..Default::default()
+        }
+    }
+
+    /// Applies an event to the state, returning whether it concerns this position
+    ///
+    /// ## Arguments
+    ///
+    /// * `event`: The event to apply, in the order it was emitted
+    #[inline]
+    pub fn apply_event(&mut self, event: &PositionEvent) -> Result<bool, Error> {
+        if event.token_id() != self.token_id {
+            return Ok(false);
+        }
+        match event {
+            PositionEvent::IncreaseLiquidity(event) => {
+                self.liquidity = self
+                    .liquidity
+                    .checked_add(event.liquidity)
+                    .ok_or(Error::AddDeltaOverflow)?;
+                self.deposited0 += event.amount0;
+                self.deposited1 += event.amount1;
+            }
+            PositionEvent::DecreaseLiquidity(event) => {
+                self.liquidity = self.liquidity.checked_sub(event.liquidity).ok_or(
+                    Error::LiquidityUnderflow {
+                        liquidity: event.liquidity,
+                        available: self.liquidity,
+                    },
+                )?;
+                self.tokens_owed0 += event.amount0;
+                self.tokens_owed1 += event.amount1;
+                self.withdrawn0 += event.amount0;
+                self.withdrawn1 += event.amount1;
+            }
+            PositionEvent::Collect(event) => {
+                self.tokens_owed0 = self.tokens_owed0.saturating_sub(event.amount0);
+                self.tokens_owed1 = self.tokens_owed1.saturating_sub(event.amount1);
+                self.collected0 += event.amount0;
+                self.collected1 += event.amount1;
+            }
+            PositionEvent::Transfer(event) => self.owner = event.to,
+        }
+        Ok(true)
+    }
+
+    /// Decodes a log and applies it to the state, returning whether it concerns this position
+    ///
+    /// ## Arguments
+    ///
+    /// * `data`: The log data emitted by the `NonfungiblePositionManager`
+    #[inline]
+    pub fn apply_log_data(&mut self, data: &LogData) -> Result<bool, Error> {
+        match PositionEvent::decode_log_data(data) {
+            Some(event) => self.apply_event(&event),
+            None => Ok(false),
+        }
+    }
+
+    /// Returns the fees collected so far, i.e. the amounts collected beyond the principal
+    #[inline]
+    #[must_use]
+    pub fn fees_collected(&self) -> (U256, U256) {
+        (
+            self.collected0
+                .saturating_sub(self.withdrawn0 - self.tokens_owed0),
+            self.collected1
+                .saturating_sub(self.withdrawn1 - self.tokens_owed1),
+        )
+    }
+
+    /// Creates a [`Position`] with the current liquidity of the history
+    ///
+    /// ## Arguments
+    ///
+    /// * `pool`: The pool of the position
+    /// * `tick_lower`: The lower tick of the position
+    /// * `tick_upper`: The upper tick of the position
+    #[inline]
+    pub fn to_position<TP: TickDataProvider>(
+        &self,
+        pool: Pool<TP>,
+        tick_lower: TP::Index,
+        tick_upper: TP::Index,
+    ) -> Position<TP> {
+        Position::new(pool, self.liquidity, tick_lower, tick_upper)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use alloy_primitives::address;
+
+    const OWNER: Address = address!("0000000000000000000000000000000000000003");
+
+    #[test]
+    fn test_apply_events() {
+        let token_id = U256::from(1);
+        let mut history = PositionHistory::new(token_id);
+        let events = [
+            Transfer {
+                from: Address::ZERO,
+                to: OWNER,
+                tokenId: token_id,
+            }
+            .encode_log_data(),
+            IncreaseLiquidity {
+                tokenId: token_id,
+                liquidity: 100,
+                amount0: U256::from(10),
+                amount1: U256::from(20),
+            }
+            .encode_log_data(),
+            DecreaseLiquidity {
+                tokenId: token_id,
+                liquidity: 40,
+                amount0: U256::from(4),
+                amount1: U256::from(8),
+            }
+            .encode_log_data(),
+            Collect {
+                tokenId: token_id,
+                recipient: OWNER,
+                amount0: U256::from(5),
+                amount1: U256::from(8),
+            }
+            .encode_log_data(),
+        ];
+        for data in &events {
+            assert!(history.apply_log_data(data).unwrap());
+        }
+        assert_eq!(history.owner, OWNER);
+        assert_eq!(history.liquidity, 60);
+        assert_eq!(history.deposited0, U256::from(10));
+        assert_eq!(history.tokens_owed0, U256::ZERO);
+        assert_eq!(history.collected0, U256::from(5));
+        assert_eq!(history.fees_collected(), (U256::from(1), U256::ZERO));
+
+        let other = IncreaseLiquidity {
+            tokenId: U256::from(2),
+            liquidity: 1,
+            amount0: U256::ZERO,
+            amount1: U256::ZERO,
+        };
+        assert!(!history
+            .apply_event(&PositionEvent::IncreaseLiquidity(other))
+            .unwrap());
+        assert!(matches!(
+            history.apply_event(&PositionEvent::DecreaseLiquidity(DecreaseLiquidity {
+                tokenId: token_id,
+                liquidity: 61,
+                amount0: U256::ZERO,
+                amount1: U256::ZERO,
+            })),
+            Err(Error::LiquidityUnderflow {
+                liquidity: 61,
+                available: 60
+            })
+        ));
+    }
+}
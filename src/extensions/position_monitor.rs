@@ -0,0 +1,339 @@
+//! ## Position Monitor
+//! Watches the state of positions and notifies a [`PositionEventSink`] when a position leaves its
+//! range, accrues fees above a threshold or has its liquidity changed, so that alerting
+//! integrations only need to implement the sink.
+
+use crate::prelude::*;
+use alloc::collections::BTreeMap;
+use alloy_primitives::U256;
+
+/// Receives the alerts raised by a [`PositionMonitor`].
+///
+/// All methods default to doing nothing, so a sink only implements the alerts it handles.
+pub trait PositionEventSink {
+    type Error;
+
+    /// Called when the current tick of the pool leaves the range of the position
+    ///
+    /// ## Arguments
+    ///
+    /// * `token_id`: The token id of the position
+    /// * `tick_current`: The current tick of the pool
+    /// * `tick_lower`: The lower tick of the position
+    /// * `tick_upper`: The upper tick of the position
+    #[inline]
+    fn on_out_of_range(
+        &mut self,
+        token_id: U256,
+        tick_current: i32,
+        tick_lower: i32,
+        tick_upper: i32,
+    ) -> Result<(), Self::Error> {
+        let _ = (token_id, tick_current, tick_lower, tick_upper);
+        Ok(())
+    }
+
+    /// Called when the fees owed to the position reach the threshold in either token
+    ///
+    /// ## Arguments
+    ///
+    /// * `token_id`: The token id of the position
+    /// * `fees0`: The fees owed in token0
+    /// * `fees1`: The fees owed in token1
+    #[inline]
+    fn on_fees_above_threshold(
+        &mut self,
+        token_id: U256,
+        fees0: U256,
+        fees1: U256,
+    ) -> Result<(), Self::Error> {
+        let _ = (token_id, fees0, fees1);
+        Ok(())
+    }
+
+    /// Called when the liquidity of the position differs from the last observation
+    ///
+    /// ## Arguments
+    ///
+    /// * `token_id`: The token id of the position
+    /// * `liquidity_before`: The liquidity at the last observation
+    /// * `liquidity_after`: The current liquidity
+    #[inline]
+    fn on_liquidity_changed(
+        &mut self,
+        token_id: U256,
+        liquidity_before: u128,
+        liquidity_after: u128,
+    ) -> Result<(), Self::Error> {
+        let _ = (token_id, liquidity_before, liquidity_after);
+        Ok(())
+    }
+}
+
+/// The state of a position at its last observation by a [`PositionMonitor`].
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+struct ObservedState {
+    liquidity: u128,
+    in_range: bool,
+    fees_above_threshold: bool,
+}
+
+/// Raises alerts to a [`PositionEventSink`] on the transitions of the observed positions.
+///
+/// Alerts are edge triggered: a position out of range or with fees above the threshold is only
+/// reported when it gets there, not on every observation.
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+pub struct PositionMonitor {
+    /// The fees owed in token0 from which [`PositionEventSink::on_fees_above_threshold`] is
+    /// called.
+    pub fee_threshold0: U256,
+    /// The fees owed in token1 from which [`PositionEventSink::on_fees_above_threshold`] is
+    /// called.
+    pub fee_threshold1: U256,
+    states: BTreeMap<U256, ObservedState>,
+}
+
+impl PositionMonitor {
+    /// Creates a monitor with the given fee thresholds
+    ///
+    /// ## Arguments
+    ///
+    /// * `fee_threshold0`: The fees owed in token0 that trigger an alert
+    /// * `fee_threshold1`: The fees owed in token1 that trigger an alert
+    #[inline]
+    #[must_use]
+    pub const fn new(fee_threshold0: U256, fee_threshold1: U256) -> Self {
+        Self {
+            fee_threshold0,
+            fee_threshold1,
+            states: BTreeMap::new(),
+        }
+    }
+
+    /// Compares the current state of a position with its last observation and notifies the sink
+    /// of the transitions
+    ///
+    /// The first observation of a position raises the out of range and fee alerts that apply,
+    /// but no liquidity change.
+    ///
+    /// ## Arguments
+    ///
+    /// * `token_id`: The token id of the position
+    /// * `position`: The current state of the position
+    /// * `fees_owed`: The fees currently owed to the position in token0 and token1
+    /// * `sink`: The sink to notify
+    #[inline]
+    pub fn observe<TP, S>(
+        &mut self,
+        token_id: U256,
+        position: &Position<TP>,
+        fees_owed: (U256, U256),
+        sink: &mut S,
+    ) -> Result<(), S::Error>
+    where
+        TP: TickDataProvider,
+        S: PositionEventSink + ?Sized,
+    {
+        let tick_current = position.pool.tick_current;
+        let state = ObservedState {
+            liquidity: position.liquidity,
+            in_range: position.tick_lower <= tick_current && tick_current < position.tick_upper,
+            fees_above_threshold: fees_owed.0 >= self.fee_threshold0
+                || fees_owed.1 >= self.fee_threshold1,
+        };
+        let previous = self.states.insert(token_id, state);
+        if let Some(previous) = previous {
+            if previous.liquidity != state.liquidity {
+                sink.on_liquidity_changed(token_id, previous.liquidity, state.liquidity)?;
+            }
+        }
+        if !state.in_range && previous.map_or(true, |previous| previous.in_range) {
+            sink.on_out_of_range(
+                token_id,
+                tick_current.to_i24().as_i32(),
+                position.tick_lower.to_i24().as_i32(),
+                position.tick_upper.to_i24().as_i32(),
+            )?;
+        }
+        if state.fees_above_threshold
+            && !previous.is_some_and(|previous| previous.fees_above_threshold)
+        {
+            sink.on_fees_above_threshold(token_id, fees_owed.0, fees_owed.1)?;
+        }
+        Ok(())
+    }
+
+    /// Stops monitoring a position, e.g. once burned
+    ///
+    /// ## Arguments
+    ///
+    /// * `token_id`: The token id of the position
+    #[inline]
+    pub fn forget(&mut self, token_id: U256) -> bool {
+        self.states.remove(&token_id).is_some()
+    }
+}
+
+/// A [`PositionEventSink`] writing each alert as a line of JSON, e.g. to a file tailed by an
+/// exporter. Requires the `std` feature.
+#[cfg(feature = "std")]
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+pub struct JsonLinesSink<W> {
+    pub writer: W,
+}
+
+#[cfg(feature = "std")]
+impl<W: std::io::Write> JsonLinesSink<W> {
+    /// Creates a sink writing to the given writer
+    ///
+    /// ## Arguments
+    ///
+    /// * `writer`: The writer of the lines, e.g. a file opened in append mode
+    #[inline]
+    #[must_use]
+    pub const fn new(writer: W) -> Self {
+        Self { writer }
+    }
+
+    fn write_line(&mut self, value: &serde_json::Value) -> std::io::Result<()> {
+        serde_json::to_writer(&mut self.writer, value)?;
+        self.writer.write_all(b"\n")?;
+        self.writer.flush()
+    }
+}
+
+#[cfg(feature = "std")]
+impl<W: std::io::Write> PositionEventSink for JsonLinesSink<W> {
+    type Error = std::io::Error;
+
+    #[inline]
+    fn on_out_of_range(
+        &mut self,
+        token_id: U256,
+        tick_current: i32,
+        tick_lower: i32,
+        tick_upper: i32,
+    ) -> std::io::Result<()> {
+        self.write_line(&serde_json::json!({
+            "event": "out_of_range",
+            "token_id": token_id.to_string(),
+            "tick_current": tick_current,
+            "tick_lower": tick_lower,
+            "tick_upper": tick_upper,
+        }))
+    }
+
+    #[inline]
+    fn on_fees_above_threshold(
+        &mut self,
+        token_id: U256,
+        fees0: U256,
+        fees1: U256,
+    ) -> std::io::Result<()> {
+        self.write_line(&serde_json::json!({
+            "event": "fees_above_threshold",
+            "token_id": token_id.to_string(),
+            "fees0": fees0.to_string(),
+            "fees1": fees1.to_string(),
+        }))
+    }
+
+    #[inline]
+    fn on_liquidity_changed(
+        &mut self,
+        token_id: U256,
+        liquidity_before: u128,
+        liquidity_after: u128,
+    ) -> std::io::Result<()> {
+        self.write_line(&serde_json::json!({
+            "event": "liquidity_changed",
+            "token_id": token_id.to_string(),
+            "liquidity_before": liquidity_before.to_string(),
+            "liquidity_after": liquidity_after.to_string(),
+        }))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::tests::*;
+    use alloc::{vec, vec::Vec};
+
+    #[derive(Default)]
+    struct Recorder(Vec<&'static str>);
+
+    impl PositionEventSink for Recorder {
+        type Error = ();
+
+        fn on_out_of_range(&mut self, _: U256, _: i32, _: i32, _: i32) -> Result<(), ()> {
+            self.0.push("out_of_range");
+            Ok(())
+        }
+
+        fn on_fees_above_threshold(&mut self, _: U256, _: U256, _: U256) -> Result<(), ()> {
+            self.0.push("fees_above_threshold");
+            Ok(())
+        }
+
+        fn on_liquidity_changed(&mut self, _: U256, _: u128, _: u128) -> Result<(), ()> {
+            self.0.push("liquidity_changed");
+            Ok(())
+        }
+    }
+
+    #[test]
+    fn test_alerts_on_transitions() {
+        let pool = make_pool(TOKEN0.clone(), TOKEN1.clone());
+        let spacing = pool.tick_spacing();
+        let token_id = U256::from(1);
+        let mut monitor = PositionMonitor::new(U256::from(100), U256::from(100));
+        let mut sink = Recorder::default();
+
+        let in_range = Position::new(pool.clone(), 1000, -spacing, spacing);
+        monitor
+            .observe(token_id, &in_range, (U256::ZERO, U256::ZERO), &mut sink)
+            .unwrap();
+        assert!(sink.0.is_empty());
+
+        let out_of_range = Position::new(pool, 2000, spacing, spacing * 2);
+        monitor
+            .observe(
+                token_id,
+                &out_of_range,
+                (U256::from(100), U256::ZERO),
+                &mut sink,
+            )
+            .unwrap();
+        monitor
+            .observe(
+                token_id,
+                &out_of_range,
+                (U256::from(200), U256::ZERO),
+                &mut sink,
+            )
+            .unwrap();
+        assert_eq!(
+            sink.0,
+            vec!["liquidity_changed", "out_of_range", "fees_above_threshold"]
+        );
+        assert!(monitor.forget(token_id));
+    }
+
+    #[cfg(feature = "std")]
+    #[test]
+    fn test_json_lines_sink() {
+        let mut sink = JsonLinesSink::new(Vec::new());
+        sink.on_liquidity_changed(U256::from(1), 0, 10).unwrap();
+        sink.on_out_of_range(U256::from(1), 5, 10, 20).unwrap();
+        let output = String::from_utf8(sink.writer).unwrap();
+        let lines: Vec<serde_json::Value> = output
+            .lines()
+            .map(|line| serde_json::from_str(line).unwrap())
+            .collect();
+        assert_eq!(lines.len(), 2);
+        assert_eq!(lines[0]["event"], "liquidity_changed");
+        assert_eq!(lines[0]["liquidity_after"], "10");
+        assert_eq!(lines[1]["tick_current"], 5);
+    }
+}
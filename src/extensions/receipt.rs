@@ -0,0 +1,258 @@
+//! ## Receipt
+//! Decodes the `Swap`, `Transfer`, `Withdrawal` and `Collect` events of a transaction receipt and
+//! reconciles the actual amounts against the expectations encoded by
+//! [`swap_call_parameters_with_summary`], for monitoring execution quality.
+
+use crate::{
+    payments::encode_fee_bips,
+    prelude::{
+        IERC20Events::Transfer, INonfungiblePositionManagerEvents::Collect,
+        IUniswapV3PoolEvents::Swap, IWETH9Events::Withdrawal, *,
+    },
+};
+use alloc::vec::Vec;
+use alloy::rpc::types::{Log, TransactionReceipt};
+use alloy_primitives::{Address, U256};
+use alloy_sol_types::SolEvent;
+
+/// The events relevant to swaps and liquidity management decoded from a transaction, each paired
+/// with the address of the contract that emitted it.
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+pub struct ExecutionReport {
+    /// `Swap` events emitted by pools.
+    pub swaps: Vec<(Address, Swap)>,
+    /// ERC20 `Transfer` events emitted by tokens.
+    pub transfers: Vec<(Address, Transfer)>,
+    /// `Withdrawal` events emitted by WETH when unwrapping.
+    pub withdrawals: Vec<(Address, Withdrawal)>,
+    /// `Collect` events emitted by the position manager.
+    pub collects: Vec<(Address, Collect)>,
+}
+
+/// The result of reconciling an [`ExecutionReport`] against a [`SwapPlanSummary`].
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub struct SwapReconciliation {
+    /// The amount of the output received by the recipient.
+    pub amount_out: U256,
+    /// The amount of the output taken as fee.
+    pub fee_amount: U256,
+    /// Whether the total output, including the fee, is at least the encoded minimum.
+    pub min_out_respected: bool,
+    /// Whether a fee was taken if and only if one was encoded.
+    pub fee_matches: bool,
+}
+
+impl ExecutionReport {
+    /// Decodes the known events from the logs of a transaction receipt
+    ///
+    /// ## Arguments
+    ///
+    /// * `receipt`: The transaction receipt
+    #[inline]
+    #[must_use]
+    pub fn from_receipt(receipt: &TransactionReceipt) -> Self {
+        Self::from_logs(receipt.inner.logs())
+    }
+
+    /// Decodes the known events from the given logs, skipping the others
+    ///
+    /// ## Arguments
+    ///
+    /// * `logs`: The logs of a transaction
+    #[inline]
+    #[must_use]
+    pub fn from_logs(logs: &[Log]) -> Self {
+        let mut report = Self::default();
+        for log in logs {
+            let address = log.address();
+            if let Ok(swap) = Swap::decode_log_data(log.data(), true) {
+                report.swaps.push((address, swap));
+            } else if let Ok(transfer) = Transfer::decode_log_data(log.data(), true) {
+                report.transfers.push((address, transfer));
+            } else if let Ok(withdrawal) = Withdrawal::decode_log_data(log.data(), true) {
+                report.withdrawals.push((address, withdrawal));
+            } else if let Ok(collect) = Collect::decode_log_data(log.data(), true) {
+                report.collects.push((address, collect));
+            }
+        }
+        report
+    }
+
+    /// Returns the total amount of `token` transferred to `to`
+    ///
+    /// ## Arguments
+    ///
+    /// * `token`: The token address
+    /// * `to`: The receiver of the transfers
+    #[inline]
+    #[must_use]
+    pub fn transferred_to(&self, token: Address, to: Address) -> U256 {
+        self.transfers
+            .iter()
+            .filter(|(address, transfer)| *address == token && transfer.to == to)
+            .fold(U256::ZERO, |sum, (_, transfer)| sum + transfer.value)
+    }
+
+    /// Reconciles the actual output of the transaction against the expectations of a swap
+    ///
+    /// The output is considered unwrapped to ether if the output token, i.e. WETH, emitted a
+    /// `Withdrawal` event, in which case the amounts are inferred from the withdrawals since ether
+    /// transfers and refunds are not logged. When the fee is taken in ether, or the fee recipient
+    /// is also the recipient of the output, the fee is split off the total like the router does.
+    ///
+    /// ## Arguments
+    ///
+    /// * `summary`: The summary returned by [`swap_call_parameters_with_summary`]
+    #[inline]
+    #[must_use]
+    pub fn reconcile(&self, summary: &SwapPlanSummary) -> SwapReconciliation {
+        let Some(token_out) = summary.swaps.last().map(|swap| swap.token_out) else {
+            return SwapReconciliation::default();
+        };
+        let split_fee = |total: U256| {
+            let fee_amount = summary.fee.as_ref().map_or(U256::ZERO, |fee| {
+                total * encode_fee_bips(fee.fee.clone()) / U256::from(10_000)
+            });
+            (total - fee_amount, fee_amount)
+        };
+        let withdrawn = self
+            .withdrawals
+            .iter()
+            .filter(|(address, _)| *address == token_out)
+            .fold(U256::ZERO, |sum, (_, withdrawal)| sum + withdrawal.wad);
+        let (amount_out, fee_amount) = if withdrawn.is_zero() {
+            let received = self.transferred_to(token_out, summary.recipient);
+            match &summary.fee {
+                Some(fee) if fee.recipient != summary.recipient => {
+                    (received, self.transferred_to(token_out, fee.recipient))
+                }
+                // the fee and the output are transferred to the same account
+                _ => split_fee(received),
+            }
+        } else {
+            let fee_in_weth = summary.fee.as_ref().map_or(U256::ZERO, |fee| {
+                self.transferred_to(token_out, fee.recipient)
+            });
+            if fee_in_weth.is_zero() {
+                split_fee(withdrawn)
+            } else {
+                // the fee was swept in WETH before the remainder was unwrapped
+                (withdrawn, fee_in_weth)
+            }
+        };
+        SwapReconciliation {
+            amount_out,
+            fee_amount,
+            min_out_respected: amount_out + fee_amount >= summary.total_amount_out_minimum,
+            fee_matches: summary.fee.is_some() == !fee_amount.is_zero(),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use alloy_primitives::{address, LogData};
+
+    const TOKEN_OUT: Address = address!("0000000000000000000000000000000000000002");
+    const RECIPIENT: Address = address!("0000000000000000000000000000000000000003");
+    const FEE_RECIPIENT: Address = address!("0000000000000000000000000000000000000009");
+
+    fn log(address: Address, data: LogData) -> Log {
+        Log {
+            inner: alloy_primitives::Log { address, data },
+            ..Default::default()
+        }
+    }
+
+    fn summary(fee: Option<FeeOptions>) -> SwapPlanSummary {
+        SwapPlanSummary {
+            swaps: vec![SwapLimits {
+                token_in: Address::ZERO,
+                token_out: TOKEN_OUT,
+                pool_fees: vec![FeeAmount::MEDIUM],
+                amount_in_maximum: U256::from(100),
+                amount_out_minimum: U256::from(97),
+                fee_amount: U256::ZERO,
+                recipient: Address::ZERO,
+            }],
+            router_custody: true,
+            recipient: RECIPIENT,
+            total_amount_out_minimum: U256::from(97),
+            fee,
+            refund_eth: false,
+            value: U256::ZERO,
+        }
+    }
+
+    #[test]
+    fn test_reconcile_erc20_output_with_fee() {
+        let transfer = |to, value| Transfer {
+            from: Address::ZERO,
+            to,
+            value: U256::from(value),
+        };
+        let report = ExecutionReport::from_logs(&[
+            log(TOKEN_OUT, transfer(RECIPIENT, 98).encode_log_data()),
+            log(TOKEN_OUT, transfer(FEE_RECIPIENT, 1).encode_log_data()),
+        ]);
+        assert_eq!(report.transfers.len(), 2);
+        let fee = FeeOptions {
+            fee: Percent::new(1, 100),
+            recipient: FEE_RECIPIENT,
+        };
+        assert_eq!(
+            report.reconcile(&summary(Some(fee))),
+            SwapReconciliation {
+                amount_out: U256::from(98),
+                fee_amount: U256::from(1),
+                min_out_respected: true,
+                fee_matches: true,
+            }
+        );
+    }
+
+    #[test]
+    fn test_reconcile_fee_recipient_is_recipient() {
+        let transfer = |value| Transfer {
+            from: Address::ZERO,
+            to: RECIPIENT,
+            value: U256::from(value),
+        };
+        let report = ExecutionReport::from_logs(&[
+            log(TOKEN_OUT, transfer(1).encode_log_data()),
+            log(TOKEN_OUT, transfer(99).encode_log_data()),
+        ]);
+        let fee = FeeOptions {
+            fee: Percent::new(1, 100),
+            recipient: RECIPIENT,
+        };
+        assert_eq!(
+            report.reconcile(&summary(Some(fee))),
+            SwapReconciliation {
+                amount_out: U256::from(99),
+                fee_amount: U256::from(1),
+                min_out_respected: true,
+                fee_matches: true,
+            }
+        );
+    }
+
+    #[test]
+    fn test_reconcile_native_output() {
+        let withdrawal = Withdrawal {
+            src: Address::ZERO,
+            wad: U256::from(96),
+        };
+        let report = ExecutionReport::from_logs(&[log(TOKEN_OUT, withdrawal.encode_log_data())]);
+        assert_eq!(
+            report.reconcile(&summary(None)),
+            SwapReconciliation {
+                amount_out: U256::from(96),
+                fee_amount: U256::ZERO,
+                min_out_respected: false,
+                fee_matches: true,
+            }
+        );
+    }
+}
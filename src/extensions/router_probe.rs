@@ -0,0 +1,282 @@
+//! ## Router Probe
+//! Verifies that a router, e.g. a fork of `SwapRouter02` deployed by an integrator, implements the
+//! functions called by some calldata before it is sent, by looking up their selectors in the
+//! function dispatcher of its bytecode. `SwapRouter02` does not implement ERC-165, so there is no
+//! `supportsInterface` to query.
+//!
+//! The same probing detects whether a deployment uses the `SwapRouter02` or the original
+//! `SwapRouter` encoding of the swap functions, see [`detect_router_target`].
+
+use crate::prelude::{Error, *};
+use alloy::{
+    eips::{BlockId, BlockNumberOrTag},
+    providers::Provider,
+    transports::Transport,
+};
+use alloy_primitives::Selector;
+use uniswap_sdk_core::prelude::*;
+
+/// Returns whether the bytecode of a contract pushes the given selector onto the stack, as the
+/// function dispatcher generated by Solidity does for every external function.
+///
+/// Selectors with leading zero bytes may be pushed with fewer than 4 bytes, so the immediate of
+/// every `PUSH1` to `PUSH4` is compared by value. The data of other pushes is skipped.
+///
+/// ## Arguments
+///
+/// * `code`: The runtime bytecode of the contract
+/// * `selector`: The selector of the function
+#[inline]
+#[must_use]
+pub fn code_has_selector(code: &[u8], selector: Selector) -> bool {
+    let selector = u32::from_be_bytes(selector.0);
+    let mut pc = 0;
+    while pc < code.len() {
+        let opcode = code[pc];
+        pc += 1;
+        // PUSH1 to PUSH32
+        if (0x60..=0x7f).contains(&opcode) {
+            let len = (opcode - 0x5f) as usize;
+            if len <= 4 {
+                if let Some(immediate) = code.get(pc..pc + len) {
+                    let value = immediate
+                        .iter()
+                        .fold(0_u32, |value, &byte| (value << 8) | byte as u32);
+                    if value == selector {
+                        return true;
+                    }
+                }
+            }
+            pc += len;
+        }
+    }
+    false
+}
+
+/// Verifies that the router implements every function called by the calldata, including the
+/// calls wrapped in a `multicall`
+///
+/// ## Arguments
+///
+/// * `provider`: The alloy provider
+/// * `router`: The address of the router
+/// * `calldata`: The calldata to be sent to the router
+/// * `block_id`: Optional block number to query
+#[inline]
+pub async fn verify_router_target<T, P>(
+    provider: P,
+    router: Address,
+    calldata: &[u8],
+    block_id: Option<BlockId>,
+) -> Result<(), Error>
+where
+    T: Transport + Clone,
+    P: Provider<T>,
+{
+    let code = provider
+        .get_code_at(router)
+        .block_id(block_id.unwrap_or(BlockId::Number(BlockNumberOrTag::Latest)))
+        .await?;
+    if code.is_empty() {
+        return Err(Error::RouterNotDeployed(router));
+    }
+    match call_selectors(calldata)
+        .into_iter()
+        .find(|selector| !code_has_selector(&code, *selector))
+    {
+        Some(selector) => Err(Error::UnsupportedRouterCall(selector)),
+        None => Ok(()),
+    }
+}
+
+/// Detects the [`RouterTarget`] of a router deployment by probing its bytecode for the selectors
+/// of the `IV3SwapRouter` and `ISwapRouter` swap functions and of both `multicall`s
+///
+/// A router with the [`SwapAbi::SwapRouter`] encoding needs [`SwapOptions::deadline`] to be set
+/// for every swap.
+///
+/// ## Arguments
+///
+/// * `provider`: The alloy provider
+/// * `router`: The address of the router
+/// * `block_id`: Optional block number to query
+#[inline]
+pub async fn detect_router_target<T, P>(
+    provider: P,
+    router: Address,
+    block_id: Option<BlockId>,
+) -> Result<RouterTarget, Error>
+where
+    T: Transport + Clone,
+    P: Provider<T>,
+{
+    let code = provider
+        .get_code_at(router)
+        .block_id(block_id.unwrap_or(BlockId::Number(BlockNumberOrTag::Latest)))
+        .await?;
+    if code.is_empty() {
+        return Err(Error::RouterNotDeployed(router));
+    }
+    let swap_abi = if code_has_selector(&code, IV3SwapRouter::exactInputSingleCall::SELECTOR.into())
+    {
+        SwapAbi::V3SwapRouter
+    } else if code_has_selector(&code, ISwapRouter::exactInputSingleCall::SELECTOR.into()) {
+        SwapAbi::SwapRouter
+    } else {
+        return Err(Error::UnsupportedRouterCall(
+            IV3SwapRouter::exactInputSingleCall::SELECTOR.into(),
+        ));
+    };
+    // only `SwapRouter02` implements the `multicall` with a deadline
+    let variant = if code_has_selector(&code, IMulticallExtended::multicallCall::SELECTOR.into()) {
+        RouterVariant::SwapRouter02
+    } else if code_has_selector(&code, IMulticall::multicallCall::SELECTOR.into()) {
+        RouterVariant::SwapRouter
+    } else {
+        RouterVariant::SwapOnly
+    };
+    Ok(RouterTarget {
+        address: router,
+        variant,
+        swap_abi,
+    })
+}
+
+/// Same as [`swap_call_parameters`] but also verifies that the [`RouterTarget`] of the options,
+/// if any, implements the functions called before returning the calldata
+///
+/// ## Arguments
+///
+/// * `trades`: trades to produce call parameters for
+/// * `options`: options for the call parameters
+/// * `provider`: The alloy provider
+/// * `block_id`: Optional block number to query
+#[inline]
+pub async fn swap_call_parameters_verified<TInput, TOutput, TP, T, P>(
+    trades: &mut [Trade<TInput, TOutput, TP>],
+    options: SwapOptions,
+    provider: P,
+    block_id: Option<BlockId>,
+) -> Result<MethodParameters, Error>
+where
+    TInput: BaseCurrency,
+    TOutput: BaseCurrency,
+    TP: TickDataProvider,
+    T: Transport + Clone,
+    P: Provider<T>,
+{
+    let router = options.router;
+    let params = swap_call_parameters(trades, options)?;
+    if let Some(router) = router {
+        verify_router_target(provider, router.address, &params.calldata, block_id).await?;
+    }
+    Ok(params)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::tests::*;
+    use alloy_primitives::{address, hex};
+    use alloy_sol_types::SolCall;
+
+    const SWAP_ROUTER_02: Address = address!("68b3465833fb72A70ecDF485E0e4C7bD8665Fc45");
+
+    #[test]
+    fn test_code_has_selector() {
+        // PUSH4 0x12345678 PUSH32 0x..aabbccdd PUSH3 0x00ffee
+        let mut code = hex!("6312345678").to_vec();
+        code.push(0x7f);
+        code.extend_from_slice(&[0; 28]);
+        code.extend_from_slice(&hex!("aabbccdd"));
+        code.extend_from_slice(&hex!("6200ffee"));
+        assert!(code_has_selector(&code, Selector::from(hex!("12345678"))));
+        assert!(code_has_selector(&code, Selector::from(hex!("0000ffee"))));
+        assert!(!code_has_selector(&code, Selector::from(hex!("aabbccdd"))));
+    }
+
+    #[tokio::test]
+    async fn test_detect_router_target() {
+        let target = detect_router_target(PROVIDER.clone(), SWAP_ROUTER_02, *BLOCK_ID)
+            .await
+            .unwrap();
+        assert_eq!(
+            target,
+            RouterTarget {
+                address: SWAP_ROUTER_02,
+                variant: RouterVariant::SwapRouter02,
+                swap_abi: SwapAbi::V3SwapRouter,
+            }
+        );
+
+        let swap_router = address!("E592427A0AEce92De3Edee1F18E0157C05861564");
+        let target = detect_router_target(PROVIDER.clone(), swap_router, *BLOCK_ID)
+            .await
+            .unwrap();
+        assert_eq!(target.variant, RouterVariant::SwapRouter);
+        assert_eq!(target.swap_abi, SwapAbi::SwapRouter);
+
+        let err = detect_router_target(PROVIDER.clone(), WETH.address(), *BLOCK_ID)
+            .await
+            .unwrap_err();
+        assert!(matches!(err, Error::UnsupportedRouterCall(_)));
+    }
+
+    #[tokio::test]
+    async fn test_verify_router_target() {
+        let pool = Pool::from_pool_key(
+            1,
+            FACTORY_ADDRESS,
+            WETH.address(),
+            USDC.address(),
+            FeeAmount::LOW,
+            PROVIDER.clone(),
+            *BLOCK_ID,
+        )
+        .await
+        .unwrap();
+        let trade = Trade::from_route(
+            Route::new(vec![pool], USDC.clone(), ETHER.clone()),
+            CurrencyAmount::from_raw_amount(USDC.clone(), 1_000_000).unwrap(),
+            TradeType::ExactInput,
+        )
+        .unwrap();
+        let options = SwapOptions {
+            slippage_tolerance: Percent::new(1, 100),
+            router: Some(RouterTarget {
+                address: SWAP_ROUTER_02,
+                variant: RouterVariant::SwapRouter02,
+                ..Default::default()
+            }),
+            ..Default::default()
+        };
+        let params = swap_call_parameters_verified(
+            &mut [trade.clone()],
+            options.clone(),
+            PROVIDER.clone(),
+            *BLOCK_ID,
+        )
+        .await
+        .unwrap();
+        assert!(!params.calldata.is_empty());
+
+        // WETH implements none of the router functions
+        let err = swap_call_parameters_verified(
+            &mut [trade],
+            SwapOptions {
+                router: Some(RouterTarget {
+                    address: WETH.address(),
+                    variant: RouterVariant::SwapRouter02,
+                    ..Default::default()
+                }),
+                ..options
+            },
+            PROVIDER.clone(),
+            *BLOCK_ID,
+        )
+        .await
+        .unwrap_err();
+        assert!(matches!(err, Error::UnsupportedRouterCall(selector)
+            if selector.0 == IMulticall::multicallCall::SELECTOR));
+    }
+}
@@ -0,0 +1,227 @@
+//! ## RPC Fixtures
+//! A transport that records the JSON-RPC responses of another transport and replays them offline,
+//! so that tests against pinned mainnet blocks are deterministic and do not need an archive node.
+//! Enabled by the `fixtures` feature.
+
+use alloc::{boxed::Box, collections::BTreeMap, format, string::String, sync::Arc, vec::Vec};
+use alloy::{
+    rpc::json_rpc::{RequestPacket, Response, ResponsePacket, SerializedRequest},
+    transports::{Transport, TransportError, TransportErrorKind, TransportFut},
+};
+use core::task::{Context, Poll};
+use std::{
+    fs::File,
+    io::{BufReader, BufWriter},
+    path::Path,
+    sync::Mutex,
+};
+use tower::Service;
+
+/// The recorded responses keyed by method and params, see [`fixture_key`].
+pub type Fixtures = BTreeMap<String, Response>;
+
+/// Returns the key a request is recorded under, i.e. its method and serialized params
+#[inline]
+#[must_use]
+pub fn fixture_key(request: &SerializedRequest) -> String {
+    format!(
+        "{}:{}",
+        request.method(),
+        request.params().map_or("", |params| params.get())
+    )
+}
+
+/// Loads fixtures saved by [`FixtureTransport::save`]
+///
+/// ## Arguments
+///
+/// * `path`: The path of the JSON file
+#[inline]
+pub fn load_fixtures(path: impl AsRef<Path>) -> std::io::Result<Fixtures> {
+    let reader = BufReader::new(File::open(path)?);
+    Ok(serde_json::from_reader(reader)?)
+}
+
+/// A transport serving requests from recorded fixtures.
+///
+/// Requests that have been recorded are answered from the fixtures. Others are forwarded to the
+/// inner transport, if any, and their responses recorded; without an inner transport they fail.
+#[derive(Clone, Debug)]
+pub struct FixtureTransport<T> {
+    inner: Option<T>,
+    fixtures: Arc<Mutex<Fixtures>>,
+}
+
+impl<T> FixtureTransport<T> {
+    /// Creates a transport recording the responses of `inner`
+    ///
+    /// ## Arguments
+    ///
+    /// * `inner`: The transport to forward unrecorded requests to
+    #[inline]
+    pub fn record(inner: T) -> Self {
+        Self::with_fixtures(Some(inner), Fixtures::new())
+    }
+
+    /// Creates a transport replaying the given fixtures only
+    ///
+    /// ## Arguments
+    ///
+    /// * `fixtures`: The recorded responses
+    #[inline]
+    pub fn replay(fixtures: Fixtures) -> Self {
+        Self::with_fixtures(None, fixtures)
+    }
+
+    /// Creates a transport replaying the given fixtures and recording the responses of `inner` to
+    /// the other requests, if any
+    ///
+    /// ## Arguments
+    ///
+    /// * `inner`: The optional transport to forward unrecorded requests to
+    /// * `fixtures`: The recorded responses
+    #[inline]
+    pub fn with_fixtures(inner: Option<T>, fixtures: Fixtures) -> Self {
+        Self {
+            inner,
+            fixtures: Arc::new(Mutex::new(fixtures)),
+        }
+    }
+
+    /// Returns a copy of the responses recorded so far
+    #[inline]
+    #[must_use]
+    pub fn fixtures(&self) -> Fixtures {
+        self.fixtures.lock().unwrap().clone()
+    }
+
+    /// Saves the responses recorded so far to a JSON file
+    ///
+    /// ## Arguments
+    ///
+    /// * `path`: The path of the JSON file
+    #[inline]
+    pub fn save(&self, path: impl AsRef<Path>) -> std::io::Result<()> {
+        let writer = BufWriter::new(File::create(path)?);
+        serde_json::to_writer_pretty(writer, &self.fixtures())?;
+        Ok(())
+    }
+}
+
+impl<T: Transport + Clone> FixtureTransport<T> {
+    async fn request(&mut self, request: SerializedRequest) -> Result<Response, TransportError> {
+        let key = fixture_key(&request);
+        let recorded = self.fixtures.lock().unwrap().get(&key).cloned();
+        if let Some(response) = recorded {
+            return Ok(Response {
+                id: request.id().clone(),
+                payload: response.payload,
+            });
+        }
+        let Some(inner) = self.inner.as_mut() else {
+            return Err(TransportErrorKind::custom_str(&format!(
+                "no fixture recorded for {key}"
+            )));
+        };
+        let ResponsePacket::Single(response) = inner.call(RequestPacket::Single(request)).await?
+        else {
+            return Err(TransportErrorKind::custom_str(
+                "batch response to a single request",
+            ));
+        };
+        self.fixtures.lock().unwrap().insert(key, response.clone());
+        Ok(response)
+    }
+}
+
+impl<T: Transport + Clone> Service<RequestPacket> for FixtureTransport<T> {
+    type Response = ResponsePacket;
+    type Error = TransportError;
+    type Future = TransportFut<'static>;
+
+    #[inline]
+    fn poll_ready(&mut self, cx: &mut Context<'_>) -> Poll<Result<(), Self::Error>> {
+        match self.inner.as_mut() {
+            Some(inner) => inner.poll_ready(cx),
+            None => Poll::Ready(Ok(())),
+        }
+    }
+
+    #[inline]
+    fn call(&mut self, packet: RequestPacket) -> Self::Future {
+        let mut this = self.clone();
+        Box::pin(async move {
+            match packet {
+                RequestPacket::Single(request) => {
+                    this.request(request).await.map(ResponsePacket::Single)
+                }
+                RequestPacket::Batch(requests) => {
+                    let mut responses = Vec::with_capacity(requests.len());
+                    for request in requests {
+                        responses.push(this.request(request).await?);
+                    }
+                    Ok(ResponsePacket::Batch(responses))
+                }
+            }
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use alloy::{
+        providers::{Provider, ProviderBuilder},
+        rpc::{client::RpcClient, json_rpc::ResponsePayload},
+    };
+    use serde_json::value::RawValue;
+
+    /// A transport answering every request with `0x1`, standing in for a node.
+    #[derive(Clone, Debug)]
+    struct OneTransport;
+
+    impl Service<RequestPacket> for OneTransport {
+        type Response = ResponsePacket;
+        type Error = TransportError;
+        type Future = TransportFut<'static>;
+
+        fn poll_ready(&mut self, _: &mut Context<'_>) -> Poll<Result<(), Self::Error>> {
+            Poll::Ready(Ok(()))
+        }
+
+        fn call(&mut self, packet: RequestPacket) -> Self::Future {
+            Box::pin(async move {
+                let RequestPacket::Single(request) = packet else {
+                    return Err(TransportErrorKind::custom_str("batch request"));
+                };
+                Ok(ResponsePacket::Single(Response {
+                    id: request.id().clone(),
+                    payload: ResponsePayload::Success(
+                        RawValue::from_string("\"0x1\"".into()).unwrap(),
+                    ),
+                }))
+            })
+        }
+    }
+
+    #[tokio::test]
+    async fn test_record_and_replay() {
+        let transport = FixtureTransport::record(OneTransport);
+        let provider = ProviderBuilder::new().on_client(RpcClient::new(transport.clone(), false));
+        assert_eq!(provider.get_chain_id().await.unwrap(), 1);
+        assert_eq!(transport.fixtures().len(), 1);
+
+        // round trip through a file outside of the source tree
+        let path = std::env::temp_dir().join(format!("rpc_fixtures_{}.json", std::process::id()));
+        transport.save(&path).unwrap();
+        let fixtures = load_fixtures(&path).unwrap();
+        std::fs::remove_file(&path).unwrap();
+        assert_eq!(fixtures.len(), 1);
+
+        let replay = FixtureTransport::<OneTransport>::replay(fixtures);
+        let provider = ProviderBuilder::new().on_client(RpcClient::new(replay, false));
+        assert_eq!(provider.get_chain_id().await.unwrap(), 1);
+        // requests that were not recorded fail without an inner transport
+        assert!(provider.get_block_number().await.is_err());
+    }
+}
@@ -0,0 +1,351 @@
+//! ## Storage Tick Data Provider
+//! A data provider that reads the tick bitmap and the ticks of a pool directly from its storage
+//! with batched `eth_getStorageAt` requests, for chains where the lens contracts are not deployed.
+
+use crate::prelude::*;
+use alloy::{
+    eips::{BlockId, BlockNumberOrTag},
+    providers::Provider,
+    rpc::client::BatchRequest,
+    transports::Transport,
+};
+use alloy_primitives::{aliases::I24, keccak256, Address, U256};
+use derive_more::Deref;
+use uniswap_lens::bindings::iuniswapv3pool::IUniswapV3Pool::IUniswapV3PoolInstance;
+
+/// The storage slot of the `ticks` mapping of `UniswapV3Pool`.
+pub const TICKS_SLOT: u64 = 5;
+/// The storage slot of the `tickBitmap` mapping of `UniswapV3Pool`.
+pub const TICK_BITMAP_SLOT: u64 = 6;
+
+/// The maximum number of `eth_getStorageAt` requests sent in a single batch.
+const BATCH_SIZE: usize = 100;
+
+/// Computes the storage slot of the value of a mapping keyed by a signed integer, i.e.
+/// `keccak256(abi.encode(key, slot))`
+///
+/// ## Arguments
+///
+/// * `key`: The mapping key, e.g. a tick or a tick bitmap word position
+/// * `slot`: The storage slot of the mapping
+#[inline]
+#[must_use]
+pub fn mapping_slot(key: i32, slot: u64) -> U256 {
+    let mut preimage = [0_u8; 64];
+    if key < 0 {
+        preimage[..28].fill(0xff);
+    }
+    preimage[28..32].copy_from_slice(&key.to_be_bytes());
+    preimage[56..].copy_from_slice(&slot.to_be_bytes());
+    U256::from_be_bytes(keccak256(preimage).0)
+}
+
+/// Reads the given storage slots of a contract in batches of [`BATCH_SIZE`] requests
+pub(crate) async fn get_storage_batched<T, P>(
+    provider: &P,
+    address: Address,
+    slots: &[U256],
+    block_id: BlockId,
+) -> Result<Vec<U256>, Error>
+where
+    T: Transport + Clone,
+    P: Provider<T>,
+{
+    let mut values = Vec::with_capacity(slots.len());
+    for chunk in slots.chunks(BATCH_SIZE) {
+        let mut batch = BatchRequest::new(provider.client());
+        let waiters = chunk
+            .iter()
+            .map(|slot| batch.add_call::<_, U256>("eth_getStorageAt", &(address, *slot, block_id)))
+            .collect::<Result<Vec<_>, _>>()?;
+        batch.send().await?;
+        for waiter in waiters {
+            values.push(waiter.await?);
+        }
+    }
+    Ok(values)
+}
+
+/// A data provider that fetches ticks from the storage of the pool without any lens contract.
+#[derive(Clone, Debug, PartialEq, Deref)]
+pub struct StorageTickDataProvider<I = I24> {
+    pub pool: Address,
+    pub tick_lower: I,
+    pub tick_upper: I,
+    pub tick_spacing: I,
+    pub block_id: Option<BlockId>,
+    #[deref]
+    pub ticks: Vec<Tick<I>>,
+}
+
+impl<I: TickIndex> StorageTickDataProvider<I> {
+    /// Reads the bitmap words covering the tick range, then the initialized ticks within it
+    ///
+    /// ## Arguments
+    ///
+    /// * `pool`: The pool address
+    /// * `provider`: The alloy provider
+    /// * `tick_lower`: The lower tick of the range to fetch, defaults to [`MIN_TICK`]
+    /// * `tick_upper`: The upper tick of the range to fetch, defaults to [`MAX_TICK`]
+    /// * `block_id`: Optional block number to query
+    #[inline]
+    pub async fn new<T, P>(
+        pool: Address,
+        provider: P,
+        tick_lower: Option<I>,
+        tick_upper: Option<I>,
+        block_id: Option<BlockId>,
+    ) -> Result<Self, Error>
+    where
+        T: Transport + Clone,
+        P: Provider<T>,
+    {
+        Self::new_with_limits(
+            pool,
+            provider,
+            tick_lower,
+            tick_upper,
+            block_id,
+            TickDataProviderLimits::default(),
+        )
+        .await
+    }
+
+    /// Same as [`Self::new`] but fails with [`Error::TickLimitExceeded`] if the tick range spans
+    /// too many bitmap words, before reading the bitmap, or contains too many initialized ticks,
+    /// before reading the ticks
+    ///
+    /// ## Arguments
+    ///
+    /// * `pool`: The pool address
+    /// * `provider`: The alloy provider
+    /// * `tick_lower`: The lower tick of the range to fetch, defaults to [`MIN_TICK`]
+    /// * `tick_upper`: The upper tick of the range to fetch, defaults to [`MAX_TICK`]
+    /// * `block_id`: Optional block number to query
+    /// * `limits`: The limits to enforce
+    #[inline]
+    pub async fn new_with_limits<T, P>(
+        pool: Address,
+        provider: P,
+        tick_lower: Option<I>,
+        tick_upper: Option<I>,
+        block_id: Option<BlockId>,
+        limits: TickDataProviderLimits,
+    ) -> Result<Self, Error>
+    where
+        T: Transport + Clone,
+        P: Provider<T>,
+    {
+        Self::fetch(
+            pool,
+            provider,
+            tick_lower,
+            tick_upper,
+            block_id,
+            limits,
+            NoopTickMetrics,
+        )
+        .await
+    }
+
+    /// Same as [`Self::new`] but records the requests and the ticks fetched to a [`TickMetrics`]
+    /// sink
+    ///
+    /// ## Arguments
+    ///
+    /// * `pool`: The pool address
+    /// * `provider`: The alloy provider
+    /// * `tick_lower`: The lower tick of the range to fetch, defaults to [`MIN_TICK`]
+    /// * `tick_upper`: The upper tick of the range to fetch, defaults to [`MAX_TICK`]
+    /// * `block_id`: Optional block number to query
+    /// * `metrics`: The sink
+    #[inline]
+    pub async fn new_metered<T, P>(
+        pool: Address,
+        provider: P,
+        tick_lower: Option<I>,
+        tick_upper: Option<I>,
+        block_id: Option<BlockId>,
+        metrics: impl TickMetrics,
+    ) -> Result<Self, Error>
+    where
+        T: Transport + Clone,
+        P: Provider<T>,
+    {
+        Self::fetch(
+            pool,
+            provider,
+            tick_lower,
+            tick_upper,
+            block_id,
+            TickDataProviderLimits::default(),
+            metrics,
+        )
+        .await
+    }
+
+    async fn fetch<T, P>(
+        pool: Address,
+        provider: P,
+        tick_lower: Option<I>,
+        tick_upper: Option<I>,
+        block_id: Option<BlockId>,
+        limits: TickDataProviderLimits,
+        metrics: impl TickMetrics,
+    ) -> Result<Self, Error>
+    where
+        T: Transport + Clone,
+        P: Provider<T>,
+    {
+        metrics.record_rpc_calls(1);
+        let block = block_id.unwrap_or(BlockId::Number(BlockNumberOrTag::Latest));
+        let tick_spacing = IUniswapV3PoolInstance::<T, _>::new(pool, &provider)
+            .tickSpacing()
+            .block(block)
+            .call()
+            .await?
+            ._0
+            .as_i32();
+        let tick_lower = tick_lower.map_or(MIN_TICK, I::to_i24).as_i32();
+        let tick_upper = tick_upper.map_or(MAX_TICK, I::to_i24).as_i32();
+        let (word_lower, _) = tick_lower.compress(tick_spacing).position();
+        let (word_upper, _) = tick_upper.compress(tick_spacing).position();
+        limits.check_tick_range(tick_lower, tick_upper, tick_spacing)?;
+
+        let word_slots: Vec<U256> = (word_lower..=word_upper)
+            .map(|word| mapping_slot(word, TICK_BITMAP_SLOT))
+            .collect();
+        metrics.record_rpc_calls(word_slots.len() as u64);
+        let words = get_storage_batched(&provider, pool, &word_slots, block).await?;
+        let mut indices: Vec<i32> = Vec::new();
+        for (word, bitmap) in (word_lower..=word_upper).zip(words) {
+            if bitmap.is_zero() {
+                continue;
+            }
+            for bit in 0..256 {
+                if bitmap.bit(bit) {
+                    let tick = ((word << 8) + bit as i32) * tick_spacing;
+                    if tick_lower <= tick && tick <= tick_upper {
+                        indices.push(tick);
+                    }
+                }
+            }
+        }
+        limits.check_ticks(indices.len())?;
+
+        let tick_slots: Vec<U256> = indices
+            .iter()
+            .map(|&tick| mapping_slot(tick, TICKS_SLOT))
+            .collect();
+        metrics.record_rpc_calls(tick_slots.len() as u64);
+        let infos = get_storage_batched(&provider, pool, &tick_slots, block).await?;
+        // the first slot of `Tick.Info` packs `uint128 liquidityGross` and `int128 liquidityNet`
+        let ticks: Vec<_> = indices
+            .into_iter()
+            .zip(infos)
+            .map(|(index, info)| {
+                let limbs = info.as_limbs();
+                Tick::new(
+                    I::try_from(index).unwrap(),
+                    limbs[0] as u128 | ((limbs[1] as u128) << 64),
+                    (limbs[2] as u128 | ((limbs[3] as u128) << 64)) as i128,
+                )
+            })
+            .collect();
+        metrics.record_ticks_fetched(ticks.len() as u64);
+        Ok(Self {
+            pool,
+            tick_lower: I::try_from(tick_lower).unwrap(),
+            tick_upper: I::try_from(tick_upper).unwrap(),
+            tick_spacing: I::try_from(tick_spacing).unwrap(),
+            block_id,
+            ticks,
+        })
+    }
+}
+
+impl<I: TickIndex> From<StorageTickDataProvider<I>> for TickListDataProvider<I> {
+    #[inline]
+    fn from(provider: StorageTickDataProvider<I>) -> Self {
+        assert!(!provider.ticks.is_empty());
+        Self::new(provider.ticks, provider.tick_spacing)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::tests::*;
+    use alloy_primitives::address;
+
+    #[tokio::test]
+    async fn test_storage_tick_data_provider() -> Result<(), Error> {
+        let pool = address!("88e6A0c2dDD26FEEb64F039a2c41296FcB3f5640");
+        let provider = StorageTickDataProvider::new(
+            pool,
+            PROVIDER.clone(),
+            Some(-100000),
+            Some(-80000),
+            *BLOCK_ID,
+        )
+        .await?;
+        assert!(!provider.ticks.is_empty());
+        provider.ticks.validate_list(10);
+        let tick = provider.get_tick(-92110)?;
+        assert_eq!(tick.liquidity_gross, 398290794261);
+        assert_eq!(tick.liquidity_net, 398290794261);
+
+        let ephemeral = EphemeralTickDataProvider::new(
+            pool,
+            PROVIDER.clone(),
+            Some(-100000),
+            Some(-80000),
+            *BLOCK_ID,
+        )
+        .await?;
+        let expected: Vec<_> = ephemeral
+            .ticks
+            .into_iter()
+            .filter(|tick| (-100000..=-80000).contains(&tick.index))
+            .collect();
+        assert_eq!(provider.ticks, expected);
+
+        let limits = TickDataProviderLimits {
+            max_ticks: Some(provider.ticks.len() - 1),
+            max_word_range: None,
+        };
+        let res = StorageTickDataProvider::<i32>::new_with_limits(
+            pool,
+            PROVIDER.clone(),
+            Some(-100000),
+            Some(-80000),
+            *BLOCK_ID,
+            limits,
+        )
+        .await;
+        assert!(matches!(res, Err(Error::TickLimitExceeded)));
+
+        let metrics = AtomicTickMetrics::new();
+        let metered = StorageTickDataProvider::<i32>::new_metered(
+            pool,
+            PROVIDER.clone(),
+            Some(-100000),
+            Some(-80000),
+            *BLOCK_ID,
+            &metrics,
+        )
+        .await?;
+        assert_eq!(metered.ticks, provider.ticks);
+        let ticks = provider.ticks.len() as u64;
+        // `tickSpacing`, the 9 bitmap words from -40 to -32 and the ticks
+        assert_eq!(
+            metrics.snapshot(),
+            TickMetricsSnapshot {
+                rpc_calls: 1 + 9 + ticks,
+                ticks_fetched: ticks,
+                ..Default::default()
+            }
+        );
+        Ok(())
+    }
+}
@@ -0,0 +1,371 @@
+//! ## Swap Executor
+//! An opinionated facade over quoting, slippage, calldata encoding, approvals, deadline, gas
+//! estimation and receipt parsing for swaps through `SwapRouter02`. The lower level functions
+//! remain available for anything it does not cover.
+
+use crate::prelude::{Error, *};
+use alloy::{
+    contract::Error as ContractError,
+    eips::{BlockId, BlockNumberOrTag},
+    providers::Provider,
+    rpc::types::{TransactionReceipt, TransactionRequest},
+    transports::Transport,
+};
+use alloy_primitives::{Address, ChainId, U256};
+use alloy_sol_types::SolCall;
+use core::marker::PhantomData;
+use uniswap_sdk_core::prelude::*;
+
+/// The contracts and defaults used by a [`SwapExecutor`] on a chain.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+pub struct ChainConfig {
+    pub chain_id: ChainId,
+    /// The `SwapRouter02` the swaps are sent to.
+    pub swap_router: Address,
+    /// The `QuoterV2` used to quote the swaps.
+    pub quoter: Address,
+    /// The gas limit relative to the gas estimate, in basis points.
+    pub gas_limit_bps: u64,
+}
+
+impl ChainConfig {
+    /// Creates the config of a chain with the canonical deployments and a 20% gas limit buffer,
+    /// the `QuoterV2` of [`ChainDeployment::for_chain`] if the chain has one
+    ///
+    /// ## Arguments
+    ///
+    /// * `chain_id`: The chain id
+    #[inline]
+    pub fn new(chain_id: ChainId) -> Result<Self, Error> {
+        let swap_router = SWAP_ROUTER_02_ADDRESSES
+            .get(&chain_id)
+            .ok_or(Error::UnsupportedChain(chain_id))?;
+        let quoter = match ChainDeployment::for_chain(chain_id) {
+            Some(deployment) => deployment.quoter_v2,
+            None => *QUOTER_V2_ADDRESSES
+                .get(&chain_id)
+                .ok_or(Error::UnsupportedChain(chain_id))?,
+        };
+        Ok(Self {
+            chain_id,
+            swap_router: *swap_router,
+            quoter,
+            gas_limit_bps: 12_000,
+        })
+    }
+}
+
+/// A swap built by [`SwapExecutor::build`], ready to be sent.
+#[derive(Clone, Debug)]
+pub struct SwapPlan {
+    /// The swap transaction, with a gas limit unless an approval is required first.
+    pub tx: TransactionRequest,
+    /// The approval of the input token to send before the swap if the allowance is insufficient.
+    pub approval: Option<TransactionRequest>,
+    /// The timestamp after which the swap reverts.
+    pub deadline: U256,
+    /// The limits encoded in the swap.
+    pub summary: SwapPlanSummary,
+    /// Whether the output is unwrapped to ether.
+    pub output_is_native: bool,
+}
+
+/// The result of a swap sent by [`SwapExecutor::send`].
+#[derive(Clone, Debug)]
+pub struct SwapOutcome {
+    pub receipt: TransactionReceipt,
+    /// The events decoded from the receipt.
+    pub report: ExecutionReport,
+    /// The actual output reconciled against the encoded limits.
+    pub reconciliation: SwapReconciliation,
+}
+
+/// Quotes, builds and sends swaps on behalf of an account in a few calls.
+///
+/// The provider must be able to sign for the sender, e.g. with a wallet filler.
+#[derive(Clone, Debug)]
+pub struct SwapExecutor<T, P> {
+    pub provider: P,
+    /// The account sending and receiving the swaps.
+    pub sender: Address,
+    pub config: ChainConfig,
+    /// The block the quotes, allowances and gas estimates are made against.
+    pub block_id: BlockId,
+    _transport: PhantomData<T>,
+}
+
+impl<T, P> SwapExecutor<T, P>
+where
+    T: Transport + Clone,
+    P: Provider<T>,
+{
+    /// Creates an executor working against the latest block
+    ///
+    /// ## Arguments
+    ///
+    /// * `provider`: The alloy provider, able to sign for the sender
+    /// * `sender`: The account sending and receiving the swaps
+    /// * `config`: The contracts and defaults of the chain
+    #[inline]
+    #[must_use]
+    pub const fn new(provider: P, sender: Address, config: ChainConfig) -> Self {
+        Self {
+            provider,
+            sender,
+            config,
+            block_id: BlockId::Number(BlockNumberOrTag::Latest),
+            _transport: PhantomData,
+        }
+    }
+
+    /// Quotes a swap through a route with `QuoterV2` and returns the corresponding trade
+    ///
+    /// ## Arguments
+    ///
+    /// * `route`: The route to swap through
+    /// * `amount`: The amount specified, either input or output, depending on `trade_type`
+    /// * `trade_type`: Whether the trade is an exact input or exact output swap
+    #[inline]
+    pub async fn quote<TInput, TOutput, TP>(
+        &self,
+        route: Route<TInput, TOutput, TP>,
+        amount: &CurrencyAmount<impl BaseCurrency>,
+        trade_type: TradeType,
+    ) -> Result<Trade<TInput, TOutput, TP>, Error>
+    where
+        TInput: BaseCurrency,
+        TOutput: BaseCurrency,
+        TP: TickDataProvider,
+    {
+        let quote = quote_route_with_quoter_v2(
+            &self.provider,
+            self.config.quoter,
+            &route,
+            amount,
+            trade_type,
+            Some(self.block_id),
+        )
+        .await?;
+        let (amount_in, amount_out) = match trade_type {
+            TradeType::ExactInput => (amount.quotient(), quote.amount.to_big_int()),
+            TradeType::ExactOutput => (quote.amount.to_big_int(), amount.quotient()),
+        };
+        let input_amount = CurrencyAmount::from_raw_amount(route.input.clone(), amount_in)?;
+        let output_amount = CurrencyAmount::from_raw_amount(route.output.clone(), amount_out)?;
+        Trade::create_unchecked_trade(route, input_amount, output_amount, trade_type)
+    }
+
+    /// Builds the swap of the given trades to the sender with a deadline, the approval of the
+    /// input token if needed and a gas limit
+    ///
+    /// ## Arguments
+    ///
+    /// * `trades`: The trades to execute, with the same input and output
+    /// * `slippage_tolerance`: How much the execution price is allowed to move unfavorably
+    /// * `deadline`: The timestamp after which the swap reverts
+    ///
+    /// ## Returns
+    ///
+    /// [`Error::NoTrades`] if `trades` is empty
+    #[inline]
+    pub async fn build<TInput, TOutput, TP>(
+        &self,
+        trades: &mut [Trade<TInput, TOutput, TP>],
+        slippage_tolerance: Percent,
+        deadline: U256,
+    ) -> Result<SwapPlan, Error>
+    where
+        TInput: BaseCurrency,
+        TOutput: BaseCurrency,
+        TP: TickDataProvider,
+    {
+        let Some(trade) = trades.first() else {
+            return Err(Error::NoTrades);
+        };
+        let input_currency = trade.input_currency();
+        let input_is_native = input_currency.is_native();
+        let token_in = input_currency.wrapped().address();
+        let output_is_native = trade.output_currency().is_native();
+        let (params, summary) = swap_call_parameters_with_summary(
+            trades,
+            SwapOptions {
+                slippage_tolerance,
+                recipient: self.sender,
+                multicall_wrapping: MulticallWrapping::Always,
+                ..Default::default()
+            },
+        )?;
+
+        // add the deadline by re-encoding the calls with the extended `multicall`
+        let data = IMulticall::multicallCall::abi_decode(&params.calldata, true)
+            .map_err(ContractError::from)?
+            .data;
+        let tx = TransactionRequest::default()
+            .from(self.sender)
+            .to(self.config.swap_router)
+            .input(
+                IMulticallExtended::multicallCall { deadline, data }
+                    .abi_encode()
+                    .into(),
+            )
+            .value(params.value);
+
+        let approval = if input_is_native {
+            None
+        } else {
+            let amount_in_maximum = summary
+                .swaps
+                .iter()
+                .fold(U256::ZERO, |sum, swap| sum + swap.amount_in_maximum);
+            let allowance_tx = TransactionRequest::default().to(token_in).input(
+                IERC20::allowanceCall {
+                    owner: self.sender,
+                    spender: self.config.swap_router,
+                }
+                .abi_encode()
+                .into(),
+            );
+            let res = self
+                .provider
+                .call(&allowance_tx)
+                .block(self.block_id)
+                .await?;
+            let allowance = IERC20::allowanceCall::abi_decode_returns(&res, true)
+                .map_err(ContractError::from)?
+                ._0;
+            (allowance < amount_in_maximum).then(|| {
+                TransactionRequest::default()
+                    .from(self.sender)
+                    .to(token_in)
+                    .input(
+                        IERC20::approveCall {
+                            spender: self.config.swap_router,
+                            amount: amount_in_maximum,
+                        }
+                        .abi_encode()
+                        .into(),
+                    )
+            })
+        };
+        // the swap cannot be estimated before the approval is mined
+        let tx = if approval.is_none() {
+            self.with_gas_limit(tx, self.block_id).await?
+        } else {
+            tx
+        };
+        Ok(SwapPlan {
+            tx,
+            approval,
+            deadline,
+            summary,
+            output_is_native,
+        })
+    }
+
+    /// Sends the approval if any and the swap, waits for the receipt and reconciles the output
+    ///
+    /// ## Arguments
+    ///
+    /// * `plan`: The swap returned by [`Self::build`]
+    #[inline]
+    pub async fn send(&self, plan: SwapPlan) -> Result<SwapOutcome, Error> {
+        let SwapPlan {
+            mut tx,
+            approval,
+            summary,
+            ..
+        } = plan;
+        if let Some(approval) = approval {
+            self.provider
+                .send_transaction(approval)
+                .await?
+                .get_receipt()
+                .await
+                .map_err(ContractError::from)?;
+            tx = self
+                .with_gas_limit(tx, BlockId::Number(BlockNumberOrTag::Latest))
+                .await?;
+        }
+        let receipt = self
+            .provider
+            .send_transaction(tx)
+            .await?
+            .get_receipt()
+            .await
+            .map_err(ContractError::from)?;
+        let report = ExecutionReport::from_receipt(&receipt);
+        let reconciliation = report.reconcile(&summary);
+        Ok(SwapOutcome {
+            receipt,
+            report,
+            reconciliation,
+        })
+    }
+
+    async fn with_gas_limit(
+        &self,
+        tx: TransactionRequest,
+        block_id: BlockId,
+    ) -> Result<TransactionRequest, Error> {
+        let gas = self.provider.estimate_gas(&tx).block(block_id).await?;
+        Ok(tx.gas_limit(gas * self.config.gas_limit_bps / 10_000))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::tests::*;
+    use alloy_primitives::address;
+
+    const SENDER: Address = address!("0000000000000000000000000000000000000003");
+
+    #[tokio::test]
+    async fn test_quote_and_build() {
+        let executor = SwapExecutor {
+            block_id: BLOCK_ID.unwrap(),
+            ..SwapExecutor::new(PROVIDER.clone(), SENDER, ChainConfig::new(1).unwrap())
+        };
+        let pool = Pool::from_pool_key(
+            1,
+            FACTORY_ADDRESS,
+            WETH.address(),
+            USDC.address(),
+            FeeAmount::LOW,
+            PROVIDER.clone(),
+            *BLOCK_ID,
+        )
+        .await
+        .unwrap();
+        let amount_in = CurrencyAmount::from_raw_amount(WETH.clone(), 10_u128.pow(18)).unwrap();
+        let trade = executor
+            .quote(
+                Route::new(vec![pool], WETH.clone(), USDC.clone()),
+                &amount_in,
+                TradeType::ExactInput,
+            )
+            .await
+            .unwrap();
+        assert!(trade.output_amount().unwrap().quotient() > BigInt::ZERO);
+
+        let deadline = U256::from(u64::MAX);
+        let plan = executor
+            .build(&mut [trade], Percent::new(5, 1000), deadline)
+            .await
+            .unwrap();
+        assert!(plan.approval.is_some());
+        assert!(plan.tx.gas.is_none());
+        assert_eq!(plan.deadline, deadline);
+        assert_eq!(plan.summary.swaps.len(), 1);
+        assert_eq!(plan.summary.recipient, SENDER);
+        assert!(!plan.output_is_native);
+
+        let mut no_trades: [Trade<Token, Token, NoTickDataProvider>; 0] = [];
+        assert!(matches!(
+            executor
+                .build(&mut no_trades, Percent::new(5, 1000), deadline)
+                .await,
+            Err(Error::NoTrades)
+        ));
+    }
+}
@@ -0,0 +1,252 @@
+//! ## Tick Lens Data Provider
+//! A data provider that fetches the populated ticks of a pool word by word from the canonical
+//! `TickLens` periphery contract, for chains where the ephemeral lens contracts can't be used.
+
+use super::storage_tick_data_provider::{get_storage_batched, mapping_slot, TICK_BITMAP_SLOT};
+use crate::prelude::{Error, *};
+use alloy::{
+    contract::Error as ContractError,
+    eips::{BlockId, BlockNumberOrTag},
+    providers::Provider,
+    rpc::{client::BatchRequest, types::TransactionRequest},
+    transports::Transport,
+};
+use alloy_primitives::{address, aliases::I24, Address, Bytes};
+use alloy_sol_types::SolCall;
+use derive_more::Deref;
+use uniswap_lens::bindings::iuniswapv3pool::IUniswapV3Pool::IUniswapV3PoolInstance;
+
+/// The address of the `TickLens` contract on Ethereum, Optimism, Arbitrum and Polygon.
+pub const TICK_LENS_ADDRESS: Address = address!("bfd8137f7d1516D3ea5cA83523914859ec47F573");
+
+/// Options for fetching ticks with [`TickLensDataProvider`].
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+pub struct TickLensOptions {
+    /// The address of the `TickLens` contract on the chain of the pool.
+    pub tick_lens: Address,
+    /// The first tick bitmap word to fetch, defaults to the word of [`MIN_TICK`].
+    pub word_lower: Option<i16>,
+    /// The last tick bitmap word to fetch, defaults to the word of [`MAX_TICK`].
+    pub word_upper: Option<i16>,
+    /// The maximum number of `eth_call`s sent at once in a JSON-RPC batch.
+    pub concurrency: usize,
+    /// The limits checked before fetching the bitmap and after fetching the ticks.
+    pub limits: TickDataProviderLimits,
+}
+
+impl Default for TickLensOptions {
+    #[inline]
+    fn default() -> Self {
+        Self {
+            tick_lens: TICK_LENS_ADDRESS,
+            word_lower: None,
+            word_upper: None,
+            concurrency: 20,
+            limits: TickDataProviderLimits::default(),
+        }
+    }
+}
+
+/// A data provider that fetches ticks with the `TickLens` contract.
+///
+/// The tick bitmap is read from storage first, so that only the words with populated ticks are
+/// queried from the lens.
+#[derive(Clone, Debug, PartialEq, Deref)]
+pub struct TickLensDataProvider<I = I24> {
+    pub pool: Address,
+    pub word_lower: i16,
+    pub word_upper: i16,
+    pub tick_spacing: I,
+    pub block_id: Option<BlockId>,
+    #[deref]
+    pub ticks: Vec<Tick<I>>,
+}
+
+impl<I: TickIndex> TickLensDataProvider<I> {
+    /// Fetches the populated ticks in the word range of the options
+    ///
+    /// ## Arguments
+    ///
+    /// * `pool`: The pool address
+    /// * `provider`: The alloy provider
+    /// * `options`: The lens address, the word range and the concurrency of the requests
+    /// * `block_id`: Optional block number to query
+    #[inline]
+    pub async fn new<T, P>(
+        pool: Address,
+        provider: P,
+        options: TickLensOptions,
+        block_id: Option<BlockId>,
+    ) -> Result<Self, Error>
+    where
+        T: Transport + Clone,
+        P: Provider<T>,
+    {
+        Self::new_metered(pool, provider, options, block_id, NoopTickMetrics).await
+    }
+
+    /// Same as [`Self::new`] but records the calls and the ticks fetched to a [`TickMetrics`] sink
+    ///
+    /// ## Arguments
+    ///
+    /// * `pool`: The pool address
+    /// * `provider`: The alloy provider
+    /// * `options`: The lens address, the word range and the concurrency of the requests
+    /// * `block_id`: Optional block number to query
+    /// * `metrics`: The sink
+    #[inline]
+    pub async fn new_metered<T, P>(
+        pool: Address,
+        provider: P,
+        options: TickLensOptions,
+        block_id: Option<BlockId>,
+        metrics: impl TickMetrics,
+    ) -> Result<Self, Error>
+    where
+        T: Transport + Clone,
+        P: Provider<T>,
+    {
+        assert!(options.concurrency > 0, "CONCURRENCY");
+        let block = block_id.unwrap_or(BlockId::Number(BlockNumberOrTag::Latest));
+        let tick_spacing = IUniswapV3PoolInstance::<T, _>::new(pool, &provider)
+            .tickSpacing()
+            .block(block)
+            .call()
+            .await?
+            ._0
+            .as_i32();
+        let word_lower = options
+            .word_lower
+            .unwrap_or_else(|| MIN_TICK_I32.compress(tick_spacing).position().0 as i16);
+        let word_upper = options
+            .word_upper
+            .unwrap_or_else(|| MAX_TICK_I32.compress(tick_spacing).position().0 as i16);
+        if let Some(max_word_range) = options.limits.max_word_range {
+            if (word_upper as i64 - word_lower as i64 + 1) > max_word_range as i64 {
+                return Err(Error::TickLimitExceeded);
+            }
+        }
+
+        let word_slots: Vec<_> = (word_lower..=word_upper)
+            .map(|word| mapping_slot(word.into(), TICK_BITMAP_SLOT))
+            .collect();
+        let bitmaps = get_storage_batched(&provider, pool, &word_slots, block).await?;
+        metrics.record_rpc_calls(1 + word_slots.len() as u64);
+        let words: Vec<i16> = (word_lower..=word_upper)
+            .zip(bitmaps)
+            .filter(|(_, bitmap)| !bitmap.is_zero())
+            .map(|(word, _)| word)
+            .collect();
+
+        let mut ticks = Vec::new();
+        for chunk in words.chunks(options.concurrency) {
+            let mut batch = BatchRequest::new(provider.client());
+            let waiters = chunk
+                .iter()
+                .map(|&word| {
+                    let tx = TransactionRequest::default().to(options.tick_lens).input(
+                        ITickLens::getPopulatedTicksInWordCall {
+                            pool,
+                            tickBitmapIndex: word,
+                        }
+                        .abi_encode()
+                        .into(),
+                    );
+                    batch.add_call::<_, Bytes>("eth_call", &(tx, block))
+                })
+                .collect::<Result<Vec<_>, _>>()?;
+            batch.send().await?;
+            metrics.record_rpc_calls(chunk.len() as u64);
+            for waiter in waiters {
+                let res = waiter.await?;
+                let populated_ticks =
+                    ITickLens::getPopulatedTicksInWordCall::abi_decode_returns(&res, true)
+                        .map_err(ContractError::from)?
+                        .populatedTicks;
+                ticks.extend(populated_ticks.into_iter().map(|tick| {
+                    Tick::new(
+                        I::from_i24(tick.tick),
+                        tick.liquidityGross,
+                        tick.liquidityNet,
+                    )
+                }));
+            }
+        }
+        metrics.record_ticks_fetched(ticks.len() as u64);
+        options.limits.check_ticks(ticks.len())?;
+        // the lens returns the ticks of a word in descending order
+        ticks.sort_unstable_by_key(|tick| tick.index);
+        Ok(Self {
+            pool,
+            word_lower,
+            word_upper,
+            tick_spacing: I::try_from(tick_spacing).unwrap(),
+            block_id,
+            ticks,
+        })
+    }
+
+    /// Converts the fetched ticks into a [`TickMap`]
+    #[inline]
+    #[must_use]
+    pub fn into_tick_map(self) -> TickMap<I> {
+        TickMap::new(self.ticks, self.tick_spacing)
+    }
+}
+
+impl<I: TickIndex> From<TickLensDataProvider<I>> for TickListDataProvider<I> {
+    #[inline]
+    fn from(provider: TickLensDataProvider<I>) -> Self {
+        assert!(!provider.ticks.is_empty());
+        Self::new(provider.ticks, provider.tick_spacing)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::tests::*;
+
+    #[tokio::test]
+    async fn test_tick_lens_data_provider() -> Result<(), Error> {
+        let pool = address!("88e6A0c2dDD26FEEb64F039a2c41296FcB3f5640");
+        let word = |tick: i32| tick.compress(10).position().0;
+        let metrics = AtomicTickMetrics::new();
+        let provider = TickLensDataProvider::<i32>::new_metered(
+            pool,
+            PROVIDER.clone(),
+            TickLensOptions {
+                word_lower: Some(word(-100000) as i16),
+                word_upper: Some(word(-80000) as i16),
+                concurrency: 2,
+                ..Default::default()
+            },
+            *BLOCK_ID,
+            &metrics,
+        )
+        .await?;
+        assert_eq!(
+            metrics.snapshot().ticks_fetched,
+            provider.ticks.len() as u64
+        );
+        assert!(metrics.snapshot().rpc_calls > (word(-80000) - word(-100000)) as u64);
+        provider.ticks.validate_list(10);
+        let tick = provider.get_tick(-92110)?;
+        assert_eq!(tick.liquidity_gross, 398290794261);
+        assert_eq!(tick.liquidity_net, 398290794261);
+
+        let ephemeral =
+            EphemeralTickDataProvider::<i32>::new(pool, PROVIDER.clone(), None, None, *BLOCK_ID)
+                .await?;
+        let expected: Vec<_> = ephemeral
+            .ticks
+            .into_iter()
+            .filter(|tick| (word(-100000)..=word(-80000)).contains(&word(tick.index)))
+            .collect();
+        assert_eq!(provider.ticks, expected);
+
+        let tick_map = provider.into_tick_map();
+        assert_eq!(tick_map.get_tick(-92110)?.liquidity_gross, 398290794261);
+        Ok(())
+    }
+}
@@ -31,6 +31,26 @@ impl<I: TickIndex> TickMap<I> {
             tick_spacing,
         }
     }
+
+    /// Same as [`Self::new`] but rejects tick lists exceeding the given limits
+    ///
+    /// ## Arguments
+    ///
+    /// * `ticks`: The sorted list of initialized ticks
+    /// * `tick_spacing`: The tick spacing of the pool
+    /// * `limits`: The limits to enforce
+    #[inline]
+    pub fn new_with_limits(
+        ticks: Vec<Tick<I>>,
+        tick_spacing: I,
+        limits: TickDataProviderLimits,
+    ) -> Result<Self, Error> {
+        limits.check_ticks(ticks.len())?;
+        if let (Some(first), Some(last)) = (ticks.first(), ticks.last()) {
+            limits.check_tick_range(first.index, last.index, tick_spacing)?;
+        }
+        Ok(Self::new(ticks, tick_spacing))
+    }
 }
 
 impl<I: TickIndex> TickDataProvider for TickMap<I> {
@@ -0,0 +1,105 @@
+use crate::prelude::{mul_div_rounding_up, Error, FeeAmount, IUniswapV3PoolActions};
+use alloy_primitives::{aliases::U24, Address, Bytes, U256};
+use alloy_sol_types::SolCall;
+
+/// The denominator of the pool fee, i.e. fees are denominated in hundredths of bips.
+const FEE_DENOMINATOR: U256 = U256::from_limbs([1_000_000, 0, 0, 0]);
+
+#[inline]
+fn fee_pips(fee: FeeAmount) -> U256 {
+    let fee: U24 = fee.into();
+    U256::from(fee)
+}
+
+/// Computes the fee of a flash loan from a pool, rounded up like `UniswapV3Pool.flash`, i.e.
+/// `FullMath.mulDivRoundingUp(amount, fee, 1e6)`
+///
+/// ## Arguments
+///
+/// * `amount`: The amount of a token borrowed
+/// * `fee`: The fee tier of the pool
+#[inline]
+pub fn flash_fee(amount: U256, fee: FeeAmount) -> Result<U256, Error> {
+    mul_div_rounding_up(amount, fee_pips(fee), FEE_DENOMINATOR)
+}
+
+/// Computes the minimum amount to pay back to the pool in the flash callback, i.e. the amount
+/// borrowed plus [`flash_fee`]. Paying back less reverts with `F0` or `F1`.
+///
+/// ## Arguments
+///
+/// * `amount`: The amount of a token borrowed
+/// * `fee`: The fee tier of the pool
+#[inline]
+pub fn flash_repayment(amount: U256, fee: FeeAmount) -> Result<U256, Error> {
+    // `amount * 1e6` is divisible by 1e6, so rounding up the total rounds up the fee alone
+    mul_div_rounding_up(amount, FEE_DENOMINATOR + fee_pips(fee), FEE_DENOMINATOR)
+}
+
+/// Produces the calldata to borrow tokens from a pool with `flash`. The recipient must implement
+/// `IUniswapV3FlashCallback` and pay back [`flash_repayment`] of each amount in the callback.
+///
+/// ## Arguments
+///
+/// * `recipient`: The address receiving the tokens and the callback
+/// * `amount0`: The amount of token0 to borrow
+/// * `amount1`: The amount of token1 to borrow
+/// * `data`: Any data passed through to the callback
+#[inline]
+#[must_use]
+pub fn encode_flash(recipient: Address, amount0: U256, amount1: U256, data: Bytes) -> Bytes {
+    IUniswapV3PoolActions::flashCall {
+        recipient,
+        amount0,
+        amount1,
+        data,
+    }
+    .abi_encode()
+    .into()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use alloy_primitives::{hex, uint};
+
+    #[test]
+    fn test_flash_fee() {
+        // vectors of the `#flash` tests of `UniswapV3Pool.spec.ts` with a fee of 0.3%
+        let cases = [(1000, 3), (2000, 6), (1001, 4), (2001, 7), (0, 0)];
+        for (amount, fee) in cases {
+            assert_eq!(
+                flash_fee(U256::from(amount), FeeAmount::MEDIUM).unwrap(),
+                U256::from(fee)
+            );
+            assert_eq!(
+                flash_repayment(U256::from(amount), FeeAmount::MEDIUM).unwrap(),
+                U256::from(amount + fee)
+            );
+        }
+        assert_eq!(
+            flash_fee(U256::from(1999), FeeAmount::LOWEST).unwrap(),
+            U256::from(1)
+        );
+        assert_eq!(
+            flash_fee(uint!(1000000000000000000_U256), FeeAmount::HIGH).unwrap(),
+            uint!(10000000000000000_U256)
+        );
+        assert!(flash_repayment(U256::MAX, FeeAmount::LOW).is_err());
+    }
+
+    #[test]
+    fn test_encode_flash() {
+        let calldata = encode_flash(
+            Address::ZERO,
+            U256::from(1),
+            U256::from(2),
+            Bytes::from(hex!("abcd")),
+        );
+        assert_eq!(&calldata[..4], IUniswapV3PoolActions::flashCall::SELECTOR);
+        let call = IUniswapV3PoolActions::flashCall::abi_decode(&calldata, true).unwrap();
+        assert_eq!(call.amount0, U256::from(1));
+        assert_eq!(call.amount1, U256::from(2));
+        assert_eq!(call.data, Bytes::from(hex!("abcd")));
+    }
+}
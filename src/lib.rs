@@ -14,8 +14,9 @@
 //! - An [`extensions`](./src/extensions) feature for additional functionalities related to Uniswap
 //!   V3, including:
 //!
-//!     - [`pool`](./src/extensions/pool.rs) module for creating a `Pool` struct from a pool key and
-//!       fetching the liquidity map within a tick range for the specified pool, using RPC client.
+//!     - [`pool`](./src/extensions/pool.rs) module for creating a `Pool` struct from a pool key,
+//!       batching many pools into one `Multicall3` call, and fetching the liquidity map within a
+//!       tick range for the specified pool, using RPC client.
 //!     - [`position`](./src/extensions/position.rs) module for creating a `Position` struct from a
 //!       token id and fetching the state and pool for all positions of the specified owner, using
 //!       RPC client, etc.
@@ -26,8 +27,43 @@
 //!       in a single `eth_call`.
 //!     - [`ephemeral_tick_map_data_provider`](./src/extensions/ephemeral_tick_map_data_provider.rs)
 //!       fetches ticks in a single `eth_call` and creates a `TickMap`
+//!     - [`storage_tick_data_provider`](./src/extensions/storage_tick_data_provider.rs) fetches
+//!       ticks from the storage of the pool with batched `eth_getStorageAt` requests, without any
+//!       lens contract
+//!     - [`tick_lens_data_provider`](./src/extensions/tick_lens_data_provider.rs) fetches the
+//!       populated ticks word by word from the canonical `TickLens` contract
 //!     - [`tick_map`](./src/extensions/tick_map.rs) provides a way to access tick data directly
 //!       from a hashmap, supposedly more efficient than `TickList`
+//!     - [`candles`](./src/extensions/candles.rs) aggregates the `Swap` events of a pool into
+//!       OHLCV candles
+//!     - [`liquidity_diff`](./src/extensions/liquidity_diff.rs) attributes the changes of the
+//!       ticks of a pool between two blocks to the `Mint` and `Burn` events in between
+//!     - [`oracle`](./src/extensions/oracle.rs) keeps a local replica of the observations of a
+//!       pool and serves `observe` and TWAP queries without RPC calls
+//!     - [`pool_tracker`](./src/extensions/pool_tracker.rs) keeps the state of a set of pools up
+//!       to date from their events, streamed with the `pubsub` feature or polled
+//!     - [`position_history`](./src/extensions/position_history.rs) folds the events of the
+//!       position manager into the state of a position
+//!     - [`position_monitor`](./src/extensions/position_monitor.rs) raises out of range, fee
+//!       and liquidity alerts to a pluggable sink, e.g. a JSON-lines file
+//!     - [`receipt`](./src/extensions/receipt.rs) decodes the events of a transaction receipt and
+//!       reconciles the actual swap output against the encoded expectations
+//!     - [`pending_pool`](./src/extensions/pending_pool.rs) overlays the swaps of pending
+//!       transactions on the confirmed state of a pool
+//!     - [`permit_domain`](./src/extensions/permit_domain.rs) verifies the EIP-712 domain of
+//!       the NFT permits against the deployed position manager
+//!     - [`permit_pruning`](./src/extensions/permit_pruning.rs) drops the permits of tokens that
+//!       are already approved at a pinned block
+//!     - [`permit2_allowance`](./src/extensions/permit2_allowance.rs) reads the `Permit2`
+//!       allowances of an owner and plans whether a new signature is needed for a trade
+//!     - [`router_probe`](./src/extensions/router_probe.rs) verifies that a custom router
+//!       implements the functions called before the calldata is returned
+//!     - [`onchain_quoter`](./src/extensions/onchain_quoter.rs) builds trades from the exact
+//!       amounts quoted by `QuoterV2`, without the tick data of the pools
+//!     - [`gas_measurement`](./src/extensions/gas_measurement.rs) measures the gas of generated
+//!       calldata on a node or fork and decodes the revert reason when it fails
+//!     - [`swap_executor`](./src/extensions/swap_executor.rs) quotes, builds and sends swaps with
+//!       approvals, deadline and gas estimation handled
 
 #![cfg_attr(not(any(feature = "std", test)), no_std)]
 #![warn(
@@ -56,32 +92,53 @@
 extern crate alloc;
 
 pub mod abi;
+pub mod approve_and_call;
 pub mod constants;
+pub mod decode;
 pub mod entities;
 pub mod error;
+pub mod flash;
 pub mod multicall;
 pub mod nonfungible_position_manager;
 pub mod payments;
+pub mod permit2;
 pub mod quoter;
 pub mod self_permit;
 pub mod staker;
 pub mod swap_router;
+pub mod universal_router;
 pub mod utils;
 
 #[cfg(feature = "extensions")]
 pub mod extensions;
 
+#[cfg(feature = "bench-utils")]
+pub mod bench_utils;
+
+#[cfg(feature = "schemars")]
+pub mod config;
+
+#[cfg(feature = "compat")]
+pub mod compat;
+
 #[cfg(test)]
 mod tests;
 
 pub mod prelude {
     pub use crate::{
-        abi::*, constants::*, entities::*, error::*, multicall::*, nonfungible_position_manager::*,
-        payments::*, quoter::*, self_permit::*, staker::*, swap_router::*, utils::*,
+        abi::*, approve_and_call::*, constants::*, decode::*, entities::*, error::*, flash::*,
+        multicall::*, nonfungible_position_manager::*, payments::*, permit2::*, quoter::*,
+        self_permit::*, staker::*, swap_router::*, universal_router::*, utils::*,
     };
 
     pub use uniswap_sdk_core as sdk_core;
 
     #[cfg(feature = "extensions")]
     pub use crate::extensions::*;
+
+    #[cfg(feature = "schemars")]
+    pub use crate::config::*;
+
+    #[cfg(feature = "compat")]
+    pub use crate::compat::{ToAlloy, ToEthers};
 }
@@ -1,20 +1,157 @@
 use crate::prelude::*;
 use alloc::vec::Vec;
-use alloy_primitives::Bytes;
+use alloy_primitives::{Address, Bytes, U256};
 use alloy_sol_types::{Error, SolCall};
 
+/// Encodes the calls into the calldata of `multicall(bytes[])`, or returns the call itself if there
+/// is only one
+///
+/// The calls are only borrowed while being encoded into a buffer of the exact encoded size. The
+/// iterator is cloned to walk the calls once for the length and the offsets and once more for the
+/// data, so nothing is collected in between.
+///
+/// ## Arguments
+///
+/// * `data`: The calls to encode, e.g. a `Vec<Bytes>`, a `&[Bytes]` or an iterator over `&Bytes`
 #[inline]
 #[must_use]
-pub fn encode_multicall<B: Into<Bytes>>(data: Vec<B>) -> Bytes {
-    if data.len() == 1 {
-        data.into_iter().next().unwrap().into()
-    } else {
-        IMulticall::multicallCall {
-            data: data.into_iter().map(Into::into).collect(),
+pub fn encode_multicall<I>(data: I) -> Bytes
+where
+    I: IntoIterator,
+    I::IntoIter: Clone,
+    I::Item: AsRef<[u8]>,
+{
+    let data = data.into_iter();
+    let mut out = Vec::with_capacity(encoded_len(data.clone()));
+    encode_calls_into(data, &mut out);
+    out.into()
+}
+
+/// Same as [`encode_multicall`] but appends the calldata to `out` without intermediate
+/// allocations.
+///
+/// ## Arguments
+///
+/// * `data`: The calls to encode
+/// * `out`: The buffer to append the calldata to
+#[inline]
+pub fn encode_multicall_into<B: AsRef<[u8]>>(data: &[B], out: &mut Vec<u8>) {
+    encode_calls_into(data.iter(), out);
+}
+
+/// Encodes the calls by walking the iterator once for the length, once for the offsets and once
+/// for the data
+fn encode_calls_into<I>(data: I, out: &mut Vec<u8>)
+where
+    I: Iterator + Clone,
+    I::Item: AsRef<[u8]>,
+{
+    let mut calls = data.clone();
+    if let (Some(call), None) = (calls.next(), calls.next()) {
+        out.extend_from_slice(call.as_ref());
+        return;
+    }
+    out.reserve(encoded_len(data.clone()));
+    out.extend_from_slice(&IMulticall::multicallCall::SELECTOR);
+    push_word(out, 32);
+    let count = data.clone().count();
+    push_word(out, count);
+    let mut offset = 32 * count;
+    for call in data.clone() {
+        push_word(out, offset);
+        offset += 32 + padded_len(call.as_ref().len());
+    }
+    for call in data {
+        let call = call.as_ref();
+        push_word(out, call.len());
+        out.extend_from_slice(call);
+        out.resize(out.len() + padded_len(call.len()) - call.len(), 0);
+    }
+}
+
+/// A reusable buffer for encoding multicalls, so that encoding many of them in a row, e.g. when
+/// generating orders in bulk, reuses one allocation instead of allocating for every calldata.
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+pub struct MulticallEncoder {
+    buffer: Vec<u8>,
+}
+
+impl MulticallEncoder {
+    #[inline]
+    #[must_use]
+    pub const fn new() -> Self {
+        Self { buffer: Vec::new() }
+    }
+
+    /// Creates an encoder whose buffer holds calldata of the given length without reallocating
+    ///
+    /// ## Arguments
+    ///
+    /// * `capacity`: The capacity of the buffer in bytes
+    #[inline]
+    #[must_use]
+    pub fn with_capacity(capacity: usize) -> Self {
+        Self {
+            buffer: Vec::with_capacity(capacity),
         }
-        .abi_encode()
-        .into()
     }
+
+    /// Encodes the calls like [`encode_multicall`], overwriting the previous calldata
+    ///
+    /// ## Arguments
+    ///
+    /// * `data`: The calls to encode
+    ///
+    /// ## Returns
+    ///
+    /// The calldata, borrowed from the buffer until the next encode
+    #[inline]
+    pub fn encode<B: AsRef<[u8]>>(&mut self, data: &[B]) -> &[u8] {
+        self.buffer.clear();
+        encode_multicall_into(data, &mut self.buffer);
+        &self.buffer
+    }
+
+    /// Same as [`Self::encode`] but copies the calldata out of the buffer
+    ///
+    /// ## Arguments
+    ///
+    /// * `data`: The calls to encode
+    #[inline]
+    pub fn encode_to_bytes<B: AsRef<[u8]>>(&mut self, data: &[B]) -> Bytes {
+        Bytes::copy_from_slice(self.encode(data))
+    }
+}
+
+#[inline]
+fn push_word(out: &mut Vec<u8>, word: usize) {
+    out.extend_from_slice(&U256::from(word).to_be_bytes::<32>());
+}
+
+#[inline]
+const fn padded_len(len: usize) -> usize {
+    len.div_ceil(32) * 32
+}
+
+/// Returns the length of the calldata produced by [`encode_multicall`] for the given calls.
+#[inline]
+#[must_use]
+pub fn multicall_encoded_len<B: AsRef<[u8]>>(data: &[B]) -> usize {
+    encoded_len(data.iter())
+}
+
+fn encoded_len<I>(data: I) -> usize
+where
+    I: Iterator + Clone,
+    I::Item: AsRef<[u8]>,
+{
+    let mut calls = data.clone();
+    if let (Some(call), None) = (calls.next(), calls.next()) {
+        return call.as_ref().len();
+    }
+    data.fold(4 + 64, |len, call| {
+        len + 64 + padded_len(call.as_ref().len())
+    })
 }
 
 #[inline]
@@ -27,6 +164,82 @@ where
         .map(|decoded| decoded.data.into_iter().map(Into::into).collect())
 }
 
+/// A call bundled into a single transaction to `Multicall3`, see [`encode_multicall3`].
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct Multicall3Call {
+    /// The contract called, e.g. the position manager or the swap router.
+    pub target: Address,
+    /// Whether the other calls still go through if this one reverts.
+    pub allow_failure: bool,
+    /// The calldata and the value sent with it.
+    pub parameters: MethodParameters,
+}
+
+impl Multicall3Call {
+    /// Creates a call which reverts the whole transaction if it fails
+    ///
+    /// ## Arguments
+    ///
+    /// * `target`: The contract called
+    /// * `parameters`: The calldata and value, e.g. returned by [`add_call_parameters`]
+    #[inline]
+    #[must_use]
+    pub const fn new(target: Address, parameters: MethodParameters) -> Self {
+        Self {
+            target,
+            allow_failure: false,
+            parameters,
+        }
+    }
+}
+
+/// Bundles calls to independent contracts into one transaction to [`MULTICALL3_ADDRESS`],
+/// encoded with `aggregate3Value` if any call sends ether and `aggregate3` otherwise
+///
+/// Note that the calls are made by `Multicall3`, so the tokens pulled by the targets must be held
+/// by or approved from it rather than the sender of the transaction.
+///
+/// ## Arguments
+///
+/// * `calls`: The calls, in the order they are made
+#[inline]
+#[must_use]
+pub fn encode_multicall3(calls: Vec<Multicall3Call>) -> MethodParameters {
+    let value = calls
+        .iter()
+        .fold(U256::ZERO, |value, call| value + call.parameters.value);
+    let calldata = if value.is_zero() {
+        IMulticall3::aggregate3Call {
+            calls: calls
+                .into_iter()
+                .map(|call| IMulticall3::Call3 {
+                    target: call.target,
+                    allowFailure: call.allow_failure,
+                    callData: call.parameters.calldata,
+                })
+                .collect(),
+        }
+        .abi_encode()
+    } else {
+        IMulticall3::aggregate3ValueCall {
+            calls: calls
+                .into_iter()
+                .map(|call| IMulticall3::Call3Value {
+                    target: call.target,
+                    allowFailure: call.allow_failure,
+                    value: call.parameters.value,
+                    callData: call.parameters.calldata,
+                })
+                .collect(),
+        }
+        .abi_encode()
+    };
+    MethodParameters {
+        calldata: calldata.into(),
+        value,
+    }
+}
+
 pub trait Multicall: Sized {
     fn encode_multicall(self) -> Bytes;
 
@@ -80,6 +293,121 @@ mod tests {
         }
     }
 
+    mod encode_into {
+        use super::*;
+
+        #[test]
+        fn matches_encode_multicall() {
+            let cases: Vec<Vec<Vec<u8>>> = vec![
+                vec![],
+                vec![vec![0x01]],
+                vec![vec![0xaa; 32], vec![0xbb; 33], vec![]],
+            ];
+            for calls in cases {
+                let mut out = vec![0xff];
+                encode_multicall_into(&calls, &mut out);
+                assert_eq!(out[0], 0xff);
+                assert_eq!(out[1..], encode_multicall(calls.clone())[..]);
+                assert_eq!(out.len() - 1, multicall_encoded_len(&calls));
+            }
+        }
+    }
+
+    mod encoder {
+        use super::*;
+
+        #[test]
+        fn accepts_iterators_of_references() {
+            let calls: Vec<Bytes> = vec![hex!("aaaa").into(), hex!("bbbb").into()];
+            assert_eq!(
+                encode_multicall(calls.iter().chain(&calls)),
+                encode_multicall(
+                    calls
+                        .iter()
+                        .cloned()
+                        .chain(calls.clone())
+                        .collect::<Vec<_>>()
+                )
+            );
+            assert_eq!(encode_multicall(&calls[..1]), calls[0]);
+        }
+
+        #[test]
+        fn reuses_the_buffer() {
+            let mut encoder = MulticallEncoder::new();
+            let long = vec![vec![0xaa; 100], vec![0xbb; 33]];
+            let short = vec![vec![0xcc; 4], vec![]];
+            assert_eq!(encoder.encode(&long), &encode_multicall(long.clone())[..]);
+            let capacity = encoder.buffer.capacity();
+            assert_eq!(
+                encoder.encode_to_bytes(&short),
+                encode_multicall(short.clone())
+            );
+            assert_eq!(encoder.buffer.capacity(), capacity);
+            assert_eq!(encoder.encode(&short[..1]), &short[0][..]);
+        }
+    }
+
+    mod multicall3 {
+        use super::*;
+        use alloy_primitives::address;
+
+        #[test]
+        fn encodes_aggregate3_without_value() {
+            let calls = vec![
+                Multicall3Call::new(
+                    address!("0000000000000000000000000000000000000001"),
+                    MethodParameters {
+                        calldata: hex!("aaaa").into(),
+                        value: U256::ZERO,
+                    },
+                ),
+                Multicall3Call {
+                    allow_failure: true,
+                    ..Multicall3Call::new(
+                        address!("0000000000000000000000000000000000000002"),
+                        MethodParameters {
+                            calldata: hex!("bbbb").into(),
+                            value: U256::ZERO,
+                        },
+                    )
+                },
+            ];
+            let MethodParameters { calldata, value } = encode_multicall3(calls);
+            assert_eq!(value, U256::ZERO);
+            let decoded = IMulticall3::aggregate3Call::abi_decode(&calldata, true).unwrap();
+            assert_eq!(decoded.calls.len(), 2);
+            assert!(!decoded.calls[0].allowFailure);
+            assert!(decoded.calls[1].allowFailure);
+            assert_eq!(decoded.calls[1].callData, Bytes::from(hex!("bbbb")));
+        }
+
+        #[test]
+        fn encodes_aggregate3_value_with_value() {
+            let calls = vec![
+                Multicall3Call::new(
+                    address!("0000000000000000000000000000000000000001"),
+                    MethodParameters {
+                        calldata: hex!("aaaa").into(),
+                        value: U256::from(1),
+                    },
+                ),
+                Multicall3Call::new(
+                    address!("0000000000000000000000000000000000000002"),
+                    MethodParameters {
+                        calldata: hex!("bbbb").into(),
+                        value: U256::from(2),
+                    },
+                ),
+            ];
+            let MethodParameters { calldata, value } = encode_multicall3(calls);
+            assert_eq!(value, U256::from(3));
+            let decoded = IMulticall3::aggregate3ValueCall::abi_decode(&calldata, true).unwrap();
+            assert_eq!(decoded.calls[0].value, U256::from(1));
+            assert_eq!(decoded.calls[1].value, U256::from(2));
+        }
+    }
+
     mod decode {
         use super::*;
 
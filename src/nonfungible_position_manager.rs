@@ -1,9 +1,79 @@
 use crate::prelude::{Error, *};
-use alloy_primitives::{Bytes, PrimitiveSignature, B256, U256};
+use alloc::borrow::Cow;
+use alloy_primitives::{Bytes, PrimitiveSignature, B256, U160, U256};
 use alloy_sol_types::{eip712_domain, Eip712Domain, SolCall, SolStruct};
 use num_traits::ToPrimitive;
 use uniswap_sdk_core::prelude::*;
 
+/// The position manager the calls are encoded for, e.g. a fork of `NonfungiblePositionManager`
+/// with a different permit domain or without `sweepToken`.
+#[derive(Clone, Debug, PartialEq, Eq, Hash)]
+#[cfg_attr(
+    feature = "schemars",
+    derive(serde::Serialize, serde::Deserialize, schemars::JsonSchema)
+)]
+pub struct NpmConfig {
+    /// The address of the position manager.
+    #[cfg_attr(feature = "schemars", schemars(with = "String"))]
+    pub address: Address,
+    /// The name of the EIP-712 domain of the NFT permits.
+    pub eip712_name: Cow<'static, str>,
+    /// The version of the EIP-712 domain of the NFT permits.
+    pub eip712_version: Cow<'static, str>,
+    /// Whether the position manager implements `sweepToken`, needed to collect native currency.
+    pub supports_sweep: bool,
+}
+
+impl NpmConfig {
+    /// Returns the config of the canonical `NonfungiblePositionManager` deployed at the address
+    ///
+    /// ## Arguments
+    ///
+    /// * `address`: The address of the position manager
+    #[inline]
+    #[must_use]
+    pub const fn new(address: Address) -> Self {
+        Self {
+            address,
+            eip712_name: Cow::Borrowed("Uniswap V3 Positions NFT-V1"),
+            eip712_version: Cow::Borrowed("1"),
+            supports_sweep: true,
+        }
+    }
+
+    /// Returns the config of the `NonfungiblePositionManager` of the Uniswap Labs deployment on a
+    /// chain, or `None` if the chain is not in [`ChainDeployment::for_chain`]
+    ///
+    /// ## Arguments
+    ///
+    /// * `chain_id`: The chain ID
+    #[inline]
+    #[must_use]
+    pub const fn for_chain(chain_id: u64) -> Option<Self> {
+        match ChainDeployment::for_chain(chain_id) {
+            Some(deployment) => Some(Self::new(deployment.nonfungible_position_manager)),
+            None => None,
+        }
+    }
+
+    /// Returns the EIP-712 domain of the NFT permits of the position manager
+    ///
+    /// ## Arguments
+    ///
+    /// * `chain_id`: The chain ID
+    #[inline]
+    #[must_use]
+    pub fn eip712_domain(&self, chain_id: u64) -> Eip712Domain {
+        Eip712Domain::new(
+            Some(self.eip712_name.clone()),
+            Some(self.eip712_version.clone()),
+            Some(U256::from(chain_id)),
+            Some(self.address),
+            None,
+        )
+    }
+}
+
 #[derive(Debug, Clone, Copy, PartialEq, Eq)]
 pub struct MintSpecificOptions {
     /// The account that should receive the minted NFT.
@@ -130,11 +200,12 @@ pub fn create_call_parameters<TP: TickDataProvider>(pool: &Pool<TP>) -> MethodPa
     }
 }
 
-#[inline]
-pub fn add_call_parameters<TP: TickDataProvider>(
+/// Encodes the calls adding liquidity to a position without the final `refundETH`, returning
+/// them with the amount of ether to send
+fn encode_add<TP: TickDataProvider>(
     position: &mut Position<TP>,
     options: AddLiquidityOptions,
-) -> Result<MethodParameters, Error> {
+) -> Result<(Vec<Bytes>, U256), Error> {
     assert!(position.liquidity > 0, "ZERO_LIQUIDITY");
 
     let mut calldatas: Vec<Bytes> = Vec::with_capacity(5);
@@ -211,7 +282,7 @@ pub fn add_call_parameters<TP: TickDataProvider>(
 
     let mut value = U256::ZERO;
 
-    if let Some(ether) = options.use_native {
+    if let Some(ether) = options.use_native.filter(requires_weth9) {
         let wrapped = ether.wrapped();
         let wrapped_value = if position.pool.token0.equals(wrapped) {
             amount0_desired
@@ -220,27 +291,141 @@ pub fn add_call_parameters<TP: TickDataProvider>(
         } else {
             panic!("NO_WETH");
         };
+        value = wrapped_value;
+    }
+    Ok((calldatas, value))
+}
 
-        // we only need to refund if we're actually sending ETH
-        if wrapped_value > U256::ZERO {
-            calldatas.push(encode_refund_eth());
-        }
+#[inline]
+pub fn add_call_parameters<TP: TickDataProvider>(
+    position: &mut Position<TP>,
+    options: AddLiquidityOptions,
+) -> Result<MethodParameters, Error> {
+    let (mut calldatas, value) = encode_add(position, options)?;
 
-        value = wrapped_value;
+    // we only need to refund if we're actually sending ETH
+    if value > U256::ZERO {
+        calldatas.push(encode_refund_eth());
+    }
+    Ok(MethodParameters {
+        calldata: encode_multicall(&calldatas),
+        value,
+    })
+}
+
+/// Produces the calldata for minting or increasing several positions in a single multicall
+///
+/// The ether sent for the positions using native currency is summed up and refunded once at
+/// the end, so that the leftover of one position can't be refunded before the next is funded.
+///
+/// ## Arguments
+///
+/// * `positions`: The positions to add liquidity to
+/// * `options`: The options of each position, in the same order as `positions`
+#[inline]
+pub fn add_call_parameters_batch<TP: TickDataProvider>(
+    positions: &mut [Position<TP>],
+    options: Vec<AddLiquidityOptions>,
+) -> Result<MethodParameters, Error> {
+    if positions.len() != options.len() {
+        return Err(Error::LengthMismatch {
+            expected: positions.len(),
+            actual: options.len(),
+        });
+    }
+    let mut calldatas: Vec<Bytes> = Vec::with_capacity(positions.len() * 2 + 1);
+    let mut value = U256::ZERO;
+    for (position, options) in positions.iter_mut().zip(options) {
+        let (position_calldatas, position_value) = encode_add(position, options)?;
+        calldatas.extend(position_calldatas);
+        value += position_value;
+    }
+    if value > U256::ZERO {
+        calldatas.push(encode_refund_eth());
     }
     Ok(MethodParameters {
-        calldata: encode_multicall(calldatas),
+        calldata: encode_multicall(&calldatas),
         value,
     })
 }
 
+/// Everything a UI needs to render before adding liquidity, see [`preview_add_liquidity`].
+#[derive(Clone, Debug, PartialEq)]
+pub struct AddLiquidityPreview {
+    /// The liquidity added.
+    pub liquidity: u128,
+    /// The amount of token0 sent to the position manager.
+    pub amount0_desired: U256,
+    /// The amount of token1 sent to the position manager.
+    pub amount1_desired: U256,
+    /// The minimum amount of token0 added given the slippage tolerance.
+    pub amount0_min: U256,
+    /// The minimum amount of token1 added given the slippage tolerance.
+    pub amount1_min: U256,
+    /// Whether the current price is within the range of the position.
+    pub in_range: bool,
+    /// The share of the active liquidity of the pool held by the liquidity added, zero if out of
+    /// range.
+    pub share_of_pool: Percent,
+    /// The price of token0 at the lower tick of the position.
+    pub price_lower: Price<Token, Token>,
+    /// The price of token0 at the upper tick of the position.
+    pub price_upper: Price<Token, Token>,
+    /// The calldata and value of the transaction, as returned by [`add_call_parameters`].
+    pub parameters: MethodParameters,
+}
+
+/// Produces the calldata for adding liquidity along with the amounts, range prices and share of
+/// the pool of the position, so that the transaction can be shown before it is sent
+///
+/// ## Arguments
+///
+/// * `position`: The position to add liquidity to
+/// * `options`: Additional information necessary for generating the calldata
+#[inline]
+pub fn preview_add_liquidity<TP: TickDataProvider>(
+    position: &mut Position<TP>,
+    options: AddLiquidityOptions,
+) -> Result<AddLiquidityPreview, Error> {
+    let MintAmounts {
+        amount0: amount0_desired,
+        amount1: amount1_desired,
+    } = position.mint_amounts_cached()?;
+    let MintAmounts {
+        amount0: amount0_min,
+        amount1: amount1_min,
+    } = position.mint_amounts_with_slippage(&options.slippage_tolerance)?;
+    let tick_current = position.pool.tick_current;
+    let in_range = position.tick_lower <= tick_current && tick_current < position.tick_upper;
+    let share_of_pool = if in_range {
+        Percent::new(
+            position.liquidity,
+            BigInt::from(position.pool.liquidity) + BigInt::from(position.liquidity),
+        )
+    } else {
+        Percent::new(0, 1)
+    };
+    Ok(AddLiquidityPreview {
+        liquidity: position.liquidity,
+        amount0_desired,
+        amount1_desired,
+        amount0_min,
+        amount1_min,
+        in_range,
+        share_of_pool,
+        price_lower: position.token0_price_lower()?,
+        price_upper: position.token0_price_upper()?,
+        parameters: add_call_parameters(position, options)?,
+    })
+}
+
 fn encode_collect<Currency0: BaseCurrency, Currency1: BaseCurrency>(
     options: &CollectOptions<Currency0, Currency1>,
 ) -> Vec<Bytes> {
     let mut calldatas: Vec<Bytes> = Vec::with_capacity(3);
 
-    let involves_eth = options.expected_currency_owed0.currency.is_native()
-        || options.expected_currency_owed1.currency.is_native();
+    let owed0_is_native = requires_weth9(&options.expected_currency_owed0.currency);
+    let involves_eth = owed0_is_native || requires_weth9(&options.expected_currency_owed1.currency);
 
     // collect
     calldatas.push(
@@ -264,7 +449,7 @@ fn encode_collect<Currency0: BaseCurrency, Currency1: BaseCurrency>(
         let eth_amount: U256;
         let token: &Token;
         let token_amount: U256;
-        if options.expected_currency_owed0.currency.is_native() {
+        if owed0_is_native {
             eth_amount = U256::from_big_int(options.expected_currency_owed0.quotient());
             token = options.expected_currency_owed1.currency.wrapped();
             token_amount = U256::from_big_int(options.expected_currency_owed1.quotient());
@@ -292,22 +477,37 @@ pub fn collect_call_parameters<Currency0: BaseCurrency, Currency1: BaseCurrency>
     let calldatas = encode_collect(options);
 
     MethodParameters {
-        calldata: encode_multicall(calldatas),
+        calldata: encode_multicall(&calldatas),
         value: U256::ZERO,
     }
 }
 
-/// Produces the calldata for completely or partially exiting a position
-///
-/// ## Arguments
-///
-/// * `position`: The position to exit
-/// * `options`: Additional information necessary for generating the calldata
-#[inline]
-pub fn remove_call_parameters<Currency0, Currency1, TP>(
+/// Encodes the permit of the position manager to manage a position
+fn encode_nft_permit(token_id: U256, permit: &NFTPermitOptions) -> Bytes {
+    IERC721Permit::permitCall {
+        spender: permit.spender,
+        tokenId: token_id,
+        deadline: permit.deadline,
+        v: permit.signature.v() as u8 + 27,
+        r: permit.signature.r().into(),
+        s: permit.signature.s().into(),
+    }
+    .abi_encode()
+    .into()
+}
+
+/// Encodes a call of `SwapRouter02` forwarding a call to the position manager
+fn encode_call_position_manager(data: Bytes) -> Bytes {
+    IApproveAndCall::callPositionManagerCall { data }
+        .abi_encode()
+        .into()
+}
+
+/// Encodes the calls exiting a position
+fn encode_remove<Currency0, Currency1, TP>(
     position: &Position<TP>,
     options: RemoveLiquidityOptions<Currency0, Currency1>,
-) -> Result<MethodParameters, Error>
+) -> Result<Vec<Bytes>, Error>
 where
     Currency0: BaseCurrency,
     Currency1: BaseCurrency,
@@ -319,40 +519,15 @@ where
     let token_id = options.token_id;
 
     // construct a partial position with a percentage of liquidity
-    let partial_position = Position::new(
-        Pool::new(
-            position.pool.token0.clone(),
-            position.pool.token1.clone(),
-            position.pool.fee,
-            position.pool.sqrt_ratio_x96,
-            position.pool.liquidity,
-        )?,
-        (options.liquidity_percentage.clone() * Percent::new(position.liquidity, 1))
-            .quotient()
-            .to_u128()
-            .unwrap(),
-        position.tick_lower.try_into().unwrap(),
-        position.tick_upper.try_into().unwrap(),
-    );
+    let partial_position = partial_position(position, &options.liquidity_percentage)?;
     assert!(partial_position.liquidity > 0, "ZERO_LIQUIDITY");
 
     // slippage-adjusted underlying amounts
     let (amount0_min, amount1_min) =
         partial_position.burn_amounts_with_slippage(&options.slippage_tolerance)?;
 
-    if let Some(permit) = options.permit {
-        calldatas.push(
-            IERC721Permit::permitCall {
-                spender: permit.spender,
-                tokenId: token_id,
-                deadline: permit.deadline,
-                v: permit.signature.v() as u8 + 27,
-                r: permit.signature.r().into(),
-                s: permit.signature.s().into(),
-            }
-            .abi_encode()
-            .into(),
-        );
+    if let Some(permit) = &options.permit {
+        calldatas.push(encode_nft_permit(token_id, permit));
     };
 
     // remove liquidity
@@ -400,13 +575,418 @@ where
     } else {
         assert!(!options.burn_token, "CANNOT_BURN");
     }
+    Ok(calldatas)
+}
 
+/// Produces the calldata for completely or partially exiting a position
+///
+/// ## Arguments
+///
+/// * `position`: The position to exit
+/// * `options`: Additional information necessary for generating the calldata
+#[inline]
+pub fn remove_call_parameters<Currency0, Currency1, TP>(
+    position: &Position<TP>,
+    options: RemoveLiquidityOptions<Currency0, Currency1>,
+) -> Result<MethodParameters, Error>
+where
+    Currency0: BaseCurrency,
+    Currency1: BaseCurrency,
+    TP: TickDataProvider,
+{
     Ok(MethodParameters {
-        calldata: encode_multicall(calldatas),
+        calldata: encode_multicall(&encode_remove(position, options)?),
+        value: U256::ZERO,
+    })
+}
+
+/// Produces the calldata for completely or partially exiting several positions in a single
+/// multicall
+///
+/// ## Arguments
+///
+/// * `positions`: The positions to exit
+/// * `options`: The options of each position, in the same order as `positions`
+#[inline]
+pub fn remove_call_parameters_batch<Currency0, Currency1, TP>(
+    positions: &[Position<TP>],
+    options: Vec<RemoveLiquidityOptions<Currency0, Currency1>>,
+) -> Result<MethodParameters, Error>
+where
+    Currency0: BaseCurrency,
+    Currency1: BaseCurrency,
+    TP: TickDataProvider,
+{
+    if positions.len() != options.len() {
+        return Err(Error::LengthMismatch {
+            expected: positions.len(),
+            actual: options.len(),
+        });
+    }
+    let mut calldatas: Vec<Bytes> = Vec::with_capacity(positions.len() * 4);
+    for (position, options) in positions.iter().zip(options) {
+        calldatas.extend(encode_remove(position, options)?);
+    }
+    Ok(MethodParameters {
+        calldata: encode_multicall(&calldatas),
+        value: U256::ZERO,
+    })
+}
+
+/// How [`plan_fee_tier_migration`] batches the migration through `SwapRouter02`, which exits the
+/// position on behalf of its owner with `callPositionManager`, swaps the tokens it collected and
+/// mints with the tokens it holds.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+pub struct MigrationRouterOptions {
+    /// The address of `SwapRouter02`, which collects the tokens and must be approved for the
+    /// position, e.g. by the permit of the remove options.
+    pub router: Address,
+    /// The approval of token0 from the router to the position manager.
+    pub token0_approval: ApprovalType,
+    /// The approval of token1 from the router to the position manager.
+    pub token1_approval: ApprovalType,
+}
+
+/// The migration of a position to another fee tier of the same pair, see
+/// [`plan_fee_tier_migration`].
+#[derive(Clone, Debug)]
+pub struct FeeTierMigration<TP: TickDataProvider> {
+    /// The amounts freed by exiting the position and collecting the tokens owed, and the swap
+    /// balancing them for the target pool.
+    pub rebalance: RebalancePlan,
+    /// The position minted in the target pool, priced after the swap.
+    pub position: Position<TP>,
+    /// The fee and price impact of the swap, i.e. the value of the amount in at the price of the
+    /// target pool before the swap less the amount out, in the output token of the swap.
+    pub cost: CurrencyAmount<Token>,
+    /// The calldata exiting the position through the position manager.
+    pub exit_parameters: MethodParameters,
+    /// The calldata minting the new position through the position manager, to be sent after the
+    /// swap if any.
+    pub mint_parameters: MethodParameters,
+    /// The exit, the swap if any and the mint in a single `SwapRouter02` multicall checked against
+    /// the deadline of the add options, see [`MigrationRouterOptions`].
+    pub parameters: MethodParameters,
+}
+
+/// Returns the part of a position exited with a percentage of its liquidity
+fn partial_position<TP: TickDataProvider>(
+    position: &Position<TP>,
+    liquidity_percentage: &Percent,
+) -> Result<Position, Error> {
+    Ok(Position::new(
+        Pool::new(
+            position.pool.token0.clone(),
+            position.pool.token1.clone(),
+            position.pool.fee,
+            position.pool.sqrt_ratio_x96,
+            position.pool.liquidity,
+        )?,
+        (liquidity_percentage.clone() * Percent::new(position.liquidity, 1))
+            .quotient()
+            .to_u128()
+            .ok_or(Error::LiquidityOverflow)?,
+        position.tick_lower.to_i24().as_i32(),
+        position.tick_upper.to_i24().as_i32(),
+    ))
+}
+
+/// Plans the migration of a position to another fee tier of the same pair: the amounts freed by
+/// exiting it and collecting the tokens owed, the swap in the target pool needed since the prices
+/// of the pools differ, the new position over the same range rounded to the tick spacing of the
+/// target pool, and the calldata
+///
+/// The batched calldata is sent to `SwapRouter02`, which exits the position through
+/// `callPositionManager`, collects only the amount to swap first so that the swap spends its
+/// whole balance of the input token, collects the rest, mints the new position with all the
+/// tokens it holds and sweeps the tokens left unused back to the sender. Native currencies are
+/// collected and minted as their wrapped token, and the collect recipient of `remove_options` only
+/// applies to the exit calldata. Fails with [`Error::UnsupportedRouterCall`] if `add_options`
+/// increase an existing position, since the router can only mint.
+///
+/// ## Arguments
+///
+/// * `position`: The position to migrate
+/// * `target_pool`: The pool of the same pair in the target fee tier
+/// * `remove_options`: The options for exiting the position, whose expected currencies owed are
+///   the fees collected with it
+/// * `add_options`: The options for minting the new position, whose slippage tolerance also
+///   bounds the output of the swap
+/// * `router_options`: The router batching the migration
+#[inline]
+pub fn plan_fee_tier_migration<Currency0, Currency1, TP, TP2>(
+    position: &Position<TP>,
+    target_pool: &Pool<TP2>,
+    remove_options: RemoveLiquidityOptions<Currency0, Currency1>,
+    add_options: AddLiquidityOptions,
+    router_options: MigrationRouterOptions,
+) -> Result<FeeTierMigration<TP2>, Error>
+where
+    Currency0: BaseCurrency,
+    Currency1: BaseCurrency,
+    TP: TickDataProvider,
+    TP2: TickDataProvider + Clone,
+{
+    if !position.pool.token0.equals(&target_pool.token0)
+        || !position.pool.token1.equals(&target_pool.token1)
+    {
+        return Err(Error::InvalidToken);
+    }
+    let AddLiquiditySpecificOptions::Mint(MintSpecificOptions { recipient, .. }) =
+        add_options.specific_opts
+    else {
+        return Err(Error::UnsupportedRouterCall(
+            INonfungiblePositionManager::increaseLiquidityCall::SELECTOR.into(),
+        ));
+    };
+    let tick_spacing = target_pool.tick_spacing();
+    let tick_lower = nearest_usable_tick(
+        TP2::Index::from_i24(position.tick_lower.to_i24()),
+        tick_spacing,
+    );
+    let mut tick_upper = nearest_usable_tick(
+        TP2::Index::from_i24(position.tick_upper.to_i24()),
+        tick_spacing,
+    );
+    if tick_upper <= tick_lower {
+        tick_upper = tick_lower + tick_spacing;
+    }
+
+    // the part of the position exited and the fees collected with it
+    let partial_position = partial_position(position, &remove_options.liquidity_percentage)?;
+    let owed0 = big_int_to_u256(
+        &remove_options
+            .collect_options
+            .expected_currency_owed0
+            .quotient(),
+    )?;
+    let owed1 = big_int_to_u256(
+        &remove_options
+            .collect_options
+            .expected_currency_owed1
+            .quotient(),
+    )?;
+    let rebalance = RebalancePlan::for_amounts(
+        target_pool,
+        big_int_to_u256(&partial_position.amount0()?.quotient())? + owed0,
+        big_int_to_u256(&partial_position.amount1()?.quotient())? + owed1,
+        tick_lower,
+        tick_upper,
+    )?;
+
+    let (token_in, token_out) = if rebalance.zero_for_one {
+        (&target_pool.token0, &target_pool.token1)
+    } else {
+        (&target_pool.token1, &target_pool.token0)
+    };
+    let amount_in =
+        CurrencyAmount::from_raw_amount(token_in.clone(), rebalance.amount_in.to_big_int())?;
+    let amount_out =
+        CurrencyAmount::from_raw_amount(token_out.clone(), rebalance.amount_out.to_big_int())?;
+    let cost = target_pool
+        .price_of(token_in)?
+        .quote(&amount_in)?
+        .subtract(&amount_out)?;
+    let mut pool = target_pool.clone();
+    if !rebalance.amount_in.is_zero() {
+        pool.get_output_amount_mut(&amount_in, None)?;
+    }
+    let mut new_position = Position::new(pool, rebalance.liquidity, tick_lower, tick_upper);
+
+    // the batched migration through the router
+    let token_id = remove_options.token_id;
+    let (amount0_min, amount1_min) =
+        partial_position.burn_amounts_with_slippage(&remove_options.slippage_tolerance)?;
+    let mut npm_calldatas: Vec<Bytes> = Vec::with_capacity(5);
+    if let Some(permit) = &remove_options.permit {
+        npm_calldatas.push(encode_nft_permit(token_id, permit));
+    }
+    npm_calldatas.push(
+        INonfungiblePositionManager::decreaseLiquidityCall {
+            params: INonfungiblePositionManager::DecreaseLiquidityParams {
+                tokenId: token_id,
+                liquidity: partial_position.liquidity,
+                amount0Min: amount0_min,
+                amount1Min: amount1_min,
+                deadline: remove_options.deadline,
+            },
+        }
+        .abi_encode()
+        .into(),
+    );
+    let collect = |amount0_max: u128, amount1_max: u128| -> Bytes {
+        INonfungiblePositionManager::collectCall {
+            params: INonfungiblePositionManager::CollectParams {
+                tokenId: token_id,
+                recipient: router_options.router,
+                amount0Max: amount0_max,
+                amount1Max: amount1_max,
+            },
+        }
+        .abi_encode()
+        .into()
+    };
+    let mut swap_calldata = None;
+    if !rebalance.amount_in.is_zero() {
+        // the amount in is at most the amount freed, which is owed by the position in 128 bits
+        let amount_in_max = rebalance.amount_in.saturating_to::<u128>();
+        npm_calldatas.push(if rebalance.zero_for_one {
+            collect(amount_in_max, 0)
+        } else {
+            collect(0, amount_in_max)
+        });
+        let amount_out_minimum = amount_out
+            .multiply(&(Percent::new(1, 1) + add_options.slippage_tolerance.clone()).invert())?
+            .quotient();
+        swap_calldata = Some(
+            IV3SwapRouter::exactInputSingleCall {
+                params: IV3SwapRouter::ExactInputSingleParams {
+                    tokenIn: token_in.address(),
+                    tokenOut: token_out.address(),
+                    fee: target_pool.fee.into(),
+                    recipient: ADDRESS_THIS,
+                    // the balance of the router, i.e. the amount collected
+                    amountIn: U256::ZERO,
+                    amountOutMinimum: big_int_to_u256(&amount_out_minimum)?,
+                    sqrtPriceLimitX96: U160::ZERO,
+                },
+            }
+            .abi_encode()
+            .into(),
+        );
+    }
+    let mut rest_calldatas: Vec<Bytes> = Vec::with_capacity(2);
+    rest_calldatas.push(collect(u128::MAX, u128::MAX));
+    if remove_options.liquidity_percentage == Percent::new(1, 1) {
+        if remove_options.burn_token {
+            rest_calldatas.push(
+                INonfungiblePositionManager::burnCall { tokenId: token_id }
+                    .abi_encode()
+                    .into(),
+            );
+        }
+    } else {
+        assert!(!remove_options.burn_token, "CANNOT_BURN");
+    }
+    let MintAmounts {
+        amount0: mint_amount0_min,
+        amount1: mint_amount1_min,
+    } = new_position.mint_amounts_with_slippage(&add_options.slippage_tolerance)?;
+    let (token0, token1) = (target_pool.token0.address(), target_pool.token1.address());
+    let mut calldatas: Vec<Bytes> = npm_calldatas
+        .into_iter()
+        .map(encode_call_position_manager)
+        .chain(swap_calldata)
+        .chain(rest_calldatas.into_iter().map(encode_call_position_manager))
+        .chain(encode_approve(token0, router_options.token0_approval))
+        .chain(encode_approve(token1, router_options.token1_approval))
+        .collect();
+    calldatas.push(
+        IApproveAndCall::mintCall {
+            params: IApproveAndCall::MintParams {
+                token0,
+                token1,
+                fee: target_pool.fee.into(),
+                tickLower: tick_lower.to_i24(),
+                tickUpper: tick_upper.to_i24(),
+                amount0Min: mint_amount0_min,
+                amount1Min: mint_amount1_min,
+                recipient,
+            },
+        }
+        .abi_encode()
+        .into(),
+    );
+    for token in [token0, token1] {
+        calldatas.push(
+            IPeripheryPaymentsExtended::sweepTokenCall {
+                token,
+                amountMinimum: U256::ZERO,
+            }
+            .abi_encode()
+            .into(),
+        );
+    }
+    let parameters = MethodParameters {
+        calldata: IMulticallExtended::multicallCall {
+            deadline: add_options.deadline,
+            data: calldatas,
+        }
+        .abi_encode()
+        .into(),
         value: U256::ZERO,
+    };
+
+    let exit_parameters = remove_call_parameters(position, remove_options)?;
+    let (add_calldatas, value) = encode_add(&mut new_position, add_options)?;
+    let refund = (value > U256::ZERO).then(encode_refund_eth);
+    let mint_parameters = MethodParameters {
+        calldata: encode_multicall(add_calldatas.iter().chain(&refund)),
+        value,
+    };
+    Ok(FeeTierMigration {
+        rebalance,
+        position: new_position,
+        cost,
+        exit_parameters,
+        mint_parameters,
+        parameters,
     })
 }
 
+/// Same as [`collect_call_parameters`] but for the given position manager, which must support
+/// `sweepToken` if either currency is native
+///
+/// ## Arguments
+///
+/// * `options`: The options for collecting
+/// * `config`: The position manager the calls are encoded for
+#[inline]
+pub fn collect_call_parameters_with_config<Currency0: BaseCurrency, Currency1: BaseCurrency>(
+    options: &CollectOptions<Currency0, Currency1>,
+    config: &NpmConfig,
+) -> Result<MethodParameters, Error> {
+    check_sweep_support(options, config)?;
+    Ok(collect_call_parameters(options))
+}
+
+/// Same as [`remove_call_parameters`] but for the given position manager, which must support
+/// `sweepToken` if either currency is native
+///
+/// ## Arguments
+///
+/// * `position`: The position to exit
+/// * `options`: Additional information necessary for generating the calldata
+/// * `config`: The position manager the calls are encoded for
+#[inline]
+pub fn remove_call_parameters_with_config<Currency0, Currency1, TP>(
+    position: &Position<TP>,
+    options: RemoveLiquidityOptions<Currency0, Currency1>,
+    config: &NpmConfig,
+) -> Result<MethodParameters, Error>
+where
+    Currency0: BaseCurrency,
+    Currency1: BaseCurrency,
+    TP: TickDataProvider,
+{
+    check_sweep_support(&options.collect_options, config)?;
+    remove_call_parameters(position, options)
+}
+
+#[inline]
+fn check_sweep_support<Currency0: BaseCurrency, Currency1: BaseCurrency>(
+    options: &CollectOptions<Currency0, Currency1>,
+    config: &NpmConfig,
+) -> Result<(), Error> {
+    if !config.supports_sweep
+        && (requires_weth9(&options.expected_currency_owed0.currency)
+            || requires_weth9(&options.expected_currency_owed1.currency))
+    {
+        return Err(Error::SweepNotSupported(config.address));
+    }
+    Ok(())
+}
+
 #[inline]
 pub fn safe_transfer_from_parameters(options: SafeTransferOptions) -> MethodParameters {
     let calldata = if options.data.is_empty() {
@@ -493,6 +1073,60 @@ pub const fn get_permit_data(
     }
 }
 
+/// Same as [`get_permit_data`] but with the name and version of the EIP-712 domain overridden,
+/// e.g. for forks of the position manager with a renamed NFT
+///
+/// ## Arguments
+///
+/// * `permit`: The permit values to sign
+/// * `position_manager`: The address of the position manager
+/// * `chain_id`: The chain ID
+/// * `name`: The name of the EIP-712 domain
+/// * `version`: The version of the EIP-712 domain
+#[inline]
+#[must_use]
+pub fn get_permit_data_with_domain(
+    permit: NFTPermitValues,
+    position_manager: Address,
+    chain_id: u64,
+    name: impl Into<Cow<'static, str>>,
+    version: impl Into<Cow<'static, str>>,
+) -> NFTPermitData {
+    NFTPermitData {
+        domain: Eip712Domain::new(
+            Some(name.into()),
+            Some(version.into()),
+            Some(U256::from(chain_id)),
+            Some(position_manager),
+            None,
+        ),
+        values: permit,
+    }
+}
+
+/// Same as [`get_permit_data`] but with the EIP-712 domain of the given position manager
+///
+/// ## Arguments
+///
+/// * `permit`: The permit values to sign
+/// * `config`: The position manager the permit is signed for
+/// * `chain_id`: The chain ID
+#[inline]
+#[must_use]
+pub fn get_permit_data_with_config(
+    permit: NFTPermitValues,
+    config: &NpmConfig,
+    chain_id: u64,
+) -> NFTPermitData {
+    get_permit_data_with_domain(
+        permit,
+        config.address,
+        chain_id,
+        config.eip712_name.clone(),
+        config.eip712_version.clone(),
+    )
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -610,6 +1244,59 @@ mod tests {
         );
     }
 
+    #[test]
+    fn test_preview_add_liquidity() {
+        let tick_spacing = FeeAmount::MEDIUM.tick_spacing().as_i32();
+        let mut position = Position::new(POOL_0_1.clone(), 1, -tick_spacing, tick_spacing);
+        let options = AddLiquidityOptions {
+            slippage_tolerance: SLIPPAGE_TOLERANCE.clone(),
+            deadline: DEADLINE,
+            use_native: None,
+            token0_permit: None,
+            token1_permit: None,
+            specific_opts: AddLiquiditySpecificOptions::Mint(MintSpecificOptions {
+                recipient: RECIPIENT,
+                create_pool: false,
+            }),
+        };
+        let preview = preview_add_liquidity(&mut position, options.clone()).unwrap();
+        assert_eq!(preview.liquidity, 1);
+        assert_eq!(preview.amount0_desired, uint!(1_U256));
+        assert_eq!(preview.amount1_desired, uint!(1_U256));
+        assert_eq!(preview.amount0_min, U256::ZERO);
+        assert_eq!(preview.amount1_min, U256::ZERO);
+        assert!(preview.in_range);
+        assert_eq!(
+            preview.share_of_pool,
+            Percent::new(1, POOL_0_1.liquidity + 1)
+        );
+        assert_eq!(preview.price_lower, position.token0_price_lower().unwrap());
+        assert_eq!(preview.price_upper, position.token0_price_upper().unwrap());
+        assert_eq!(
+            preview.parameters,
+            add_call_parameters(&mut position, options).unwrap()
+        );
+
+        let mut out_of_range = Position::new(POOL_0_1.clone(), 1, tick_spacing, tick_spacing * 2);
+        let preview = preview_add_liquidity(
+            &mut out_of_range,
+            AddLiquidityOptions {
+                slippage_tolerance: SLIPPAGE_TOLERANCE.clone(),
+                deadline: DEADLINE,
+                use_native: None,
+                token0_permit: None,
+                token1_permit: None,
+                specific_opts: AddLiquiditySpecificOptions::Mint(MintSpecificOptions {
+                    recipient: RECIPIENT,
+                    create_pool: false,
+                }),
+            },
+        )
+        .unwrap();
+        assert!(!preview.in_range);
+        assert_eq!(preview.share_of_pool, Percent::new(0, 1));
+    }
+
     #[test]
     fn test_add_call_parameters_increase() {
         let mut position = Position::new(
@@ -699,6 +1386,90 @@ mod tests {
         );
     }
 
+    #[test]
+    fn test_add_call_parameters_use_native_erc20() {
+        let celo = native_currency(42220);
+        let cusd = uniswap_sdk_core::token!(
+            42220,
+            "765DE816845861e75A25fCA122bb6898B8B1282a",
+            18,
+            "cUSD"
+        );
+        let mut position = Position::new(
+            make_pool(celo.wrapped().clone(), cusd),
+            1,
+            -FeeAmount::MEDIUM.tick_spacing().as_i32(),
+            FeeAmount::MEDIUM.tick_spacing().as_i32(),
+        );
+        let MethodParameters { calldata, value } = add_call_parameters(
+            &mut position,
+            AddLiquidityOptions {
+                slippage_tolerance: SLIPPAGE_TOLERANCE.clone(),
+                deadline: DEADLINE,
+                use_native: Some(celo.clone()),
+                token0_permit: None,
+                token1_permit: None,
+                specific_opts: AddLiquiditySpecificOptions::Mint(MintSpecificOptions {
+                    recipient: RECIPIENT,
+                    create_pool: false,
+                }),
+            },
+        )
+        .unwrap();
+        // CELO is pulled as an ERC20 token, so neither value nor a refundETH is sent
+        assert_eq!(value, U256::ZERO);
+        let mint = INonfungiblePositionManager::mintCall::abi_decode(&calldata, true).unwrap();
+        assert!(
+            mint.params.token0 == celo.wrapped().address()
+                || mint.params.token1 == celo.wrapped().address()
+        );
+    }
+
+    #[test]
+    fn test_add_call_parameters_batch() {
+        let tick_spacing = FeeAmount::MEDIUM.tick_spacing().as_i32();
+        let options = |token_id: u64| AddLiquidityOptions {
+            slippage_tolerance: SLIPPAGE_TOLERANCE.clone(),
+            deadline: DEADLINE,
+            use_native: Some(ETHER.clone()),
+            token0_permit: None,
+            token1_permit: None,
+            specific_opts: AddLiquiditySpecificOptions::Increase(IncreaseSpecificOptions {
+                token_id: U256::from(token_id),
+            }),
+        };
+        let mut positions = [
+            Position::new(POOL_1_WETH.clone(), 1, -tick_spacing, tick_spacing),
+            Position::new(POOL_1_WETH.clone(), 1, -tick_spacing, tick_spacing),
+        ];
+        let mut expected_value = U256::ZERO;
+        let mut expected_calldatas = Vec::new();
+        for (i, position) in positions.iter().enumerate() {
+            let mut position = position.clone();
+            let single = add_call_parameters(&mut position, options(i as u64 + 1)).unwrap();
+            let mut calldatas = decode_multicall::<Bytes, _>(&single.calldata).unwrap();
+            // drop the individual refundETH
+            calldatas.pop();
+            expected_calldatas.extend(calldatas);
+            expected_value += single.value;
+        }
+        expected_calldatas.push(encode_refund_eth());
+
+        let MethodParameters { calldata, value } =
+            add_call_parameters_batch(&mut positions, vec![options(1), options(2)]).unwrap();
+        assert_eq!(value, expected_value);
+        assert_eq!(value, uint!(2_U256));
+        assert_eq!(calldata, encode_multicall(expected_calldatas));
+
+        assert!(matches!(
+            add_call_parameters_batch(&mut positions, vec![options(1)]),
+            Err(Error::LengthMismatch {
+                expected: 2,
+                actual: 1
+            })
+        ));
+    }
+
     #[test]
     fn test_collect_call_parameters() {
         let MethodParameters { calldata, value } = collect_call_parameters(&COLLECT_OPTIONS);
@@ -847,6 +1618,247 @@ mod tests {
         );
     }
 
+    #[test]
+    fn test_remove_call_parameters_batch() {
+        let tick_spacing = FeeAmount::MEDIUM.tick_spacing().as_i32();
+        let positions = [
+            Position::new(POOL_0_1.clone(), 100, -tick_spacing, tick_spacing),
+            Position::new(POOL_0_1.clone(), 200, -tick_spacing * 2, tick_spacing * 2),
+        ];
+        let options = |token_id: U256| RemoveLiquidityOptions {
+            token_id,
+            liquidity_percentage: Percent::new(1, 2),
+            slippage_tolerance: SLIPPAGE_TOLERANCE.clone(),
+            deadline: DEADLINE,
+            burn_token: false,
+            permit: None,
+            collect_options: CollectOptions {
+                token_id,
+                ..COLLECT_OPTIONS.clone()
+            },
+        };
+        let token_ids = [uint!(1_U256), uint!(2_U256)];
+        let mut expected_calldatas = Vec::new();
+        for (position, token_id) in positions.iter().zip(token_ids) {
+            let single = remove_call_parameters(position, options(token_id)).unwrap();
+            expected_calldatas.extend(decode_multicall::<Bytes, _>(&single.calldata).unwrap());
+        }
+
+        let MethodParameters { calldata, value } =
+            remove_call_parameters_batch(&positions, token_ids.map(options).to_vec()).unwrap();
+        assert_eq!(value, U256::ZERO);
+        assert_eq!(expected_calldatas.len(), 4);
+        assert_eq!(calldata, encode_multicall(expected_calldatas));
+
+        assert!(matches!(
+            remove_call_parameters_batch(&positions, vec![options(token_ids[0])]),
+            Err(Error::LengthMismatch {
+                expected: 2,
+                actual: 1
+            })
+        ));
+    }
+
+    #[test]
+    fn test_plan_fee_tier_migration() {
+        let tick_spacing = FeeAmount::MEDIUM.tick_spacing().as_i32();
+        let target_pool = |sqrt_ratio_x96: U160| {
+            let tick_spacing = FeeAmount::LOW.tick_spacing();
+            Pool::new_with_tick_data_provider(
+                TOKEN0.clone(),
+                TOKEN1.clone(),
+                FeeAmount::LOW,
+                sqrt_ratio_x96,
+                LIQUIDITY,
+                TickListDataProvider::new(
+                    vec![
+                        Tick::new(
+                            nearest_usable_tick(MIN_TICK, tick_spacing).as_i32(),
+                            LIQUIDITY,
+                            LIQUIDITY as i128,
+                        ),
+                        Tick::new(
+                            nearest_usable_tick(MAX_TICK, tick_spacing).as_i32(),
+                            LIQUIDITY,
+                            -(LIQUIDITY as i128),
+                        ),
+                    ],
+                    tick_spacing.as_i32(),
+                ),
+            )
+            .unwrap()
+        };
+        let remove_options = RemoveLiquidityOptions {
+            token_id: TOKEN_ID,
+            liquidity_percentage: Percent::new(1, 1),
+            slippage_tolerance: SLIPPAGE_TOLERANCE.clone(),
+            deadline: DEADLINE,
+            burn_token: true,
+            permit: None,
+            collect_options: COLLECT_OPTIONS.clone(),
+        };
+        let add_options = AddLiquidityOptions {
+            slippage_tolerance: SLIPPAGE_TOLERANCE.clone(),
+            deadline: DEADLINE,
+            use_native: None,
+            token0_permit: None,
+            token1_permit: None,
+            specific_opts: AddLiquiditySpecificOptions::Mint(MintSpecificOptions {
+                recipient: RECIPIENT,
+                create_pool: false,
+            }),
+        };
+
+        let router_options = MigrationRouterOptions {
+            router: SENDER,
+            token0_approval: ApprovalType::Max,
+            token1_approval: ApprovalType::NotRequired,
+        };
+        let router_calls = |parameters: &MethodParameters| {
+            let decoded =
+                IMulticallExtended::multicallCall::abi_decode(&parameters.calldata, true).unwrap();
+            assert_eq!(decoded.deadline, DEADLINE);
+            assert_eq!(parameters.value, U256::ZERO);
+            decoded.data
+        };
+        let forwarded = |call: &Bytes| {
+            IApproveAndCall::callPositionManagerCall::abi_decode(call, true)
+                .unwrap()
+                .data
+        };
+
+        // below the price of both pools, the position only holds token1 and no swap is needed
+        let position = Position::new(
+            make_pool(TOKEN0.clone(), TOKEN1.clone()),
+            100000,
+            -tick_spacing * 10,
+            -tick_spacing,
+        );
+        let migration = plan_fee_tier_migration(
+            &position,
+            &target_pool(SQRT_RATIO_X96),
+            remove_options.clone(),
+            add_options.clone(),
+            router_options,
+        )
+        .unwrap();
+        assert!(migration.rebalance.amount_in.is_zero());
+        assert_eq!(migration.cost.quotient(), BigInt::ZERO);
+        assert_eq!(migration.position.tick_lower, -tick_spacing * 10);
+        assert_eq!(migration.position.pool.fee, FeeAmount::LOW);
+        assert_eq!(
+            migration.exit_parameters,
+            remove_call_parameters(&position, remove_options.clone()).unwrap()
+        );
+        assert_eq!(
+            migration.mint_parameters,
+            add_call_parameters(&mut migration.position.clone(), add_options.clone()).unwrap()
+        );
+        let calls = router_calls(&migration.parameters);
+        assert_eq!(calls.len(), 7);
+        for call in &calls[..3] {
+            assert_eq!(
+                call[..4],
+                IApproveAndCall::callPositionManagerCall::SELECTOR
+            );
+        }
+        let collect =
+            INonfungiblePositionManager::collectCall::abi_decode(&forwarded(&calls[1]), true)
+                .unwrap()
+                .params;
+        assert_eq!(collect.recipient, SENDER);
+        assert_eq!(collect.amount0Max, u128::MAX);
+        assert_eq!(
+            forwarded(&calls[2])[..4],
+            INonfungiblePositionManager::burnCall::SELECTOR
+        );
+        assert_eq!(calls[3][..4], IApproveAndCall::approveMaxCall::SELECTOR);
+        let mint = IApproveAndCall::mintCall::abi_decode(&calls[4], true)
+            .unwrap()
+            .params;
+        assert_eq!(FeeAmount::from(mint.fee), FeeAmount::LOW);
+        assert_eq!(mint.recipient, RECIPIENT);
+
+        // the target pool must be of the same pair
+        assert!(matches!(
+            plan_fee_tier_migration(
+                &position,
+                &make_pool(TOKEN0.clone(), TOKEN2.clone()),
+                remove_options.clone(),
+                add_options.clone(),
+                router_options,
+            ),
+            Err(Error::InvalidToken)
+        ));
+
+        // the fees owed in token0 are swapped for token1 as well
+        let mut remove_options_with_fees = remove_options.clone();
+        remove_options_with_fees
+            .collect_options
+            .expected_currency_owed0 =
+            CurrencyAmount::from_raw_amount(TOKEN0.clone(), 1000).unwrap();
+        let migration = plan_fee_tier_migration(
+            &position,
+            &target_pool(SQRT_RATIO_X96),
+            remove_options_with_fees,
+            add_options.clone(),
+            router_options,
+        )
+        .unwrap();
+        assert_eq!(migration.rebalance.amount0_freed, U256::from(1000));
+        assert!(migration.rebalance.zero_for_one);
+        assert!(migration.rebalance.amount_in > U256::ZERO);
+
+        // the target pool is priced higher, so part of token0 is swapped for token1 first
+        let position = Position::new(
+            make_pool(TOKEN0.clone(), TOKEN1.clone()),
+            100000,
+            -tick_spacing * 10,
+            tick_spacing * 10,
+        );
+        let migration = plan_fee_tier_migration(
+            &position,
+            &target_pool(encode_sqrt_ratio_x96(101, 100)),
+            remove_options,
+            add_options,
+            router_options,
+        )
+        .unwrap();
+        assert!(migration.rebalance.zero_for_one);
+        assert!(migration.rebalance.amount_in > U256::ZERO);
+        assert!(migration.cost.quotient() >= BigInt::ZERO);
+        assert_eq!(
+            migration.position.pool.sqrt_ratio_x96,
+            migration.rebalance.sqrt_ratio_x96_after
+        );
+        assert_eq!(migration.position.liquidity, migration.rebalance.liquidity);
+        let calls = router_calls(&migration.parameters);
+        assert_eq!(calls.len(), 9);
+        // only the amount swapped is collected before the swap, which spends the whole balance
+        let collect =
+            INonfungiblePositionManager::collectCall::abi_decode(&forwarded(&calls[1]), true)
+                .unwrap()
+                .params;
+        assert_eq!(
+            U256::from(collect.amount0Max),
+            migration.rebalance.amount_in
+        );
+        assert_eq!(collect.amount1Max, 0);
+        let swap = IV3SwapRouter::exactInputSingleCall::abi_decode(&calls[2], true)
+            .unwrap()
+            .params;
+        assert_eq!(swap.tokenIn, TOKEN0.address());
+        assert_eq!(swap.recipient, ADDRESS_THIS);
+        assert!(swap.amountIn.is_zero());
+        assert!(swap.amountOutMinimum > U256::ZERO);
+        assert!(swap.amountOutMinimum <= migration.rebalance.amount_out);
+        let collect =
+            INonfungiblePositionManager::collectCall::abi_decode(&forwarded(&calls[3]), true)
+                .unwrap()
+                .params;
+        assert_eq!(collect.amount1Max, u128::MAX);
+    }
+
     #[test]
     fn test_remove_call_parameters_eth() {
         let MethodParameters { calldata, value } = remove_call_parameters(
@@ -932,4 +1944,65 @@ mod tests {
             hex!("b88d4fde000000000000000000000000000000000000000000000000000000000000000400000000000000000000000000000000000000000000000000000000000000030000000000000000000000000000000000000000000000000000000000000001000000000000000000000000000000000000000000000000000000000000008000000000000000000000000000000000000000000000000000000000000000140000000000000000000000000000000000009004000000000000000000000000")
         );
     }
+
+    #[test]
+    fn test_npm_config_permit_domain() {
+        let permit = NFTPermitValues {
+            spender: RECIPIENT,
+            tokenId: TOKEN_ID,
+            nonce: U256::ZERO,
+            deadline: DEADLINE,
+        };
+        let npm = address!("C36442b4a4522E871399CD717aBDD847Ab11FE88");
+        let mut config = NpmConfig::for_chain(1).unwrap();
+        assert_eq!(config, NpmConfig::new(npm));
+        assert_eq!(
+            NpmConfig::for_chain(8453).unwrap().address,
+            address!("03a520b32C04BF3bEEf7BEb72E919cf822Ed34f1")
+        );
+        assert_eq!(NpmConfig::for_chain(5), None);
+        assert_eq!(
+            get_permit_data_with_config(permit.clone(), &config, 1),
+            get_permit_data(permit.clone(), npm, 1)
+        );
+        config.eip712_name = "Fork Positions NFT-V1".into();
+        config.eip712_version = "2".into();
+        assert_ne!(
+            get_permit_data_with_config(permit.clone(), &config, 1).eip712_signing_hash(),
+            get_permit_data(permit, npm, 1).eip712_signing_hash()
+        );
+    }
+
+    #[test]
+    fn test_get_permit_data_with_domain() {
+        let permit = NFTPermitValues {
+            spender: RECIPIENT,
+            tokenId: TOKEN_ID,
+            nonce: U256::ZERO,
+            deadline: DEADLINE,
+        };
+        let npm = address!("C36442b4a4522E871399CD717aBDD847Ab11FE88");
+        assert_eq!(
+            get_permit_data_with_domain(permit.clone(), npm, 1, "Uniswap V3 Positions NFT-V1", "1"),
+            get_permit_data(permit.clone(), npm, 1)
+        );
+        let data = get_permit_data_with_domain(permit, npm, 1, "Fork Positions NFT-V1", "2");
+        assert_eq!(data.domain.name.as_deref(), Some("Fork Positions NFT-V1"));
+        assert_eq!(data.domain.version.as_deref(), Some("2"));
+    }
+
+    #[test]
+    fn test_collect_call_parameters_with_config() {
+        let mut config = NpmConfig::new(SENDER);
+        assert_eq!(
+            collect_call_parameters_with_config(&COLLECT_OPTIONS2, &config).unwrap(),
+            collect_call_parameters(&COLLECT_OPTIONS2)
+        );
+        config.supports_sweep = false;
+        assert!(collect_call_parameters_with_config(&COLLECT_OPTIONS, &config).is_ok());
+        assert!(matches!(
+            collect_call_parameters_with_config(&COLLECT_OPTIONS2, &config),
+            Err(Error::SweepNotSupported(address)) if address == SENDER
+        ));
+    }
 }
@@ -1,7 +1,13 @@
-use crate::prelude::{FromBig, IPeripheryPaymentsWithFee};
+use crate::prelude::{
+    is_native_erc20, unwrap_multicall, Error, FromBig, IMulticall, IMulticallExtended,
+    IPeripheryPaymentsExtended, IPeripheryPaymentsWithFee, MethodParameters, ToBig,
+};
+use alloc::vec::Vec;
 use alloy_primitives::{Address, Bytes, U256};
-use alloy_sol_types::SolCall;
-use uniswap_sdk_core::prelude::{FractionBase, Percent};
+use alloy_sol_types::{SolCall, SolInterface};
+use uniswap_sdk_core::prelude::{BaseCurrency, FractionBase, Percent};
+use IPeripheryPaymentsExtended::IPeripheryPaymentsExtendedCalls;
+use IPeripheryPaymentsWithFee::IPeripheryPaymentsWithFeeCalls;
 
 #[derive(Debug, Clone, PartialEq, Eq)]
 pub struct FeeOptions {
@@ -12,10 +18,47 @@ pub struct FeeOptions {
 }
 
 #[inline]
-fn encode_fee_bips(fee: Percent) -> U256 {
+pub(crate) fn encode_fee_bips(fee: Percent) -> U256 {
     U256::from_big_int((fee * Percent::new(10000, 1)).quotient())
 }
 
+/// The maximum fee in basis points accepted by `PeripheryPaymentsWithFee`.
+pub const MAX_FEE_BIPS: u64 = 100;
+
+/// Validates that a fee can be taken by the periphery contracts, i.e. that it is sent to a nonzero
+/// address and is between 1 and [`MAX_FEE_BIPS`] basis points
+///
+/// ## Arguments
+///
+/// * `fee_options`: The fee to validate
+#[inline]
+pub fn validate_fee_options(fee_options: &FeeOptions) -> Result<(), Error> {
+    if fee_options.recipient == Address::ZERO {
+        return Err(Error::InvalidFeeRecipient);
+    }
+    let fee_bips = encode_fee_bips(fee_options.fee.clone());
+    if fee_bips.is_zero() || fee_bips > U256::from(MAX_FEE_BIPS) {
+        return Err(Error::InvalidFeeBips(fee_bips));
+    }
+    Ok(())
+}
+
+/// Returns whether a currency is the native currency of a chain where it has to be wrapped into,
+/// or unwrapped from, WETH9 by the periphery contracts, see [`is_native_erc20`].
+///
+/// The native currency of a chain where it is an ERC20 token must be created with
+/// [`native_currency`](crate::prelude::native_currency), as the encoders still wrap it to look up
+/// the token it is paid in.
+///
+/// ## Arguments
+///
+/// * `currency`: The currency to check
+#[inline]
+#[must_use]
+pub fn requires_weth9(currency: &impl BaseCurrency) -> bool {
+    currency.is_native() && !is_native_erc20(currency.chain_id())
+}
+
 #[inline]
 #[must_use]
 pub fn encode_unwrap_weth9(
@@ -77,11 +120,293 @@ pub fn encode_refund_eth() -> Bytes {
         .into()
 }
 
+/// Encodes `pull`, which transfers `value` of `token` from the sender to the router, e.g. with an
+/// allowance granted through a permit
+///
+/// ## Arguments
+///
+/// * `token`: The token to pull
+/// * `value`: The amount to pull
+#[inline]
+#[must_use]
+pub fn encode_pull(token: Address, value: U256) -> Bytes {
+    IPeripheryPaymentsExtended::pullCall { token, value }
+        .abi_encode()
+        .into()
+}
+
+/// Encodes `wrapETH`, which wraps `value` of the ether held by the router into WETH9, e.g. to
+/// wrap only part of the value sent
+///
+/// ## Arguments
+///
+/// * `value`: The amount of ether to wrap
+#[inline]
+#[must_use]
+pub fn encode_wrap_eth(value: U256) -> Bytes {
+    IPeripheryPaymentsExtended::wrapETHCall { value }
+        .abi_encode()
+        .into()
+}
+
+/// Same as [`encode_unwrap_weth9`] but sends the ether to the sender, which saves the recipient
+/// from the calldata
+///
+/// ## Arguments
+///
+/// * `amount_minimum`: The minimum amount of WETH9 to unwrap
+/// * `fee_options`: Optional fee taken on the unwrapped amount
+#[inline]
+#[must_use]
+pub fn encode_unwrap_weth9_to_sender(
+    amount_minimum: U256,
+    fee_options: Option<FeeOptions>,
+) -> Bytes {
+    if let Some(fee_options) = fee_options {
+        IPeripheryPaymentsExtended::unwrapWETH9WithFeeCall {
+            amountMinimum: amount_minimum,
+            feeBips: encode_fee_bips(fee_options.fee),
+            feeRecipient: fee_options.recipient,
+        }
+        .abi_encode()
+    } else {
+        IPeripheryPaymentsExtended::unwrapWETH9Call {
+            amountMinimum: amount_minimum,
+        }
+        .abi_encode()
+    }
+    .into()
+}
+
+/// Same as [`encode_sweep_token`] but sends the tokens to the sender
+///
+/// ## Arguments
+///
+/// * `token`: The token to sweep
+/// * `amount_minimum`: The minimum amount of the token to sweep
+/// * `fee_options`: Optional fee taken on the swept amount
+#[inline]
+#[must_use]
+pub fn encode_sweep_token_to_sender(
+    token: Address,
+    amount_minimum: U256,
+    fee_options: Option<FeeOptions>,
+) -> Bytes {
+    if let Some(fee_options) = fee_options {
+        IPeripheryPaymentsExtended::sweepTokenWithFeeCall {
+            token,
+            amountMinimum: amount_minimum,
+            feeBips: encode_fee_bips(fee_options.fee),
+            feeRecipient: fee_options.recipient,
+        }
+        .abi_encode()
+    } else {
+        IPeripheryPaymentsExtended::sweepTokenCall {
+            token,
+            amountMinimum: amount_minimum,
+        }
+        .abi_encode()
+    }
+    .into()
+}
+
+/// A payment function of the periphery contracts, to compose custom flows around the calls
+/// encoded by the SDK, see [`with_payments`].
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub enum PaymentCall {
+    /// Transfers `value` of `token` from the sender to the router.
+    Pull { token: Address, value: U256 },
+    /// Wraps `value` of the ether held by the router. The value is added to the `msg.value` by
+    /// [`with_payments`].
+    WrapEth { value: U256 },
+    /// Unwraps the WETH9 held by the router to the recipient, or to the sender if `None`.
+    UnwrapWeth9 {
+        amount_minimum: U256,
+        recipient: Option<Address>,
+        fee_options: Option<FeeOptions>,
+    },
+    /// Sweeps the `token` held by the router to the recipient, or to the sender if `None`.
+    SweepToken {
+        token: Address,
+        amount_minimum: U256,
+        recipient: Option<Address>,
+        fee_options: Option<FeeOptions>,
+    },
+    /// Refunds the ether held by the router to the sender.
+    RefundEth,
+}
+
+impl PaymentCall {
+    /// Encodes the call
+    #[inline]
+    #[must_use]
+    pub fn encode(&self) -> Bytes {
+        match self {
+            Self::Pull { token, value } => encode_pull(*token, *value),
+            Self::WrapEth { value } => encode_wrap_eth(*value),
+            Self::UnwrapWeth9 {
+                amount_minimum,
+                recipient: Some(recipient),
+                fee_options,
+            } => encode_unwrap_weth9(*amount_minimum, *recipient, fee_options.clone()),
+            Self::UnwrapWeth9 {
+                amount_minimum,
+                recipient: None,
+                fee_options,
+            } => encode_unwrap_weth9_to_sender(*amount_minimum, fee_options.clone()),
+            Self::SweepToken {
+                token,
+                amount_minimum,
+                recipient: Some(recipient),
+                fee_options,
+            } => encode_sweep_token(*token, *amount_minimum, *recipient, fee_options.clone()),
+            Self::SweepToken {
+                token,
+                amount_minimum,
+                recipient: None,
+                fee_options,
+            } => encode_sweep_token_to_sender(*token, *amount_minimum, fee_options.clone()),
+            Self::RefundEth => encode_refund_eth(),
+        }
+    }
+
+    /// Decodes a payment call, the inverse of [`Self::encode`]
+    ///
+    /// ## Arguments
+    ///
+    /// * `calldata`: The calldata of the call
+    ///
+    /// ## Returns
+    ///
+    /// `None` if the calldata is not a payment call
+    #[inline]
+    #[must_use]
+    pub fn decode(calldata: &[u8]) -> Option<Self> {
+        let fee_options = |fee_bips: U256, recipient: Address| {
+            Some(FeeOptions {
+                fee: Percent::new(fee_bips.to_big_int(), 10000),
+                recipient,
+            })
+        };
+        if let Ok(call) = IPeripheryPaymentsWithFeeCalls::abi_decode(calldata, true) {
+            return Some(match call {
+                IPeripheryPaymentsWithFeeCalls::unwrapWETH9(call) => Self::UnwrapWeth9 {
+                    amount_minimum: call.amountMinimum,
+                    recipient: Some(call.recipient),
+                    fee_options: None,
+                },
+                IPeripheryPaymentsWithFeeCalls::unwrapWETH9WithFee(call) => Self::UnwrapWeth9 {
+                    amount_minimum: call.amountMinimum,
+                    recipient: Some(call.recipient),
+                    fee_options: fee_options(call.feeBips, call.feeRecipient),
+                },
+                IPeripheryPaymentsWithFeeCalls::sweepToken(call) => Self::SweepToken {
+                    token: call.token,
+                    amount_minimum: call.amountMinimum,
+                    recipient: Some(call.recipient),
+                    fee_options: None,
+                },
+                IPeripheryPaymentsWithFeeCalls::sweepTokenWithFee(call) => Self::SweepToken {
+                    token: call.token,
+                    amount_minimum: call.amountMinimum,
+                    recipient: Some(call.recipient),
+                    fee_options: fee_options(call.feeBips, call.feeRecipient),
+                },
+                IPeripheryPaymentsWithFeeCalls::refundETH(_) => Self::RefundEth,
+            });
+        }
+        Some(
+            match IPeripheryPaymentsExtendedCalls::abi_decode(calldata, true).ok()? {
+                IPeripheryPaymentsExtendedCalls::pull(call) => Self::Pull {
+                    token: call.token,
+                    value: call.value,
+                },
+                IPeripheryPaymentsExtendedCalls::wrapETH(call) => {
+                    Self::WrapEth { value: call.value }
+                }
+                IPeripheryPaymentsExtendedCalls::unwrapWETH9(call) => Self::UnwrapWeth9 {
+                    amount_minimum: call.amountMinimum,
+                    recipient: None,
+                    fee_options: None,
+                },
+                IPeripheryPaymentsExtendedCalls::unwrapWETH9WithFee(call) => Self::UnwrapWeth9 {
+                    amount_minimum: call.amountMinimum,
+                    recipient: None,
+                    fee_options: fee_options(call.feeBips, call.feeRecipient),
+                },
+                IPeripheryPaymentsExtendedCalls::sweepToken(call) => Self::SweepToken {
+                    token: call.token,
+                    amount_minimum: call.amountMinimum,
+                    recipient: None,
+                    fee_options: None,
+                },
+                IPeripheryPaymentsExtendedCalls::sweepTokenWithFee(call) => Self::SweepToken {
+                    token: call.token,
+                    amount_minimum: call.amountMinimum,
+                    recipient: None,
+                    fee_options: fee_options(call.feeBips, call.feeRecipient),
+                },
+            },
+        )
+    }
+}
+
+impl From<PaymentCall> for Bytes {
+    #[inline]
+    fn from(call: PaymentCall) -> Self {
+        call.encode()
+    }
+}
+
+/// Inserts payment calls before and after the calls of router calldata, e.g. returned by
+/// [`swap_call_parameters`](crate::swap_router::swap_call_parameters), and re-encodes them in a
+/// single `multicall`, keeping the deadline if any
+///
+/// ## Arguments
+///
+/// * `parameters`: The calldata and value of the router calls
+/// * `before`: The payments executed before the router calls
+/// * `after`: The payments executed after the router calls
+#[inline]
+#[must_use]
+pub fn with_payments(
+    parameters: &MethodParameters,
+    before: &[PaymentCall],
+    after: &[PaymentCall],
+) -> MethodParameters {
+    let (deadline, calls) = unwrap_multicall(&parameters.calldata);
+    let mut data: Vec<Bytes> = Vec::with_capacity(before.len() + calls.len() + after.len());
+    data.extend(before.iter().map(PaymentCall::encode));
+    data.extend(calls);
+    data.extend(after.iter().map(PaymentCall::encode));
+    let wrapped: U256 = before
+        .iter()
+        .chain(after)
+        .map(|call| match call {
+            PaymentCall::WrapEth { value } => *value,
+            _ => U256::ZERO,
+        })
+        .sum();
+    let calldata = match deadline {
+        Some(deadline) => IMulticallExtended::multicallCall { deadline, data }.abi_encode(),
+        None => IMulticall::multicallCall { data }.abi_encode(),
+    };
+    MethodParameters {
+        calldata: calldata.into(),
+        value: parameters.value + wrapped,
+    }
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
+    use crate::prelude::native_currency;
     use alloy_primitives::{address, hex, uint};
     use once_cell::sync::Lazy;
+    use uniswap_sdk_core::{
+        prelude::{BaseCurrencyCore, Ether, Token, WETH9},
+        token,
+    };
 
     const RECIPIENT: Address = address!("0000000000000000000000000000000000000003");
     const AMOUNT: U256 = uint!(123_U256);
@@ -132,4 +457,193 @@ mod tests {
         let calldata = encode_refund_eth();
         assert_eq!(calldata.to_vec(), hex!("12210e8a"));
     }
+
+    #[test]
+    fn test_encode_pull() {
+        let calldata = encode_pull(TOKEN, AMOUNT);
+        assert_eq!(
+            calldata.to_vec(),
+            hex!("f2d5d56b0000000000000000000000000000000000000000000000000000000000000001000000000000000000000000000000000000000000000000000000000000007b")
+        );
+    }
+
+    #[test]
+    fn test_encode_wrap_eth() {
+        let calldata = encode_wrap_eth(AMOUNT);
+        assert_eq!(
+            calldata.to_vec(),
+            hex!("1c58db4f000000000000000000000000000000000000000000000000000000000000007b")
+        );
+    }
+
+    #[test]
+    fn test_encode_unwrap_weth9_to_sender() {
+        let calldata = encode_unwrap_weth9_to_sender(AMOUNT, None);
+        assert_eq!(
+            calldata.to_vec(),
+            hex!("49616997000000000000000000000000000000000000000000000000000000000000007b")
+        );
+        let calldata = encode_unwrap_weth9_to_sender(AMOUNT, Some(FEE_OPTIONS.clone()));
+        assert_eq!(
+            calldata.to_vec(),
+            hex!("d4ef38de000000000000000000000000000000000000000000000000000000000000007b000000000000000000000000000000000000000000000000000000000000000a0000000000000000000000000000000000000000000000000000000000000009")
+        );
+    }
+
+    #[test]
+    fn test_encode_sweep_token_to_sender() {
+        let calldata = encode_sweep_token_to_sender(TOKEN, AMOUNT, None);
+        assert_eq!(
+            calldata.to_vec(),
+            hex!("e90a182f0000000000000000000000000000000000000000000000000000000000000001000000000000000000000000000000000000000000000000000000000000007b")
+        );
+        let calldata = encode_sweep_token_to_sender(TOKEN, AMOUNT, Some(FEE_OPTIONS.clone()));
+        assert_eq!(
+            calldata.to_vec(),
+            hex!("3068c5540000000000000000000000000000000000000000000000000000000000000001000000000000000000000000000000000000000000000000000000000000007b000000000000000000000000000000000000000000000000000000000000000a0000000000000000000000000000000000000000000000000000000000000009")
+        );
+    }
+
+    #[test]
+    fn test_with_payments() {
+        let swap = MethodParameters {
+            calldata: encode_refund_eth(),
+            value: AMOUNT,
+        };
+        let MethodParameters { calldata, value } = with_payments(
+            &swap,
+            &[PaymentCall::WrapEth { value: AMOUNT }],
+            &[PaymentCall::SweepToken {
+                token: TOKEN,
+                amount_minimum: U256::ZERO,
+                recipient: None,
+                fee_options: None,
+            }],
+        );
+        assert_eq!(value, AMOUNT * U256::from(2));
+        let calls = IMulticall::multicallCall::abi_decode(&calldata, true)
+            .unwrap()
+            .data;
+        assert_eq!(
+            calls,
+            [
+                encode_wrap_eth(AMOUNT),
+                encode_refund_eth(),
+                encode_sweep_token_to_sender(TOKEN, U256::ZERO, None)
+            ]
+        );
+
+        // the deadline of an extended multicall is kept
+        let swap = MethodParameters {
+            calldata: IMulticallExtended::multicallCall {
+                deadline: AMOUNT,
+                data: alloc::vec![encode_refund_eth(), encode_refund_eth()],
+            }
+            .abi_encode()
+            .into(),
+            value: U256::ZERO,
+        };
+        let MethodParameters { calldata, .. } = with_payments(
+            &swap,
+            &[PaymentCall::Pull {
+                token: TOKEN,
+                value: AMOUNT,
+            }],
+            &[],
+        );
+        let call = IMulticallExtended::multicallCall::abi_decode(&calldata, true).unwrap();
+        assert_eq!(call.deadline, AMOUNT);
+        assert_eq!(call.data.len(), 3);
+        assert_eq!(call.data[0], encode_pull(TOKEN, AMOUNT));
+    }
+
+    #[test]
+    fn test_validate_fee_options() {
+        assert!(validate_fee_options(&FEE_OPTIONS).is_ok());
+        assert!(matches!(
+            validate_fee_options(&FeeOptions {
+                recipient: Address::ZERO,
+                ..FEE_OPTIONS.clone()
+            }),
+            Err(Error::InvalidFeeRecipient)
+        ));
+        for (fee, bips) in [(Percent::new(0, 1), 0), (Percent::new(2, 100), 200)] {
+            assert!(matches!(
+                validate_fee_options(&FeeOptions {
+                    fee,
+                    ..FEE_OPTIONS.clone()
+                }),
+                Err(Error::InvalidFeeBips(b)) if b == U256::from(bips)
+            ));
+        }
+    }
+
+    #[test]
+    fn test_requires_weth9() {
+        assert!(requires_weth9(&Ether::on_chain(1)));
+        // MATIC is wrapped into WMATIC on Polygon
+        let matic = native_currency(137);
+        assert!(requires_weth9(&matic));
+        assert_eq!(matic.wrapped(), &WETH9::on_chain(137).unwrap());
+        assert_eq!(matic.wrapped().symbol.as_deref(), Some("WMATIC"));
+        // CELO is an ERC20 token on Celo
+        for chain_id in [42220, 44787] {
+            let celo = native_currency(chain_id);
+            assert!(!requires_weth9(&celo));
+            assert!(celo.is_native());
+            assert_eq!(celo.wrapped().chain_id(), chain_id);
+            assert_eq!(celo.wrapped().symbol.as_deref(), Some("CELO"));
+        }
+        assert_eq!(
+            native_currency(42220).wrapped().address(),
+            address!("471EcE3750Da237f93B8E339c536989b8978a438")
+        );
+        assert!(!requires_weth9(&token!(
+            1,
+            "0000000000000000000000000000000000000001",
+            18
+        )));
+    }
+
+    #[test]
+    fn test_decode_payment_calls() {
+        let fee_options = Some(FeeOptions {
+            fee: Percent::new(10, 10000),
+            ..FEE_OPTIONS.clone()
+        });
+        let calls = [
+            PaymentCall::Pull {
+                token: TOKEN,
+                value: AMOUNT,
+            },
+            PaymentCall::WrapEth { value: AMOUNT },
+            PaymentCall::UnwrapWeth9 {
+                amount_minimum: AMOUNT,
+                recipient: Some(RECIPIENT),
+                fee_options: fee_options.clone(),
+            },
+            PaymentCall::UnwrapWeth9 {
+                amount_minimum: AMOUNT,
+                recipient: None,
+                fee_options: None,
+            },
+            PaymentCall::SweepToken {
+                token: TOKEN,
+                amount_minimum: AMOUNT,
+                recipient: Some(RECIPIENT),
+                fee_options: None,
+            },
+            PaymentCall::SweepToken {
+                token: TOKEN,
+                amount_minimum: AMOUNT,
+                recipient: None,
+                fee_options,
+            },
+            PaymentCall::RefundEth,
+        ];
+        for call in calls {
+            assert_eq!(PaymentCall::decode(&call.encode()), Some(call));
+        }
+        assert_eq!(PaymentCall::decode(&hex!("deadbeef")), None);
+    }
 }
@@ -0,0 +1,252 @@
+//! ## Permit2
+//! Signature based allowances through the canonical `Permit2` contract, which the Universal Router
+//! spends from instead of the ERC20 allowances used by `SwapRouter02`.
+
+use crate::prelude::*;
+use alloy_primitives::{address, aliases::U48, Bytes, PrimitiveSignature, U160, U256};
+use alloy_sol_types::{eip712_domain, SolCall, SolStruct};
+use uniswap_sdk_core::prelude::*;
+
+/// The address of the `Permit2` contract, the same on every chain.
+pub const PERMIT2_ADDRESS: Address = address!("000000000022D473030F116dDEE9F6B43aC78BA3");
+
+pub type Permit2Single = IAllowanceTransfer::PermitSingle;
+pub type Permit2Batch = IAllowanceTransfer::PermitBatch;
+
+/// A signed [`Permit2Single`] of the input token of a swap.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Permit2Permit {
+    pub permit: Permit2Single,
+    pub signature: PrimitiveSignature,
+}
+
+/// A signed [`Permit2Batch`] including the input token of a swap, e.g. to also permit the tokens
+/// spent by other commands of the same transaction.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Permit2BatchPermit {
+    pub permit: Permit2Batch,
+    pub signature: PrimitiveSignature,
+}
+
+/// A signed `Permit2` permit for spending the input of a swap, see
+/// [`SwapOptions::input_token_permit2`].
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum Permit2Options {
+    Single(Permit2Permit),
+    Batch(Permit2BatchPermit),
+}
+
+/// Get the EIP-712 domain and values to sign for a `Permit2` permit, either a [`Permit2Single`]
+/// or a [`Permit2Batch`].
+///
+/// ## Arguments
+///
+/// * `permit`: The permit values to sign
+/// * `chain_id`: The chain ID
+#[inline]
+#[must_use]
+pub const fn get_permit2_data<P: SolStruct>(permit: P, chain_id: u64) -> ERC20PermitData<P> {
+    let domain = eip712_domain! {
+        name: "Permit2",
+        chain_id: chain_id,
+        verifying_contract: PERMIT2_ADDRESS,
+    };
+    ERC20PermitData {
+        domain,
+        values: permit,
+    }
+}
+
+/// Encodes a signature in the 65 bytes `r || s || v` form expected by `Permit2`
+///
+/// ## Arguments
+///
+/// * `signature`: The signature of the permit
+#[inline]
+#[must_use]
+pub fn encode_permit2_signature(signature: &PrimitiveSignature) -> Bytes {
+    signature.as_bytes().into()
+}
+
+/// Encodes the call to `Permit2` that spends a signed permit directly, e.g. to set an allowance
+/// ahead of a router that does not forward permits
+///
+/// ## Arguments
+///
+/// * `owner`: The owner of the tokens who signed the permit
+/// * `permit`: The signed permit
+#[inline]
+#[must_use]
+pub fn encode_permit2_permit(owner: Address, permit: &Permit2Permit) -> Bytes {
+    IAllowanceTransfer::permit_0Call {
+        owner,
+        permitSingle: permit.permit.clone(),
+        signature: encode_permit2_signature(&permit.signature),
+    }
+    .abi_encode()
+    .into()
+}
+
+/// The allowance of a spender over the tokens of an owner in `Permit2`.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, Hash)]
+pub struct Permit2Allowance {
+    /// The amount the spender is allowed to transfer.
+    pub amount: U160,
+    /// The timestamp until which the allowance is valid, inclusive.
+    pub expiration: u64,
+    /// The nonce of the next permit of the owner for the token and spender.
+    pub nonce: u64,
+}
+
+impl Permit2Allowance {
+    /// Whether the allowance covers the amount until the given timestamp
+    ///
+    /// ## Arguments
+    ///
+    /// * `amount`: The amount to be transferred
+    /// * `valid_until`: The latest timestamp the transfer may happen at, e.g. the deadline of the
+    ///   swap
+    #[inline]
+    #[must_use]
+    pub fn covers(&self, amount: U256, valid_until: u64) -> bool {
+        U256::from(self.amount) >= amount && self.expiration >= valid_until
+    }
+}
+
+/// The terms of the permits signed by [`plan_permit2`].
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, Hash)]
+pub struct Permit2PlanOptions {
+    /// The amount to permit, raised to the amount needed if lower, or the maximum if `None`.
+    pub amount: Option<U160>,
+    /// The timestamp until which the permitted allowance is valid.
+    pub expiration: u64,
+    /// The timestamp until which the signature can be spent.
+    pub sig_deadline: U256,
+}
+
+/// What the owner must do before a spender can transfer an amount through `Permit2`.
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+pub struct Permit2Plan {
+    /// Whether the ERC20 allowance of the owner to `Permit2` must be raised first.
+    pub approve_permit2: bool,
+    /// The permit to sign, or `None` if the current allowance already covers the amount.
+    pub permit: Option<Permit2Single>,
+}
+
+/// Decides whether a new `Permit2` signature is needed to transfer an amount, so that flows only
+/// request one when the current allowance is too low or expires too early
+///
+/// ## Arguments
+///
+/// * `token`: The token to transfer
+/// * `spender`: The spender of the allowance, e.g. the Universal Router
+/// * `amount`: The amount to transfer, e.g. the maximum amount in of a trade
+/// * `valid_until`: The latest timestamp the transfer may happen at, e.g. the deadline of the swap
+/// * `erc20_allowance`: The ERC20 allowance of the owner to `Permit2`
+/// * `allowance`: The current `Permit2` allowance of the owner to the spender
+/// * `options`: The terms of the new permit if one is needed
+#[inline]
+#[must_use]
+pub fn plan_permit2(
+    token: Address,
+    spender: Address,
+    amount: U256,
+    valid_until: u64,
+    erc20_allowance: U256,
+    allowance: &Permit2Allowance,
+    options: &Permit2PlanOptions,
+) -> Permit2Plan {
+    let permit = (!allowance.covers(amount, valid_until)).then(|| Permit2Single {
+        details: IAllowanceTransfer::PermitDetails {
+            token,
+            amount: options.amount.map_or(U160::MAX, |permitted| {
+                permitted.max(U160::saturating_from(amount))
+            }),
+            expiration: U48::saturating_from(options.expiration.max(valid_until)),
+            nonce: U48::saturating_from(allowance.nonce),
+        },
+        spender,
+        sigDeadline: options.sig_deadline,
+    });
+    Permit2Plan {
+        approve_permit2: erc20_allowance < amount,
+        permit,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use alloy_primitives::b256;
+
+    #[test]
+    fn test_permit_single_type_hash() {
+        let permit = Permit2Single {
+            details: IAllowanceTransfer::PermitDetails {
+                token: address!("0000000000000000000000000000000000000001"),
+                amount: U160::from(1),
+                expiration: U48::from(123),
+                nonce: U48::ZERO,
+            },
+            spender: address!("0000000000000000000000000000000000000002"),
+            sigDeadline: U256::from(123),
+        };
+        assert_eq!(
+            permit.eip712_type_hash(),
+            b256!("f3841cd1ff0085026a6327b620b67997ce40f282c88a8e905a7a5626e310f3d0")
+        );
+        let data = get_permit2_data(permit, 1);
+        assert_eq!(data.domain.verifying_contract, Some(PERMIT2_ADDRESS));
+        assert_eq!(data.domain.version, None);
+    }
+
+    #[test]
+    fn test_encode_permit2_signature() {
+        let signature = PrimitiveSignature::new(U256::from(1), U256::from(2), true);
+        let encoded = encode_permit2_signature(&signature);
+        assert_eq!(encoded.len(), 65);
+        assert_eq!(encoded[31], 1);
+        assert_eq!(encoded[63], 2);
+        assert_eq!(encoded[64], 28);
+    }
+
+    #[test]
+    fn test_plan_permit2() {
+        let token = address!("0000000000000000000000000000000000000001");
+        let spender = address!("0000000000000000000000000000000000000002");
+        let allowance = Permit2Allowance {
+            amount: U160::from(100),
+            expiration: 1000,
+            nonce: 3,
+        };
+        let options = Permit2PlanOptions {
+            amount: None,
+            expiration: 2000,
+            sig_deadline: U256::from(1100),
+        };
+        let plan = |amount: u64, valid_until, erc20_allowance: u64| {
+            plan_permit2(
+                token,
+                spender,
+                U256::from(amount),
+                valid_until,
+                U256::from(erc20_allowance),
+                &allowance,
+                &options,
+            )
+        };
+
+        // covered in amount and time
+        assert_eq!(plan(100, 1000, 100), Permit2Plan::default());
+        // the allowance expires before the deadline
+        let expired = plan(100, 1001, 100).permit.unwrap();
+        assert_eq!(expired.details.nonce, U48::from(3));
+        assert_eq!(expired.details.amount, U160::MAX);
+        assert_eq!(expired.details.expiration, U48::from(2000));
+        assert_eq!(expired.sigDeadline, U256::from(1100));
+        // the allowance is too low and so is the ERC20 allowance to Permit2
+        let too_low = plan(101, 1000, 0);
+        assert!(too_low.approve_permit2);
+        assert!(too_low.permit.is_some());
+    }
+}
@@ -1,5 +1,6 @@
-use super::abi::ISelfPermit;
-use alloy_primitives::{Bytes, PrimitiveSignature, B256, U256};
+use super::abi::{IDaiPermit, IERC20Permit, ISelfPermit};
+use alloc::{borrow::Cow, vec::Vec};
+use alloy_primitives::{address, Bytes, PrimitiveSignature, B256, U256};
 use alloy_sol_types::{eip712_domain, Eip712Domain, SolCall, SolStruct};
 use uniswap_sdk_core::prelude::*;
 
@@ -171,6 +172,202 @@ pub fn encode_permit(token: &impl BaseCurrency, options: PermitOptions) -> Bytes
     .into()
 }
 
+/// Encodes the `selfPermit` or `selfPermitAllowed` calls of several tokens, e.g. to prepend to a
+/// multicall spending all of them
+///
+/// ## Arguments
+///
+/// * `permits`: The tokens and the permits to encode for them
+#[inline]
+#[must_use]
+pub fn encode_permit_batch<'a, C: BaseCurrency + 'a>(
+    permits: impl IntoIterator<Item = (&'a C, PermitOptions)>,
+) -> Vec<Bytes> {
+    permits
+        .into_iter()
+        .map(|(token, options)| encode_permit(token, options))
+        .collect()
+}
+
+/// The EIP-712 domain and permit type of a token, since neither is part of the ERC-20 standard.
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub struct TokenPermitConfig {
+    /// The `name` of the domain.
+    pub name: Cow<'static, str>,
+    /// The `version` of the domain.
+    pub version: Cow<'static, str>,
+    /// Whether the token uses DAI-style permits with a nonce, an expiry and an `allowed` flag
+    /// instead of EIP-2612 permits with a value and a deadline.
+    pub allowed: bool,
+}
+
+/// Resolves the [`TokenPermitConfig`] of a token, e.g. from a curated list or from the `name` and
+/// `version` read onchain.
+///
+/// It is implemented for closures, and [`KnownPermitConfigs`] covers the common tokens.
+pub trait PermitConfigResolver {
+    /// Returns the permit configuration of the token, or `None` if it is unknown or the token does
+    /// not support permits
+    fn permit_config(&self, token: &Token) -> Option<TokenPermitConfig>;
+}
+
+impl<F> PermitConfigResolver for F
+where
+    F: Fn(&Token) -> Option<TokenPermitConfig>,
+{
+    #[inline]
+    fn permit_config(&self, token: &Token) -> Option<TokenPermitConfig> {
+        self(token)
+    }
+}
+
+/// A [`PermitConfigResolver`] that knows the domains of DAI and USDC on Ethereum, and otherwise
+/// assumes an EIP-2612 permit with the name of the token and version `1`.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Hash)]
+pub struct KnownPermitConfigs;
+
+impl PermitConfigResolver for KnownPermitConfigs {
+    #[inline]
+    fn permit_config(&self, token: &Token) -> Option<TokenPermitConfig> {
+        const DAI: Address = address!("6B175474E89094C44Da98b954EedeAC495271d0F");
+        const USDC: Address = address!("A0b86991c6218b36c1d19D4a2e9Eb0cE3606eB48");
+        let mainnet = token.chain_id() == 1;
+        let (name, version, allowed) = if mainnet && token.address() == DAI {
+            (Cow::Borrowed("Dai Stablecoin"), "1", true)
+        } else if mainnet && token.address() == USDC {
+            (Cow::Borrowed("USD Coin"), "2", false)
+        } else {
+            (Cow::Owned(token.name.clone()?), "1", false)
+        };
+        Some(TokenPermitConfig {
+            name,
+            version: version.into(),
+            allowed,
+        })
+    }
+}
+
+/// The typed data of an EIP-2612 or DAI-style permit, see [`get_token_permit_data`].
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum TokenPermitData {
+    /// An EIP-2612 permit of an amount until a deadline, encoded with `selfPermit`.
+    Standard(ERC20PermitData<IERC20Permit::Permit>),
+    /// A DAI-style permit of the maximum amount until an expiry, encoded with
+    /// `selfPermitAllowed`.
+    Allowed(ERC20PermitData<IDaiPermit::Permit>),
+}
+
+impl TokenPermitData {
+    /// The domain of the permit
+    #[inline]
+    #[must_use]
+    pub const fn domain(&self) -> &Eip712Domain {
+        match self {
+            Self::Standard(data) => &data.domain,
+            Self::Allowed(data) => &data.domain,
+        }
+    }
+
+    /// The hash of the typed data to sign
+    #[inline]
+    #[must_use]
+    pub fn eip712_signing_hash(&self) -> B256 {
+        match self {
+            Self::Standard(data) => data.eip712_signing_hash(),
+            Self::Allowed(data) => data.eip712_signing_hash(),
+        }
+    }
+
+    /// Returns the options to pass to [`encode_permit`] once the permit is signed
+    ///
+    /// ## Arguments
+    ///
+    /// * `signature`: The signature of [`Self::eip712_signing_hash`] by the owner
+    #[inline]
+    #[must_use]
+    pub const fn permit_options(&self, signature: PrimitiveSignature) -> PermitOptions {
+        match self {
+            Self::Standard(data) => PermitOptions::Standard(StandardPermitArguments {
+                signature,
+                amount: data.values.value,
+                deadline: data.values.deadline,
+            }),
+            Self::Allowed(data) => PermitOptions::Allowed(AllowedPermitArguments {
+                signature,
+                nonce: data.values.nonce,
+                expiry: data.values.expiry,
+            }),
+        }
+    }
+}
+
+/// Builds the typed data to sign for a permit of a token, with the domain and the permit type
+/// given by the resolver
+///
+/// DAI-style permits approve the maximum amount, so `amount` is ignored for them, and expire at
+/// `deadline`.
+///
+/// ## Arguments
+///
+/// * `token`: The token to permit
+/// * `owner`: The owner of the tokens, i.e. the signer
+/// * `spender`: The spender, e.g. the swap router or the position manager
+/// * `amount`: The amount to approve
+/// * `nonce`: The current permit nonce of the owner on the token
+/// * `deadline`: The timestamp after which the permit can't be used
+/// * `resolver`: Resolves the domain and the permit type of the token
+///
+/// ## Returns
+///
+/// `None` if the resolver doesn't know the token
+#[inline]
+#[must_use]
+pub fn get_token_permit_data(
+    token: &Token,
+    owner: Address,
+    spender: Address,
+    amount: U256,
+    nonce: U256,
+    deadline: U256,
+    resolver: &impl PermitConfigResolver,
+) -> Option<TokenPermitData> {
+    let TokenPermitConfig {
+        name,
+        version,
+        allowed,
+    } = resolver.permit_config(token)?;
+    let domain = Eip712Domain::new(
+        Some(name),
+        Some(version),
+        Some(U256::from(token.chain_id())),
+        Some(token.address()),
+        None,
+    );
+    Some(if allowed {
+        TokenPermitData::Allowed(ERC20PermitData {
+            domain,
+            values: IDaiPermit::Permit {
+                holder: owner,
+                spender,
+                nonce,
+                expiry: deadline,
+                allowed: true,
+            },
+        })
+    } else {
+        TokenPermitData::Standard(ERC20PermitData {
+            domain,
+            values: IERC20Permit::Permit {
+                owner,
+                spender,
+                value: amount,
+                nonce,
+                deadline,
+            },
+        })
+    })
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -197,6 +394,96 @@ mod tests {
         assert_eq!(calldata, hex!("f3995c670000000000000000000000000000000000000000000000000000000000000001000000000000000000000000000000000000000000000000000000000000007b000000000000000000000000000000000000000000000000000000000000007b000000000000000000000000000000000000000000000000000000000000001b00000000000000000000000000000000000000000000000000000000000000010000000000000000000000000000000000000000000000000000000000000002").to_vec());
     }
 
+    #[test]
+    fn test_get_token_permit_data() {
+        let owner = address!("0000000000000000000000000000000000000002");
+        let spender = address!("0000000000000000000000000000000000000003");
+        let dai = token!(
+            1,
+            "6B175474E89094C44Da98b954EedeAC495271d0F",
+            18,
+            "DAI",
+            "Dai Stablecoin"
+        );
+        let data = get_token_permit_data(
+            &dai,
+            owner,
+            spender,
+            uint!(1_U256),
+            uint!(2_U256),
+            uint!(123_U256),
+            &KnownPermitConfigs,
+        )
+        .unwrap();
+        assert_eq!(
+            data.domain(),
+            &eip712_domain! {
+                name: "Dai Stablecoin",
+                version: "1",
+                chain_id: 1,
+                verifying_contract: dai.address(),
+            }
+        );
+        let TokenPermitData::Allowed(permit) = &data else {
+            panic!("expected a DAI-style permit");
+        };
+        assert!(permit.values.allowed);
+        assert_eq!(permit.values.expiry, uint!(123_U256));
+
+        let signature = PrimitiveSignature::new(uint!(1_U256), uint!(2_U256), false);
+        assert_eq!(
+            encode_permit(&dai, data.permit_options(signature)),
+            encode_permit(
+                &dai,
+                PermitOptions::Allowed(AllowedPermitArguments::new(
+                    uint!(1_U256),
+                    uint!(2_U256),
+                    false,
+                    uint!(2_U256),
+                    uint!(123_U256),
+                ))
+            )
+        );
+
+        // unnamed tokens are unknown unless resolved by a hook
+        assert!(get_token_permit_data(
+            &TOKEN,
+            owner,
+            spender,
+            uint!(1_U256),
+            uint!(0_U256),
+            uint!(123_U256),
+            &KnownPermitConfigs,
+        )
+        .is_none());
+        let resolver = |_: &Token| {
+            Some(TokenPermitConfig {
+                name: "ONE".into(),
+                version: "2".into(),
+                allowed: false,
+            })
+        };
+        let data = get_token_permit_data(
+            &TOKEN,
+            owner,
+            spender,
+            uint!(1_U256),
+            uint!(0_U256),
+            uint!(123_U256),
+            &resolver,
+        )
+        .unwrap();
+        assert_eq!(data.domain().version.as_deref(), Some("2"));
+        assert!(matches!(
+            data.permit_options(signature),
+            PermitOptions::Standard(StandardPermitArguments { amount, .. }) if amount == uint!(1_U256)
+        ));
+        assert_eq!(
+            encode_permit_batch([(&*TOKEN, data.permit_options(signature))]),
+            vec![encode_permit(&*TOKEN, data.permit_options(signature))]
+        );
+    }
+
     #[test]
     fn test_encode_permit_allowed() {
         let allowed_permit_options = AllowedPermitArguments::new(
@@ -121,7 +121,7 @@ pub fn collect_rewards<TP: TickDataProvider>(
         );
     }
     MethodParameters {
-        calldata: encode_multicall(calldatas),
+        calldata: encode_multicall(&calldatas),
         value: U256::ZERO,
     }
 }
@@ -156,7 +156,165 @@ pub fn withdraw_token<TP: TickDataProvider>(
         .into(),
     );
     MethodParameters {
-        calldata: encode_multicall(calldatas),
+        calldata: encode_multicall(&calldatas),
+        value: U256::ZERO,
+    }
+}
+
+/// Unstakes a position from multiple programs, claims each distinct reward token once and
+/// optionally restakes the position in the same programs.
+///
+/// Unlike [`collect_rewards`], which claims once per program, the rewards of programs sharing a
+/// reward token are claimed in a single call, as the staker accrues them per reward token.
+///
+/// ## Arguments
+///
+/// * `incentive_keys`: The programs that `options.token_id` is staked in.
+/// * `options`: ClaimOptions to specify tokenId, recipient, and amount of each reward token.
+/// * `restake`: Whether to stake the position again in the programs after claiming.
+#[inline]
+pub fn claim_rewards<TP: TickDataProvider>(
+    incentive_keys: &[IncentiveKey<TP>],
+    options: ClaimOptions,
+    restake: bool,
+) -> MethodParameters {
+    let stake_in: &[IncentiveKey<TP>] = if restake { incentive_keys } else { &[] };
+    restake_token(incentive_keys, stake_in, options)
+}
+
+/// Moves a position from some programs to others in one multicall: unstakes it from
+/// `unstake_from`, claims each distinct reward token of those programs once, then stakes it in
+/// `stake_in`.
+///
+/// ## Arguments
+///
+/// * `unstake_from`: The programs to unstake `options.token_id` from.
+/// * `stake_in`: The programs to stake `options.token_id` in afterwards.
+/// * `options`: ClaimOptions to specify tokenId, recipient, and amount of each reward token.
+#[inline]
+pub fn restake_token<TP: TickDataProvider>(
+    unstake_from: &[IncentiveKey<TP>],
+    stake_in: &[IncentiveKey<TP>],
+    options: ClaimOptions,
+) -> MethodParameters {
+    let mut calldatas: Vec<Bytes> = Vec::with_capacity(unstake_from.len() * 2 + stake_in.len());
+    let mut reward_tokens: Vec<Address> = Vec::with_capacity(unstake_from.len());
+    for incentive_key in unstake_from {
+        calldatas.push(
+            IUniswapV3Staker::unstakeTokenCall {
+                key: encode_incentive_key(incentive_key),
+                tokenId: options.token_id,
+            }
+            .abi_encode()
+            .into(),
+        );
+        if !reward_tokens.contains(&incentive_key.reward_token) {
+            reward_tokens.push(incentive_key.reward_token);
+        }
+    }
+    for reward_token in reward_tokens {
+        calldatas.push(
+            IUniswapV3Staker::claimRewardCall {
+                rewardToken: reward_token,
+                to: options.recipient,
+                amountRequested: options.amount.unwrap_or_default(),
+            }
+            .abi_encode()
+            .into(),
+        );
+    }
+    for incentive_key in stake_in {
+        calldatas.push(
+            IUniswapV3Staker::stakeTokenCall {
+                key: encode_incentive_key(incentive_key),
+                tokenId: options.token_id,
+            }
+            .abi_encode()
+            .into(),
+        );
+    }
+    MethodParameters {
+        calldata: encode_multicall(&calldatas),
+        value: U256::ZERO,
+    }
+}
+
+/// Withdraws a deposited position that is not staked in any program.
+///
+/// ## Arguments
+///
+/// * `token_id`: The id of the NFT
+/// * `options`: The owner to send the position to and the `data` passed to `safeTransferFrom`.
+#[inline]
+pub fn withdraw_deposit(token_id: U256, options: WithdrawOptions) -> MethodParameters {
+    MethodParameters {
+        calldata: IUniswapV3Staker::withdrawTokenCall {
+            tokenId: token_id,
+            to: options.owner,
+            data: options.data.unwrap_or_default(),
+        }
+        .abi_encode()
+        .into(),
+        value: U256::ZERO,
+    }
+}
+
+/// Transfers the ownership of a deposit to another address, without withdrawing the position.
+///
+/// ## Arguments
+///
+/// * `token_id`: The id of the NFT
+/// * `to`: The new owner of the deposit
+#[inline]
+#[must_use]
+pub fn transfer_deposit(token_id: U256, to: Address) -> MethodParameters {
+    MethodParameters {
+        calldata: IUniswapV3Staker::transferDepositCall {
+            tokenId: token_id,
+            to,
+        }
+        .abi_encode()
+        .into(),
+        value: U256::ZERO,
+    }
+}
+
+/// Creates a staking program funded with `reward` of the reward token, which must be approved
+/// to the staker beforehand.
+///
+/// ## Arguments
+///
+/// * `incentive_key`: The staking program to create.
+/// * `reward`: The amount of reward tokens to distribute.
+#[inline]
+pub fn create_incentive<TP: TickDataProvider>(
+    incentive_key: &IncentiveKey<TP>,
+    reward: U256,
+) -> MethodParameters {
+    MethodParameters {
+        calldata: IUniswapV3Staker::createIncentiveCall {
+            key: encode_incentive_key(incentive_key),
+            reward,
+        }
+        .abi_encode()
+        .into(),
+        value: U256::ZERO,
+    }
+}
+
+/// Ends a staking program after its end time and refunds the remaining rewards to the refundee.
+///
+/// ## Arguments
+///
+/// * `incentive_key`: The staking program to end.
+#[inline]
+pub fn end_incentive<TP: TickDataProvider>(incentive_key: &IncentiveKey<TP>) -> MethodParameters {
+    MethodParameters {
+        calldata: IUniswapV3Staker::endIncentiveCall {
+            key: encode_incentive_key(incentive_key),
+        }
+        .abi_encode()
+        .into(),
         value: U256::ZERO,
     }
 }
@@ -297,6 +455,80 @@ mod tests {
         );
     }
 
+    #[test]
+    fn test_claim_rewards_claims_each_reward_token_once() {
+        let options = ClaimOptions {
+            token_id: TOKEN_ID,
+            recipient: RECIPIENT,
+            amount: None,
+        };
+        let MethodParameters { calldata, value } = claim_rewards(&INCENTIVE_KEYS, options, true);
+        assert_eq!(value, U256::ZERO);
+        let calls: Vec<Bytes> = decode_multicall(calldata).unwrap();
+        assert_eq!(calls.len(), 5);
+        assert_eq!(
+            calls[2],
+            Bytes::from(
+                IUniswapV3Staker::claimRewardCall {
+                    rewardToken: REWARD.address(),
+                    to: RECIPIENT,
+                    amountRequested: U256::ZERO,
+                }
+                .abi_encode()
+            )
+        );
+        for (call, key) in calls[3..].iter().zip(INCENTIVE_KEYS.iter()) {
+            assert_eq!(
+                *call,
+                Bytes::from(
+                    IUniswapV3Staker::stakeTokenCall {
+                        key: encode_incentive_key(key),
+                        tokenId: TOKEN_ID,
+                    }
+                    .abi_encode()
+                )
+            );
+        }
+
+        let MethodParameters { calldata, .. } = claim_rewards(&INCENTIVE_KEYS, options, false);
+        assert_eq!(decode_multicall::<Bytes, _>(calldata).unwrap().len(), 3);
+    }
+
+    #[test]
+    fn test_restake_token_moves_programs() {
+        let options = ClaimOptions {
+            token_id: TOKEN_ID,
+            recipient: RECIPIENT,
+            amount: Some(uint!(1_U256)),
+        };
+        let MethodParameters { calldata, .. } =
+            restake_token(&INCENTIVE_KEYS[..1], &INCENTIVE_KEYS[1..], options);
+        let calls: Vec<Bytes> = decode_multicall(calldata).unwrap();
+        assert_eq!(calls.len(), 3);
+        assert_eq!(calls[0][..4], IUniswapV3Staker::unstakeTokenCall::SELECTOR);
+        assert_eq!(calls[1][..4], IUniswapV3Staker::claimRewardCall::SELECTOR);
+        assert_eq!(
+            IUniswapV3Staker::stakeTokenCall::abi_decode(&calls[2], true)
+                .unwrap()
+                .key,
+            encode_incentive_key(&INCENTIVE_KEYS[1])
+        );
+    }
+
+    #[test]
+    fn test_withdraw_deposit_passes_data() {
+        let MethodParameters { calldata, value } =
+            withdraw_deposit(TOKEN_ID, WITHDRAW_OPTIONS.withdraw_options.clone());
+        assert_eq!(value, U256::ZERO);
+        let call = IUniswapV3Staker::withdrawTokenCall::abi_decode(&calldata, true).unwrap();
+        assert_eq!(call.tokenId, TOKEN_ID);
+        assert_eq!(call.to, SENDER);
+        assert_eq!(
+            call.data.to_vec(),
+            hex!("0000000000000000000000000000000000000008")
+        );
+    }
+
     #[test]
     fn test_encode_deposit_succeeds_single_key() {
         let deposit = encode_deposit(&[INCENTIVE_KEY.clone()]);
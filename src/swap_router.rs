@@ -1,8 +1,298 @@
 use crate::prelude::{Error, *};
-use alloy_primitives::{Bytes, U160, U256};
+use alloc::vec;
+use alloy_primitives::{Bytes, Selector, U160, U256};
 use alloy_sol_types::SolCall;
 use uniswap_sdk_core::prelude::*;
 
+/// Controls whether the calls to the router are wrapped in a `multicall`.
+///
+/// A bare call skips the `multicall` dispatch, i.e. the extra `delegatecall` and the decoding of
+/// the `bytes[]` argument, which saves a few thousand gas for single-hop swaps that need neither a
+/// permit, an unwrap, a sweep nor a refund.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, Hash)]
+#[cfg_attr(
+    feature = "schemars",
+    derive(serde::Serialize, serde::Deserialize, schemars::JsonSchema)
+)]
+pub enum MulticallWrapping {
+    /// Emit a bare call when the swap encodes to a single call, otherwise use `multicall`.
+    #[default]
+    Auto,
+    /// Always wrap the calls in a `multicall`, even if there is only one.
+    Always,
+    /// Never wrap the calls in a `multicall`. Fails with [`Error::MulticallRequired`] if the swap
+    /// requires more than one call.
+    Never,
+}
+
+/// The currency the fee on the output of a swap is taken in.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, Hash)]
+#[cfg_attr(
+    feature = "schemars",
+    derive(serde::Serialize, serde::Deserialize, schemars::JsonSchema)
+)]
+pub enum FeeCurrency {
+    /// The output currency, i.e. ether if the output is native.
+    #[default]
+    Output,
+    /// The wrapped native token when the output is ether. The fee is swept in WETH to the fee
+    /// recipient and the remainder to the router itself, i.e. [`SwapOptions::router`] or the
+    /// canonical `SwapRouter02` of the chain, which then unwraps it to the recipient.
+    WrappedNative,
+}
+
+/// The interface implemented by the router the calls are sent to.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, Hash)]
+#[cfg_attr(
+    feature = "schemars",
+    derive(serde::Serialize, serde::Deserialize, schemars::JsonSchema)
+)]
+pub enum RouterVariant {
+    /// `SwapRouter02` or a fork with the same interface, i.e. the swaps, `multicall`, the payments
+    /// and the self permits.
+    #[default]
+    SwapRouter02,
+    /// A fork implementing only the swap functions of `IV3SwapRouter`. Swaps that need any other
+    /// call, e.g. to unwrap ether, take a fee or spend a permit, cannot be encoded for it.
+    SwapOnly,
+    /// The original `SwapRouter` or a fork of it, i.e. the `ISwapRouter` swaps, `multicall`
+    /// without a deadline, the payments with a recipient and the self permits.
+    SwapRouter,
+}
+
+impl RouterVariant {
+    /// Returns whether a router of this variant implements the function with the given selector
+    ///
+    /// ## Arguments
+    ///
+    /// * `selector`: The selector of the function
+    #[inline]
+    #[must_use]
+    pub fn supports(self, selector: Selector) -> bool {
+        let swaps = IV3SwapRouter::IV3SwapRouterCalls::SELECTORS;
+        match self {
+            Self::SwapRouter => {
+                ISwapRouter::ISwapRouterCalls::SELECTORS.contains(&selector.0)
+                    || IPeripheryPaymentsWithFee::IPeripheryPaymentsWithFeeCalls::SELECTORS
+                        .contains(&selector.0)
+                    || ISelfPermit::ISelfPermitCalls::SELECTORS.contains(&selector.0)
+                    || selector.0 == IMulticall::multicallCall::SELECTOR
+            }
+            Self::SwapRouter02 => {
+                swaps.contains(&selector.0)
+                    || IPeripheryPaymentsWithFee::IPeripheryPaymentsWithFeeCalls::SELECTORS
+                        .contains(&selector.0)
+                    || IPeripheryPaymentsExtended::IPeripheryPaymentsExtendedCalls::SELECTORS
+                        .contains(&selector.0)
+                    || ISelfPermit::ISelfPermitCalls::SELECTORS.contains(&selector.0)
+                    || selector.0 == IMulticall::multicallCall::SELECTOR
+                    || selector.0 == IMulticallExtended::multicallCall::SELECTOR
+            }
+            Self::SwapOnly => swaps.contains(&selector.0),
+        }
+    }
+}
+
+/// The router the calls are encoded for, e.g. a fork of `SwapRouter02` deployed by an integrator.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, Hash)]
+#[cfg_attr(
+    feature = "schemars",
+    derive(serde::Serialize, serde::Deserialize, schemars::JsonSchema)
+)]
+pub struct RouterTarget {
+    /// The address of the router.
+    #[cfg_attr(feature = "schemars", schemars(with = "String"))]
+    pub address: Address,
+    /// The interface implemented by the router.
+    #[cfg_attr(feature = "schemars", serde(default))]
+    pub variant: RouterVariant,
+    /// The encoding of the swap functions of the router.
+    #[cfg_attr(feature = "schemars", serde(default))]
+    pub swap_abi: SwapAbi,
+}
+
+impl RouterTarget {
+    /// Returns whether the router implements the function with the given selector, given its
+    /// variant and swap ABI
+    ///
+    /// ## Arguments
+    ///
+    /// * `selector`: The selector of the function
+    #[inline]
+    #[must_use]
+    pub fn supports(self, selector: Selector) -> bool {
+        match self.swap_abi {
+            SwapAbi::V3SwapRouter => self.variant.supports(selector),
+            SwapAbi::SwapRouter => {
+                ISwapRouter::ISwapRouterCalls::SELECTORS.contains(&selector.0)
+                    || (!IV3SwapRouter::IV3SwapRouterCalls::SELECTORS.contains(&selector.0)
+                        && self.variant.supports(selector))
+            }
+        }
+    }
+}
+
+/// The encoding of the swap functions of a router, which differs between the original
+/// `SwapRouter` and `SwapRouter02`, and so between their forks on other chains.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, Hash)]
+#[cfg_attr(
+    feature = "schemars",
+    derive(serde::Serialize, serde::Deserialize, schemars::JsonSchema)
+)]
+pub enum SwapAbi {
+    /// The `IV3SwapRouter` functions of `SwapRouter02`, whose parameters have no deadline.
+    #[default]
+    V3SwapRouter,
+    /// The `ISwapRouter` functions of the original `SwapRouter`, whose parameters include a
+    /// deadline, as kept by some forks, e.g. on BNB Chain. The deadline is taken from
+    /// [`SwapOptions::deadline`].
+    SwapRouter,
+}
+
+/// Encodes an `exactInputSingle` or `exactOutputSingle` call with the given swap ABI, and the
+/// deadline if it encodes one
+#[allow(clippy::too_many_arguments)]
+fn encode_single_swap(
+    swap_abi: SwapAbi,
+    deadline: U256,
+    trade_type: TradeType,
+    token_in: Address,
+    token_out: Address,
+    fee: FeeAmount,
+    recipient: Address,
+    amount_in: U256,
+    amount_out: U256,
+    sqrt_price_limit_x96: U160,
+) -> Bytes {
+    match (swap_abi, trade_type) {
+        (SwapAbi::V3SwapRouter, TradeType::ExactInput) => IV3SwapRouter::exactInputSingleCall {
+            params: IV3SwapRouter::ExactInputSingleParams {
+                tokenIn: token_in,
+                tokenOut: token_out,
+                fee: fee.into(),
+                recipient,
+                amountIn: amount_in,
+                amountOutMinimum: amount_out,
+                sqrtPriceLimitX96: sqrt_price_limit_x96,
+            },
+        }
+        .abi_encode(),
+        (SwapAbi::V3SwapRouter, TradeType::ExactOutput) => IV3SwapRouter::exactOutputSingleCall {
+            params: IV3SwapRouter::ExactOutputSingleParams {
+                tokenIn: token_in,
+                tokenOut: token_out,
+                fee: fee.into(),
+                recipient,
+                amountOut: amount_out,
+                amountInMaximum: amount_in,
+                sqrtPriceLimitX96: sqrt_price_limit_x96,
+            },
+        }
+        .abi_encode(),
+        (SwapAbi::SwapRouter, TradeType::ExactInput) => ISwapRouter::exactInputSingleCall {
+            params: ISwapRouter::ExactInputSingleParams {
+                tokenIn: token_in,
+                tokenOut: token_out,
+                fee: fee.into(),
+                recipient,
+                deadline,
+                amountIn: amount_in,
+                amountOutMinimum: amount_out,
+                sqrtPriceLimitX96: sqrt_price_limit_x96,
+            },
+        }
+        .abi_encode(),
+        (SwapAbi::SwapRouter, TradeType::ExactOutput) => ISwapRouter::exactOutputSingleCall {
+            params: ISwapRouter::ExactOutputSingleParams {
+                tokenIn: token_in,
+                tokenOut: token_out,
+                fee: fee.into(),
+                recipient,
+                deadline,
+                amountOut: amount_out,
+                amountInMaximum: amount_in,
+                sqrtPriceLimitX96: sqrt_price_limit_x96,
+            },
+        }
+        .abi_encode(),
+    }
+    .into()
+}
+
+/// Encodes an `exactInput` or `exactOutput` call with the given swap ABI, and the deadline if it
+/// encodes one
+fn encode_path_swap(
+    swap_abi: SwapAbi,
+    deadline: U256,
+    trade_type: TradeType,
+    path: Bytes,
+    recipient: Address,
+    amount_in: U256,
+    amount_out: U256,
+) -> Bytes {
+    match (swap_abi, trade_type) {
+        (SwapAbi::V3SwapRouter, TradeType::ExactInput) => IV3SwapRouter::exactInputCall {
+            params: IV3SwapRouter::ExactInputParams {
+                path,
+                recipient,
+                amountIn: amount_in,
+                amountOutMinimum: amount_out,
+            },
+        }
+        .abi_encode(),
+        (SwapAbi::V3SwapRouter, TradeType::ExactOutput) => IV3SwapRouter::exactOutputCall {
+            params: IV3SwapRouter::ExactOutputParams {
+                path,
+                recipient,
+                amountOut: amount_out,
+                amountInMaximum: amount_in,
+            },
+        }
+        .abi_encode(),
+        (SwapAbi::SwapRouter, TradeType::ExactInput) => ISwapRouter::exactInputCall {
+            params: ISwapRouter::ExactInputParams {
+                path,
+                recipient,
+                deadline,
+                amountIn: amount_in,
+                amountOutMinimum: amount_out,
+            },
+        }
+        .abi_encode(),
+        (SwapAbi::SwapRouter, TradeType::ExactOutput) => ISwapRouter::exactOutputCall {
+            params: ISwapRouter::ExactOutputParams {
+                path,
+                recipient,
+                deadline,
+                amountOut: amount_out,
+                amountInMaximum: amount_in,
+            },
+        }
+        .abi_encode(),
+    }
+    .into()
+}
+
+/// Returns the selectors of the calls encoded in router calldata, flattening `multicall`s
+///
+/// ## Arguments
+///
+/// * `calldata`: The calldata sent to the router
+#[inline]
+#[must_use]
+pub fn call_selectors(calldata: &[u8]) -> Vec<Selector> {
+    let Some((_, calls)) = split_multicall(calldata) else {
+        return calldata
+            .get(..4)
+            .map(|selector| vec![Selector::from_slice(selector)])
+            .unwrap_or_default();
+    };
+    let mut selectors = Vec::with_capacity(calls.len() + 1);
+    selectors.push(Selector::from_slice(&calldata[..4]));
+    selectors.extend(calls.iter().flat_map(|call| call_selectors(call)));
+    selectors
+}
+
 /// Options for producing the arguments to send calls to the router.
 #[derive(Clone, Debug, Default, PartialEq, Eq)]
 pub struct SwapOptions {
@@ -12,10 +302,79 @@ pub struct SwapOptions {
     pub recipient: Address,
     /// The optional permit parameters for spending the input.
     pub input_token_permit: Option<PermitOptions>,
+    /// The optional `Permit2` permit for spending the input. Only the Universal Router spends
+    /// `Permit2` allowances, see [`universal_router_swap_call_parameters`], so the other routers
+    /// reject it with [`Error::UnsupportedRouterCall`].
+    pub input_token_permit2: Option<Permit2Options>,
     /// The optional price limit for the trade.
     pub sqrt_price_limit_x96: Option<U160>,
     /// Optional information for taking a fee on output.
     pub fee: Option<FeeOptions>,
+    /// The target the router forwards the output to after the swaps, instead of `recipient`, e.g. a
+    /// vault or hook contract that rejects the transfer from the pool made during the swap. The
+    /// router then takes custody of the output even when no fee is taken and appends a
+    /// `sweepToken` to the target, or an `unwrapWETH9` if the output is native.
+    pub sweep_output: Option<Address>,
+    /// Whether to wrap the calls in a `multicall`.
+    pub multicall_wrapping: MulticallWrapping,
+    /// Optional recipients overriding `recipient` for the trade at the same index, e.g. to execute
+    /// the swaps of several accounts in one transaction. Trades without an override, or with
+    /// `None`, send their output to `recipient`. Overrides are rejected when the router takes
+    /// custody of the output, since it is then forwarded to `recipient` as a whole.
+    pub trade_recipients: Vec<Option<Address>>,
+    /// The currency the fee is taken in, only relevant if a fee is taken on a native output.
+    pub fee_currency: FeeCurrency,
+    /// The router the calls are encoded for, if not the canonical `SwapRouter02`. The calls it does
+    /// not implement are rejected.
+    pub router: Option<RouterTarget>,
+    /// The deadline encoded in the swaps, in epoch seconds, required by a router with the
+    /// [`SwapAbi::SwapRouter`] encoding and ignored otherwise.
+    pub deadline: Option<U256>,
+    /// Whether to derive the price limit of the single-hop swaps of exact input trades from the
+    /// slippage tolerance when `sqrt_price_limit_x96` is not set, see
+    /// [`sqrt_price_limit_from_slippage`], so that the pool itself stops the swap at the worst
+    /// acceptable price. Exact output swaps are left without a limit, which could stop them short
+    /// of their output.
+    pub derive_sqrt_price_limit: bool,
+}
+
+/// The limits encoded for a single swap by [`swap_call_parameters_with_summary`].
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct SwapLimits {
+    /// The address of the input token, wrapped if native.
+    pub token_in: Address,
+    /// The address of the output token, wrapped if native.
+    pub token_out: Address,
+    /// The fee tiers of the pools along the route.
+    pub pool_fees: Vec<FeeAmount>,
+    /// The maximum amount of the input token spent.
+    pub amount_in_maximum: U256,
+    /// The minimum amount of the output token received.
+    pub amount_out_minimum: U256,
+    /// The part of `amount_out_minimum` taken as fee on the output, zero if no fee is taken.
+    pub fee_amount: U256,
+    /// The recipient encoded in the swap call, [`Address::ZERO`] if the router takes custody.
+    pub recipient: Address,
+}
+
+/// A summary of what [`swap_call_parameters_with_summary`] encoded, so that execution systems can
+/// persist the limits without decoding the calldata.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct SwapPlanSummary {
+    /// The limits of every swap, in the order they are executed.
+    pub swaps: Vec<SwapLimits>,
+    /// Whether the router takes custody of the output before unwrapping or sweeping it.
+    pub router_custody: bool,
+    /// The final recipient of the output.
+    pub recipient: Address,
+    /// The minimum total output unwrapped or swept to the recipient.
+    pub total_amount_out_minimum: U256,
+    /// The fee taken on the output, if any.
+    pub fee: Option<FeeOptions>,
+    /// Whether unspent ether is refunded to the sender.
+    pub refund_eth: bool,
+    /// The amount of ether (wei) sent with the call.
+    pub value: U256,
 }
 
 /// Produces the on-chain method name to call and the hex encoded parameters to pass as arguments
@@ -34,6 +393,25 @@ pub fn swap_call_parameters<TInput, TOutput, TP>(
     trades: &mut [Trade<TInput, TOutput, TP>],
     options: SwapOptions,
 ) -> Result<MethodParameters, Error>
+where
+    TInput: BaseCurrency,
+    TOutput: BaseCurrency,
+    TP: TickDataProvider,
+{
+    swap_call_parameters_with_summary(trades, options).map(|(params, _)| params)
+}
+
+/// Same as [`swap_call_parameters`] but also returns a [`SwapPlanSummary`] of the encoded limits
+///
+/// ## Arguments
+///
+/// * `trades`: trades to produce call parameters for
+/// * `options`: options for the call parameters
+#[inline]
+pub fn swap_call_parameters_with_summary<TInput, TOutput, TP>(
+    trades: &mut [Trade<TInput, TOutput, TP>],
+    options: SwapOptions,
+) -> Result<(MethodParameters, SwapPlanSummary), Error>
 where
     TInput: BaseCurrency,
     TOutput: BaseCurrency,
@@ -43,17 +421,33 @@ where
         slippage_tolerance,
         recipient,
         input_token_permit,
+        input_token_permit2,
         sqrt_price_limit_x96,
         fee,
+        sweep_output,
+        multicall_wrapping,
+        trade_recipients,
+        fee_currency,
+        router,
+        deadline,
+        derive_sqrt_price_limit,
     } = options;
+    if input_token_permit2.is_some() {
+        return Err(Error::UnsupportedRouterCall(
+            IAllowanceTransfer::permit_0Call::SELECTOR.into(),
+        ));
+    }
     let sample_trade = &trades[0];
     let input_currency = sample_trade.input_currency();
     let token_in = input_currency.wrapped();
-    let input_is_native = input_currency.is_native();
+    let input_is_native = requires_weth9(input_currency);
     let output_currency = sample_trade.output_currency();
     let token_out = output_currency.wrapped();
+    // copied so that the trades can be borrowed mutably below
+    let token_out_address = token_out.address();
+    let chain_id = token_out.chain_id();
     let output_currency_address = output_currency.address();
-    let output_is_native = output_currency.is_native();
+    let output_is_native = requires_weth9(output_currency);
     let trade_type = sample_trade.trade_type;
 
     // All trades should have the same starting and ending token.
@@ -68,7 +462,19 @@ where
         );
     }
 
+    if let Some(fee) = &fee {
+        validate_fee_options(fee)?;
+    }
+    if fee_currency == FeeCurrency::WrappedNative && (fee.is_none() || !output_is_native) {
+        return Err(Error::InvalidFeeCurrency);
+    }
+
     let num_swaps = trades.iter().map(|trade| trade.swaps.len()).sum::<usize>();
+    let swap_abi = router.map(|router| router.swap_abi).unwrap_or_default();
+    let deadline = match (swap_abi, deadline) {
+        (SwapAbi::SwapRouter, None) => return Err(Error::DeadlineRequired),
+        (_, deadline) => deadline.unwrap_or_default(),
+    };
 
     let mut calldatas: Vec<Bytes> = Vec::with_capacity(num_swaps + 3);
 
@@ -84,12 +490,21 @@ where
             .minimum_amount_out_cached(slippage_tolerance.clone(), None)?
             .quotient();
     }
-    let total_amount_out = U256::from_big_int(total_amount_out);
+    let total_amount_out = big_int_to_u256(&total_amount_out)?;
 
     // flag for whether a refund needs to happen
     let must_refund = input_is_native && trade_type == TradeType::ExactOutput;
     // flags for whether funds should be sent first to the router
-    let router_must_custody = output_is_native || fee.is_some();
+    let router_must_custody = output_is_native || fee.is_some() || sweep_output.is_some();
+    // the final recipient of the output
+    let recipient = sweep_output.unwrap_or(recipient);
+
+    if trade_recipients.len() > trades.len()
+        || (router_must_custody && trade_recipients.iter().any(Option::is_some))
+    {
+        return Err(Error::InvalidRecipientOverride);
+    }
+    let mut swap_limits: Vec<SwapLimits> = Vec::with_capacity(num_swaps);
 
     let mut total_value = BigInt::ZERO;
     if input_is_native {
@@ -100,111 +515,145 @@ where
         }
     }
 
-    for trade in trades.iter() {
+    for (i, trade) in trades.iter().enumerate() {
+        let swap_recipient = if router_must_custody {
+            Address::ZERO
+        } else {
+            trade_recipients
+                .get(i)
+                .copied()
+                .flatten()
+                .unwrap_or(recipient)
+        };
         for Swap {
             route,
             input_amount,
             output_amount,
         } in &trade.swaps
         {
-            let amount_in = U256::from_big_int(
-                trade
+            let amount_in = big_int_to_u256(
+                &trade
                     .maximum_amount_in(slippage_tolerance.clone(), Some(input_amount.clone()))?
                     .quotient(),
-            );
-            let amount_out = U256::from_big_int(
-                trade
+            )?;
+            let amount_out = big_int_to_u256(
+                &trade
                     .minimum_amount_out(slippage_tolerance.clone(), Some(output_amount.clone()))?
                     .quotient(),
-            );
+            )?;
+            let fee_amount = match &fee {
+                Some(fee) => mul_div(
+                    amount_out,
+                    encode_fee_bips(fee.fee.clone()),
+                    U256::from(10_000),
+                )?,
+                None => U256::ZERO,
+            };
+            swap_limits.push(SwapLimits {
+                token_in: route.input.wrapped().address(),
+                token_out: route.output.wrapped().address(),
+                pool_fees: route.pools.iter().map(|pool| pool.fee).collect(),
+                amount_in_maximum: amount_in,
+                amount_out_minimum: amount_out,
+                fee_amount,
+                recipient: swap_recipient,
+            });
 
             if route.pools.len() == 1 {
-                calldatas.push(match trade.trade_type {
-                    TradeType::ExactInput => IV3SwapRouter::exactInputSingleCall {
-                        params: IV3SwapRouter::ExactInputSingleParams {
-                            tokenIn: route.input.wrapped().address(),
-                            tokenOut: route.output.wrapped().address(),
-                            fee: route.pools[0].fee.into(),
-                            recipient: if router_must_custody {
-                                Address::ZERO
-                            } else {
-                                recipient
-                            },
-                            amountIn: amount_in,
-                            amountOutMinimum: amount_out,
-                            sqrtPriceLimitX96: sqrt_price_limit_x96.unwrap_or_default(),
-                        },
-                    }
-                    .abi_encode()
-                    .into(),
-                    TradeType::ExactOutput => IV3SwapRouter::exactOutputSingleCall {
-                        params: IV3SwapRouter::ExactOutputSingleParams {
-                            tokenIn: route.input.wrapped().address(),
-                            tokenOut: route.output.wrapped().address(),
-                            fee: route.pools[0].fee.into(),
-                            recipient: if router_must_custody {
-                                Address::ZERO
-                            } else {
-                                recipient
-                            },
-                            amountOut: amount_out,
-                            amountInMaximum: amount_in,
-                            sqrtPriceLimitX96: sqrt_price_limit_x96.unwrap_or_default(),
-                        },
+                let pool = &route.pools[0];
+                let sqrt_price_limit_x96 = match sqrt_price_limit_x96 {
+                    // a limit could stop an exact output swap short of its output
+                    None if derive_sqrt_price_limit
+                        && trade.trade_type == TradeType::ExactInput =>
+                    {
+                        let quote = pool.quote_output(input_amount, None)?;
+                        Some(sqrt_price_limit_from_slippage(
+                            quote.sqrt_price_x96_after,
+                            route.input.wrapped().equals(&pool.token0),
+                            &slippage_tolerance,
+                        ))
                     }
-                    .abi_encode()
-                    .into(),
-                });
+                    limit => limit,
+                };
+                calldatas.push(encode_single_swap(
+                    swap_abi,
+                    deadline,
+                    trade.trade_type,
+                    route.input.wrapped().address(),
+                    route.output.wrapped().address(),
+                    pool.fee,
+                    swap_recipient,
+                    amount_in,
+                    amount_out,
+                    sqrt_price_limit_x96.unwrap_or_default(),
+                ));
             } else {
                 assert!(sqrt_price_limit_x96.is_none(), "MULTIHOP_PRICE_LIMIT");
 
                 let path = encode_route_to_path(route, trade.trade_type == TradeType::ExactOutput);
 
-                calldatas.push(match trade.trade_type {
-                    TradeType::ExactInput => IV3SwapRouter::exactInputCall {
-                        params: IV3SwapRouter::ExactInputParams {
-                            path,
-                            recipient: if router_must_custody {
-                                Address::ZERO
-                            } else {
-                                recipient
-                            },
-                            amountIn: amount_in,
-                            amountOutMinimum: amount_out,
-                        },
-                    }
-                    .abi_encode()
-                    .into(),
-                    TradeType::ExactOutput => IV3SwapRouter::exactOutputCall {
-                        params: IV3SwapRouter::ExactOutputParams {
-                            path,
-                            recipient: if router_must_custody {
-                                Address::ZERO
-                            } else {
-                                recipient
-                            },
-                            amountOut: amount_out,
-                            amountInMaximum: amount_in,
-                        },
-                    }
-                    .abi_encode()
-                    .into(),
-                });
+                calldatas.push(encode_path_swap(
+                    swap_abi,
+                    deadline,
+                    trade.trade_type,
+                    path,
+                    swap_recipient,
+                    amount_in,
+                    amount_out,
+                ));
             }
         }
     }
 
+    let summary = SwapPlanSummary {
+        swaps: swap_limits,
+        router_custody: router_must_custody,
+        recipient,
+        total_amount_out_minimum: total_amount_out,
+        fee: fee.clone(),
+        refund_eth: must_refund,
+        value: big_int_to_u256(&total_value)?,
+    };
+
     // unwrap
     if router_must_custody {
-        if output_is_native {
-            calldatas.push(encode_unwrap_weth9(total_amount_out, recipient, fee));
-        } else {
-            calldatas.push(encode_sweep_token(
-                output_currency_address,
-                total_amount_out,
-                recipient,
-                fee,
-            ));
+        match (fee_currency, fee) {
+            (FeeCurrency::WrappedNative, Some(fee)) => {
+                // sweep the fee in WETH and the remainder to the router, then unwrap the remainder
+                let router = match router {
+                    Some(router) => router.address,
+                    None => *SWAP_ROUTER_02_ADDRESSES
+                        .get(&chain_id)
+                        .ok_or(Error::UnsupportedChain(chain_id))?,
+                };
+                let fee_amount = mul_div(
+                    total_amount_out,
+                    encode_fee_bips(fee.fee.clone()),
+                    U256::from(10_000),
+                )?;
+                calldatas.push(encode_sweep_token(
+                    token_out_address,
+                    total_amount_out,
+                    router,
+                    Some(fee),
+                ));
+                calldatas.push(encode_unwrap_weth9(
+                    total_amount_out - fee_amount,
+                    recipient,
+                    None,
+                ));
+            }
+            (_, fee) if output_is_native => {
+                calldatas.push(encode_unwrap_weth9(total_amount_out, recipient, fee));
+            }
+            (_, fee) => {
+                calldatas.push(encode_sweep_token(
+                    output_currency_address,
+                    total_amount_out,
+                    recipient,
+                    fee,
+                ));
+            }
         }
     }
 
@@ -213,10 +662,73 @@ where
         calldatas.push(encode_refund_eth());
     }
 
-    Ok(MethodParameters {
-        calldata: encode_multicall(calldatas),
-        value: U256::from_big_int(total_value),
-    })
+    let calldata = match multicall_wrapping {
+        MulticallWrapping::Auto => encode_multicall(&calldatas),
+        MulticallWrapping::Always => IMulticall::multicallCall { data: calldatas }
+            .abi_encode()
+            .into(),
+        MulticallWrapping::Never => {
+            if calldatas.len() != 1 {
+                return Err(Error::MulticallRequired(calldatas.len()));
+            }
+            calldatas.pop().unwrap()
+        }
+    };
+    if let Some(router) = router {
+        if let Some(selector) = call_selectors(&calldata)
+            .into_iter()
+            .find(|selector| !router.supports(*selector))
+        {
+            return Err(Error::UnsupportedRouterCall(selector));
+        }
+    }
+    Ok((
+        MethodParameters {
+            calldata,
+            value: summary.value,
+        },
+        summary,
+    ))
+}
+
+/// Validates that the native input and output of a route are consistent with the swap options, for
+/// integrations that encode the router calls themselves instead of using [`swap_call_parameters`].
+///
+/// The router must take custody of the output, i.e. the swap must be sent to [`Address::ZERO`],
+/// when the output is native, a fee is taken or `sweep_output` is set. Conversely, sending the
+/// output to the router when none of them applies leaves the funds stuck in the router.
+///
+/// ## Arguments
+///
+/// * `route`: The route of the swap
+/// * `options`: The options the calls are encoded with
+/// * `swap_recipient`: The recipient encoded in the swap call
+#[inline]
+pub fn validate_swap_route<TInput, TOutput, TP>(
+    route: &Route<TInput, TOutput, TP>,
+    options: &SwapOptions,
+    swap_recipient: Address,
+) -> Result<(), Error>
+where
+    TInput: BaseCurrency,
+    TOutput: BaseCurrency,
+    TP: TickDataProvider,
+{
+    if requires_weth9(&route.input) && options.input_token_permit.is_some() {
+        return Err(Error::NativeCurrencyMismatch);
+    }
+    if let Some(fee) = &options.fee {
+        validate_fee_options(fee)?;
+        if swap_recipient != Address::ZERO {
+            return Err(Error::FeeWithoutCustody);
+        }
+    }
+    let router_must_custody =
+        requires_weth9(&route.output) || options.fee.is_some() || options.sweep_output.is_some();
+    if router_must_custody != (swap_recipient == Address::ZERO) {
+        return Err(Error::NativeCurrencyMismatch);
+    }
+    Ok(())
 }
 
 #[cfg(test)]
@@ -247,8 +759,16 @@ mod tests {
         slippage_tolerance: SLIPPAGE_TOLERANCE.clone(),
         recipient: RECIPIENT,
         input_token_permit: None,
+        input_token_permit2: None,
         sqrt_price_limit_x96: None,
         fee: None,
+        sweep_output: None,
+        multicall_wrapping: MulticallWrapping::Auto,
+        trade_recipients: Vec::new(),
+        fee_currency: FeeCurrency::Output,
+        router: None,
+        deadline: None,
+        derive_sqrt_price_limit: false,
     });
 
     mod single_trade_input {
@@ -462,6 +982,676 @@ mod tests {
             assert_eq!(calldata.to_vec(), hex!("ac9650d8000000000000000000000000000000000000000000000000000000000000002000000000000000000000000000000000000000000000000000000000000000020000000000000000000000000000000000000000000000000000000000000040000000000000000000000000000000000000000000000000000000000000016000000000000000000000000000000000000000000000000000000000000000e404e45aaf000000000000000000000000000000000000000000000000000000000000000100000000000000000000000000000000000000000000000000000000000000020000000000000000000000000000000000000000000000000000000000000bb800000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000064000000000000000000000000000000000000000000000000000000000000006100000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000a4e0e189a00000000000000000000000000000000000000000000000000000000000000002000000000000000000000000000000000000000000000000000000000000006100000000000000000000000000000000000000000000000000000000000000030000000000000000000000000000000000000000000000000000000000000032000000000000000000000000000000000000000000000000000000000000000300000000000000000000000000000000000000000000000000000000"));
             assert_eq!(value, U256::ZERO);
         }
+        #[test]
+        fn sweep_output() {
+            let trade = Trade::from_route(
+                Route::new(vec![POOL_0_1.clone()], TOKEN0.clone(), TOKEN1.clone()),
+                CurrencyAmount::from_raw_amount(TOKEN0.clone(), 100).unwrap(),
+                TradeType::ExactInput,
+            )
+            .unwrap();
+            let MethodParameters { calldata, value } = swap_call_parameters(
+                &mut [trade.clone()],
+                SwapOptions {
+                    sweep_output: Some(RECIPIENT),
+                    ..SWAP_OPTIONS.clone()
+                },
+            )
+            .unwrap();
+            assert_eq!(calldata.to_vec(), hex!("ac9650d8000000000000000000000000000000000000000000000000000000000000002000000000000000000000000000000000000000000000000000000000000000020000000000000000000000000000000000000000000000000000000000000040000000000000000000000000000000000000000000000000000000000000016000000000000000000000000000000000000000000000000000000000000000e404e45aaf000000000000000000000000000000000000000000000000000000000000000100000000000000000000000000000000000000000000000000000000000000020000000000000000000000000000000000000000000000000000000000000bb80000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000006400000000000000000000000000000000000000000000000000000000000000610000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000064df2ab5bb00000000000000000000000000000000000000000000000000000000000000020000000000000000000000000000000000000000000000000000000000000061000000000000000000000000000000000000000000000000000000000000000300000000000000000000000000000000000000000000000000000000"));
+            assert_eq!(value, U256::ZERO);
+
+            // the output is swept to a hook target instead of the recipient
+            let hook = address!("0000000000000000000000000000000000000007");
+            let (MethodParameters { calldata, .. }, summary) = swap_call_parameters_with_summary(
+                &mut [trade],
+                SwapOptions {
+                    sweep_output: Some(hook),
+                    ..SWAP_OPTIONS.clone()
+                },
+            )
+            .unwrap();
+            let calls = <Vec<Bytes>>::decode_multicall(calldata).unwrap();
+            let sweep =
+                IPeripheryPaymentsWithFee::sweepTokenCall::abi_decode(&calls[1], true).unwrap();
+            assert_eq!(sweep.recipient, hook);
+            assert_eq!(summary.recipient, hook);
+            assert!(summary.router_custody);
+        }
+        #[test]
+        fn always_wrap_in_multicall() {
+            let trade = Trade::from_route(
+                Route::new(vec![POOL_0_1.clone()], TOKEN0.clone(), TOKEN1.clone()),
+                CurrencyAmount::from_raw_amount(TOKEN0.clone(), 100).unwrap(),
+                TradeType::ExactInput,
+            )
+            .unwrap();
+            let bare = swap_call_parameters(&mut [trade.clone()], SWAP_OPTIONS.clone()).unwrap();
+            let wrapped = swap_call_parameters(
+                &mut [trade],
+                SwapOptions {
+                    multicall_wrapping: MulticallWrapping::Always,
+                    ..SWAP_OPTIONS.clone()
+                },
+            )
+            .unwrap();
+            let decoded = <Vec<Bytes>>::decode_multicall(wrapped.calldata).unwrap();
+            assert_eq!(decoded, vec![bare.calldata]);
+            assert_eq!(wrapped.value, bare.value);
+        }
+
+        #[test]
+        fn never_wrap_in_multicall() {
+            let trade = Trade::from_route(
+                Route::new(vec![POOL_1_WETH.clone()], TOKEN1.clone(), ETHER.clone()),
+                CurrencyAmount::from_raw_amount(TOKEN1.clone(), 100).unwrap(),
+                TradeType::ExactInput,
+            )
+            .unwrap();
+            // unwrapping the WETH output needs a second call
+            let result = swap_call_parameters(
+                &mut [trade],
+                SwapOptions {
+                    multicall_wrapping: MulticallWrapping::Never,
+                    ..SWAP_OPTIONS.clone()
+                },
+            );
+            assert!(matches!(result, Err(Error::MulticallRequired(2))));
+        }
+    }
+
+    mod summary {
+        use super::*;
+
+        #[test]
+        fn eth_out_with_fee() {
+            let trade = Trade::from_route(
+                Route::new(vec![POOL_1_WETH.clone()], TOKEN1.clone(), ETHER.clone()),
+                CurrencyAmount::from_raw_amount(TOKEN1.clone(), 100).unwrap(),
+                TradeType::ExactInput,
+            )
+            .unwrap();
+            let fee = FeeOptions {
+                fee: Percent::new(5, 1000),
+                recipient: RECIPIENT,
+            };
+            let options = SwapOptions {
+                fee: Some(fee.clone()),
+                ..SWAP_OPTIONS.clone()
+            };
+            let (params, summary) =
+                swap_call_parameters_with_summary(&mut [trade.clone()], options.clone()).unwrap();
+            assert_eq!(params, swap_call_parameters(&mut [trade], options).unwrap());
+            assert_eq!(
+                summary,
+                SwapPlanSummary {
+                    swaps: vec![SwapLimits {
+                        token_in: TOKEN1.address(),
+                        token_out: WETH.address(),
+                        pool_fees: vec![FeeAmount::MEDIUM],
+                        amount_in_maximum: uint!(100_U256),
+                        amount_out_minimum: uint!(0x61_U256),
+                        fee_amount: U256::ZERO,
+                        recipient: Address::ZERO,
+                    }],
+                    router_custody: true,
+                    recipient: RECIPIENT,
+                    total_amount_out_minimum: uint!(0x61_U256),
+                    fee: Some(fee),
+                    refund_eth: false,
+                    value: U256::ZERO,
+                }
+            );
+        }
+
+        #[test]
+        fn fee_amount_per_swap() {
+            let trade = Trade::from_route(
+                Route::new(vec![POOL_1_WETH.clone()], TOKEN1.clone(), ETHER.clone()),
+                CurrencyAmount::from_raw_amount(TOKEN1.clone(), 1_000_000).unwrap(),
+                TradeType::ExactInput,
+            )
+            .unwrap();
+            let options = SwapOptions {
+                fee: Some(FeeOptions {
+                    fee: Percent::new(5, 1000),
+                    recipient: RECIPIENT,
+                }),
+                ..SWAP_OPTIONS.clone()
+            };
+            let (_, summary) = swap_call_parameters_with_summary(&mut [trade], options).unwrap();
+            let swap = &summary.swaps[0];
+            assert!(!swap.fee_amount.is_zero());
+            assert_eq!(
+                swap.fee_amount,
+                swap.amount_out_minimum * U256::from(50) / U256::from(10_000)
+            );
+        }
+    }
+
+    mod fee_currency {
+        use super::*;
+
+        const ROUTER: Address = address!("0000000000000000000000000000000000000005");
+
+        fn eth_out_trade() -> Trade<Token, Ether, TickListDataProvider> {
+            Trade::from_route(
+                Route::new(vec![POOL_1_WETH.clone()], TOKEN1.clone(), ETHER.clone()),
+                CurrencyAmount::from_raw_amount(TOKEN1.clone(), 100).unwrap(),
+                TradeType::ExactInput,
+            )
+            .unwrap()
+        }
+
+        #[test]
+        fn fee_in_weth_when_eth_out() {
+            let fee = FeeOptions {
+                fee: Percent::new(5, 1000),
+                recipient: RECIPIENT,
+            };
+            let options = SwapOptions {
+                fee: Some(fee.clone()),
+                fee_currency: FeeCurrency::WrappedNative,
+                router: Some(RouterTarget {
+                    address: ROUTER,
+                    ..Default::default()
+                }),
+                ..SWAP_OPTIONS.clone()
+            };
+            let MethodParameters { calldata, value } =
+                swap_call_parameters(&mut [eth_out_trade()], options).unwrap();
+            assert_eq!(value, U256::ZERO);
+            let calls = IMulticall::multicallCall::abi_decode(&calldata, true)
+                .unwrap()
+                .data;
+            assert_eq!(calls.len(), 3);
+            assert_eq!(
+                calls[1],
+                encode_sweep_token(WETH.address(), uint!(0x61_U256), ROUTER, Some(fee))
+            );
+            // the fee of 50 bips on 0x61 rounds down to zero
+            assert_eq!(
+                calls[2],
+                encode_unwrap_weth9(uint!(0x61_U256), RECIPIENT, None)
+            );
+        }
+
+        #[test]
+        fn fee_in_weth_swept_to_canonical_router() {
+            let fee = FeeOptions {
+                fee: Percent::new(5, 1000),
+                recipient: RECIPIENT,
+            };
+            let options = SwapOptions {
+                fee: Some(fee.clone()),
+                fee_currency: FeeCurrency::WrappedNative,
+                ..SWAP_OPTIONS.clone()
+            };
+            let MethodParameters { calldata, .. } =
+                swap_call_parameters(&mut [eth_out_trade()], options).unwrap();
+            let calls = IMulticall::multicallCall::abi_decode(&calldata, true)
+                .unwrap()
+                .data;
+            assert_eq!(
+                calls[1],
+                encode_sweep_token(
+                    WETH.address(),
+                    uint!(0x61_U256),
+                    SWAP_ROUTER_02_ADDRESSES[&1],
+                    Some(fee)
+                )
+            );
+        }
+
+        #[test]
+        fn rejects_fee_in_weth_without_eth_out() {
+            let trade = Trade::from_route(
+                Route::new(vec![POOL_0_1.clone()], TOKEN0.clone(), TOKEN1.clone()),
+                CurrencyAmount::from_raw_amount(TOKEN0.clone(), 100).unwrap(),
+                TradeType::ExactInput,
+            )
+            .unwrap();
+            let options = SwapOptions {
+                fee: Some(FeeOptions {
+                    fee: Percent::new(5, 1000),
+                    recipient: RECIPIENT,
+                }),
+                fee_currency: FeeCurrency::WrappedNative,
+                ..SWAP_OPTIONS.clone()
+            };
+            assert!(matches!(
+                swap_call_parameters(&mut [trade], options),
+                Err(Error::InvalidFeeCurrency)
+            ));
+            let options = SwapOptions {
+                fee_currency: FeeCurrency::WrappedNative,
+                ..SWAP_OPTIONS.clone()
+            };
+            assert!(matches!(
+                swap_call_parameters(&mut [eth_out_trade()], options),
+                Err(Error::InvalidFeeCurrency)
+            ));
+        }
+
+        #[test]
+        fn rejects_invalid_fee() {
+            let options = SwapOptions {
+                fee: Some(FeeOptions {
+                    fee: Percent::new(5, 1000),
+                    recipient: Address::ZERO,
+                }),
+                ..SWAP_OPTIONS.clone()
+            };
+            assert!(matches!(
+                swap_call_parameters(&mut [eth_out_trade()], options),
+                Err(Error::InvalidFeeRecipient)
+            ));
+            let options = SwapOptions {
+                fee: Some(FeeOptions {
+                    fee: Percent::new(5, 100),
+                    recipient: RECIPIENT,
+                }),
+                ..SWAP_OPTIONS.clone()
+            };
+            assert!(matches!(
+                swap_call_parameters(&mut [eth_out_trade()], options),
+                Err(Error::InvalidFeeBips(_))
+            ));
+        }
+    }
+
+    mod trade_recipients {
+        use super::*;
+
+        const OTHER_RECIPIENT: Address = address!("0000000000000000000000000000000000000004");
+
+        fn trades() -> [Trade<Token, Token, TickListDataProvider>; 2] {
+            let trade = Trade::from_route(
+                Route::new(vec![POOL_0_1.clone()], TOKEN0.clone(), TOKEN1.clone()),
+                CurrencyAmount::from_raw_amount(TOKEN0.clone(), 100).unwrap(),
+                TradeType::ExactInput,
+            )
+            .unwrap();
+            [trade.clone(), trade]
+        }
+
+        #[test]
+        fn overrides_recipient_per_trade() {
+            let options = SwapOptions {
+                trade_recipients: vec![None, Some(OTHER_RECIPIENT)],
+                ..SWAP_OPTIONS.clone()
+            };
+            let (_, summary) = swap_call_parameters_with_summary(&mut trades(), options).unwrap();
+            let recipients: Vec<Address> =
+                summary.swaps.iter().map(|swap| swap.recipient).collect();
+            assert_eq!(recipients, vec![RECIPIENT, OTHER_RECIPIENT]);
+        }
+
+        #[test]
+        fn rejects_overrides_with_custody() {
+            let options = SwapOptions {
+                trade_recipients: vec![Some(OTHER_RECIPIENT)],
+                sweep_output: Some(RECIPIENT),
+                ..SWAP_OPTIONS.clone()
+            };
+            assert!(matches!(
+                swap_call_parameters(&mut trades(), options),
+                Err(Error::InvalidRecipientOverride)
+            ));
+        }
+
+        #[test]
+        fn rejects_more_overrides_than_trades() {
+            let options = SwapOptions {
+                trade_recipients: vec![None, None, Some(OTHER_RECIPIENT)],
+                ..SWAP_OPTIONS.clone()
+            };
+            assert!(matches!(
+                swap_call_parameters(&mut trades(), options),
+                Err(Error::InvalidRecipientOverride)
+            ));
+        }
+    }
+
+    mod router_target {
+        use super::*;
+
+        const ROUTER: Address = address!("0000000000000000000000000000000000000005");
+
+        fn trade<TOutput: BaseCurrency>(
+            pool: &Pool<TickListDataProvider>,
+            output: TOutput,
+        ) -> Trade<Token, TOutput, TickListDataProvider> {
+            Trade::from_route(
+                Route::new(vec![pool.clone()], TOKEN1.clone(), output),
+                CurrencyAmount::from_raw_amount(TOKEN1.clone(), 100).unwrap(),
+                TradeType::ExactInput,
+            )
+            .unwrap()
+        }
+
+        fn options(variant: RouterVariant) -> SwapOptions {
+            SwapOptions {
+                router: Some(RouterTarget {
+                    address: ROUTER,
+                    variant,
+                    ..Default::default()
+                }),
+                ..SWAP_OPTIONS.clone()
+            }
+        }
+
+        #[test]
+        fn rejects_permit2() {
+            let permit = Permit2Permit {
+                permit: Permit2Single {
+                    details: IAllowanceTransfer::PermitDetails {
+                        token: TOKEN1.address(),
+                        amount: U160::MAX,
+                        expiration: Default::default(),
+                        nonce: Default::default(),
+                    },
+                    spender: ROUTER,
+                    sigDeadline: U256::MAX,
+                },
+                signature: alloy_primitives::PrimitiveSignature::new(
+                    U256::from(1),
+                    U256::from(2),
+                    false,
+                ),
+            };
+            let err = swap_call_parameters(
+                &mut [trade(&POOL_0_1, TOKEN0.clone())],
+                SwapOptions {
+                    input_token_permit2: Some(Permit2Options::Single(permit)),
+                    ..SWAP_OPTIONS.clone()
+                },
+            )
+            .unwrap_err();
+            assert!(matches!(err, Error::UnsupportedRouterCall(selector)
+                if selector.0 == IAllowanceTransfer::permit_0Call::SELECTOR));
+        }
+
+        #[test]
+        fn swap_router_abi_encodes_deadline() {
+            let deadline = U256::from(1_700_000_000);
+            let params = swap_call_parameters(
+                &mut [trade(&POOL_0_1, TOKEN0.clone())],
+                SwapOptions {
+                    router: Some(RouterTarget {
+                        address: ROUTER,
+                        variant: RouterVariant::SwapOnly,
+                        swap_abi: SwapAbi::SwapRouter,
+                    }),
+                    deadline: Some(deadline),
+                    ..SWAP_OPTIONS.clone()
+                },
+            )
+            .unwrap();
+            let expected = IV3SwapRouter::exactInputSingleCall::abi_decode(
+                &swap_call_parameters(
+                    &mut [trade(&POOL_0_1, TOKEN0.clone())],
+                    SWAP_OPTIONS.clone(),
+                )
+                .unwrap()
+                .calldata,
+                true,
+            )
+            .unwrap()
+            .params;
+            let call =
+                ISwapRouter::exactInputSingleCall::abi_decode(&params.calldata, true).unwrap();
+            assert_eq!(
+                call.params,
+                ISwapRouter::ExactInputSingleParams {
+                    tokenIn: expected.tokenIn,
+                    tokenOut: expected.tokenOut,
+                    fee: expected.fee,
+                    recipient: expected.recipient,
+                    deadline,
+                    amountIn: expected.amountIn,
+                    amountOutMinimum: expected.amountOutMinimum,
+                    sqrtPriceLimitX96: expected.sqrtPriceLimitX96,
+                }
+            );
+        }
+
+        #[test]
+        fn swap_router_abi_rejects_v3_swap_router_selectors() {
+            let router = RouterTarget {
+                address: ROUTER,
+                variant: RouterVariant::SwapRouter02,
+                swap_abi: SwapAbi::SwapRouter,
+            };
+            assert!(router.supports(ISwapRouter::exactInputCall::SELECTOR.into()));
+            assert!(router.supports(IMulticall::multicallCall::SELECTOR.into()));
+            assert!(!router.supports(IV3SwapRouter::exactInputCall::SELECTOR.into()));
+
+            // the original `SwapRouter` has neither the extended payments nor the extended `multicall`
+            let router = RouterTarget {
+                variant: RouterVariant::SwapRouter,
+                ..router
+            };
+            assert!(router.supports(ISwapRouter::exactInputCall::SELECTOR.into()));
+            assert!(router.supports(IMulticall::multicallCall::SELECTOR.into()));
+            assert!(router.supports(IPeripheryPaymentsWithFee::unwrapWETH9Call::SELECTOR.into()));
+            assert!(!router.supports(IMulticallExtended::multicallCall::SELECTOR.into()));
+            assert!(!router.supports(IPeripheryPaymentsExtended::unwrapWETH9Call::SELECTOR.into()));
+            assert!(!router.supports(IV3SwapRouter::exactInputCall::SELECTOR.into()));
+        }
+
+        #[test]
+        fn swap_router_abi_requires_deadline() {
+            let err = swap_call_parameters(
+                &mut [trade(&POOL_0_1, TOKEN0.clone())],
+                SwapOptions {
+                    router: Some(RouterTarget {
+                        address: ROUTER,
+                        variant: RouterVariant::SwapRouter,
+                        swap_abi: SwapAbi::SwapRouter,
+                    }),
+                    ..SWAP_OPTIONS.clone()
+                },
+            )
+            .unwrap_err();
+            assert!(matches!(err, Error::DeadlineRequired));
+        }
+
+        #[test]
+        fn swap_only_router_accepts_bare_swaps() {
+            let expected = swap_call_parameters(
+                &mut [trade(&POOL_0_1, TOKEN0.clone())],
+                SWAP_OPTIONS.clone(),
+            )
+            .unwrap();
+            let params = swap_call_parameters(
+                &mut [trade(&POOL_0_1, TOKEN0.clone())],
+                options(RouterVariant::SwapOnly),
+            )
+            .unwrap();
+            assert_eq!(params, expected);
+            assert_eq!(
+                call_selectors(&params.calldata),
+                vec![Selector::from(
+                    IV3SwapRouter::exactInputSingleCall::SELECTOR
+                )]
+            );
+        }
+
+        #[test]
+        fn swap_only_router_rejects_unwrap() {
+            let err = swap_call_parameters(
+                &mut [trade(&POOL_1_WETH, ETHER.clone())],
+                options(RouterVariant::SwapOnly),
+            )
+            .unwrap_err();
+            assert!(matches!(err, Error::UnsupportedRouterCall(selector)
+                if selector.0 == IMulticall::multicallCall::SELECTOR));
+
+            let params = swap_call_parameters(
+                &mut [trade(&POOL_1_WETH, ETHER.clone())],
+                options(RouterVariant::SwapRouter02),
+            )
+            .unwrap();
+            assert_eq!(
+                call_selectors(&params.calldata),
+                vec![
+                    Selector::from(IMulticall::multicallCall::SELECTOR),
+                    IV3SwapRouter::exactInputSingleCall::SELECTOR.into(),
+                    IPeripheryPaymentsWithFee::unwrapWETH9Call::SELECTOR.into(),
+                ]
+            );
+        }
+    }
+
+    mod derive_sqrt_price_limit {
+        use super::*;
+
+        fn options() -> SwapOptions {
+            SwapOptions {
+                derive_sqrt_price_limit: true,
+                ..SWAP_OPTIONS.clone()
+            }
+        }
+
+        #[test]
+        fn derives_limit_of_single_hop_exact_input() {
+            let amount_in = CurrencyAmount::from_raw_amount(TOKEN0.clone(), 100).unwrap();
+            let trade = Trade::from_route(
+                Route::new(vec![POOL_0_1.clone()], TOKEN0.clone(), TOKEN1.clone()),
+                amount_in.clone(),
+                TradeType::ExactInput,
+            )
+            .unwrap();
+            let params = swap_call_parameters(&mut [trade], options()).unwrap();
+            let call =
+                IV3SwapRouter::exactInputSingleCall::abi_decode(&params.calldata, true).unwrap();
+            let expected = sqrt_price_limit_from_slippage(
+                POOL_0_1
+                    .quote_output(&amount_in, None)
+                    .unwrap()
+                    .sqrt_price_x96_after,
+                true,
+                &SLIPPAGE_TOLERANCE,
+            );
+            assert_eq!(call.params.sqrtPriceLimitX96, expected);
+            assert!(expected < POOL_0_1.sqrt_ratio_x96);
+        }
+
+        #[test]
+        fn no_limit_of_single_hop_exact_output() {
+            let trade = Trade::from_route(
+                Route::new(vec![POOL_0_1.clone()], TOKEN1.clone(), TOKEN0.clone()),
+                CurrencyAmount::from_raw_amount(TOKEN0.clone(), 100).unwrap(),
+                TradeType::ExactOutput,
+            )
+            .unwrap();
+            let params = swap_call_parameters(&mut [trade.clone()], options()).unwrap();
+            let call =
+                IV3SwapRouter::exactOutputSingleCall::abi_decode(&params.calldata, true).unwrap();
+            assert_eq!(call.params.sqrtPriceLimitX96, U160::ZERO);
+            assert_eq!(
+                params,
+                swap_call_parameters(&mut [trade], SWAP_OPTIONS.clone()).unwrap()
+            );
+        }
+
+        #[test]
+        fn keeps_explicit_limit_and_multihop() {
+            let limit = U160::from(1) << 90;
+            let trade = Trade::from_route(
+                Route::new(vec![POOL_0_1.clone()], TOKEN0.clone(), TOKEN1.clone()),
+                CurrencyAmount::from_raw_amount(TOKEN0.clone(), 100).unwrap(),
+                TradeType::ExactInput,
+            )
+            .unwrap();
+            let params = swap_call_parameters(
+                &mut [trade],
+                SwapOptions {
+                    sqrt_price_limit_x96: Some(limit),
+                    ..options()
+                },
+            )
+            .unwrap();
+            let call =
+                IV3SwapRouter::exactInputSingleCall::abi_decode(&params.calldata, true).unwrap();
+            assert_eq!(call.params.sqrtPriceLimitX96, limit);
+
+            let trade = Trade::from_route(
+                Route::new(
+                    vec![POOL_0_1.clone(), POOL_1_WETH.clone()],
+                    TOKEN0.clone(),
+                    WETH.clone(),
+                ),
+                CurrencyAmount::from_raw_amount(TOKEN0.clone(), 100).unwrap(),
+                TradeType::ExactInput,
+            )
+            .unwrap();
+            assert_eq!(
+                swap_call_parameters(&mut [trade.clone()], options()).unwrap(),
+                swap_call_parameters(&mut [trade], SWAP_OPTIONS.clone()).unwrap()
+            );
+        }
+    }
+
+    mod validate_swap_route {
+        use super::*;
+
+        #[test]
+        fn token_output_to_recipient() {
+            let route = Route::new(vec![POOL_0_1.clone()], TOKEN0.clone(), TOKEN1.clone());
+            assert!(validate_swap_route(&route, &SWAP_OPTIONS, RECIPIENT).is_ok());
+            assert!(matches!(
+                validate_swap_route(&route, &SWAP_OPTIONS, Address::ZERO),
+                Err(Error::NativeCurrencyMismatch)
+            ));
+        }
+
+        #[test]
+        fn eth_output_requires_custody() {
+            let route = Route::new(vec![POOL_1_WETH.clone()], TOKEN1.clone(), ETHER.clone());
+            assert!(validate_swap_route(&route, &SWAP_OPTIONS, Address::ZERO).is_ok());
+            assert!(matches!(
+                validate_swap_route(&route, &SWAP_OPTIONS, RECIPIENT),
+                Err(Error::NativeCurrencyMismatch)
+            ));
+        }
+
+        #[test]
+        fn fee_requires_custody() {
+            let route = Route::new(vec![POOL_0_1.clone()], TOKEN0.clone(), TOKEN1.clone());
+            let options = SwapOptions {
+                fee: Some(FeeOptions {
+                    fee: Percent::new(5, 1000),
+                    recipient: RECIPIENT,
+                }),
+                ..SWAP_OPTIONS.clone()
+            };
+            assert!(validate_swap_route(&route, &options, Address::ZERO).is_ok());
+            assert!(matches!(
+                validate_swap_route(&route, &options, RECIPIENT),
+                Err(Error::FeeWithoutCustody)
+            ));
+        }
+
+        #[test]
+        fn eth_input_with_permit() {
+            let route = Route::new(vec![POOL_1_WETH.clone()], ETHER.clone(), TOKEN1.clone());
+            let options = SwapOptions {
+                input_token_permit: Some(PermitOptions::Standard(StandardPermitArguments::new(
+                    U256::ZERO,
+                    U256::ZERO,
+                    false,
+                    U256::ZERO,
+                    U256::ZERO,
+                ))),
+                ..SWAP_OPTIONS.clone()
+            };
+            assert!(matches!(
+                validate_swap_route(&route, &options, RECIPIENT),
+                Err(Error::NativeCurrencyMismatch)
+            ));
+        }
     }
 
     mod multiple_trade_input {
@@ -1096,4 +2286,84 @@ mod tests {
             assert_eq!(value, U256::ZERO);
         }
     }
+
+    mod native_currency {
+        use super::*;
+        use uniswap_sdk_core::token;
+
+        static CELO: Lazy<Ether> = Lazy::new(|| native_currency(42220));
+        static CUSD: Lazy<Token> = Lazy::new(|| {
+            token!(
+                42220,
+                "765DE816845861e75A25fCA122bb6898B8B1282a",
+                18,
+                "cUSD"
+            )
+        });
+        static POOL_CELO_CUSD: Lazy<Pool<TickListDataProvider>> =
+            Lazy::new(|| make_pool(CELO.wrapped().clone(), CUSD.clone()));
+
+        #[test]
+        fn celo_in_is_paid_as_erc20() {
+            let trade = Trade::from_route(
+                Route::new(vec![POOL_CELO_CUSD.clone()], CELO.clone(), CUSD.clone()),
+                CurrencyAmount::from_raw_amount(CUSD.clone(), 100).unwrap(),
+                TradeType::ExactOutput,
+            )
+            .unwrap();
+            let params = swap_call_parameters(&mut [trade], SWAP_OPTIONS.clone()).unwrap();
+            assert_eq!(params.value, U256::ZERO);
+            // no refundETH, as no value is sent
+            assert_eq!(
+                call_selectors(&params.calldata),
+                vec![Selector::from(
+                    IV3SwapRouter::exactOutputSingleCall::SELECTOR
+                )]
+            );
+            let call =
+                IV3SwapRouter::exactOutputSingleCall::abi_decode(&params.calldata, true).unwrap();
+            assert_eq!(call.params.tokenIn, CELO.wrapped().address());
+            assert_eq!(call.params.recipient, RECIPIENT);
+        }
+
+        #[test]
+        fn celo_out_is_not_unwrapped() {
+            let trade = Trade::from_route(
+                Route::new(vec![POOL_CELO_CUSD.clone()], CUSD.clone(), CELO.clone()),
+                CurrencyAmount::from_raw_amount(CUSD.clone(), 100).unwrap(),
+                TradeType::ExactInput,
+            )
+            .unwrap();
+            let params = swap_call_parameters(&mut [trade], SWAP_OPTIONS.clone()).unwrap();
+            assert_eq!(params.value, U256::ZERO);
+            assert_eq!(
+                call_selectors(&params.calldata),
+                vec![Selector::from(
+                    IV3SwapRouter::exactInputSingleCall::SELECTOR
+                )]
+            );
+            let call =
+                IV3SwapRouter::exactInputSingleCall::abi_decode(&params.calldata, true).unwrap();
+            assert_eq!(call.params.tokenOut, CELO.wrapped().address());
+            assert_eq!(call.params.recipient, RECIPIENT);
+        }
+
+        #[test]
+        fn matic_in_is_wrapped() {
+            let matic = native_currency(137);
+            let usdc = token!(137, "3c499c542cEF5E3811e1192ce70d8cC03d5c3359", 6, "USDC");
+            let pool = make_pool(matic.wrapped().clone(), usdc.clone());
+            let trade = Trade::from_route(
+                Route::new(vec![pool], matic.clone(), usdc),
+                CurrencyAmount::from_raw_amount(matic.clone(), 100).unwrap(),
+                TradeType::ExactInput,
+            )
+            .unwrap();
+            let params = swap_call_parameters(&mut [trade], SWAP_OPTIONS.clone()).unwrap();
+            assert_eq!(params.value, uint!(100_U256));
+            let call =
+                IV3SwapRouter::exactInputSingleCall::abi_decode(&params.calldata, true).unwrap();
+            assert_eq!(call.params.tokenIn, WETH9::on_chain(137).unwrap().address());
+        }
+    }
 }
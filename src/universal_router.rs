@@ -0,0 +1,446 @@
+//! ## Universal Router
+//! Encodes V3 swaps for the Universal Router, which pulls the input through `Permit2` allowances
+//! and can spend a signed [`Permit2Options`] permit in the same transaction.
+
+use crate::prelude::{Error, *};
+use alloc::vec::Vec;
+use alloy_primitives::{Bytes, U256};
+use alloy_sol_types::{SolCall, SolValue};
+use uniswap_sdk_core::prelude::*;
+
+/// The commands of the Universal Router used by [`universal_router_swap_call_parameters`].
+pub mod commands {
+    pub const V3_SWAP_EXACT_IN: u8 = 0x00;
+    pub const V3_SWAP_EXACT_OUT: u8 = 0x01;
+    pub const PERMIT2_PERMIT_BATCH: u8 = 0x03;
+    pub const PERMIT2_PERMIT: u8 = 0x0a;
+    pub const WRAP_ETH: u8 = 0x0b;
+    pub const UNWRAP_WETH: u8 = 0x0c;
+}
+
+/// Produces the calldata of `execute` on the Universal Router for the given V3 trades.
+///
+/// The input is paid from the `Permit2` allowance of the sender, set by the optional
+/// [`SwapOptions::input_token_permit2`], or wrapped from the ether sent with the call if native. A
+/// native output is unwrapped to the recipient, and any ether not spent by exact output trades is
+/// refunded to the sender. Of the other options, only the slippage tolerance and the recipient
+/// apply to the Universal Router.
+///
+/// ## Arguments
+///
+/// * `trades`: trades to produce call parameters for
+/// * `options`: options for the call parameters
+/// * `deadline`: When the transaction expires, in epoch seconds
+#[inline]
+pub fn universal_router_swap_call_parameters<TInput, TOutput, TP>(
+    trades: &mut [Trade<TInput, TOutput, TP>],
+    options: SwapOptions,
+    deadline: U256,
+) -> Result<MethodParameters, Error>
+where
+    TInput: BaseCurrency,
+    TOutput: BaseCurrency,
+    TP: TickDataProvider,
+{
+    let SwapOptions {
+        slippage_tolerance,
+        recipient,
+        input_token_permit,
+        input_token_permit2,
+        ..
+    } = options;
+    // the Universal Router only spends `Permit2` allowances
+    if input_token_permit.is_some() {
+        return Err(Error::UnsupportedRouterCall(
+            ISelfPermit::selfPermitCall::SELECTOR.into(),
+        ));
+    }
+    let sample_trade = trades.first().ok_or(Error::NoTrades)?;
+    let token_in = sample_trade.input_currency().wrapped().clone();
+    let input_is_native = requires_weth9(sample_trade.input_currency());
+    let output_is_native = requires_weth9(sample_trade.output_currency());
+    let trade_type = sample_trade.trade_type;
+    for trade in trades.iter() {
+        assert!(
+            trade.input_currency().wrapped().equals(&token_in),
+            "TOKEN_IN_DIFF"
+        );
+        assert!(
+            trade
+                .output_currency()
+                .wrapped()
+                .equals(sample_trade.output_currency().wrapped()),
+            "TOKEN_OUT_DIFF"
+        );
+    }
+
+    let mut command_ids: Vec<u8> = Vec::with_capacity(trades.len() + 3);
+    let mut inputs: Vec<Bytes> = Vec::with_capacity(trades.len() + 3);
+
+    if let Some(permit) = input_token_permit2 {
+        assert!(!input_is_native, "NON_TOKEN_PERMIT");
+        match permit {
+            Permit2Options::Single(permit) => {
+                if permit.permit.details.token != token_in.address() {
+                    return Err(Error::CurrencyMismatch {
+                        expected: token_in.address(),
+                        actual: permit.permit.details.token,
+                    });
+                }
+                command_ids.push(commands::PERMIT2_PERMIT);
+                inputs.push(
+                    (permit.permit, encode_permit2_signature(&permit.signature))
+                        .abi_encode_params()
+                        .into(),
+                );
+            }
+            Permit2Options::Batch(permit) => {
+                if !permit
+                    .permit
+                    .details
+                    .iter()
+                    .any(|details| details.token == token_in.address())
+                {
+                    return Err(Error::CurrencyMismatch {
+                        expected: token_in.address(),
+                        actual: permit
+                            .permit
+                            .details
+                            .first()
+                            .map_or(Address::ZERO, |details| details.token),
+                    });
+                }
+                command_ids.push(commands::PERMIT2_PERMIT_BATCH);
+                inputs.push(
+                    (permit.permit, encode_permit2_signature(&permit.signature))
+                        .abi_encode_params()
+                        .into(),
+                );
+            }
+        }
+    }
+
+    let mut total_amount_in = BigInt::ZERO;
+    let mut total_amount_out = BigInt::ZERO;
+    for trade in trades.iter_mut() {
+        total_amount_in += trade
+            .maximum_amount_in_cached(slippage_tolerance.clone(), None)?
+            .quotient();
+        total_amount_out += trade
+            .minimum_amount_out_cached(slippage_tolerance.clone(), None)?
+            .quotient();
+    }
+    let total_amount_in = big_int_to_u256(&total_amount_in)?;
+    let total_amount_out = big_int_to_u256(&total_amount_out)?;
+
+    if input_is_native {
+        command_ids.push(commands::WRAP_ETH);
+        inputs.push((ADDRESS_THIS, total_amount_in).abi_encode_params().into());
+    }
+
+    let swap_recipient = if output_is_native {
+        ADDRESS_THIS
+    } else {
+        recipient
+    };
+    for trade in trades.iter() {
+        for Swap {
+            route,
+            input_amount,
+            output_amount,
+        } in &trade.swaps
+        {
+            let amount_in = big_int_to_u256(
+                &trade
+                    .maximum_amount_in(slippage_tolerance.clone(), Some(input_amount.clone()))?
+                    .quotient(),
+            )?;
+            let amount_out = big_int_to_u256(
+                &trade
+                    .minimum_amount_out(slippage_tolerance.clone(), Some(output_amount.clone()))?
+                    .quotient(),
+            )?;
+            let exact_output = trade.trade_type == TradeType::ExactOutput;
+            let path = encode_route_to_path(route, exact_output);
+            if exact_output {
+                command_ids.push(commands::V3_SWAP_EXACT_OUT);
+                inputs.push(
+                    (
+                        swap_recipient,
+                        amount_out,
+                        amount_in,
+                        path,
+                        !input_is_native,
+                    )
+                        .abi_encode_params()
+                        .into(),
+                );
+            } else {
+                command_ids.push(commands::V3_SWAP_EXACT_IN);
+                inputs.push(
+                    (
+                        swap_recipient,
+                        amount_in,
+                        amount_out,
+                        path,
+                        !input_is_native,
+                    )
+                        .abi_encode_params()
+                        .into(),
+                );
+            }
+        }
+    }
+
+    if output_is_native {
+        command_ids.push(commands::UNWRAP_WETH);
+        inputs.push((recipient, total_amount_out).abi_encode_params().into());
+    }
+    // refund the ether wrapped but not spent
+    if input_is_native && trade_type == TradeType::ExactOutput {
+        command_ids.push(commands::UNWRAP_WETH);
+        inputs.push((MSG_SENDER, U256::ZERO).abi_encode_params().into());
+    }
+
+    Ok(MethodParameters {
+        calldata: IUniversalRouter::executeCall {
+            commands: command_ids.into(),
+            inputs,
+            deadline,
+        }
+        .abi_encode()
+        .into(),
+        value: if input_is_native {
+            total_amount_in
+        } else {
+            U256::ZERO
+        },
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::tests::*;
+    use alloy_primitives::{address, aliases::U48, PrimitiveSignature, U160};
+
+    const RECIPIENT: Address = address!("0000000000000000000000000000000000000003");
+
+    const DEADLINE: U256 = U256::from_limbs([123, 0, 0, 0]);
+
+    fn options() -> SwapOptions {
+        SwapOptions {
+            slippage_tolerance: Percent::new(1, 100),
+            recipient: RECIPIENT,
+            ..Default::default()
+        }
+    }
+
+    fn trades() -> [Trade<Token, Token, TickListDataProvider>; 1] {
+        [Trade::from_route(
+            Route::new(
+                vec![make_pool(TOKEN0.clone(), TOKEN1.clone())],
+                TOKEN0.clone(),
+                TOKEN1.clone(),
+            ),
+            CurrencyAmount::from_raw_amount(TOKEN0.clone(), 100).unwrap(),
+            TradeType::ExactInput,
+        )
+        .unwrap()]
+    }
+
+    fn permit(token: Address) -> Permit2Permit {
+        Permit2Permit {
+            permit: Permit2Single {
+                details: IAllowanceTransfer::PermitDetails {
+                    token,
+                    amount: U160::MAX,
+                    expiration: U48::from(123),
+                    nonce: U48::ZERO,
+                },
+                spender: RECIPIENT,
+                sigDeadline: U256::from(123),
+            },
+            signature: PrimitiveSignature::new(U256::from(1), U256::from(2), false),
+        }
+    }
+
+    fn permit_batch(tokens: &[Address]) -> Permit2BatchPermit {
+        Permit2BatchPermit {
+            permit: Permit2Batch {
+                details: tokens
+                    .iter()
+                    .map(|&token| IAllowanceTransfer::PermitDetails {
+                        token,
+                        amount: U160::MAX,
+                        expiration: U48::from(123),
+                        nonce: U48::ZERO,
+                    })
+                    .collect(),
+                spender: RECIPIENT,
+                sigDeadline: U256::from(123),
+            },
+            signature: PrimitiveSignature::new(U256::from(1), U256::from(2), false),
+        }
+    }
+
+    #[test]
+    fn encodes_permit_and_swap() {
+        let mut trades = trades();
+        let params = universal_router_swap_call_parameters(
+            &mut trades,
+            SwapOptions {
+                input_token_permit2: Some(Permit2Options::Single(permit(TOKEN0.address()))),
+                ..options()
+            },
+            DEADLINE,
+        )
+        .unwrap();
+        assert_eq!(params.value, U256::ZERO);
+        let call = IUniversalRouter::executeCall::abi_decode(&params.calldata, true).unwrap();
+        assert_eq!(
+            call.commands.to_vec(),
+            vec![commands::PERMIT2_PERMIT, commands::V3_SWAP_EXACT_IN]
+        );
+        assert_eq!(call.deadline, U256::from(123));
+        let (decoded, signature) =
+            <(Permit2Single, Bytes)>::abi_decode_params(&call.inputs[0], true).unwrap();
+        assert_eq!(decoded, permit(TOKEN0.address()).permit);
+        assert_eq!(signature.len(), 65);
+        let (recipient, amount_in, _, path, payer_is_user) =
+            <(Address, U256, U256, Bytes, bool)>::abi_decode_params(&call.inputs[1], true).unwrap();
+        assert_eq!(recipient, RECIPIENT);
+        assert_eq!(amount_in, U256::from(100));
+        assert_eq!(path, encode_route_to_path(&trades[0].swaps[0].route, false));
+        assert!(payer_is_user);
+    }
+
+    #[test]
+    fn encodes_permit_batch_and_swap() {
+        let permit = permit_batch(&[TOKEN1.address(), TOKEN0.address()]);
+        let params = universal_router_swap_call_parameters(
+            &mut trades(),
+            SwapOptions {
+                input_token_permit2: Some(Permit2Options::Batch(permit.clone())),
+                ..options()
+            },
+            DEADLINE,
+        )
+        .unwrap();
+        let call = IUniversalRouter::executeCall::abi_decode(&params.calldata, true).unwrap();
+        assert_eq!(
+            call.commands.to_vec(),
+            vec![commands::PERMIT2_PERMIT_BATCH, commands::V3_SWAP_EXACT_IN]
+        );
+        let (decoded, signature) =
+            <(Permit2Batch, Bytes)>::abi_decode_params(&call.inputs[0], true).unwrap();
+        assert_eq!(decoded, permit.permit);
+        assert_eq!(signature, encode_permit2_signature(&permit.signature));
+    }
+
+    #[test]
+    fn rejects_permit_of_another_token() {
+        for permit in [
+            Permit2Options::Single(permit(TOKEN1.address())),
+            Permit2Options::Batch(permit_batch(&[TOKEN1.address(), TOKEN2.address()])),
+        ] {
+            assert!(matches!(
+                universal_router_swap_call_parameters(
+                    &mut trades(),
+                    SwapOptions {
+                        input_token_permit2: Some(permit),
+                        ..options()
+                    },
+                    DEADLINE,
+                ),
+                Err(Error::CurrencyMismatch { .. })
+            ));
+        }
+    }
+
+    #[test]
+    fn rejects_self_permit() {
+        let err = universal_router_swap_call_parameters(
+            &mut trades(),
+            SwapOptions {
+                input_token_permit: Some(PermitOptions::Standard(StandardPermitArguments::new(
+                    U256::from(1),
+                    U256::from(2),
+                    false,
+                    U256::MAX,
+                    U256::MAX,
+                ))),
+                ..options()
+            },
+            DEADLINE,
+        )
+        .unwrap_err();
+        assert!(matches!(err, Error::UnsupportedRouterCall(selector)
+            if selector.0 == ISelfPermit::selfPermitCall::SELECTOR));
+    }
+
+    #[test]
+    fn rejects_empty_trades() {
+        let mut trades: [Trade<Token, Token, TickListDataProvider>; 0] = [];
+        assert!(matches!(
+            universal_router_swap_call_parameters(&mut trades, options(), DEADLINE),
+            Err(Error::NoTrades)
+        ));
+    }
+
+    #[test]
+    fn wraps_native_input_and_refunds_exact_output() {
+        let mut trades = [Trade::from_route(
+            Route::new(
+                vec![make_pool(TOKEN1.clone(), WETH.clone())],
+                ETHER.clone(),
+                TOKEN1.clone(),
+            ),
+            CurrencyAmount::from_raw_amount(TOKEN1.clone(), 100).unwrap(),
+            TradeType::ExactOutput,
+        )
+        .unwrap()];
+        let params =
+            universal_router_swap_call_parameters(&mut trades, options(), DEADLINE).unwrap();
+        let call = IUniversalRouter::executeCall::abi_decode(&params.calldata, true).unwrap();
+        assert_eq!(
+            call.commands.to_vec(),
+            vec![
+                commands::WRAP_ETH,
+                commands::V3_SWAP_EXACT_OUT,
+                commands::UNWRAP_WETH
+            ]
+        );
+        let (_, amount_in) = <(Address, U256)>::abi_decode_params(&call.inputs[0], true).unwrap();
+        assert_eq!(params.value, amount_in);
+        let (.., payer_is_user) =
+            <(Address, U256, U256, Bytes, bool)>::abi_decode_params(&call.inputs[1], true).unwrap();
+        assert!(!payer_is_user);
+    }
+
+    #[test]
+    fn unwraps_native_output() {
+        let mut trades = [Trade::from_route(
+            Route::new(
+                vec![make_pool(TOKEN1.clone(), WETH.clone())],
+                TOKEN1.clone(),
+                ETHER.clone(),
+            ),
+            CurrencyAmount::from_raw_amount(TOKEN1.clone(), 100).unwrap(),
+            TradeType::ExactInput,
+        )
+        .unwrap()];
+        let params =
+            universal_router_swap_call_parameters(&mut trades, options(), DEADLINE).unwrap();
+        let call = IUniversalRouter::executeCall::abi_decode(&params.calldata, true).unwrap();
+        assert_eq!(
+            call.commands.to_vec(),
+            vec![commands::V3_SWAP_EXACT_IN, commands::UNWRAP_WETH]
+        );
+        let (recipient, ..) =
+            <(Address, U256, U256, Bytes, bool)>::abi_decode_params(&call.inputs[0], true).unwrap();
+        assert_eq!(recipient, ADDRESS_THIS);
+        let (recipient, _) = <(Address, U256)>::abi_decode_params(&call.inputs[1], true).unwrap();
+        assert_eq!(recipient, RECIPIENT);
+    }
+}
@@ -0,0 +1,117 @@
+//! ## Cache
+//! The storage of the `_cached` methods of [`Trade`](crate::prelude::Trade),
+//! [`Position`](crate::prelude::Position) and [`Route`](crate::prelude::Route). With the
+//! `no-cache` feature, [`Cached`] is zero-sized and never holds a value, so that the entities keep
+//! their minimal size and the `_cached` methods recompute every time.
+
+#[cfg(feature = "no-cache")]
+use core::marker::PhantomData;
+
+/// Whether [`Cached`] values are stored, i.e. the `no-cache` feature is disabled.
+pub const CACHE_ENABLED: bool = cfg!(not(feature = "no-cache"));
+
+/// A lazily computed value, stored unless the `no-cache` feature is enabled.
+#[cfg(not(feature = "no-cache"))]
+#[derive(Clone, Debug, PartialEq, Eq, Hash)]
+pub struct Cached<T>(Option<T>);
+
+/// A lazily computed value, stored unless the `no-cache` feature is enabled.
+#[cfg(feature = "no-cache")]
+#[derive(Debug)]
+pub struct Cached<T>(PhantomData<fn() -> T>);
+
+#[cfg(not(feature = "no-cache"))]
+impl<T> Cached<T> {
+    /// Creates an empty cache
+    #[inline]
+    #[must_use]
+    pub const fn new() -> Self {
+        Self(None)
+    }
+
+    /// Returns the cached value, if any
+    #[inline]
+    #[must_use]
+    pub const fn get(&self) -> Option<&T> {
+        self.0.as_ref()
+    }
+
+    /// Stores the value
+    #[inline]
+    pub fn set(&mut self, value: T) {
+        self.0 = Some(value);
+    }
+}
+
+#[cfg(feature = "no-cache")]
+impl<T> Cached<T> {
+    /// Creates an empty cache
+    #[inline]
+    #[must_use]
+    pub const fn new() -> Self {
+        Self(PhantomData)
+    }
+
+    /// Returns the cached value, always `None` with the `no-cache` feature
+    #[inline]
+    #[must_use]
+    pub const fn get(&self) -> Option<&T> {
+        None
+    }
+
+    /// Discards the value with the `no-cache` feature
+    #[inline]
+    pub fn set(&mut self, _value: T) {}
+}
+
+// implemented by hand so that the bounds do not leak to the entities without a cache
+#[cfg(feature = "no-cache")]
+impl<T> Clone for Cached<T> {
+    #[inline]
+    fn clone(&self) -> Self {
+        *self
+    }
+}
+
+#[cfg(feature = "no-cache")]
+impl<T> Copy for Cached<T> {}
+
+#[cfg(feature = "no-cache")]
+impl<T> PartialEq for Cached<T> {
+    #[inline]
+    fn eq(&self, _other: &Self) -> bool {
+        true
+    }
+}
+
+#[cfg(feature = "no-cache")]
+impl<T> Eq for Cached<T> {}
+
+#[cfg(feature = "no-cache")]
+impl<T> core::hash::Hash for Cached<T> {
+    #[inline]
+    fn hash<H: core::hash::Hasher>(&self, _state: &mut H) {}
+}
+
+impl<T> Default for Cached<T> {
+    #[inline]
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_cached() {
+        let mut cached = Cached::<u32>::new();
+        assert_eq!(cached.get(), None);
+        cached.set(1);
+        assert_eq!(cached.get().copied(), CACHE_ENABLED.then_some(1));
+        if !CACHE_ENABLED {
+            assert_eq!(core::mem::size_of::<Cached<u32>>(), 0);
+        }
+    }
+}
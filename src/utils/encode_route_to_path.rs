@@ -1,22 +1,23 @@
 use crate::prelude::*;
+use alloc::vec::Vec;
 use alloy_primitives::{aliases::U24, Bytes};
-use alloy_sol_types::SolValue;
 use uniswap_sdk_core::prelude::*;
 
 #[inline]
 fn encode_leg<'a, TP: TickDataProvider>(
     pool: &'a Pool<TP>,
     input_token: &'a Token,
-) -> (&'a Token, Vec<u8>) {
-    let output_token;
-    let leg: (Address, U24) = if pool.token0.equals(input_token) {
-        output_token = &pool.token1;
-        (pool.token0.address(), pool.fee.into())
+    path: &mut Vec<u8>,
+) -> &'a Token {
+    let output_token = if pool.token0.equals(input_token) {
+        &pool.token1
     } else {
-        output_token = &pool.token0;
-        (pool.token1.address(), pool.fee.into())
+        &pool.token0
     };
-    (output_token, leg.abi_encode_packed())
+    let fee: U24 = pool.fee.into();
+    path.extend_from_slice(input_token.address().as_slice());
+    path.extend_from_slice(&fee.to_be_bytes::<3>());
+    output_token
 }
 
 /// Converts a route to a hex encoded path.
@@ -36,24 +37,42 @@ where
     TP: TickDataProvider,
 {
     let mut path: Vec<u8> = Vec::with_capacity(23 * route.pools.len() + 20);
+    encode_route_to_path_into(route, exact_output, &mut path);
+    path.into()
+}
+
+/// Same as [`encode_route_to_path()`] but appends the path to `path` without intermediate
+/// allocations.
+///
+/// ## Arguments
+///
+/// * `route`: the v3 path to convert to an encoded path
+/// * `exact_output`: whether the route should be encoded in reverse, for making exact output swaps
+/// * `path`: the buffer to append the encoded path to
+#[inline]
+pub fn encode_route_to_path_into<TInput, TOutput, TP>(
+    route: &Route<TInput, TOutput, TP>,
+    exact_output: bool,
+    path: &mut Vec<u8>,
+) where
+    TInput: BaseCurrency,
+    TOutput: BaseCurrency,
+    TP: TickDataProvider,
+{
+    path.reserve(23 * route.pools.len() + 20);
     if exact_output {
         let mut output_token = route.output.wrapped();
         for pool in route.pools.iter().rev() {
-            let (input_token, leg) = encode_leg(pool, output_token);
-            output_token = input_token;
-            path.extend(leg);
+            output_token = encode_leg(pool, output_token, path);
         }
-        path.extend(route.input.address().abi_encode_packed());
+        path.extend_from_slice(route.input.wrapped().address().as_slice());
     } else {
         let mut input_token = route.input.wrapped();
         for pool in &route.pools {
-            let (output_token, leg) = encode_leg(pool, input_token);
-            input_token = output_token;
-            path.extend(leg);
+            input_token = encode_leg(pool, input_token, path);
         }
-        path.extend(route.output.address().abi_encode_packed());
+        path.extend_from_slice(route.output.wrapped().address().as_slice());
     }
-    path.into()
 }
 
 #[cfg(test)]
@@ -198,4 +217,12 @@ mod tests {
             hex!("c02aaa39b223fe8d0a0e5c4f27ead9083c756cc2000bb80000000000000000000000000000000000000002000bb80000000000000000000000000000000000000001")
         );
     }
+
+    #[test]
+    fn encode_into_appends_to_buffer() {
+        let mut path = vec![0xff];
+        encode_route_to_path_into(&ROUTE_0_1_WETH, true, &mut path);
+        assert_eq!(path[0], 0xff);
+        assert_eq!(path[1..], encode_route_to_path(&ROUTE_0_1_WETH, true)[..]);
+    }
 }
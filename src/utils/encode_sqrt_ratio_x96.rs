@@ -1,6 +1,7 @@
 use super::FromBig;
+use crate::error::Error;
 use alloy_primitives::Uint;
-use num_bigint::BigInt;
+use num_bigint::{BigInt, Sign};
 use uniswap_sdk_core::utils::sqrt;
 
 /// Returns the sqrt ratio as a Q64.96 corresponding to a given ratio of `amount1` and `amount0`.
@@ -12,7 +13,8 @@ use uniswap_sdk_core::utils::sqrt;
 ///
 /// ## Returns
 ///
-/// The sqrt ratio as a Q64.96
+/// The sqrt ratio as a Q64.96, see [`try_encode_sqrt_ratio_x96`] for ratios whose sqrt may not fit
+/// in `BITS` bits
 #[inline]
 pub fn encode_sqrt_ratio_x96<const BITS: usize, const LIMBS: usize>(
     amount1: impl Into<BigInt>,
@@ -23,10 +25,44 @@ pub fn encode_sqrt_ratio_x96<const BITS: usize, const LIMBS: usize>(
     Uint::from_big_int(sqrt(&(numerator / denominator)).unwrap())
 }
 
+/// Same as [`encode_sqrt_ratio_x96`] but checks that the sqrt ratio fits in `BITS` bits, e.g. for
+/// the prices of tokens whose decimals differ by up to 36, whose raw ratios span more than the
+/// 2^-128 to 2^128 representable by a [`U160`](alloy_primitives::U160) sqrt ratio.
+///
+/// ## Arguments
+///
+/// * `amount1`: The numerator amount i.e., the amount of token1
+/// * `amount0`: The denominator amount i.e., the amount of token0
+///
+/// ## Returns
+///
+/// [`Error::PriceOverflow`] if the sqrt ratio does not fit in `BITS` bits, [`Error::InvalidPrice`]
+/// if the ratio is negative or `amount0` is zero
+#[inline]
+pub fn try_encode_sqrt_ratio_x96<const BITS: usize, const LIMBS: usize>(
+    amount1: impl Into<BigInt>,
+    amount0: impl Into<BigInt>,
+) -> Result<Uint<BITS, LIMBS>, Error> {
+    let numerator: BigInt = amount1.into() << 192;
+    let denominator: BigInt = amount0.into();
+    if denominator.sign() == Sign::NoSign {
+        return Err(Error::InvalidPrice);
+    }
+    let ratio_x192 = numerator / denominator;
+    if ratio_x192.sign() == Sign::Minus {
+        return Err(Error::InvalidPrice);
+    }
+    let (_, limbs) = sqrt(&ratio_x192)
+        .map_err(|_| Error::InvalidPrice)?
+        .to_u64_digits();
+    Uint::checked_from_limbs_slice(&limbs).ok_or(Error::PriceOverflow)
+}
+
 #[cfg(test)]
 mod tests {
-    use crate::utils::{encode_sqrt_ratio_x96, Q96};
-    use alloy_primitives::U256;
+    use super::*;
+    use crate::utils::Q96;
+    use alloy_primitives::{U160, U256};
 
     #[test]
     fn test_encode_sqrt_ratio_x96() {
@@ -48,4 +84,30 @@ mod tests {
             U256::from(137227202865029797602485611888_u128)
         );
     }
+
+    #[test]
+    fn test_try_encode_sqrt_ratio_x96() {
+        assert_eq!(
+            try_encode_sqrt_ratio_x96::<160, 3>(333, 111).unwrap(),
+            encode_sqrt_ratio_x96::<160, 3>(333, 111)
+        );
+        // 1 unit of a 0 decimals token0 for 100 units of a 36 decimals token1
+        let ten = BigInt::from(10);
+        assert_eq!(
+            try_encode_sqrt_ratio_x96::<160, 3>(ten.pow(38), 1).unwrap(),
+            U160::from(10_u64.pow(19)) << 96
+        );
+        assert!(matches!(
+            try_encode_sqrt_ratio_x96::<160, 3>(ten.pow(39), 1),
+            Err(Error::PriceOverflow)
+        ));
+        assert!(matches!(
+            try_encode_sqrt_ratio_x96::<160, 3>(-1, 1),
+            Err(Error::InvalidPrice)
+        ));
+        assert!(matches!(
+            try_encode_sqrt_ratio_x96::<160, 3>(1, 0),
+            Err(Error::InvalidPrice)
+        ));
+    }
 }
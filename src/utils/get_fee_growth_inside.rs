@@ -1,11 +1,70 @@
 use alloy_primitives::Uint;
 
+/// The fee growth on the other side of a tick from the current tick, i.e. `feeGrowthOutside0X128`
+/// and `feeGrowthOutside1X128` of `ticks(tick)` on the pool.
 #[derive(Clone, Copy, Debug, Default, PartialEq)]
 pub struct FeeGrowthOutside<const BITS: usize, const LIMBS: usize> {
+    /// The fee growth of token0 per unit of liquidity on the other side of the tick.
     pub fee_growth_outside0_x128: Uint<BITS, LIMBS>,
+    /// The fee growth of token1 per unit of liquidity on the other side of the tick.
     pub fee_growth_outside1_x128: Uint<BITS, LIMBS>,
 }
 
+/// The state of a pool needed to compute the fee growth inside a tick range, e.g. as fetched by
+/// `get_fee_growth_inputs` in the extensions.
+#[derive(Clone, Copy, Debug, Default, PartialEq)]
+pub struct FeeGrowthInputs<T, const BITS: usize, const LIMBS: usize> {
+    /// The lower tick of the range.
+    pub tick_lower: T,
+    /// The upper tick of the range.
+    pub tick_upper: T,
+    /// The current tick of the pool.
+    pub tick_current: T,
+    /// The fee growth of token0 per unit of liquidity over the life of the pool.
+    pub fee_growth_global0_x128: Uint<BITS, LIMBS>,
+    /// The fee growth of token1 per unit of liquidity over the life of the pool.
+    pub fee_growth_global1_x128: Uint<BITS, LIMBS>,
+    /// The fee growth outside the lower tick.
+    pub lower: FeeGrowthOutside<BITS, LIMBS>,
+    /// The fee growth outside the upper tick.
+    pub upper: FeeGrowthOutside<BITS, LIMBS>,
+}
+
+impl<T: Copy + PartialOrd, const BITS: usize, const LIMBS: usize> FeeGrowthInputs<T, BITS, LIMBS> {
+    /// Computes the fee growth of token0 and token1 inside the range, see
+    /// [`get_fee_growth_inside`]
+    #[inline]
+    #[must_use]
+    pub fn fee_growth_inside(&self) -> (Uint<BITS, LIMBS>, Uint<BITS, LIMBS>) {
+        get_fee_growth_inside(
+            self.lower,
+            self.upper,
+            self.tick_lower,
+            self.tick_upper,
+            self.tick_current,
+            self.fee_growth_global0_x128,
+            self.fee_growth_global1_x128,
+        )
+    }
+}
+
+/// Computes the fee growth of token0 and token1 per unit of liquidity inside a tick range, i.e.
+/// the `feeGrowthInside0LastX128` and `feeGrowthInside1LastX128` a position over the range would
+/// record if it were poked now
+///
+/// Like the on-chain `getFeeGrowthInside`, the subtractions wrap around on underflow, so only the
+/// difference between two values for the same range is meaningful, e.g. to compute the fees
+/// earned by some liquidity between two blocks with [`get_tokens_owed`](super::get_tokens_owed()).
+///
+/// ## Arguments
+///
+/// * `lower`: The fee growth outside the lower tick
+/// * `upper`: The fee growth outside the upper tick
+/// * `tick_lower`: The lower tick of the range
+/// * `tick_upper`: The upper tick of the range
+/// * `tick_current`: The current tick of the pool
+/// * `fee_growth_global0_x128`: The global fee growth of token0 of the pool
+/// * `fee_growth_global1_x128`: The global fee growth of token1 of the pool
 #[inline]
 #[allow(clippy::needless_pass_by_value)]
 pub fn get_fee_growth_inside<const BITS: usize, const LIMBS: usize, T: PartialOrd>(
@@ -20,18 +79,26 @@ pub fn get_fee_growth_inside<const BITS: usize, const LIMBS: usize, T: PartialOr
     let fee_growth_inside0_x128;
     let fee_growth_inside1_x128;
     if tick_current < tick_lower {
-        fee_growth_inside0_x128 = lower.fee_growth_outside0_x128 - upper.fee_growth_outside0_x128;
-        fee_growth_inside1_x128 = lower.fee_growth_outside1_x128 - upper.fee_growth_outside1_x128;
+        fee_growth_inside0_x128 = lower
+            .fee_growth_outside0_x128
+            .wrapping_sub(upper.fee_growth_outside0_x128);
+        fee_growth_inside1_x128 = lower
+            .fee_growth_outside1_x128
+            .wrapping_sub(upper.fee_growth_outside1_x128);
     } else if tick_current >= tick_upper {
-        fee_growth_inside0_x128 = upper.fee_growth_outside0_x128 - lower.fee_growth_outside0_x128;
-        fee_growth_inside1_x128 = upper.fee_growth_outside1_x128 - lower.fee_growth_outside1_x128;
+        fee_growth_inside0_x128 = upper
+            .fee_growth_outside0_x128
+            .wrapping_sub(lower.fee_growth_outside0_x128);
+        fee_growth_inside1_x128 = upper
+            .fee_growth_outside1_x128
+            .wrapping_sub(lower.fee_growth_outside1_x128);
     } else {
         fee_growth_inside0_x128 = fee_growth_global0_x128
-            - lower.fee_growth_outside0_x128
-            - upper.fee_growth_outside0_x128;
+            .wrapping_sub(lower.fee_growth_outside0_x128)
+            .wrapping_sub(upper.fee_growth_outside0_x128);
         fee_growth_inside1_x128 = fee_growth_global1_x128
-            - lower.fee_growth_outside1_x128
-            - upper.fee_growth_outside1_x128;
+            .wrapping_sub(lower.fee_growth_outside1_x128)
+            .wrapping_sub(upper.fee_growth_outside1_x128);
     }
     (fee_growth_inside0_x128, fee_growth_inside1_x128)
 }
@@ -72,6 +139,23 @@ mod tests {
         assert_eq!(fee_growth_inside1_x128, Q128);
     }
 
+    #[test]
+    fn test_inputs_below_range() {
+        let inputs = FeeGrowthInputs {
+            tick_lower: -1,
+            tick_upper: 1,
+            tick_current: -2,
+            fee_growth_global0_x128: Q128,
+            fee_growth_global1_x128: Q128,
+            lower: FeeGrowthOutside {
+                fee_growth_outside0_x128: Q128,
+                fee_growth_outside1_x128: Q128 >> 1,
+            },
+            upper: FeeGrowthOutside::default(),
+        };
+        assert_eq!(inputs.fee_growth_inside(), (Q128, Q128 >> 1));
+    }
+
     #[test]
     fn test_non_zero_some_outside() {
         let q127 = Q128 >> 1;
@@ -1,7 +1,29 @@
-use super::Q128;
-use alloy_primitives::Uint;
+use super::{FullMath, Q128};
+use alloc::vec::Vec;
+use alloy_primitives::{Uint, U256};
+
+/// The fee growth checkpoints of a position used to compute the fees owed to it
+#[derive(Clone, Copy, Debug, Default, PartialEq)]
+pub struct PositionFeeGrowth<const BITS: usize, const LIMBS: usize> {
+    /// The fee growth of token0 inside the position's range as of its last update.
+    pub fee_growth_inside_0_last_x128: Uint<BITS, LIMBS>,
+    /// The fee growth of token1 inside the position's range as of its last update.
+    pub fee_growth_inside_1_last_x128: Uint<BITS, LIMBS>,
+    /// The liquidity of the position.
+    pub liquidity: u128,
+    /// The current fee growth of token0 inside the position's range.
+    pub fee_growth_inside_0_x128: Uint<BITS, LIMBS>,
+    /// The current fee growth of token1 inside the position's range.
+    pub fee_growth_inside_1_x128: Uint<BITS, LIMBS>,
+}
 
 /// Computes the amount of fees owed to a position
+///
+/// Matches `Position.update` on-chain: the fee growth delta relies on uint256 underflow, so it
+/// wraps around when the current fee growth inside is below the last checkpoint, which happens
+/// for old positions once the fee growth counters have overflowed. The product with the liquidity
+/// is computed with full precision like `FullMath.mulDiv`, then truncated to 128 bits like the
+/// `uint128` cast of the contract.
 #[inline]
 #[must_use]
 pub fn get_tokens_owed<const BITS: usize, const LIMBS: usize>(
@@ -11,19 +33,41 @@ pub fn get_tokens_owed<const BITS: usize, const LIMBS: usize>(
     fee_growth_inside_0_x128: Uint<BITS, LIMBS>,
     fee_growth_inside_1_x128: Uint<BITS, LIMBS>,
 ) -> (Uint<BITS, LIMBS>, Uint<BITS, LIMBS>) {
-    let liquidity = Uint::from(liquidity);
-    let q128 = Uint::from(Q128);
-    let tokens_owed_0 =
-        (fee_growth_inside_0_x128 - fee_growth_inside_0_last_x128) * liquidity / q128;
-    let tokens_owed_1 =
-        (fee_growth_inside_1_x128 - fee_growth_inside_1_last_x128) * liquidity / q128;
+    let liquidity = U256::from(liquidity);
+    let owed = |delta: Uint<BITS, LIMBS>| {
+        // cannot overflow since liquidity < 2^128
+        let [lo, hi, ..] = delta.mul_div(liquidity, Q128).unwrap().into_limbs();
+        Uint::from(lo as u128 | (hi as u128) << 64)
+    };
+    let tokens_owed_0 = owed(fee_growth_inside_0_x128.wrapping_sub(fee_growth_inside_0_last_x128));
+    let tokens_owed_1 = owed(fee_growth_inside_1_x128.wrapping_sub(fee_growth_inside_1_last_x128));
     (tokens_owed_0, tokens_owed_1)
 }
 
+/// Computes the amount of fees owed to each of the given positions, see [`get_tokens_owed`]
+#[inline]
+#[must_use]
+pub fn get_tokens_owed_batch<const BITS: usize, const LIMBS: usize>(
+    positions: &[PositionFeeGrowth<BITS, LIMBS>],
+) -> Vec<(Uint<BITS, LIMBS>, Uint<BITS, LIMBS>)> {
+    positions
+        .iter()
+        .map(|position| {
+            get_tokens_owed(
+                position.fee_growth_inside_0_last_x128,
+                position.fee_growth_inside_1_last_x128,
+                position.liquidity,
+                position.fee_growth_inside_0_x128,
+                position.fee_growth_inside_1_x128,
+            )
+        })
+        .collect()
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
-    use alloy_primitives::U256;
+    use alloy_primitives::uint;
 
     #[test]
     fn test_get_tokens_owed() {
@@ -31,4 +75,56 @@ mod tests {
         assert_eq!(tokens_owed_0, U256::from(1));
         assert_eq!(tokens_owed_1, U256::from(1));
     }
+
+    #[test]
+    fn test_get_tokens_owed_wraps_around() {
+        let (tokens_owed_0, tokens_owed_1) = get_tokens_owed(
+            U256::MAX,
+            U256::MAX - Q128,
+            1,
+            Q128 - U256::from(1),
+            U256::MAX,
+        );
+        assert_eq!(tokens_owed_0, U256::from(1));
+        assert_eq!(tokens_owed_1, U256::from(1));
+    }
+
+    #[test]
+    fn test_get_tokens_owed_truncates_to_u128() {
+        // the full precision product is 2^256 - 2^128 - 1
+        let (tokens_owed_0, _) =
+            get_tokens_owed(U256::ZERO, U256::ZERO, u128::MAX, U256::MAX, U256::ZERO);
+        assert_eq!(tokens_owed_0, U256::from(u128::MAX));
+        // 2^128 + 1 wraps around to 1
+        let (tokens_owed_0, _) = get_tokens_owed(
+            U256::ZERO,
+            U256::ZERO,
+            1 << 127,
+            uint!(0x200000000000000000000000000000002_U256),
+            U256::ZERO,
+        );
+        assert_eq!(tokens_owed_0, U256::from(1));
+    }
+
+    #[test]
+    fn test_get_tokens_owed_batch() {
+        let positions = [
+            PositionFeeGrowth {
+                liquidity: 1,
+                fee_growth_inside_0_x128: Q128,
+                fee_growth_inside_1_x128: Q128,
+                ..Default::default()
+            },
+            PositionFeeGrowth {
+                fee_growth_inside_0_last_x128: U256::MAX,
+                liquidity: 2,
+                fee_growth_inside_0_x128: Q128 - U256::from(1),
+                ..Default::default()
+            },
+        ];
+        assert_eq!(
+            get_tokens_owed_batch(&positions),
+            vec![(U256::from(1), U256::from(1)), (U256::from(2), U256::ZERO)]
+        );
+    }
 }
@@ -0,0 +1,268 @@
+//! ## Liquidity Distribution
+//! Walks the tick data provider of a pool outwards from the current price to chart its active
+//! liquidity and the token amounts available between price points, e.g. for depth charts.
+
+use crate::prelude::{Error, *};
+use alloc::vec::Vec;
+use alloy_primitives::{U160, U256};
+use uniswap_sdk_core::prelude::*;
+
+/// A range of ticks over which the active liquidity of a pool is constant.
+///
+/// The amounts are those swapped out when the price moves from the current price through the
+/// bucket, without fees, i.e. the tokens the liquidity of the bucket holds. The bucket containing
+/// the current price is split at the current tick.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub struct LiquidityBucket<I = i32> {
+    /// The lower tick of the bucket.
+    pub tick_lower: I,
+    /// The upper tick of the bucket.
+    pub tick_upper: I,
+    /// The active liquidity between the ticks.
+    pub liquidity: u128,
+    /// The amount of token0 bought by moving the price up through the bucket, zero below the
+    /// current price.
+    pub amount0: U256,
+    /// The amount of token1 bought by moving the price down through the bucket, zero above the
+    /// current price.
+    pub amount1: U256,
+    /// The amount of token0 bought by moving the price up from the current price to the upper
+    /// tick of the bucket.
+    pub cumulative_amount0: U256,
+    /// The amount of token1 bought by moving the price down from the current price to the lower
+    /// tick of the bucket.
+    pub cumulative_amount1: U256,
+}
+
+/// Walks the ticks of the pool from the current price in the given direction up to the price
+/// limit, returning the buckets in the order they are walked
+fn walk<TP: TickDataProvider>(
+    pool: &Pool<TP>,
+    zero_for_one: bool,
+    sqrt_ratio_limit_x96: U160,
+) -> Result<Vec<LiquidityBucket<TP::Index>>, Error> {
+    let tick_spacing = pool.tick_spacing();
+    let mut buckets: Vec<LiquidityBucket<TP::Index>> = Vec::new();
+    let mut tick = pool.tick_current;
+    let mut sqrt_ratio_x96 = pool.sqrt_ratio_x96;
+    let mut liquidity = pool.liquidity;
+    let mut cumulative = U256::ZERO;
+    // the price and the cumulative amount at the start of the last bucket, whose amounts are
+    // computed over the whole bucket to not accumulate the rounding of every step
+    let mut sqrt_ratio_start_x96 = sqrt_ratio_x96;
+    let mut cumulative_start = U256::ZERO;
+    // whether an initialized tick was crossed by the last step, i.e. the liquidity changed
+    let mut crossed = true;
+    while if zero_for_one {
+        sqrt_ratio_x96 > sqrt_ratio_limit_x96
+    } else {
+        sqrt_ratio_x96 < sqrt_ratio_limit_x96
+    } {
+        let (tick_next, initialized) = pool
+            .tick_data_provider
+            .next_initialized_tick_within_one_word(tick, zero_for_one, tick_spacing)?;
+        let tick_next = TP::Index::from_i24(tick_next.to_i24().clamp(MIN_TICK, MAX_TICK));
+        let sqrt_ratio_next_x96 = get_sqrt_ratio_at_tick(tick_next.to_i24())?;
+        let (sqrt_ratio_end_x96, tick_end) = if (zero_for_one
+            && sqrt_ratio_next_x96 < sqrt_ratio_limit_x96)
+            || (!zero_for_one && sqrt_ratio_next_x96 > sqrt_ratio_limit_x96)
+        {
+            (
+                sqrt_ratio_limit_x96,
+                TP::Index::from_i24(sqrt_ratio_limit_x96.get_tick_at_sqrt_ratio()?),
+            )
+        } else {
+            (sqrt_ratio_next_x96, tick_next)
+        };
+
+        if crossed {
+            sqrt_ratio_start_x96 = sqrt_ratio_x96;
+            cumulative_start = cumulative;
+        }
+        let (amount0, amount1) = if zero_for_one {
+            (
+                U256::ZERO,
+                get_amount_1_delta(sqrt_ratio_end_x96, sqrt_ratio_start_x96, liquidity, false)?,
+            )
+        } else {
+            (
+                get_amount_0_delta(sqrt_ratio_start_x96, sqrt_ratio_end_x96, liquidity, false)?,
+                U256::ZERO,
+            )
+        };
+        cumulative = cumulative_start + amount0 + amount1;
+        match buckets.last_mut() {
+            Some(bucket) if !crossed => {
+                if zero_for_one {
+                    bucket.tick_lower = tick_end;
+                    bucket.cumulative_amount1 = cumulative;
+                } else {
+                    bucket.tick_upper = tick_end;
+                    bucket.cumulative_amount0 = cumulative;
+                }
+                bucket.amount0 = amount0;
+                bucket.amount1 = amount1;
+            }
+            last => {
+                let tick_start = last.map_or(pool.tick_current, |bucket| {
+                    if zero_for_one {
+                        bucket.tick_lower
+                    } else {
+                        bucket.tick_upper
+                    }
+                });
+                let (tick_lower, tick_upper) = if zero_for_one {
+                    (tick_end, tick_start)
+                } else {
+                    (tick_start, tick_end)
+                };
+                buckets.push(LiquidityBucket {
+                    tick_lower,
+                    tick_upper,
+                    liquidity,
+                    amount0,
+                    amount1,
+                    cumulative_amount0: if zero_for_one { U256::ZERO } else { cumulative },
+                    cumulative_amount1: if zero_for_one { cumulative } else { U256::ZERO },
+                });
+            }
+        }
+
+        crossed = initialized && sqrt_ratio_end_x96 == sqrt_ratio_next_x96;
+        if crossed {
+            let mut liquidity_net = pool.tick_data_provider.get_tick(tick_next)?.liquidity_net;
+            // if we're moving leftward, we interpret liquidityNet as the opposite sign
+            if zero_for_one {
+                liquidity_net = -liquidity_net;
+            }
+            liquidity = add_delta(liquidity, liquidity_net)?;
+        }
+        tick = if zero_for_one && sqrt_ratio_end_x96 == sqrt_ratio_next_x96 {
+            tick_next - TP::Index::ONE
+        } else {
+            tick_end
+        };
+        sqrt_ratio_x96 = sqrt_ratio_end_x96;
+    }
+    Ok(buckets)
+}
+
+/// Returns the distribution of the active liquidity of a pool between two ticks, as buckets of
+/// constant liquidity sorted by tick, with the amounts available in each bucket and cumulatively
+/// from the current price
+///
+/// ## Arguments
+///
+/// * `pool`: The pool, whose tick data provider must cover the range
+/// * `tick_lower`: The tick down to which the liquidity below the current price is walked
+/// * `tick_upper`: The tick up to which the liquidity above the current price is walked
+#[inline]
+pub fn liquidity_distribution<TP: TickDataProvider>(
+    pool: &Pool<TP>,
+    tick_lower: TP::Index,
+    tick_upper: TP::Index,
+) -> Result<Vec<LiquidityBucket<TP::Index>>, Error> {
+    let sqrt_ratio_lower_x96 = get_sqrt_ratio_at_tick(tick_lower.to_i24().max(MIN_TICK))?;
+    let sqrt_ratio_upper_x96 = get_sqrt_ratio_at_tick(tick_upper.to_i24().min(MAX_TICK))?;
+    let mut buckets = walk(pool, true, sqrt_ratio_lower_x96)?;
+    buckets.reverse();
+    buckets.extend(walk(pool, false, sqrt_ratio_upper_x96)?);
+    Ok(buckets)
+}
+
+/// Returns the amount of token0 bought by moving the price of a pool up by the given percentage,
+/// and the amount of token1 bought by moving it down by the same factor, without fees, e.g. the
+/// ±2% depth of a market
+///
+/// ## Arguments
+///
+/// * `pool`: The pool, whose tick data provider must cover the price range
+/// * `price_change`: The relative change of the price, i.e. up to `p * (1 + x)` and down to `p /
+///   (1 + x)`
+#[inline]
+pub fn liquidity_depth<TP: TickDataProvider>(
+    pool: &Pool<TP>,
+    price_change: &Percent,
+) -> Result<(U256, U256), Error> {
+    let sqrt_ratio_upper_x96 =
+        sqrt_price_limit_from_slippage(pool.sqrt_ratio_x96, false, price_change);
+    let sqrt_ratio_lower_x96 =
+        sqrt_price_limit_from_slippage(pool.sqrt_ratio_x96, true, price_change);
+    let amount0 = walk(pool, false, sqrt_ratio_upper_x96)?
+        .last()
+        .map_or(U256::ZERO, |bucket| bucket.cumulative_amount0);
+    let amount1 = walk(pool, true, sqrt_ratio_lower_x96)?
+        .last()
+        .map_or(U256::ZERO, |bucket| bucket.cumulative_amount1);
+    Ok((amount0, amount1))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::tests::*;
+
+    #[test]
+    fn distribution_of_full_range_liquidity() {
+        let pool = make_pool(TOKEN0.clone(), TOKEN1.clone());
+        let tick_spacing = pool.tick_spacing();
+        let tick_lower = nearest_usable_tick(MIN_TICK_I32, tick_spacing);
+        let tick_upper = nearest_usable_tick(MAX_TICK_I32, tick_spacing);
+        let buckets = liquidity_distribution(&pool, tick_lower, tick_upper).unwrap();
+        assert_eq!(buckets.len(), 2);
+        let (below, above) = (buckets[0], buckets[1]);
+        assert_eq!(
+            (below.tick_lower, below.tick_upper),
+            (tick_lower, pool.tick_current)
+        );
+        assert_eq!(
+            (above.tick_lower, above.tick_upper),
+            (pool.tick_current, tick_upper)
+        );
+        assert_eq!(below.liquidity, LIQUIDITY);
+        assert_eq!(above.liquidity, LIQUIDITY);
+        assert_eq!(below.amount0, U256::ZERO);
+        assert_eq!(above.amount1, U256::ZERO);
+        assert_eq!(below.cumulative_amount1, below.amount1);
+        assert_eq!(above.cumulative_amount0, above.amount0);
+        // the full range liquidity at price 1 holds about as much of each token
+        assert_eq!(
+            above.amount0,
+            get_amount_0_delta(
+                pool.sqrt_ratio_x96,
+                get_sqrt_ratio_at_tick(tick_upper.to_i24()).unwrap(),
+                LIQUIDITY,
+                false
+            )
+            .unwrap()
+        );
+        assert!(above.amount0.abs_diff(below.amount1) <= U256::from(1));
+    }
+
+    #[test]
+    fn distribution_beyond_the_initialized_ticks() {
+        let pool = make_pool(TOKEN0.clone(), TOKEN1.clone());
+        let buckets = liquidity_distribution(&pool, MIN_TICK_I32, MAX_TICK_I32).unwrap();
+        assert_eq!(buckets.len(), 4);
+        assert_eq!(buckets[0].tick_lower, MIN_TICK_I32);
+        assert_eq!(buckets[0].liquidity, 0);
+        assert_eq!(buckets[0].cumulative_amount1, buckets[1].cumulative_amount1);
+        assert_eq!(buckets[3].tick_upper, MAX_TICK_I32);
+        assert_eq!(buckets[3].liquidity, 0);
+        for pair in buckets.windows(2) {
+            assert_eq!(pair[0].tick_upper, pair[1].tick_lower);
+        }
+    }
+
+    #[test]
+    fn depth_within_one_percent() {
+        let pool = make_pool(TOKEN0.clone(), TOKEN1.clone());
+        let (amount0, amount1) = liquidity_depth(&pool, &Percent::new(1, 100)).unwrap();
+        // L * (1 - 1 / sqrt(1.01)) of either token at price 1
+        assert_eq!(amount0, U256::from(4962));
+        assert!(amount0.abs_diff(amount1) <= U256::from(1));
+        // the depth grows with the price change
+        let (amount0_2, _) = liquidity_depth(&pool, &Percent::new(2, 100)).unwrap();
+        assert!(amount0_2 > amount0);
+    }
+}
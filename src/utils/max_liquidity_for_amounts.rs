@@ -141,6 +141,81 @@ pub fn max_liquidity_for_amounts<const BITS: usize, const LIMBS: usize>(
     }
 }
 
+/// Computes the liquidity received for a given amount of token0 at an arbitrary price, including
+/// when the price is outside the range, e.g. for single-sided deposits.
+///
+/// Only the part of the range above the current price requires token0, so no liquidity is
+/// received when the price is at or above the upper boundary.
+///
+/// ## Arguments
+///
+/// * `sqrt_ratio_current_x96`: The current price
+/// * `sqrt_ratio_a_x96`: The price at the lower boundary
+/// * `sqrt_ratio_b_x96`: The price at the upper boundary
+/// * `amount0`: The token0 amount
+/// * `use_full_precision`: if false, rounds like `LiquidityAmounts#getLiquidityForAmount0` in the
+///   v3 periphery
+///
+/// returns: liquidity for amount0
+#[inline]
+#[must_use]
+pub fn liquidity_from_amount0_at_price<const BITS: usize, const LIMBS: usize>(
+    sqrt_ratio_current_x96: Uint<BITS, LIMBS>,
+    sqrt_ratio_a_x96: Uint<BITS, LIMBS>,
+    sqrt_ratio_b_x96: Uint<BITS, LIMBS>,
+    amount0: U256,
+    use_full_precision: bool,
+) -> BigUint {
+    let (sqrt_ratio_a_x96, sqrt_ratio_b_x96) = if sqrt_ratio_a_x96 > sqrt_ratio_b_x96 {
+        (sqrt_ratio_b_x96, sqrt_ratio_a_x96)
+    } else {
+        (sqrt_ratio_a_x96, sqrt_ratio_b_x96)
+    };
+    if sqrt_ratio_current_x96 >= sqrt_ratio_b_x96 {
+        return BigUint::ZERO;
+    }
+    let sqrt_ratio_lower_x96 = sqrt_ratio_current_x96.max(sqrt_ratio_a_x96);
+    if use_full_precision {
+        max_liquidity_for_amount0_precise(sqrt_ratio_lower_x96, sqrt_ratio_b_x96, amount0)
+    } else {
+        max_liquidity_for_amount0_imprecise(sqrt_ratio_lower_x96, sqrt_ratio_b_x96, amount0)
+    }
+}
+
+/// Computes the liquidity received for a given amount of token1 at an arbitrary price, including
+/// when the price is outside the range, e.g. for single-sided deposits.
+///
+/// Only the part of the range below the current price requires token1, so no liquidity is
+/// received when the price is at or below the lower boundary.
+///
+/// ## Arguments
+///
+/// * `sqrt_ratio_current_x96`: The current price
+/// * `sqrt_ratio_a_x96`: The price at the lower boundary
+/// * `sqrt_ratio_b_x96`: The price at the upper boundary
+/// * `amount1`: The token1 amount
+///
+/// returns: liquidity for amount1
+#[inline]
+#[must_use]
+pub fn liquidity_from_amount1_at_price<const BITS: usize, const LIMBS: usize>(
+    sqrt_ratio_current_x96: Uint<BITS, LIMBS>,
+    sqrt_ratio_a_x96: Uint<BITS, LIMBS>,
+    sqrt_ratio_b_x96: Uint<BITS, LIMBS>,
+    amount1: U256,
+) -> BigUint {
+    let (sqrt_ratio_a_x96, sqrt_ratio_b_x96) = if sqrt_ratio_a_x96 > sqrt_ratio_b_x96 {
+        (sqrt_ratio_b_x96, sqrt_ratio_a_x96)
+    } else {
+        (sqrt_ratio_a_x96, sqrt_ratio_b_x96)
+    };
+    if sqrt_ratio_current_x96 <= sqrt_ratio_a_x96 {
+        return BigUint::ZERO;
+    }
+    let sqrt_ratio_upper_x96 = sqrt_ratio_current_x96.min(sqrt_ratio_b_x96);
+    max_liquidity_for_amount1(sqrt_ratio_a_x96, sqrt_ratio_upper_x96, amount1)
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -430,4 +505,37 @@ mod tests {
             2097_u64.into()
         );
     }
+
+    #[test]
+    fn liquidity_from_amount0_at_price_out_of_range() {
+        let price = |a, b| encode_sqrt_ratio_x96::<256, 4>(a, b);
+        let liquidity = |current| {
+            liquidity_from_amount0_at_price(
+                current,
+                price(110, 100),
+                price(100, 110),
+                U256::from(100),
+                false,
+            )
+        };
+        assert_eq!(liquidity(price(99, 110)), 1048_u64.into());
+        assert_eq!(liquidity(price(1, 1)), 2148_u64.into());
+        assert_eq!(liquidity(price(111, 100)), BigUint::ZERO);
+    }
+
+    #[test]
+    fn liquidity_from_amount1_at_price_out_of_range() {
+        let price = |a, b| encode_sqrt_ratio_x96::<256, 4>(a, b);
+        let liquidity = |current| {
+            liquidity_from_amount1_at_price(
+                current,
+                price(100, 110),
+                price(110, 100),
+                U256::from(200),
+            )
+        };
+        assert_eq!(liquidity(price(111, 100)), 2097_u64.into());
+        assert_eq!(liquidity(price(1, 1)), 4297_u64.into());
+        assert_eq!(liquidity(price(99, 110)), BigUint::ZERO);
+    }
 }
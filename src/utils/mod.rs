@@ -1,14 +1,17 @@
 pub mod bit_math;
+pub mod cache;
 pub mod compute_pool_address;
 pub mod encode_route_to_path;
 pub mod encode_sqrt_ratio_x96;
 pub mod full_math;
 pub mod get_fee_growth_inside;
 pub mod get_tokens_owed;
+pub mod liquidity_distribution;
 pub mod liquidity_math;
 pub mod max_liquidity_for_amounts;
 pub mod nearest_usable_tick;
 pub mod price_tick_conversions;
+pub mod slippage;
 pub mod sqrt_price_math;
 pub mod swap_math;
 pub mod tick_list;
@@ -16,16 +19,21 @@ pub mod tick_math;
 mod types;
 
 pub use bit_math::*;
+pub use cache::*;
 pub use compute_pool_address::compute_pool_address;
-pub use encode_route_to_path::encode_route_to_path;
-pub use encode_sqrt_ratio_x96::encode_sqrt_ratio_x96;
+pub use encode_route_to_path::{encode_route_to_path, encode_route_to_path_into};
+pub use encode_sqrt_ratio_x96::{encode_sqrt_ratio_x96, try_encode_sqrt_ratio_x96};
 pub use full_math::*;
 pub use get_fee_growth_inside::*;
-pub use get_tokens_owed::get_tokens_owed;
+pub use get_tokens_owed::*;
+pub use liquidity_distribution::*;
 pub use liquidity_math::add_delta;
 pub use max_liquidity_for_amounts::*;
-pub use nearest_usable_tick::nearest_usable_tick;
+pub use nearest_usable_tick::{
+    nearest_usable_tick, tick_range_around, usable_tick_ceil, usable_tick_floor,
+};
 pub use price_tick_conversions::*;
+pub use slippage::*;
 pub use sqrt_price_math::*;
 pub use swap_math::*;
 pub use tick_list::TickList;
@@ -29,11 +29,84 @@ pub fn nearest_usable_tick<I: TickIndex>(tick: I, tick_spacing: I) -> I {
     .unwrap()
 }
 
+/// Returns the largest usable tick for the given tick spacing that is less than or equal to the
+/// given tick, or the smallest usable tick if there is none
+///
+/// ## Arguments
+///
+/// * `tick`: the target tick
+/// * `tick_spacing`: the spacing of the pool
+#[inline]
+pub fn usable_tick_floor<I: TickIndex>(tick: I, tick_spacing: I) -> I {
+    let tick = tick.try_into().unwrap();
+    let tick_spacing = tick_spacing.try_into().unwrap();
+    assert!(tick_spacing > 0, "TICK_SPACING");
+    assert!((MIN_TICK..=MAX_TICK).contains(&tick), "TICK_BOUND");
+    let rounded = Integer::div_floor(&tick, &tick_spacing) * tick_spacing;
+    I::try_from(if rounded < MIN_TICK {
+        rounded + tick_spacing
+    } else {
+        rounded
+    })
+    .unwrap()
+}
+
+/// Returns the smallest usable tick for the given tick spacing that is greater than or equal to
+/// the given tick, or the largest usable tick if there is none
+///
+/// ## Arguments
+///
+/// * `tick`: the target tick
+/// * `tick_spacing`: the spacing of the pool
+#[inline]
+pub fn usable_tick_ceil<I: TickIndex>(tick: I, tick_spacing: I) -> I {
+    let tick = tick.try_into().unwrap();
+    let tick_spacing = tick_spacing.try_into().unwrap();
+    assert!(tick_spacing > 0, "TICK_SPACING");
+    assert!((MIN_TICK..=MAX_TICK).contains(&tick), "TICK_BOUND");
+    let rounded = Integer::div_ceil(&tick, &tick_spacing) * tick_spacing;
+    I::try_from(if rounded > MAX_TICK {
+        rounded - tick_spacing
+    } else {
+        rounded
+    })
+    .unwrap()
+}
+
+/// Returns the usable tick range spanning `num_spacings` tick spacings on each side of the
+/// spacing that contains `tick`, clamped to the usable tick bounds
+///
+/// ## Arguments
+///
+/// * `tick`: the center tick, usually the current tick of the pool
+/// * `tick_spacing`: the spacing of the pool
+/// * `num_spacings`: the number of tick spacings on each side
+///
+/// ## Returns
+///
+/// (tick_lower, tick_upper)
+#[inline]
+pub fn tick_range_around<I: TickIndex>(tick: I, tick_spacing: I, num_spacings: u32) -> (I, I) {
+    let tick: i32 = tick.try_into().unwrap();
+    let tick_spacing: i32 = tick_spacing.try_into().unwrap();
+    assert!(tick_spacing > 0, "TICK_SPACING");
+    let spacing = tick_spacing as i64;
+    let max_usable = (MAX_TICK / tick_spacing * tick_spacing) as i64;
+    let base = Integer::div_floor(&tick, &tick_spacing) as i64 * spacing;
+    let tick_lower = (base - num_spacings as i64 * spacing).max(-max_usable);
+    let tick_upper = (base + (num_spacings as i64 + 1) * spacing).min(max_usable);
+    (
+        I::try_from(tick_lower as i32).unwrap(),
+        I::try_from(tick_upper as i32).unwrap(),
+    )
+}
+
 #[cfg(test)]
 mod tests {
     use crate::utils::{
         nearest_usable_tick,
         tick_math::{MAX_TICK, MIN_TICK},
+        tick_range_around, usable_tick_ceil, usable_tick_floor,
     };
     use alloy_primitives::aliases::I24;
 
@@ -95,4 +168,33 @@ mod tests {
         let tick = MAX_TICK / I24::from_limbs([2]) + I24::from_limbs([100]);
         assert_eq!(nearest_usable_tick(MAX_TICK, tick), tick);
     }
+
+    #[test]
+    fn test_tick_range_around() {
+        assert_eq!(tick_range_around(5, 10, 2), (-20, 30));
+        assert_eq!(tick_range_around(-5, 10, 0), (-10, 0));
+        assert_eq!(tick_range_around(0, 60, u32::MAX), (-887220, 887220));
+    }
+
+    #[test]
+    fn rounds_in_direction() {
+        for tick in [-6, -5, -4, 0, 4, 5, 6, 10] {
+            let floor = usable_tick_floor(tick, 10);
+            let ceil = usable_tick_ceil(tick, 10);
+            assert!(floor <= tick && tick - floor < 10);
+            assert!(ceil >= tick && ceil - tick < 10);
+            let nearest = nearest_usable_tick(tick, 10);
+            assert!(nearest == floor || nearest == ceil);
+        }
+        assert_eq!(usable_tick_floor(-FIVE, TEN), -TEN);
+        assert_eq!(usable_tick_ceil(FIVE, TEN), TEN);
+    }
+
+    #[test]
+    fn cannot_round_directionally_past_bounds() {
+        assert_eq!(usable_tick_floor(-887272, 10), -887270);
+        assert_eq!(usable_tick_ceil(-887272, 10), -887270);
+        assert_eq!(usable_tick_floor(887272, 10), 887270);
+        assert_eq!(usable_tick_ceil(887272, 10), 887270);
+    }
 }
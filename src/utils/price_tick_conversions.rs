@@ -2,6 +2,7 @@
 //! Utility functions for converting between [`I24`] ticks and SDK Core [`Price`] prices.
 
 use crate::prelude::{Error, *};
+use alloc::vec::Vec;
 use alloy_primitives::{aliases::I24, U160};
 use uniswap_sdk_core::prelude::*;
 
@@ -30,20 +31,90 @@ pub fn tick_to_price(
     })
 }
 
+/// Returns the prices of the base token in terms of the quote token at the given sqrt ratios,
+/// adjusted for decimals, i.e. [`Price::adjusted_for_decimals`] of the corresponding prices.
+///
+/// The token order and the decimal scaling factors are computed once for all elements, which makes
+/// it suitable for converting many ratios at once, e.g. to render a liquidity distribution.
+///
+/// ## Arguments
+///
+/// * `base_token`: the base token of the prices
+/// * `quote_token`: the quote token of the prices
+/// * `sqrt_ratios_x96`: the sqrt ratios for which to return the prices
+#[inline]
+pub fn sqrt_ratios_to_prices(
+    base_token: &Token,
+    quote_token: &Token,
+    sqrt_ratios_x96: &[U160],
+) -> Result<Vec<Fraction>, Error> {
+    let sorted = base_token.sorts_before(quote_token)?;
+    let q192 = Q192.to_big_int();
+    let base_scalar = BigInt::from(10).pow(base_token.decimals() as u32);
+    let quote_scalar = BigInt::from(10).pow(quote_token.decimals() as u32);
+    let (numerator_scalar, denominator_scalar) = if sorted {
+        (base_scalar, q192 * quote_scalar)
+    } else {
+        (q192 * base_scalar, quote_scalar)
+    };
+    Ok(sqrt_ratios_x96
+        .iter()
+        .map(|sqrt_ratio_x96| {
+            let ratio_x192 = sqrt_ratio_x96.to_big_int().pow(2);
+            if sorted {
+                Fraction::new(ratio_x192 * &numerator_scalar, denominator_scalar.clone())
+            } else {
+                Fraction::new(numerator_scalar.clone(), ratio_x192 * &denominator_scalar)
+            }
+        })
+        .collect())
+}
+
+/// Returns the prices of the base token in terms of the quote token at the given ticks, adjusted
+/// for decimals, i.e. [`Price::adjusted_for_decimals`] of the prices returned by [`tick_to_price`].
+///
+/// ## Arguments
+///
+/// * `base_token`: the base token of the prices
+/// * `quote_token`: the quote token of the prices
+/// * `ticks`: the ticks for which to return the prices
+#[inline]
+pub fn ticks_to_prices(
+    base_token: &Token,
+    quote_token: &Token,
+    ticks: &[I24],
+) -> Result<Vec<Fraction>, Error> {
+    let sqrt_ratios_x96 = ticks
+        .iter()
+        .map(|&tick| get_sqrt_ratio_at_tick(tick))
+        .collect::<Result<Vec<_>, _>>()?;
+    sqrt_ratios_to_prices(base_token, quote_token, &sqrt_ratios_x96)
+}
+
 /// Returns the first tick for which the given price is greater than or equal to the tick price
 ///
+/// The price is in raw units, so the tokens may have any decimals, e.g. 0 or 36, as long as the
+/// raw ratio of token1 to token0 is within the prices of [`MIN_TICK`] and [`MAX_TICK`], i.e.
+/// roughly 2^-128 to 2^128. For tokens whose decimals differ by 36 that is only up to about 340
+/// of the token with more decimals per unit of the other.
+///
 /// ## Arguments
 ///
 /// * `price`: for which to return the closest tick that represents a price less than or equal to
 ///   the input price, i.e. the price of the returned tick is less than or equal to the input price
+///
+/// ## Returns
+///
+/// [`Error::PriceOverflow`] if the sqrt ratio of the price does not fit in 160 bits, or
+/// [`Error::InvalidSqrtPrice`] if it is outside the range of ticks
 #[inline]
 pub fn price_to_closest_tick(price: &Price<Token, Token>) -> Result<I24, Error> {
     const ONE: I24 = I24::from_limbs([1]);
     let sorted = price.base_currency.sorts_before(&price.quote_currency)?;
     let sqrt_ratio_x96: U160 = if sorted {
-        encode_sqrt_ratio_x96(price.numerator.clone(), price.denominator.clone())
+        try_encode_sqrt_ratio_x96(price.numerator.clone(), price.denominator.clone())?
     } else {
-        encode_sqrt_ratio_x96(price.denominator.clone(), price.numerator.clone())
+        try_encode_sqrt_ratio_x96(price.denominator.clone(), price.numerator.clone())?
     };
     let tick = sqrt_ratio_x96.get_tick_at_sqrt_ratio()?;
     let next_tick_price = tick_to_price(
@@ -64,6 +135,65 @@ pub fn price_to_closest_tick(price: &Price<Token, Token>) -> Result<I24, Error>
     })
 }
 
+/// Returns the usable tick range of the narrowest position containing both prices, i.e. the lower
+/// tick is rounded down and the upper tick rounded up to the tick spacing, so that the range
+/// always contains the requested prices.
+///
+/// ## Arguments
+///
+/// * `price_a`: one of the bounds of the price range
+/// * `price_b`: the other bound of the price range, with the same base and quote tokens
+/// * `tick_spacing`: the spacing of the pool
+///
+/// ## Returns
+///
+/// (tick_lower, tick_upper)
+#[inline]
+pub fn price_range_to_usable_ticks(
+    price_a: &Price<Token, Token>,
+    price_b: &Price<Token, Token>,
+    tick_spacing: I24,
+) -> Result<(I24, I24), Error> {
+    let sorted = price_a
+        .base_currency
+        .sorts_before(&price_a.quote_currency)?;
+    // the closest tick is the tick at or below the price in token1/token0 terms
+    let tick_at_or_above = |price: &Price<Token, Token>| -> Result<I24, Error> {
+        let tick = price_to_closest_tick(price)?;
+        let tick_price = tick_to_price(
+            price.base_currency.clone(),
+            price.quote_currency.clone(),
+            tick,
+        )?;
+        let below = if sorted {
+            &tick_price < price
+        } else {
+            &tick_price > price
+        };
+        Ok(if below && tick < MAX_TICK {
+            tick + I24::ONE
+        } else {
+            tick
+        })
+    };
+    let tick_a = price_to_closest_tick(price_a)?;
+    let tick_b = price_to_closest_tick(price_b)?;
+    let (tick_lower, tick_upper) = if tick_a <= tick_b {
+        (tick_a, tick_at_or_above(price_b)?)
+    } else {
+        (tick_b, tick_at_or_above(price_a)?)
+    };
+    let tick_lower = usable_tick_floor(tick_lower, tick_spacing);
+    let tick_upper = usable_tick_ceil(tick_upper, tick_spacing);
+    Ok(if tick_lower < tick_upper {
+        (tick_lower, tick_upper)
+    } else if tick_upper + tick_spacing <= MAX_TICK {
+        (tick_lower, tick_upper + tick_spacing)
+    } else {
+        (tick_lower - tick_spacing, tick_upper)
+    })
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -97,6 +227,33 @@ mod tests {
             "token2"
         )
     });
+    static TOKEN3_0DECIMALS: Lazy<Token> = Lazy::new(|| {
+        token!(
+            1,
+            "3333333333333333333333333333333333333333",
+            0,
+            "T3",
+            "token3"
+        )
+    });
+    static TOKEN4_24DECIMALS: Lazy<Token> = Lazy::new(|| {
+        token!(
+            1,
+            "4444444444444444444444444444444444444444",
+            24,
+            "T4",
+            "token4"
+        )
+    });
+    static TOKEN5_36DECIMALS: Lazy<Token> = Lazy::new(|| {
+        token!(
+            1,
+            "5555555555555555555555555555555555555555",
+            36,
+            "T5",
+            "token5"
+        )
+    });
 
     #[test]
     fn tick_to_price_test_1() {
@@ -351,4 +508,217 @@ mod tests {
             -I24::from_limbs([276225])
         );
     }
+
+    #[test]
+    fn ticks_to_prices_match_tick_to_price() {
+        let ticks = [
+            -I24::from_limbs([276423]),
+            -I24::from_limbs([74959]),
+            I24::ZERO,
+            I24::from_limbs([74959]),
+            MIN_TICK,
+            MAX_TICK,
+        ];
+        for (base, quote) in [
+            (&*TOKEN0, &*TOKEN1),
+            (&*TOKEN1, &*TOKEN0),
+            (&*TOKEN0, &*TOKEN2_6DECIMALS),
+            (&*TOKEN2_6DECIMALS, &*TOKEN0),
+        ] {
+            let prices = ticks_to_prices(base, quote, &ticks).unwrap();
+            assert_eq!(prices.len(), ticks.len());
+            for (tick, price) in ticks.into_iter().zip(prices) {
+                let expected = tick_to_price(base.clone(), quote.clone(), tick)
+                    .unwrap()
+                    .adjusted_for_decimals();
+                assert_eq!(
+                    price.numerator.clone() * expected.denominator.clone(),
+                    expected.numerator.clone() * price.denominator.clone()
+                );
+            }
+        }
+    }
+
+    #[test]
+    fn ticks_to_prices_out_of_bounds() {
+        assert!(ticks_to_prices(&TOKEN0, &TOKEN1, &[MAX_TICK + I24::ONE]).is_err());
+        assert!(ticks_to_prices(&TOKEN0, &TOKEN1, &[]).unwrap().is_empty());
+    }
+
+    #[test]
+    fn price_range_to_usable_ticks_contains_prices() {
+        let tick_spacing = I24::from_limbs([60]);
+        // the nearest usable tick of both bounds is 74940, which would exclude the upper price
+        let price_lower =
+            tick_to_price(TOKEN0.clone(), TOKEN1.clone(), I24::from_limbs([74941])).unwrap();
+        let price_upper =
+            tick_to_price(TOKEN0.clone(), TOKEN1.clone(), I24::from_limbs([74959])).unwrap();
+        let expected = (I24::from_limbs([74940]), I24::from_limbs([75000]));
+        for base in [false, true] {
+            let (price_a, price_b) = if base {
+                (price_upper.invert(), price_lower.invert())
+            } else {
+                (price_lower.clone(), price_upper.clone())
+            };
+            assert_eq!(
+                price_range_to_usable_ticks(&price_a, &price_b, tick_spacing).unwrap(),
+                expected
+            );
+            assert_eq!(
+                price_range_to_usable_ticks(&price_b, &price_a, tick_spacing).unwrap(),
+                expected
+            );
+        }
+
+        // prices between ticks round outwards, prices on a usable tick stay on it
+        let price = tick_to_price(TOKEN0.clone(), TOKEN1.clone(), I24::from_limbs([120])).unwrap();
+        let above = Price::new(
+            TOKEN0.clone(),
+            TOKEN1.clone(),
+            price.denominator.clone() * BigInt::from(1_000_000),
+            price.numerator.clone() * BigInt::from(1_000_001),
+        );
+        assert_eq!(
+            price_range_to_usable_ticks(&price, &above, tick_spacing).unwrap(),
+            (I24::from_limbs([120]), I24::from_limbs([180]))
+        );
+        assert_eq!(
+            price_range_to_usable_ticks(&price, &price, tick_spacing).unwrap(),
+            (I24::from_limbs([120]), I24::from_limbs([180]))
+        );
+    }
+
+    #[test]
+    fn round_trips_for_tokens_with_0_24_and_36_decimals() {
+        let tokens = [
+            &*TOKEN0,
+            &*TOKEN2_6DECIMALS,
+            &*TOKEN3_0DECIMALS,
+            &*TOKEN4_24DECIMALS,
+            &*TOKEN5_36DECIMALS,
+        ];
+        let ticks = [
+            MIN_TICK,
+            -I24::from_limbs([276225]),
+            I24::ZERO,
+            I24::from_limbs([828972]),
+            MAX_TICK - I24::ONE,
+        ];
+        for base in tokens {
+            for quote in tokens.into_iter().filter(|&quote| quote != base) {
+                for tick in ticks {
+                    let price = tick_to_price(base.clone(), quote.clone(), tick).unwrap();
+                    assert_eq!(price_to_closest_tick(&price).unwrap(), tick);
+                }
+            }
+        }
+    }
+
+    #[test]
+    fn tick_to_price_for_tokens_with_0_24_and_36_decimals() {
+        assert_eq!(
+            tick_to_price(
+                TOKEN3_0DECIMALS.clone(),
+                TOKEN4_24DECIMALS.clone(),
+                I24::from_limbs([552648])
+            )
+            .unwrap()
+            .to_significant(5, None)
+            .unwrap(),
+            "0.99999"
+        );
+        assert_eq!(
+            tick_to_price(
+                TOKEN3_0DECIMALS.clone(),
+                TOKEN5_36DECIMALS.clone(),
+                I24::from_limbs([828972])
+            )
+            .unwrap()
+            .to_significant(5, None)
+            .unwrap(),
+            "0.99999"
+        );
+    }
+
+    #[test]
+    fn price_to_closest_tick_for_tokens_with_0_24_and_36_decimals() {
+        let ten = BigInt::from(10);
+        // 1 unit of token3 for 1 unit of token4
+        assert_eq!(
+            price_to_closest_tick(&Price::new(
+                TOKEN3_0DECIMALS.clone(),
+                TOKEN4_24DECIMALS.clone(),
+                1,
+                ten.pow(24),
+            ))
+            .unwrap(),
+            I24::from_limbs([552648])
+        );
+        // 1 unit of token3 for 1 unit of token5, in both directions
+        assert_eq!(
+            price_to_closest_tick(&Price::new(
+                TOKEN3_0DECIMALS.clone(),
+                TOKEN5_36DECIMALS.clone(),
+                1,
+                ten.pow(36),
+            ))
+            .unwrap(),
+            I24::from_limbs([828972])
+        );
+        assert_eq!(
+            price_to_closest_tick(&Price::new(
+                TOKEN5_36DECIMALS.clone(),
+                TOKEN3_0DECIMALS.clone(),
+                ten.pow(36),
+                1,
+            ))
+            .unwrap(),
+            I24::from_limbs([828972])
+        );
+        // 100 units of token5 per unit of token3 are still within the range of ticks
+        assert_eq!(
+            price_to_closest_tick(&Price::new(
+                TOKEN3_0DECIMALS.clone(),
+                TOKEN5_36DECIMALS.clone(),
+                1,
+                ten.pow(38),
+            ))
+            .unwrap(),
+            I24::from_limbs([875026])
+        );
+    }
+
+    #[test]
+    fn price_to_closest_tick_out_of_range() {
+        let ten = BigInt::from(10);
+        // 1000 units of token5 per unit of token3 overflow the sqrt ratio
+        assert!(matches!(
+            price_to_closest_tick(&Price::new(
+                TOKEN3_0DECIMALS.clone(),
+                TOKEN5_36DECIMALS.clone(),
+                1,
+                ten.pow(39),
+            )),
+            Err(Error::PriceOverflow)
+        ));
+        assert!(matches!(
+            price_to_closest_tick(&Price::new(
+                TOKEN5_36DECIMALS.clone(),
+                TOKEN3_0DECIMALS.clone(),
+                ten.pow(39),
+                1,
+            )),
+            Err(Error::PriceOverflow)
+        ));
+        // a price below that of the minimum tick
+        assert!(matches!(
+            price_to_closest_tick(&Price::new(
+                TOKEN3_0DECIMALS.clone(),
+                TOKEN5_36DECIMALS.clone(),
+                ten.pow(39),
+                1,
+            )),
+            Err(Error::InvalidSqrtPrice(_))
+        ));
+    }
 }
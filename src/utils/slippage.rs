@@ -0,0 +1,163 @@
+use crate::prelude::{Error, *};
+use alloy_primitives::U160;
+use once_cell::sync::Lazy;
+use uniswap_sdk_core::prelude::*;
+
+/// The lower bound of the slippage tolerance suggested by [`suggest_slippage_tolerance`], 0.05%.
+pub static MIN_SLIPPAGE_TOLERANCE: Lazy<Percent> = Lazy::new(|| Percent::new(5, 10_000));
+
+/// The upper bound of the slippage tolerance suggested by [`suggest_slippage_tolerance`], 5%.
+pub static MAX_SLIPPAGE_TOLERANCE: Lazy<Percent> = Lazy::new(|| Percent::new(5, 100));
+
+/// A slippage tolerance suggested by [`suggest_slippage_tolerance`] and how it was derived.
+#[derive(Clone, Debug, PartialEq)]
+pub struct SlippageSuggestion {
+    /// The suggested slippage tolerance, the sum of `price_move` and `volatility_buffer` clamped
+    /// between [`MIN_SLIPPAGE_TOLERANCE`] and [`MAX_SLIPPAGE_TOLERANCE`].
+    pub tolerance: Percent,
+    /// How much the mid price moves when swapping the amount, i.e. how much a competing trade of
+    /// the same size executed first would worsen the price. Shallow pools move more.
+    pub price_move: Percent,
+    /// Twice the recent volatility, covering most market moves before the trade is executed.
+    pub volatility_buffer: Percent,
+    /// Whether the tolerance was clamped to the bounds.
+    pub clamped: bool,
+}
+
+/// Suggests a slippage tolerance for swapping an amount through a pool from the depth of the pool
+/// at the size of the trade and the recent volatility of its price
+///
+/// ## Arguments
+///
+/// * `pool`: The pool to swap through
+/// * `amount_in`: The amount swapped
+/// * `recent_volatility`: The standard deviation of the relative price changes over the expected
+///   time to execution, e.g. a few blocks, estimated from recent swaps
+#[inline]
+pub fn suggest_slippage_tolerance<TP: Clone + TickDataProvider>(
+    pool: &Pool<TP>,
+    amount_in: &CurrencyAmount<impl BaseCurrency>,
+    recent_volatility: &Percent,
+) -> Result<SlippageSuggestion, Error> {
+    let mut pool_after = pool.clone();
+    pool_after.get_output_amount_mut(amount_in, None)?;
+    // the price moves by 1 - (lower / higher)^2 in either direction
+    let (higher, lower) = if pool.sqrt_ratio_x96 >= pool_after.sqrt_ratio_x96 {
+        (pool.sqrt_ratio_x96, pool_after.sqrt_ratio_x96)
+    } else {
+        (pool_after.sqrt_ratio_x96, pool.sqrt_ratio_x96)
+    };
+    let higher = higher.to_big_uint();
+    let lower = lower.to_big_uint();
+    let higher_squared = &higher * &higher;
+    let price_move = Percent::new(&higher_squared - &lower * &lower, higher_squared);
+    let volatility_buffer = recent_volatility.clone() * Percent::new(2, 1);
+
+    let tolerance = price_move.clone() + volatility_buffer.clone();
+    let (tolerance, clamped) = if tolerance < *MIN_SLIPPAGE_TOLERANCE {
+        (MIN_SLIPPAGE_TOLERANCE.clone(), true)
+    } else if tolerance > *MAX_SLIPPAGE_TOLERANCE {
+        (MAX_SLIPPAGE_TOLERANCE.clone(), true)
+    } else {
+        (tolerance, false)
+    };
+    Ok(SlippageSuggestion {
+        tolerance,
+        price_move,
+        volatility_buffer,
+        clamped,
+    })
+}
+
+/// Derives the price limit of a single-hop swap from the slippage tolerance, i.e. the expected
+/// price of the pool after the swap worsened by the slippage tolerance, so that the pool itself
+/// stops the swap at the worst acceptable price
+///
+/// ## Arguments
+///
+/// * `sqrt_price_x96_after`: The expected sqrt price of the pool after the swap
+/// * `zero_for_one`: Whether the swap is from token0 to token1, i.e. moves the price down
+/// * `slippage_tolerance`: How much the price is allowed to move unfavorably
+#[inline]
+#[must_use]
+pub fn sqrt_price_limit_from_slippage(
+    sqrt_price_x96_after: U160,
+    zero_for_one: bool,
+    slippage_tolerance: &Percent,
+) -> U160 {
+    let numerator = slippage_tolerance.numerator();
+    let denominator = slippage_tolerance.denominator();
+    let sqrt_price = sqrt_price_x96_after.to_big_int();
+    let price_x192 = &sqrt_price * &sqrt_price;
+    // the price may move down to p / (1 + s) or up to p * (1 + s)
+    let limit = if zero_for_one {
+        (price_x192 * denominator / (denominator + numerator)).sqrt()
+    } else {
+        (price_x192 * (denominator + numerator) / denominator).sqrt()
+    };
+    U160::from_big_int(
+        limit
+            .max(MIN_SQRT_RATIO.to_big_int() + 1)
+            .min(MAX_SQRT_RATIO.to_big_int() - 1),
+    )
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::tests::*;
+
+    #[test]
+    fn clamps_to_minimum_for_small_trades() {
+        let pool = make_pool(TOKEN0.clone(), TOKEN1.clone());
+        let amount = CurrencyAmount::from_raw_amount(TOKEN0.clone(), 10).unwrap();
+        let suggestion = suggest_slippage_tolerance(&pool, &amount, &Percent::new(0, 1)).unwrap();
+        assert!(suggestion.clamped);
+        assert_eq!(suggestion.tolerance, *MIN_SLIPPAGE_TOLERANCE);
+        assert!(suggestion.price_move < *MIN_SLIPPAGE_TOLERANCE);
+    }
+
+    #[test]
+    fn adds_volatility_buffer_to_price_move() {
+        let pool = make_pool(TOKEN0.clone(), TOKEN1.clone());
+        let amount = CurrencyAmount::from_raw_amount(TOKEN1.clone(), 1000).unwrap();
+        let suggestion =
+            suggest_slippage_tolerance(&pool, &amount, &Percent::new(5, 1000)).unwrap();
+        assert!(!suggestion.clamped);
+        assert_eq!(suggestion.volatility_buffer, Percent::new(1, 100));
+        assert_eq!(
+            suggestion.tolerance,
+            suggestion.price_move.clone() + Percent::new(1, 100)
+        );
+        // swapping 0.1% of the liquidity moves the price by about 0.2%
+        assert_eq!(suggestion.price_move.to_fixed(2, None), "0.20");
+    }
+
+    #[test]
+    fn clamps_to_maximum_for_volatile_pools() {
+        let pool = make_pool(TOKEN0.clone(), TOKEN1.clone());
+        let amount = CurrencyAmount::from_raw_amount(TOKEN0.clone(), 1000).unwrap();
+        let suggestion = suggest_slippage_tolerance(&pool, &amount, &Percent::new(1, 10)).unwrap();
+        assert!(suggestion.clamped);
+        assert_eq!(suggestion.tolerance, *MAX_SLIPPAGE_TOLERANCE);
+    }
+
+    #[test]
+    fn price_limit_from_slippage() {
+        let sqrt_price_x96 = encode_sqrt_ratio_x96(1, 1);
+        let slippage = Percent::new(21, 100);
+        // 1 / 1.21 = (1 / 1.1)^2
+        assert_eq!(
+            sqrt_price_limit_from_slippage(sqrt_price_x96, true, &slippage),
+            encode_sqrt_ratio_x96(100, 121)
+        );
+        assert_eq!(
+            sqrt_price_limit_from_slippage(sqrt_price_x96, false, &slippage),
+            encode_sqrt_ratio_x96(121, 100)
+        );
+        assert_eq!(
+            sqrt_price_limit_from_slippage(MIN_SQRT_RATIO, true, &slippage),
+            MIN_SQRT_RATIO + U160::from(1)
+        );
+    }
+}
@@ -8,17 +8,35 @@ pub struct SwapState<I = i32> {
     pub sqrt_price_x96: U160,
     pub tick_current: I,
     pub liquidity: u128,
+    /// The total fee paid to liquidity providers, in the input token.
+    pub fee_amount: U256,
+    /// The number of initialized ticks crossed.
+    pub ticks_crossed: u32,
 }
 
-#[derive(Clone, Copy, Debug, Default)]
-struct StepComputations<I = i32> {
-    sqrt_price_start_x96: U160,
-    tick_next: I,
-    initialized: bool,
-    sqrt_price_next_x96: U160,
-    amount_in: U256,
-    amount_out: U256,
-    fee_amount: U256,
+/// A step of a swap, i.e. the swap within one tick bitmap word or up to the next initialized tick.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub struct SwapStep<I = i32> {
+    /// The price at the beginning of the step.
+    pub sqrt_price_start_x96: U160,
+    /// The next tick to swap to from the current tick in the swap direction.
+    pub tick_next: I,
+    /// Whether `tick_next` is initialized.
+    pub initialized: bool,
+    /// The price at `tick_next`.
+    pub sqrt_price_next_x96: U160,
+    /// How much is being swapped in in this step.
+    pub amount_in: U256,
+    /// How much is being swapped out.
+    pub amount_out: U256,
+    /// How much fee is being paid in.
+    pub fee_amount: U256,
+    /// The in range liquidity the step is swapped against.
+    pub liquidity: u128,
+    /// The price at the end of the step.
+    pub sqrt_price_end_x96: U160,
+    /// The current tick at the end of the step, after crossing `tick_next` if it was reached.
+    pub tick_end: I,
 }
 
 /// Computes the result of swapping some amount in, or amount out, given the parameters of the swap
@@ -153,64 +171,125 @@ pub fn compute_swap_step<const BITS: usize, const LIMBS: usize>(
     Ok((sqrt_ratio_next_x96, amount_in, amount_out, fee_amount))
 }
 
-#[inline]
-#[allow(clippy::too_many_arguments)]
-pub fn v3_swap<TP: TickDataProvider>(
+/// The swap loop of a pool driven one step at a time, so that external logic, e.g. the state of
+/// another venue, can be interleaved between the tick crossings of a swap.
+///
+/// [`v3_swap`] runs the machine to completion.
+#[derive(Clone, Debug)]
+pub struct SwapStateMachine<'a, TP: TickDataProvider> {
     fee: U24,
-    sqrt_price_x96: U160,
-    tick_current: TP::Index,
-    liquidity: u128,
     tick_spacing: TP::Index,
-    tick_data_provider: &TP,
+    tick_data_provider: &'a TP,
     zero_for_one: bool,
-    amount_specified: I256,
-    sqrt_price_limit_x96: Option<U160>,
-) -> Result<SwapState<TP::Index>, Error> {
-    let sqrt_price_limit_x96 = sqrt_price_limit_x96.unwrap_or_else(|| {
+    sqrt_price_limit_x96: U160,
+    exact_input: bool,
+    state: SwapState<TP::Index>,
+}
+
+impl<'a, TP: TickDataProvider> SwapStateMachine<'a, TP> {
+    /// Starts a swap, see [`v3_swap`] for the arguments
+    ///
+    /// ## Arguments
+    ///
+    /// * `fee`: The fee of the pool in hundredths of a bip
+    /// * `sqrt_price_x96`: The current sqrt price of the pool
+    /// * `tick_current`: The current tick of the pool
+    /// * `liquidity`: The current in range liquidity of the pool
+    /// * `tick_spacing`: The tick spacing of the pool
+    /// * `tick_data_provider`: The tick data provider of the pool
+    /// * `zero_for_one`: The direction of the swap
+    /// * `amount_specified`: The amount of the swap, positive for an exact input
+    /// * `sqrt_price_limit_x96`: The price the swap cannot go beyond
+    #[inline]
+    #[allow(clippy::too_many_arguments)]
+    pub fn new(
+        fee: U24,
+        sqrt_price_x96: U160,
+        tick_current: TP::Index,
+        liquidity: u128,
+        tick_spacing: TP::Index,
+        tick_data_provider: &'a TP,
+        zero_for_one: bool,
+        amount_specified: I256,
+        sqrt_price_limit_x96: Option<U160>,
+    ) -> Self {
+        let sqrt_price_limit_x96 =
+            sqrt_price_limit_x96.unwrap_or(SwapDirection::new(zero_for_one).price_limit_default);
+
         if zero_for_one {
-            MIN_SQRT_RATIO + ONE
+            assert!(sqrt_price_limit_x96 > MIN_SQRT_RATIO, "RATIO_MIN");
+            assert!(sqrt_price_limit_x96 < sqrt_price_x96, "RATIO_CURRENT");
         } else {
-            MAX_SQRT_RATIO - ONE
+            assert!(sqrt_price_limit_x96 < MAX_SQRT_RATIO, "RATIO_MAX");
+            assert!(sqrt_price_limit_x96 > sqrt_price_x96, "RATIO_CURRENT");
         }
-    });
 
-    if zero_for_one {
-        assert!(sqrt_price_limit_x96 > MIN_SQRT_RATIO, "RATIO_MIN");
-        assert!(sqrt_price_limit_x96 < sqrt_price_x96, "RATIO_CURRENT");
-    } else {
-        assert!(sqrt_price_limit_x96 < MAX_SQRT_RATIO, "RATIO_MAX");
-        assert!(sqrt_price_limit_x96 > sqrt_price_x96, "RATIO_CURRENT");
+        Self {
+            fee,
+            tick_spacing,
+            tick_data_provider,
+            zero_for_one,
+            sqrt_price_limit_x96,
+            exact_input: amount_specified >= I256::ZERO,
+            // keep track of swap state
+            state: SwapState {
+                amount_specified_remaining: amount_specified,
+                amount_calculated: I256::ZERO,
+                sqrt_price_x96,
+                tick_current,
+                liquidity,
+                fee_amount: U256::ZERO,
+                ticks_crossed: 0,
+            },
+        }
     }
 
-    let exact_input = amount_specified >= I256::ZERO;
+    /// The state of the swap after the steps taken so far
+    #[inline]
+    #[must_use]
+    pub const fn state(&self) -> &SwapState<TP::Index> {
+        &self.state
+    }
 
-    // keep track of swap state
-    let mut state = SwapState {
-        amount_specified_remaining: amount_specified,
-        amount_calculated: I256::ZERO,
-        sqrt_price_x96,
-        tick_current,
-        liquidity,
-    };
+    /// Consumes the machine, returning the state of the swap after the steps taken so far
+    #[inline]
+    #[must_use]
+    pub const fn into_state(self) -> SwapState<TP::Index> {
+        self.state
+    }
 
-    // start swap while loop
-    while !state.amount_specified_remaining.is_zero()
-        && state.sqrt_price_x96 != sqrt_price_limit_x96
-    {
-        let mut step = StepComputations {
+    /// Whether the whole amount has been swapped or the price limit reached
+    #[inline]
+    #[must_use]
+    pub fn is_done(&self) -> bool {
+        self.state.amount_specified_remaining.is_zero()
+            || self.state.sqrt_price_x96 == self.sqrt_price_limit_x96
+    }
+
+    /// Takes the next step of the swap, returning `None` if the swap is done
+    #[inline]
+    pub fn next_step(&mut self) -> Result<Option<SwapStep<TP::Index>>, Error> {
+        if self.is_done() {
+            return Ok(None);
+        }
+        let zero_for_one = self.zero_for_one;
+        let state = &mut self.state;
+        let mut step = SwapStep {
             sqrt_price_start_x96: state.sqrt_price_x96,
+            liquidity: state.liquidity,
             ..Default::default()
         };
 
         // because each iteration of the while loop rounds, we can't optimize this code
         // (relative to the smart contract) by simply traversing to the next available tick, we
         // instead need to exactly replicate
-        (step.tick_next, step.initialized) = tick_data_provider
-            .next_initialized_tick_within_one_word(
-                state.tick_current,
-                zero_for_one,
-                tick_spacing,
-            )?;
+        let tick_current = state.tick_current;
+        (step.tick_next, step.initialized) = self
+            .tick_data_provider
+            .next_initialized_tick_within_one_word(tick_current, zero_for_one, self.tick_spacing)
+            .map_err(|err| {
+                err.with_context(ErrorContext::new().with_tick(tick_current.to_i24().as_i32()))
+            })?;
 
         step.tick_next = TP::Index::from_i24(step.tick_next.to_i24().clamp(MIN_TICK, MAX_TICK));
         step.sqrt_price_next_x96 = get_sqrt_ratio_at_tick(step.tick_next.to_i24())?;
@@ -223,16 +302,17 @@ pub fn v3_swap<TP: TickDataProvider>(
         ) = compute_swap_step(
             state.sqrt_price_x96,
             if zero_for_one {
-                step.sqrt_price_next_x96.max(sqrt_price_limit_x96)
+                step.sqrt_price_next_x96.max(self.sqrt_price_limit_x96)
             } else {
-                step.sqrt_price_next_x96.min(sqrt_price_limit_x96)
+                step.sqrt_price_next_x96.min(self.sqrt_price_limit_x96)
             },
             state.liquidity,
             state.amount_specified_remaining,
-            fee,
+            self.fee,
         )?;
+        state.fee_amount += step.fee_amount;
 
-        if exact_input {
+        if self.exact_input {
             state.amount_specified_remaining = I256::from_raw(
                 state.amount_specified_remaining.into_raw() - step.amount_in - step.fee_amount,
             );
@@ -249,13 +329,22 @@ pub fn v3_swap<TP: TickDataProvider>(
         if state.sqrt_price_x96 == step.sqrt_price_next_x96 {
             // if the tick is initialized, run the tick transition
             if step.initialized {
-                let mut liquidity_net = tick_data_provider.get_tick(step.tick_next)?.liquidity_net;
+                let mut liquidity_net = self
+                    .tick_data_provider
+                    .get_tick(step.tick_next)
+                    .map_err(|err| {
+                        err.with_context(
+                            ErrorContext::new().with_tick(step.tick_next.to_i24().as_i32()),
+                        )
+                    })?
+                    .liquidity_net;
                 // if we're moving leftward, we interpret liquidityNet as the opposite sign
                 // safe because liquidityNet cannot be type(int128).min
                 if zero_for_one {
                     liquidity_net = -liquidity_net;
                 }
                 state.liquidity = add_delta(state.liquidity, liquidity_net)?;
+                state.ticks_crossed += 1;
             }
             state.tick_current = if zero_for_one {
                 step.tick_next - TP::Index::ONE
@@ -268,9 +357,90 @@ pub fn v3_swap<TP: TickDataProvider>(
             state.tick_current =
                 TP::Index::from_i24(state.sqrt_price_x96.get_tick_at_sqrt_ratio()?);
         }
+        step.sqrt_price_end_x96 = state.sqrt_price_x96;
+        step.tick_end = state.tick_current;
+
+        Ok(Some(step))
     }
 
-    Ok(state)
+    /// Turns the machine into an iterator over the remaining steps of the swap
+    #[inline]
+    #[must_use]
+    pub const fn into_steps(self) -> SwapSteps<'a, TP> {
+        SwapSteps {
+            machine: self,
+            failed: false,
+        }
+    }
+}
+
+/// An iterator over the steps of a swap, see [`Pool::swap_steps`].
+///
+/// The iterator ends after the first error.
+#[derive(Clone, Debug)]
+pub struct SwapSteps<'a, TP: TickDataProvider> {
+    machine: SwapStateMachine<'a, TP>,
+    failed: bool,
+}
+
+impl<'a, TP: TickDataProvider> SwapSteps<'a, TP> {
+    /// The state of the swap after the steps yielded so far
+    #[inline]
+    #[must_use]
+    pub const fn state(&self) -> &SwapState<TP::Index> {
+        self.machine.state()
+    }
+
+    /// Consumes the iterator, returning the underlying state machine
+    #[inline]
+    #[must_use]
+    pub const fn into_machine(self) -> SwapStateMachine<'a, TP> {
+        self.machine
+    }
+}
+
+impl<TP: TickDataProvider> Iterator for SwapSteps<'_, TP> {
+    type Item = Result<SwapStep<TP::Index>, Error>;
+
+    #[inline]
+    fn next(&mut self) -> Option<Self::Item> {
+        if self.failed {
+            return None;
+        }
+        let step = self.machine.next_step().transpose();
+        self.failed = matches!(step, Some(Err(_)));
+        step
+    }
+}
+
+impl<TP: TickDataProvider> core::iter::FusedIterator for SwapSteps<'_, TP> {}
+
+#[inline]
+#[allow(clippy::too_many_arguments)]
+pub fn v3_swap<TP: TickDataProvider>(
+    fee: U24,
+    sqrt_price_x96: U160,
+    tick_current: TP::Index,
+    liquidity: u128,
+    tick_spacing: TP::Index,
+    tick_data_provider: &TP,
+    zero_for_one: bool,
+    amount_specified: I256,
+    sqrt_price_limit_x96: Option<U160>,
+) -> Result<SwapState<TP::Index>, Error> {
+    let mut machine = SwapStateMachine::new(
+        fee,
+        sqrt_price_x96,
+        tick_current,
+        liquidity,
+        tick_spacing,
+        tick_data_provider,
+        zero_for_one,
+        amount_specified,
+        sqrt_price_limit_x96,
+    );
+    while machine.next_step()?.is_some() {}
+    Ok(machine.into_state())
 }
 
 #[cfg(test)]
@@ -1,6 +1,87 @@
-use alloy_primitives::{Signed, Uint};
+use crate::error::Error;
+use alloy_primitives::{Signed, Uint, U160, U256};
 use bigdecimal::BigDecimal;
 use num_bigint::{BigInt, BigUint, Sign};
+use num_integer::Integer;
+use uniswap_sdk_core::prelude::{Fraction, Rounding};
+
+/// Widens a [`U160`], e.g. a sqrt ratio, to a [`U256`] for use in full precision math. The
+/// conversion is lossless.
+#[inline]
+#[must_use]
+pub const fn u160_to_u256(x: U160) -> U256 {
+    let [a, b, c] = *x.as_limbs();
+    U256::from_limbs([a, b, c, 0])
+}
+
+/// Narrows a [`U256`] to a [`U160`], e.g. to use the result of full precision math as a sqrt ratio
+/// or a price limit.
+///
+/// ## Returns
+///
+/// [`Error::SafeCastToU160Overflow`] if the value does not fit in 160 bits
+#[inline]
+pub const fn u256_to_u160(x: U256) -> Result<U160, Error> {
+    let [a, b, c, d] = *x.as_limbs();
+    if d != 0 || c >> 32 != 0 {
+        Err(Error::SafeCastToU160Overflow)
+    } else {
+        Ok(U160::from_limbs([a, b, c]))
+    }
+}
+
+/// Converts a [`BigInt`], e.g. the quotient of a slippage adjusted amount, to a [`U256`].
+///
+/// Unlike [`FromBig::from_big_int`], which wraps negative values around and panics on values wider
+/// than 256 bits, the conversion is checked.
+///
+/// ## Returns
+///
+/// [`Error::SafeCastToU256Overflow`] if the value is negative or does not fit in 256 bits
+#[inline]
+pub fn big_int_to_u256(x: &BigInt) -> Result<U256, Error> {
+    let (sign, data) = x.to_u64_digits();
+    if sign == Sign::Minus || data.len() > 4 {
+        return Err(Error::SafeCastToU256Overflow);
+    }
+    Ok(U256::from_limbs_slice(&data))
+}
+
+/// Converts a [`BigInt`] to a [`U256`], saturating negative values to zero and values wider than
+/// 256 bits to [`U256::MAX`].
+#[inline]
+#[must_use]
+pub fn big_int_to_u256_saturating(x: &BigInt) -> U256 {
+    match x.sign() {
+        Sign::Minus => U256::ZERO,
+        _ => big_int_to_u256(x).unwrap_or(U256::MAX),
+    }
+}
+
+/// Converts a [`Fraction`], e.g. an amount multiplied by a price or a slippage tolerance, to a
+/// [`U256`] in full precision, only rounding the final division in the given direction.
+///
+/// ## Arguments
+///
+/// * `fraction`: The fraction to convert, whose numerator and denominator may be arbitrarily large
+/// * `rounding`: The rounding of the division
+///
+/// ## Returns
+///
+/// [`Error::SafeCastToU256Overflow`] if the rounded value is negative or does not fit in 256 bits
+#[inline]
+pub fn fraction_to_u256(fraction: &Fraction, rounding: Rounding) -> Result<U256, Error> {
+    let numerator = &fraction.numerator;
+    let denominator = &fraction.denominator;
+    let quotient = match rounding {
+        Rounding::RoundDown => numerator.div_floor(denominator),
+        Rounding::RoundUp => numerator.div_ceil(denominator),
+        Rounding::RoundHalfUp => {
+            (numerator * 2_u32 + denominator).div_floor(&(denominator * 2_u32))
+        }
+    };
+    big_int_to_u256(&quotient)
+}
 
 pub trait ToBig {
     fn to_big_uint(&self) -> BigUint;
@@ -74,7 +155,98 @@ impl<const BITS: usize, const LIMBS: usize> FromBig for Signed<BITS, LIMBS> {
 #[cfg(test)]
 mod tests {
     use super::*;
-    use alloy_primitives::{I256, U256};
+    use alloy_primitives::I256;
+
+    #[test]
+    fn test_u160_u256_conversions() {
+        assert_eq!(u160_to_u256(U160::MAX), U256::from(U160::MAX));
+        assert_eq!(u256_to_u160(U256::from(U160::MAX)).unwrap(), U160::MAX);
+        assert_eq!(u256_to_u160(U256::ZERO).unwrap(), U160::ZERO);
+        assert!(matches!(
+            u256_to_u160(U256::from(U160::MAX) + U256::from(1)),
+            Err(Error::SafeCastToU160Overflow)
+        ));
+        assert!(matches!(
+            u256_to_u160(U256::MAX),
+            Err(Error::SafeCastToU160Overflow)
+        ));
+    }
+
+    #[test]
+    fn test_big_int_to_u256() {
+        let max = U256::MAX.to_big_int();
+        assert_eq!(big_int_to_u256(&max).unwrap(), U256::MAX);
+        assert_eq!(big_int_to_u256(&BigInt::ZERO).unwrap(), U256::ZERO);
+        assert!(matches!(
+            big_int_to_u256(&(&max + 1)),
+            Err(Error::SafeCastToU256Overflow)
+        ));
+        assert!(matches!(
+            big_int_to_u256(&BigInt::from(-1)),
+            Err(Error::SafeCastToU256Overflow)
+        ));
+        assert_eq!(big_int_to_u256_saturating(&(&max + 1)), U256::MAX);
+        assert_eq!(big_int_to_u256_saturating(&BigInt::from(-1)), U256::ZERO);
+    }
+
+    #[test]
+    fn test_fraction_to_u256_rounding() {
+        let third = Fraction::new(1, 3);
+        assert_eq!(
+            fraction_to_u256(&third, Rounding::RoundDown).unwrap(),
+            U256::ZERO
+        );
+        assert_eq!(
+            fraction_to_u256(&third, Rounding::RoundUp).unwrap(),
+            U256::from(1)
+        );
+        assert_eq!(
+            fraction_to_u256(&third, Rounding::RoundHalfUp).unwrap(),
+            U256::ZERO
+        );
+        assert_eq!(
+            fraction_to_u256(&Fraction::new(1, 2), Rounding::RoundHalfUp).unwrap(),
+            U256::from(1)
+        );
+        assert!(matches!(
+            fraction_to_u256(&Fraction::new(-1, 2), Rounding::RoundDown),
+            Err(Error::SafeCastToU256Overflow)
+        ));
+    }
+
+    #[test]
+    fn test_fraction_to_u256_wide_intermediates() {
+        // the numerator and denominator exceed 256 bits while the value fits
+        let max = U256::MAX.to_big_int();
+        let wide = Fraction::new(&max * &max, max.clone());
+        assert_eq!(
+            fraction_to_u256(&wide, Rounding::RoundDown).unwrap(),
+            U256::MAX
+        );
+        let over = Fraction::new(&max * &max, &max - 1);
+        assert!(matches!(
+            fraction_to_u256(&over, Rounding::RoundDown),
+            Err(Error::SafeCastToU256Overflow)
+        ));
+    }
+
+    #[test]
+    fn test_fraction_to_u256_shib_wbtc() {
+        // 1 trillion SHIB (18 decimals) at 1e-10 WBTC (8 decimals) per SHIB, i.e. a raw price of
+        // 1e-20, less a 0.5% slippage tolerance applied twice
+        let amount = BigInt::from(10).pow(30);
+        let price = Fraction::new(1, BigInt::from(10).pow(20));
+        let slippage = Fraction::new(1000, 1005);
+        let out = Fraction::new(amount, 1) * price * slippage.clone() * slippage;
+        assert_eq!(
+            fraction_to_u256(&out, Rounding::RoundDown).unwrap(),
+            U256::from(9_900_745_031_u64)
+        );
+        assert_eq!(
+            fraction_to_u256(&out, Rounding::RoundUp).unwrap(),
+            U256::from(9_900_745_032_u64)
+        );
+    }
 
     #[test]
     fn test_uint_to_big() {
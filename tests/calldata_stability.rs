@@ -0,0 +1,215 @@
+//! Golden calldata corpus.
+//!
+//! Every case encodes a fixed set of inputs and compares the result byte-for-byte against the hex
+//! file of the same name in `tests/calldata`. Any difference is a breaking change for integrators
+//! who audit calldata and must come with a bump of [`ENCODING_VERSION`].
+//!
+//! To regenerate the corpus after an intentional change, run
+//!
+//! ```sh
+//! UPDATE_CALLDATA=1 cargo test --test calldata_stability
+//! git diff tests/calldata
+//! ```
+
+use alloy_primitives::{address, hex, uint, Address, Bytes, U256};
+use std::{fs, path::PathBuf};
+use uniswap_sdk_core::{prelude::*, token};
+use uniswap_v3_sdk::prelude::*;
+
+/// The encoding version the checked-in corpus was generated with.
+const CORPUS_VERSION: u32 = 1;
+
+const RECIPIENT: Address = address!("0000000000000000000000000000000000000003");
+const SENDER: Address = address!("0000000000000000000000000000000000000004");
+const FEE_RECIPIENT: Address = address!("0000000000000000000000000000000000000009");
+const TOKEN_ID: U256 = uint!(1_U256);
+const AMOUNT: U256 = uint!(123_U256);
+
+fn token0() -> Token {
+    token!(1, "0000000000000000000000000000000000000001", 18, "t0")
+}
+
+fn token1() -> Token {
+    token!(1, "0000000000000000000000000000000000000002", 18, "t1")
+}
+
+fn weth() -> Token {
+    Ether::on_chain(1).wrapped().clone()
+}
+
+fn make_pool(token0: Token, token1: Token) -> Pool<TickListDataProvider> {
+    let fee = FeeAmount::MEDIUM;
+    let liquidity = 1_000_000_u128;
+    Pool::new_with_tick_data_provider(
+        token0,
+        token1,
+        fee,
+        encode_sqrt_ratio_x96(1, 1),
+        liquidity,
+        TickListDataProvider::new(
+            vec![
+                Tick::new(
+                    nearest_usable_tick(MIN_TICK, fee.tick_spacing()).as_i32(),
+                    liquidity,
+                    liquidity as i128,
+                ),
+                Tick::new(
+                    nearest_usable_tick(MAX_TICK, fee.tick_spacing()).as_i32(),
+                    liquidity,
+                    -(liquidity as i128),
+                ),
+            ],
+            fee.tick_spacing().as_i32(),
+        ),
+    )
+    .unwrap()
+}
+
+fn swap_options() -> SwapOptions {
+    SwapOptions {
+        slippage_tolerance: Percent::new(1, 100),
+        recipient: RECIPIENT,
+        ..Default::default()
+    }
+}
+
+fn golden_path(name: &str) -> PathBuf {
+    PathBuf::from(env!("CARGO_MANIFEST_DIR"))
+        .join("tests")
+        .join("calldata")
+        .join(format!("{name}.hex"))
+}
+
+/// Compares `calldata` against the golden file `name`, or rewrites the file when `UPDATE_CALLDATA`
+/// is set.
+fn check(name: &str, calldata: Bytes) {
+    let path = golden_path(name);
+    let actual = hex::encode_prefixed(&calldata);
+    if std::env::var_os("UPDATE_CALLDATA").is_some() {
+        fs::write(&path, format!("{actual}\n")).unwrap();
+        return;
+    }
+    let expected = fs::read_to_string(&path)
+        .unwrap_or_else(|_| panic!("missing golden file {}", path.display()));
+    assert_eq!(
+        actual,
+        expected.trim(),
+        "calldata for `{name}` changed; bump ENCODING_VERSION and regenerate the corpus"
+    );
+}
+
+#[test]
+fn corpus_version_matches() {
+    assert_eq!(
+        ENCODING_VERSION, CORPUS_VERSION,
+        "regenerate the corpus and update CORPUS_VERSION"
+    );
+}
+
+#[test]
+fn npm_create() {
+    let pool = Pool::new(
+        token0(),
+        token1(),
+        FeeAmount::MEDIUM,
+        encode_sqrt_ratio_x96(1, 1),
+        0,
+    )
+    .unwrap();
+    check("npm_create", create_call_parameters(&pool).calldata);
+}
+
+#[test]
+fn npm_collect() {
+    let params = collect_call_parameters(&CollectOptions {
+        token_id: TOKEN_ID,
+        expected_currency_owed0: CurrencyAmount::from_raw_amount(token0(), 0).unwrap(),
+        expected_currency_owed1: CurrencyAmount::from_raw_amount(token1(), 0).unwrap(),
+        recipient: RECIPIENT,
+    });
+    check("npm_collect", params.calldata);
+}
+
+#[test]
+fn npm_safe_transfer_from() {
+    let params = safe_transfer_from_parameters(SafeTransferOptions {
+        sender: SENDER,
+        recipient: RECIPIENT,
+        token_id: TOKEN_ID,
+        data: Bytes::default(),
+    });
+    check("npm_safe_transfer_from", params.calldata);
+}
+
+#[test]
+fn payments_unwrap_weth9() {
+    check(
+        "payments_unwrap_weth9",
+        encode_unwrap_weth9(AMOUNT, RECIPIENT, None),
+    );
+}
+
+#[test]
+fn payments_unwrap_weth9_with_fee() {
+    let fee = FeeOptions {
+        fee: Percent::new(1, 1000),
+        recipient: FEE_RECIPIENT,
+    };
+    check(
+        "payments_unwrap_weth9_with_fee",
+        encode_unwrap_weth9(AMOUNT, RECIPIENT, Some(fee)),
+    );
+}
+
+#[test]
+fn payments_sweep_token() {
+    check(
+        "payments_sweep_token",
+        encode_sweep_token(token0().address(), AMOUNT, RECIPIENT, None),
+    );
+}
+
+#[test]
+fn payments_refund_eth() {
+    check("payments_refund_eth", encode_refund_eth());
+}
+
+#[test]
+fn swap_single_hop_exact_input() {
+    let trade = Trade::from_route(
+        Route::new(vec![make_pool(token0(), token1())], token0(), token1()),
+        CurrencyAmount::from_raw_amount(token0(), 100).unwrap(),
+        TradeType::ExactInput,
+    )
+    .unwrap();
+    let params = swap_call_parameters(&mut [trade], swap_options()).unwrap();
+    check("swap_single_hop_exact_input", params.calldata);
+}
+
+#[test]
+fn swap_single_hop_exact_output() {
+    let trade = Trade::from_route(
+        Route::new(vec![make_pool(token0(), token1())], token0(), token1()),
+        CurrencyAmount::from_raw_amount(token1(), 100).unwrap(),
+        TradeType::ExactOutput,
+    )
+    .unwrap();
+    let params = swap_call_parameters(&mut [trade], swap_options()).unwrap();
+    check("swap_single_hop_exact_output", params.calldata);
+}
+
+#[test]
+fn swap_multi_hop_exact_input() {
+    let trade = Trade::from_route(
+        Route::new(
+            vec![make_pool(token0(), token1()), make_pool(token1(), weth())],
+            token0(),
+            weth(),
+        ),
+        CurrencyAmount::from_raw_amount(token0(), 100).unwrap(),
+        TradeType::ExactInput,
+    )
+    .unwrap();
+    let params = swap_call_parameters(&mut [trade], swap_options()).unwrap();
+    check("swap_multi_hop_exact_input", params.calldata);
+}